@@ -0,0 +1,175 @@
+//! Transcoding between the Opus frames used throughout the audio pipeline and G.711 μ-law (PCMU).
+//!
+//! PCMU is only supported for interop testing with third-party endpoints. Capture and playback
+//! always operate on Opus frames, which are transcoded at the WebRTC boundary if a call negotiated
+//! PCMU instead.
+
+use crate::error::AudioError;
+use crate::{EncodedAudioFrame, FRAME_SIZE, MAX_OPUS_FRAME_SIZE, TARGET_SAMPLE_RATE};
+use anyhow::Context;
+use bytes::Bytes;
+
+/// Sample rate of G.711 audio.
+pub const PCMU_SAMPLE_RATE: u32 = 8_000;
+
+const RESAMPLE_FACTOR: usize = (TARGET_SAMPLE_RATE / PCMU_SAMPLE_RATE) as usize;
+const MAX_OPUS_FRAME_SAMPLES: usize = TARGET_SAMPLE_RATE as usize * 120 / 1000;
+const MULAW_BIAS: i32 = 0x84;
+const MULAW_CLIP: i32 = 32_635;
+
+/// Transcodes Opus frames into PCMU payloads.
+pub struct PcmuEncoder {
+    decoder: opus::Decoder,
+    decoded: Vec<f32>,
+}
+
+impl PcmuEncoder {
+    pub fn new() -> Result<Self, AudioError> {
+        let decoder = opus::Decoder::new(TARGET_SAMPLE_RATE, opus::Channels::Mono)
+            .context("Failed to create Opus decoder")?;
+
+        Ok(Self {
+            decoder,
+            decoded: vec![0.0f32; MAX_OPUS_FRAME_SAMPLES],
+        })
+    }
+
+    /// Decodes the given Opus frame and returns it as PCMU payload at [`PCMU_SAMPLE_RATE`].
+    pub fn encode(&mut self, frame: &[u8]) -> Result<EncodedAudioFrame, AudioError> {
+        let n = self
+            .decoder
+            .decode_float(frame, &mut self.decoded, false)
+            .context("Failed to decode Opus frame")?;
+
+        // Averaging the decimated samples is a crude low-pass filter, but sufficient for the
+        // narrowband voice PCMU is used for.
+        Ok(self.decoded[..n]
+            .chunks(RESAMPLE_FACTOR)
+            .map(|chunk| mulaw_encode(to_i16(chunk.iter().sum::<f32>() / chunk.len() as f32)))
+            .collect::<Vec<_>>()
+            .into())
+    }
+}
+
+/// Transcodes PCMU payloads into Opus frames of [`crate::FRAME_DURATION_MS`].
+pub struct PcmuDecoder {
+    encoder: opus::Encoder,
+    frame: Vec<f32>,
+    encoded: Vec<u8>,
+    last_sample: f32,
+}
+
+impl PcmuDecoder {
+    pub fn new() -> Result<Self, AudioError> {
+        let mut encoder = opus::Encoder::new(
+            TARGET_SAMPLE_RATE,
+            opus::Channels::Mono,
+            opus::Application::Voip,
+        )
+        .context("Failed to create Opus encoder")?;
+        encoder
+            .set_bitrate(opus::Bitrate::Max)
+            .context("Failed to set Opus bitrate")?;
+
+        Ok(Self {
+            encoder,
+            frame: Vec::with_capacity(FRAME_SIZE),
+            encoded: vec![0u8; MAX_OPUS_FRAME_SIZE],
+            last_sample: 0.0,
+        })
+    }
+
+    /// Decodes the given PCMU payload and returns all Opus frames completed by it. Samples not
+    /// filling a complete frame are kept until the next payload.
+    pub fn decode(&mut self, payload: &[u8]) -> Result<Vec<EncodedAudioFrame>, AudioError> {
+        let mut frames = Vec::new();
+        for &byte in payload {
+            let sample = f32::from(mulaw_decode(byte)) / 32_768.0;
+            for step in 1..=RESAMPLE_FACTOR {
+                let t = step as f32 / RESAMPLE_FACTOR as f32;
+                self.frame
+                    .push(self.last_sample + (sample - self.last_sample) * t);
+            }
+            self.last_sample = sample;
+
+            if self.frame.len() == FRAME_SIZE {
+                let len = self
+                    .encoder
+                    .encode_float(&self.frame, &mut self.encoded)
+                    .context("Failed to encode Opus frame")?;
+                self.frame.clear();
+                frames.push(Bytes::copy_from_slice(&self.encoded[..len]));
+            }
+        }
+        Ok(frames)
+    }
+}
+
+#[inline]
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16
+}
+
+/// Encodes a linear sample as G.711 μ-law.
+fn mulaw_encode(sample: i16) -> u8 {
+    let sign = if sample < 0 { 0x80 } else { 0x00 };
+    let magnitude = i32::from(sample).abs().min(MULAW_CLIP) + MULAW_BIAS;
+    let exponent = (0..8)
+        .rev()
+        .find(|exponent| magnitude & (0x80 << exponent) != 0)
+        .unwrap_or(0);
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+    !(sign | (exponent << 4) | mantissa) as u8
+}
+
+/// Decodes a G.711 μ-law byte into a linear sample.
+fn mulaw_decode(byte: u8) -> i16 {
+    let byte = !byte;
+    let exponent = i32::from((byte >> 4) & 0x07);
+    let mantissa = i32::from(byte & 0x0F);
+    let magnitude = (((mantissa << 3) + MULAW_BIAS) << exponent) - MULAW_BIAS;
+    if byte & 0x80 != 0 {
+        -magnitude as i16
+    } else {
+        magnitude as i16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mulaw_encodes_silence() {
+        assert_eq!(mulaw_encode(0), 0xFF);
+        assert_eq!(mulaw_decode(0xFF), 0);
+    }
+
+    #[test]
+    fn mulaw_roundtrip_is_within_quantization_error() {
+        for sample in (i16::MIN..=i16::MAX).step_by(97) {
+            let decoded = mulaw_decode(mulaw_encode(sample));
+            let expected = i32::from(sample).clamp(-MULAW_CLIP, MULAW_CLIP);
+            let error = (i32::from(decoded) - expected).abs();
+            // The quantization step doubles with every segment, up to 1024 in the highest one.
+            assert!(
+                error <= 512,
+                "sample {sample} decoded to {decoded} (error {error})"
+            );
+        }
+    }
+
+    #[test]
+    fn transcodes_between_pcmu_and_opus() {
+        let mut decoder = PcmuDecoder::new().unwrap();
+        let mut encoder = PcmuEncoder::new().unwrap();
+        let payload = vec![0xFFu8; PCMU_SAMPLE_RATE as usize * 20 / 1000];
+
+        assert!(decoder.decode(&payload[..80]).unwrap().is_empty());
+        let frames = decoder.decode(&payload[80..]).unwrap();
+        assert_eq!(frames.len(), 1);
+
+        let pcmu = encoder.encode(&frames[0]).unwrap();
+        assert_eq!(pcmu.len(), payload.len());
+    }
+}
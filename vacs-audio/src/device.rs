@@ -25,11 +25,240 @@ impl Display for DeviceType {
     }
 }
 
+/// Ratio between output and input sample rate of the resampler converting between a device
+/// running at `sample_rate` and [`TARGET_SAMPLE_RATE`].
+fn resample_ratio(device_type: DeviceType, sample_rate: u32) -> f64 {
+    match device_type {
+        DeviceType::Input => TARGET_SAMPLE_RATE as f64 / sample_rate as f64,
+        DeviceType::Output => sample_rate as f64 / TARGET_SAMPLE_RATE as f64,
+    }
+}
+
+/// Trade-off between quality, latency and CPU usage of the resampler used for devices not
+/// running at [`TARGET_SAMPLE_RATE`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResamplerQuality {
+    /// Short sinc filter with linear interpolation, lowest latency and CPU usage.
+    Fast,
+    /// Medium length sinc filter, suitable for most machines.
+    Balanced,
+    /// Long sinc filter with cubic interpolation, highest quality.
+    #[default]
+    HighQuality,
+}
+
+/// Optional preferences biasing the stream config picked for a device, e.g. to force a mono input
+/// on a device also offering stereo. Without hints, the config closest to [`TARGET_SAMPLE_RATE`]
+/// and the device type's preferred channel count is picked.
+///
+/// A hinted channel count or sample rate outweighs the regular scoring. Hints not supported by
+/// any config of a device are ignored with a warning.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamConfigHints {
+    pub channels: Option<u16>,
+    pub sample_rate: Option<u32>,
+}
+
+impl StreamConfigHints {
+    /// Drops all hints not supported by any of the given configs.
+    fn supported_by(self, configs: &[SupportedStreamConfigRange]) -> Self {
+        let channels = self.channels.filter(|&channels| {
+            let supported = configs.iter().any(|range| range.channels() == channels);
+            if !supported {
+                tracing::warn!(?channels, "Ignoring channels hint not supported by device");
+            }
+            supported
+        });
+        let sample_rate = self.sample_rate.filter(|&sample_rate| {
+            let supported = configs
+                .iter()
+                .any(|range| Self::supports_sample_rate(range, sample_rate));
+            if !supported {
+                tracing::warn!(
+                    ?sample_rate,
+                    "Ignoring sample rate hint not supported by device"
+                );
+            }
+            supported
+        });
+
+        Self {
+            channels,
+            sample_rate,
+        }
+    }
+
+    /// Number of hints the given config does not satisfy.
+    fn mismatches(&self, range: &SupportedStreamConfigRange) -> u8 {
+        let channels = self
+            .channels
+            .is_some_and(|channels| range.channels() != channels);
+        let sample_rate = self
+            .sample_rate
+            .is_some_and(|sample_rate| !Self::supports_sample_rate(range, sample_rate));
+        u8::from(channels) + u8::from(sample_rate)
+    }
+
+    fn supports_sample_rate(range: &SupportedStreamConfigRange, sample_rate: u32) -> bool {
+        (range.min_sample_rate().0..=range.max_sample_rate().0).contains(&sample_rate)
+    }
+}
+
+impl ResamplerQuality {
+    fn interpolation_parameters(self) -> SincInterpolationParameters {
+        match self {
+            ResamplerQuality::Fast => SincInterpolationParameters {
+                sinc_len: 32,
+                f_cutoff: 0.85,
+                interpolation: SincInterpolationType::Linear,
+                oversampling_factor: 64,
+                window: WindowFunction::Hann2,
+            },
+            ResamplerQuality::Balanced => SincInterpolationParameters {
+                sinc_len: 128,
+                f_cutoff: 0.92,
+                interpolation: SincInterpolationType::Linear,
+                oversampling_factor: 128,
+                window: WindowFunction::Blackman2,
+            },
+            ResamplerQuality::HighQuality => SincInterpolationParameters {
+                sinc_len: 256,
+                f_cutoff: 0.95,
+                interpolation: SincInterpolationType::Cubic,
+                oversampling_factor: 256,
+                window: WindowFunction::BlackmanHarris2,
+            },
+        }
+    }
+
+    pub(crate) fn build(
+        self,
+        resample_ratio: f64,
+        chunk_size: usize,
+    ) -> Result<SincFixedIn<f32>, AudioError> {
+        Ok(SincFixedIn::<f32>::new(
+            resample_ratio,
+            2.0,
+            self.interpolation_parameters(),
+            chunk_size,
+            1,
+        )
+        .context("Failed to create resampler")?)
+    }
+}
+
+/// Stream config picked for a device, e.g. for display purposes.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamDeviceInfo {
+    pub device_type: DeviceType,
+    pub device_name: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: String,
+    /// Whether audio is resampled from/to [`TARGET_SAMPLE_RATE`].
+    pub resampled: bool,
+}
+
+/// Stream config range supported by a device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamConfigRangeInfo {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub sample_format: String,
+}
+
+impl From<&SupportedStreamConfigRange> for StreamConfigRangeInfo {
+    fn from(range: &SupportedStreamConfigRange) -> Self {
+        Self {
+            channels: range.channels(),
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            sample_format: range.sample_format().to_string(),
+        }
+    }
+}
+
+/// Stream configs supported by a device and the one picked for it, see
+/// [`DeviceSelector::all_device_capabilities`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCapabilities {
+    pub device_name: String,
+    pub supported_configs: Vec<StreamConfigRangeInfo>,
+    /// Config vacs would pick, `None` if the device cannot be used.
+    pub picked_config: Option<PickedStreamConfig>,
+    /// Reason the device cannot be used.
+    pub error: Option<String>,
+}
+
+/// Stream config picked for a device, see [`DeviceCapabilities`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickedStreamConfig {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub sample_format: String,
+    /// Whether audio is resampled from/to [`TARGET_SAMPLE_RATE`].
+    pub resampled: bool,
+}
+
+impl DeviceCapabilities {
+    fn new(
+        device_type: DeviceType,
+        device_name: String,
+        configs: Result<Vec<SupportedStreamConfigRange>, AudioError>,
+        hints: StreamConfigHints,
+    ) -> Self {
+        let configs = match configs {
+            Ok(configs) => configs,
+            Err(err) => {
+                return Self {
+                    device_name,
+                    supported_configs: Vec::new(),
+                    picked_config: None,
+                    error: Some(format!("{err:#}")),
+                };
+            }
+        };
+
+        let supported_configs = configs.iter().map(StreamConfigRangeInfo::from).collect();
+        let (picked_config, error) = match DeviceSelector::select_stream_config(
+            configs,
+            DeviceSelector::preferred_channels(device_type),
+            hints,
+        ) {
+            Ok((config, _)) => (
+                Some(PickedStreamConfig {
+                    channels: config.channels(),
+                    sample_rate: config.sample_rate().0,
+                    sample_format: config.sample_format().to_string(),
+                    resampled: config.sample_rate().0 != TARGET_SAMPLE_RATE,
+                }),
+                None,
+            ),
+            Err(err) => (None, Some(format!("{err:#}"))),
+        };
+
+        Self {
+            device_name,
+            supported_configs,
+            picked_config,
+            error,
+        }
+    }
+}
+
 pub struct StreamDevice {
     pub(crate) device_type: DeviceType,
     pub(crate) device: cpal::Device,
     pub(crate) config: cpal::StreamConfig,
     pub(crate) sample_format: SampleFormat,
+    pub(crate) resampler_quality: ResamplerQuality,
 }
 
 impl StreamDevice {
@@ -53,6 +282,31 @@ impl StreamDevice {
         self.config.channels
     }
 
+    /// Summary of the stream config picked for this device.
+    pub fn config_info(&self) -> StreamDeviceInfo {
+        StreamDeviceInfo {
+            device_type: self.device_type,
+            device_name: self.name(),
+            sample_rate: self.sample_rate(),
+            channels: self.channels(),
+            sample_format: self.sample_format.to_string(),
+            resampled: self.sample_rate() != TARGET_SAMPLE_RATE,
+        }
+    }
+
+    /// Sets the quality of the resampler used if the device doesn't run at [`TARGET_SAMPLE_RATE`].
+    pub fn with_resampler_quality(mut self, quality: ResamplerQuality) -> Self {
+        self.resampler_quality = quality;
+        self
+    }
+
+    /// Resolves the requested output channel map (1-based hardware channels) against this
+    /// device's channel count. See [`ChannelMap::resolve`].
+    #[inline]
+    pub fn channel_map(&self, requested: Option<&[u16]>) -> ChannelMap {
+        ChannelMap::resolve(requested, self.channels())
+    }
+
     #[instrument(level = "trace", skip(data_callback, error_callback), err)]
     pub(crate) fn build_input_stream<D, E>(
         &self,
@@ -187,32 +441,16 @@ impl StreamDevice {
         if self.sample_rate() == TARGET_SAMPLE_RATE {
             Ok(None)
         } else {
-            let resampler_params = SincInterpolationParameters {
-                sinc_len: 256,
-                f_cutoff: 0.95,
-                interpolation: SincInterpolationType::Cubic,
-                oversampling_factor: 256,
-                window: WindowFunction::BlackmanHarris2,
-            };
+            let resample_ratio = resample_ratio(self.device_type, self.sample_rate());
 
-            let resample_ratio = match self.device_type {
-                DeviceType::Input => TARGET_SAMPLE_RATE as f64 / self.sample_rate() as f64,
-                DeviceType::Output => self.sample_rate() as f64 / TARGET_SAMPLE_RATE as f64,
+            let chunk_size = if let cpal::BufferSize::Fixed(n) = self.config.buffer_size {
+                n as usize
+            } else {
+                1024usize
             };
 
             Ok(Some(
-                SincFixedIn::<f32>::new(
-                    resample_ratio,
-                    2.0,
-                    resampler_params,
-                    if let cpal::BufferSize::Fixed(n) = self.config.buffer_size {
-                        n as usize
-                    } else {
-                        1024usize
-                    },
-                    1,
-                )
-                .context("Failed to create resampler")?,
+                self.resampler_quality.build(resample_ratio, chunk_size)?,
             ))
         }
     }
@@ -222,15 +460,92 @@ impl Debug for StreamDevice {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "StreamDevice {{ device_type: {}, device: {}, config: {:?}, sample_format: {:?} }}",
+            "StreamDevice {{ device_type: {}, device: {}, config: {:?}, sample_format: {:?}, resampler_quality: {:?} }}",
             self.device_type,
             self.device.name().unwrap_or_default(),
             self.config,
-            self.sample_format
+            self.sample_format,
+            self.resampler_quality
         )
     }
 }
 
+/// Hardware output channels receiving mono audio, e.g. call audio on a multi-channel interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelMap {
+    device_channels: usize, // >= 1
+    targets: Vec<usize>,    // 0-based, sorted, deduplicated, < device_channels
+}
+
+impl ChannelMap {
+    /// Maps mono audio to all channels of a device with `device_channels` channels.
+    pub fn all(device_channels: u16) -> Self {
+        let device_channels = device_channels.max(1) as usize;
+        Self {
+            device_channels,
+            targets: (0..device_channels).collect(),
+        }
+    }
+
+    /// Resolves a list of 1-based hardware channels against the device's channel count.
+    ///
+    /// Without an explicit map, mono audio is duplicated onto all channels. If the requested map
+    /// is empty or references channels the device doesn't have, a warning is logged and all
+    /// channels are used instead.
+    pub fn resolve(requested: Option<&[u16]>, device_channels: u16) -> Self {
+        let Some(requested) = requested else {
+            return Self::all(device_channels);
+        };
+
+        let all = Self::all(device_channels);
+        if requested.is_empty() {
+            tracing::warn!("Empty output channel map, falling back to all channels");
+            return all;
+        }
+
+        if let Some(invalid) = requested
+            .iter()
+            .find(|&&ch| ch == 0 || ch as usize > all.device_channels)
+        {
+            tracing::warn!(
+                ?requested,
+                ?invalid,
+                device_channels = ?all.device_channels,
+                "Output channel map references unavailable channel, falling back to all channels"
+            );
+            return all;
+        }
+
+        let mut targets = requested
+            .iter()
+            .map(|&ch| ch as usize - 1)
+            .collect::<Vec<_>>();
+        targets.sort_unstable();
+        targets.dedup();
+
+        Self {
+            device_channels: all.device_channels,
+            targets,
+        }
+    }
+
+    /// Number of interleaved channels per frame of the device.
+    #[inline]
+    pub fn device_channels(&self) -> usize {
+        self.device_channels
+    }
+
+    /// Adds the given mono sample onto all mapped channels of an interleaved output frame.
+    #[inline]
+    pub fn mix_into_frame(&self, frame: &mut [f32], sample: f32) {
+        for &ch in &self.targets {
+            if let Some(x) = frame.get_mut(ch) {
+                *x += sample;
+            }
+        }
+    }
+}
+
 pub struct DeviceSelector {}
 
 impl DeviceSelector {
@@ -239,12 +554,13 @@ impl DeviceSelector {
         device_type: DeviceType,
         preferred_host: Option<&str>,
         preferred_device_name: Option<&str>,
+        hints: StreamConfigHints,
     ) -> Result<(StreamDevice, bool), AudioError> {
         tracing::debug!("Opening device");
 
         let host = Self::select_host(preferred_host);
         let (device, stream_config, is_fallback) =
-            Self::pick_device_with_stream_config(device_type, &host, preferred_device_name)?;
+            Self::pick_device_with_stream_config(device_type, &host, preferred_device_name, hints)?;
 
         tracing::debug!(?stream_config, device = ?DeviceDebug(&device), ?is_fallback, "Opened device");
         Ok((
@@ -253,6 +569,7 @@ impl DeviceSelector {
                 device,
                 config: stream_config.config(),
                 sample_format: stream_config.sample_format(),
+                resampler_quality: ResamplerQuality::default(),
             },
             is_fallback,
         ))
@@ -291,7 +608,12 @@ impl DeviceSelector {
             .into_iter()
             .filter_map(|device| {
                 if let Ok(device_name) = device.name()
-                    && Self::pick_best_stream_config(device_type, &device).is_ok()
+                    && Self::pick_best_stream_config(
+                        device_type,
+                        &device,
+                        StreamConfigHints::default(),
+                    )
+                    .is_ok()
                 {
                     Some(device_name)
                 } else {
@@ -313,7 +635,7 @@ impl DeviceSelector {
 
         let host = Self::select_host(preferred_host);
         let (device, _) = Self::select_device(device_type, &host, None)?;
-        Self::pick_best_stream_config(device_type, &device)?;
+        Self::pick_best_stream_config(device_type, &device, StreamConfigHints::default())?;
 
         tracing::debug!(device = ?DeviceDebug(&device), "Retrieved device name for default device");
         Ok(device.name().unwrap_or_default())
@@ -329,12 +651,54 @@ impl DeviceSelector {
 
         let host = Self::select_host(preferred_host);
         let (device, _) = Self::select_device(device_type, &host, preferred_device_name)?;
-        Self::pick_best_stream_config(device_type, &device)?;
+        Self::pick_best_stream_config(device_type, &device, StreamConfigHints::default())?;
 
         tracing::debug!(device = ?DeviceDebug(&device), "Retrieved device name for picked device");
         Ok(device.name().unwrap_or_default())
     }
 
+    /// Lists the stream configs supported by every device of the given type along with the
+    /// config vacs would pick, e.g. to diagnose why a device was chosen or rejected.
+    #[instrument(level = "debug", err)]
+    pub fn all_device_capabilities(
+        device_type: DeviceType,
+        preferred_host: Option<&str>,
+        hints: StreamConfigHints,
+    ) -> Result<Vec<DeviceCapabilities>, AudioError> {
+        tracing::debug!("Retrieving device capabilities");
+
+        let host = Self::select_host(preferred_host);
+        let capabilities = Self::host_devices(device_type, &host)?
+            .into_iter()
+            .map(|device| {
+                DeviceCapabilities::new(
+                    device_type,
+                    device.name().unwrap_or_default(),
+                    Self::supported_stream_configs(device_type, &device),
+                    hints,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        tracing::debug!(device_count = ?capabilities.len(), "Retrieved device capabilities");
+        Ok(capabilities)
+    }
+
+    /// Returns the host name matching the preferred host, using the same matching as when
+    /// opening devices: an exact match (ignoring case) first, a substring match otherwise.
+    pub fn find_host_name<'a>(preferred_host: &str, host_names: &'a [String]) -> Option<&'a str> {
+        let preferred = preferred_host.to_lowercase();
+        host_names
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(preferred_host))
+            .or_else(|| {
+                host_names
+                    .iter()
+                    .find(|name| name.to_lowercase().contains(&preferred))
+            })
+            .map(String::as_str)
+    }
+
     #[instrument(level = "trace")]
     fn select_host(preferred_host: Option<&str>) -> cpal::Host {
         tracing::trace!("Selecting host");
@@ -342,18 +706,14 @@ impl DeviceSelector {
         let hosts = cpal::available_hosts();
 
         if let Some(name) = preferred_host {
-            if let Some(id) = hosts.iter().find(|id| id.name().eq_ignore_ascii_case(name)) {
-                tracing::trace!(?id, "Selected preferred audio host");
-                return cpal::host_from_id(*id).unwrap_or(cpal::default_host());
-            }
-            if let Some(id) = hosts
+            let host_names = hosts
                 .iter()
-                .find(|id| id.name().to_lowercase().contains(&name.to_lowercase()))
+                .map(|id| id.name().to_string())
+                .collect::<Vec<_>>();
+            if let Some(host_name) = Self::find_host_name(name, &host_names)
+                && let Some(id) = hosts.iter().find(|id| id.name() == host_name)
             {
-                tracing::trace!(
-                    ?id,
-                    "Selected preferred audio host (based on substring match)"
-                );
+                tracing::trace!(?id, "Selected preferred audio host");
                 return cpal::host_from_id(*id).unwrap_or(cpal::default_host());
             }
         }
@@ -367,11 +727,12 @@ impl DeviceSelector {
         device_type: DeviceType,
         host: &cpal::Host,
         preferred_device_name: Option<&str>,
+        hints: StreamConfigHints,
     ) -> Result<(cpal::Device, SupportedStreamConfig, bool), AudioError> {
         let (mut device, mut is_fallback) =
             Self::select_device(device_type, host, preferred_device_name)?;
 
-        let (stream_config, _) = match Self::pick_best_stream_config(device_type, &device) {
+        let (stream_config, _) = match Self::pick_best_stream_config(device_type, &device, hints) {
             Ok(stream_config) => stream_config,
             Err(err) => {
                 tracing::warn!(?err, device = ?DeviceDebug(&device), "Failed to pick stream config for preferred device, picking best fallback device");
@@ -384,7 +745,9 @@ impl DeviceSelector {
                 )> = None;
 
                 for dev in devices {
-                    if let Ok((config, score)) = Self::pick_best_stream_config(device_type, &dev) {
+                    if let Ok((config, score)) =
+                        Self::pick_best_stream_config(device_type, &dev, hints)
+                    {
                         match &mut best_fallback {
                             None => best_fallback = Some((dev, config, score)),
                             Some((_, _, best_score)) => {
@@ -476,48 +839,81 @@ impl DeviceSelector {
     fn pick_best_stream_config(
         device_type: DeviceType,
         device: &cpal::Device,
+        hints: StreamConfigHints,
     ) -> Result<(SupportedStreamConfig, StreamConfigScore), AudioError> {
         tracing::trace!("Picking best stream config");
 
-        let (configs, preferred_channels): (Vec<SupportedStreamConfigRange>, u16) =
-            match device_type {
-                DeviceType::Input => (
-                    device
-                        .supported_input_configs()
-                        .context("Failed to get supported input configs")?
-                        .collect(),
-                    1,
-                ),
-                DeviceType::Output => (
-                    device
-                        .supported_output_configs()
-                        .context("Failed to get supported output configs")?
-                        .collect(),
-                    2,
-                ),
-            };
+        let configs = Self::supported_stream_configs(device_type, device)?;
+        let (stream_config, score) =
+            Self::select_stream_config(configs, Self::preferred_channels(device_type), hints)?;
 
-        let mut best: Option<(SupportedStreamConfigRange, StreamConfigScore)> = None;
+        tracing::trace!(?stream_config, ?score, "Picked best stream config");
+        Ok((stream_config, score))
+    }
+
+    fn supported_stream_configs(
+        device_type: DeviceType,
+        device: &cpal::Device,
+    ) -> Result<Vec<SupportedStreamConfigRange>, AudioError> {
+        Ok(match device_type {
+            DeviceType::Input => device
+                .supported_input_configs()
+                .context("Failed to get supported input configs")?
+                .collect(),
+            DeviceType::Output => device
+                .supported_output_configs()
+                .context("Failed to get supported output configs")?
+                .collect(),
+        })
+    }
+
+    #[inline]
+    fn preferred_channels(device_type: DeviceType) -> u16 {
+        match device_type {
+            DeviceType::Input => 1,
+            DeviceType::Output => 2,
+        }
+    }
+
+    /// Picks the supported config closest to [`TARGET_SAMPLE_RATE`] and the preferred channel
+    /// count, using the closest sample rate the config supports. Configs not supporting the target
+    /// sample rate are resampled, channel counts other than the preferred one are down- or upmixed
+    /// by the capture and playback streams.
+    ///
+    /// Configs satisfying more of the supported [`StreamConfigHints`] are always preferred, a
+    /// hinted sample rate is used instead of the closest one to the target.
+    fn select_stream_config(
+        configs: impl IntoIterator<Item = SupportedStreamConfigRange>,
+        preferred_channels: u16,
+        hints: StreamConfigHints,
+    ) -> Result<(SupportedStreamConfig, StreamConfigScore), AudioError> {
+        let configs = configs.into_iter().collect::<Vec<_>>();
+        let hints = hints.supported_by(&configs);
+
+        let mut best: Option<(SupportedStreamConfigRange, u8, StreamConfigScore)> = None;
 
         for range in configs {
+            let mismatches = hints.mismatches(&range);
             let score = Self::score_stream_config_range(&range, preferred_channels);
             match &mut best {
-                None => best = Some((range, score)),
-                Some((_, best_score)) => {
-                    if score < *best_score {
-                        *best_score = score;
-                        best = Some((range, score));
+                None => best = Some((range, mismatches, score)),
+                Some((_, best_mismatches, best_score)) => {
+                    if (mismatches, score) < (*best_mismatches, *best_score) {
+                        best = Some((range, mismatches, score));
                     }
                 }
             }
         }
 
-        let (range, score) =
+        let (range, _, score) =
             best.ok_or_else(|| anyhow::anyhow!("No supported stream config found"))?;
-        let sample_rate =
-            Self::closest_sample_rate(range.min_sample_rate().0, range.max_sample_rate().0);
+        let sample_rate = match hints.sample_rate {
+            Some(sample_rate) if StreamConfigHints::supports_sample_rate(&range, sample_rate) => {
+                sample_rate
+            }
+            _ => Self::closest_sample_rate(range.min_sample_rate().0, range.max_sample_rate().0),
+        };
 
-        tracing::trace!(?range, ?score, ?sample_rate, "Picked best stream config");
         Ok((range.with_sample_rate(cpal::SampleRate(sample_rate)), score))
     }
 
@@ -592,3 +988,397 @@ impl<'a> Debug for HostDebug<'a> {
         f.debug_tuple("Host").field(&self.0.id().name()).finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mix(map: &ChannelMap, sample: f32) -> Vec<f32> {
+        let mut frame = vec![0.0f32; map.device_channels()];
+        map.mix_into_frame(&mut frame, sample);
+        frame
+    }
+
+    /// Resamples a 100 Hz to 4 kHz sine sweep from 24 kHz to 48 kHz and returns the output
+    /// delay in frames as well as the RMS error against the ideal sweep.
+    fn resample_sweep(quality: ResamplerQuality) -> (usize, f32) {
+        use rubato::Resampler;
+
+        const IN_RATE: f32 = 24_000.0;
+        const OUT_RATE: f32 = 48_000.0;
+        const DURATION: f32 = 1.0;
+        const CHUNK: usize = 1024;
+
+        let sweep = |t: f32| {
+            let (f0, f1) = (100.0f32, 4000.0f32);
+            (2.0 * std::f32::consts::PI * (f0 * t + (f1 - f0) * t * t / (2.0 * DURATION))).sin()
+        };
+
+        let mut resampler = quality.build((OUT_RATE / IN_RATE) as f64, CHUNK).unwrap();
+        let input = (0..(IN_RATE * DURATION) as usize)
+            .map(|n| sweep(n as f32 / IN_RATE))
+            .collect::<Vec<_>>();
+
+        let mut output = Vec::new();
+        for chunk in input.chunks_exact(CHUNK) {
+            let resampled = resampler.process(&[chunk], None).unwrap();
+            output.extend_from_slice(&resampled[0]);
+        }
+
+        let delay = resampler.output_delay();
+        let start = OUT_RATE as usize / 10;
+        let end = output.len() - OUT_RATE as usize / 10;
+
+        // Allow for sub-frame misalignment of the reported delay
+        let error = (delay.saturating_sub(2)..=delay + 2)
+            .map(|lag| {
+                let sum_sq = (start..end)
+                    .map(|m| {
+                        let expected = sweep((m - lag) as f32 / OUT_RATE);
+                        (output[m] - expected).powi(2)
+                    })
+                    .sum::<f32>();
+                (sum_sq / (end - start) as f32).sqrt()
+            })
+            .fold(f32::MAX, f32::min);
+
+        (delay, error)
+    }
+
+    #[test]
+    fn fast_resampler_has_lower_delay_within_error_bound() {
+        // -34 dB relative to a full scale sine
+        const MAX_ERROR: f32 = 0.02;
+
+        let (fast_delay, fast_error) = resample_sweep(ResamplerQuality::Fast);
+        let (balanced_delay, balanced_error) = resample_sweep(ResamplerQuality::Balanced);
+        let (hq_delay, hq_error) = resample_sweep(ResamplerQuality::HighQuality);
+
+        assert!(fast_delay < balanced_delay);
+        assert!(balanced_delay < hq_delay);
+
+        assert!(fast_error < MAX_ERROR, "fast resampler error {fast_error}");
+        assert!(
+            balanced_error < MAX_ERROR,
+            "balanced resampler error {balanced_error}"
+        );
+        assert!(
+            hq_error < MAX_ERROR,
+            "high quality resampler error {hq_error}"
+        );
+    }
+
+    fn range(
+        channels: u16,
+        min: u32,
+        max: u32,
+        format: SampleFormat,
+    ) -> SupportedStreamConfigRange {
+        SupportedStreamConfigRange::new(
+            channels,
+            cpal::SampleRate(min),
+            cpal::SampleRate(max),
+            cpal::SupportedBufferSize::Unknown,
+            format,
+        )
+    }
+
+    fn select(
+        configs: Vec<SupportedStreamConfigRange>,
+        preferred_channels: u16,
+    ) -> SupportedStreamConfig {
+        select_with_hints(configs, preferred_channels, StreamConfigHints::default())
+    }
+
+    fn select_with_hints(
+        configs: Vec<SupportedStreamConfigRange>,
+        preferred_channels: u16,
+        hints: StreamConfigHints,
+    ) -> SupportedStreamConfig {
+        DeviceSelector::select_stream_config(configs, preferred_channels, hints)
+            .unwrap()
+            .0
+    }
+
+    /// Runs a chunk of silence through the resampler the device would use, verifying the
+    /// produced frame count matches the sample rate conversion.
+    fn assert_resamples(device_type: DeviceType, sample_rate: u32) {
+        use rubato::Resampler;
+
+        let ratio = resample_ratio(device_type, sample_rate);
+        let mut resampler = ResamplerQuality::default().build(ratio, 1024).unwrap();
+
+        let mut produced = 0usize;
+        let mut consumed = 0usize;
+        for _ in 0..50 {
+            let input = vec![0.0f32; resampler.input_frames_next()];
+            consumed += input.len();
+            produced += resampler.process(&[input], None).unwrap()[0].len();
+        }
+
+        let expected = consumed as f64 * ratio;
+        assert!((produced as f64 - expected).abs() / expected < 0.01);
+    }
+
+    #[test]
+    fn score_prefers_target_sample_rate() {
+        let target = range(2, 48_000, 48_000, SampleFormat::F32);
+        let lower = range(2, 44_100, 44_100, SampleFormat::F32);
+        let higher = range(2, 96_000, 96_000, SampleFormat::F32);
+
+        let score =
+            |r: &SupportedStreamConfigRange| DeviceSelector::score_stream_config_range(r, 2);
+        assert!(score(&target) < score(&lower));
+        assert!(score(&lower) < score(&higher));
+    }
+
+    #[test]
+    fn picks_44100_only_output_device() {
+        let lower = range(2, 44_100, 44_100, SampleFormat::F32);
+        assert_eq!(
+            DeviceSelector::score_stream_config_range(&lower, 2),
+            StreamConfigScore(3_900, 0, 0)
+        );
+
+        let config = select(vec![lower], 2);
+        assert_eq!(config.sample_rate().0, 44_100);
+        assert_eq!(config.channels(), 2);
+        assert_resamples(DeviceType::Output, config.sample_rate().0);
+    }
+
+    #[test]
+    fn picks_96000_only_input_device() {
+        let higher = range(2, 96_000, 96_000, SampleFormat::I16);
+        assert_eq!(
+            DeviceSelector::score_stream_config_range(&higher, 1),
+            StreamConfigScore(48_000, 1, 1)
+        );
+
+        let config = select(vec![higher], 1);
+        assert_eq!(config.sample_rate().0, 96_000);
+        assert_eq!(config.channels(), 2);
+        assert_resamples(DeviceType::Input, config.sample_rate().0);
+    }
+
+    #[test]
+    fn picks_nearest_sample_rate_within_range() {
+        let config = select(vec![range(2, 88_200, 192_000, SampleFormat::F32)], 2);
+        assert_eq!(config.sample_rate().0, 88_200);
+
+        let config = select(vec![range(2, 8_000, 32_000, SampleFormat::F32)], 2);
+        assert_eq!(config.sample_rate().0, 32_000);
+
+        let config = select(vec![range(2, 8_000, 192_000, SampleFormat::F32)], 2);
+        assert_eq!(config.sample_rate().0, TARGET_SAMPLE_RATE);
+    }
+
+    #[test]
+    fn picks_fewest_channels_for_multi_channel_only_device() {
+        let config = select(
+            vec![
+                range(8, 44_100, 96_000, SampleFormat::F32),
+                range(6, 44_100, 96_000, SampleFormat::F32),
+            ],
+            2,
+        );
+        assert_eq!(config.channels(), 6);
+        assert_eq!(config.sample_rate().0, TARGET_SAMPLE_RATE);
+
+        // Call audio is duplicated onto all channels by default
+        let map = ChannelMap::all(config.channels());
+        assert_eq!(mix(&map, 0.5), vec![0.5; 6]);
+    }
+
+    #[test]
+    fn sample_rate_outweighs_channels_and_format() {
+        let config = select(
+            vec![
+                range(2, 44_100, 44_100, SampleFormat::F32),
+                range(4, 48_000, 48_000, SampleFormat::I16),
+            ],
+            2,
+        );
+        assert_eq!(config.sample_rate().0, TARGET_SAMPLE_RATE);
+        assert_eq!(config.channels(), 4);
+        assert_eq!(config.sample_format(), SampleFormat::I16);
+    }
+
+    #[test]
+    fn no_supported_config_is_an_error() {
+        assert!(
+            DeviceSelector::select_stream_config(Vec::new(), 2, StreamConfigHints::default())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn channels_hint_outweighs_sample_rate() {
+        let configs = vec![
+            range(2, 48_000, 48_000, SampleFormat::F32),
+            range(1, 44_100, 44_100, SampleFormat::I16),
+        ];
+        assert_eq!(select(configs.clone(), 2).channels(), 2);
+
+        let hints = StreamConfigHints {
+            channels: Some(1),
+            sample_rate: None,
+        };
+        let config = select_with_hints(configs, 2, hints);
+        assert_eq!(config.channels(), 1);
+        assert_eq!(config.sample_rate().0, 44_100);
+    }
+
+    #[test]
+    fn sample_rate_hint_picks_hinted_rate() {
+        let configs = vec![
+            range(2, 8_000, 96_000, SampleFormat::F32),
+            range(2, 48_000, 48_000, SampleFormat::F32),
+        ];
+        assert_eq!(
+            select(configs.clone(), 2).sample_rate().0,
+            TARGET_SAMPLE_RATE
+        );
+
+        let hints = StreamConfigHints {
+            channels: None,
+            sample_rate: Some(44_100),
+        };
+        assert_eq!(select_with_hints(configs, 2, hints).sample_rate().0, 44_100);
+    }
+
+    #[test]
+    fn unsupported_hints_are_ignored() {
+        let configs = vec![
+            range(2, 44_100, 44_100, SampleFormat::F32),
+            range(4, 48_000, 48_000, SampleFormat::I16),
+        ];
+        let hints = StreamConfigHints {
+            channels: Some(1),
+            sample_rate: Some(96_000),
+        };
+
+        assert_eq!(
+            select_with_hints(configs.clone(), 2, hints),
+            select(configs, 2)
+        );
+    }
+
+    #[test]
+    fn gathers_device_capabilities() {
+        let devices = [
+            (
+                "Headset",
+                Ok(vec![
+                    range(2, 44_100, 44_100, SampleFormat::F32),
+                    range(1, 8_000, 48_000, SampleFormat::I16),
+                ]),
+            ),
+            ("Empty", Ok(Vec::new())),
+            (
+                "Unplugged",
+                Err(AudioError::Other(anyhow::anyhow!("Device not available"))),
+            ),
+        ];
+
+        let capabilities = devices
+            .into_iter()
+            .map(|(name, configs)| {
+                DeviceCapabilities::new(
+                    DeviceType::Input,
+                    name.to_string(),
+                    configs,
+                    StreamConfigHints::default(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let headset = &capabilities[0];
+        assert_eq!(headset.device_name, "Headset");
+        assert_eq!(
+            headset.supported_configs,
+            vec![
+                StreamConfigRangeInfo {
+                    channels: 2,
+                    min_sample_rate: 44_100,
+                    max_sample_rate: 44_100,
+                    sample_format: "f32".to_string(),
+                },
+                StreamConfigRangeInfo {
+                    channels: 1,
+                    min_sample_rate: 8_000,
+                    max_sample_rate: 48_000,
+                    sample_format: "i16".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            headset.picked_config,
+            Some(PickedStreamConfig {
+                channels: 1,
+                sample_rate: TARGET_SAMPLE_RATE,
+                sample_format: "i16".to_string(),
+                resampled: false,
+            })
+        );
+        assert_eq!(headset.error, None);
+
+        let empty = &capabilities[1];
+        assert!(empty.supported_configs.is_empty());
+        assert_eq!(empty.picked_config, None);
+        assert!(empty.error.is_some());
+
+        let unplugged = &capabilities[2];
+        assert_eq!(unplugged.picked_config, None);
+        assert!(
+            unplugged
+                .error
+                .as_deref()
+                .is_some_and(|err| err.contains("Device not available"))
+        );
+    }
+
+    #[test]
+    fn finds_host_names() {
+        let hosts = vec!["ALSA".to_string(), "JACK".to_string()];
+
+        assert_eq!(DeviceSelector::find_host_name("alsa", &hosts), Some("ALSA"));
+        assert_eq!(DeviceSelector::find_host_name("JAC", &hosts), Some("JACK"));
+        assert_eq!(DeviceSelector::find_host_name("PulseAudio", &hosts), None);
+        assert_eq!(DeviceSelector::find_host_name("ASIO", &[]), None);
+    }
+
+    #[test]
+    fn channel_map_defaults_to_all_channels() {
+        assert_eq!(mix(&ChannelMap::resolve(None, 1), 0.5), vec![0.5]);
+        assert_eq!(mix(&ChannelMap::resolve(None, 2), 0.5), vec![0.5, 0.5]);
+        assert_eq!(mix(&ChannelMap::resolve(None, 6), 0.5), vec![0.5; 6]);
+    }
+
+    #[test]
+    fn channel_map_selects_requested_channels() {
+        let map = ChannelMap::resolve(Some(&[3, 4]), 6);
+        assert_eq!(mix(&map, 0.5), vec![0.0, 0.0, 0.5, 0.5, 0.0, 0.0]);
+
+        let map = ChannelMap::resolve(Some(&[2]), 2);
+        assert_eq!(mix(&map, 0.5), vec![0.0, 0.5]);
+
+        let map = ChannelMap::resolve(Some(&[1, 1, 8]), 8);
+        assert_eq!(mix(&map, 0.5), vec![0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn channel_map_falls_back_when_out_of_range() {
+        assert_eq!(ChannelMap::resolve(Some(&[3, 4]), 2), ChannelMap::all(2));
+        assert_eq!(ChannelMap::resolve(Some(&[0]), 2), ChannelMap::all(2));
+        assert_eq!(ChannelMap::resolve(Some(&[]), 6), ChannelMap::all(6));
+        assert_eq!(ChannelMap::resolve(Some(&[1]), 0), ChannelMap::all(1));
+    }
+
+    #[test]
+    fn channel_map_ignores_truncated_frames() {
+        let map = ChannelMap::resolve(Some(&[3, 4]), 6);
+        let mut frame = vec![0.0f32; 3];
+        map.mix_into_frame(&mut frame, 0.5);
+        assert_eq!(frame, vec![0.0, 0.0, 0.5]);
+    }
+}
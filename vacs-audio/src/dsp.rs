@@ -57,9 +57,77 @@ const GATE_ATTACK_S: f32 = 0.008f32; // 8 ms
 /// Range: 0.050..=0.200 (50–200 ms).
 const GATE_RELEASE_S: f32 = 0.090f32; // 90 ms
 
-/// Soft limiter ceiling in dBFS. Set just below 0 dBFS to avoid clipping.
-/// Range: -6.0..=-0.1. More negative = gentler, more headroom.
-const LIMITER_THR_DBFS: f32 = -1.0f32;
+/// Comfort noise level relative to the recent signal RMS (linear).
+/// Range: 0.05..=0.2. Higher = more audible noise floor during underruns.
+const COMFORT_NOISE_RELATIVE_LEVEL: f32 = 0.1f32;
+
+/// Maximum comfort noise amplitude in dBFS, keeps loud speech from producing loud noise.
+/// Range: -60..=-35 dB.
+const COMFORT_NOISE_MAX_DBFS: f32 = -40.0f32;
+
+/// Time constant (seconds) of the signal energy tracker used to shape comfort noise.
+/// Range: 0.1..=1.0. Longer = noise level follows the conversation rather than single syllables.
+const COMFORT_NOISE_ENERGY_TAU_S: f32 = 0.3f32;
+
+/// Crossfade time (seconds) between the signal and comfort noise on underrun and recovery.
+/// Range: 0.002..=0.010 (2–10 ms). Shorter = more likely to click.
+const COMFORT_NOISE_FADE_S: f32 = 0.005f32; // 5 ms
+
+/// Time constant (seconds) of the far-end loudness estimate used for normalization.
+/// Range: 1.0..=10.0. Longer = slower, less audible gain changes.
+const LOUDNESS_TAU_S: f32 = 3.0f32;
+
+/// Frames with an RMS below this level (dBFS) are treated as speech pauses, holding the gain.
+/// Range: -60..=-40 dB. Less negative = more background noise ignored, but quiet speech too.
+const LOUDNESS_SPEECH_THRESHOLD_DB: f32 = -50.0f32;
+
+/// Maximum gain (dB) applied to quiet peers, limits how much their noise floor is amplified.
+/// Range: 6..=18 dB.
+const LOUDNESS_MAX_GAIN_DB: f32 = 12.0f32;
+
+/// Minimum gain (dB) applied to loud peers.
+/// Range: -24..=-6 dB.
+const LOUDNESS_MIN_GAIN_DB: f32 = -18.0f32;
+
+/// Ceiling (dBFS) of the soft limiter applied after the normalization gain, catching peaks of
+/// sudden loud speech before the gain has adapted.
+/// Range: -6.0..=-0.1. More negative = more headroom.
+const LOUDNESS_LIMITER_CEILING_DBFS: f32 = -1.0f32;
+
+/// Far-end RMS level (dBFS) above which a peer is considered speaking.
+/// Range: -50..=-30 dB. Less negative = background noise is less likely to trigger.
+const SPEAKING_OPEN_DB: f32 = -40.0f32;
+
+/// Far-end RMS level (dBFS) below which a peer stops speaking (must be < open for hysteresis).
+/// Range: (SPEAKING_OPEN_DB-12)..=(SPEAKING_OPEN_DB-4).
+const SPEAKING_CLOSE_DB: f32 = -48.0f32;
+
+/// Time constant (seconds) of the short-window energy estimate used for speaking detection.
+/// Range: 0.02..=0.1. Longer = smoother, but slower to react.
+const SPEAKING_ENERGY_TAU_S: f32 = 0.05f32;
+
+/// Time (seconds) the level has to stay below the close threshold before speaking ends.
+/// Range: 0.2..=0.5. Longer = brief pauses between words don't end the indication.
+const SPEAKING_HANGOVER_S: f32 = 0.3f32;
+
+/// Oversampling factor used for true-peak detection (ITU-R BS.1770 recommends 4x at 48 kHz).
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+
+/// Number of taps of the windowed-sinc interpolation filter used for true-peak detection.
+/// Range: 8..=48. More taps = more accurate inter-sample peaks at a higher CPU cost.
+const TRUE_PEAK_TAPS: usize = 16;
+
+/// Lowest configurable soft limiter ceiling in dBFS. More negative = more headroom.
+const LIMITER_MIN_CEILING_DBFS: f32 = -6.0f32;
+
+/// Highest configurable soft limiter ceiling in dBFS. Set just below 0 dBFS to avoid clipping.
+const LIMITER_MAX_CEILING_DBFS: f32 = -0.1f32;
+
+/// Distance (dB) below the ceiling at which the soft limiter starts compressing peaks.
+/// Range: 1.0..=6.0. Larger = softer knee, but speech peaks are touched earlier.
+/// With the default ceiling of -1 dBFS, the knee sits at -4 dBFS. The previous fixed limiter
+/// started compressing at -1 dBFS and let peaks approach 0 dBFS instead.
+const LIMITER_KNEE_DB: f32 = 3.0f32;
 
 /// One-pole DC blocker (very low-cut high-pass).
 /// Removes DC bias and sub-Hz drift without coloring audible band.
@@ -152,32 +220,41 @@ impl NoiseGate {
     }
 }
 
-/// Simple peak soft-knee limiter near 0 dBFS.
-/// Transparent under normal speech; gently tames unexpected peaks.
+/// Simple peak soft-knee limiter below 0 dBFS.
+/// Transparent under normal speech; peaks above the knee approach, but never exceed the ceiling.
 struct SoftLimiter {
-    thr: f32, // linear amplitude
+    ceiling: f32, // linear amplitude
+    knee: f32,    // linear amplitude
 }
 
-impl Default for SoftLimiter {
-    fn default() -> Self {
+impl SoftLimiter {
+    fn new(ceiling_dbfs: f32) -> Self {
+        let lin = |db: f32| 10.0f32.powf(db / 20.0f32);
+        let ceiling_dbfs = ceiling_dbfs.clamp(LIMITER_MIN_CEILING_DBFS, LIMITER_MAX_CEILING_DBFS);
         Self {
-            thr: 10.0f32.powf(LIMITER_THR_DBFS / 20.0f32),
+            ceiling: lin(ceiling_dbfs),
+            knee: lin(ceiling_dbfs - LIMITER_KNEE_DB),
         }
     }
-}
 
-impl SoftLimiter {
+    /// Limits all samples of the frame, returning the number of samples which exceeded the
+    /// ceiling and were pulled back below it.
     #[inline]
-    pub fn process_frame(&mut self, frame: &mut [f32]) {
+    pub fn process_frame(&mut self, frame: &mut [f32]) -> usize {
+        let range = self.ceiling - self.knee;
+        let mut clipped = 0usize;
         for s in frame.iter_mut() {
             let a = s.abs();
-            if a > self.thr {
-                let sign = s.signum();
-                let over = (a - self.thr) / (1.0f32 - self.thr + 1e-9f32);
-                let soft = self.thr + over / (1.0f32 + over); // soft knee curve
-                *s = sign * soft.min(0.9999f32);
+            if a > self.knee {
+                if a > self.ceiling {
+                    clipped += 1;
+                }
+                // tanh has unity slope at 0, so the curve joins the linear part without a kink
+                let soft = self.knee + range * ((a - self.knee) / range).tanh();
+                *s = s.signum() * soft.min(self.ceiling);
             }
         }
+        clipped
     }
 }
 
@@ -190,8 +267,10 @@ pub struct MicProcessor {
     soft_limiter: SoftLimiter,
 }
 
-impl Default for MicProcessor {
-    fn default() -> Self {
+impl MicProcessor {
+    /// Creates a new processor limiting peaks to the given ceiling (dBFS), clamped to
+    /// [`LIMITER_MIN_CEILING_DBFS`]..=[`LIMITER_MAX_CEILING_DBFS`].
+    pub fn new(limiter_ceiling_dbfs: f32) -> Self {
         let coeffs = Coefficients::from_params(
             Type::HighPass,
             TARGET_SAMPLE_RATE.hz(),
@@ -203,15 +282,15 @@ impl Default for MicProcessor {
             dc_block: DcBlock::default(),
             hpf: DirectForm2Transposed::new(coeffs),
             noise_gate: NoiseGate::default(),
-            soft_limiter: SoftLimiter::default(),
+            soft_limiter: SoftLimiter::new(limiter_ceiling_dbfs),
         }
     }
-}
 
-impl MicProcessor {
     /// Process one 20 ms (960-sample) frame at [`TARGET_SAMPLE_RATE`].
     /// Assumes frame is **mono f32** at the target rate.
-    pub fn process_frame(&mut self, frame: &mut [f32]) {
+    ///
+    /// Returns the number of samples which exceeded the limiter ceiling.
+    pub fn process_frame(&mut self, frame: &mut [f32]) -> usize {
         // Per-sample IIR (stateful) stages first.
         for s in frame.iter_mut() {
             *s = self.dc_block.process(*s);
@@ -219,6 +298,545 @@ impl MicProcessor {
         }
         // Then frame-level dynamics.
         self.noise_gate.process_frame(frame);
+        self.soft_limiter.process_frame(frame)
+    }
+}
+
+/// Conceals playback buffer underruns with low-level comfort noise instead of hard silence.
+///
+/// Underruns are caused by network jitter, but also by peers pausing transmission while not
+/// speaking (Opus DTX), so comfort noise also keeps their silence from sounding like a dropout.
+///
+/// While samples are available, they are passed through unchanged and only used to track the
+/// recent signal energy. On underrun, the last sample decays while crossfading into white noise
+/// shaped to that energy; once samples arrive again, the signal is crossfaded back in.
+pub(crate) struct ComfortNoise {
+    energy: f32,
+    energy_coeff: f32,
+    max_amplitude: f32,
+    fade: f32,
+    fade_step: f32,
+    held: f32,
+    rng: u32,
+}
+
+impl Default for ComfortNoise {
+    fn default() -> Self {
+        let fs = TARGET_SAMPLE_RATE as f32;
+        Self {
+            energy: 0.0f32,
+            energy_coeff: 1.0 - (-1.0 / (COMFORT_NOISE_ENERGY_TAU_S * fs)).exp(),
+            max_amplitude: 10.0f32.powf(COMFORT_NOISE_MAX_DBFS / 20.0f32),
+            fade: 0.0f32,
+            fade_step: 1.0 / (COMFORT_NOISE_FADE_S * fs),
+            held: 0.0f32,
+            rng: 0x9E37_79B9,
+        }
+    }
+}
+
+impl ComfortNoise {
+    /// Returns the next output sample, given the next buffered sample or `None` on underrun.
+    #[inline]
+    pub fn process(&mut self, sample: Option<f32>) -> f32 {
+        match sample {
+            Some(s) => {
+                self.energy += self.energy_coeff * (s * s - self.energy);
+                self.held = s;
+
+                if self.fade <= 0.0 {
+                    return s;
+                }
+
+                self.fade = (self.fade - self.fade_step).max(0.0);
+                s * (1.0 - self.fade) + self.noise() * self.fade
+            }
+            None => {
+                self.fade = (self.fade + self.fade_step).min(1.0);
+                self.held *= 1.0 - self.fade_step;
+                self.held * (1.0 - self.fade) + self.noise() * self.fade
+            }
+        }
+    }
+
+    #[inline]
+    fn noise(&mut self) -> f32 {
+        // xorshift32, uniform in [-1.0, 1.0)
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        let white = (self.rng as f32 / u32::MAX as f32) * 2.0 - 1.0;
+
+        let amplitude = (self.energy.sqrt() * COMFORT_NOISE_RELATIVE_LEVEL).min(self.max_amplitude);
+        white * amplitude
+    }
+}
+
+/// Slowly adjusts the gain of a far-end stream so its speech RMS approaches a target level.
+///
+/// Loudness is only tracked on frames containing speech, the gain is held during pauses to avoid
+/// pumping up the background noise. Until [`LOUDNESS_TAU_S`] worth of speech has been observed, the
+/// estimate is a plain average, so a new peer is normalized quickly.
+pub(crate) struct LoudnessNormalizer {
+    target_energy: f32,
+    speech_threshold_energy: f32,
+    min_gain: f32,
+    max_gain: f32,
+    energy: f32,
+    speech_samples: f32,
+    gain: f32,
+    soft_limiter: SoftLimiter,
+}
+
+impl LoudnessNormalizer {
+    pub fn new(target_dbfs: f32) -> Self {
+        let lin = |db: f32| 10.0f32.powf(db / 20.0f32);
+        Self {
+            target_energy: lin(target_dbfs).powi(2),
+            speech_threshold_energy: lin(LOUDNESS_SPEECH_THRESHOLD_DB).powi(2),
+            min_gain: lin(LOUDNESS_MIN_GAIN_DB),
+            max_gain: lin(LOUDNESS_MAX_GAIN_DB),
+            energy: 0.0f32,
+            speech_samples: 0.0f32,
+            gain: 1.0f32,
+            soft_limiter: SoftLimiter::new(LOUDNESS_LIMITER_CEILING_DBFS),
+        }
+    }
+
+    /// Process one decoded frame at [`TARGET_SAMPLE_RATE`], ramping the gain across the frame and
+    /// limiting the amplified peaks.
+    pub fn process_frame(&mut self, frame: &mut [f32]) {
+        if frame.is_empty() {
+            return;
+        }
+
+        let len = frame.len() as f32;
+        let energy = frame.iter().map(|s| s * s).sum::<f32>() / len;
+
+        let target_gain = if energy >= self.speech_threshold_energy {
+            let fs = TARGET_SAMPLE_RATE as f32;
+            self.speech_samples = (self.speech_samples + len).min(LOUDNESS_TAU_S * fs);
+            let coeff = (len / self.speech_samples).max(1.0 - (-len / (LOUDNESS_TAU_S * fs)).exp());
+            self.energy += coeff * (energy - self.energy);
+
+            (self.target_energy / self.energy)
+                .sqrt()
+                .clamp(self.min_gain, self.max_gain)
+        } else {
+            self.gain
+        };
+
+        let step = (target_gain - self.gain) / len;
+        for s in frame.iter_mut() {
+            self.gain += step;
+            *s *= self.gain;
+        }
+        self.gain = target_gain;
         self.soft_limiter.process_frame(frame);
     }
 }
+
+/// Detects whether a far-end stream currently contains speech, based on its short-window energy.
+///
+/// Uses separate open/close thresholds and a hangover time, so the indication doesn't flicker on
+/// brief pauses or levels hovering around a single threshold.
+pub(crate) struct SpeakingDetector {
+    open_energy: f32,
+    close_energy: f32,
+    energy_coeff: f32,
+    energy: f32,
+    hangover_samples: usize,
+    quiet_samples: usize,
+    speaking: bool,
+}
+
+impl Default for SpeakingDetector {
+    fn default() -> Self {
+        let fs = TARGET_SAMPLE_RATE as f32;
+        let energy = |db: f32| 10.0f32.powf(db / 10.0f32);
+        Self {
+            open_energy: energy(SPEAKING_OPEN_DB),
+            close_energy: energy(SPEAKING_CLOSE_DB),
+            energy_coeff: 1.0 - (-1.0 / (SPEAKING_ENERGY_TAU_S * fs)).exp(),
+            energy: 0.0f32,
+            hangover_samples: (SPEAKING_HANGOVER_S * fs) as usize,
+            quiet_samples: 0,
+            speaking: false,
+        }
+    }
+}
+
+impl SpeakingDetector {
+    /// Process one decoded frame at [`TARGET_SAMPLE_RATE`], returning the new state if it changed.
+    pub fn process_frame(&mut self, frame: &[f32]) -> Option<bool> {
+        for &s in frame {
+            self.energy += self.energy_coeff * (s * s - self.energy);
+        }
+
+        if self.energy >= self.open_energy {
+            self.quiet_samples = 0;
+            if !self.speaking {
+                self.speaking = true;
+                return Some(true);
+            }
+        } else if self.speaking {
+            if self.energy < self.close_energy {
+                self.quiet_samples += frame.len();
+                if self.quiet_samples >= self.hangover_samples {
+                    self.speaking = false;
+                    self.quiet_samples = 0;
+                    return Some(false);
+                }
+            } else {
+                self.quiet_samples = 0;
+            }
+        }
+        None
+    }
+
+    /// Whether speech is currently indicated.
+    pub fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+}
+
+/// Detects inter-sample peaks by interpolating the signal at [`TRUE_PEAK_OVERSAMPLING`] times
+/// the sample rate using a Hann-windowed sinc filter.
+pub(crate) struct TruePeakDetector {
+    phases: [[f32; TRUE_PEAK_TAPS]; TRUE_PEAK_OVERSAMPLING - 1],
+    history: [f32; TRUE_PEAK_TAPS],
+    pos: usize,
+}
+
+impl Default for TruePeakDetector {
+    fn default() -> Self {
+        let half = (TRUE_PEAK_TAPS / 2) as f32;
+        let mut phases = [[0.0f32; TRUE_PEAK_TAPS]; TRUE_PEAK_OVERSAMPLING - 1];
+        for (k, phase) in phases.iter_mut().enumerate() {
+            // Interpolate between the two center taps at fraction (k + 1) / oversampling
+            let frac = (k + 1) as f32 / TRUE_PEAK_OVERSAMPLING as f32;
+            for (i, coeff) in phase.iter_mut().enumerate() {
+                let d = (half - 1.0 + frac) - i as f32;
+                let x = std::f32::consts::PI * d;
+                let sinc = if d == 0.0 { 1.0 } else { x.sin() / x };
+                let window = 0.5 * (1.0 + (std::f32::consts::PI * d / half).cos());
+                *coeff = sinc * window;
+            }
+            // Normalize to unity DC gain
+            let sum: f32 = phase.iter().sum();
+            phase.iter_mut().for_each(|c| *c /= sum);
+        }
+
+        Self {
+            phases,
+            history: [0.0f32; TRUE_PEAK_TAPS],
+            pos: 0,
+        }
+    }
+}
+
+impl TruePeakDetector {
+    /// Pushes a sample and returns the largest absolute value among it and the interpolated
+    /// samples preceding it.
+    ///
+    /// Interpolated values lag behind the input by half the filter length.
+    #[inline]
+    pub fn process(&mut self, s: f32) -> f32 {
+        self.history[self.pos] = s;
+        self.pos = (self.pos + 1) % TRUE_PEAK_TAPS;
+
+        let mut peak = s.abs();
+        for phase in &self.phases {
+            // history[pos] is the oldest sample
+            let mut acc = 0.0f32;
+            for (i, coeff) in phase.iter().enumerate() {
+                acc += coeff * self.history[(self.pos + i) % TRUE_PEAK_TAPS];
+            }
+            peak = peak.max(acc.abs());
+        }
+        peak
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_INPUT_LIMITER_CEILING_DBFS, FRAME_SIZE};
+
+    /// Largest sample-to-sample step considered inaudible as a click.
+    const MAX_STEP: f32 = 0.05f32;
+
+    fn sine(n: usize, freq: f32, amplitude: f32) -> Vec<f32> {
+        (0..n)
+            .map(|i| {
+                amplitude
+                    * (2.0 * std::f32::consts::PI * freq * i as f32 / TARGET_SAMPLE_RATE as f32)
+                        .sin()
+            })
+            .collect()
+    }
+
+    fn max_step(samples: &[f32]) -> f32 {
+        samples
+            .windows(2)
+            .map(|w| (w[1] - w[0]).abs())
+            .fold(0.0f32, f32::max)
+    }
+
+    fn true_peak(samples: &[f32]) -> f32 {
+        let mut detector = TruePeakDetector::default();
+        samples
+            .iter()
+            .fold(0.0f32, |peak, &s| peak.max(detector.process(s)))
+    }
+
+    fn to_db(lin: f32) -> f32 {
+        20.0 * lin.log10()
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0f32, |acc, s| acc.max(s.abs()))
+    }
+
+    #[test]
+    fn limiter_keeps_over_unity_signal_below_ceiling() {
+        for ceiling_db in [DEFAULT_INPUT_LIMITER_CEILING_DBFS, -3.0, -0.1] {
+            let mut limiter = SoftLimiter::new(ceiling_db);
+            let mut samples = sine(4800, 1000.0, 4.0);
+            let clipped = limiter.process_frame(&mut samples);
+
+            assert!(clipped > 0);
+            assert!(
+                peak(&samples) <= limiter.ceiling,
+                "peak {} exceeds ceiling {ceiling_db} dBFS",
+                to_db(peak(&samples))
+            );
+        }
+    }
+
+    #[test]
+    fn limiter_is_transparent_below_knee() {
+        let mut limiter = SoftLimiter::new(DEFAULT_INPUT_LIMITER_CEILING_DBFS);
+        let original = sine(4800, 1000.0, 0.5);
+        let mut samples = original.clone();
+
+        assert_eq!(limiter.process_frame(&mut samples), 0);
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn limiter_ceiling_is_clamped() {
+        let mut limiter = SoftLimiter::new(6.0);
+        assert!((to_db(limiter.ceiling) - LIMITER_MAX_CEILING_DBFS).abs() < 1e-3);
+
+        let mut samples = sine(4800, 1000.0, 2.0);
+        limiter.process_frame(&mut samples);
+        assert!(peak(&samples) <= limiter.ceiling);
+    }
+
+    #[test]
+    fn mic_processor_limits_amplified_input() {
+        let mut processor = MicProcessor::new(-3.0);
+        let mut samples = sine(FRAME_SIZE * 10, 1000.0, 0.5)
+            .into_iter()
+            .map(|s| s * 4.0)
+            .collect::<Vec<_>>();
+
+        let clipped = samples
+            .chunks_mut(FRAME_SIZE)
+            .map(|frame| processor.process_frame(frame))
+            .sum::<usize>();
+
+        assert!(clipped > 0);
+        assert!(to_db(peak(&samples)) <= -3.0 + 1e-3);
+    }
+
+    #[test]
+    fn true_peak_detects_inter_sample_peaks() {
+        // Sine at fs/4 with 45° phase offset, every sample lands at ±0.707 while the signal peaks at 1.0
+        let samples = (0..480)
+            .map(|i| (std::f32::consts::FRAC_PI_2 * i as f32 + std::f32::consts::FRAC_PI_4).sin())
+            .collect::<Vec<_>>();
+
+        let sample_peak = samples.iter().fold(0.0f32, |acc, s| acc.max(s.abs()));
+        assert!((to_db(sample_peak) + 3.01).abs() < 0.01);
+        assert!(to_db(true_peak(&samples)).abs() < 0.5);
+    }
+
+    #[test]
+    fn true_peak_matches_sample_peak_for_low_frequencies() {
+        let samples = sine(4800, 1000.0, 0.5);
+        assert!((to_db(true_peak(&samples)) - to_db(0.5)).abs() < 0.1);
+    }
+
+    #[test]
+    fn true_peak_of_silence_is_zero() {
+        assert_eq!(true_peak(&[0.0f32; 480]), 0.0);
+    }
+
+    fn rms_db(samples: &[f32]) -> f32 {
+        to_db((samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt())
+    }
+
+    fn normalize(normalizer: &mut LoudnessNormalizer, mut samples: Vec<f32>) -> Vec<f32> {
+        for frame in samples.chunks_mut(FRAME_SIZE) {
+            normalizer.process_frame(frame);
+        }
+        samples
+    }
+
+    #[test]
+    fn loudness_normalizes_loud_and_quiet_peers() {
+        const TARGET_DB: f32 = -20.0;
+        // Separate normalizers per peer, so the loud one does not affect the quiet one
+        let mut loud = LoudnessNormalizer::new(TARGET_DB);
+        let mut quiet = LoudnessNormalizer::new(TARGET_DB);
+
+        let loud_out = normalize(&mut loud, sine(TARGET_SAMPLE_RATE as usize * 5, 300.0, 0.7));
+        let quiet_out = normalize(
+            &mut quiet,
+            sine(TARGET_SAMPLE_RATE as usize * 5, 300.0, 0.05),
+        );
+
+        let last_second = TARGET_SAMPLE_RATE as usize * 4..;
+        assert!((rms_db(&loud_out[last_second.clone()]) - TARGET_DB).abs() < 0.5);
+        assert!((rms_db(&quiet_out[last_second]) - TARGET_DB).abs() < 0.5);
+        assert!(max_step(&loud_out) < MAX_STEP);
+        assert!(max_step(&quiet_out) < MAX_STEP);
+    }
+
+    #[test]
+    fn loudness_gain_is_limited() {
+        let mut normalizer = LoudnessNormalizer::new(-20.0);
+        // -60 dBFS speech would require +40 dB
+        let out = normalize(&mut normalizer, sine(FRAME_SIZE * 50, 300.0, 0.0014));
+        assert!(normalizer.gain <= 10.0f32.powf(LOUDNESS_MAX_GAIN_DB / 20.0) + 1e-3);
+        assert!(rms_db(&out) < -40.0);
+    }
+
+    #[test]
+    fn loudness_gain_is_followed_by_limiter() {
+        let mut normalizer = LoudnessNormalizer::new(-20.0);
+        normalize(&mut normalizer, sine(FRAME_SIZE * 100, 300.0, 0.02));
+        assert!(normalizer.gain > 3.0);
+
+        // A sudden shout hits the full gain before the estimate catches up
+        let out = normalize(&mut normalizer, sine(FRAME_SIZE * 5, 300.0, 0.9));
+        assert!(to_db(peak(&out)) <= LOUDNESS_LIMITER_CEILING_DBFS + 1e-3);
+    }
+
+    #[test]
+    fn loudness_gain_is_held_during_pauses() {
+        let mut normalizer = LoudnessNormalizer::new(-20.0);
+        normalize(&mut normalizer, sine(FRAME_SIZE * 100, 300.0, 0.05));
+        let gain = normalizer.gain;
+        assert!(gain > 1.0);
+
+        // Two seconds of silence and faint background noise do not pump up the gain
+        let pause = (0..FRAME_SIZE * 100)
+            .map(|i| if i % 2 == 0 { 0.001 } else { -0.001 })
+            .collect::<Vec<_>>();
+        let pause_out = normalize(&mut normalizer, pause);
+        assert_eq!(normalizer.gain, gain);
+        assert!(rms_db(&pause_out) < -45.0);
+
+        // Speech after the pause continues at the normalized level right away
+        let out = normalize(&mut normalizer, sine(FRAME_SIZE * 5, 300.0, 0.05));
+        assert!((rms_db(&out) + 20.0).abs() < 0.5);
+    }
+
+    /// Feeds the samples frame by frame and returns all state changes with their frame index.
+    fn speaking_changes(detector: &mut SpeakingDetector, samples: &[f32]) -> Vec<(usize, bool)> {
+        samples
+            .chunks(FRAME_SIZE)
+            .enumerate()
+            .filter_map(|(i, frame)| detector.process_frame(frame).map(|speaking| (i, speaking)))
+            .collect()
+    }
+
+    #[test]
+    fn speaking_detected_on_speech() {
+        let mut detector = SpeakingDetector::default();
+        assert!(speaking_changes(&mut detector, &[0.0; FRAME_SIZE * 50]).is_empty());
+
+        let changes = speaking_changes(&mut detector, &sine(FRAME_SIZE * 50, 300.0, 0.1));
+        assert_eq!(changes, vec![(0, true)]);
+    }
+
+    #[test]
+    fn speaking_does_not_flicker_on_brief_pauses() {
+        let mut detector = SpeakingDetector::default();
+        let speech = sine(FRAME_SIZE * 15, 300.0, 0.1);
+
+        let mut changes = Vec::new();
+        for _ in 0..5 {
+            changes.extend(speaking_changes(&mut detector, &speech));
+            // 200 ms pause between words
+            changes.extend(speaking_changes(&mut detector, &[0.0; FRAME_SIZE * 10]));
+        }
+        assert_eq!(changes, vec![(0, true)]);
+    }
+
+    #[test]
+    fn speaking_ends_after_hangover() {
+        let mut detector = SpeakingDetector::default();
+        speaking_changes(&mut detector, &sine(FRAME_SIZE * 50, 300.0, 0.1));
+
+        let changes = speaking_changes(&mut detector, &[0.0; FRAME_SIZE * 100]);
+        assert_eq!(changes.len(), 1);
+        let (frame, speaking) = changes[0];
+        assert!(!speaking);
+        // Not before the hangover has passed after the level dropped below the close threshold
+        assert!(frame * FRAME_SIZE >= (SPEAKING_HANGOVER_S * TARGET_SAMPLE_RATE as f32) as usize);
+    }
+
+    #[test]
+    fn speaking_uses_hysteresis() {
+        // RMS of -44 dBFS, between the close and open thresholds
+        let between = sine(FRAME_SIZE * 100, 300.0, 0.0089);
+
+        let mut detector = SpeakingDetector::default();
+        assert!(speaking_changes(&mut detector, &between).is_empty());
+
+        let mut detector = SpeakingDetector::default();
+        speaking_changes(&mut detector, &sine(FRAME_SIZE * 50, 300.0, 0.1));
+        assert!(speaking_changes(&mut detector, &between).is_empty());
+    }
+
+    #[test]
+    fn comfort_noise_passes_samples_through() {
+        let mut cn = ComfortNoise::default();
+        for s in sine(4800, 200.0, 0.5) {
+            assert_eq!(cn.process(Some(s)), s);
+        }
+    }
+
+    #[test]
+    fn comfort_noise_on_empty_buffer_is_silent_initially() {
+        let mut cn = ComfortNoise::default();
+        for _ in 0..4800 {
+            assert_eq!(cn.process(None), 0.0);
+        }
+    }
+
+    #[test]
+    fn comfort_noise_underrun_does_not_click() {
+        let mut cn = ComfortNoise::default();
+        let signal = sine(9600, 200.0, 0.5);
+
+        let mut output = Vec::new();
+        output.extend(signal[..4800].iter().map(|&s| cn.process(Some(s))));
+        output.extend((0..4800).map(|_| cn.process(None)));
+        output.extend(signal[4800..].iter().map(|&s| cn.process(Some(s))));
+
+        assert!(max_step(&output) < MAX_STEP);
+
+        let underrun = &output[4800 + 480..9600];
+        let peak = underrun.iter().fold(0.0f32, |acc, s| acc.max(s.abs()));
+        assert!(peak > 0.0, "underrun should produce comfort noise");
+        assert!(peak <= 10.0f32.powf(COMFORT_NOISE_MAX_DBFS / 20.0f32));
+
+        // Once recovered, samples are passed through unchanged again
+        assert_eq!(&output[output.len() - 100..], &signal[signal.len() - 100..]);
+    }
+}
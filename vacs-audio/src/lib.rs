@@ -1,3 +1,4 @@
+pub mod codec;
 pub mod device;
 mod dsp;
 pub mod error;
@@ -15,3 +16,7 @@ pub type EncodedAudioFrame = bytes::Bytes;
 pub const TARGET_SAMPLE_RATE: u32 = 48_000;
 pub const FRAME_DURATION_MS: u64 = 20;
 const FRAME_SIZE: usize = TARGET_SAMPLE_RATE as usize * FRAME_DURATION_MS as usize / 1000;
+const MAX_OPUS_FRAME_SIZE: usize = 1275; // max size of an Opus frame according to RFC 6716 3.2.1.
+/// Default ceiling (dBFS) of the soft limiter applied to the input before encoding.
+/// The limiter starts compressing 3 dB below the ceiling, at -4 dBFS by default.
+pub const DEFAULT_INPUT_LIMITER_CEILING_DBFS: f32 = -1.0;
@@ -1,21 +1,120 @@
 use crate::cpal;
 use crate::sources::{AudioSource, AudioSourceId};
 use std::collections::HashMap;
+use std::time::Duration;
 
-#[derive(Default)]
+/// Maximum gain applied to a source, allows boosting quiet sources above unity gain.
+const MAX_SOURCE_GAIN: f32 = 2.0;
+
+/// Mixes all registered [`AudioSource`]s into the output buffer.
+///
+/// The mixer owns the gain of its sources: the volume a source was created with is taken over when
+/// it is added, after which the source itself runs at unity gain. Volume changes are applied as a
+/// linear ramp over the configured duration to avoid zipper noise on fast slider movements.
 pub struct Mixer {
-    sources: HashMap<AudioSourceId, Box<dyn AudioSource>>,
+    sources: HashMap<AudioSourceId, MixerSource>,
+    channels: usize,    // >= 1
+    ramp_frames: usize, // >= 1
+    buffer: Vec<f32>,
+}
+
+struct MixerSource {
+    source: Box<dyn AudioSource>,
+    gain: GainRamp,
+    /// Frames left until a draining source is removed, `None` while the source is not draining.
+    drain_frames: Option<usize>,
+}
+
+/// Gain moving linearly towards its target over a fixed number of frames.
+#[derive(Debug, Clone, Copy)]
+struct GainRamp {
+    current: f32,
+    target: f32,
+    step: f32,
+    remaining_frames: usize,
+}
+
+impl GainRamp {
+    fn new(gain: f32) -> Self {
+        Self {
+            current: gain,
+            target: gain,
+            step: 0.0,
+            remaining_frames: 0,
+        }
+    }
+
+    fn set_target(&mut self, target: f32, ramp_frames: usize) {
+        self.target = target;
+        self.step = (target - self.current) / ramp_frames as f32;
+        self.remaining_frames = ramp_frames;
+    }
+
+    fn next(&mut self) -> f32 {
+        if self.remaining_frames > 0 {
+            self.remaining_frames -= 1;
+            // Snap to the target on the last frame to avoid accumulating rounding errors
+            self.current = if self.remaining_frames == 0 {
+                self.target
+            } else {
+                self.current + self.step
+            };
+        }
+        self.current
+    }
 }
 
 impl Mixer {
+    pub fn new(sample_rate: u32, channels: usize, volume_ramp: Duration) -> Self {
+        Self {
+            sources: HashMap::new(),
+            channels: channels.max(1),
+            ramp_frames: ((volume_ramp.as_secs_f32() * sample_rate as f32) as usize).max(1),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Number of frames covering the given duration at the given sample rate.
+    pub fn frames_for(sample_rate: u32, duration: Duration) -> usize {
+        (duration.as_secs_f32() * sample_rate as f32) as usize
+    }
+
     pub fn mix(&mut self, output: &mut [f32]) {
         // Initialize the output buffer by writing EQUILIBRIUM to all of its samples. AudioSources will
         // add their own samples on top of this.
         output.fill(cpal::Sample::EQUILIBRIUM);
 
-        // Mix all sources into the output buffer, adding their samples on top of the EQUILIBRIUM.
+        // Only reallocates if the device requests a larger buffer than before.
+        if self.buffer.len() < output.len() {
+            self.buffer.resize(output.len(), cpal::Sample::EQUILIBRIUM);
+        }
+        let buffer = &mut self.buffer[..output.len()];
+
+        // Draining sources are removed once they played all buffered samples or their window elapsed.
+        self.sources.retain(|_, src| {
+            !src.drain_frames
+                .is_some_and(|remaining| remaining == 0 || src.source.is_drained())
+        });
+
+        // Mix each source into the scratch buffer first, so its gain can be applied per frame before
+        // adding its samples on top of the output buffer.
         for src in self.sources.values_mut() {
-            src.mix_into(output);
+            buffer.fill(cpal::Sample::EQUILIBRIUM);
+            src.source.mix_into(buffer);
+
+            for (out_frame, frame) in output
+                .chunks_mut(self.channels)
+                .zip(buffer.chunks(self.channels))
+            {
+                let gain = src.gain.next();
+                for (out, sample) in out_frame.iter_mut().zip(frame) {
+                    *out += sample * gain;
+                }
+            }
+
+            if let Some(remaining) = &mut src.drain_frames {
+                *remaining = remaining.saturating_sub(output.len() / self.channels);
+            }
         }
 
         // Clamp mixed samples to [-1.0, 1.0] to avoid clipping.
@@ -24,35 +123,241 @@ impl Mixer {
         }
     }
 
-    pub fn add_source(&mut self, source_id: AudioSourceId, source: Box<dyn AudioSource>) {
-        self.sources.insert(source_id, source);
+    pub fn add_source(&mut self, source_id: AudioSourceId, mut source: Box<dyn AudioSource>) {
+        let gain = GainRamp::new(source.volume());
+        source.set_volume(1.0);
+        self.sources.insert(
+            source_id,
+            MixerSource {
+                source,
+                gain,
+                drain_frames: None,
+            },
+        );
     }
 
     pub fn remove_source(&mut self, source_id: AudioSourceId) {
         self.sources.remove(&source_id);
     }
 
+    /// Removes the source once it played all of its buffered samples, but after `max_frames` at
+    /// the latest.
+    pub fn drain_source(&mut self, source_id: AudioSourceId, max_frames: usize) {
+        if let Some(src) = self.sources.get_mut(&source_id) {
+            src.drain_frames = Some(max_frames);
+        }
+    }
+
     pub fn start_source(&mut self, source_id: AudioSourceId) {
-        if let Some(source) = self.sources.get_mut(&source_id) {
-            source.start();
+        if let Some(src) = self.sources.get_mut(&source_id) {
+            src.source.start();
         }
     }
 
     pub fn stop_source(&mut self, source_id: AudioSourceId) {
-        if let Some(source) = self.sources.get_mut(&source_id) {
-            source.stop();
+        if let Some(src) = self.sources.get_mut(&source_id) {
+            src.source.stop();
         }
     }
 
     pub fn restart_source(&mut self, source_id: AudioSourceId) {
-        if let Some(source) = self.sources.get_mut(&source_id) {
-            source.restart();
+        if let Some(src) = self.sources.get_mut(&source_id) {
+            src.source.restart();
         }
     }
 
     pub fn set_source_volume(&mut self, source_id: AudioSourceId, volume: f32) {
-        if let Some(source) = self.sources.get_mut(&source_id) {
-            source.set_volume(volume);
+        if let Some(src) = self.sources.get_mut(&source_id) {
+            src.gain
+                .set_target(volume.clamp(0.0, MAX_SOURCE_GAIN), self.ramp_frames);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Source emitting a constant sample on all channels.
+    struct ConstantSource {
+        value: f32,
+        volume: f32,
+    }
+
+    impl AudioSource for ConstantSource {
+        fn mix_into(&mut self, output: &mut [f32]) {
+            for s in output {
+                *s += self.value * self.volume;
+            }
+        }
+
+        fn start(&mut self) {}
+
+        fn stop(&mut self) {}
+
+        fn set_volume(&mut self, volume: f32) {
+            self.volume = volume;
+        }
+
+        fn volume(&self) -> f32 {
+            self.volume
         }
     }
+
+    /// Source playing back a fixed number of buffered samples on all channels.
+    struct BufferedSource {
+        samples: VecDeque<f32>,
+        channels: usize,
+    }
+
+    impl AudioSource for BufferedSource {
+        fn mix_into(&mut self, output: &mut [f32]) {
+            for frame in output.chunks_mut(self.channels) {
+                let Some(sample) = self.samples.pop_front() else {
+                    break;
+                };
+                frame.iter_mut().for_each(|s| *s += sample);
+            }
+        }
+
+        fn start(&mut self) {}
+
+        fn stop(&mut self) {}
+
+        fn set_volume(&mut self, _volume: f32) {}
+
+        fn volume(&self) -> f32 {
+            1.0
+        }
+
+        fn is_drained(&self) -> bool {
+            self.samples.is_empty()
+        }
+    }
+
+    fn mixer_with_source(volume: f32) -> Mixer {
+        // 10 frames ramp
+        let mut mixer = Mixer::new(1_000, 2, Duration::from_millis(10));
+        mixer.add_source(0, Box::new(ConstantSource { value: 1.0, volume }));
+        mixer
+    }
+
+    #[test]
+    fn keeps_initial_source_volume() {
+        let mut mixer = mixer_with_source(0.5);
+
+        let mut output = [0.0f32; 8];
+        mixer.mix(&mut output);
+        assert_eq!(output, [0.5; 8]);
+    }
+
+    #[test]
+    fn ramps_gain_on_volume_change() {
+        let mut mixer = mixer_with_source(0.5);
+        mixer.mix(&mut [0.0f32; 8]);
+
+        mixer.set_source_volume(0, 1.0);
+        let mut output = [0.0f32; 32];
+        mixer.mix(&mut output);
+
+        let frames = output
+            .chunks(2)
+            .map(|frame| {
+                assert_eq!(frame[0], frame[1], "all channels must share the same gain");
+                frame[0]
+            })
+            .collect::<Vec<_>>();
+
+        assert!(frames[0] > 0.5 && frames[0] < 0.6, "ramp must start gently");
+        for pair in frames.windows(2) {
+            assert!(pair[1] >= pair[0], "gain must not move away from target");
+            assert!(pair[1] - pair[0] <= 0.05 + 1e-5, "discontinuity {pair:?}");
+        }
+        assert!(frames[8] < 1.0, "ramp must span multiple frames");
+        assert_eq!(frames[9..], [1.0; 7]);
+    }
+
+    #[test]
+    fn continues_ramp_across_callbacks() {
+        let mut mixer = mixer_with_source(1.0);
+        mixer.set_source_volume(0, 0.0);
+
+        let mut first = [0.0f32; 8];
+        mixer.mix(&mut first);
+        let mut second = [0.0f32; 16];
+        mixer.mix(&mut second);
+
+        assert!((first[6] - 0.6).abs() < 1e-5);
+        assert!((second[0] - 0.5).abs() < 1e-5);
+        assert!(second[10..].iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn silencing_source_keeps_others() {
+        let mut mixer = Mixer::new(1_000, 1, Duration::from_millis(1));
+        mixer.add_source(
+            0,
+            Box::new(ConstantSource {
+                value: 0.25,
+                volume: 1.0,
+            }),
+        );
+        mixer.add_source(
+            1,
+            Box::new(ConstantSource {
+                value: 0.5,
+                volume: 0.5,
+            }),
+        );
+
+        mixer.set_source_volume(1, 0.0);
+        let mut output = [0.0f32; 4];
+        mixer.mix(&mut output);
+        assert_eq!(output, [0.25; 4]);
+
+        // Restoring the previous gain brings the source back
+        mixer.set_source_volume(1, 0.5);
+        mixer.mix(&mut output);
+        assert_eq!(output, [0.5; 4]);
+    }
+
+    #[test]
+    fn draining_source_plays_buffered_samples() {
+        let mut mixer = Mixer::new(1_000, 2, Duration::from_millis(1));
+        mixer.add_source(
+            0,
+            Box::new(BufferedSource {
+                samples: VecDeque::from([0.1, 0.2, 0.3, 0.4, 0.5, 0.6]),
+                channels: 2,
+            }),
+        );
+        mixer.mix(&mut [0.0f32; 4]);
+
+        mixer.drain_source(0, 100);
+        let mut output = [0.0f32; 12];
+        mixer.mix(&mut output);
+        assert_eq!(output[..8], [0.3, 0.3, 0.4, 0.4, 0.5, 0.5, 0.6, 0.6]);
+        assert_eq!(mixer.sources.len(), 1);
+
+        // The source is removed with the next callback once it played all of its samples.
+        mixer.mix(&mut output);
+        assert!(mixer.sources.is_empty());
+    }
+
+    #[test]
+    fn draining_source_is_removed_after_window() {
+        let mut mixer = mixer_with_source(1.0);
+        mixer.drain_source(0, 6);
+
+        let mut output = [0.0f32; 8];
+        mixer.mix(&mut output);
+        assert_eq!(output, [1.0; 8]);
+        mixer.mix(&mut output);
+        assert_eq!(output, [1.0; 8]);
+
+        mixer.mix(&mut output);
+        assert_eq!(output, [0.0; 8]);
+        assert!(mixer.sources.is_empty());
+    }
 }
@@ -1,4 +1,5 @@
 pub mod opus;
+pub mod sample;
 pub mod waveform;
 
 pub type AudioSourceId = usize;
@@ -49,4 +50,17 @@ pub trait AudioSource: Send {
     /// not destructively to their sample data. The volume should not be applied to the rest of the
     /// data already present in the output buffer.
     fn set_volume(&mut self, volume: f32);
+    /// Current per-source playback volume.
+    ///
+    /// The [`crate::mixer::Mixer`] takes over this volume when the source is added and applies all
+    /// further volume changes itself, ramping them smoothly.
+    fn volume(&self) -> f32;
+    /// Whether the source has no buffered samples left to play.
+    ///
+    /// Sources removed via [`crate::mixer::Mixer::drain_source`] keep being mixed until this returns
+    /// `true` or their drain window elapsed. The default implementation reports sources without an
+    /// internal buffer as drained right away.
+    fn is_drained(&self) -> bool {
+        true
+    }
 }
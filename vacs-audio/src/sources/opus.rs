@@ -1,9 +1,12 @@
+use crate::device::ChannelMap;
+use crate::dsp::{ComfortNoise, LoudnessNormalizer, SpeakingDetector};
 use crate::sources::AudioSource;
-use crate::{EncodedAudioFrame, FRAME_SIZE, TARGET_SAMPLE_RATE};
+use crate::{EncodedAudioFrame, FRAME_DURATION_MS, FRAME_SIZE, TARGET_SAMPLE_RATE};
 use anyhow::{Context, Result};
-use ringbuf::traits::{Consumer, Producer, Split};
+use ringbuf::traits::{Consumer, Observer, Producer, Split};
 use ringbuf::{HeapCons, HeapProd, HeapRb};
 use rubato::{Resampler, SincFixedIn};
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tracing::{Instrument, instrument};
@@ -13,19 +16,23 @@ const RESAMPLER_BUFFER_SIZE: usize = 8192;
 pub struct OpusSource {
     cons: HeapCons<f32>,
     decoder_task: JoinHandle<()>,
-    output_channels: u16, // >= 1
-    volume: f32,          // 0.0 - 1.0
-    amp: f32,             // >= 0.1
+    channel_map: ChannelMap,
+    volume: f32, // 0.0 - 1.0
+    amp: f32,    // >= 0.1
+    comfort_noise: Option<ComfortNoise>,
 }
 
 impl OpusSource {
-    #[instrument(level = "debug", skip(rx, resampler), err)]
+    #[instrument(level = "debug", skip(rx, resampler, on_speaking), err)]
     pub fn new(
         mut rx: mpsc::Receiver<EncodedAudioFrame>,
         mut resampler: Option<SincFixedIn<f32>>,
-        output_channels: u16,
+        channel_map: ChannelMap,
         volume: f32,
         amp: f32,
+        comfort_noise: bool,
+        loudness_target_dbfs: Option<f32>,
+        on_speaking: Option<Box<dyn Fn(bool) + Send>>,
     ) -> Result<Self> {
         tracing::trace!("Creating Opus source");
 
@@ -33,11 +40,16 @@ impl OpusSource {
         let (mut prod, cons): (HeapProd<f32>, HeapCons<f32>) = HeapRb::new(FRAME_SIZE * 10).split();
 
         // Our captured input audio will always be in mono and is transmitted via a webrtc mono stream,
-        // so we can safely default to a mono Opus decoder here. Interleaving to stereo output devices
-        // is handled by `AudioSource` implementation.
+        // so we can safely default to a mono Opus decoder here. Interleaving to the mapped output
+        // channels is handled by `AudioSource` implementation.
         let mut decoder = opus::Decoder::new(TARGET_SAMPLE_RATE, opus::Channels::Mono)
             .context("Failed to create Opus decoder")?;
 
+        // Normalization runs per source, so each peer is adjusted based on its own loudness only.
+        let mut normalizer = loudness_target_dbfs.map(LoudnessNormalizer::new);
+        let mut speaking_detector =
+            on_speaking.map(|on_speaking| (SpeakingDetector::default(), on_speaking));
+
         let decoder_task = tokio::runtime::Handle::current().spawn(
             async move {
                 tracing::debug!("Starting Opus decoder task");
@@ -49,9 +61,46 @@ impl OpusSource {
 
                 let mut overflows = 0usize;
 
-                while let Some(frame) = rx.recv().await {
+                loop {
+                    // Peers using DTX stop sending frames while silent, so a missing frame is
+                    // treated as silence to end the speaking indication.
+                    let frame = match &mut speaking_detector {
+                        Some((detector, on_speaking)) if detector.is_speaking() => {
+                            match tokio::time::timeout(
+                                Duration::from_millis(FRAME_DURATION_MS),
+                                rx.recv(),
+                            )
+                            .await
+                            {
+                                Ok(frame) => frame,
+                                Err(_) => {
+                                    if let Some(speaking) =
+                                        detector.process_frame(&[0.0f32; FRAME_SIZE])
+                                    {
+                                        on_speaking(speaking);
+                                    }
+                                    continue;
+                                }
+                            }
+                        }
+                        _ => rx.recv().await,
+                    };
+                    let Some(frame) = frame else {
+                        break;
+                    };
+
                     match decoder.decode_float(&frame, &mut decoded, false) {
                         Ok(n) => {
+                            if let Some((detector, on_speaking)) = &mut speaking_detector
+                                && let Some(speaking) = detector.process_frame(&decoded[..n])
+                            {
+                                on_speaking(speaking);
+                            }
+
+                            if let Some(normalizer) = &mut normalizer {
+                                normalizer.process_frame(&mut decoded[..n]);
+                            }
+
                             let samples = if let Some(resampler) = &mut resampler {
                                 let need = resampler.input_frames_next();
 
@@ -109,9 +158,10 @@ impl OpusSource {
         Ok(Self {
             cons,
             decoder_task,
-            output_channels: output_channels.max(1),
+            channel_map,
             volume: volume.clamp(0.0, 1.0),
             amp: amp.max(0.1),
+            comfort_noise: comfort_noise.then(ComfortNoise::default),
         })
     }
 
@@ -124,26 +174,25 @@ impl OpusSource {
 
 impl AudioSource for OpusSource {
     fn mix_into(&mut self, output: &mut [f32]) {
-        // Only a single output channel --> no interleaving required, just copy samples
-        if self.output_channels == 1 {
-            for (out_s, s) in output.iter_mut().zip(self.cons.pop_iter()) {
-                *out_s += s * self.amp * self.volume;
-            }
+        let gain = self.amp * self.volume;
+        let frames = output.chunks_mut(self.channel_map.device_channels());
 
-            // Do not backfill tail samples, as output buffer is already initialized with EQUILIBRIUM
-            // and other AudioSources might have already added their samples to the buffer.
+        // With comfort noise enabled, buffer underruns are concealed and every output frame receives
+        // a sample. Otherwise, we stop once the buffer is drained and do not backfill tail samples,
+        // as output buffer is already initialized with EQUILIBRIUM and other AudioSources might have
+        // already added their samples to the buffer.
+        if let Some(comfort_noise) = &mut self.comfort_noise {
+            for frame in frames {
+                let s = comfort_noise.process(self.cons.try_pop());
+                self.channel_map.mix_into_frame(frame, s * gain);
+            }
             return;
         }
 
-        // Interleaved multi-channel: duplicate mono sample across channels
+        // Interleaved output: place mono sample on all mapped channels
         // Limit by frames so we don’t overrun the output
-        for (frame, s) in output
-            .chunks_mut(self.output_channels as usize)
-            .zip(self.cons.pop_iter())
-        {
-            for x in frame {
-                *x += s * self.amp * self.volume;
-            }
+        for (frame, s) in frames.zip(self.cons.pop_iter()) {
+            self.channel_map.mix_into_frame(frame, s * gain);
         }
     }
 
@@ -158,4 +207,68 @@ impl AudioSource for OpusSource {
     fn set_volume(&mut self, volume: f32) {
         self.volume = volume.clamp(0.0, 1.0);
     }
+
+    fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    fn is_drained(&self) -> bool {
+        self.cons.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded_speech(frames: usize) -> Vec<EncodedAudioFrame> {
+        let mut encoder = opus::Encoder::new(
+            TARGET_SAMPLE_RATE,
+            opus::Channels::Mono,
+            opus::Application::Voip,
+        )
+        .unwrap();
+        let samples = (0..FRAME_SIZE * frames)
+            .map(|i| {
+                0.1 * (2.0 * std::f32::consts::PI * 300.0 * i as f32 / TARGET_SAMPLE_RATE as f32)
+                    .sin()
+            })
+            .collect::<Vec<_>>();
+        samples
+            .chunks(FRAME_SIZE)
+            .map(|frame| EncodedAudioFrame::from(encoder.encode_vec_float(frame, 1275).unwrap()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn speaking_ends_when_frames_stop() {
+        let (tx, rx) = mpsc::channel(64);
+        let (speaking_tx, mut speaking_rx) = mpsc::unbounded_channel();
+        let source = OpusSource::new(
+            rx,
+            None,
+            ChannelMap::all(1),
+            1.0,
+            1.0,
+            false,
+            None,
+            Some(Box::new(move |speaking| {
+                speaking_tx.send(speaking).ok();
+            })),
+        )
+        .unwrap();
+
+        for frame in encoded_speech(25) {
+            tx.send(frame).await.unwrap();
+        }
+        let speaking = tokio::time::timeout(Duration::from_secs(1), speaking_rx.recv()).await;
+        assert_eq!(speaking, Ok(Some(true)));
+
+        // With DTX, the peer stops sending frames but keeps the track open
+        let speaking = tokio::time::timeout(Duration::from_secs(2), speaking_rx.recv()).await;
+        assert_eq!(speaking, Ok(Some(false)));
+
+        drop(tx);
+        source.stop();
+    }
 }
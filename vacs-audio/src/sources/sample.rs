@@ -0,0 +1,560 @@
+use crate::device::ResamplerQuality;
+use crate::error::AudioError;
+use crate::sources::AudioSource;
+use anyhow::Context;
+use rubato::Resampler;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::instrument;
+
+/// Maximum duration of a sample, longer files are rejected to keep memory usage in check.
+const MAX_SAMPLE_DURATION: Duration = Duration::from_secs(30);
+const RESAMPLER_CHUNK_SIZE: usize = 1024;
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Decoded mono audio sample, e.g. loaded from a WAV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleData {
+    samples: Arc<[f32]>,
+    sample_rate: u32,
+}
+
+impl SampleData {
+    pub fn new(samples: impl Into<Arc<[f32]>>, sample_rate: u32) -> Self {
+        Self {
+            samples: samples.into(),
+            sample_rate,
+        }
+    }
+
+    /// Loads a WAV file, see [`SampleData::from_wav`].
+    pub fn load(path: &Path) -> Result<Self, AudioError> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read sample file {}", path.display()))?;
+        Ok(parse_wav(&bytes).with_context(|| format!("Invalid sample file {}", path.display()))?)
+    }
+
+    /// Decodes an uncompressed WAV file containing 8, 16, 24 or 32 bit integer or 32 bit float
+    /// samples. Multichannel files are downmixed to mono.
+    pub fn from_wav(bytes: &[u8]) -> Result<Self, AudioError> {
+        Ok(parse_wav(bytes)?)
+    }
+
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.samples.len() as f64 / self.sample_rate as f64)
+    }
+
+    /// Returns the sample converted to the given sample rate, keeping its duration.
+    pub fn resampled(
+        self,
+        sample_rate: u32,
+        quality: ResamplerQuality,
+    ) -> Result<Self, AudioError> {
+        if self.sample_rate == sample_rate || self.samples.is_empty() {
+            return Ok(Self {
+                sample_rate,
+                ..self
+            });
+        }
+
+        let mut resampler = quality.build(
+            sample_rate as f64 / self.sample_rate as f64,
+            RESAMPLER_CHUNK_SIZE,
+        )?;
+        let delay = resampler.output_delay();
+        let expected_len =
+            (self.samples.len() as u64 * sample_rate as u64 / self.sample_rate as u64) as usize;
+
+        // Feed silence after the end of the sample to flush the resampler's delay line.
+        let mut output = Vec::with_capacity(expected_len + delay + RESAMPLER_CHUNK_SIZE * 2);
+        let mut chunk = vec![0.0f32; RESAMPLER_CHUNK_SIZE];
+        let mut pos = 0;
+        while output.len() < expected_len + delay {
+            chunk.fill(0.0);
+            if pos < self.samples.len() {
+                let end = (pos + RESAMPLER_CHUNK_SIZE).min(self.samples.len());
+                chunk[..end - pos].copy_from_slice(&self.samples[pos..end]);
+            }
+            pos += RESAMPLER_CHUNK_SIZE;
+
+            let resampled = resampler
+                .process(&[&chunk[..]], None)
+                .context("Failed to resample sample")?;
+            output.extend_from_slice(&resampled[0]);
+        }
+
+        output.drain(..delay);
+        output.truncate(expected_len);
+
+        Ok(Self::new(output, sample_rate))
+    }
+}
+
+fn parse_wav(bytes: &[u8]) -> anyhow::Result<SampleData> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        anyhow::bail!("Not a RIFF/WAVE file");
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into()?) as usize;
+        let start = pos + 8;
+        let end = start
+            .checked_add(size)
+            .filter(|end| *end <= bytes.len())
+            .context("Truncated chunk")?;
+
+        match id {
+            b"fmt " => format = Some(WavFormat::parse(&bytes[start..end])?),
+            b"data" => data = Some(&bytes[start..end]),
+            _ => {}
+        }
+
+        // Chunks are padded to an even size
+        pos = end + (size & 1);
+    }
+
+    let format = format.context("Missing fmt chunk")?;
+    let data = data.context("Missing data chunk")?;
+
+    let frames = data.len() / format.block_align();
+    if frames == 0 {
+        anyhow::bail!("Empty data chunk");
+    }
+    if frames as u64 > MAX_SAMPLE_DURATION.as_secs() * format.sample_rate as u64 {
+        anyhow::bail!(
+            "Sample is longer than the supported maximum of {}s",
+            MAX_SAMPLE_DURATION.as_secs()
+        );
+    }
+
+    let bytes_per_sample = format.bits_per_sample as usize / 8;
+    let samples = data
+        .chunks_exact(format.block_align())
+        .map(|frame| {
+            frame
+                .chunks_exact(bytes_per_sample)
+                .map(|sample| format.decode(sample))
+                .sum::<f32>()
+                / format.channels as f32
+        })
+        .collect::<Vec<_>>();
+
+    Ok(SampleData::new(samples, format.sample_rate))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SampleFormat {
+    Int,
+    Float,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WavFormat {
+    sample_format: SampleFormat,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+impl WavFormat {
+    fn parse(chunk: &[u8]) -> anyhow::Result<Self> {
+        if chunk.len() < 16 {
+            anyhow::bail!("Truncated fmt chunk");
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([chunk[i], chunk[i + 1]]);
+
+        let mut format_tag = u16_at(0);
+        if format_tag == WAVE_FORMAT_EXTENSIBLE {
+            if chunk.len() < 26 {
+                anyhow::bail!("Truncated extensible fmt chunk");
+            }
+            // The sub format GUID starts with the actual format tag
+            format_tag = u16_at(24);
+        }
+
+        let format = Self {
+            sample_format: match format_tag {
+                WAVE_FORMAT_PCM => SampleFormat::Int,
+                WAVE_FORMAT_IEEE_FLOAT => SampleFormat::Float,
+                tag => anyhow::bail!("Unsupported format tag {tag:#06x}, only PCM is supported"),
+            },
+            channels: u16_at(2),
+            sample_rate: u32::from_le_bytes(chunk[4..8].try_into()?),
+            bits_per_sample: u16_at(14),
+        };
+
+        if format.channels == 0 {
+            anyhow::bail!("Invalid channel count 0");
+        }
+        if format.sample_rate == 0 {
+            anyhow::bail!("Invalid sample rate 0");
+        }
+        match (format.sample_format, format.bits_per_sample) {
+            (SampleFormat::Int, 8 | 16 | 24 | 32) | (SampleFormat::Float, 32) => {}
+            (sample_format, bits) => {
+                anyhow::bail!("Unsupported sample format {sample_format:?} with {bits} bits")
+            }
+        }
+
+        Ok(format)
+    }
+
+    fn block_align(&self) -> usize {
+        self.channels as usize * self.bits_per_sample as usize / 8
+    }
+
+    fn decode(&self, sample: &[u8]) -> f32 {
+        match (self.sample_format, sample.len()) {
+            (SampleFormat::Float, _) => {
+                f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]])
+            }
+            // 8 bit samples are unsigned
+            (SampleFormat::Int, 1) => (sample[0] as f32 - 128.0) / 128.0,
+            (SampleFormat::Int, 2) => i16::from_le_bytes([sample[0], sample[1]]) as f32 / 32_768.0,
+            (SampleFormat::Int, 3) => {
+                // Shift into the upper bytes of an i32 to sign-extend
+                i32::from_le_bytes([0, sample[0], sample[1], sample[2]]) as f32 / 2_147_483_648.0
+            }
+            (SampleFormat::Int, _) => {
+                i32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]) as f32
+                    / 2_147_483_648.0
+            }
+        }
+    }
+}
+
+/// Plays back a [`SampleData`], either once or in a loop.
+///
+/// The sample must already match the sample rate of the output stream it is added to.
+pub struct SampleSource {
+    samples: Arc<[f32]>,
+
+    output_channels: usize, // >= 1
+    volume: f32,            // 0.0 - 1.0
+
+    looped: bool,
+    fade_samples: usize,
+
+    pos: usize,
+    active: bool,
+    /// Number of samples played since the source was started, driving the attack envelope.
+    attack_pos: usize,
+    /// Position inside the release envelope while fading out.
+    release_pos: Option<usize>,
+    /// Envelope level at the time the release was initiated.
+    release_start: f32,
+}
+
+impl SampleSource {
+    pub fn new(
+        sample: SampleData,
+        looped: bool,
+        fade_dur: Duration,
+        output_channels: usize,
+        volume: f32,
+    ) -> Self {
+        Self {
+            fade_samples: ((fade_dur.as_secs_f32() * sample.sample_rate as f32) as usize).max(1),
+            samples: sample.samples,
+
+            output_channels: output_channels.max(1),
+            volume: volume.clamp(0.0, 1.0),
+
+            looped,
+
+            pos: 0,
+            active: false,
+            attack_pos: 0,
+            release_pos: None,
+            release_start: 0.0,
+        }
+    }
+
+    fn attack_envelope(&self) -> f32 {
+        self.attack_pos.min(self.fade_samples) as f32 / self.fade_samples as f32
+    }
+}
+
+impl AudioSource for SampleSource {
+    fn mix_into(&mut self, output: &mut [f32]) {
+        if !self.active || self.volume == 0.0 || self.samples.is_empty() {
+            return;
+        }
+
+        for frame in output.chunks_mut(self.output_channels) {
+            let mut envelope = self.attack_envelope();
+            if let Some(release_pos) = self.release_pos {
+                if release_pos >= self.fade_samples {
+                    self.active = false;
+                    self.release_pos = None;
+                    break;
+                }
+                envelope = envelope.min(
+                    self.release_start * (1.0 - release_pos as f32 / self.fade_samples as f32),
+                );
+                self.release_pos = Some(release_pos + 1);
+            }
+            self.attack_pos += 1;
+
+            let gain = self.volume * envelope;
+
+            let sample = self.samples[self.pos] * gain;
+            for s in frame.iter_mut() {
+                *s += sample;
+            }
+
+            self.pos += 1;
+            if self.pos >= self.samples.len() {
+                self.pos = 0;
+                if !self.looped {
+                    self.active = false;
+                    self.release_pos = None;
+                    break;
+                }
+            }
+        }
+    }
+
+    #[instrument(level = "trace", skip(self), fields(len = self.samples.len()))]
+    fn start(&mut self) {
+        tracing::trace!("Starting sample source");
+        self.active = true;
+        self.attack_pos = 0;
+        self.release_pos = None;
+        self.pos = 0;
+    }
+
+    #[instrument(level = "trace", skip(self), fields(len = self.samples.len()))]
+    fn stop(&mut self) {
+        tracing::trace!("Stopping sample source");
+        if self.active && self.release_pos.is_none() {
+            self.release_start = self.attack_envelope();
+            self.release_pos = Some(0);
+        }
+    }
+
+    #[instrument(level = "trace", skip(self), fields(len = self.samples.len()))]
+    fn restart(&mut self) {
+        tracing::trace!("Restarting sample source");
+        self.start();
+    }
+
+    fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    fn volume(&self) -> f32 {
+        self.volume
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(format_tag: u16, channels: u16, sample_rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&format_tag.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+
+        let mut chunks = Vec::new();
+        chunks.extend_from_slice(b"WAVE");
+        for (id, content) in [(b"fmt ", &fmt[..]), (b"data", data)] {
+            chunks.extend_from_slice(id);
+            chunks.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            chunks.extend_from_slice(content);
+            if content.len() % 2 == 1 {
+                chunks.push(0);
+            }
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(chunks.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&chunks);
+        bytes
+    }
+
+    fn i16_data(samples: &[i16]) -> Vec<u8> {
+        samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn decodes_pcm16_mono() {
+        let bytes = wav(
+            WAVE_FORMAT_PCM,
+            1,
+            44_100,
+            16,
+            &i16_data(&[0, 16_384, -16_384, i16::MIN]),
+        );
+
+        let sample = SampleData::from_wav(&bytes).unwrap();
+        assert_eq!(sample.sample_rate(), 44_100);
+        assert_eq!(sample.samples(), &[0.0, 0.5, -0.5, -1.0]);
+    }
+
+    #[test]
+    fn downmixes_stereo() {
+        let bytes = wav(
+            WAVE_FORMAT_PCM,
+            2,
+            48_000,
+            16,
+            &i16_data(&[16_384, 0, -16_384, -16_384]),
+        );
+
+        let sample = SampleData::from_wav(&bytes).unwrap();
+        assert_eq!(sample.samples(), &[0.25, -0.5]);
+    }
+
+    #[test]
+    fn decodes_float_and_24_bit() {
+        let float = [0.25f32, -0.75]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect::<Vec<_>>();
+        let sample = SampleData::from_wav(&wav(WAVE_FORMAT_IEEE_FLOAT, 1, 48_000, 32, &float));
+        assert_eq!(sample.unwrap().samples(), &[0.25, -0.75]);
+
+        // 0x400000 = 0.5, 0xC00000 = -0.5
+        let pcm24 = [0x00, 0x00, 0x40, 0x00, 0x00, 0xC0];
+        let sample = SampleData::from_wav(&wav(WAVE_FORMAT_PCM, 1, 48_000, 24, &pcm24));
+        assert_eq!(sample.unwrap().samples(), &[0.5, -0.5]);
+    }
+
+    #[test]
+    fn skips_unknown_and_odd_sized_chunks() {
+        let wav = wav(WAVE_FORMAT_PCM, 1, 8_000, 8, &[128, 192, 64]);
+        // Insert an odd-sized LIST chunk (with padding byte) in front of the fmt chunk
+        let list = [b"LIST".as_slice(), &3u32.to_le_bytes(), &[1, 2, 3, 0]].concat();
+        let bytes = [&wav[..12], &list, &wav[12..]].concat();
+
+        let sample = SampleData::from_wav(&bytes).unwrap();
+        assert_eq!(sample.samples(), &[0.0, 0.5, -0.5]);
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        assert!(SampleData::from_wav(b"").is_err());
+        assert!(SampleData::from_wav(b"RIFF\0\0\0\0AVI LIST").is_err());
+        // Compressed formats
+        assert!(SampleData::from_wav(&wav(0x0055, 1, 48_000, 16, &[0; 4])).is_err());
+        // Unsupported bit depth
+        assert!(SampleData::from_wav(&wav(WAVE_FORMAT_PCM, 1, 48_000, 12, &[0; 4])).is_err());
+        // No samples
+        assert!(SampleData::from_wav(&wav(WAVE_FORMAT_PCM, 1, 48_000, 16, &[])).is_err());
+
+        let mut truncated = wav(WAVE_FORMAT_PCM, 1, 48_000, 16, &i16_data(&[0; 16]));
+        truncated.truncate(truncated.len() - 8);
+        assert!(SampleData::from_wav(&truncated).is_err());
+    }
+
+    #[test]
+    fn rejects_too_long_samples() {
+        let frames = (MAX_SAMPLE_DURATION.as_secs() * 8_000 + 1) as usize;
+        let bytes = wav(WAVE_FORMAT_PCM, 1, 8_000, 8, &vec![128; frames]);
+        assert!(SampleData::from_wav(&bytes).is_err());
+    }
+
+    #[test]
+    fn resampling_keeps_duration() {
+        let tone = (0..44_100)
+            .map(|n| (2.0 * std::f32::consts::PI * 440.0 * n as f32 / 44_100.0).sin() * 0.5)
+            .collect::<Vec<_>>();
+        let sample = SampleData::new(tone, 44_100);
+
+        let resampled = sample
+            .clone()
+            .resampled(48_000, ResamplerQuality::Fast)
+            .unwrap();
+        assert_eq!(resampled.sample_rate(), 48_000);
+        assert_eq!(resampled.samples().len(), 48_000);
+        assert_eq!(resampled.duration(), sample.duration());
+
+        let peak = resampled.samples()[4_800..43_200]
+            .iter()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.05, "unexpected peak {peak}");
+    }
+
+    #[test]
+    fn resampling_to_same_rate_is_noop() {
+        let sample = SampleData::new(vec![0.1, 0.2, 0.3], 48_000);
+        assert_eq!(
+            sample
+                .clone()
+                .resampled(48_000, ResamplerQuality::Fast)
+                .unwrap(),
+            sample
+        );
+    }
+
+    #[test]
+    fn one_shot_source_stops_after_sample() {
+        let sample = SampleData::new(vec![0.5; 4], 1_000);
+        let mut source = SampleSource::new(sample, false, Duration::from_millis(2), 2, 1.0);
+
+        let mut output = [0.0f32; 12];
+        source.mix_into(&mut output);
+        assert_eq!(
+            output, [0.0; 12],
+            "inactive source must not produce samples"
+        );
+
+        source.start();
+        source.mix_into(&mut output);
+        assert_eq!(
+            output,
+            [0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.5, 0.5, 0.0, 0.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn looped_source_repeats_sample() {
+        let sample = SampleData::new(vec![0.1, 0.2], 1_000);
+        let mut source = SampleSource::new(sample, true, Duration::from_millis(1), 1, 1.0);
+
+        let mut output = [0.0f32; 5];
+        source.start();
+        source.mix_into(&mut output);
+        assert_eq!(output, [0.0, 0.2, 0.1, 0.2, 0.1]);
+    }
+
+    #[test]
+    fn source_fades_out_on_stop() {
+        let sample = SampleData::new(vec![1.0; 100], 1_000);
+        let mut source = SampleSource::new(sample, true, Duration::from_millis(4), 1, 1.0);
+
+        source.start();
+        let mut output = [0.0f32; 8];
+        source.mix_into(&mut output[..2]);
+        source.stop();
+        source.mix_into(&mut output[2..]);
+
+        // Release starts from the attack level reached so far instead of jumping to full volume
+        assert_eq!(output, [0.0, 0.25, 0.5, 0.375, 0.25, 0.125, 0.0, 0.0]);
+        assert!(!source.active);
+    }
+}
@@ -33,6 +33,9 @@ pub struct WaveformSource {
     attack_samples: usize,
     release_samples: usize,
     env_pos: usize,
+    release_pos: usize,
+    /// Envelope level at the time the release was initiated, so the fade-out starts without a jump.
+    release_start: f32,
 
     active: bool,
     releasing: bool,
@@ -74,9 +77,11 @@ impl WaveformSource {
             output_channels: output_channels.max(1),
             volume: volume.clamp(0.0, 1.0),
 
-            attack_samples: (fade_dur.as_secs_f32() * sample_rate) as usize,
-            release_samples: (fade_dur.as_secs_f32() * sample_rate) as usize,
+            attack_samples: ((fade_dur.as_secs_f32() * sample_rate) as usize).max(1),
+            release_samples: ((fade_dur.as_secs_f32() * sample_rate) as usize).max(1),
             env_pos: 0,
+            release_pos: 0,
+            release_start: 0.0,
 
             active: false,
             releasing: false,
@@ -113,27 +118,34 @@ impl WaveformSource {
     }
 
     fn generate_envelope(&self) -> f32 {
+        let env = self.tone_envelope();
         if self.releasing {
-            let rel = self.env_pos.min(self.release_samples);
-            1.0 - rel as f32 / self.release_samples as f32
+            // Never exceed the tone envelope, so a release initiated right before the end of the
+            // beep still fades out completely instead of being cut off at the end of the tone.
+            let rel = self.release_pos.min(self.release_samples);
+            env.min(self.release_start * (1.0 - rel as f32 / self.release_samples as f32))
         } else {
-            // Check if we're near the end of the beep and need to apply release envelope
-            let remaining_samples = self.tone_samples - self.cycle_pos;
-            if remaining_samples <= self.release_samples {
-                // Apply release envelope for natural end of beep
-                let release_progress = self.release_samples - remaining_samples;
-                let rel_amp = 1.0 - release_progress as f32 / self.release_samples as f32;
-
-                // Also apply attack envelope if we're still in attack phase
-                let att = self.env_pos.min(self.attack_samples);
-                let att_amp = att as f32 / self.attack_samples as f32;
-
-                att_amp.min(rel_amp)
-            } else {
-                // Normal attack envelope
-                let att = self.env_pos.min(self.attack_samples);
-                att as f32 / self.attack_samples as f32
-            }
+            env
+        }
+    }
+
+    fn tone_envelope(&self) -> f32 {
+        // Check if we're near the end of the beep and need to apply release envelope
+        let remaining_samples = self.tone_samples.saturating_sub(self.cycle_pos);
+        if remaining_samples <= self.release_samples {
+            // Apply release envelope for natural end of beep
+            let release_progress = self.release_samples - remaining_samples;
+            let rel_amp = 1.0 - release_progress as f32 / self.release_samples as f32;
+
+            // Also apply attack envelope if we're still in attack phase
+            let att = self.env_pos.min(self.attack_samples);
+            let att_amp = att as f32 / self.attack_samples as f32;
+
+            att_amp.min(rel_amp)
+        } else {
+            // Normal attack envelope
+            let att = self.env_pos.min(self.attack_samples);
+            att as f32 / self.attack_samples as f32
         }
     }
 }
@@ -157,6 +169,9 @@ impl AudioSource for WaveformSource {
                 sample *= self.generate_envelope();
 
                 self.env_pos += 1;
+                if self.releasing {
+                    self.release_pos += 1;
+                }
             } else if self.releasing && !self.restarting {
                 // Stop if playing silence, releasing and not restarting
                 self.active = false;
@@ -187,6 +202,7 @@ impl AudioSource for WaveformSource {
                     self.releasing = false;
                     self.cycle_pos = 0;
                     self.env_pos = 0;
+                    self.release_pos = 0;
                 } else if self.looped {
                     // Reset cycle
                     self.cycle_pos = 0;
@@ -199,7 +215,7 @@ impl AudioSource for WaveformSource {
             }
 
             // Check if envelope completed
-            if self.releasing && self.env_pos >= self.release_samples {
+            if self.releasing && self.release_pos >= self.release_samples {
                 self.releasing = false;
 
                 if self.restarting {
@@ -226,6 +242,7 @@ impl AudioSource for WaveformSource {
         self.releasing = false;
         self.restarting = false;
         self.env_pos = 0;
+        self.release_pos = 0;
         self.cycle_pos = 0;
     }
 
@@ -237,11 +254,16 @@ impl AudioSource for WaveformSource {
     fn stop(&mut self) {
         tracing::trace!("Stopping waveform source");
         // If we are currently releasing, we ignore the call to stop.
-        // If not, we initiate the release. In case we are stopping while playing silence,
-        // mix_into will abort early.
+        // If not, we initiate the release, starting at the current envelope level. In case we are
+        // stopping while playing silence, mix_into will abort early.
         if self.active && !self.releasing {
+            self.release_start = if self.cycle_pos < self.tone_samples {
+                self.tone_envelope()
+            } else {
+                0.0
+            };
             self.releasing = true;
-            self.env_pos = 0;
+            self.release_pos = 0;
         }
     }
 
@@ -269,4 +291,99 @@ impl AudioSource for WaveformSource {
         tracing::trace!("Setting volume for waveform source");
         self.volume = volume.clamp(0.0, 1.0);
     }
+
+    fn volume(&self) -> f32 {
+        self.volume
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Square wave staying at 1.0 for the first half second, making the envelope directly visible
+    /// in the emitted samples. Fades last 10 samples.
+    fn source(tone_dur: Duration) -> WaveformSource {
+        WaveformSource::new(
+            WaveformTone::new(1.0, Waveform::Square, 1.0),
+            tone_dur,
+            None,
+            Duration::from_millis(10),
+            1000.0,
+            1,
+            1.0,
+        )
+    }
+
+    fn assert_samples(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (i, (a, e)) in actual.iter().zip(expected).enumerate() {
+            assert!((a - e).abs() < 1e-5, "sample {i}: expected {e}, got {a}");
+        }
+    }
+
+    fn max_step(samples: &[f32]) -> f32 {
+        samples
+            .windows(2)
+            .map(|w| (w[1] - w[0]).abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn fades_in_on_start() {
+        let mut source = source(Duration::from_millis(400));
+        source.start();
+
+        let mut output = [0.0f32; 12];
+        source.mix_into(&mut output);
+        assert_samples(
+            &output,
+            &[0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.0],
+        );
+    }
+
+    #[test]
+    fn fades_out_on_stop() {
+        let mut source = source(Duration::from_millis(400));
+        source.start();
+        source.mix_into(&mut [0.0f32; 100]);
+
+        source.stop();
+        let mut output = [0.0f32; 12];
+        source.mix_into(&mut output);
+        assert_samples(
+            &output,
+            &[1.0, 0.9, 0.8, 0.7, 0.6, 0.5, 0.4, 0.3, 0.2, 0.1, 0.0, 0.0],
+        );
+        assert!(!source.active);
+    }
+
+    #[test]
+    fn stop_during_fade_in_releases_from_current_level() {
+        let mut source = source(Duration::from_millis(400));
+        source.start();
+        let mut output = [0.0f32; 20];
+        source.mix_into(&mut output[..5]);
+
+        source.stop();
+        source.mix_into(&mut output[5..]);
+        assert!(output[5] <= 0.5 + 1e-5, "release must not jump up");
+        assert!(max_step(&output) <= 0.1 + 1e-5);
+        assert_eq!(output[15..], [0.0; 5]);
+        assert!(!source.active);
+    }
+
+    #[test]
+    fn stop_near_end_of_tone_completes_fade() {
+        let mut source = source(Duration::from_millis(100));
+        source.start();
+        let mut output = [0.0f32; 100];
+        source.mix_into(&mut output[..97]);
+
+        source.stop();
+        source.mix_into(&mut output[97..]);
+        assert!(max_step(&output[90..]) <= 0.1 + 1e-5);
+        assert!(output[99] <= 0.1 + 1e-5, "fade-out must not be truncated");
+        assert!(!source.active);
+    }
 }
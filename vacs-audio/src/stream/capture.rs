@@ -1,9 +1,9 @@
 use crate::cpal;
 use crate::cpal::traits::StreamTrait;
 use crate::device::{DeviceType, StreamDevice};
-use crate::dsp::{MicProcessor, downmix_interleaved_to_mono};
+use crate::dsp::{MicProcessor, TruePeakDetector, downmix_interleaved_to_mono};
 use crate::error::AudioError;
-use crate::{EncodedAudioFrame, FRAME_SIZE, TARGET_SAMPLE_RATE};
+use crate::{EncodedAudioFrame, FRAME_SIZE, MAX_OPUS_FRAME_SIZE, TARGET_SAMPLE_RATE};
 use anyhow::Context;
 use bytes::Bytes;
 use parking_lot::lock_api::Mutex;
@@ -21,10 +21,13 @@ use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 
-const MAX_OPUS_FRAME_SIZE: usize = 1275; // max size of an Opus frame according to RFC 6716 3.2.1.
+const MAX_DTX_FRAME_SIZE: usize = 2; // Opus frames up to this size do not need to be transmitted.
 const MIN_INPUT_BUFFER_SIZE: usize = 4096;
 const RESAMPLER_BUFFER_WAIT: Duration = Duration::from_micros(500);
 
+/// Minimum interval between warnings about input samples exceeding the limiter ceiling.
+const CLIP_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
 const INPUT_VOLUME_OPS_CAPACITY: usize = 16;
 const INPUT_VOLUME_OPS_PER_DATA_CALLBACK: usize = 16;
 
@@ -48,6 +51,8 @@ impl CaptureStream {
         amp: f32,
         error_tx: mpsc::Sender<AudioError>,
         muted: bool,
+        opus_dtx: bool,
+        limiter_ceiling_dbfs: f32,
     ) -> Result<Self, AudioError> {
         tracing::debug!("Starting input capture stream");
         debug_assert!(matches!(device.device_type, DeviceType::Input));
@@ -116,7 +121,7 @@ impl CaptureStream {
 
         let mut resampler = device.resampler()?;
 
-        let mut opus_framer = OpusFramer::new(tx)?;
+        let mut opus_framer = OpusFramer::new(tx, opus_dtx, limiter_ceiling_dbfs)?;
 
         let task = tokio::runtime::Handle::current().spawn_blocking(move || {
             tracing::trace!("Input capture stream task started");
@@ -305,11 +310,18 @@ struct OpusFramer {
     processor: MicProcessor,
     encoder: opus::Encoder,
     encoded: Vec<u8>,
+    dtx: bool,
     tx: mpsc::Sender<EncodedAudioFrame>,
+    clipped: usize,
+    last_clip_report: Option<Instant>,
 }
 
 impl OpusFramer {
-    fn new(tx: mpsc::Sender<EncodedAudioFrame>) -> Result<Self, AudioError> {
+    fn new(
+        tx: mpsc::Sender<EncodedAudioFrame>,
+        dtx: bool,
+        limiter_ceiling_dbfs: f32,
+    ) -> Result<Self, AudioError> {
         let mut encoder = opus::Encoder::new(
             TARGET_SAMPLE_RATE,
             opus::Channels::Mono,
@@ -323,14 +335,18 @@ impl OpusFramer {
             .set_inband_fec(true)
             .context("Failed to set opus inband fec")?;
         encoder.set_vbr(false).context("Failed to set opus vbr")?;
+        encoder.set_dtx(dtx).context("Failed to set opus dtx")?;
 
         Ok(Self {
             frame: [0.0f32; FRAME_SIZE],
             pos: 0usize,
-            processor: MicProcessor::default(),
+            processor: MicProcessor::new(limiter_ceiling_dbfs),
             encoder,
             encoded: vec![0u8; MAX_OPUS_FRAME_SIZE],
+            dtx,
             tx,
+            clipped: 0usize,
+            last_clip_report: None,
         })
     }
 
@@ -347,9 +363,13 @@ impl OpusFramer {
             samples = &samples[take..];
 
             if self.pos == FRAME_SIZE {
-                self.processor.process_frame(&mut self.frame);
+                self.clipped += self.processor.process_frame(&mut self.frame);
+                self.report_clipping();
 
                 match self.encoder.encode_float(&self.frame, &mut self.encoded) {
+                    // Packets of 2 bytes or less are DTX frames during silence and don't need to be
+                    // transmitted, the receiving side conceals the gap with comfort noise.
+                    Ok(len) if self.dtx && len <= MAX_DTX_FRAME_SIZE => {}
                     Ok(len) => {
                         let bytes = Bytes::copy_from_slice(&self.encoded[..len]);
                         if let Err(err) = self.tx.try_send(bytes) {
@@ -365,24 +385,45 @@ impl OpusFramer {
             }
         }
     }
+
+    /// Logs the number of samples caught by the limiter, at most once per [`CLIP_REPORT_INTERVAL`].
+    fn report_clipping(&mut self) {
+        if self.clipped == 0
+            || self
+                .last_clip_report
+                .is_some_and(|last| last.elapsed() < CLIP_REPORT_INTERVAL)
+        {
+            return;
+        }
+
+        tracing::warn!(
+            clipped = ?self.clipped,
+            "Input exceeded limiter ceiling, consider lowering the input volume or amplification"
+        );
+        self.clipped = 0;
+        self.last_clip_report = Some(Instant::now());
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InputLevel {
-    pub dbfs_rms: f32,  // e.g. -23.4
-    pub dbfs_peak: f32, // e.g. -1.2
-    pub norm: f32,      // 0..1, for display purposes
-    pub clipping: bool,
+    pub dbfs_rms: f32,       // e.g. -23.4
+    pub dbfs_peak: f32,      // e.g. -1.2
+    pub dbfs_true_peak: f32, // e.g. -0.8, includes inter-sample peaks
+    pub norm: f32,           // 0..1, for display purposes
+    pub clipping: bool,      // at least one sample hit full scale
 }
 
 pub struct InputLevelMeter {
     window_samples: usize, // ~10-20ms worth of samples
     sum_sq: f64,
     peak: f32,
+    true_peak: f32,
+    true_peak_detector: TruePeakDetector,
     count: usize,
     last_emit: Instant,
-    emit_interval: Duration, // e.g. 16ms => ~60fps
+    emit_interval: Duration, // e.g. 33ms => ~30fps
     // smoothing (EMA in dB)
     ema_db: f32,
     attack: f32,  // 0..1, (higher = faster rise)
@@ -393,6 +434,10 @@ const INPUT_LEVEL_METER_WINDOW_MS: f32 = 15.0;
 
 const INPUT_LEVEL_MIN_DB: f32 = -60.0;
 const INPUT_LEVEL_MAX_DB: f32 = 0.0;
+const INPUT_LEVEL_SILENCE_DB: f32 = -90.0;
+/// Sample magnitude considered clipped, slightly below 1.0 to account for integer sample formats
+/// never reaching full scale on the positive side.
+const INPUT_LEVEL_CLIP_THRESHOLD: f32 = 0.999;
 
 impl InputLevelMeter {
     pub fn new(sample_rate: f32) -> Self {
@@ -402,9 +447,11 @@ impl InputLevelMeter {
             window_samples: window_samples.max(1),
             sum_sq: 0.0,
             peak: 0.0,
+            true_peak: 0.0,
+            true_peak_detector: TruePeakDetector::default(),
             count: 0,
             last_emit: Instant::now(),
-            emit_interval: Duration::from_millis(16),
+            emit_interval: Duration::from_millis(33),
             ema_db: -90.0,
             attack: 0.5,
             release: 0.1,
@@ -412,47 +459,143 @@ impl InputLevelMeter {
     }
 
     pub fn push_sample(&mut self, s: f32) -> Option<InputLevel> {
-        let a = s.abs();
-        self.peak = self.peak.max(a);
-        self.sum_sq += (s as f64) * (s as f64);
-        self.count += 1;
+        self.accumulate(s);
 
         if self.count >= self.window_samples && self.last_emit.elapsed() >= self.emit_interval {
-            let rms = (self.sum_sq / (self.count as f64)).sqrt() as f32;
-            let dbfs_rms = if rms > 0.0 { 20.0 * rms.log10() } else { -90.0 };
-            let dbfs_peak = if self.peak > 0.0 {
-                20.0 * self.peak.log10()
-            } else {
-                -90.0
-            };
-
-            let alpha = if dbfs_rms > self.ema_db {
-                self.attack
-            } else {
-                self.release
-            };
-            self.ema_db = self.ema_db + alpha * (dbfs_rms - self.ema_db);
-
-            let mut norm =
-                (self.ema_db - INPUT_LEVEL_MIN_DB) / (INPUT_LEVEL_MAX_DB - INPUT_LEVEL_MIN_DB);
-            norm = norm.clamp(0.0, 1.0);
-
-            let clipping = self.peak >= 0.999;
-
-            let out = InputLevel {
-                dbfs_rms,
-                dbfs_peak,
-                norm,
-                clipping,
-            };
-
-            self.sum_sq = 0.0;
-            self.peak = 0.0;
-            self.count = 0;
             self.last_emit = Instant::now();
-
-            return Some(out);
+            return Some(self.take_level());
         }
         None
     }
+
+    fn accumulate(&mut self, s: f32) {
+        self.peak = self.peak.max(s.abs());
+        self.true_peak = self.true_peak.max(self.true_peak_detector.process(s));
+        self.sum_sq += (s as f64) * (s as f64);
+        self.count += 1;
+    }
+
+    fn take_level(&mut self) -> InputLevel {
+        let rms = (self.sum_sq / (self.count.max(1) as f64)).sqrt() as f32;
+        let dbfs_rms = to_dbfs(rms);
+        let dbfs_peak = to_dbfs(self.peak);
+        let dbfs_true_peak = to_dbfs(self.true_peak);
+
+        let alpha = if dbfs_rms > self.ema_db {
+            self.attack
+        } else {
+            self.release
+        };
+        self.ema_db = self.ema_db + alpha * (dbfs_rms - self.ema_db);
+
+        let mut norm =
+            (self.ema_db - INPUT_LEVEL_MIN_DB) / (INPUT_LEVEL_MAX_DB - INPUT_LEVEL_MIN_DB);
+        norm = norm.clamp(0.0, 1.0);
+
+        let clipping = self.peak >= INPUT_LEVEL_CLIP_THRESHOLD;
+
+        self.sum_sq = 0.0;
+        self.peak = 0.0;
+        self.true_peak = 0.0;
+        self.count = 0;
+
+        InputLevel {
+            dbfs_rms,
+            dbfs_peak,
+            dbfs_true_peak,
+            norm,
+            clipping,
+        }
+    }
+}
+
+#[inline]
+fn to_dbfs(lin: f32) -> f32 {
+    if lin > 0.0 {
+        20.0 * lin.log10()
+    } else {
+        INPUT_LEVEL_SILENCE_DB
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DEFAULT_INPUT_LIMITER_CEILING_DBFS;
+
+    fn measure(samples: impl IntoIterator<Item = f32>) -> InputLevel {
+        let mut meter = InputLevelMeter::new(TARGET_SAMPLE_RATE as f32);
+        for s in samples {
+            meter.accumulate(s);
+        }
+        meter.take_level()
+    }
+
+    fn sine(amplitude: f32, gain: f32) -> impl Iterator<Item = f32> {
+        (0..4800).map(move |i| {
+            amplitude
+                * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / TARGET_SAMPLE_RATE as f32).sin()
+                * gain
+        })
+    }
+
+    #[test]
+    fn opus_encoder_dtx_is_configurable() {
+        let (tx, _rx) = mpsc::channel(1);
+
+        let mut framer =
+            OpusFramer::new(tx.clone(), true, DEFAULT_INPUT_LIMITER_CEILING_DBFS).unwrap();
+        assert!(framer.encoder.get_dtx().unwrap());
+
+        let mut framer = OpusFramer::new(tx, false, DEFAULT_INPUT_LIMITER_CEILING_DBFS).unwrap();
+        assert!(!framer.encoder.get_dtx().unwrap());
+    }
+
+    #[test]
+    fn level_of_silence() {
+        let level = measure(std::iter::repeat_n(0.0, 4800));
+        assert_eq!(level.dbfs_rms, INPUT_LEVEL_SILENCE_DB);
+        assert_eq!(level.dbfs_peak, INPUT_LEVEL_SILENCE_DB);
+        assert_eq!(level.dbfs_true_peak, INPUT_LEVEL_SILENCE_DB);
+        assert!(!level.clipping);
+    }
+
+    #[test]
+    fn level_of_sine_below_full_scale() {
+        let level = measure(sine(0.5, 1.0));
+        assert!((level.dbfs_peak - -6.02).abs() < 0.1);
+        assert!((level.dbfs_rms - -9.03).abs() < 0.1);
+        assert!(level.dbfs_true_peak >= level.dbfs_peak);
+        assert!(!level.clipping);
+    }
+
+    #[test]
+    fn amp_gain_causes_clipping() {
+        let level = measure(sine(0.5, 4.0));
+        assert!(level.dbfs_peak > 0.0);
+        assert!(level.clipping);
+    }
+
+    #[test]
+    fn full_scale_sample_is_clipping() {
+        let level = measure([0.0, 0.2, -1.0, 0.1]);
+        assert!(level.clipping);
+
+        let level = measure([0.0, 0.2, -0.9, 0.1]);
+        assert!(!level.clipping);
+    }
+
+    #[test]
+    fn level_is_reset_after_measuring() {
+        let mut meter = InputLevelMeter::new(TARGET_SAMPLE_RATE as f32);
+        for s in sine(0.5, 4.0) {
+            meter.accumulate(s);
+        }
+        assert!(meter.take_level().clipping);
+
+        for s in sine(0.1, 1.0) {
+            meter.accumulate(s);
+        }
+        assert!(!meter.take_level().clipping);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::cpal;
 use crate::cpal::traits::StreamTrait;
-use crate::device::{DeviceType, StreamDevice};
+use crate::device::{ChannelMap, DeviceType, StreamDevice};
 use crate::error::AudioError;
 use crate::mixer::Mixer;
 use crate::sources::{AudioSource, AudioSourceId};
@@ -12,6 +12,7 @@ use ringbuf::traits::Split;
 use rubato::SincFixedIn;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, atomic};
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::instrument;
 
@@ -32,12 +33,17 @@ impl PlaybackStream {
     #[instrument(level = "debug", skip(error_tx), err)]
     pub fn start(
         device: StreamDevice,
+        volume_ramp: Duration,
         error_tx: mpsc::Sender<AudioError>,
     ) -> Result<Self, AudioError> {
         tracing::debug!("Starting input capture stream");
         debug_assert!(matches!(device.device_type, DeviceType::Output));
 
-        let mut mixer = Mixer::default();
+        let mut mixer = Mixer::new(
+            device.sample_rate(),
+            device.channels() as usize,
+            volume_ramp,
+        );
         let (ops_prod, mut ops_cons) = HeapRb::<MixerOp>::new(MIXER_OPS_CAPACITY).split();
 
         let deafened = Arc::new(AtomicBool::new(false));
@@ -122,6 +128,24 @@ impl PlaybackStream {
         }
     }
 
+    /// Removes the audio source once it played all of its buffered samples, but after `max` at the
+    /// latest.
+    #[instrument(level = "trace", skip(self))]
+    pub fn drain_audio_source(&self, id: AudioSourceId, max: Duration) {
+        tracing::trace!("Draining audio source from mixer");
+        let max_frames = Mixer::frames_for(self.device.sample_rate(), max);
+        if self
+            .mixer_ops
+            .lock()
+            .try_push(Box::new(move |mixer: &mut Mixer| {
+                mixer.drain_source(id, max_frames)
+            }))
+            .is_err()
+        {
+            tracing::warn!("Failed to drain audio source from mixer");
+        }
+    }
+
     #[instrument(level = "trace", skip(self))]
     pub fn start_audio_source(&self, id: AudioSourceId) {
         tracing::trace!("Starting audio source");
@@ -186,10 +210,18 @@ impl PlaybackStream {
         self.device.resampler()
     }
 
+    pub fn sample_rate(&self) -> u32 {
+        self.device.sample_rate()
+    }
+
     pub fn channels(&self) -> u16 {
         self.device.channels()
     }
 
+    pub fn channel_map(&self, requested: Option<&[u16]>) -> ChannelMap {
+        self.device.channel_map(requested)
+    }
+
     pub fn device_name(&self) -> String {
         self.device.name()
     }
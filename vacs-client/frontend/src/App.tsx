@@ -34,8 +34,6 @@ function App() {
     const authStatus = useAuthStore(state => state.status);
 
     useEffect(() => {
-        void invoke("app_frontend_ready");
-
         const cleanups: (() => void)[] = [];
 
         cleanups.push(setupErrorListeners());
@@ -43,6 +41,9 @@ function App() {
         cleanups.push(setupSignalingListeners());
         cleanups.push(setupWebrtcListeners());
 
+        // Invoked after registering the listeners, so events emitted once ready are not missed
+        void invoke("app_frontend_ready");
+
         void invokeSafe("auth_check_session");
 
         void fetchCapabilities();
@@ -61,6 +61,18 @@ function CallQueue() {
                             alt="Disconnected"
                         />
                     )}
+                    {callDisplay.type === "accepted" && callDisplay.peerSpeaking && (
+                        <div
+                            className="absolute top-1.5 right-1.5 h-2.5 w-2.5 rounded-full bg-green-400 border border-green-700"
+                            title="Speaking"
+                        />
+                    )}
+                    {callDisplay.type === "accepted" && callDisplay.peerMuted && (
+                        <div
+                            className="absolute top-1.5 left-1.5 h-2.5 w-2.5 rounded-full bg-red-500 border border-red-800"
+                            title="Muted"
+                        />
+                    )}
                     <Button
                         color={cdColor}
                         highlight={
@@ -1,14 +1,41 @@
-import {useSignalingStore} from "../stores/signaling-store.ts";
+import {searchStations, useSignalingStore} from "../stores/signaling-store.ts";
 import DAKey from "./ui/DAKey.tsx";
 import Button from "./ui/Button.tsx";
 import {ClientInfoWithAlias} from "../types/client-info.ts";
 import {useCallStore} from "../stores/call-store.ts";
 import {useFilterStore} from "../stores/filter-store.ts";
+import {useEffect, useState} from "preact/hooks";
+import {TargetedEvent} from "preact";
+import {clsx} from "clsx";
+import {StationSearchResult} from "../types/stations.ts";
 
 function DAKeyArea() {
     const clients = useSignalingStore(state => state.clients);
     const grouping = useSignalingStore(state => state.getActiveStationsProfileConfig()?.grouping);
-    const {filter, setFilter} = useFilterStore(state => state);
+    const {filter, search, setFilter, setSearch} = useFilterStore(state => state);
+    const [searchResult, setSearchResult] = useState<StationSearchResult | undefined>(undefined);
+
+    useEffect(() => {
+        if (search.trim() === "") {
+            setSearchResult(undefined);
+            return;
+        }
+
+        // Results of an outdated query must not overwrite the ones of the current query
+        let outdated = false;
+        void searchStations(search).then(result => {
+            if (!outdated) setSearchResult(result);
+        });
+        return () => {
+            outdated = true;
+        };
+    }, [search, clients, grouping]);
+
+    const handleSearchChange = (event: TargetedEvent<HTMLInputElement>) => {
+        if (event.target instanceof HTMLInputElement) {
+            setSearch(event.target.value);
+        }
+    };
 
     const getGroups = (clients: ClientInfoWithAlias[], slice: number, prefix = "") => {
         const groups = [
@@ -42,11 +69,40 @@ function DAKeyArea() {
         ));
     };
 
+    const renderSearchResult = (result: StationSearchResult) => {
+        const matches = result.stations
+            .map(id => clients.find(client => client.id === id))
+            .filter(client => client !== undefined);
+        return (
+            <>
+                {renderGroups(result.groups)}
+                {renderClients(matches)}
+            </>
+        );
+    };
+
     const renderKeys = () => {
+        if (search.trim() !== "") {
+            return searchResult !== undefined ? renderSearchResult(searchResult) : <></>;
+        }
+
         if (filter === "OTHER") {
             return renderClients(clients.filter(client => !client.displayName.includes("_")));
         }
 
+        if (typeof grouping === "object") {
+            if (filter !== "") {
+                // Compare whole groups, as shorter names form their own group
+                return renderClients(
+                    clients.filter(
+                        client =>
+                            client.displayName.split("_")[0].slice(0, grouping.Prefix) === filter,
+                    ),
+                );
+            }
+            return renderGroups(getGroups(clients, grouping.Prefix));
+        }
+
         switch (grouping) {
             case "Fir":
             case "Icao": {
@@ -77,8 +133,21 @@ function DAKeyArea() {
     };
 
     return (
-        <div className="grid grid-rows-6 grid-flow-col h-full py-3 px-2 gap-3 overflow-x-auto overflow-y-hidden">
-            {renderKeys()}
+        <div className="h-full w-full flex flex-col">
+            <input
+                type="text"
+                placeholder="Search stations"
+                className={clsx(
+                    "w-80 h-9 mx-2 mt-3 px-2 rounded border border-gray-700 bg-slate-200 text-lg font-semibold",
+                    "focus:border-red-500 focus:outline-none",
+                )}
+                onChange={handleSearchChange}
+                onKeyDown={event => event.key === "Escape" && setSearch("")}
+                value={search}
+            />
+            <div className="grid grid-rows-6 grid-flow-col h-full min-h-0 py-3 px-2 gap-3 overflow-x-auto overflow-y-hidden">
+                {renderKeys()}
+            </div>
         </div>
     );
 }
@@ -2,6 +2,7 @@ import Button from "./ui/Button.tsx";
 import wrenchAndDriver from "../assets/wrench-and-driver.svg";
 import mission from "../assets/mission.svg";
 import LinkButton from "./ui/LinkButton.tsx";
+import DoNotDisturbButton from "./ui/DoNotDisturbButton.tsx";
 
 function FunctionKeys() {
     return (
@@ -47,6 +48,7 @@ function FunctionKeys() {
             <Button color="cyan" className="text-slate-400" disabled={true}>
                 SPLIT
             </Button>
+            <DoNotDisturbButton />
             <LinkButton path="/settings" className="h-full">
                 <img src={wrenchAndDriver} alt="Settings" className="h-12 w-12" draggable={false} />
             </LinkButton>
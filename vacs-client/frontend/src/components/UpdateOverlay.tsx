@@ -1,5 +1,5 @@
 import {clsx} from "clsx";
-import {useUpdateStore} from "../stores/update-store.ts";
+import {IncompatibleProtocolUpdate, useUpdateStore} from "../stores/update-store.ts";
 import Button from "./ui/Button.tsx";
 import {useEffect, useRef, useState} from "preact/hooks";
 import {getCurrentWindow} from "@tauri-apps/api/window";
@@ -7,15 +7,20 @@ import {useAsyncDebounceState} from "../hooks/debounce-hook.ts";
 import {listen, UnlistenFn} from "@tauri-apps/api/event";
 import {invokeStrict} from "../error.ts";
 import {getVersion} from "@tauri-apps/api/app";
+import {openUrl} from "@tauri-apps/plugin-opener";
 
 function UpdateOverlay() {
     const overlayVisible = useUpdateStore(state => state.overlayVisible);
     const mandatoryDialogVisible = useUpdateStore(state => state.mandatoryDialogVisible);
     const downloadDialogVisible = useUpdateStore(state => state.downloadDialogVisible);
+    const currentVersion = useUpdateStore(state => state.currentVersion);
     const newVersion = useUpdateStore(state => state.newVersion);
+    const criticalIssue = useUpdateStore(state => state.criticalIssue);
+    const incompatibleProtocol = useUpdateStore(state => state.incompatibleProtocol);
     const {
         setVersions: setUpdateVersions,
         openMandatoryDialog,
+        openIncompatibleProtocolDialog,
         openDownloadDialog,
         closeOverlay,
     } = useUpdateStore(state => state.actions);
@@ -39,12 +44,13 @@ function UpdateOverlay() {
                     currentVersion: string;
                     newVersion?: string;
                     required: boolean;
+                    mandatory: boolean;
                 }>("app_check_for_update");
 
                 setUpdateVersions(checkUpdateResult.currentVersion, checkUpdateResult.newVersion);
 
                 if (checkUpdateResult.required) {
-                    openMandatoryDialog();
+                    openMandatoryDialog(checkUpdateResult.mandatory);
                 } else {
                     closeOverlay();
                 }
@@ -56,6 +62,31 @@ function UpdateOverlay() {
         void checkForUpdate();
     }, [closeOverlay, openMandatoryDialog, setUpdateVersions]);
 
+    useEffect(() => {
+        const unlisten = listen<IncompatibleProtocolUpdate>(
+            "update:incompatible-protocol",
+            async event => {
+                try {
+                    const checkUpdateResult = await invokeStrict<{
+                        currentVersion: string;
+                        newVersion?: string;
+                    }>("app_check_for_update");
+                    setUpdateVersions(
+                        checkUpdateResult.currentVersion,
+                        checkUpdateResult.newVersion,
+                    );
+                } catch {
+                    setUpdateVersions(await getVersion());
+                }
+                openIncompatibleProtocolDialog(event.payload);
+            },
+        );
+
+        return () => {
+            unlisten.then(fn => fn());
+        };
+    }, [openIncompatibleProtocolDialog, setUpdateVersions]);
+
     useEffect(() => {
         if (downloadDialogVisible) {
             unlistenFns.current.push(
@@ -89,11 +120,35 @@ function UpdateOverlay() {
                         Mandatory update
                     </p>
                     <p className="w-full text-center wrap-break-word mb-2">
-                        In order to continue using VACS, you will need to update to version v
-                        {newVersion}.<br />
-                        Do you want to download and install the update?
-                        <br />
-                        This will restart the application.
+                        {incompatibleProtocol ? (
+                            <>
+                                Version v{currentVersion} is no longer compatible with the server
+                                {incompatibleProtocol.minProtocolVersion &&
+                                    ` (protocol version ${incompatibleProtocol.minProtocolVersion} or newer required)`}
+                                .{" "}
+                                {newVersion
+                                    ? `You will need to update to version v${newVersion}.`
+                                    : "Please download and install a compatible version."}
+                            </>
+                        ) : criticalIssue ? (
+                            <>
+                                Version v{currentVersion} contains a critical issue and can no
+                                longer be used. You will need to update to version v{newVersion}.
+                            </>
+                        ) : (
+                            <>
+                                In order to continue using VACS, you will need to update to version
+                                v{newVersion}.
+                            </>
+                        )}
+                        {(!incompatibleProtocol || newVersion) && (
+                            <>
+                                <br />
+                                Do you want to download and install the update?
+                                <br />
+                                This will restart the application.
+                            </>
+                        )}
                     </p>
                     <div
                         className={clsx(
@@ -110,14 +165,29 @@ function UpdateOverlay() {
                         >
                             Quit
                         </Button>
-                        <Button
-                            color="green"
-                            className="px-3 py-1"
-                            onClick={handleUpdateClick}
-                            disabled={updating}
-                        >
-                            Update
-                        </Button>
+                        {!incompatibleProtocol || newVersion ? (
+                            <Button
+                                color="green"
+                                className="px-3 py-1"
+                                onClick={handleUpdateClick}
+                                disabled={updating}
+                            >
+                                Update
+                            </Button>
+                        ) : (
+                            incompatibleProtocol.downloadUrl && (
+                                <Button
+                                    color="green"
+                                    className="px-3 py-1"
+                                    onClick={() =>
+                                        incompatibleProtocol.downloadUrl &&
+                                        void openUrl(incompatibleProtocol.downloadUrl)
+                                    }
+                                >
+                                    Download
+                                </Button>
+                            )
+                        )}
                     </div>
                     {updating && <p className="w-full text-center font-semibold">Updating...</p>}
                 </div>
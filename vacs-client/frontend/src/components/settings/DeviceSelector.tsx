@@ -7,7 +7,13 @@ import {useCallStore} from "../../stores/call-store.ts";
 import {clsx} from "clsx";
 
 type DeviceSelectorProps = {
-    deviceType: "Input" | "Output";
+    deviceType: "Input" | "Output" | "Notification";
+};
+
+const deviceLabels: Record<DeviceSelectorProps["deviceType"], string> = {
+    Input: "Microphone",
+    Output: "Headset",
+    Notification: "Ringer",
 };
 
 function DeviceSelector(props: DeviceSelectorProps) {
@@ -28,7 +34,10 @@ function DeviceSelector(props: DeviceSelectorProps) {
                 audioDevices.preferred !== audioDevices.picked;
             const defaultDevice = {
                 value: "",
-                text: `Default (${audioDevices.default})`,
+                text:
+                    props.deviceType === "Notification"
+                        ? "Same as headset"
+                        : `Default (${audioDevices.default})`,
                 className: "text-initial",
             };
 
@@ -80,7 +89,7 @@ function DeviceSelector(props: DeviceSelectorProps) {
     return (
         <>
             <p className="w-full text-center font-semibold">
-                {props.deviceType === "Output" ? "Headset" : "Microphone"}
+                {deviceLabels[props.deviceType]}
             </p>
             <Select
                 name={props.deviceType}
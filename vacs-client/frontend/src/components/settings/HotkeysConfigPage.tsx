@@ -2,7 +2,12 @@ import {CloseButton} from "../../pages/SettingsPage.tsx";
 import KeyCapture from "./KeyCapture.tsx";
 import {codeToLabel} from "../../types/transmit.ts";
 import {useEffect, useState} from "preact/hooks";
-import {KeybindsConfig, KeybindType} from "../../types/keybinds.ts";
+import {
+    formatKeybindConflicts,
+    KeybindConflict,
+    KeybindsConfig,
+    KeybindType,
+} from "../../types/keybinds.ts";
 import {invokeSafe, invokeStrict} from "../../error.ts";
 import {useCapabilitiesStore} from "../../stores/capabilities-store.ts";
 import {useAsyncDebounce} from "../../hooks/debounce-hook.ts";
@@ -20,6 +25,12 @@ async function codeToKeybind(code: string | null): Promise<Keybind> {
 function HotkeysConfigPage() {
     const [acceptCall, setAcceptCall] = useState<Keybind | undefined>(undefined);
     const [endCall, setEndCall] = useState<Keybind | undefined>(undefined);
+    const [nextProfile, setNextProfile] = useState<Keybind | undefined>(undefined);
+    const [prevProfile, setPrevProfile] = useState<Keybind | undefined>(undefined);
+    const [toggleDoNotDisturb, setToggleDoNotDisturb] = useState<Keybind | undefined>(
+        undefined,
+    );
+    const [panic, setPanic] = useState<Keybind | undefined>(undefined);
 
     useEffect(() => {
         const fetchConfig = async () => {
@@ -27,6 +38,10 @@ function HotkeysConfigPage() {
                 const config = await invokeStrict<KeybindsConfig>("keybinds_get_keybinds_config");
                 setAcceptCall(await codeToKeybind(config.acceptCall));
                 setEndCall(await codeToKeybind(config.endCall));
+                setNextProfile(await codeToKeybind(config.nextProfile));
+                setPrevProfile(await codeToKeybind(config.prevProfile));
+                setToggleDoNotDisturb(await codeToKeybind(config.toggleDoNotDisturb));
+                setPanic(await codeToKeybind(config.panic));
             } catch {}
         };
 
@@ -53,6 +68,30 @@ function HotkeysConfigPage() {
                             keybind={endCall}
                             setKeybind={setEndCall}
                         />
+                        <KeybindField
+                            type="NextProfile"
+                            label="Next stations profile"
+                            keybind={nextProfile}
+                            setKeybind={setNextProfile}
+                        />
+                        <KeybindField
+                            type="PrevProfile"
+                            label="Previous stations profile"
+                            keybind={prevProfile}
+                            setKeybind={setPrevProfile}
+                        />
+                        <KeybindField
+                            type="ToggleDoNotDisturb"
+                            label="Toggle do not disturb"
+                            keybind={toggleDoNotDisturb}
+                            setKeybind={setToggleDoNotDisturb}
+                        />
+                        <KeybindField
+                            type="Panic"
+                            label="Panic (mute all audio)"
+                            keybind={panic}
+                            setKeybind={setPanic}
+                        />
                     </div>
                 </div>
                 <div className="h-20 w-full shrink-0 flex flex-row gap-2 justify-end p-2 [&>button]:px-1 [&>button]:shrink-0 overflow-x-auto scrollbar-hide">
@@ -72,8 +111,18 @@ type KeybindFieldProps = {
 
 function KeybindField({type, label, keybind, setKeybind}: KeybindFieldProps) {
     const hasExternal = useCapabilitiesStore(state => state.platform === "LinuxWayland");
+    const [conflict, setConflict] = useState<string | null>(null);
 
     const handleOnCapture = async (code: string | null) => {
+        const conflicts = await invokeSafe<KeybindConflict[]>("keybinds_check_binding", {
+            keybind: type,
+            code,
+        });
+        if (conflicts === undefined) return;
+
+        setConflict(conflicts.length > 0 ? formatKeybindConflicts(conflicts) : null);
+        if (conflicts.length > 0) return;
+
         try {
             await invokeStrict("keybinds_set_binding", {keybind: type, code});
             setKeybind(await codeToKeybind(code));
@@ -90,6 +139,7 @@ function KeybindField({type, label, keybind, setKeybind}: KeybindFieldProps) {
                     label={keybind.label}
                     onCapture={handleOnCapture}
                     onRemove={() => handleOnCapture(null)}
+                    conflict={conflict}
                 />
             ) : (
                 <p>Loading...</p>
@@ -7,12 +7,26 @@ type KeyCaptureProps = {
     onCapture: (code: string) => Promise<void>;
     onRemove: () => Promise<void>;
     disabled?: boolean;
+    allowCombos?: boolean;
+    conflict?: string | null;
 };
 
+const MODIFIER_CODES = [
+    "ControlLeft",
+    "ControlRight",
+    "ShiftLeft",
+    "ShiftRight",
+    "AltLeft",
+    "AltRight",
+    "MetaLeft",
+    "MetaRight",
+];
+
 function KeyCapture(props: KeyCaptureProps) {
-    const {onCapture} = props;
+    const {onCapture, allowCombos} = props;
     const [capturing, setCapturing] = useState<boolean>(false);
     const keySelectRef = useRef<HTMLDivElement | null>(null);
+    const heldModifiersRef = useRef<string[]>([]);
 
     const isRemoveDisabled = props.disabled || props.label === null;
 
@@ -34,13 +48,37 @@ function KeyCapture(props: KeyCaptureProps) {
                 code = event.key;
             }
 
+            // Modifiers only start a combo, the combo is captured once the main key is pressed.
+            if (allowCombos && MODIFIER_CODES.includes(code)) {
+                if (!heldModifiersRef.current.includes(code)) {
+                    heldModifiersRef.current.push(code);
+                }
+                return;
+            }
+
             try {
-                await onCapture(code);
+                await onCapture(allowCombos ? [...heldModifiersRef.current, code].join("+") : code);
             } finally {
                 setCapturing(false);
             }
         },
-        [onCapture],
+        [onCapture, allowCombos],
+    );
+
+    const handleKeyUpEvent = useCallback(
+        async (event: KeyboardEvent) => {
+            event.preventDefault();
+
+            // Releasing a modifier without pressing another key binds the modifier on its own.
+            if (!allowCombos || !heldModifiersRef.current.includes(event.code)) return;
+
+            try {
+                await onCapture(event.code);
+            } finally {
+                setCapturing(false);
+            }
+        },
+        [onCapture, allowCombos],
     );
 
     const handleClickOutside = useCallback((event: MouseEvent) => {
@@ -73,24 +111,27 @@ function KeyCapture(props: KeyCaptureProps) {
     useEffect(() => {
         if (!capturing) return;
 
+        heldModifiersRef.current = [];
+
         document.addEventListener("keydown", handleKeyDownEvent);
-        document.addEventListener("keyup", preventKeyUpEvent);
+        document.addEventListener("keyup", handleKeyUpEvent);
         document.addEventListener("click", handleClickOutside);
 
         return () => {
             if (capturing) {
                 document.removeEventListener("keydown", handleKeyDownEvent);
-                document.removeEventListener("keyup", preventKeyUpEvent);
+                document.removeEventListener("keyup", handleKeyUpEvent);
                 document.removeEventListener("click", handleClickOutside);
             }
         };
-    }, [capturing, handleKeyDownEvent, handleClickOutside]);
+    }, [capturing, handleKeyDownEvent, handleKeyUpEvent, handleClickOutside]);
 
     return (
         <div className="grow h-full min-w-0 flex flex-row items-center justify-center">
             <div
                 ref={keySelectRef}
                 onClick={handleKeySelectOnClick}
+                title={props.conflict ?? undefined}
                 className={clsx(
                     "w-full h-full min-w-10 min-h-8 grow text-sm py-1 px-2 rounded text-center flex items-center justify-center",
                     "bg-gray-300 border-2",
@@ -98,6 +139,7 @@ function KeyCapture(props: KeyCaptureProps) {
                         ? "border-r-gray-100 border-b-gray-100 border-t-gray-700 border-l-gray-700 [&>*]:translate-y-[1px] [&>*]:translate-x-[1px]"
                         : "border-t-gray-100 border-l-gray-100 border-r-gray-700 border-b-gray-700",
                     props.disabled ? "brightness-90 cursor-not-allowed" : "cursor-pointer",
+                    props.conflict && "outline-2 outline-red-500",
                 )}
             >
                 <p className="truncate max-w-full">
@@ -128,8 +170,4 @@ function KeyCapture(props: KeyCaptureProps) {
     );
 }
 
-const preventKeyUpEvent = (event: KeyboardEvent) => {
-    event.preventDefault();
-};
-
 export default KeyCapture;
@@ -9,6 +9,7 @@ import {
     withRadioLabels,
     RadioConfigWithLabels,
     isRadioIntegration,
+    isGamepadCode,
 } from "../../types/transmit.ts";
 import {invokeSafe, invokeStrict} from "../../error.ts";
 import KeyCapture from "./KeyCapture.tsx";
@@ -19,11 +20,16 @@ import {TargetedEvent} from "preact";
 import {RadioState} from "../../types/radio.ts";
 import {StatusColors} from "../ui/StatusIndicator.tsx";
 import {useRadioState} from "../../hooks/radio-state-hook.ts";
-import {transmitModeToKeybind} from "../../types/keybinds.ts";
+import {
+    formatKeybindConflicts,
+    KeybindConflict,
+    transmitModeToKeybind,
+} from "../../types/keybinds.ts";
 
 function TransmitModeSettings() {
     const capKeybindListener = useCapabilitiesStore(state => state.keybindListener);
     const capPlatform = useCapabilitiesStore(state => state.platform);
+    const capGlobalShortcutsPortal = useCapabilitiesStore(state => state.globalShortcutsPortal);
     const [transmitConfig, setTransmitConfig] = useState<TransmitConfigWithLabels | undefined>(
         undefined,
     );
@@ -55,7 +61,11 @@ function TransmitModeSettings() {
                     <div className="w-full px-3 flex flex-row gap-3 items-center justify-center">
                         <p
                             className="text-sm text-gray-700 py-1.5 cursor-help"
-                            title={`Unfortunately, keybinds are not yet supported on ${capPlatform}`}
+                            title={
+                                capPlatform === "LinuxWayland" && !capGlobalShortcutsPortal
+                                    ? "Keybinds require the Global Shortcuts portal, which is not available in your desktop environment"
+                                    : `Unfortunately, keybinds are not yet supported on ${capPlatform}`
+                            }
                         >
                             Not available.
                         </p>
@@ -83,6 +93,9 @@ function TransmitModeSettings() {
                             <br />
                             <b>Push-to-mute:</b> Mic unmuted, press and hold key to mute in a call.
                             <br />
+                            <b>Toggle modes:</b> Press key once to start talking (or muting) in a
+                            call, press again to stop. Ends automatically with the call.
+                            <br />
                             <b>Radio Integration:</b> While not in a call, press and hold key to
                             transmit on radio. During a call, the key will behave as a PTT key.
                             Toggling{" "}
@@ -174,16 +187,46 @@ type TransmitConfigSettingsProps = {
 function TransmitConfigSettings({transmitConfig, setTransmitConfig}: TransmitConfigSettingsProps) {
     const capPlatform = useCapabilitiesStore(state => state.platform);
     const [waylandBinding, setWaylandBinding] = useState<string | undefined>(undefined);
+    const [conflict, setConflict] = useState<string | null>(null);
+    const [capturingGamepad, setCapturingGamepad] = useState<boolean>(false);
+
+    const transmitCode =
+        transmitConfig.mode === "PushToTalk" || transmitConfig.mode === "PushToTalkToggle"
+            ? transmitConfig.pushToTalk
+            : transmitConfig.mode === "PushToMute" || transmitConfig.mode === "PushToMuteToggle"
+              ? transmitConfig.pushToMute
+              : transmitConfig.mode === "RadioIntegration"
+                ? transmitConfig.radioPushToTalk
+                : null;
+    const transmitLabel =
+        transmitConfig.mode === "PushToTalk" || transmitConfig.mode === "PushToTalkToggle"
+            ? transmitConfig.pushToTalkLabel
+            : transmitConfig.mode === "PushToMute" || transmitConfig.mode === "PushToMuteToggle"
+              ? transmitConfig.pushToMuteLabel
+              : transmitConfig.mode === "RadioIntegration"
+                ? transmitConfig.radioPushToTalkLabel
+                : "";
 
     const handleOnTransmitCapture = async (code: string) => {
         if (transmitConfig === undefined || transmitConfig.mode === "VoiceActivation") return;
 
+        const conflicts = await invokeSafe<KeybindConflict[]>("keybinds_check_binding", {
+            keybind: transmitModeToKeybind(transmitConfig.mode),
+            code,
+        });
+        if (conflicts === undefined) return;
+
+        setConflict(conflicts.length > 0 ? formatKeybindConflicts(conflicts) : null);
+        if (conflicts.length > 0) return;
+
         let newConfig: TransmitConfig;
         switch (transmitConfig.mode) {
             case "PushToTalk":
+            case "PushToTalkToggle":
                 newConfig = {...transmitConfig, pushToTalk: code};
                 break;
             case "PushToMute":
+            case "PushToMuteToggle":
                 newConfig = {...transmitConfig, pushToMute: code};
                 break;
             case "RadioIntegration":
@@ -203,6 +246,8 @@ function TransmitConfigSettings({transmitConfig, setTransmitConfig}: TransmitCon
         const previousTransmitConfig = transmitConfig;
         const newTransmitConfig = {...transmitConfig, mode: value};
 
+        setConflict(null);
+
         setTransmitConfig(newTransmitConfig);
 
         try {
@@ -215,12 +260,16 @@ function TransmitConfigSettings({transmitConfig, setTransmitConfig}: TransmitCon
     const handleOnTransmitRemoveClick = async () => {
         if (transmitConfig === undefined || transmitConfig.mode === "VoiceActivation") return;
 
+        setConflict(null);
+
         let newConfig: TransmitConfig;
         switch (transmitConfig.mode) {
             case "PushToTalk":
+            case "PushToTalkToggle":
                 newConfig = {...transmitConfig, pushToTalk: null};
                 break;
             case "PushToMute":
+            case "PushToMuteToggle":
                 newConfig = {...transmitConfig, pushToMute: null};
                 break;
             case "RadioIntegration":
@@ -234,6 +283,18 @@ function TransmitConfigSettings({transmitConfig, setTransmitConfig}: TransmitCon
         } catch {}
     };
 
+    const handleOnGamepadCaptureClick = async () => {
+        if (transmitConfig.mode === "VoiceActivation" || capturingGamepad) return;
+
+        setCapturingGamepad(true);
+        const code = await invokeSafe<string | null>("keybinds_capture_gamepad_button");
+        setCapturingGamepad(false);
+
+        if (code) {
+            await handleOnTransmitCapture(code);
+        }
+    };
+
     const handleOpenSystemShortcutsOnClick = useAsyncDebounce(async () => {
         await invokeSafe("keybinds_open_system_shortcuts_settings");
     });
@@ -269,7 +330,9 @@ function TransmitConfigSettings({transmitConfig, setTransmitConfig}: TransmitCon
                 options={[
                     {value: "VoiceActivation", text: "Voice activation"},
                     {value: "PushToTalk", text: "Push-to-talk"},
+                    {value: "PushToTalkToggle", text: "Push-to-talk (toggle)"},
                     {value: "PushToMute", text: "Push-to-mute"},
+                    {value: "PushToMuteToggle", text: "Push-to-mute (toggle)"},
                     ...(capPlatform === "Windows" ||
                     capPlatform === "MacOs" ||
                     capPlatform === "LinuxWayland"
@@ -296,27 +359,53 @@ function TransmitConfigSettings({transmitConfig, setTransmitConfig}: TransmitCon
                     )}
                 >
                     <p className="truncate max-w-full">
-                        {transmitConfig.mode !== "VoiceActivation"
-                            ? waylandBinding || "Not bound"
-                            : ""}
+                        {transmitConfig.mode === "VoiceActivation"
+                            ? ""
+                            : isGamepadCode(transmitCode)
+                              ? transmitLabel
+                              : waylandBinding || "Not bound"}
                     </p>
                 </div>
             ) : (
                 <KeyCapture
-                    label={
-                        transmitConfig.mode === "PushToTalk"
-                            ? transmitConfig.pushToTalkLabel
-                            : transmitConfig.mode === "PushToMute"
-                              ? transmitConfig.pushToMuteLabel
-                              : transmitConfig.mode === "RadioIntegration"
-                                ? transmitConfig.radioPushToTalkLabel
-                                : ""
-                    }
+                    label={transmitLabel}
                     onCapture={handleOnTransmitCapture}
                     onRemove={handleOnTransmitRemoveClick}
                     disabled={transmitConfig.mode === "VoiceActivation"}
+                    allowCombos
+                    conflict={conflict}
                 />
             )}
+            <svg
+                onClick={handleOnGamepadCaptureClick}
+                xmlns="http://www.w3.org/2000/svg"
+                width="27"
+                height="27"
+                viewBox="0 0 24 24"
+                fill="none"
+                strokeWidth="2"
+                strokeLinecap="round"
+                strokeLinejoin="round"
+                className={clsx(
+                    "shrink-0 p-1 !pl-0",
+                    transmitConfig.mode === "VoiceActivation"
+                        ? "stroke-gray-500 cursor-not-allowed"
+                        : capturingGamepad
+                          ? "stroke-blue-600 animate-pulse"
+                          : "stroke-gray-700 hover:stroke-blue-600 transition-colors cursor-pointer",
+                )}
+            >
+                <title>
+                    {capturingGamepad
+                        ? "Press a button on your gamepad or joystick"
+                        : "Bind a gamepad or joystick button"}
+                </title>
+                <path d="M6 11h4" />
+                <path d="M8 9v4" />
+                <path d="M15 12h.01" />
+                <path d="M18 10h.01" />
+                <path d="M17.32 5H6.68a4 4 0 0 0-3.978 3.59l-.608 6.09A3 3 0 0 0 5.08 18c1.06 0 1.98-.6 2.47-1.5l.61-1.13A2.5 2.5 0 0 1 10.36 14h3.28a2.5 2.5 0 0 1 2.2 1.37l.61 1.13c.49.9 1.41 1.5 2.47 1.5a3 3 0 0 0 2.986-3.32l-.608-6.09A4 4 0 0 0 17.32 5z" />
+            </svg>
         </>
     );
 }
@@ -426,6 +515,7 @@ function RadioIntegrationSettings({
             newConfig = {
                 ...radioConfig,
                 trackAudio: {
+                    ...radioConfig.trackAudio,
                     endpoint: endpoint,
                 },
             };
@@ -475,12 +565,20 @@ function RadioIntegrationSettings({
                     />
                     <TrackAudioStatusIndicator />
                 </div>
+            ) : !capKeybindEmitter ? (
+                <p
+                    className="w-full text-sm text-gray-700 py-1.5 cursor-help"
+                    title="Audio for Vatsim requires emitting key presses, which is not supported on your platform. Please use the TrackAudio integration instead."
+                >
+                    Not available, use TrackAudio.
+                </p>
             ) : (
                 <KeyCapture
                     label={radioConfig.audioForVatsim?.emitLabel ?? null}
                     onCapture={handleOnRadioIntegrationCapture}
                     onRemove={handleOnRadioIntegrationRemoveClick}
                     disabled={transmitConfig.mode !== "RadioIntegration"}
+                    allowCombos
                 />
             )}
         </>
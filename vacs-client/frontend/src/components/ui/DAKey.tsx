@@ -1,4 +1,6 @@
+import {clsx} from "clsx";
 import {ClientInfoWithAlias, splitDisplayName} from "../../types/client-info.ts";
+import {formatFrequency} from "../../types/stations.ts";
 import Button from "./Button.tsx";
 import {useAsyncDebounce} from "../../hooks/debounce-hook.ts";
 import {invokeStrict} from "../../error.ts";
@@ -17,6 +19,7 @@ function DAKey({client}: DAKeyProps) {
         state => state.actions,
     );
     const selectedProfile = useSignalingStore(state => state.getActiveStationsProfileConfig());
+    const sharesFrequency = useSignalingStore(state => state.frequencyPeers.includes(client.id));
 
     const isCalling = incomingCalls.some(peer => peer.id === client.id);
     const beingCalled = callDisplay?.type === "outgoing" && callDisplay.peer.id === client.id;
@@ -67,11 +70,20 @@ function DAKey({client}: DAKeyProps) {
             highlight={beingCalled || isRejected ? "green" : undefined}
             onClick={handleClick}
         >
-            <p className="w-full truncate" title={client.displayName}>
+            <p
+                className={clsx("w-full truncate", sharesFrequency && "underline")}
+                title={
+                    sharesFrequency ? `${client.displayName} (same frequency)` : client.displayName
+                }
+            >
                 {stationName}
             </p>
             {stationType !== "" && <p>{stationType}</p>}
-            {showFrequency && <p title={client.frequency}>{client.frequency}</p>}
+            {showFrequency && (
+                <p title={client.frequency}>
+                    {formatFrequency(client.frequency, selectedProfile?.frequencyFormat)}
+                </p>
+            )}
         </Button>
     );
     // 320-340<br/>E2<br/>EC
@@ -0,0 +1,48 @@
+import Button from "./Button.tsx";
+import {useAsyncDebounce} from "../../hooks/debounce-hook.ts";
+import {invokeSafe} from "../../error.ts";
+import {useEffect, useState} from "preact/hooks";
+import {listen} from "@tauri-apps/api/event";
+
+function DoNotDisturbButton() {
+    const [enabled, setEnabled] = useState<boolean>(false);
+    const [rejected, setRejected] = useState<number>(0);
+
+    const handleOnClick = useAsyncDebounce(async () => {
+        await invokeSafe("signaling_set_do_not_disturb", {enabled: !enabled});
+    });
+
+    useEffect(() => {
+        void invokeSafe<boolean>("signaling_get_do_not_disturb").then(enabled => {
+            if (enabled !== undefined) setEnabled(enabled);
+        });
+
+        const unlistenToggle = listen<boolean>("signaling:do-not-disturb", event => {
+            setEnabled(event.payload);
+            setRejected(0);
+        });
+        const unlistenRejected = listen<string>("signaling:dnd-rejected", () => {
+            setRejected(rejected => rejected + 1);
+        });
+
+        return () => {
+            unlistenToggle.then(fn => fn());
+            unlistenRejected.then(fn => fn());
+        };
+    }, []);
+
+    return (
+        <Button color={enabled ? "blue" : "cyan"} onClick={handleOnClick}>
+            <p>
+                DND
+                {enabled && rejected > 0 && (
+                    <>
+                        <br />({rejected})
+                    </>
+                )}
+            </p>
+        </Button>
+    );
+}
+
+export default DoNotDisturbButton;
@@ -2,11 +2,13 @@ import Button from "./Button.tsx";
 import {useCallStore} from "../../stores/call-store.ts";
 import {navigate} from "wouter/use-browser-location";
 import {useFilterStore} from "../../stores/filter-store.ts";
+import {useTransmitLatched} from "../../hooks/transmit-latched-hook.ts";
 
 function PhoneButton() {
     const blink = useCallStore(state => state.blink);
     const callDisplayType = useCallStore(state => state.callDisplay?.type);
     const setFilter = useFilterStore(state => state.setFilter);
+    const transmitLatched = useTransmitLatched();
 
     return (
         <Button
@@ -24,7 +26,9 @@ function PhoneButton() {
             highlight={
                 callDisplayType === "outgoing" || callDisplayType === "rejected"
                     ? "green"
-                    : undefined
+                    : callDisplayType === "accepted" && transmitLatched
+                      ? "gray"
+                      : undefined
             }
             className="w-46 min-h-16 text-xl"
             onClick={() => {
@@ -3,7 +3,7 @@ import {clsx} from "clsx";
 import {useRadioState} from "../../hooks/radio-state-hook.ts";
 
 function RadioButton() {
-    const {state, handleButtonClick} = useRadioState();
+    const {state, unavailableReason, handleButtonClick} = useRadioState();
     const disabled = state === "NotConfigured" || state === "Disconnected";
     const textMuted = state === "NotConfigured";
 
@@ -34,6 +34,7 @@ function RadioButton() {
             disabled={state === "NotConfigured"}
             softDisabled={disabled}
             onClick={handleButtonClick}
+            title={unavailableReason ?? undefined}
             className={clsx("text-xl w-46", textMuted && "text-gray-500")}
         >
             Radio
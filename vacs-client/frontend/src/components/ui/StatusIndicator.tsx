@@ -13,6 +13,8 @@ export const StatusColors: Record<Status, string> = {
 
 function StatusIndicator() {
     const connected = useSignalingStore(state => state.connectionState === "connected");
+    const latencyMs = useSignalingStore(state => state.latencyMs);
+    const sessionStats = useSignalingStore(state => state.sessionStats);
     const callConnectionState = useCallStore(state => state.callDisplay?.connectionState);
     const status = ((): Status => {
         if (connected) {
@@ -26,7 +28,27 @@ function StatusIndicator() {
         return "gray";
     })();
 
-    return <div className={clsx("h-3 w-3 rounded-full border", StatusColors[status])}></div>;
+    const details: string[] = [];
+    if (latencyMs !== null) {
+        details.push(`Latency: ${latencyMs} ms`);
+    }
+    if (sessionStats !== null && sessionStats.uptimeSecs !== null) {
+        details.push(`Connected for: ${formatUptime(sessionStats.uptimeSecs)}`);
+        details.push(`Reconnects: ${sessionStats.reconnects}`);
+    }
+
+    return (
+        <div
+            className={clsx("h-3 w-3 rounded-full border", StatusColors[status])}
+            title={connected && details.length > 0 ? details.join("\n") : undefined}
+        ></div>
+    );
+}
+
+function formatUptime(secs: number): string {
+    const hours = Math.floor(secs / 3600);
+    const minutes = Math.floor((secs % 3600) / 60);
+    return hours > 0 ? `${hours} h ${minutes} min` : `${minutes} min`;
 }
 
 export default StatusIndicator;
@@ -6,6 +6,7 @@ import {useAsyncDebounce} from "./debounce-hook.ts";
 
 export function useRadioState() {
     const [state, setState] = useState<RadioState>("NotConfigured");
+    const [unavailableReason, setUnavailableReason] = useState<string | null>(null);
 
     useEffect(() => {
         const fetchState = async () => {
@@ -20,9 +21,13 @@ export function useRadioState() {
         const unlisten = listen<RadioState>("radio:state", event => {
             setState(event.payload);
         });
+        const unlistenUnavailable = listen<string | null>("radio:unavailable", event => {
+            setUnavailableReason(event.payload);
+        });
 
         return () => {
             unlisten.then(fn => fn());
+            unlistenUnavailable.then(fn => fn());
         };
     }, []);
 
@@ -35,5 +40,5 @@ export function useRadioState() {
         }
     });
 
-    return {state, canReconnect, handleButtonClick};
+    return {state, unavailableReason, canReconnect, handleButtonClick};
 }
@@ -0,0 +1,28 @@
+import {useEffect, useState} from "preact/hooks";
+import {invokeStrict} from "../error.ts";
+import {listen} from "@tauri-apps/api/event";
+
+export function useTransmitLatched() {
+    const [latched, setLatched] = useState<boolean>(false);
+
+    useEffect(() => {
+        const fetchLatched = async () => {
+            try {
+                const latched = await invokeStrict<boolean>("keybinds_get_transmit_latched");
+                setLatched(latched);
+            } catch {}
+        };
+
+        void fetchLatched();
+
+        const unlisten = listen<boolean>("keybinds:transmit-latched", event => {
+            setLatched(event.payload);
+        });
+
+        return () => {
+            unlisten.then(fn => fn());
+        };
+    }, []);
+
+    return latched;
+}
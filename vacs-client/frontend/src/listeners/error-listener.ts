@@ -1,6 +1,7 @@
 import {useErrorOverlayStore} from "../stores/error-overlay-store.ts";
 import {listen, UnlistenFn} from "@tauri-apps/api/event";
 import {Error} from "../error.ts";
+import {HostFallback} from "../types/audio.ts";
 
 export function setupErrorListeners() {
     const openErrorOverlay = useErrorOverlayStore.getState().open;
@@ -17,6 +18,13 @@ export function setupErrorListeners() {
                     event.payload.timeoutMs,
                 );
             }),
+            listen<HostFallback>("audio:host-fallback", event => {
+                openErrorOverlay(
+                    "Audio host unavailable",
+                    `The selected audio host ${event.payload.missingHost} is not available, using ${event.payload.host} instead. Check your audio settings.`,
+                    true,
+                );
+            }),
         );
     };
 
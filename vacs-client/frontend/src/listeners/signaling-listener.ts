@@ -1,5 +1,10 @@
 import {listen, UnlistenFn} from "@tauri-apps/api/event";
-import {useSignalingStore} from "../stores/signaling-store.ts";
+import {
+    ReconnectAttempt,
+    SessionStats,
+    useSignalingStore,
+    VatsimConnectionWait,
+} from "../stores/signaling-store.ts";
 import {ClientInfo} from "../types/client-info.ts";
 import {useCallStore} from "../stores/call-store.ts";
 import {useErrorOverlayStore} from "../stores/error-overlay-store.ts";
@@ -9,14 +14,22 @@ import {StationsConfig} from "../types/stations.ts";
 export function setupSignalingListeners() {
     const {
         setConnectionState,
+        setDisconnectReason,
+        setLatencyMs,
+        setReconnectAttempt,
+        setSessionStats,
+        setVatsimConnectionWait,
         setClientInfo,
         setClients,
         addClient,
         getClientInfo,
         removeClient,
+        setFrequencyPeers,
         setStationsConfig,
+        setActiveStationsProfileConfig,
     } = useSignalingStore.getState();
     const {
+        setOutgoingCall,
         addIncomingCall,
         removePeer,
         rejectPeer,
@@ -32,15 +45,36 @@ export function setupSignalingListeners() {
         unlistenFns.push(
             listen<ClientInfo>("signaling:connected", event => {
                 setConnectionState("connected");
+                setDisconnectReason(null);
+                setReconnectAttempt(null);
+                setVatsimConnectionWait(null);
                 setClientInfo(event.payload);
             }),
+            listen<string | null>("signaling:disconnect-reason", event => {
+                setDisconnectReason(event.payload);
+            }),
+            listen<number | null>("signaling:latency", event => {
+                setLatencyMs(event.payload);
+            }),
+            listen<SessionStats>("signaling:session-stats", event => {
+                setSessionStats(event.payload);
+            }),
             listen("signaling:reconnecting", () => {
                 setConnectionState("connecting");
             }),
+            listen<ReconnectAttempt>("signaling:reconnect-attempt", event => {
+                setReconnectAttempt(event.payload);
+            }),
+            listen<VatsimConnectionWait>("signaling:waiting-for-vatsim", event => {
+                setVatsimConnectionWait(event.payload);
+            }),
             listen("signaling:disconnected", () => {
                 setConnectionState("disconnected");
+                setReconnectAttempt(null);
+                setVatsimConnectionWait(null);
                 setClientInfo({displayName: "", frequency: ""});
                 setClients([]);
+                setFrequencyPeers([]);
                 resetCallStore();
                 clearCallList();
             }),
@@ -54,21 +88,52 @@ export function setupSignalingListeners() {
                 removeClient(event.payload);
                 removePeer(event.payload);
             }),
+            listen<string[]>("signaling:frequency-peers", event => {
+                setFrequencyPeers(event.payload);
+            }),
             listen<string>("signaling:call-invite", event => {
                 addIncomingCall(getClientInfo(event.payload));
             }),
+            listen<string>("signaling:call-rejoin", event => {
+                setOutgoingCall(getClientInfo(event.payload));
+            }),
+            listen<string>("signaling:outgoing-call", event => {
+                setOutgoingCall(getClientInfo(event.payload));
+            }),
             listen<string>("signaling:call-accept", event => {
                 acceptCall(getClientInfo(event.payload));
             }),
             listen<string>("signaling:call-end", event => {
                 removePeer(event.payload, true);
             }),
+            listen<{peerId: string; remainingSecs: number}>(
+                "signaling:call-duration-warning",
+                event => {
+                    openErrorOverlay(
+                        "Call ending soon",
+                        `Call with ${event.payload.peerId} reaches the maximum call duration in ${event.payload.remainingSecs} seconds`,
+                        true,
+                        5000,
+                    );
+                },
+            ),
+            listen<string>("signaling:call-max-duration-reached", event => {
+                openErrorOverlay(
+                    "Call ended",
+                    `Call with ${event.payload} was ended after reaching the maximum call duration`,
+                    true,
+                    5000,
+                );
+            }),
             listen<string>("signaling:force-call-end", event => {
                 removePeer(event.payload);
             }),
             listen<string>("signaling:call-reject", event => {
                 rejectPeer(event.payload);
             }),
+            listen<string>("signaling:call-busy", event => {
+                rejectPeer(event.payload);
+            }),
             listen<string>("signaling:peer-not-found", event => {
                 removeClient(event.payload);
                 removePeer(event.payload);
@@ -96,6 +161,9 @@ export function setupSignalingListeners() {
                 console.log("Received stations config:", event.payload);
                 setStationsConfig(event.payload);
             }),
+            listen<string>("signaling:stations-profile-selected", event => {
+                setActiveStationsProfileConfig(event.payload);
+            }),
         );
     };
 
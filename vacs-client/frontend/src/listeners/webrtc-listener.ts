@@ -1,9 +1,13 @@
 import {listen, UnlistenFn} from "@tauri-apps/api/event";
 import {useCallStore} from "../stores/call-store.ts";
+import {useErrorOverlayStore} from "../stores/error-overlay-store.ts";
 import {CallError} from "../error.ts";
+import {PeerMuted, PeerSpeaking} from "../types/audio.ts";
 
 export function setupWebrtcListeners() {
-    const {errorPeer, setConnectionState} = useCallStore.getState().actions;
+    const {errorPeer, setConnectionState, setPeerSpeaking, setPeerMuted} =
+        useCallStore.getState().actions;
+    const openErrorOverlay = useErrorOverlayStore.getState().open;
 
     const unlistenFns: Promise<UnlistenFn>[] = [];
 
@@ -18,6 +22,20 @@ export function setupWebrtcListeners() {
             listen<CallError>("webrtc:call-error", event => {
                 errorPeer(event.payload.peerId, event.payload.reason);
             }),
+            listen<PeerSpeaking>("call:peer-speaking", event => {
+                setPeerSpeaking(event.payload.peerId, event.payload.speaking);
+            }),
+            listen<PeerMuted>("call:peer-muted", event => {
+                setPeerMuted(event.payload.peerId, event.payload.muted);
+            }),
+            listen<number>("audio:long-transmit", event => {
+                openErrorOverlay(
+                    "Microphone open",
+                    `Your microphone has been transmitting for more than ${event.payload} seconds. Check your transmit key.`,
+                    true,
+                    10000,
+                );
+            }),
         );
     };
 
@@ -9,14 +9,21 @@ import {useSignalingStore} from "../stores/signaling-store.ts";
 function ConnectPage() {
     const connecting = useSignalingStore(state => state.connectionState === "connecting");
     const setConnectionState = useSignalingStore(state => state.setConnectionState);
+    const disconnectReason = useSignalingStore(state => state.disconnectReason);
+    const reconnectAttempt = useSignalingStore(state => state.reconnectAttempt);
+    const vatsimConnectionWait = useSignalingStore(state => state.vatsimConnectionWait);
+    const setVatsimConnectionWait = useSignalingStore(state => state.setVatsimConnectionWait);
+    const setDisconnectReason = useSignalingStore(state => state.setDisconnectReason);
     const [terminateDialogOpen, setTerminateDialogOpen] = useState<boolean>(false);
 
     const handleConnectClick = useAsyncDebounce(async () => {
         setConnectionState("connecting");
+        setDisconnectReason(null);
         try {
             await invoke("signaling_connect");
         } catch (e) {
             setConnectionState("disconnected");
+            setVatsimConnectionWait(null);
             if (
                 isError(e) &&
                 (e.message === "Login failed: Another client with your CID is already connected." ||
@@ -38,7 +45,7 @@ function ConnectPage() {
     });
 
     return (
-        <div className="h-full w-full flex justify-center items-center p-4">
+        <div className="h-full w-full flex flex-col gap-3 justify-center items-center p-4">
             <Button
                 color="green"
                 className={clsx(
@@ -49,6 +56,24 @@ function ConnectPage() {
             >
                 {!connecting ? "Connect" : "Connecting..."}
             </Button>
+            {connecting && reconnectAttempt !== null && (
+                <p className="text-sm text-gray-700">
+                    Attempt {reconnectAttempt.attempt} of {reconnectAttempt.maxAttempts}
+                </p>
+            )}
+            {connecting && vatsimConnectionWait !== null && (
+                <p className="max-w-100 text-center text-sm text-gray-700 wrap-break-word">
+                    Waiting for your VATSIM connection, retrying in{" "}
+                    {vatsimConnectionWait.retryInSecs}s (attempt {vatsimConnectionWait.retry} of{" "}
+                    {vatsimConnectionWait.maxRetries})
+                </p>
+            )}
+            {disconnectReason !== null && (
+                <p className="max-w-100 text-center text-sm text-gray-700 wrap-break-word">
+                    {connecting && "Reconnecting after: "}
+                    {disconnectReason}
+                </p>
+            )}
 
             <div
                 style={{display: terminateDialogOpen ? "flex" : "none"}}
@@ -77,18 +77,16 @@ function MissionPage() {
                                       : "Show all"}
                             </p>
                             <p>Grouping:</p>
-                            <p>{GroupingLabels[selectedProfile?.grouping ?? "None"]}</p>
+                            <p>{groupingLabel(selectedProfile?.grouping ?? "None")}</p>
                             <p>Alias:</p>
                             <div className="overflow-y-auto">
                                 <div className="grid grid-flow-row grid-cols-2">
-                                    {Object.entries(selectedProfile?.aliases ?? {})
-                                        .sort()
-                                        .map(([key, value]) => (
-                                            <p
-                                                className="h-min"
-                                                key={key}
-                                            >{`${key} => ${value}`}</p>
-                                        ))}
+                                    {[
+                                        ...Object.entries(selectedProfile?.cidAliases ?? {}).sort(),
+                                        ...Object.entries(selectedProfile?.aliases ?? {}).sort(),
+                                    ].map(([key, value]) => (
+                                        <p className="h-min" key={key}>{`${key} => ${value}`}</p>
+                                    ))}
                                 </div>
                             </div>
                         </div>
@@ -103,7 +101,13 @@ function MissionPage() {
     );
 }
 
-const GroupingLabels: {[key in StationsGroupMode]: string} = {
+function groupingLabel(grouping: StationsGroupMode): string {
+    return typeof grouping === "object"
+        ? `First ${grouping.Prefix} letters`
+        : GroupingLabels[grouping];
+}
+
+const GroupingLabels: {[key in Exclude<StationsGroupMode, object>]: string} = {
     None: "None",
     Fir: "FIR",
     Icao: "ICAO",
@@ -1,7 +1,7 @@
 import Button from "../components/ui/Button.tsx";
 import {navigate} from "wouter/use-browser-location";
 import {useAuthStore} from "../stores/auth-store.ts";
-import {invokeSafe, invokeStrict} from "../error.ts";
+import {invokeSafe, invokeStrict, openErrorOverlayFromUnknown} from "../error.ts";
 import {useAsyncDebounce} from "../hooks/debounce-hook.ts";
 import {useSignalingStore} from "../stores/signaling-store.ts";
 import DeviceSelector from "../components/settings/DeviceSelector.tsx";
@@ -30,20 +30,19 @@ function SettingsPage() {
                             <div className="w-full px-3 py-1.5 flex flex-col">
                                 <AudioHostSelector />
                                 <DeviceSelector deviceType="Output" />
+                                <DeviceSelector deviceType="Notification" />
                                 <DeviceSelector deviceType="Input" />
                             </div>
                             <div className="py-0.5 flex flex-col gap-2">
                                 <p className="pt-1 text-center font-semibold uppercase border-t-2 border-zinc-200">
                                     Miscellaneous
                                 </p>
-                                <div className="px-3 pb-2 grid grid-cols-[repeat(3,auto)] justify-center grid-rows-2 gap-4 [&>button]:h-16">
+                                <div className="px-3 pb-2 grid grid-cols-[repeat(5,auto)] justify-center grid-rows-2 gap-4 [&>button]:h-16">
                                     <UpdateButton />
                                     <Button
                                         color="gray"
                                         className="h-full text-sm"
-                                        onClick={() =>
-                                            invokeSafe("app_open_folder", {folder: "Config"})
-                                        }
+                                        onClick={() => invokeSafe("app_open_config_dir")}
                                     >
                                         Open
                                         <br />
@@ -52,16 +51,51 @@ function SettingsPage() {
                                     <Button
                                         color="gray"
                                         className="h-full text-sm"
-                                        onClick={() =>
-                                            invokeSafe("app_open_folder", {folder: "Logs"})
-                                        }
+                                        onClick={() => invokeSafe("app_open_log_file")}
                                     >
-                                        Open
+                                        Show
                                         <br />
-                                        Logs
+                                        Log File
+                                    </Button>
+                                    <CopyDiagnosticsButton />
+                                    <Button
+                                        color="gray"
+                                        className="h-full text-sm"
+                                        onClick={() => invokeSafe("app_export_settings")}
+                                    >
+                                        Export
+                                        <br />
+                                        Settings
                                     </Button>
 
                                     <WindowStateButtons />
+                                    <Button
+                                        color="gray"
+                                        className="h-full text-sm"
+                                        onClick={() => invokeSafe("app_import_settings")}
+                                    >
+                                        Import
+                                        <br />
+                                        Settings
+                                    </Button>
+                                    <Button
+                                        color="gray"
+                                        className="h-full text-sm"
+                                        onClick={() => invokeSafe("app_export_diagnostics")}
+                                    >
+                                        Export
+                                        <br />
+                                        Diagnostics
+                                    </Button>
+                                    <Button
+                                        color="gray"
+                                        className="h-full text-sm"
+                                        onClick={() => invokeSafe("audio_restart_engine")}
+                                    >
+                                        Restart
+                                        <br />
+                                        Audio
+                                    </Button>
                                 </div>
                             </div>
                         </div>
@@ -168,13 +202,14 @@ function UpdateButton() {
                 currentVersion: string;
                 newVersion?: string;
                 required: boolean;
+                mandatory: boolean;
             }>("app_check_for_update");
             if (checkUpdateResult === undefined) return;
 
             setUpdateVersions(checkUpdateResult.currentVersion, checkUpdateResult.newVersion);
 
             if (checkUpdateResult.required) {
-                openMandatoryDialog();
+                openMandatoryDialog(checkUpdateResult.mandatory);
             } else {
                 if (checkUpdateResult.newVersion === undefined) {
                     setNoNewVersion(true);
@@ -215,6 +250,42 @@ function UpdateButton() {
     );
 }
 
+function CopyDiagnosticsButton() {
+    const [copied, setCopied] = useState<boolean>(false);
+
+    const handleOnClick = useAsyncDebounce(async () => {
+        const diagnostics = await invokeSafe<unknown>("app_get_diagnostics");
+        if (diagnostics === undefined) return;
+
+        try {
+            await navigator.clipboard.writeText(JSON.stringify(diagnostics, null, 2));
+            setCopied(true);
+        } catch (e) {
+            openErrorOverlayFromUnknown(e);
+        }
+    });
+
+    useEffect(() => {
+        if (!copied) return;
+        const timeout = setTimeout(() => setCopied(false), 2000);
+        return () => clearTimeout(timeout);
+    }, [copied]);
+
+    return (
+        <Button color="gray" className="h-full text-sm" onClick={handleOnClick}>
+            {copied ? (
+                <p>Copied!</p>
+            ) : (
+                <p>
+                    Copy
+                    <br />
+                    Diagnostics
+                </p>
+            )}
+        </Button>
+    );
+}
+
 function WindowStateButtons() {
     const [alwaysOnTop, setAlwaysOnTop] = useState<boolean>(false);
     const [fullscreen, setFullscreen] = useState<boolean>(false);
@@ -12,6 +12,8 @@ type CallDisplay = {
     peer: ClientInfoWithAlias;
     errorReason?: string;
     connectionState?: ConnectionState;
+    peerSpeaking?: boolean;
+    peerMuted?: boolean;
 };
 
 type CallState = {
@@ -30,6 +32,8 @@ type CallState = {
         errorPeer: (peerId: string, reason: string) => void;
         dismissErrorPeer: () => void;
         setConnectionState: (peerId: string, connectionState: ConnectionState) => void;
+        setPeerSpeaking: (peerId: string, speaking: boolean) => void;
+        setPeerMuted: (peerId: string, muted: boolean) => void;
         reset: () => void;
     };
 };
@@ -157,6 +161,32 @@ export const useCallStore = create<CallState>()((set, get) => ({
 
             set({callDisplay: {...callDisplay, connectionState}});
         },
+        setPeerSpeaking: (peerId, speaking) => {
+            const callDisplay = get().callDisplay;
+
+            if (
+                callDisplay === undefined ||
+                callDisplay.peer.id !== peerId ||
+                callDisplay.type !== "accepted"
+            ) {
+                return;
+            }
+
+            set({callDisplay: {...callDisplay, peerSpeaking: speaking}});
+        },
+        setPeerMuted: (peerId, muted) => {
+            const callDisplay = get().callDisplay;
+
+            if (
+                callDisplay === undefined ||
+                callDisplay.peer.id !== peerId ||
+                callDisplay.type !== "accepted"
+            ) {
+                return;
+            }
+
+            set({callDisplay: {...callDisplay, peerMuted: muted}});
+        },
         reset: () => {
             clearTimeout(get().blinkTimeoutId);
             set({
@@ -1,11 +1,14 @@
 import {create} from "zustand/react";
-import {Capabilities, Platform} from "../types/capabilities.ts";
+import {Capabilities, KeybindBackend, Platform} from "../types/capabilities.ts";
 import {invokeStrict} from "../error.ts";
 
 type CapabilitiesState = {
     alwaysOnTop: boolean;
     keybindListener: boolean;
     keybindEmitter: boolean;
+    keybindListenerBackend: KeybindBackend;
+    keybindEmitterBackend: KeybindBackend;
+    globalShortcutsPortal: boolean;
     platform: Platform;
     setCapabilities: (capabilities: Capabilities) => void;
 };
@@ -14,6 +17,9 @@ export const useCapabilitiesStore = create<CapabilitiesState>()(set => ({
     alwaysOnTop: false,
     keybindListener: false,
     keybindEmitter: false,
+    keybindListenerBackend: "noop",
+    keybindEmitterBackend: "noop",
+    globalShortcutsPortal: false,
     platform: "Unknown",
     setCapabilities: capabilities => {
         set({...capabilities});
@@ -2,10 +2,15 @@ import {create} from "zustand/react";
 
 type FilterState = {
     filter: string;
+    search: string;
     setFilter: (filter: string) => void;
+    setSearch: (search: string) => void;
 };
 
 export const useFilterStore = create<FilterState>()(set => ({
     filter: "",
-    setFilter: filter => set({filter}),
+    search: "",
+    // Selecting a group (or resetting the filter) ends any active search
+    setFilter: filter => set({filter, search: ""}),
+    setSearch: search => set({search}),
 }));
@@ -2,29 +2,59 @@ import {ClientInfo, ClientInfoWithAlias} from "../types/client-info.ts";
 import {create} from "zustand/react";
 import {
     filterAndSortClients,
+    resolveAlias,
     StationsConfigProfiles,
     StationsConfig,
     StationsProfileConfig,
+    StationSearchResult,
 } from "../types/stations.ts";
 import {invokeStrict} from "../error.ts";
 
 type ConnectionState = "connecting" | "connected" | "disconnected";
 
+export type ReconnectAttempt = {
+    attempt: number;
+    maxAttempts: number;
+};
+
+export type SessionStats = {
+    uptimeSecs: number | null;
+    reconnects: number;
+};
+
+export type VatsimConnectionWait = {
+    retry: number;
+    maxRetries: number;
+    retryInSecs: number;
+};
+
 type SignalingState = {
     connectionState: ConnectionState;
+    disconnectReason: string | null;
+    latencyMs: number | null; // last measured round-trip time to the signaling server
+    reconnectAttempt: ReconnectAttempt | null; // current attempt while automatically reconnecting
+    sessionStats: SessionStats | null; // uptime and reconnects since the last explicit connect
+    vatsimConnectionWait: VatsimConnectionWait | null; // pending login retry while no active VATSIM connection was found
     displayName: string;
     alias: string | undefined;
     frequency: string;
     allClients: ClientInfoWithAlias[]; // all available clients, including those filtered out by stations config
     clients: ClientInfoWithAlias[]; // list of clients to be displayed in UI, pre-processed by stations config and priority/sorting
+    frequencyPeers: string[]; // CIDs of other clients sharing the own primary frequency
     stationsConfigProfiles: StationsConfigProfiles;
     activeStationsProfileConfig: string;
     setConnectionState: (state: ConnectionState) => void;
+    setDisconnectReason: (reason: string | null) => void;
+    setLatencyMs: (latencyMs: number | null) => void;
+    setReconnectAttempt: (attempt: ReconnectAttempt | null) => void;
+    setSessionStats: (stats: SessionStats | null) => void;
+    setVatsimConnectionWait: (wait: VatsimConnectionWait | null) => void;
     setClientInfo: (info: Omit<ClientInfo, "id">) => void;
     setClients: (clients: ClientInfo[]) => void;
     addClient: (client: ClientInfo) => void;
     getClientInfo: (cid: string) => ClientInfoWithAlias;
     removeClient: (cid: string) => void;
+    setFrequencyPeers: (peers: string[]) => void;
     setStationsConfig: (config: StationsConfig) => void;
     setActiveStationsProfileConfig: (profile: string) => void;
     getActiveStationsProfileConfig: () => StationsProfileConfig | undefined;
@@ -32,14 +62,25 @@ type SignalingState = {
 
 export const useSignalingStore = create<SignalingState>()((set, get) => ({
     connectionState: "disconnected",
+    disconnectReason: null,
+    latencyMs: null,
+    reconnectAttempt: null,
+    sessionStats: null,
+    vatsimConnectionWait: null,
     displayName: "",
     alias: undefined,
     frequency: "",
     allClients: [],
     clients: [],
+    frequencyPeers: [],
     stationsConfigProfiles: {},
     activeStationsProfileConfig: "Default",
     setConnectionState: connectionState => set({connectionState}),
+    setDisconnectReason: disconnectReason => set({disconnectReason}),
+    setLatencyMs: latencyMs => set({latencyMs}),
+    setReconnectAttempt: reconnectAttempt => set({reconnectAttempt}),
+    setSessionStats: sessionStats => set({sessionStats}),
+    setVatsimConnectionWait: vatsimConnectionWait => set({vatsimConnectionWait}),
     setClientInfo: info => {
         set({
             displayName: info.displayName,
@@ -48,11 +89,11 @@ export const useSignalingStore = create<SignalingState>()((set, get) => ({
         });
     },
     setClients: clients => {
-        const aliases = get().getActiveStationsProfileConfig()?.aliases ?? {};
+        const profile = get().getActiveStationsProfileConfig();
 
         const clientsWithAliases = clients.map<ClientInfoWithAlias>(client => ({
             ...client,
-            alias: aliases[client.frequency],
+            alias: resolveAlias(client, profile),
         }));
 
         set({
@@ -68,7 +109,7 @@ export const useSignalingStore = create<SignalingState>()((set, get) => ({
 
         clients.push({
             ...client,
-            alias: get().getActiveStationsProfileConfig()?.aliases?.[client.frequency],
+            alias: resolveAlias(client, get().getActiveStationsProfileConfig()),
         });
 
         set({
@@ -89,16 +130,17 @@ export const useSignalingStore = create<SignalingState>()((set, get) => ({
             clients: get().clients.filter(client => client.id !== cid),
         });
     },
+    setFrequencyPeers: frequencyPeers => set({frequencyPeers}),
     setStationsConfig: config => {
         set({
             activeStationsProfileConfig: config.selectedProfile,
             stationsConfigProfiles: config.profiles,
         });
 
-        const aliases = get().getActiveStationsProfileConfig()?.aliases ?? {};
+        const profile = get().getActiveStationsProfileConfig();
         const clients = get().allClients.map<ClientInfoWithAlias>(client => ({
             ...client,
-            alias: aliases[client.frequency],
+            alias: resolveAlias(client, profile),
         }));
 
         set({
@@ -110,10 +152,9 @@ export const useSignalingStore = create<SignalingState>()((set, get) => ({
         set({activeStationsProfileConfig: profile});
 
         const newProfile = get().getActiveStationsProfileConfig();
-        const aliases = newProfile?.aliases ?? {};
         const clients = get().allClients.map<ClientInfoWithAlias>(client => ({
             ...client,
-            alias: aliases[client.frequency],
+            alias: resolveAlias(client, newProfile),
         }));
 
         set({
@@ -135,3 +176,16 @@ export const fetchStationsConfig = async () => {
         useSignalingStore.getState().setStationsConfig(config);
     } catch {}
 };
+
+export const searchStations = async (query: string): Promise<StationSearchResult | undefined> => {
+    const stations = useSignalingStore
+        .getState()
+        .clients.map(({id, displayName, alias}) => ({id, displayName, alias}));
+
+    try {
+        return await invokeStrict<StationSearchResult>("signaling_search_stations", {
+            stations,
+            query,
+        });
+    } catch {}
+};
@@ -1,14 +1,23 @@
 import {create} from "zustand/react";
 
+export type IncompatibleProtocolUpdate = {
+    protocolVersion: string;
+    minProtocolVersion: string | null;
+    downloadUrl: string | null;
+};
+
 type UpdateState = {
     overlayVisible: boolean;
     mandatoryDialogVisible: boolean;
+    criticalIssue: boolean;
     downloadDialogVisible: boolean;
     currentVersion: string;
     newVersion?: string;
+    incompatibleProtocol?: IncompatibleProtocolUpdate;
     actions: {
         setVersions: (currentVersion: string, newVersion?: string) => void;
-        openMandatoryDialog: () => void;
+        openMandatoryDialog: (criticalIssue?: boolean) => void;
+        openIncompatibleProtocolDialog: (update: IncompatibleProtocolUpdate) => void;
         openDownloadDialog: () => void;
         closeOverlay: () => void;
     };
@@ -17,15 +26,30 @@ type UpdateState = {
 export const useUpdateStore = create<UpdateState>()(set => ({
     overlayVisible: true,
     mandatoryDialogVisible: false,
+    criticalIssue: false,
     downloadDialogVisible: false,
     currentVersion: "",
     newVersion: undefined,
+    incompatibleProtocol: undefined,
     actions: {
         setVersions: (currentVersion: string, newVersion?: string) => {
             set({currentVersion, newVersion});
         },
-        openMandatoryDialog: () => {
-            set({overlayVisible: true, mandatoryDialogVisible: true, downloadDialogVisible: false});
+        openMandatoryDialog: (criticalIssue?: boolean) => {
+            set(state => ({
+                overlayVisible: true,
+                mandatoryDialogVisible: true,
+                downloadDialogVisible: false,
+                criticalIssue: criticalIssue ?? state.criticalIssue,
+            }));
+        },
+        openIncompatibleProtocolDialog: (update: IncompatibleProtocolUpdate) => {
+            set({
+                overlayVisible: true,
+                mandatoryDialogVisible: true,
+                downloadDialogVisible: false,
+                incompatibleProtocol: update,
+            });
         },
         openDownloadDialog: () => {
             set({overlayVisible: true, downloadDialogVisible: true, mandatoryDialogVisible: false});
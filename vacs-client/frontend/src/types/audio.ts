@@ -17,9 +17,46 @@ export type AudioHosts = {
     all: string[];
 };
 
+export type PeerSpeaking = {
+    peerId: string;
+    speaking: boolean;
+};
+
+export type PeerMuted = {
+    peerId: string;
+    muted: boolean;
+};
+
 export type InputLevel = {
     dbfsRms: number; // e.g. -23.4
     dbfsPeak: number; // e.g. -1.2
+    dbfsTruePeak: number; // e.g. -0.8, includes inter-sample peaks
     norm: number; // 0..1, for display purposes
     clipping: boolean;
 };
+
+export type HostFallback = {
+    missingHost: string;
+    host: string;
+};
+
+export type StreamConfigRange = {
+    channels: number;
+    minSampleRate: number;
+    maxSampleRate: number;
+    sampleFormat: string;
+};
+
+export type PickedStreamConfig = {
+    channels: number;
+    sampleRate: number;
+    sampleFormat: string;
+    resampled: boolean;
+};
+
+export type DeviceCapabilities = {
+    deviceName: string;
+    supportedConfigs: StreamConfigRange[];
+    pickedConfig: PickedStreamConfig | null;
+    error: string | null;
+};
@@ -6,10 +6,15 @@ export type Platform =
     | "MacOs"
     | "Unknown";
 
+export type KeybindBackend = "win32" | "coreGraphics" | "waylandPortal" | "noop";
+
 export type Capabilities = {
     alwaysOnTop: boolean;
     windowState: boolean;
     keybindListener: boolean;
     keybindEmitter: boolean;
+    keybindListenerBackend: KeybindBackend;
+    keybindEmitterBackend: KeybindBackend;
+    globalShortcutsPortal: boolean;
     platform: Platform;
 };
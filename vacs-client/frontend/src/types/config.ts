@@ -0,0 +1,8 @@
+export type ConfigIssueSeverity = "Warning" | "Error";
+
+export type ConfigIssue = {
+    severity: ConfigIssueSeverity;
+    file: string | null;
+    field: string | null;
+    message: string;
+};
@@ -5,18 +5,63 @@ export type KeybindType =
     | "PushToMute"
     | "RadioIntegration"
     | "AcceptCall"
-    | "EndCall";
+    | "EndCall"
+    | "NextProfile"
+    | "PrevProfile"
+    | "ToggleDoNotDisturb"
+    | "Panic";
 
 export type KeybindsConfig = {
     acceptCall: string | null;
     endCall: string | null;
+    nextProfile: string | null;
+    prevProfile: string | null;
+    toggleDoNotDisturb: string | null;
+    panic: string | null;
 };
 
+export type KeybindAction =
+    | "Transmit"
+    | "AcceptCall"
+    | "EndCall"
+    | "NextProfile"
+    | "PrevProfile"
+    | "ToggleDoNotDisturb"
+    | "Panic"
+    | "RadioEmit";
+
+export type KeybindConflict = {
+    code: string;
+    actions: KeybindAction[];
+};
+
+const KEYBIND_ACTION_LABELS: Record<KeybindAction, string> = {
+    Transmit: "Transmit",
+    AcceptCall: "Accept call",
+    EndCall: "End call",
+    NextProfile: "Next stations profile",
+    PrevProfile: "Previous stations profile",
+    ToggleDoNotDisturb: "Toggle do not disturb",
+    Panic: "Panic (mute all audio)",
+    RadioEmit: "AFV emit",
+};
+
+export function formatKeybindConflicts(conflicts: KeybindConflict[]): string {
+    return conflicts
+        .map(conflict => {
+            const actions = conflict.actions.map(action => KEYBIND_ACTION_LABELS[action]);
+            return `${conflict.code} is bound to ${actions.join(", ")}`;
+        })
+        .join("\n");
+}
+
 export function transmitModeToKeybind(mode: TransmitMode): KeybindType | null {
     switch (mode) {
         case "PushToTalk":
+        case "PushToTalkToggle":
             return "PushToTalk";
         case "PushToMute":
+        case "PushToMuteToggle":
             return "PushToMute";
         case "RadioIntegration":
             return "RadioIntegration";
@@ -0,0 +1,171 @@
+import {test} from "node:test";
+import assert from "node:assert/strict";
+import {
+    filterAndSortClients,
+    formatFrequency,
+    type PatternSyntax,
+    resolveAlias,
+    type StationsProfileConfig,
+} from "./stations.ts";
+import type {ClientInfoWithAlias} from "./client-info.ts";
+
+const CALLSIGNS = [
+    "LOVV_CTR",
+    "LOVV_E_CTR",
+    "LOWW_APP",
+    "LOWW_M_APP",
+    "LOWW_TWR",
+    "LOWW_GND",
+    "LOWI_TWR",
+    "LON_S_FMP",
+    "EDMM_ALB_CTR",
+    "EDDM_TWR",
+];
+
+function profile(
+    patternSyntax: PatternSyntax,
+    include: string[],
+    exclude: string[],
+    priority: string[],
+): StationsProfileConfig {
+    return {
+        include,
+        exclude,
+        priority,
+        favorites: [],
+        aliases: {},
+        cidAliases: {},
+        frequencies: "ShowAll",
+        frequencyFormat: "ThreeDecimal",
+        grouping: "None",
+        patternSyntax,
+    };
+}
+
+function shown(config: StationsProfileConfig): string[] {
+    const clients: ClientInfoWithAlias[] = CALLSIGNS.map((displayName, i) => ({
+        id: `${i}`,
+        displayName,
+        frequency: "",
+        alias: undefined,
+    }));
+    return filterAndSortClients(clients, config).map(client => client.displayName);
+}
+
+test("glob and regex equivalent patterns show the same stations", () => {
+    const glob = profile(
+        "Glob",
+        ["LO*", "EDMM_*"],
+        ["*_GND", "LON*"],
+        ["LOVV*", "*_CTR", "*_APP"],
+    );
+    const regex = profile(
+        "Regex",
+        ["^LO", "^EDMM_"],
+        ["_GND$", "^LON"],
+        ["^LOVV", "_CTR$", "_APP$"],
+    );
+
+    assert.deepEqual(shown(glob), shown(regex));
+    assert.deepEqual(shown(glob), [
+        // Priority buckets in the order of their patterns
+        "LOVV_CTR",
+        "LOVV_E_CTR",
+        "EDMM_ALB_CTR",
+        "LOWW_APP",
+        "LOWW_M_APP",
+        // Remaining stations by station type and name
+        "LOWI_TWR",
+        "LOWW_TWR",
+    ]);
+});
+
+test("regex supports alternation and character classes", () => {
+    const glob = profile("Glob", ["LOWW_*_APP", "LOWW_*_CTR"], [], []);
+    const regex = profile("Regex", ["^LO(WW|VV)_[A-Z]_(APP|CTR)$"], [], []);
+
+    assert.deepEqual(shown(glob), ["LOWW_M_APP"]);
+    assert.deepEqual(shown(regex), ["LOWW_M_APP", "LOVV_E_CTR"]);
+});
+
+test("same pattern differs between syntaxes", () => {
+    // Globs are anchored, regexes are not
+    assert.deepEqual(shown(profile("Glob", ["loww"], [], [])), []);
+    assert.deepEqual(shown(profile("Regex", ["loww"], [], [])), [
+        "LOWW_APP",
+        "LOWW_M_APP",
+        "LOWW_GND",
+        "LOWW_TWR",
+    ]);
+});
+
+test("favorites outrank priority", () => {
+    const config = profile("Glob", [], ["LON*"], ["LOVV_*", "*_CTR", "*_APP"]);
+    config.favorites = ["EDDM_TWR", "LOWW_*", "LON_*"];
+
+    assert.deepEqual(shown(config), [
+        // Favorites in the order of their patterns, within a favorite by priority
+        "EDDM_TWR",
+        "LOWW_APP",
+        "LOWW_M_APP",
+        "LOWW_GND",
+        "LOWW_TWR",
+        // Remaining priority buckets
+        "LOVV_CTR",
+        "LOVV_E_CTR",
+        "EDMM_ALB_CTR",
+        "LOWI_TWR",
+    ]);
+    // Excluded favorites are never shown
+    assert.ok(!shown(config).includes("LON_S_FMP"));
+});
+
+test("invalid regex patterns match nothing", () => {
+    assert.deepEqual(shown(profile("Regex", ["LOWW_(APP"], [], [])), []);
+    assert.equal(shown(profile("Regex", [], ["("], ["[A-"])).length, CALLSIGNS.length);
+});
+
+test("cid alias takes precedence over frequency alias", () => {
+    const config = profile("Glob", [], [], []);
+    config.aliases = {"132.600": "AC_CTR"};
+    config.cidAliases = {"1234567": "WI_TRAINEE_APP"};
+
+    // CID alias → frequency alias → VATSIM callsign
+    const alias = (id: string, frequency: string) => resolveAlias({id, frequency}, config);
+    assert.equal(alias("1234567", "132.600"), "WI_TRAINEE_APP");
+    assert.equal(alias("7654321", "132.605"), "AC_CTR");
+    assert.equal(alias("1234567", "118.100"), "WI_TRAINEE_APP");
+    assert.equal(alias("7654321", "132.600"), "AC_CTR");
+    assert.equal(alias("7654321", "118.100"), undefined);
+    assert.equal(resolveAlias({id: "1234567", frequency: "132.600"}, undefined), undefined);
+});
+
+test("frequency alias matched exactly before normalized", () => {
+    const config = profile("Glob", [], [], []);
+    config.aliases = {"132.605": "AC_E_CTR", "132.600": "AC_CTR"};
+
+    assert.equal(resolveAlias({id: "1", frequency: "132.605"}, config), "AC_E_CTR");
+    // Equally normalized frequencies are resolved in the order of their keys
+    assert.equal(resolveAlias({id: "1", frequency: "132.60"}, config), "AC_CTR");
+});
+
+test("formats frequencies", () => {
+    for (const [raw, threeDecimal, channel] of [
+        ["123.450", "123.450", "123.455"],
+        ["123.455", "123.450", "123.455"],
+        ["123.45", "123.450", "123.455"],
+        ["123.460", "123.458", "123.460"],
+        ["123.465", "123.466", "123.465"],
+        ["123.475", "123.475", "123.480"],
+        ["118.000", "118.000", "118.005"],
+        ["136.990", "136.991", "136.990"],
+    ]) {
+        assert.equal(formatFrequency(raw, "ThreeDecimal"), threeDecimal, raw);
+        assert.equal(formatFrequency(raw, "Channel8_33"), channel, raw);
+    }
+
+    // Invalid frequencies are displayed as received
+    assert.equal(formatFrequency("199.998", "Channel8_33"), "199.998");
+    assert.equal(formatFrequency("", "ThreeDecimal"), "");
+    assert.equal(formatFrequency("123.450", undefined), "123.450");
+});
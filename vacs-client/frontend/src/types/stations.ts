@@ -1,4 +1,4 @@
-import {ClientInfoWithAlias, splitDisplayName} from "./client-info.ts";
+import {type ClientInfo, type ClientInfoWithAlias, splitDisplayName} from "./client-info.ts";
 
 export type StationsConfig = {
     selectedProfile: string;
@@ -11,13 +11,78 @@ export type StationsProfileConfig = {
     include: string[];
     exclude: string[];
     priority: string[];
+    favorites: string[];
     aliases: Record<string, string>;
+    cidAliases: Record<string, string>;
     frequencies: FrequencyDisplayMode;
+    frequencyFormat: FrequencyFormat;
     grouping: StationsGroupMode;
+    patternSyntax: PatternSyntax;
+};
+
+export type StationSearchResult = {
+    groups: string[]; // group headers containing matches, usable as filter
+    stations: string[]; // CIDs of matching stations, prefix matches first
 };
 
 export type FrequencyDisplayMode = "ShowAll" | "HideAliased" | "HideAll";
-export type StationsGroupMode = "None" | "Fir" | "FirAndIcao" | "Icao";
+export type FrequencyFormat = "ThreeDecimal" | "Channel8_33";
+export type StationsGroupMode = "None" | "Fir" | "FirAndIcao" | "Icao" | {Prefix: number};
+export type PatternSyntax = "Glob" | "Regex";
+
+/**
+ * Resolves the display name alias of a client: CID alias → frequency alias → none (VATSIM callsign).
+ * Frequency aliases are matched exactly first, then by their normalized frequency.
+ */
+export function resolveAlias(
+    client: Pick<ClientInfo, "id" | "frequency">,
+    profile: StationsProfileConfig | undefined,
+): string | undefined {
+    const alias = profile?.cidAliases?.[client.id] ?? profile?.aliases?.[client.frequency];
+    if (alias !== undefined || profile === undefined) return alias;
+
+    const frequency = normalizeFrequency(client.frequency);
+    if (frequency === undefined) return undefined;
+
+    return Object.entries(profile.aliases ?? {})
+        .filter(([key]) => normalizeFrequency(key) === frequency)
+        .sort(([a], [b]) => a.localeCompare(b))[0]?.[1];
+}
+
+// Offset of the frequency referred to by an 8.33 kHz channel name within its 25 kHz block
+const CHANNEL_OFFSETS_HZ: Record<number, number | undefined> = {0: 0, 5: 0, 10: 8_333, 15: 16_667};
+
+/**
+ * Normalizes a frequency or 8.33 kHz channel name to the frequency of the channel in Hz,
+ * mirroring the backend. Returns undefined for values not denoting a valid channel.
+ */
+export function normalizeFrequency(frequency: string): number | undefined {
+    const match = /^(\d+)\.(\d{1,3})$/.exec(frequency.trim());
+    if (match === null) return undefined;
+
+    const mhz = parseInt(match[1], 10);
+    const khz = parseInt(match[2].padEnd(3, "0"), 10);
+    const offsetHz = CHANNEL_OFFSETS_HZ[khz % 25];
+    if (offsetHz === undefined) return undefined;
+
+    return mhz * 1_000_000 + (khz - (khz % 25)) * 1_000 + offsetHz;
+}
+
+/**
+ * Formats a frequency for display on DA keys, returning it unchanged if it cannot be normalized.
+ */
+export function formatFrequency(frequency: string, format: FrequencyFormat | undefined): string {
+    const hz = normalizeFrequency(frequency);
+    if (hz === undefined) return frequency;
+
+    const mhz = Math.floor(hz / 1_000_000);
+    let khz = Math.floor((hz % 1_000_000) / 1_000);
+    if (format === "Channel8_33") {
+        // Channel names within a 25 kHz block end in 5, 10 and 15 kHz
+        khz = khz - (khz % 25) + (Math.floor((hz % 25_000) / 8_333) + 1) * 5;
+    }
+    return `${mhz}.${khz.toString().padStart(3, "0")}`;
+}
 
 function globToRegex(pattern: string): RegExp {
     const escaped = pattern
@@ -28,13 +93,28 @@ function globToRegex(pattern: string): RegExp {
     return new RegExp(`^${escaped}$`, "i");
 }
 
-function matchesAnyPattern(callsign: string, patterns: string[]): boolean {
+// Matches nothing, used in place of invalid regex patterns
+const NEVER_MATCHES = /(?!)/;
+
+function compilePattern(pattern: string, syntax: PatternSyntax): RegExp {
+    if (syntax === "Glob") return globToRegex(pattern);
+
+    // Regex patterns are validated by the backend when loading the config, but an invalid one must
+    // never break rendering the stations
+    try {
+        return new RegExp(pattern, "i");
+    } catch {
+        return NEVER_MATCHES;
+    }
+}
+
+function matchesAnyPattern(callsign: string, patterns: string[], syntax: PatternSyntax): boolean {
     if (patterns.length === 0) return false;
-    return patterns.some(pattern => globToRegex(pattern).test(callsign));
+    return patterns.some(pattern => compilePattern(pattern, syntax).test(callsign));
 }
 
-function findFirstMatchIndex(callsign: string, patterns: string[]): number {
-    return patterns.findIndex(pattern => globToRegex(pattern).test(callsign));
+function findFirstMatchIndex(callsign: string, patterns: string[], syntax: PatternSyntax): number {
+    return patterns.findIndex(pattern => compilePattern(pattern, syntax).test(callsign));
 }
 
 function filterClients(
@@ -44,9 +124,10 @@ function filterClients(
     if (!profile) return clients;
 
     return clients.filter(client => {
-        if (matchesAnyPattern(client.displayName, profile.exclude)) return false;
+        if (matchesAnyPattern(client.displayName, profile.exclude, profile.patternSyntax))
+            return false;
         if (profile.include.length === 0) return true;
-        return matchesAnyPattern(client.displayName, profile.include);
+        return matchesAnyPattern(client.displayName, profile.include, profile.patternSyntax);
     });
 }
 
@@ -57,10 +138,37 @@ function sortClients(
     if (!profile) return clients;
 
     return clients.sort((a, b) => {
-        const aPriorityIndex = findFirstMatchIndex(a.alias ?? a.displayName, profile.priority);
-        const bPriorityIndex = findFirstMatchIndex(b.alias ?? b.displayName, profile.priority);
+        const aFavoriteIndex = findFirstMatchIndex(
+            a.alias ?? a.displayName,
+            profile.favorites ?? [],
+            profile.patternSyntax,
+        );
+        const bFavoriteIndex = findFirstMatchIndex(
+            b.alias ?? b.displayName,
+            profile.favorites ?? [],
+            profile.patternSyntax,
+        );
+
+        // 1. Pin favorites above all priority buckets, in the order of their patterns
+        const aEffectiveFavorite = aFavoriteIndex === -1 ? Number.MAX_SAFE_INTEGER : aFavoriteIndex;
+        const bEffectiveFavorite = bFavoriteIndex === -1 ? Number.MAX_SAFE_INTEGER : bFavoriteIndex;
+
+        if (aEffectiveFavorite !== bEffectiveFavorite) {
+            return aEffectiveFavorite - bEffectiveFavorite;
+        }
 
-        // 1. Sort by priority bucket (lower index = higher priority)
+        const aPriorityIndex = findFirstMatchIndex(
+            a.alias ?? a.displayName,
+            profile.priority,
+            profile.patternSyntax,
+        );
+        const bPriorityIndex = findFirstMatchIndex(
+            b.alias ?? b.displayName,
+            profile.priority,
+            profile.patternSyntax,
+        );
+
+        // 2. Sort by priority bucket (lower index = higher priority)
         const aEffectivePriority = aPriorityIndex === -1 ? Number.MAX_SAFE_INTEGER : aPriorityIndex;
         const bEffectivePriority = bPriorityIndex === -1 ? Number.MAX_SAFE_INTEGER : bPriorityIndex;
 
@@ -71,17 +179,17 @@ function sortClients(
         const [aStationName, aStationType] = splitDisplayName(a);
         const [bStationName, bStationType] = splitDisplayName(b);
 
-        // 2. Sort non-prioritized station types before clients without any station type
+        // 3. Sort non-prioritized station types before clients without any station type
         if (aStationType.length === 0 && bStationType.length > 0) {
             return 1;
         } else if (aStationType.length > 0 && bStationType.length === 0) {
             return -1;
         }
 
-        // 3. Sort by station type alphabetically
+        // 4. Sort by station type alphabetically
         const stationType = aStationType.localeCompare(bStationType);
 
-        // 4. Sort by station name alphabetically
+        // 5. Sort by station name alphabetically
         return stationType !== 0 ? stationType : aStationName.localeCompare(bStationName);
     });
 }
@@ -3,6 +3,8 @@ const ALL_TRANSMIT_MODES = [
     "PushToTalk",
     "PushToMute",
     "RadioIntegration",
+    "PushToTalkToggle",
+    "PushToMuteToggle",
 ] as const;
 export type TransmitMode = (typeof ALL_TRANSMIT_MODES)[number];
 
@@ -34,6 +36,7 @@ export type RadioConfig = {
     integration: RadioIntegration;
     audioForVatsim: AudioForVatsimRadioConfig | null;
     trackAudio: TrackAudioRadioConfig | null;
+    maxTransmitDurationSecs?: number | null;
 };
 
 export type RadioConfigWithLabels = RadioConfig & {
@@ -50,6 +53,7 @@ export type AudioForVatsimRadioConfigLabels = {
 
 export type TrackAudioRadioConfig = {
     endpoint: string | null;
+    heartbeatIntervalMs?: number | null;
 };
 
 export async function withTransmitLabels(
@@ -57,9 +61,10 @@ export async function withTransmitLabels(
 ): Promise<TransmitConfigWithLabels> {
     return {
         ...config,
-        pushToTalkLabel: config.pushToTalk && (await codeToLabel(config.pushToTalk)),
-        pushToMuteLabel: config.pushToMute && (await codeToLabel(config.pushToMute)),
-        radioPushToTalkLabel: config.radioPushToTalk && (await codeToLabel(config.radioPushToTalk)),
+        pushToTalkLabel: config.pushToTalk && (await comboToLabel(config.pushToTalk)),
+        pushToMuteLabel: config.pushToMute && (await comboToLabel(config.pushToMute)),
+        radioPushToTalkLabel:
+            config.radioPushToTalk && (await comboToLabel(config.radioPushToTalk)),
     };
 }
 
@@ -69,12 +74,27 @@ export async function withRadioLabels(config: RadioConfig): Promise<RadioConfigW
         audioForVatsim: config.audioForVatsim && {
             ...config.audioForVatsim,
             emitLabel:
-                config.audioForVatsim.emit && (await codeToLabel(config.audioForVatsim.emit)),
+                config.audioForVatsim.emit && (await comboToLabel(config.audioForVatsim.emit)),
         },
     };
 }
 
+export async function comboToLabel(combo: string): Promise<string> {
+    const labels = await Promise.all(combo.split("+").map(codeToLabel));
+    return labels.join("+");
+}
+
+export const GAMEPAD_CODE_PREFIX = "Gamepad:";
+
+export function isGamepadCode(code: string | null): boolean {
+    return code?.startsWith(GAMEPAD_CODE_PREFIX) ?? false;
+}
+
 export async function codeToLabel(code: string): Promise<string> {
+    if (isGamepadCode(code)) {
+        return `Gamepad ${code.slice(GAMEPAD_CODE_PREFIX.length)}`;
+    }
+
     const keyboard = (
         navigator as {
             keyboard?: {
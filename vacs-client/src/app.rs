@@ -1,28 +1,54 @@
+use crate::app::deep_link::DeepLinkAction;
 use crate::app::state::AppState;
+use crate::app::state::http::HttpState;
+use crate::app::state::signaling::AppStateSignalingExt;
 use crate::auth;
 use crate::config::BackendEndpoint;
-use crate::error::{Error, FrontendError};
+use crate::error::{Error, FrontendError, StartupDiagnostics};
 use anyhow::Context;
 use rfd::{MessageButtons, MessageDialogResult};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use std::process::Stdio;
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_updater::{Update, UpdaterExt};
 use url::Url;
+use vacs_signaling::client::DisconnectCause;
+use vacs_signaling::protocol::VACS_PROTOCOL_VERSION;
+use vacs_signaling::protocol::http::auth::UserInfo;
 
 pub(crate) mod commands;
+pub(crate) mod deep_link;
+pub(crate) mod diagnostics;
+pub(crate) mod logging;
 pub(crate) mod state;
 pub(crate) mod window;
 
 pub fn handle_deep_link(app: AppHandle, url: String) {
-    let url = url.to_string();
-    tauri::async_runtime::spawn(async move {
-        if let Err(err) = auth::handle_auth_callback(&app, &url).await {
-            app.emit("auth:error", Value::Null).ok();
-            app.emit::<FrontendError>("error", err.into()).ok();
+    match DeepLinkAction::parse(&url) {
+        Some(DeepLinkAction::Auth) => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = auth::handle_auth_callback(&app, &url).await {
+                    app.emit("auth:error", Value::Null).ok();
+                    app.emit::<FrontendError>("error", err.into()).ok();
+                }
+            });
         }
-    });
+        Some(DeepLinkAction::Call { peer_id }) => {
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<AppState>();
+                let mut state = state.lock().await;
+                if let Err(err) = state.start_deep_link_call(&app, peer_id).await {
+                    app.emit::<FrontendError>("error", err.into()).ok();
+                }
+            });
+        }
+        None => log::warn!("Ignoring unknown or malformed deep link {url}"),
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -32,9 +58,48 @@ pub struct UpdateInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     new_version: Option<String>,
     required: bool,
+    mandatory: bool,
+}
+
+/// Update required after the signaling server rejected the client's protocol version, emitted as
+/// `update:incompatible-protocol` to prompt the user to update.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncompatibleProtocolUpdate {
+    protocol_version: String,
+    min_protocol_version: Option<String>,
+    download_url: Option<String>,
+}
+
+impl IncompatibleProtocolUpdate {
+    /// Returns the required update if the client was disconnected due to an incompatible protocol
+    /// version, `None` for all other disconnects.
+    pub fn from_disconnect_cause(cause: &DisconnectCause) -> Option<Self> {
+        match cause {
+            DisconnectCause::IncompatibleProtocolVersion(details) => Some(Self {
+                protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+                min_protocol_version: details.min_protocol_version.clone(),
+                download_url: details.download_url.clone(),
+            }),
+            _ => None,
+        }
+    }
 }
 
 pub async fn get_update(app: &AppHandle) -> Result<Option<Update>, Error> {
+    // Staged rollouts are offered based on the CID, which is only known with a valid session
+    let cid = match app
+        .state::<HttpState>()
+        .http_get::<UserInfo>(BackendEndpoint::UserInfo, None)
+        .await
+    {
+        Ok(user_info) => user_info.cid,
+        Err(err) => {
+            log::debug!("Checking for update without CID, failed to fetch user info: {err:?}");
+            String::new()
+        }
+    };
+
     let state = app.state::<AppState>();
     let state = state.lock().await;
     let channel = &state.config.client.release_channel;
@@ -42,7 +107,8 @@ pub async fn get_update(app: &AppHandle) -> Result<Option<Update>, Error> {
         .config
         .backend
         .endpoint_url(BackendEndpoint::VersionUpdateCheck)
-        .replace("{{channel}}", channel.as_str());
+        .replace("{{channel}}", channel.as_str())
+        .replace("{{cid}}", &cid);
 
     log::info!("Checking for update at {updater_url}...");
 
@@ -59,7 +125,17 @@ pub async fn get_update(app: &AppHandle) -> Result<Option<Update>, Error> {
         .context("Failed to check for updates")?)
 }
 
-pub fn open_fatal_error_dialog(app: &AppHandle, msg: &str) {
+/// File in the logs folder the diagnostics of the last failed startup are written to.
+const STARTUP_DIAGNOSTICS_FILE_NAME: &str = "startup-diagnostics.json";
+
+pub fn open_fatal_error_dialog(app: &AppHandle, diagnostics: &StartupDiagnostics) {
+    write_startup_diagnostics(app, diagnostics);
+
+    let msg = format!(
+        "{}\n\n{}",
+        diagnostics.message.as_deref().unwrap_or_default(),
+        diagnostics.details()
+    );
     let open_logs = "Open logs folder";
     let result = rfd::MessageDialog::new()
         .set_level(rfd::MessageLevel::Error)
@@ -87,6 +163,25 @@ pub fn open_fatal_error_dialog(app: &AppHandle, msg: &str) {
     };
 }
 
+/// Writes the diagnostics next to the logs, so they can be attached to bug reports even though
+/// the app never started.
+fn write_startup_diagnostics(app: &AppHandle, diagnostics: &StartupDiagnostics) {
+    let result = app
+        .path()
+        .app_log_dir()
+        .context("Failed to get logs folder")
+        .and_then(|dir| {
+            let json = serde_json::to_string_pretty(diagnostics)
+                .context("Failed to serialize startup diagnostics")?;
+            std::fs::create_dir_all(&dir).context("Failed to create logs folder")?;
+            std::fs::write(dir.join(STARTUP_DIAGNOSTICS_FILE_NAME), json)
+                .context("Failed to write startup diagnostics")
+        });
+    if let Err(err) = result {
+        log::warn!("Failed to persist startup diagnostics: {err:?}");
+    }
+}
+
 #[derive(Debug, Clone, Copy, Deserialize)]
 pub enum AppFolder {
     Config,
@@ -113,6 +208,89 @@ pub fn open_app_folder(app: &AppHandle, folder: AppFolder) -> Result<(), Error>
     Ok(())
 }
 
+/// Reveals the current log file in the OS file manager, falling back to opening the logs folder
+/// if nothing has been logged to the file yet.
+pub fn reveal_log_file(app: &AppHandle) -> Result<(), Error> {
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .context("Failed to get logs folder")?;
+    // tauri_plugin_log writes to a file named after the app, rotated files get a timestamp suffix
+    let log_file = log_dir.join(format!("{}.log", app.package_info().name));
+
+    if log_file.exists() {
+        reveal_path(&log_file).context("Failed to reveal log file")?;
+    } else {
+        log::info!(
+            "Log file {} does not exist yet, opening logs folder instead",
+            log_file.display()
+        );
+        std::fs::create_dir_all(&log_dir).context("Failed to create logs folder")?;
+        open_app_folder(app, AppFolder::Logs)?;
+    }
+
+    Ok(())
+}
+
+/// Opens the OS file manager with the given file selected.
+#[cfg(target_os = "windows")]
+fn reveal_path(path: &Path) -> anyhow::Result<()> {
+    // Explorer exits with a non-zero code even if it succeeded, so it is not waited on
+    Command::new("explorer")
+        .arg(format!("/select,{}", path.display()))
+        .spawn()
+        .context("Failed to launch Explorer")?;
+    Ok(())
+}
+
+/// Opens the OS file manager with the given file selected.
+#[cfg(target_os = "macos")]
+fn reveal_path(path: &Path) -> anyhow::Result<()> {
+    let status = Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .status()
+        .context("Failed to launch Finder")?;
+    anyhow::ensure!(status.success(), "Finder exited with {status}");
+    Ok(())
+}
+
+/// Opens the OS file manager with the given file selected.
+///
+/// Uses the freedesktop `FileManager1` interface, which is not implemented by every file
+/// manager. In that case, the containing folder is opened via `xdg-open` instead.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn reveal_path(path: &Path) -> anyhow::Result<()> {
+    let uri = Url::from_file_path(path)
+        .map_err(|_| anyhow::anyhow!("Invalid file path {}", path.display()))?;
+    let revealed = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{uri}"))
+        .arg("string:")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+
+    if !revealed {
+        log::debug!("FileManager1 not available, opening containing folder instead");
+        let folder = path.parent().context("File has no parent folder")?;
+        Command::new("xdg-open")
+            .arg(folder)
+            .spawn()
+            .context("Failed to launch xdg-open")?;
+    }
+
+    Ok(())
+}
+
 trait BlockingMessageDialog {
     fn show_blocking(self) -> MessageDialogResult;
 }
@@ -137,3 +315,41 @@ impl BlockingMessageDialog for rfd::MessageDialog {
         self.show()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vacs_signaling::protocol::ws::{LoginFailureDetails, LoginFailureReason};
+
+    #[test]
+    fn incompatible_protocol_version_prompts_update() {
+        let cause = DisconnectCause::IncompatibleProtocolVersion(LoginFailureDetails {
+            min_protocol_version: Some("2.0.0".to_string()),
+            download_url: Some("https://example.com/download".to_string()),
+        });
+
+        let update = IncompatibleProtocolUpdate::from_disconnect_cause(&cause).unwrap();
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            serde_json::json!({
+                "protocolVersion": VACS_PROTOCOL_VERSION,
+                "minProtocolVersion": "2.0.0",
+                "downloadUrl": "https://example.com/download",
+            })
+        );
+    }
+
+    #[test]
+    fn other_disconnects_do_not_prompt_update() {
+        for cause in [
+            DisconnectCause::Requested,
+            DisconnectCause::LoginFailed(LoginFailureReason::Unauthorized),
+            DisconnectCause::Other("closed".to_string()),
+        ] {
+            assert_eq!(
+                IncompatibleProtocolUpdate::from_disconnect_cause(&cause),
+                None
+            );
+        }
+    }
+}
@@ -1,11 +1,18 @@
+use crate::app::diagnostics::DiagnosticsBundle;
+use crate::app::logging::{LogFilterHandle, parse_log_level};
 use crate::app::state::AppState;
-use crate::app::{AppFolder, UpdateInfo, get_update, open_app_folder, open_fatal_error_dialog};
+use crate::app::state::audio::AppStateAudioExt;
+use crate::app::{
+    AppFolder, UpdateInfo, get_update, open_app_folder, open_fatal_error_dialog, reveal_log_file,
+};
 use crate::build::VersionInfo;
+use crate::config::bundle::SettingsBundle;
+use crate::config::watcher::apply_config;
 use crate::config::{
-    AppConfig, CLIENT_SETTINGS_FILE_NAME, ClientConfig, FrontendStationsConfig, Persistable,
-    PersistedClientConfig,
+    AppConfig, CLIENT_SETTINGS_FILE_NAME, ClientConfig, ConfigIssue, FrontendStationsConfig,
+    Persistable, PersistedClientConfig,
 };
-use crate::error::Error;
+use crate::error::{Error, StartupDiagnostics, StartupError, StartupStep};
 use crate::platform::Capabilities;
 use anyhow::Context;
 use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
@@ -22,8 +29,17 @@ pub async fn app_frontend_ready(
     #[cfg(target_os = "linux")]
     window.eval("document.body.classList.add('linux')").ok();
 
-    let state = app_state.lock().await;
-    if let Err(err) = state.config.client.restore_window_state(&app) {
+    let mut state = app_state.lock().await;
+    if let Some(fallback) = state.take_audio_host_fallback() {
+        log::info!(
+            "Notifying frontend about audio host fallback from {} to {}",
+            fallback.missing_host,
+            fallback.host
+        );
+        app.emit("audio:host-fallback", fallback).ok();
+    }
+
+    if let Err(err) = state.config.client.restore_window_state(&app, true) {
         log::warn!("Failed to restore saved window state: {err}");
     }
 
@@ -55,7 +71,7 @@ pub async fn app_frontend_ready(
 
         open_fatal_error_dialog(
             &app,
-            "Failed to show main window. Check your logs for further details.",
+            &StartupDiagnostics::failed(StartupStep::ShowWindow, StartupError::Window),
         );
 
         app.exit(1);
@@ -71,6 +87,19 @@ pub fn app_open_folder(app: AppHandle, folder: AppFolder) -> Result<(), Error> {
     Ok(())
 }
 
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn app_open_log_file(app: AppHandle) -> Result<(), Error> {
+    reveal_log_file(&app)
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub fn app_open_config_dir(app: AppHandle) -> Result<(), Error> {
+    open_app_folder(&app, AppFolder::Config).context("Failed to open config folder")?;
+    Ok(())
+}
+
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn app_check_for_update(app: AppHandle) -> Result<UpdateInfo, Error> {
@@ -82,6 +111,7 @@ pub async fn app_check_for_update(app: AppHandle) -> Result<UpdateInfo, Error> {
             current_version,
             new_version: None,
             required: false,
+            mandatory: false,
         });
     }
 
@@ -91,13 +121,19 @@ pub async fn app_check_for_update(app: AppHandle) -> Result<UpdateInfo, Error> {
             .get("required")
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
+        let mandatory = update
+            .raw_json
+            .get("mandatory")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
-        log::info!("Update available. Required: {required}");
+        log::info!("Update available. Required: {required}, mandatory: {mandatory}");
 
         UpdateInfo {
             current_version,
             new_version: Some(update.version),
-            required,
+            required: required || mandatory,
+            mandatory,
         }
     } else {
         log::info!("No update available");
@@ -105,6 +141,7 @@ pub async fn app_check_for_update(app: AppHandle) -> Result<UpdateInfo, Error> {
             current_version,
             new_version: None,
             required: false,
+            mandatory: false,
         }
     };
 
@@ -159,6 +196,12 @@ pub async fn app_update(app: AppHandle) -> Result<(), Error> {
     Ok(())
 }
 
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn app_get_diagnostics() -> Result<StartupDiagnostics, Error> {
+    Ok(StartupDiagnostics::gather())
+}
+
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn app_platform_capabilities() -> Result<Capabilities, Error> {
@@ -226,7 +269,7 @@ pub async fn app_set_fullscreen(
             state
                 .config
                 .client
-                .restore_window_state(&app)
+                .restore_window_state(&app, false)
                 .context("Failed to restore window state")?;
         }
 
@@ -336,3 +379,129 @@ pub async fn app_pick_extra_stations_config(
 
     Ok(path)
 }
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn app_export_settings(app_state: State<'_, AppState>) -> Result<Option<String>, Error> {
+    log::debug!("Exporting settings");
+
+    let bundle = SettingsBundle::new(&app_state.lock().await.config);
+
+    let Some(path) = rfd::AsyncFileDialog::new()
+        .set_title("Export vacs settings")
+        .set_file_name("vacs-settings.toml")
+        .add_filter("TOML Files", &["toml"])
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    std::fs::write(path.path(), bundle.to_toml()?).context("Failed to write settings file")?;
+
+    log::info!("Exported settings to {:?}", path.path());
+    Ok(path.path().to_str().map(String::from))
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn app_set_log_level(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    log_filter: State<'_, LogFilterHandle>,
+    level: String,
+) -> Result<String, Error> {
+    let level = parse_log_level(&level)?;
+    log_filter.set_level(level);
+
+    let persisted_client_config: PersistedClientConfig = {
+        let mut state = app_state.lock().await;
+        state.config.client.log_level = level.as_str().to_ascii_lowercase();
+        state.config.client.clone().into()
+    };
+
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .expect("Cannot get config directory");
+    persisted_client_config.persist(&config_dir, CLIENT_SETTINGS_FILE_NAME)?;
+
+    Ok(persisted_client_config.client.log_level)
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn app_export_diagnostics(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Option<String>, Error> {
+    log::debug!("Exporting diagnostics");
+
+    let config = app_state.lock().await.config.clone();
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .context("Failed to get logs folder")?;
+    let bundle = DiagnosticsBundle::gather(&config, &log_dir)?;
+
+    let Some(path) = rfd::AsyncFileDialog::new()
+        .set_title("Export vacs diagnostics")
+        .set_file_name("vacs-diagnostics.zip")
+        .add_filter("ZIP Archives", &["zip"])
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let file = std::fs::File::create(path.path()).context("Failed to create diagnostics file")?;
+    bundle.write_zip(file)?;
+
+    log::info!("Exported diagnostics to {:?}", path.path());
+    Ok(path.path().to_str().map(String::from))
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn app_import_settings(app: AppHandle) -> Result<Option<String>, Error> {
+    log::debug!("Importing settings");
+
+    let Some(path) = rfd::AsyncFileDialog::new()
+        .set_title("Import vacs settings")
+        .add_filter("TOML Files", &["toml"])
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let content = std::fs::read_to_string(path.path()).context("Failed to read settings file")?;
+    let bundle = SettingsBundle::from_toml(&content)?;
+
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .expect("Cannot get config directory");
+    bundle.persist(&config_dir)?;
+
+    log::debug!("Reloading configuration");
+    let new_config = AppConfig::parse(&config_dir).context("Failed to reload configuration")?;
+    apply_config(&app, new_config).await;
+
+    log::info!("Imported settings from {:?}", path.path());
+    Ok(path.path().to_str().map(String::from))
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn app_validate_config(app: AppHandle) -> Result<Vec<ConfigIssue>, Error> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .context("Cannot get config directory")?;
+
+    let issues = AppConfig::validate(&config_dir);
+    log::debug!("Validated config, found {} issue(s)", issues.len());
+
+    Ok(issues)
+}
@@ -0,0 +1,164 @@
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Maximum number of digits of a VATSIM CID.
+const MAX_CID_LEN: usize = 10;
+/// Maximum time a call requested via deep link while not connected is kept around. Prevents a
+/// stale link from starting a call long after it has been clicked.
+const PENDING_CALL_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkAction {
+    /// `vacs://auth/...`, callback of the VATSIM OAuth flow.
+    Auth,
+    /// `vacs://call/<cid>`, start a call with the given peer.
+    Call { peer_id: String },
+}
+
+impl DeepLinkAction {
+    /// Parses a deep link URL, returning `None` for malformed URLs and unknown actions.
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = Url::parse(url).ok()?;
+        if url.scheme() != "vacs" {
+            return None;
+        }
+
+        match url.host_str()? {
+            "auth" => Some(Self::Auth),
+            "call" => {
+                let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+                let peer_id = segments.next()?;
+                if segments.next().is_some() || !is_valid_cid(peer_id) {
+                    return None;
+                }
+
+                Some(Self::Call {
+                    peer_id: peer_id.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn is_valid_cid(cid: &str) -> bool {
+    !cid.is_empty() && cid.len() <= MAX_CID_LEN && cid.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Call requested via deep link while the signaling client was not logged in, started once the
+/// connection has been established.
+#[derive(Debug, Default)]
+pub struct PendingCall {
+    peer_id: Option<(String, Instant)>,
+}
+
+impl PendingCall {
+    /// Queues a call with the given peer, replacing any previously queued call.
+    pub fn queue(&mut self, peer_id: String) {
+        self.peer_id = Some((peer_id, Instant::now()));
+    }
+
+    /// Takes the queued call, unless it has been queued for too long.
+    pub fn take(&mut self) -> Option<String> {
+        let (peer_id, queued_at) = self.peer_id.take()?;
+        if queued_at.elapsed() > PENDING_CALL_TIMEOUT {
+            log::info!("Discarding pending call with {peer_id}, queued too long ago");
+            return None;
+        }
+        Some(peer_id)
+    }
+
+    pub fn clear(&mut self) {
+        self.peer_id = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(peer_id: &str) -> Option<DeepLinkAction> {
+        Some(DeepLinkAction::Call {
+            peer_id: peer_id.to_string(),
+        })
+    }
+
+    #[test]
+    fn parse_call() {
+        assert_eq!(
+            DeepLinkAction::parse("vacs://call/1234567"),
+            call("1234567")
+        );
+        assert_eq!(
+            DeepLinkAction::parse("vacs://call/1234567/"),
+            call("1234567")
+        );
+        assert_eq!(DeepLinkAction::parse("vacs://call/810000"), call("810000"));
+    }
+
+    #[test]
+    fn parse_call_invalid_cid() {
+        assert_eq!(DeepLinkAction::parse("vacs://call"), None);
+        assert_eq!(DeepLinkAction::parse("vacs://call/"), None);
+        assert_eq!(DeepLinkAction::parse("vacs://call/abc"), None);
+        assert_eq!(DeepLinkAction::parse("vacs://call/12a4567"), None);
+        assert_eq!(DeepLinkAction::parse("vacs://call/-1234567"), None);
+        assert_eq!(DeepLinkAction::parse("vacs://call/12345678901"), None);
+        assert_eq!(DeepLinkAction::parse("vacs://call/1234567/extra"), None);
+        assert_eq!(DeepLinkAction::parse("vacs://call/%31%32%33"), None);
+    }
+
+    #[test]
+    fn parse_auth() {
+        assert_eq!(
+            DeepLinkAction::parse("vacs://auth/vatsim/callback?code=abc&state=def"),
+            Some(DeepLinkAction::Auth)
+        );
+    }
+
+    #[test]
+    fn parse_unknown() {
+        assert_eq!(DeepLinkAction::parse("vacs://hangup/1234567"), None);
+        assert_eq!(DeepLinkAction::parse("https://call/1234567"), None);
+        assert_eq!(DeepLinkAction::parse("vacs:call/1234567"), None);
+        assert_eq!(DeepLinkAction::parse("not a url"), None);
+        assert_eq!(DeepLinkAction::parse(""), None);
+    }
+
+    #[test]
+    fn pending_call_taken_once() {
+        let mut pending = PendingCall::default();
+        assert_eq!(pending.take(), None);
+
+        pending.queue("1234567".to_string());
+        assert_eq!(pending.take(), Some("1234567".to_string()));
+        assert_eq!(pending.take(), None);
+    }
+
+    #[test]
+    fn pending_call_replaced_by_newer() {
+        let mut pending = PendingCall::default();
+        pending.queue("1234567".to_string());
+        pending.queue("7654321".to_string());
+        assert_eq!(pending.take(), Some("7654321".to_string()));
+        assert_eq!(pending.take(), None);
+    }
+
+    #[test]
+    fn pending_call_cleared() {
+        let mut pending = PendingCall::default();
+        pending.queue("1234567".to_string());
+        pending.clear();
+        assert_eq!(pending.take(), None);
+    }
+
+    #[test]
+    fn pending_call_expires() {
+        let mut pending = PendingCall::default();
+        pending.queue("1234567".to_string());
+        if let Some((_, queued_at)) = &mut pending.peer_id {
+            *queued_at -= PENDING_CALL_TIMEOUT + Duration::from_secs(1);
+        }
+        assert_eq!(pending.take(), None);
+    }
+}
@@ -0,0 +1,357 @@
+use crate::config::AppConfig;
+use crate::error::StartupDiagnostics;
+use crate::secrets::{self, SecretKey};
+use anyhow::Context;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+use vacs_audio::device::{DeviceSelector, DeviceType};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Number of most recent log files included in a diagnostics bundle.
+pub const DIAGNOSTICS_LOG_FILES: usize = 3;
+
+const REDACTED: &str = "<redacted>";
+
+/// Segments of config keys (split at `_`) marking their value as auth material.
+const SENSITIVE_KEY_SEGMENTS: [&str; 7] = [
+    "token",
+    "secret",
+    "password",
+    "credential",
+    "credentials",
+    "cookie",
+    "username",
+];
+
+/// Audio hosts and devices available on the system.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioDeviceList {
+    pub host: String,
+    pub hosts: Vec<String>,
+    pub input: Vec<String>,
+    pub output: Vec<String>,
+}
+
+impl AudioDeviceList {
+    pub fn gather(preferred_host: Option<&str>) -> Self {
+        let devices = |device_type| {
+            DeviceSelector::all_device_names(device_type, preferred_host).unwrap_or_else(|err| {
+                log::warn!("Failed to list {device_type:?} devices for diagnostics: {err}");
+                Vec::new()
+            })
+        };
+
+        Self {
+            host: preferred_host
+                .filter(|host| !host.is_empty())
+                .map(String::from)
+                .unwrap_or_else(DeviceSelector::default_host_name),
+            hosts: DeviceSelector::all_host_names(),
+            input: devices(DeviceType::Input),
+            output: devices(DeviceType::Output),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticsInfo {
+    #[serde(flatten)]
+    diagnostics: StartupDiagnostics,
+    audio_devices: AudioDeviceList,
+}
+
+/// Archive of everything needed to look into a bug report: recent logs, the redacted config,
+/// version and platform information as well as the available audio devices.
+///
+/// Auth material is never included: token-like config values are replaced and any value
+/// stored by the secrets module is scrubbed from the config and logs alike.
+#[derive(Debug)]
+pub struct DiagnosticsBundle {
+    info: DiagnosticsInfo,
+    config: toml::Value,
+    logs: Vec<(String, String)>,
+}
+
+impl DiagnosticsBundle {
+    pub fn gather(config: &AppConfig, log_dir: &Path) -> anyhow::Result<Self> {
+        let secrets = stored_secrets();
+        let info = DiagnosticsInfo {
+            diagnostics: StartupDiagnostics::gather(),
+            audio_devices: AudioDeviceList::gather(config.audio.host_name.as_deref()),
+        };
+        Self::new(info, config, log_dir, &secrets)
+    }
+
+    fn new(
+        info: DiagnosticsInfo,
+        config: &AppConfig,
+        log_dir: &Path,
+        secrets: &[String],
+    ) -> anyhow::Result<Self> {
+        let logs = recent_log_files(log_dir)
+            .into_iter()
+            .filter_map(|path| {
+                let name = path.file_name()?.to_string_lossy().into_owned();
+                match std::fs::read(&path) {
+                    Ok(content) => Some((
+                        name,
+                        redact_secrets(&String::from_utf8_lossy(&content), secrets),
+                    )),
+                    Err(err) => {
+                        log::warn!("Failed to read log file {path:?} for diagnostics: {err}");
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            info,
+            config: redact_config(config, secrets)?,
+            logs,
+        })
+    }
+
+    /// Writes the bundle as a ZIP archive.
+    pub fn write_zip<W: Write + Seek>(&self, writer: W) -> anyhow::Result<()> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        let mut zip = ZipWriter::new(writer);
+
+        let info =
+            serde_json::to_string_pretty(&self.info).context("Failed to serialize diagnostics")?;
+        let config =
+            toml::to_string_pretty(&self.config).context("Failed to serialize redacted config")?;
+
+        let files = [
+            ("diagnostics.json".to_string(), info.as_str()),
+            ("config.toml".to_string(), config.as_str()),
+        ]
+        .into_iter()
+        .chain(
+            self.logs
+                .iter()
+                .map(|(name, content)| (format!("logs/{name}"), content.as_str())),
+        );
+
+        for (name, content) in files {
+            zip.start_file(name.as_str(), options)
+                .with_context(|| format!("Failed to add {name} to diagnostics bundle"))?;
+            zip.write_all(content.as_bytes())
+                .with_context(|| format!("Failed to write {name} to diagnostics bundle"))?;
+        }
+
+        zip.finish()
+            .context("Failed to finish diagnostics bundle")?;
+        Ok(())
+    }
+}
+
+/// Serializes the config with all auth material removed, see [`DiagnosticsBundle`].
+pub fn redact_config(config: &AppConfig, secrets: &[String]) -> anyhow::Result<toml::Value> {
+    let mut value = toml::Value::try_from(config).context("Failed to serialize config")?;
+    redact_value(&mut value, secrets);
+    Ok(value)
+}
+
+fn redact_value(value: &mut toml::Value, secrets: &[String]) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                if is_sensitive_key(key) {
+                    *value = toml::Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value, secrets);
+                }
+            }
+        }
+        toml::Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| redact_value(value, secrets)),
+        toml::Value::String(s) => *s = redact_secrets(s, secrets),
+        _ => {}
+    }
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    key.split('_')
+        .any(|segment| SENSITIVE_KEY_SEGMENTS.contains(&segment.to_ascii_lowercase().as_str()))
+}
+
+fn redact_secrets(text: &str, secrets: &[String]) -> String {
+    secrets
+        .iter()
+        .filter(|secret| !secret.is_empty())
+        .fold(text.to_string(), |text, secret| {
+            text.replace(secret.as_str(), REDACTED)
+        })
+}
+
+fn stored_secrets() -> Vec<String> {
+    SecretKey::ALL
+        .into_iter()
+        .filter_map(|key| match secrets::get(key) {
+            Ok(secret) => secret,
+            Err(err) => {
+                log::warn!(
+                    "Failed to read secret {} for redaction: {err:#}",
+                    key.as_str()
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// Returns the most recently modified log files in the given folder, newest first.
+fn recent_log_files(log_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(log_dir) else {
+        return Vec::new();
+    };
+
+    let mut files = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect::<Vec<_>>();
+    files.sort_by_key(|(modified, _)| Reverse(*modified));
+
+    files
+        .into_iter()
+        .take(DIAGNOSTICS_LOG_FILES)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+    use vacs_signaling::protocol::http::webrtc::{IceConfig, IceServer};
+
+    const SECRET: &str = "c2VjcmV0LWVuY3J5cHRpb24ta2V5";
+
+    fn test_info() -> DiagnosticsInfo {
+        DiagnosticsInfo {
+            diagnostics: StartupDiagnostics::gather(),
+            audio_devices: AudioDeviceList {
+                host: "WASAPI".to_string(),
+                hosts: vec!["WASAPI".to_string()],
+                input: vec!["Headset Microphone".to_string()],
+                output: vec!["Headset".to_string()],
+            },
+        }
+    }
+
+    fn test_config() -> AppConfig {
+        let mut config = AppConfig::default();
+        config.ice = IceConfig {
+            ice_servers: vec![IceServer {
+                urls: vec!["turn:turn.example.com:3478".to_string()],
+                username: Some("turn-user".to_string()),
+                credential: Some("turn-credential".to_string()),
+            }],
+            expires_at: None,
+        };
+        config.audio.input_device_name = Some(format!("Microphone {SECRET}"));
+        config
+    }
+
+    fn contains_sensitive_value(value: &toml::Value) -> bool {
+        match value {
+            toml::Value::Table(table) => table.iter().any(|(key, value)| {
+                (is_sensitive_key(key) && value.as_str() != Some(REDACTED))
+                    || contains_sensitive_value(value)
+            }),
+            toml::Value::Array(values) => values.iter().any(contains_sensitive_value),
+            toml::Value::String(s) => s.contains(SECRET),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn redacts_auth_material() {
+        let config = test_config();
+        let redacted = redact_config(&config, &[SECRET.to_string()]).unwrap();
+
+        assert!(!contains_sensitive_value(&redacted));
+
+        let server = &redacted["ice"]["ice_servers"][0];
+        assert_eq!(server["username"].as_str(), Some(REDACTED));
+        assert_eq!(server["credential"].as_str(), Some(REDACTED));
+        assert_eq!(
+            server["urls"][0].as_str(),
+            Some("turn:turn.example.com:3478")
+        );
+        assert_eq!(
+            redacted["audio"]["input_device_name"].as_str(),
+            Some(format!("Microphone {REDACTED}").as_str())
+        );
+    }
+
+    #[test]
+    fn sensitive_keys() {
+        for key in ["ws_token", "credential", "session_cookie", "TURN_PASSWORD"] {
+            assert!(is_sensitive_key(key), "{key} should be sensitive");
+        }
+        for key in ["tokenizer", "keybinds", "host_name", "auto_hangup_seconds"] {
+            assert!(!is_sensitive_key(key), "{key} should not be sensitive");
+        }
+    }
+
+    #[test]
+    fn bundle_contains_no_auth_material() {
+        let log_dir =
+            std::env::temp_dir().join(format!("vacs-diagnostics-test-{}", std::process::id()));
+        std::fs::create_dir_all(&log_dir).unwrap();
+        std::fs::write(
+            log_dir.join("vacs.log"),
+            format!("Loaded cookie store with key {SECRET}\n"),
+        )
+        .unwrap();
+        std::fs::write(log_dir.join("startup-diagnostics.json"), "{}").unwrap();
+
+        let bundle =
+            DiagnosticsBundle::new(test_info(), &test_config(), &log_dir, &[SECRET.to_string()])
+                .unwrap();
+        std::fs::remove_dir_all(&log_dir).unwrap();
+
+        let mut buf = Cursor::new(Vec::new());
+        bundle.write_zip(&mut buf).unwrap();
+
+        let mut archive = zip::ZipArchive::new(buf).unwrap();
+        let mut names = archive.file_names().map(String::from).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["config.toml", "diagnostics.json", "logs/vacs.log"]
+        );
+
+        for name in names {
+            let mut content = String::new();
+            archive
+                .by_name(&name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            assert!(!content.contains(SECRET), "{name} contains a secret");
+            assert!(
+                !content.contains("turn-credential"),
+                "{name} contains a credential"
+            );
+        }
+
+        let mut config = String::new();
+        archive
+            .by_name("config.toml")
+            .unwrap()
+            .read_to_string(&mut config)
+            .unwrap();
+        let config = toml::Value::Table(config.parse::<toml::Table>().unwrap());
+        assert!(!contains_sensitive_value(&config));
+    }
+}
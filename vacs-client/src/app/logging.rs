@@ -0,0 +1,139 @@
+use log::{LevelFilter, Metadata};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Crates whose log level can be adjusted at runtime. All other crates only log warnings.
+pub const VACS_LOG_TARGETS: [&str; 6] = [
+    "vacs_client_lib",
+    "vacs_audio",
+    "vacs_signaling",
+    "vacs_vatsim",
+    "vacs_webrtc",
+    "trackaudio",
+];
+
+/// Log level of all other crates, see [`VACS_LOG_TARGETS`].
+pub const OTHER_LOG_LEVEL: LevelFilter = LevelFilter::Warn;
+
+/// Parses a log level name (`off`, `error`, `warn`, `info`, `debug` or `trace`), ignoring case.
+pub fn parse_log_level(level: &str) -> anyhow::Result<LevelFilter> {
+    LevelFilter::from_str(level.trim()).map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid log level {level}, expected one of off, error, warn, info, debug or trace"
+        )
+    })
+}
+
+/// Reloadable filter deciding which records of the [`VACS_LOG_TARGETS`] are logged.
+///
+/// The log plugin is built with the most verbose level for these crates and consults the handle
+/// for every record, so changing the level via [`LogFilterHandle::set_level`] takes effect
+/// immediately without rebuilding the logger.
+#[derive(Debug, Clone)]
+pub struct LogFilterHandle {
+    level: Arc<AtomicUsize>,
+}
+
+impl Default for LogFilterHandle {
+    fn default() -> Self {
+        Self::new(LevelFilter::Trace)
+    }
+}
+
+impl LogFilterHandle {
+    pub fn new(level: LevelFilter) -> Self {
+        Self {
+            level: Arc::new(AtomicUsize::new(level as usize)),
+        }
+    }
+
+    pub fn level(&self) -> LevelFilter {
+        LevelFilter::iter()
+            .nth(self.level.load(Ordering::Relaxed))
+            .unwrap_or(LevelFilter::Trace)
+    }
+
+    pub fn set_level(&self, level: LevelFilter) {
+        let previous = self.level.swap(level as usize, Ordering::Relaxed);
+        if previous != level as usize {
+            log::info!("Log level changed to {level}");
+        }
+    }
+
+    /// Returns whether a record with the given metadata should be logged.
+    pub fn enabled(&self, metadata: &Metadata) -> bool {
+        let crate_name = metadata.target().split("::").next().unwrap_or_default();
+        if VACS_LOG_TARGETS.contains(&crate_name) {
+            metadata.level() <= self.level()
+        } else {
+            metadata.level() <= OTHER_LOG_LEVEL
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+
+    fn emitted(filter: &LogFilterHandle, records: &[(&'static str, Level)]) -> Vec<String> {
+        records
+            .iter()
+            .filter(|(target, level)| {
+                filter.enabled(&Metadata::builder().target(target).level(*level).build())
+            })
+            .map(|(target, level)| format!("{target} {level}"))
+            .collect()
+    }
+
+    #[test]
+    fn set_level_changes_emitted_records() {
+        let records = [
+            ("vacs_signaling::client", Level::Trace),
+            ("vacs_client_lib::app", Level::Debug),
+            ("vacs_audio", Level::Info),
+            ("vacs_webrtc::peer", Level::Warn),
+            ("tungstenite::protocol", Level::Debug),
+            ("tungstenite::protocol", Level::Error),
+        ];
+
+        let filter = LogFilterHandle::default();
+        assert_eq!(
+            emitted(&filter, &records),
+            vec![
+                "vacs_signaling::client TRACE",
+                "vacs_client_lib::app DEBUG",
+                "vacs_audio INFO",
+                "vacs_webrtc::peer WARN",
+                "tungstenite::protocol ERROR",
+            ]
+        );
+
+        filter.set_level(LevelFilter::Info);
+        assert_eq!(filter.level(), LevelFilter::Info);
+        assert_eq!(
+            emitted(&filter, &records),
+            vec![
+                "vacs_audio INFO",
+                "vacs_webrtc::peer WARN",
+                "tungstenite::protocol ERROR",
+            ]
+        );
+
+        filter.set_level(LevelFilter::Off);
+        assert_eq!(
+            emitted(&filter, &records),
+            vec!["tungstenite::protocol ERROR"]
+        );
+    }
+
+    #[test]
+    fn parses_log_levels() {
+        assert_eq!(parse_log_level("trace").unwrap(), LevelFilter::Trace);
+        assert_eq!(parse_log_level("DEBUG").unwrap(), LevelFilter::Debug);
+        assert_eq!(parse_log_level(" off ").unwrap(), LevelFilter::Off);
+        assert!(parse_log_level("verbose").is_err());
+        assert!(parse_log_level("").is_err());
+    }
+}
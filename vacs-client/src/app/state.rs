@@ -5,19 +5,24 @@ mod sealed;
 pub(crate) mod signaling;
 pub(crate) mod webrtc;
 
-use crate::app::state::signaling::AppStateSignalingExt;
+use crate::app::deep_link::PendingCall;
+use crate::app::state::signaling::{AppStateSignalingExt, CallResponseTask, PendingRejoin};
 use crate::app::state::webrtc::{Call, UnansweredCallGuard};
+use crate::audio::HostFallback;
 use crate::audio::manager::{AudioManager, AudioManagerHandle};
 use crate::config::AppConfig;
 use crate::error::{StartupError, StartupErrorExt};
 use crate::keybinds::engine::{KeybindEngine, KeybindEngineHandle};
 use crate::signaling::auth::TauriTokenProvider;
+use crate::signaling::frequency::FrequencyPeers;
+use crate::signaling::history::{CALL_HISTORY_FILE_NAME, CallHistory, CallOutcome};
 use parking_lot::RwLock;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 use tokio::sync::{Mutex as TokioMutex, RwLock as TokioRwLock};
 use tokio_util::sync::CancellationToken;
+use vacs_audio::device::DeviceSelector;
 use vacs_signaling::client::SignalingClient;
 use vacs_signaling::transport::tokio::TokioTransport;
 
@@ -28,10 +33,25 @@ pub struct AppStateInner {
     audio_manager: AudioManagerHandle,
     keybind_engine: KeybindEngineHandle,
     active_call: Option<Call>,
+    /// Peer ID of the call currently being negotiated, see [`webrtc::CallSetup`].
+    call_setup_peer_id: Option<String>,
     unanswered_call_guard: Option<UnansweredCallGuard>,
+    /// Awaits the peer accepting the outgoing call invite.
+    call_accept_task: Option<CallResponseTask>,
+    /// Awaits the peer answering the call offer sent after it accepted the invite.
+    call_answer_task: Option<CallResponseTask>,
     held_calls: HashMap<String, Call>,       // peer_id -> call
     outgoing_call_peer_id: Option<String>,   // peer_id
     incoming_call_peer_ids: HashSet<String>, // peer_id
+    pending_rejoin: Option<PendingRejoin>,
+    pending_call: PendingCall,
+    /// Cancels the login retries while waiting for an active VATSIM connection.
+    vatsim_connection_wait: Option<CancellationToken>,
+    do_not_disturb: bool,
+    call_history: CallHistory,
+    frequency_peers: FrequencyPeers,
+    ice_config_invalidated: bool,
+    audio_host_fallback: Option<HostFallback>,
 }
 
 pub type AppState = TokioMutex<AppStateInner>;
@@ -43,7 +63,19 @@ impl AppStateInner {
             .app_config_dir()
             .map_startup_err(StartupError::Config)?;
 
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_startup_err(StartupError::Config)?;
+
         let config = AppConfig::parse(&config_dir).map_startup_err(StartupError::Config)?;
+        let audio_host_fallback = config
+            .audio
+            .validate_host(&DeviceSelector::all_host_names())
+            .map(|missing_host| HostFallback {
+                missing_host,
+                host: DeviceSelector::default_host_name(),
+            });
         let shutdown_token = CancellationToken::new();
 
         Ok(Self {
@@ -52,7 +84,8 @@ impl AppStateInner {
                 app.clone(),
                 &config.backend.ws_url,
                 shutdown_token.child_token(),
-                config.client.max_signaling_reconnect_attempts(),
+                config.client.signaling_reconnect_config(),
+                config.client.signaling_latency_interval(),
             ),
             audio_manager: Arc::new(RwLock::new(
                 AudioManager::new(app.clone(), &config.audio)
@@ -67,14 +100,30 @@ impl AppStateInner {
             ))),
             shutdown_token,
             active_call: None,
+            call_setup_peer_id: None,
             unanswered_call_guard: None,
+            call_accept_task: None,
+            call_answer_task: None,
             held_calls: HashMap::new(),
             outgoing_call_peer_id: None,
             incoming_call_peer_ids: HashSet::new(),
+            pending_rejoin: None,
+            pending_call: PendingCall::default(),
+            vatsim_connection_wait: None,
+            do_not_disturb: false,
+            call_history: CallHistory::load(data_dir.join(CALL_HISTORY_FILE_NAME)),
+            frequency_peers: FrequencyPeers::default(),
+            ice_config_invalidated: false,
+            audio_host_fallback,
         })
     }
 
-    pub fn shutdown(&self) {
+    pub fn child_shutdown_token(&self) -> CancellationToken {
+        self.shutdown_token.child_token()
+    }
+
+    pub fn shutdown(&mut self) {
+        self.call_history.end_all(Some(CallOutcome::EndedOnExit));
         self.shutdown_token.cancel();
     }
 }
@@ -1,12 +1,80 @@
 use crate::app::state::{AppStateInner, sealed};
+use crate::audio::HostFallback;
 use crate::audio::manager::AudioManagerHandle;
+use crate::error::Error;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
 
 pub trait AppStateAudioExt: sealed::Sealed {
     fn audio_manager_handle(&self) -> AudioManagerHandle;
+    /// Takes the audio host fallback that happened on startup, if any.
+    fn take_audio_host_fallback(&mut self) -> Option<HostFallback>;
+    /// Tears down and rebuilds all audio streams using the current audio config. Call audio of
+    /// the active call and the input level meter are re-attached afterwards, to the previous
+    /// streams if the new ones fail to open.
+    async fn restart_audio_engine(&mut self, app: &AppHandle) -> Result<(), Error>;
 }
 
 impl AppStateAudioExt for AppStateInner {
     fn audio_manager_handle(&self) -> AudioManagerHandle {
         self.audio_manager.clone()
     }
+
+    fn take_audio_host_fallback(&mut self) -> Option<HostFallback> {
+        self.audio_host_fallback.take()
+    }
+
+    async fn restart_audio_engine(&mut self, app: &AppHandle) -> Result<(), Error> {
+        log::info!("Restarting audio engine");
+        app.emit("audio:restarting", Value::Null).ok();
+
+        let (call_attached, call_muted, level_meter_attached) = {
+            let audio_manager = self.audio_manager.read();
+            (
+                audio_manager.is_call_attached(),
+                audio_manager.is_call_muted(),
+                audio_manager.is_input_level_meter_attached(),
+            )
+        };
+
+        {
+            let mut audio_manager = self.audio_manager.write();
+            audio_manager.detach_call_output();
+            audio_manager.detach_input_device();
+        }
+        let call_peer_id = call_attached.then(|| self.pause_active_call()).flatten();
+
+        let restarted = self
+            .audio_manager
+            .write()
+            .restart_output_streams(app.clone(), &self.config.audio);
+        if let Err(err) = &restarted {
+            log::warn!("Failed to restart output streams, keeping the previous ones: {err:?}");
+        }
+
+        if let Some(peer_id) = call_peer_id {
+            log::debug!("Re-attaching call with peer {peer_id} after restarting audio engine");
+            self.attach_call_audio(app, &peer_id).await?;
+            if call_muted {
+                self.audio_manager
+                    .write()
+                    .set_call_muted(true, self.config.audio.output_device_volume);
+            }
+        } else if level_meter_attached {
+            log::debug!("Re-attaching input level meter after restarting audio engine");
+            let app_clone = app.clone();
+            self.audio_manager.write().attach_input_level_meter(
+                app.clone(),
+                &self.config.audio,
+                Box::new(move |level| {
+                    app_clone.emit("audio:input-level", level).ok();
+                }),
+            )?;
+        }
+
+        restarted?;
+        log::info!("Successfully restarted audio engine");
+        app.emit("audio:restarted", Value::Null).ok();
+        Ok(())
+    }
 }
@@ -1,25 +1,165 @@
+use crate::app::IncompatibleProtocolUpdate;
 use crate::app::state::http::HttpState;
-use crate::app::state::webrtc::{AppStateWebrtcExt, UnansweredCallGuard};
+use crate::app::state::webrtc::{
+    AppStateWebrtcExt, CallCleanup, UnansweredCall, UnansweredCallGuard,
+};
 use crate::app::state::{AppState, AppStateInner, sealed};
 use crate::audio::manager::{AudioManagerHandle, SourceType};
-use crate::config::{BackendEndpoint, WS_LOGIN_TIMEOUT};
-use crate::error::{Error, FrontendError};
+use crate::config::{
+    BUSY_TONE_DURATION, BackendEndpoint, SESSION_STATS_INTERVAL, WS_LOGIN_TIMEOUT,
+};
+use crate::error::{Error, FrontendError, format_disconnect_cause};
 use crate::signaling::auth::TauriTokenProvider;
+use crate::signaling::history::{CallDirection, CallHistoryEntry, CallOutcome};
 use serde::Serialize;
 use serde_json::Value;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::MutexGuard;
 use tokio_util::sync::CancellationToken;
-use vacs_signaling::client::{SignalingClient, SignalingEvent, State};
+use vacs_signaling::client::{
+    ReconnectConfig, SessionStats, SignalingClient, SignalingEvent, State,
+};
 use vacs_signaling::error::{SignalingError, SignalingRuntimeError};
 use vacs_signaling::protocol::http::webrtc::IceConfig;
-use vacs_signaling::protocol::ws::{CallErrorReason, ErrorReason, SignalingMessage};
+use vacs_signaling::protocol::ws::{
+    CallEndReason, CallErrorReason, CallRejectReason, ErrorReason, LoginFailureReason,
+    SignalingMessage,
+};
 use vacs_signaling::transport::tokio::TokioTransport;
 
-const INCOMING_CALLS_LIMIT: usize = 5;
+/// Maximum time between losing the signaling connection and reconnecting for the previously
+/// active call to still be rejoined.
+const REJOIN_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Login retries after the server found no active VATSIM connection, waiting roughly three
+/// minutes in total for controllers starting vacs shortly before connecting to VATSIM.
+const VATSIM_CONNECTION_RETRY: VatsimConnectionRetry = VatsimConnectionRetry {
+    base: Duration::from_secs(5),
+    cap: Duration::from_secs(60),
+    max_retries: 6,
+};
+
+/// Retries the login with exponential backoff while the server finds no active VATSIM
+/// connection for the client.
+///
+/// Separate from the automatic reconnect of the [`SignalingClient`], which only applies to
+/// connections lost after a successful login.
+struct VatsimConnectionRetry {
+    base: Duration,
+    cap: Duration,
+    max_retries: u8,
+}
+
+impl VatsimConnectionRetry {
+    /// Delay before the given retry, starting at 1.
+    fn delay(&self, retry: u8) -> Duration {
+        self.base
+            .saturating_mul(1 << retry.saturating_sub(1).min(16))
+            .min(self.cap)
+    }
+
+    /// Calls `connect` until it succeeds, fails for any reason other than a missing VATSIM
+    /// connection, the retries are exhausted or `cancel` is cancelled. `on_retry` is called with
+    /// the retry number and delay before waiting for each retry.
+    async fn run<F, Fut>(
+        &self,
+        mut connect: F,
+        mut on_retry: impl FnMut(u8, Duration),
+        cancel: &CancellationToken,
+    ) -> Result<(), SignalingError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<(), SignalingError>>,
+    {
+        let mut retry = 0;
+        loop {
+            match connect().await {
+                Err(
+                    err @ SignalingError::LoginError(LoginFailureReason::NoActiveVatsimConnection),
+                ) if retry < self.max_retries => {
+                    retry += 1;
+                    let delay = self.delay(retry);
+                    log::info!(
+                        "No active VATSIM connection found, retrying login in {delay:?} ({retry}/{})",
+                        self.max_retries
+                    );
+                    on_retry(retry, delay);
+
+                    tokio::select! {
+                        biased;
+                        _ = cancel.cancelled() => {
+                            log::debug!("Waiting for VATSIM connection cancelled");
+                            return Err(err);
+                        }
+                        _ = tokio::time::sleep(delay) => {}
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// How an incoming call invite is handled.
+#[derive(Debug, PartialEq, Eq)]
+enum IncomingCallAction {
+    /// Silently drop the invite, the caller keeps ringing until the call times out.
+    Ignore,
+    /// Reject the invite, optionally telling the caller why.
+    Reject(Option<CallRejectReason>),
+    /// Ring and show the invite.
+    Deliver,
+    /// Show the invite and accept it right away, the caller is on the auto-answer allowlist.
+    AutoAnswer,
+}
+
+impl IncomingCallAction {
+    fn decide(
+        ignored: bool,
+        auto_answer: bool,
+        do_not_disturb: bool,
+        incoming_calls: usize,
+        max_incoming_calls: usize,
+        in_call: bool,
+    ) -> Self {
+        if ignored {
+            Self::Ignore
+        } else if do_not_disturb {
+            Self::Reject(Some(CallRejectReason::DoNotDisturb))
+        } else if incoming_calls >= max_incoming_calls {
+            Self::Reject(None)
+        } else if auto_answer && !in_call {
+            Self::AutoAnswer
+        } else {
+            Self::Deliver
+        }
+    }
+}
+
+/// Call that was active when the signaling connection was lost unexpectedly and should be
+/// rejoined once the connection has been re-established.
+pub struct PendingRejoin {
+    peer_id: String,
+    disconnected_at: Instant,
+}
+
+/// Task sending a call setup message and handling the peer's correlated response, resolving to
+/// whether the response was received.
+///
+/// The response is still broadcast like any other message, its broadcast waits for the task
+/// instead of handling it again. This keeps the order of the messages following the response,
+/// e.g. ICE candidates are only added once the answer has been accepted.
+pub struct CallResponseTask {
+    peer_id: String,
+    /// Stops waiting for the response, a response already received is still handled.
+    cancel: CancellationToken,
+    handle: JoinHandle<bool>,
+}
 
 pub trait AppStateSignalingExt: sealed::Sealed {
-    async fn connect_signaling(&self) -> Result<(), Error>;
+    async fn connect_signaling(app: &AppHandle) -> Result<(), Error>;
     async fn disconnect_signaling(&mut self, app: &AppHandle);
     async fn handle_signaling_connection_closed(&mut self, app: &AppHandle);
     async fn send_signaling_message(&mut self, msg: SignalingMessage) -> Result<(), Error>;
@@ -33,9 +173,10 @@ pub trait AppStateSignalingExt: sealed::Sealed {
         app: AppHandle,
         ws_url: &str,
         shutdown_token: CancellationToken,
-        max_reconnect_attempts: u8,
+        reconnect_config: ReconnectConfig,
+        latency_interval: Option<Duration>,
     ) -> SignalingClient<TokioTransport, TauriTokenProvider>;
-    fn start_unanswered_call_timer(&mut self, app: &AppHandle, peer_id: &str);
+    fn start_unanswered_call_timer(&mut self, app: &AppHandle, peer_id: &str, call: UnansweredCall);
     fn cancel_unanswered_call_timer(&mut self, peer_id: &str);
     async fn accept_call(
         &mut self,
@@ -43,21 +184,65 @@ pub trait AppStateSignalingExt: sealed::Sealed {
         peer_id: Option<String>,
     ) -> Result<bool, Error>;
     async fn end_call(&mut self, app: &AppHandle, peer_id: Option<String>) -> Result<bool, Error>;
+    async fn start_call(&mut self, app: &AppHandle, peer_id: String) -> Result<(), Error>;
+    async fn start_deep_link_call(&mut self, app: &AppHandle, peer_id: String)
+    -> Result<(), Error>;
+    async fn refresh_ice_config(&mut self, app: &AppHandle);
+    fn do_not_disturb(&self) -> bool;
+    fn recent_calls(&self, limit: usize) -> Vec<CallHistoryEntry>;
+    fn set_do_not_disturb(&mut self, app: &AppHandle, enabled: bool);
+    async fn force_refresh_ice_config(&mut self, app: &AppHandle);
 }
 
 impl AppStateSignalingExt for AppStateInner {
-    async fn connect_signaling(&self) -> Result<(), Error> {
+    async fn connect_signaling(app: &AppHandle) -> Result<(), Error> {
         log::info!("Connecting to signaling server");
 
-        if self.signaling_client.state() != State::Disconnected {
-            log::info!("Already connected and logged in with signaling server");
-            return Err(Error::Signaling(Box::from(SignalingError::Other(
-                "Already connected".to_string(),
-            ))));
+        // The app state is not locked while waiting for an active VATSIM connection.
+        let (client, cancel) = {
+            let state = app.state::<AppState>();
+            let mut state = state.lock().await;
+
+            if state.signaling_client.state() != State::Disconnected {
+                log::info!("Already connected and logged in with signaling server");
+                return Err(Error::Signaling(Box::from(SignalingError::Other(
+                    "Already connected".to_string(),
+                ))));
+            }
+
+            let cancel = state.child_shutdown_token();
+            if let Some(previous) = state.vatsim_connection_wait.replace(cancel.clone()) {
+                previous.cancel();
+            }
+            (state.signaling_client.clone(), cancel)
+        };
+
+        #[derive(Clone, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct VatsimConnectionWait {
+            retry: u8,
+            max_retries: u8,
+            retry_in_secs: u64,
         }
 
         log::debug!("Connecting to signaling server");
-        self.signaling_client.connect().await?;
+        VATSIM_CONNECTION_RETRY
+            .run(
+                || client.connect(),
+                |retry, delay| {
+                    app.emit(
+                        "signaling:waiting-for-vatsim",
+                        VatsimConnectionWait {
+                            retry,
+                            max_retries: VATSIM_CONNECTION_RETRY.max_retries,
+                            retry_in_secs: delay.as_secs(),
+                        },
+                    )
+                    .ok();
+                },
+                &cancel,
+            )
+            .await?;
 
         log::info!("Successfully connected to signaling server");
         Ok(())
@@ -66,6 +251,11 @@ impl AppStateSignalingExt for AppStateInner {
     async fn disconnect_signaling(&mut self, app: &AppHandle) {
         log::info!("Disconnecting from signaling server");
 
+        self.pending_rejoin = None;
+        self.pending_call.clear();
+        if let Some(wait) = self.vatsim_connection_wait.take() {
+            wait.cancel();
+        }
         self.cleanup_signaling(app).await;
         app.emit("signaling:disconnected", Value::Null).ok();
         self.signaling_client.disconnect().await;
@@ -145,31 +335,75 @@ impl AppStateSignalingExt for AppStateInner {
         app: AppHandle,
         ws_url: &str,
         shutdown_token: CancellationToken,
-        max_reconnect_attempts: u8,
+        reconnect_config: ReconnectConfig,
+        latency_interval: Option<Duration>,
     ) -> SignalingClient<TokioTransport, TauriTokenProvider> {
-        SignalingClient::new(
+        let client = SignalingClient::new(
             TokioTransport::new(ws_url),
             TauriTokenProvider::new(app.clone()),
-            move |e| {
-                let handle = app.clone();
-                async move {
-                    Self::handle_signaling_event(&handle, e).await;
+            {
+                let app = app.clone();
+                move |e| {
+                    let handle = app.clone();
+                    async move {
+                        Self::handle_signaling_event(&handle, e).await;
+                    }
                 }
             },
-            shutdown_token,
+            shutdown_token.clone(),
             WS_LOGIN_TIMEOUT,
-            max_reconnect_attempts,
+            reconnect_config,
             tauri::async_runtime::handle().inner(),
-        )
+        );
+        client.set_latency_interval(latency_interval);
+
+        let mut latency_rx = client.subscribe_latency();
+        tauri::async_runtime::spawn({
+            let app = app.clone();
+            async move {
+                while latency_rx.changed().await.is_ok() {
+                    let latency_ms = latency_rx
+                        .borrow_and_update()
+                        .map(|latency| latency.as_millis() as u64);
+                    app.emit("signaling:latency", latency_ms).ok();
+                }
+            }
+        });
+
+        // Does not hold a clone of the client, which would keep it from shutting down on drop
+        let mut session_stats_rx = client.subscribe_session_stats();
+        tauri::async_runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(SESSION_STATS_INTERVAL);
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = shutdown_token.cancelled() => break,
+                    changed = session_stats_rx.changed() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                    }
+                    _ = ticker.tick() => {}
+                }
+                let stats = *session_stats_rx.borrow_and_update();
+                Self::emit_session_stats(&app, stats);
+            }
+        });
+
+        client
     }
 
-    fn start_unanswered_call_timer(&mut self, app: &AppHandle, peer_id: &str) {
+    fn start_unanswered_call_timer(
+        &mut self,
+        app: &AppHandle,
+        peer_id: &str,
+        call: UnansweredCall,
+    ) {
         self.cancel_unanswered_call_timer(peer_id);
 
-        let timeout = Duration::from_secs(self.config.client.auto_hangup_seconds);
-        if timeout.is_zero() {
+        let Some(timeout) = call.timeout(&self.config.client) else {
             return;
-        }
+        };
 
         let cancel = self.shutdown_token.child_token();
 
@@ -178,31 +412,41 @@ impl AppStateSignalingExt for AppStateInner {
             let peer_id = peer_id.to_string();
             let cancel = cancel.clone();
             async move {
-                log::debug!("Starting unanswered call timer of {timeout:?} for peer {peer_id}");
-                tokio::select! {
-                    biased;
-                    _ = cancel.cancelled() => {
-                        log::debug!("Unanswered call timer cancelled for peer {peer_id}");
-                    }
-                    _ = tokio::time::sleep(timeout) => {
-                        log::debug!("Unanswered call timer expired for peer {peer_id}, hanging up");
-
-                        let state = app.state::<AppState>();
-                        let mut state = state.lock().await;
+                log::debug!(
+                    "Starting unanswered call timer of {timeout:?} for {call:?} with peer {peer_id}"
+                );
+                let Some(reason) = call.expired(timeout, &cancel).await else {
+                    log::debug!("Unanswered call timer cancelled for peer {peer_id}");
+                    return;
+                };
+                log::debug!("Unanswered call timer expired for peer {peer_id}, hanging up");
 
-                        if let Err(err) = state.send_signaling_message(SignalingMessage::CallEnd { peer_id: peer_id.clone() }).await {
-                            log::warn!("Failed to send call end message after call timer expired: {err:?}");
-                        }
+                let state = app.state::<AppState>();
+                let mut state = state.lock().await;
 
-                        state.cleanup_call(&peer_id).await;
-                        state.set_outgoing_call_peer_id(None);
+                if let Err(err) = state
+                    .send_signaling_message(SignalingMessage::CallEnd {
+                        peer_id: peer_id.clone(),
+                        reason: None,
+                    })
+                    .await
+                {
+                    log::warn!("Failed to send call end message after call timer expired: {err:?}");
+                }
 
-                        let audio_manager = app.state::<AudioManagerHandle>();
-                        audio_manager.read().stop(SourceType::Ringback);
+                state.cleanup_call(&peer_id).await;
+                state.set_outgoing_call_peer_id(None);
 
-                        state.emit_call_error(&app, peer_id, false, CallErrorReason::AutoHangup);
-                    }
+                let audio_manager = app.state::<AudioManagerHandle>();
+                if call == UnansweredCall::Invite {
+                    audio_manager
+                        .read()
+                        .play_call_end_tone(CallOutcome::NoAnswer, &state.config.audio);
+                } else {
+                    audio_manager.read().stop(SourceType::Ringback);
                 }
+
+                state.emit_call_error(&app, peer_id, false, reason);
             }
         });
 
@@ -242,7 +486,7 @@ impl AppStateSignalingExt for AppStateInner {
                 .await
             {
                 Ok(config) => {
-                    self.config.ice = config;
+                    self.set_ice_config(config);
                 }
                 Err(err) => {
                     log::warn!("Failed to refresh ICE config, using cached one: {err:?}");
@@ -275,6 +519,7 @@ impl AppStateSignalingExt for AppStateInner {
 
         self.send_signaling_message(SignalingMessage::CallEnd {
             peer_id: peer_id.clone(),
+            reason: None,
         })
         .await?;
 
@@ -289,6 +534,173 @@ impl AppStateSignalingExt for AppStateInner {
 
         Ok(true)
     }
+
+    async fn start_call(&mut self, app: &AppHandle, peer_id: String) -> Result<(), Error> {
+        log::trace!("Sending call invite to {peer_id}");
+        let timeout = self.config.client.outgoing_invite_timeout();
+        let cancel = self.shutdown_token.child_token();
+        let handle = tauri::async_runtime::spawn({
+            let app = app.clone();
+            let client = self.signaling_client.clone();
+            let peer_id = peer_id.clone();
+            let cancel = cancel.clone();
+            async move {
+                let invite = SignalingMessage::CallInvite {
+                    peer_id: peer_id.clone(),
+                };
+                // Without an invite timeout, the invite stays pending until the next call
+                let res = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => return false,
+                    res = client.send_and_await(
+                        invite,
+                        |m| matches!(m, SignalingMessage::CallAccept { .. }),
+                        timeout.unwrap_or(Duration::MAX),
+                    ) => res,
+                };
+                match res {
+                    Ok(_) => {
+                        Self::handle_call_accept(&app, peer_id).await;
+                        true
+                    }
+                    Err(SignalingError::Timeout(_)) => {
+                        // Ended by the unanswered call timer
+                        log::debug!("Call invite to {peer_id} was not accepted in time");
+                        false
+                    }
+                    Err(err) => {
+                        log::warn!("Failed to send call invite to {peer_id}: {err:?}");
+                        Self::handle_call_invite_failed(&app, peer_id, err).await;
+                        false
+                    }
+                }
+            }
+        });
+        if let Some(previous) = self.call_accept_task.replace(CallResponseTask {
+            peer_id: peer_id.clone(),
+            cancel,
+            handle,
+        }) {
+            previous.cancel.cancel();
+        }
+        self.call_history.start(&peer_id, CallDirection::Outgoing);
+
+        if self.is_ice_config_expired() {
+            self.refresh_ice_config(app).await;
+        }
+
+        self.add_call_to_call_list(app, &peer_id, false);
+        self.start_unanswered_call_timer(app, &peer_id, UnansweredCall::Invite);
+        self.set_outgoing_call_peer_id(Some(peer_id));
+
+        let audio_manager = self.audio_manager.read();
+        audio_manager.stop(SourceType::Busy);
+        audio_manager.restart(SourceType::Ringback);
+
+        Ok(())
+    }
+
+    async fn start_deep_link_call(
+        &mut self,
+        app: &AppHandle,
+        peer_id: String,
+    ) -> Result<(), Error> {
+        if self.signaling_client.state() != State::LoggedIn {
+            log::info!(
+                "Not logged in with signaling server, queueing deep link call with {peer_id}"
+            );
+            self.pending_call.queue(peer_id);
+            return Ok(());
+        }
+
+        if self.outgoing_call_peer_id.is_some() || self.active_call_peer_id().is_some() {
+            log::info!(
+                "Ignoring deep link call with {peer_id}, another call is already in progress"
+            );
+            return Ok(());
+        }
+
+        log::info!("Starting call with {peer_id} from deep link");
+        self.start_call(app, peer_id.clone()).await?;
+        app.emit("signaling:outgoing-call", peer_id).ok();
+
+        Ok(())
+    }
+
+    async fn refresh_ice_config(&mut self, app: &AppHandle) {
+        let config = match app
+            .state::<HttpState>()
+            .http_get::<IceConfig>(BackendEndpoint::IceConfig, None)
+            .await
+        {
+            Ok(config) => config,
+            Err(err) => {
+                log::warn!("Failed to fetch ICE config, falling back to default: {err:?}");
+                return;
+            }
+        };
+
+        log::info!(
+            "Received ICE config from server, expires at {}",
+            config.expires_at.unwrap_or_default()
+        );
+        self.set_ice_config(config);
+    }
+
+    fn do_not_disturb(&self) -> bool {
+        self.do_not_disturb
+    }
+
+    fn recent_calls(&self, limit: usize) -> Vec<CallHistoryEntry> {
+        self.call_history.recent(limit)
+    }
+
+    fn set_do_not_disturb(&mut self, app: &AppHandle, enabled: bool) {
+        if self.do_not_disturb == enabled {
+            return;
+        }
+
+        log::info!(
+            "{} do-not-disturb mode",
+            if enabled { "Enabling" } else { "Disabling" }
+        );
+        self.do_not_disturb = enabled;
+        app.emit("signaling:do-not-disturb", enabled).ok();
+    }
+
+    async fn force_refresh_ice_config(&mut self, app: &AppHandle) {
+        if self.config.ice.expires_at.is_none() {
+            log::debug!("ICE config was not provided by server, skipping forced refresh");
+            return;
+        }
+
+        // Mark the cached config as stale so it is refreshed before the next call, even if
+        // fetching it right now fails (e.g. while the new network is not fully up yet).
+        self.ice_config_invalidated = true;
+
+        if self.signaling_client.state() == State::Disconnected {
+            log::debug!("Not connected to signaling server, deferring ICE config refresh");
+            return;
+        }
+
+        log::info!("Forcing ICE config refresh");
+        match app
+            .state::<HttpState>()
+            .http_get::<IceConfig>(BackendEndpoint::IceConfig, None)
+            .await
+        {
+            Ok(config) => {
+                log::info!(
+                    "Received ICE config from server, expires at {}",
+                    config.expires_at.unwrap_or_default()
+                );
+                self.set_ice_config(config);
+            }
+            Err(err) => {
+                log::warn!("Failed to refresh ICE config, keeping last known one: {err:?}");
+            }
+        }
+    }
 }
 
 impl AppStateInner {
@@ -301,13 +713,58 @@ impl AppStateInner {
                     &client_info.frequency,
                 );
 
-                app.emit("signaling:connected", client_info).ok();
+                app.emit("signaling:connected", &client_info).ok();
+
+                let state = app.state::<AppState>();
+                let mut state = state.lock().await;
+                state.frequency_peers.set_own(&client_info);
+                state.emit_frequency_peers(app);
+                state.rejoin_call(app).await;
+                state.start_pending_call(app).await;
             }
             SignalingEvent::Message(msg) => Self::handle_signaling_message(msg, app).await,
+            SignalingEvent::Disconnected(cause) => {
+                log::debug!("Disconnected from signaling server: {cause:?}");
+                if let Some(update) = IncompatibleProtocolUpdate::from_disconnect_cause(&cause) {
+                    log::warn!("Signaling server requires a client update: {update:?}");
+                    app.emit("update:incompatible-protocol", update).ok();
+                }
+                app.emit(
+                    "signaling:disconnect-reason",
+                    format_disconnect_cause(&cause),
+                )
+                .ok();
+            }
+            SignalingEvent::Reconnecting {
+                attempt,
+                max_attempts,
+            } => {
+                #[derive(Clone, Serialize)]
+                #[serde(rename_all = "camelCase")]
+                struct ReconnectAttempt {
+                    attempt: u8,
+                    max_attempts: u8,
+                }
+
+                log::debug!("Reconnecting to signaling server, attempt {attempt}/{max_attempts}");
+                app.emit(
+                    "signaling:reconnect-attempt",
+                    ReconnectAttempt {
+                        attempt,
+                        max_attempts,
+                    },
+                )
+                .ok();
+            }
             SignalingEvent::Error(error) => {
                 if error.is_fatal() {
                     let state = app.state::<AppState>();
                     let mut state = state.lock().await;
+                    if error.can_reconnect() {
+                        state.remember_call_for_rejoin();
+                    } else {
+                        state.pending_rejoin = None;
+                    }
                     state.handle_signaling_connection_closed(app).await;
 
                     if error.can_reconnect() {
@@ -324,87 +781,96 @@ impl AppStateInner {
     async fn handle_signaling_message(msg: SignalingMessage, app: &AppHandle) {
         match msg {
             SignalingMessage::CallInvite { peer_id } => {
-                {
-                    if app
-                        .state::<AppState>()
-                        .lock()
-                        .await
-                        .config
-                        .client
-                        .ignored
-                        .contains(&peer_id)
-                    {
-                        log::trace!("Ignoring call invite from {peer_id}");
-                        return;
-                    }
-                }
-                log::trace!("Call invite received from {peer_id}");
-
                 let state = app.state::<AppState>();
                 let mut state = state.lock().await;
 
+                let action = IncomingCallAction::decide(
+                    state.config.client.ignored.contains(&peer_id),
+                    state.config.client.auto_answer.contains(&peer_id),
+                    state.do_not_disturb,
+                    state.incoming_call_peer_ids_len(),
+                    state.config.client.max_incoming_calls,
+                    state.active_call_peer_id().is_some()
+                        || state.outgoing_call_peer_id().is_some(),
+                );
+                if action == IncomingCallAction::Ignore {
+                    log::trace!("Ignoring call invite from {peer_id}");
+                    return;
+                }
+                log::trace!("Call invite received from {peer_id}");
+
                 state.add_call_to_call_list(app, &peer_id, true);
 
-                if state.incoming_call_peer_ids_len() >= INCOMING_CALLS_LIMIT {
-                    if let Err(err) = state
-                        .send_signaling_message(SignalingMessage::CallReject {
-                            peer_id: peer_id.clone(),
-                        })
-                        .await
-                    {
-                        log::warn!("Failed to reject call invite: {err:?}");
+                match action {
+                    IncomingCallAction::Reject(reason) => {
+                        state.call_history.record_rejected(&peer_id);
+                        let do_not_disturb = reason == Some(CallRejectReason::DoNotDisturb);
+                        if let Err(err) = state
+                            .send_signaling_message(SignalingMessage::CallReject {
+                                peer_id: peer_id.clone(),
+                                reason,
+                            })
+                            .await
+                        {
+                            log::warn!("Failed to reject call invite: {err:?}");
+                        }
+
+                        if do_not_disturb {
+                            log::debug!(
+                                "Rejected call invite from {peer_id}, do-not-disturb enabled"
+                            );
+                            app.emit("signaling:dnd-rejected", &peer_id).ok();
+                        }
                     }
-                    return;
-                }
+                    IncomingCallAction::AutoAnswer => {
+                        log::debug!("Auto-answering call invite from {peer_id}");
+                        state.call_history.start(&peer_id, CallDirection::Incoming);
+                        state.add_incoming_call_peer_id(&peer_id);
+                        app.emit("signaling:call-invite", &peer_id).ok();
 
-                state.add_incoming_call_peer_id(&peer_id);
-                app.emit("signaling:call-invite", &peer_id).ok();
+                        state
+                            .audio_manager
+                            .read()
+                            .restart(SourceType::RingbackOneshot);
+
+                        match state.accept_call(app, Some(peer_id.clone())).await {
+                            Ok(_) => {
+                                app.emit("signaling:auto-answered", &peer_id).ok();
+                            }
+                            Err(err) => {
+                                log::warn!("Failed to auto-answer call invite: {err:?}");
+                                state.audio_manager.read().restart(SourceType::Ring);
+                            }
+                        }
+                    }
+                    _ => {
+                        state.call_history.start(&peer_id, CallDirection::Incoming);
+                        state.add_incoming_call_peer_id(&peer_id);
+                        app.emit("signaling:call-invite", &peer_id).ok();
 
-                state.audio_manager.read().restart(SourceType::Ring);
+                        state.audio_manager.read().restart(SourceType::Ring);
+                    }
+                }
             }
             SignalingMessage::CallAccept { peer_id } => {
                 log::trace!("Call accept received from {peer_id}");
 
+                if Self::call_response_handled(app, &peer_id, |state| &mut state.call_accept_task)
+                    .await
+                {
+                    return;
+                }
+
+                log::warn!("Received call accept message for peer that is not set as outgoing");
                 let state = app.state::<AppState>();
                 let mut state = state.lock().await;
-
-                state.cancel_unanswered_call_timer(&peer_id);
-                let res = if state.remove_outgoing_call_peer_id(&peer_id) {
-                    app.emit("signaling:call-accept", peer_id.clone()).ok();
-
-                    match state.init_call(app.clone(), peer_id.clone(), None).await {
-                        Ok(sdp) => {
-                            state
-                                .send_signaling_message(SignalingMessage::CallOffer {
-                                    peer_id,
-                                    sdp,
-                                })
-                                .await
-                        }
-                        Err(err) => {
-                            log::warn!("Failed to start call: {err:?}");
-
-                            let reason: CallErrorReason = err.into();
-                            state.emit_call_error(app, peer_id.clone(), true, reason.clone());
-                            state
-                                .send_signaling_message(SignalingMessage::CallError {
-                                    peer_id,
-                                    reason,
-                                })
-                                .await
-                        }
-                    }
-                } else {
-                    log::warn!("Received call accept message for peer that is not set as outgoing");
-                    state
-                        .send_signaling_message(SignalingMessage::CallError {
-                            peer_id,
-                            reason: CallErrorReason::CallFailure,
-                        })
-                        .await
-                };
-
-                if let Err(err) = res {
+                if let Err(err) = state
+                    .send_signaling_message(SignalingMessage::CallError {
+                        peer_id,
+                        reason: CallErrorReason::CallFailure,
+                    })
+                    .await
+                {
                     log::warn!("Failed to send call message: {err:?}");
                 }
             }
@@ -412,19 +878,23 @@ impl AppStateInner {
                 log::trace!("Call offer received from {peer_id}");
 
                 let state = app.state::<AppState>();
-                let mut state = state.lock().await;
+                let state = state.lock().await;
 
-                let res = match state
-                    .init_call(app.clone(), peer_id.clone(), Some(sdp))
-                    .await
-                {
+                let (mut state, res) = Self::setup_call(app, state, &peer_id, Some(sdp)).await;
+                let res = match res {
                     Ok(sdp) => {
-                        state
-                            .send_signaling_message(SignalingMessage::CallAnswer { peer_id, sdp })
-                            .await
+                        let res = state
+                            .send_signaling_message(SignalingMessage::CallAnswer {
+                                peer_id: peer_id.clone(),
+                                sdp,
+                            })
+                            .await;
+                        state.start_ice_trickle(&peer_id);
+                        res
                     }
                     Err(err) => {
                         log::warn!("Failed to accept call offer: {err:?}");
+                        state.call_history.end(&peer_id, Some(CallOutcome::Failed));
                         let reason: CallErrorReason = err.into();
                         state.emit_call_error(app, peer_id.clone(), true, reason.clone());
                         state
@@ -437,38 +907,70 @@ impl AppStateInner {
                     log::warn!("Failed to send call message: {err:?}");
                 }
             }
-            SignalingMessage::CallAnswer { peer_id, sdp } => {
+            SignalingMessage::CallAnswer { peer_id, .. } => {
                 log::trace!("Call answer received from {peer_id}");
 
+                if Self::call_response_handled(app, &peer_id, |state| &mut state.call_answer_task)
+                    .await
+                {
+                    return;
+                }
+
+                log::warn!("Received call answer message for peer without a pending call offer");
                 let state = app.state::<AppState>();
                 let mut state = state.lock().await;
-
-                if let Err(err) = state.accept_call_answer(&peer_id, sdp).await {
-                    log::warn!("Failed to accept answer: {err:?}");
-                    if let Err(err) = state
-                        .send_signaling_message(SignalingMessage::CallError {
-                            peer_id,
-                            reason: err.into(),
-                        })
-                        .await
-                    {
-                        log::warn!("Failed to send call end message: {err:?}");
-                    }
-                };
+                if let Err(err) = state
+                    .send_signaling_message(SignalingMessage::CallError {
+                        peer_id,
+                        reason: CallErrorReason::CallFailure,
+                    })
+                    .await
+                {
+                    log::warn!("Failed to send call message: {err:?}");
+                }
             }
-            SignalingMessage::CallEnd { peer_id } => {
-                log::trace!("Call end received from {peer_id}");
+            SignalingMessage::CallEnd { peer_id, reason } => {
+                log::trace!("Call end received from {peer_id}, reason: {reason:?}");
 
                 let state = app.state::<AppState>();
                 let mut state = state.lock().await;
 
-                if !state.cleanup_call(&peer_id).await {
-                    log::debug!("Received call end message for peer that is not active");
+                if state.cleanup_call(&peer_id).await == CallCleanup::AlreadyEnded {
+                    log::trace!("Call with {peer_id} already ended, ignoring duplicate call end");
                 }
 
+                state.cancel_unanswered_call_timer(&peer_id);
+                state.remove_outgoing_call_peer_id(&peer_id);
                 state.remove_incoming_call_peer_id(&peer_id);
 
                 app.emit("signaling:call-end", &peer_id).ok();
+                if reason == Some(CallEndReason::MaxDurationReached) {
+                    app.emit("signaling:call-max-duration-reached", &peer_id)
+                        .ok();
+                }
+            }
+            SignalingMessage::CallDurationWarning {
+                peer_id,
+                remaining_secs,
+            } => {
+                #[derive(Clone, Serialize)]
+                #[serde(rename_all = "camelCase")]
+                struct CallDurationWarning {
+                    peer_id: String,
+                    remaining_secs: u64,
+                }
+
+                log::debug!(
+                    "Call with {peer_id} reaches maximum duration in {remaining_secs} seconds"
+                );
+                app.emit(
+                    "signaling:call-duration-warning",
+                    CallDurationWarning {
+                        peer_id,
+                        remaining_secs,
+                    },
+                )
+                .ok();
             }
             SignalingMessage::CallError { peer_id, reason } => {
                 log::trace!("Call error received from {peer_id}. Reason: {reason:?}");
@@ -476,8 +978,10 @@ impl AppStateInner {
                 let state = app.state::<AppState>();
                 let mut state = state.lock().await;
 
-                if !state.cleanup_call(&peer_id).await {
-                    log::debug!("Received call end message for peer that is not active");
+                state.call_history.end(&peer_id, Some(CallOutcome::Failed));
+
+                if state.cleanup_call(&peer_id).await == CallCleanup::AlreadyEnded {
+                    log::trace!("Call with {peer_id} already ended, ignoring call error");
                 }
 
                 state.remove_outgoing_call_peer_id(&peer_id);
@@ -485,19 +989,66 @@ impl AppStateInner {
 
                 state.emit_call_error(app, peer_id, false, reason);
             }
-            SignalingMessage::CallReject { peer_id } => {
-                log::trace!("Call reject received from {peer_id}");
+            SignalingMessage::CallReject { peer_id, reason } => {
+                log::trace!("Call reject received from {peer_id}, reason: {reason:?}");
 
                 let state = app.state::<AppState>();
                 let mut state = state.lock().await;
 
                 state.cancel_unanswered_call_timer(&peer_id);
                 if state.remove_outgoing_call_peer_id(&peer_id) {
+                    state
+                        .call_history
+                        .end(&peer_id, Some(CallOutcome::Rejected));
+                    state
+                        .audio_manager
+                        .read()
+                        .play_call_end_tone(CallOutcome::Rejected, &state.config.audio);
+                    let message = match reason {
+                        Some(CallRejectReason::DoNotDisturb) => Some(format!(
+                            "{peer_id} is not accepting calls right now (do not disturb)."
+                        )),
+                        Some(CallRejectReason::NotPermitted) => Some(format!(
+                            "Calls to {peer_id} are not permitted by the server's call policy."
+                        )),
+                        None => None,
+                    };
+                    if let Some(message) = message {
+                        app.emit::<FrontendError>(
+                            "error",
+                            FrontendError::new("Call rejected", message)
+                                .non_critical()
+                                .timeout(5000),
+                        )
+                        .ok();
+                    }
                     app.emit("signaling:call-reject", peer_id).ok();
                 } else {
                     log::warn!("Received call reject message for peer that is not set as outgoing");
                 }
             }
+            SignalingMessage::CallBusy { peer_id } => {
+                log::trace!("Call busy received for {peer_id}");
+
+                let state = app.state::<AppState>();
+                let mut state = state.lock().await;
+
+                state.cancel_unanswered_call_timer(&peer_id);
+                if state.remove_outgoing_call_peer_id(&peer_id) {
+                    state.call_history.end(&peer_id, Some(CallOutcome::Busy));
+
+                    let audio_manager = state.audio_manager.clone();
+                    audio_manager.read().restart(SourceType::Busy);
+                    tauri::async_runtime::spawn(async move {
+                        tokio::time::sleep(BUSY_TONE_DURATION).await;
+                        audio_manager.read().stop(SourceType::Busy);
+                    });
+
+                    app.emit("signaling:call-busy", peer_id).ok();
+                } else {
+                    log::warn!("Received call busy message for peer that is not set as outgoing");
+                }
+            }
             SignalingMessage::CallIceCandidate { peer_id, candidate } => {
                 log::trace!("ICE candidate received from {peer_id}");
 
@@ -506,12 +1057,22 @@ impl AppStateInner {
 
                 state.set_remote_ice_candidate(&peer_id, candidate).await;
             }
+            SignalingMessage::CallIceEndOfCandidates { peer_id } => {
+                log::trace!("ICE end of candidates received from {peer_id}");
+
+                let state = app.state::<AppState>();
+                let state = state.lock().await;
+
+                state.set_remote_end_of_candidates(&peer_id).await;
+            }
             SignalingMessage::PeerNotFound { peer_id } => {
                 log::trace!("Received peer not found: {peer_id}");
 
                 let state = app.state::<AppState>();
                 let mut state = state.lock().await;
 
+                state.call_history.end(&peer_id, Some(CallOutcome::Failed));
+
                 // Stop any active webrtc call
                 state.cleanup_call(&peer_id).await;
 
@@ -526,6 +1087,14 @@ impl AppStateInner {
             SignalingMessage::ClientConnected { client } => {
                 log::trace!("Client connected: {client:?}");
 
+                let state = app.state::<AppState>();
+                let mut state = state.lock().await;
+                state
+                    .call_history
+                    .set_display_name(client.id.clone(), client.display_name.clone());
+                state.frequency_peers.insert(&client);
+                state.emit_frequency_peers(app);
+
                 app.emit("signaling:client-connected", client).ok();
             }
             SignalingMessage::ClientDisconnected { id } => {
@@ -543,16 +1112,40 @@ impl AppStateInner {
 
                 state.cancel_unanswered_call_timer(&id);
 
+                state.frequency_peers.remove(&id);
+                state.emit_frequency_peers(app);
+
                 app.emit("signaling:client-disconnected", id).ok();
             }
             SignalingMessage::ClientList { clients } => {
                 log::trace!("Received client list: {} clients connected", clients.len());
 
+                let state = app.state::<AppState>();
+                let mut state = state.lock().await;
+                state.call_history.set_display_names(
+                    clients
+                        .iter()
+                        .map(|c| (c.id.clone(), c.display_name.clone())),
+                );
+                state.frequency_peers.set_clients(&clients);
+                state.emit_frequency_peers(app);
+
                 app.emit("signaling:client-list", clients).ok();
             }
-            SignalingMessage::ClientInfo { own, info } => {
+            SignalingMessage::ClientInfo { own, info, .. } => {
                 log::trace!("Received client info. Own: {own}, info: {info:?}");
 
+                {
+                    let state = app.state::<AppState>();
+                    let mut state = state.lock().await;
+                    if own {
+                        state.frequency_peers.set_own(&info);
+                    } else {
+                        state.frequency_peers.insert(&info);
+                    }
+                    state.emit_frequency_peers(app);
+                }
+
                 let event = if own {
                     "signaling:connected"
                 } else {
@@ -593,9 +1186,9 @@ impl AppStateInner {
                     let state = app.state::<AppState>();
                     let mut state = state.lock().await;
 
-                    if !state.cleanup_call(&peer_id).await {
-                        log::debug!(
-                            "Received peer connection error message for peer that is not active"
+                    if state.cleanup_call(&peer_id).await == CallCleanup::AlreadyEnded {
+                        log::trace!(
+                            "Call with {peer_id} already ended, ignoring peer connection error"
                         );
                     }
 
@@ -645,7 +1238,268 @@ impl AppStateInner {
         }
     }
 
+    /// Waits for the [`CallResponseTask`] selected by `task` to handle the peer's response,
+    /// returning `false` if the response was not correlated with a call setup message sent to it.
+    async fn call_response_handled(
+        app: &AppHandle,
+        peer_id: &str,
+        task: fn(&mut AppStateInner) -> &mut Option<CallResponseTask>,
+    ) -> bool {
+        let task = {
+            let state = app.state::<AppState>();
+            let mut state = state.lock().await;
+            task(&mut state).take_if(|t| t.peer_id == peer_id)
+        };
+
+        match task {
+            Some(task) => task.handle.await.unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Handles the peer accepting the outgoing call invite by sending it the call offer.
+    async fn handle_call_accept(app: &AppHandle, peer_id: String) {
+        let state = app.state::<AppState>();
+        let mut state = state.lock().await;
+
+        state.cancel_unanswered_call_timer(&peer_id);
+        let res = if state.remove_outgoing_call_peer_id(&peer_id) {
+            app.emit("signaling:call-accept", peer_id.clone()).ok();
+
+            let (mut state, res) = Self::setup_call(app, state, &peer_id, None).await;
+            match res {
+                Ok(sdp) => {
+                    // Hang up if the accepted call does not connect, cancelled once connected
+                    state.start_unanswered_call_timer(app, &peer_id, UnansweredCall::Accepted);
+                    state.send_call_offer(app, peer_id, sdp);
+                    Ok(())
+                }
+                Err(err) => {
+                    log::warn!("Failed to start call: {err:?}");
+                    state.call_history.end(&peer_id, Some(CallOutcome::Failed));
+
+                    let reason: CallErrorReason = err.into();
+                    state.emit_call_error(app, peer_id.clone(), true, reason.clone());
+                    state
+                        .send_signaling_message(SignalingMessage::CallError { peer_id, reason })
+                        .await
+                }
+            }
+        } else {
+            log::warn!("Received call accept message for peer that is not set as outgoing");
+            state
+                .send_signaling_message(SignalingMessage::CallError {
+                    peer_id,
+                    reason: CallErrorReason::CallFailure,
+                })
+                .await
+        };
+
+        if let Err(err) = res {
+            log::warn!("Failed to send call message: {err:?}");
+        }
+    }
+
+    /// Ends the outgoing call after its invite could not be sent.
+    async fn handle_call_invite_failed(app: &AppHandle, peer_id: String, err: SignalingError) {
+        let state = app.state::<AppState>();
+        let mut state = state.lock().await;
+
+        state.cancel_unanswered_call_timer(&peer_id);
+        if !state.remove_outgoing_call_peer_id(&peer_id) {
+            return;
+        }
+        state.call_history.end(&peer_id, Some(CallOutcome::Failed));
+        state.emit_call_error(app, peer_id, true, CallErrorReason::SignalingFailure);
+
+        app.emit::<FrontendError>("error", Error::from(err).into())
+            .ok();
+    }
+
+    /// Sends the call offer to the peer and accepts its answer once received.
+    fn send_call_offer(&mut self, app: &AppHandle, peer_id: String, sdp: String) {
+        let timeout = self.config.client.auto_hangup_timeout();
+        let cancel = self.shutdown_token.child_token();
+        let handle = tauri::async_runtime::spawn({
+            let app = app.clone();
+            let client = self.signaling_client.clone();
+            let peer_id = peer_id.clone();
+            let cancel = cancel.clone();
+            async move {
+                let offer = SignalingMessage::CallOffer {
+                    peer_id: peer_id.clone(),
+                    sdp,
+                };
+                // Without an auto hangup timeout, the offer stays pending until the next call
+                let res = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => return false,
+                    res = client.send_and_await(
+                        offer,
+                        |m| matches!(m, SignalingMessage::CallAnswer { .. }),
+                        timeout.unwrap_or(Duration::MAX),
+                    ) => res,
+                };
+                match res {
+                    Ok(SignalingMessage::CallAnswer { sdp, .. }) => {
+                        Self::handle_call_answer(&app, peer_id, sdp).await;
+                        true
+                    }
+                    Ok(_) => false,
+                    Err(SignalingError::Timeout(_)) => {
+                        // Ended by the unanswered call timer
+                        log::debug!("Call offer to {peer_id} was not answered in time");
+                        false
+                    }
+                    Err(err) => {
+                        log::warn!("Failed to send call message: {err:?}");
+                        false
+                    }
+                }
+            }
+        });
+        if let Some(previous) = self.call_answer_task.replace(CallResponseTask {
+            peer_id,
+            cancel,
+            handle,
+        }) {
+            previous.cancel.cancel();
+        }
+    }
+
+    /// Accepts the peer's answer to the call offer and starts trickling ICE candidates to it.
+    async fn handle_call_answer(app: &AppHandle, peer_id: String, sdp: String) {
+        let state = app.state::<AppState>();
+        let mut state = state.lock().await;
+
+        if let Err(err) = state.accept_call_answer(&peer_id, sdp).await {
+            log::warn!("Failed to accept answer: {err:?}");
+            if let Err(err) = state
+                .send_signaling_message(SignalingMessage::CallError {
+                    peer_id,
+                    reason: err.into(),
+                })
+                .await
+            {
+                log::warn!("Failed to send call end message: {err:?}");
+            }
+            return;
+        }
+        state.start_ice_trickle(&peer_id);
+    }
+
+    /// Sets up the call with the peer, returning the SDP to send to it.
+    ///
+    /// The app state is released while negotiating the call, as waiting for the ICE gathering can
+    /// take a while. Signaling messages are still handled in order, as the next one is only
+    /// handled once this one has finished.
+    async fn setup_call<'a>(
+        app: &'a AppHandle,
+        mut state: MutexGuard<'a, AppStateInner>,
+        peer_id: &str,
+        offer_sdp: Option<String>,
+    ) -> (MutexGuard<'a, AppStateInner>, Result<String, Error>) {
+        let setup = match state.prepare_call(peer_id).await {
+            Ok(setup) => setup,
+            Err(err) => return (state, Err(err)),
+        };
+        drop(state);
+
+        let negotiated = setup.negotiate(offer_sdp).await;
+
+        let mut state = app.state::<AppState>().inner().lock().await;
+        let res = match negotiated {
+            Ok(sdp) => state
+                .init_call(app.clone(), peer_id.to_string(), setup)
+                .await
+                .map(|_| sdp),
+            Err(err) => {
+                state.abort_call_setup(peer_id, setup).await;
+                Err(err)
+            }
+        };
+        (state, res)
+    }
+
+    /// Records the active call to be rejoined after an automatic reconnect, if enabled.
+    fn remember_call_for_rejoin(&mut self) {
+        if self.config.client.reconnect_rejoin
+            && let Some(peer_id) = self.active_call_peer_id().cloned()
+        {
+            self.pending_rejoin = Some(PendingRejoin {
+                peer_id,
+                disconnected_at: Instant::now(),
+            });
+        }
+    }
+
+    /// Re-invites the peer of the call that was active before the connection was lost.
+    ///
+    /// If the peer has disconnected in the meantime, the server replies with
+    /// [`SignalingMessage::PeerNotFound`], which is handled like any other failed call attempt.
+    async fn rejoin_call(&mut self, app: &AppHandle) {
+        let Some(PendingRejoin {
+            peer_id,
+            disconnected_at,
+        }) = self.pending_rejoin.take()
+        else {
+            return;
+        };
+
+        if disconnected_at.elapsed() > REJOIN_GRACE_PERIOD {
+            log::info!("Not rejoining call with {peer_id}, reconnect took too long");
+            return;
+        }
+        log::info!("Rejoining call with {peer_id} after reconnect");
+
+        if let Err(err) = self.start_call(app, peer_id.clone()).await {
+            log::warn!("Failed to rejoin call with {peer_id}: {err:?}");
+            return;
+        }
+
+        app.emit("signaling:call-rejoin", peer_id).ok();
+    }
+
+    /// Starts the call requested via deep link while the signaling client was not logged in.
+    async fn start_pending_call(&mut self, app: &AppHandle) {
+        let Some(peer_id) = self.pending_call.take() else {
+            return;
+        };
+
+        if let Err(err) = self.start_deep_link_call(app, peer_id).await {
+            app.emit::<FrontendError>("error", err.into()).ok();
+        }
+    }
+
+    /// Emits the clients sharing the own primary frequency if they changed since the last update.
+    fn emit_frequency_peers(&mut self, app: &AppHandle) {
+        if let Some(peers) = self.frequency_peers.take_update() {
+            log::trace!("Clients on own frequency: {peers:?}");
+            app.emit("signaling:frequency-peers", peers).ok();
+        }
+    }
+
+    fn emit_session_stats(app: &AppHandle, stats: SessionStats) {
+        #[derive(Clone, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SessionStatsPayload {
+            uptime_secs: Option<u64>,
+            reconnects: u32,
+        }
+
+        app.emit(
+            "signaling:session-stats",
+            SessionStatsPayload {
+                uptime_secs: stats.uptime().map(|uptime| uptime.as_secs()),
+                reconnects: stats.reconnects,
+            },
+        )
+        .ok();
+    }
+
     async fn cleanup_signaling(&mut self, app: &AppHandle) {
+        self.call_history.end_all(None);
+        self.frequency_peers.clear();
         self.incoming_call_peer_ids.clear();
         self.outgoing_call_peer_id = None;
 
@@ -677,5 +1531,356 @@ impl AppStateInner {
             guard.cancel.cancel();
             guard.handle.abort();
         }
+        for task in [self.call_accept_task.take(), self.call_answer_task.take()]
+            .into_iter()
+            .flatten()
+        {
+            task.cancel.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClientConfig;
+    use futures_util::poll;
+    use std::cell::Cell;
+    use std::task::Poll;
+
+    const MAX_INCOMING_CALLS: usize = 5;
+
+    #[test]
+    fn vatsim_connection_retry_backs_off() {
+        let delays: Vec<Duration> = (1..=6)
+            .map(|retry| VATSIM_CONNECTION_RETRY.delay(retry))
+            .collect();
+        assert_eq!(
+            delays,
+            [5, 10, 20, 40, 60, 60].map(Duration::from_secs).to_vec()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn missing_vatsim_connection_is_retried() {
+        let attempts = Cell::new(0);
+        let mut retries = Vec::new();
+
+        let start = tokio::time::Instant::now();
+        let result = VATSIM_CONNECTION_RETRY
+            .run(
+                || {
+                    attempts.set(attempts.get() + 1);
+                    async {
+                        Err(SignalingError::LoginError(
+                            LoginFailureReason::NoActiveVatsimConnection,
+                        ))
+                    }
+                },
+                |retry, _| retries.push(retry),
+                &CancellationToken::new(),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(SignalingError::LoginError(
+                LoginFailureReason::NoActiveVatsimConnection
+            ))
+        ));
+        assert_eq!(attempts.get(), 7);
+        assert_eq!(retries, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            start.elapsed(),
+            (1..=6)
+                .map(|retry| VATSIM_CONNECTION_RETRY.delay(retry))
+                .sum::<Duration>()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn vatsim_connection_retry_stops_once_connected() {
+        let attempts = Cell::new(0);
+
+        let start = tokio::time::Instant::now();
+        let result = VATSIM_CONNECTION_RETRY
+            .run(
+                || {
+                    attempts.set(attempts.get() + 1);
+                    let attempt = attempts.get();
+                    async move {
+                        if attempt < 2 {
+                            Err(SignalingError::LoginError(
+                                LoginFailureReason::NoActiveVatsimConnection,
+                            ))
+                        } else {
+                            Ok(())
+                        }
+                    }
+                },
+                |_, _| {},
+                &CancellationToken::new(),
+            )
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(attempts.get(), 2);
+        assert_eq!(start.elapsed(), VATSIM_CONNECTION_RETRY.delay(1));
+    }
+
+    #[tokio::test]
+    async fn other_login_failures_are_not_retried() {
+        let attempts = Cell::new(0);
+        let mut retries = Vec::new();
+
+        let result = VATSIM_CONNECTION_RETRY
+            .run(
+                || {
+                    attempts.set(attempts.get() + 1);
+                    async {
+                        Err(SignalingError::LoginError(
+                            LoginFailureReason::InvalidCredentials,
+                        ))
+                    }
+                },
+                |retry, _| retries.push(retry),
+                &CancellationToken::new(),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(SignalingError::LoginError(
+                LoginFailureReason::InvalidCredentials
+            ))
+        ));
+        assert_eq!(attempts.get(), 1);
+        assert!(retries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelled_vatsim_connection_wait_stops_retrying() {
+        let attempts = Cell::new(0);
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let result = VATSIM_CONNECTION_RETRY
+            .run(
+                || {
+                    attempts.set(attempts.get() + 1);
+                    async {
+                        Err(SignalingError::LoginError(
+                            LoginFailureReason::NoActiveVatsimConnection,
+                        ))
+                    }
+                },
+                |_, _| {},
+                &cancel,
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[test]
+    fn incoming_call_delivered() {
+        assert_eq!(
+            IncomingCallAction::decide(false, false, false, 0, MAX_INCOMING_CALLS, false),
+            IncomingCallAction::Deliver
+        );
+        assert_eq!(
+            IncomingCallAction::decide(
+                false,
+                false,
+                false,
+                MAX_INCOMING_CALLS - 1,
+                MAX_INCOMING_CALLS,
+                false
+            ),
+            IncomingCallAction::Deliver
+        );
+    }
+
+    #[test]
+    fn incoming_call_rejected_while_do_not_disturb() {
+        assert_eq!(
+            IncomingCallAction::decide(false, false, true, 0, MAX_INCOMING_CALLS, false),
+            IncomingCallAction::Reject(Some(CallRejectReason::DoNotDisturb))
+        );
+        assert_eq!(
+            IncomingCallAction::decide(
+                false,
+                false,
+                true,
+                MAX_INCOMING_CALLS,
+                MAX_INCOMING_CALLS,
+                false
+            ),
+            IncomingCallAction::Reject(Some(CallRejectReason::DoNotDisturb))
+        );
+    }
+
+    #[test]
+    fn incoming_call_delivered_after_do_not_disturb_disabled() {
+        let invite = |do_not_disturb| {
+            IncomingCallAction::decide(false, false, do_not_disturb, 0, MAX_INCOMING_CALLS, false)
+        };
+
+        assert_ne!(invite(true), IncomingCallAction::Deliver);
+        assert_eq!(invite(false), IncomingCallAction::Deliver);
+    }
+
+    #[test]
+    fn incoming_call_from_ignored_peer() {
+        assert_eq!(
+            IncomingCallAction::decide(true, false, false, 0, MAX_INCOMING_CALLS, false),
+            IncomingCallAction::Ignore
+        );
+        assert_eq!(
+            IncomingCallAction::decide(true, false, true, 0, MAX_INCOMING_CALLS, false),
+            IncomingCallAction::Ignore
+        );
+    }
+
+    #[test]
+    fn incoming_call_limit_reached() {
+        assert_eq!(
+            IncomingCallAction::decide(
+                false,
+                false,
+                false,
+                MAX_INCOMING_CALLS,
+                MAX_INCOMING_CALLS,
+                false
+            ),
+            IncomingCallAction::Reject(None)
+        );
+    }
+
+    #[test]
+    fn incoming_call_auto_answered_from_allowlisted_peer() {
+        assert_eq!(
+            IncomingCallAction::decide(false, true, false, 0, MAX_INCOMING_CALLS, false),
+            IncomingCallAction::AutoAnswer
+        );
+        assert_eq!(
+            IncomingCallAction::decide(false, false, false, 0, MAX_INCOMING_CALLS, false),
+            IncomingCallAction::Deliver
+        );
+    }
+
+    #[test]
+    fn incoming_call_not_auto_answered_while_in_call() {
+        assert_eq!(
+            IncomingCallAction::decide(false, true, false, 0, MAX_INCOMING_CALLS, true),
+            IncomingCallAction::Deliver
+        );
+    }
+
+    #[test]
+    fn incoming_call_auto_answer_respects_limit() {
+        assert_eq!(
+            IncomingCallAction::decide(
+                false,
+                true,
+                false,
+                MAX_INCOMING_CALLS,
+                MAX_INCOMING_CALLS,
+                false
+            ),
+            IncomingCallAction::Reject(None)
+        );
+        assert_eq!(
+            IncomingCallAction::decide(
+                false,
+                true,
+                false,
+                MAX_INCOMING_CALLS - 1,
+                MAX_INCOMING_CALLS,
+                false
+            ),
+            IncomingCallAction::AutoAnswer
+        );
+    }
+
+    #[test]
+    fn incoming_call_auto_answer_overridden() {
+        assert_eq!(
+            IncomingCallAction::decide(true, true, false, 0, MAX_INCOMING_CALLS, false),
+            IncomingCallAction::Ignore
+        );
+        assert_eq!(
+            IncomingCallAction::decide(false, true, true, 0, MAX_INCOMING_CALLS, false),
+            IncomingCallAction::Reject(Some(CallRejectReason::DoNotDisturb))
+        );
+    }
+
+    #[test]
+    fn incoming_call_limit_configurable() {
+        assert_eq!(
+            IncomingCallAction::decide(false, false, false, 1, 1, false),
+            IncomingCallAction::Reject(None)
+        );
+        assert_eq!(
+            IncomingCallAction::decide(false, false, false, 1, 2, false),
+            IncomingCallAction::Deliver
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unanswered_invite_times_out_at_invite_timeout() {
+        let config = ClientConfig {
+            outgoing_invite_timeout_seconds: 1,
+            auto_hangup_seconds: 60,
+            ..Default::default()
+        };
+        let timeout = UnansweredCall::Invite.timeout(&config).unwrap();
+        assert!(timeout < UnansweredCall::Accepted.timeout(&config).unwrap());
+
+        let cancel = CancellationToken::new();
+        let timer = UnansweredCall::Invite.expired(timeout, &cancel);
+        tokio::pin!(timer);
+        assert!(poll!(&mut timer).is_pending());
+
+        tokio::time::advance(timeout - Duration::from_millis(1)).await;
+        assert!(poll!(&mut timer).is_pending());
+
+        tokio::time::advance(Duration::from_millis(1)).await;
+        assert_eq!(
+            poll!(&mut timer),
+            Poll::Ready(Some(CallErrorReason::InviteTimeout))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn accepted_call_times_out_with_auto_hangup() {
+        let timeout = ClientConfig::default().auto_hangup_timeout().unwrap();
+
+        let cancel = CancellationToken::new();
+        let timer = UnansweredCall::Accepted.expired(timeout, &cancel);
+        tokio::pin!(timer);
+        assert!(poll!(&mut timer).is_pending());
+
+        tokio::time::advance(timeout - Duration::from_millis(1)).await;
+        assert!(poll!(&mut timer).is_pending());
+
+        tokio::time::advance(Duration::from_millis(1)).await;
+        assert_eq!(
+            poll!(&mut timer),
+            Poll::Ready(Some(CallErrorReason::AutoHangup))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelled_unanswered_call_timer_does_not_expire() {
+        let cancel = CancellationToken::new();
+        let timer = UnansweredCall::Invite.expired(Duration::from_secs(60), &cancel);
+        tokio::pin!(timer);
+        assert!(poll!(&mut timer).is_pending());
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        cancel.cancel();
+        assert_eq!(poll!(&mut timer), Poll::Ready(None));
     }
 }
@@ -1,19 +1,36 @@
 use crate::app::state::signaling::AppStateSignalingExt;
 use crate::app::state::{AppState, AppStateInner, sealed};
-use crate::config::{ENCODED_AUDIO_FRAME_BUFFER_SIZE, ICE_CONFIG_EXPIRY_LEEWAY};
+use crate::audio::PeerSpeaking;
+use crate::config::{
+    CALL_OUTPUT_DRAIN_WINDOW, ClientConfig, ENCODED_AUDIO_FRAME_BUFFER_SIZE,
+    ICE_CONFIG_EXPIRY_LEEWAY,
+};
 use crate::error::{CallError, Error};
+use crate::signaling::history::CallOutcome;
 use anyhow::Context;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
-use std::time::UNIX_EPOCH;
+use std::time::{Duration, UNIX_EPOCH};
 use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use vacs_signaling::protocol::http::webrtc::IceConfig;
 use vacs_signaling::protocol::ws::{CallErrorReason, SignalingMessage};
+use vacs_webrtc::config::OpusFmtp;
 use vacs_webrtc::error::WebrtcError;
-use vacs_webrtc::{Peer, PeerConnectionState, PeerEvent};
+use vacs_webrtc::{DtlsTransportState, IceConnectionState, Peer, PeerConnectionState, PeerEvent};
+
+/// Payload of the `webrtc:ice-state` and `webrtc:dtls-state` events.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransportState<'a> {
+    peer_id: &'a str,
+    state: String,
+}
 
 #[derive(Debug)]
 pub struct UnansweredCallGuard {
@@ -22,6 +39,53 @@ pub struct UnansweredCallGuard {
     pub handle: JoinHandle<()>,
 }
 
+/// Call guarded by the unanswered call timer, ended with its own error reason once it expires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnansweredCall {
+    /// Outgoing call invite the peer neither accepted nor rejected yet.
+    Invite,
+    /// Accepted outgoing call whose connection has not been established yet.
+    Accepted,
+}
+
+impl UnansweredCall {
+    /// Timeout configured for the call, `None` if the timer is disabled.
+    pub fn timeout(self, config: &ClientConfig) -> Option<Duration> {
+        match self {
+            UnansweredCall::Invite => config.outgoing_invite_timeout(),
+            UnansweredCall::Accepted => config.auto_hangup_timeout(),
+        }
+    }
+
+    /// Waits for the timeout to pass, returning the reason to end the call with unless the timer
+    /// is cancelled before.
+    pub async fn expired(
+        self,
+        timeout: Duration,
+        cancel: &CancellationToken,
+    ) -> Option<CallErrorReason> {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => None,
+            _ = tokio::time::sleep(timeout) => Some(match self {
+                UnansweredCall::Invite => CallErrorReason::InviteTimeout,
+                UnansweredCall::Accepted => CallErrorReason::AutoHangup,
+            }),
+        }
+    }
+}
+
+/// Outcome of [`AppStateWebrtcExt::cleanup_call`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallCleanup {
+    /// The active or held call with the peer has been closed.
+    Closed,
+    /// No call with the peer exists (anymore), e.g. since both sides ended it simultaneously.
+    AlreadyEnded,
+    /// The call has been removed, but closing the peer connection failed.
+    Failed,
+}
+
 pub struct Call {
     pub(super) peer_id: String,
     peer: Peer,
@@ -35,16 +99,52 @@ impl Debug for Call {
     }
 }
 
+/// Peer of a call being set up. Negotiated via [`CallSetup::negotiate`] without holding the app
+/// state, as waiting for the ICE gathering can take a while, before being stored as the active
+/// call via [`AppStateWebrtcExt::init_call`].
+pub struct CallSetup {
+    peer: Peer,
+    events_rx: broadcast::Receiver<PeerEvent>,
+}
+
+impl CallSetup {
+    /// Creates the SDP offer or, if an offer has been received, the SDP answer to send to the peer.
+    pub async fn negotiate(&self, offer_sdp: Option<String>) -> Result<String, Error> {
+        let sdp = if let Some(sdp) = offer_sdp {
+            self.peer
+                .accept_offer(sdp)
+                .await
+                .context("Failed to accept WebRTC offer")?
+        } else {
+            self.peer
+                .create_offer()
+                .await
+                .context("Failed to create WebRTC offer")?
+        };
+        Ok(sdp)
+    }
+
+    async fn close(mut self) {
+        if let Err(err) = self.peer.close().await {
+            log::warn!("Failed to close peer of call setup: {err:?}");
+        }
+    }
+}
+
 pub trait AppStateWebrtcExt: sealed::Sealed {
+    async fn prepare_call(&mut self, peer_id: &str) -> Result<CallSetup, Error>;
     async fn init_call(
         &mut self,
         app: AppHandle,
         peer_id: String,
-        offer_sdp: Option<String>,
-    ) -> Result<String, Error>;
+        setup: CallSetup,
+    ) -> Result<(), Error>;
+    async fn abort_call_setup(&mut self, peer_id: &str, setup: CallSetup);
+    fn start_ice_trickle(&self, peer_id: &str);
     async fn accept_call_answer(&self, peer_id: &str, answer_sdp: String) -> Result<(), Error>;
     async fn set_remote_ice_candidate(&self, peer_id: &str, candidate: String);
-    async fn cleanup_call(&mut self, peer_id: &str) -> bool;
+    async fn set_remote_end_of_candidates(&self, peer_id: &str);
+    async fn cleanup_call(&mut self, peer_id: &str) -> CallCleanup;
     fn emit_call_error(
         &self,
         app: &AppHandle,
@@ -59,30 +159,46 @@ pub trait AppStateWebrtcExt: sealed::Sealed {
 }
 
 impl AppStateWebrtcExt for AppStateInner {
+    async fn prepare_call(&mut self, peer_id: &str) -> Result<CallSetup, Error> {
+        if self.active_call.is_some() || self.call_setup_peer_id.is_some() {
+            return Err(WebrtcError::CallActive.into());
+        }
+
+        let opus_fmtp = OpusFmtp {
+            use_dtx: self.config.audio.opus_dtx,
+            ..Default::default()
+        };
+        let (peer, events_rx) = Peer::new(
+            self.config.ice.clone(),
+            opus_fmtp,
+            self.config.client.media_dscp,
+            self.config.client.ice_gathering_timeout(),
+            self.config.client.call_codec,
+        )
+        .await
+        .context("Failed to create WebRTC peer")?;
+
+        self.call_setup_peer_id = Some(peer_id.to_string());
+        Ok(CallSetup { peer, events_rx })
+    }
+
     async fn init_call(
         &mut self,
         app: AppHandle,
         peer_id: String,
-        offer_sdp: Option<String>,
-    ) -> Result<String, Error> {
-        if self.active_call.is_some() {
-            return Err(WebrtcError::CallActive.into());
+        setup: CallSetup,
+    ) -> Result<(), Error> {
+        // The call might have been ended while it was negotiated
+        if self.call_setup_peer_id.as_deref() != Some(peer_id.as_str()) {
+            setup.close().await;
+            return Err(WebrtcError::NoCallActive.into());
         }
+        self.call_setup_peer_id = None;
 
-        let (peer, mut events_rx) = Peer::new(self.config.ice.clone())
-            .await
-            .context("Failed to create WebRTC peer")?;
-
-        let sdp = if let Some(sdp) = offer_sdp {
-            peer.accept_offer(sdp)
-                .await
-                .context("Failed to accept WebRTC offer")?
-        } else {
-            peer.create_offer()
-                .await
-                .context("Failed to create WebRTC offer")?
-        };
-
+        let CallSetup {
+            peer,
+            mut events_rx,
+        } = setup;
         let peer_id_clone = peer_id.clone();
 
         tauri::async_runtime::spawn(async move {
@@ -99,6 +215,9 @@ impl AppStateWebrtcExt for AppStateInner {
                                     state.on_peer_connected(&app, &peer_id_clone).await
                                 {
                                     let reason: CallErrorReason = err.into();
+                                    state
+                                        .call_history
+                                        .end(&peer_id_clone, Some(CallOutcome::Failed));
                                     state.cleanup_call(&peer_id_clone).await;
                                     if let Err(err) = state
                                         .send_signaling_message(SignalingMessage::CallError {
@@ -139,6 +258,9 @@ impl AppStateWebrtcExt for AppStateInner {
 
                                 let app_state = app.state::<AppState>();
                                 let mut state = app_state.lock().await;
+                                state
+                                    .call_history
+                                    .end(&peer_id_clone, Some(CallOutcome::Failed));
                                 state.cleanup_call(&peer_id_clone).await;
 
                                 state.emit_call_error(
@@ -174,6 +296,52 @@ impl AppStateWebrtcExt for AppStateInner {
                                 log::warn!("Failed to send ICE candidate: {err:?}");
                             }
                         }
+                        PeerEvent::IceConnectionStateChanged(state) => {
+                            match state {
+                                IceConnectionState::Failed => {
+                                    log::warn!(
+                                        "ICE failed, no connection to peer could be established"
+                                    );
+                                }
+                                state => log::debug!("Received ICE connection state: {state:?}"),
+                            }
+                            app.emit(
+                                "webrtc:ice-state",
+                                TransportState {
+                                    peer_id: &peer_id_clone,
+                                    state: state.to_string(),
+                                },
+                            )
+                            .ok();
+                        }
+                        PeerEvent::DtlsStateChanged(state) => {
+                            match state {
+                                DtlsTransportState::Failed => {
+                                    log::warn!("DTLS handshake with peer failed");
+                                }
+                                state => log::debug!("Received DTLS transport state: {state:?}"),
+                            }
+                            app.emit(
+                                "webrtc:dtls-state",
+                                TransportState {
+                                    peer_id: &peer_id_clone,
+                                    state: state.to_string(),
+                                },
+                            )
+                            .ok();
+                        }
+                        PeerEvent::IceGatheringComplete => {
+                            let app_state = app.state::<AppState>();
+                            let mut state = app_state.lock().await;
+                            if let Err(err) = state
+                                .send_signaling_message(SignalingMessage::CallIceEndOfCandidates {
+                                    peer_id: peer_id_clone.clone(),
+                                })
+                                .await
+                            {
+                                log::warn!("Failed to send ICE end of candidates: {err:?}");
+                            }
+                        }
                         PeerEvent::Error(err) => {
                             log::warn!("Received error peer event: {err}");
                         }
@@ -190,9 +358,25 @@ impl AppStateWebrtcExt for AppStateInner {
             log::trace!("WebRTC events task finished");
         });
 
+        self.call_history.answer(&peer_id);
         self.active_call = Some(Call { peer_id, peer });
 
-        Ok(sdp)
+        Ok(())
+    }
+
+    async fn abort_call_setup(&mut self, peer_id: &str, setup: CallSetup) {
+        if self.call_setup_peer_id.as_deref() == Some(peer_id) {
+            self.call_setup_peer_id = None;
+        }
+        setup.close().await;
+    }
+
+    fn start_ice_trickle(&self, peer_id: &str) {
+        if let Some(call) = &self.active_call
+            && call.peer_id == peer_id
+        {
+            call.peer.start_trickle();
+        }
     }
 
     async fn accept_call_answer(&self, peer_id: &str, answer_sdp: String) -> Result<(), Error> {
@@ -226,37 +410,60 @@ impl AppStateWebrtcExt for AppStateInner {
         }
     }
 
-    async fn cleanup_call(&mut self, peer_id: &str) -> bool {
+    async fn set_remote_end_of_candidates(&self, peer_id: &str) {
+        let res = if let Some(call) = &self.active_call
+            && call.peer_id == peer_id
+        {
+            call.peer.end_remote_ice_candidates().await
+        } else if let Some(call) = self.held_calls.get(peer_id) {
+            call.peer.end_remote_ice_candidates().await
+        } else {
+            Err(anyhow::anyhow!("Unknown peer {peer_id}").into())
+        };
+
+        if let Err(err) = res {
+            log::warn!("Failed to signal end of remote ICE candidates: {err:?}");
+        }
+    }
+
+    async fn cleanup_call(&mut self, peer_id: &str) -> CallCleanup {
         log::debug!(
             "Cleaning up call with peer {peer_id} (active: {:?})",
             self.active_call.as_ref()
         );
-        let res = if let Some(call) = &mut self.active_call
-            && call.peer_id == peer_id
-        {
-            {
-                let mut audio_manager = self.audio_manager.write();
-                audio_manager.detach_call_output();
-                audio_manager.detach_input_device();
-            }
+        self.call_history.end(peer_id, None);
+        if self.call_setup_peer_id.as_deref() == Some(peer_id) {
+            self.call_setup_peer_id = None;
+        }
 
-            self.keybind_engine.read().await.set_call_active(false);
+        let mut call = match take_call(&mut self.active_call, &mut self.held_calls, peer_id) {
+            Some(TakenCall::Active(call)) => {
+                {
+                    let mut audio_manager = self.audio_manager.write();
+                    if self.config.audio.drain_on_close {
+                        audio_manager.drain_call_output(CALL_OUTPUT_DRAIN_WINDOW);
+                    } else {
+                        audio_manager.detach_call_output();
+                    }
+                    audio_manager.detach_input_device();
+                }
 
-            let result = call.peer.close().await;
-            self.active_call = None;
-            result
-        } else if let Some(mut call) = self.held_calls.remove(peer_id) {
-            call.peer.close().await
-        } else {
-            Err(anyhow::anyhow!("Unknown peer {peer_id}").into())
+                self.keybind_engine.read().await.set_call_active(false);
+                call
+            }
+            Some(TakenCall::Held(call)) => call,
+            None => {
+                log::trace!("No call with peer {peer_id} to clean up, already ended");
+                return CallCleanup::AlreadyEnded;
+            }
         };
 
-        if let Err(err) = &res {
+        if let Err(err) = &call.peer.close().await {
             log::warn!("Failed to cleanup call: {err:?}");
-            return false;
+            return CallCleanup::Failed;
         }
 
-        true
+        CallCleanup::Closed
     }
 
     fn emit_call_error(
@@ -283,6 +490,7 @@ impl AppStateWebrtcExt for AppStateInner {
 
     fn set_ice_config(&mut self, config: IceConfig) {
         self.config.ice = config;
+        self.ice_config_invalidated = false;
     }
 
     fn is_ice_config_expired(&self) -> bool {
@@ -295,6 +503,11 @@ impl AppStateWebrtcExt for AppStateInner {
             None => return false,
         };
 
+        if self.ice_config_invalidated {
+            log::debug!("ICE config has been invalidated by a network change");
+            return true;
+        }
+
         let now = UNIX_EPOCH.elapsed().unwrap_or_default().as_secs();
         if now >= expires_at.saturating_sub(ICE_CONFIG_EXPIRY_LEEWAY.as_secs()) {
             log::debug!(
@@ -314,48 +527,34 @@ impl AppStateWebrtcExt for AppStateInner {
     }
 }
 
-impl AppStateInner {
-    async fn on_peer_connected(&mut self, app: &AppHandle, peer_id: &str) -> Result<(), Error> {
-        if let Some(call) = &mut self.active_call
-            && call.peer_id == peer_id
-        {
-            let (output_tx, output_rx) = mpsc::channel(ENCODED_AUDIO_FRAME_BUFFER_SIZE);
-            let (input_tx, input_rx) = mpsc::channel(ENCODED_AUDIO_FRAME_BUFFER_SIZE);
+/// Call removed by [`take_call`].
+#[derive(Debug)]
+enum TakenCall {
+    Active(Call),
+    Held(Call),
+}
 
-            log::debug!("Starting peer {peer_id} in WebRTC manager");
-            if let Err(err) = call.peer.start(input_rx, output_tx) {
-                log::warn!("Failed to start peer in WebRTC manager: {err:?}");
-                return Err(err.into());
-            }
+/// Removes the active or held call with the peer, `None` if no call with the peer exists (anymore).
+fn take_call(
+    active_call: &mut Option<Call>,
+    held_calls: &mut HashMap<String, Call>,
+    peer_id: &str,
+) -> Option<TakenCall> {
+    if active_call
+        .as_ref()
+        .is_some_and(|call| call.peer_id == peer_id)
+    {
+        return active_call.take().map(TakenCall::Active);
+    }
+    held_calls.remove(peer_id).map(TakenCall::Held)
+}
 
-            let attach_muted = {
-                let keybind_engine = self.keybind_engine.read().await;
-                keybind_engine.set_call_active(true);
-                keybind_engine.should_attach_input_muted()
-            };
-
-            let audio_config = self.config.audio.clone();
-            let mut audio_manager = self.audio_manager.write();
-            log::debug!("Attaching call to audio manager");
-            if let Err(err) = audio_manager.attach_call_output(
-                output_rx,
-                audio_config.output_device_volume,
-                audio_config.output_device_volume_amp,
-            ) {
-                log::warn!("Failed to attach call to audio manager: {err:?}");
-                return Err(err);
-            }
+impl AppStateInner {
+    async fn on_peer_connected(&mut self, app: &AppHandle, peer_id: &str) -> Result<(), Error> {
+        self.cancel_unanswered_call_timer(peer_id);
 
-            log::debug!("Attaching input device to audio manager");
-            if let Err(err) = audio_manager.attach_input_device(
-                app.clone(),
-                &audio_config,
-                input_tx,
-                attach_muted,
-            ) {
-                log::warn!("Failed to attach input device to audio manager: {err:?}");
-                return Err(err);
-            }
+        if self.active_call_peer_id().is_some_and(|id| id == peer_id) {
+            self.attach_call_audio(app, peer_id).await?;
 
             log::info!("Successfully established call to peer");
             app.emit("webrtc:call-connected", peer_id).ok();
@@ -370,4 +569,155 @@ impl AppStateInner {
         }
         Ok(())
     }
+
+    /// Pauses the peer of the active call after its audio has been detached, returning its peer
+    /// ID if there was an active call. Resume it via [`Self::attach_call_audio`].
+    pub(super) fn pause_active_call(&mut self) -> Option<String> {
+        let call = self.active_call.as_mut()?;
+        call.peer.pause();
+        Some(call.peer_id.clone())
+    }
+
+    /// Starts the peer of the active call and attaches its audio to the output and input devices.
+    pub(super) async fn attach_call_audio(
+        &mut self,
+        app: &AppHandle,
+        peer_id: &str,
+    ) -> Result<(), Error> {
+        let Some(call) = &mut self.active_call else {
+            return Ok(());
+        };
+
+        let (output_tx, output_rx) = mpsc::channel(ENCODED_AUDIO_FRAME_BUFFER_SIZE);
+        let (input_tx, input_rx) = mpsc::channel(ENCODED_AUDIO_FRAME_BUFFER_SIZE);
+
+        log::debug!("Starting peer {peer_id} in WebRTC manager");
+        if let Err(err) = call.peer.start(input_rx, output_tx) {
+            log::warn!("Failed to start peer in WebRTC manager: {err:?}");
+            return Err(err.into());
+        }
+
+        let attach_muted = {
+            let keybind_engine = self.keybind_engine.read().await;
+            keybind_engine.set_call_active(true);
+            keybind_engine.should_attach_input_muted()
+        };
+
+        // Emitted from the decoder task of this peer's audio source, so every peer gets its own
+        // speaking indication.
+        let on_speaking = {
+            let app = app.clone();
+            let peer_id = peer_id.to_string();
+            Box::new(move |speaking| {
+                app.emit(
+                    "call:peer-speaking",
+                    PeerSpeaking {
+                        peer_id: peer_id.clone(),
+                        speaking,
+                    },
+                )
+                .ok();
+            })
+        };
+
+        let audio_config = self.config.audio.clone();
+        let mut audio_manager = self.audio_manager.write();
+        log::debug!("Attaching call to audio manager");
+        if let Err(err) = audio_manager.attach_call_output(
+            output_rx,
+            audio_config.output_device_volume,
+            self.config.client.peer_volume(peer_id),
+            audio_config.output_device_volume_amp,
+            audio_config.comfort_noise,
+            audio_config
+                .normalize_rx_loudness
+                .then_some(audio_config.rx_loudness_target_dbfs),
+            on_speaking,
+            audio_config.output_channel_map.as_deref(),
+        ) {
+            log::warn!("Failed to attach call to audio manager: {err:?}");
+            return Err(err);
+        }
+
+        log::debug!("Attaching input device to audio manager");
+        // The input stays unmuted for the whole call unless transmitting requires a key press
+        let warn_long_transmit = self.config.client.transmit_config.mode.is_push_to_talk();
+        if let Err(err) = audio_manager.attach_input_device(
+            app.clone(),
+            &audio_config,
+            input_tx,
+            attach_muted,
+            warn_long_transmit,
+        ) {
+            log::warn!("Failed to attach input device to audio manager: {err:?}");
+            return Err(err);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn call(peer_id: &str) -> Call {
+        let ice_config = IceConfig {
+            ice_servers: Vec::new(),
+            expires_at: None,
+        };
+        let (peer, _) = Peer::new(
+            ice_config,
+            OpusFmtp::default(),
+            None,
+            None,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        Call {
+            peer_id: peer_id.to_string(),
+            peer,
+        }
+    }
+
+    #[tokio::test]
+    async fn ending_call_twice_finds_it_already_ended() {
+        let mut active_call = Some(call("client1").await);
+        let mut held_calls = HashMap::from([("client2".to_string(), call("client2").await)]);
+
+        // Both sides ending the active call simultaneously
+        let Some(TakenCall::Active(mut ended)) =
+            take_call(&mut active_call, &mut held_calls, "client1")
+        else {
+            panic!("active call not taken");
+        };
+        ended.peer.close().await.unwrap();
+        assert!(take_call(&mut active_call, &mut held_calls, "client1").is_none());
+        assert!(active_call.is_none());
+        assert!(held_calls.contains_key("client2"));
+
+        let Some(TakenCall::Held(mut ended)) =
+            take_call(&mut active_call, &mut held_calls, "client2")
+        else {
+            panic!("held call not taken");
+        };
+        ended.peer.close().await.unwrap();
+        assert!(take_call(&mut active_call, &mut held_calls, "client2").is_none());
+        assert!(held_calls.is_empty());
+    }
+
+    #[tokio::test]
+    async fn ending_other_call_keeps_active_call() {
+        let mut active_call = Some(call("client1").await);
+        let mut held_calls = HashMap::new();
+
+        assert!(take_call(&mut active_call, &mut held_calls, "client2").is_none());
+        assert_eq!(
+            active_call.as_ref().map(|call| call.peer_id.as_str()),
+            Some("client1")
+        );
+
+        active_call.unwrap().peer.close().await.unwrap();
+    }
 }
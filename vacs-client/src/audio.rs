@@ -1,7 +1,35 @@
 use serde::{Deserialize, Serialize};
+use vacs_audio::device::DeviceType;
 
 pub(crate) mod commands;
+pub(crate) mod long_transmit;
 pub(crate) mod manager;
+pub(crate) mod sound_pack;
+
+/// Payload of the `call:peer-speaking` event, emitted whenever a peer starts or stops speaking.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerSpeaking {
+    pub peer_id: String,
+    pub speaking: bool,
+}
+
+/// Payload of the `call:peer-muted` event, emitted whenever a peer is muted or unmuted locally.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerMuted {
+    pub peer_id: String,
+    pub muted: bool,
+}
+
+/// Payload of the `audio:host-fallback` event, emitted once the frontend is ready if the
+/// preferred audio host was not available on startup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostFallback {
+    pub missing_host: String,
+    pub host: String,
+}
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,6 +47,24 @@ pub struct AudioDevices {
     all: Vec<String>,
 }
 
+/// Audio device selectable in the settings. Notification sounds (ring, ringback, clicks) can be
+/// played on a separate output device, falling back to the main output device if unset.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum AudioDeviceType {
+    Input,
+    Output,
+    Notification,
+}
+
+impl AudioDeviceType {
+    pub fn device_type(self) -> DeviceType {
+        match self {
+            AudioDeviceType::Input => DeviceType::Input,
+            AudioDeviceType::Output | AudioDeviceType::Notification => DeviceType::Output,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum VolumeType {
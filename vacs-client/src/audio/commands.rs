@@ -1,13 +1,15 @@
 use crate::app::state::AppState;
+use crate::app::state::audio::AppStateAudioExt;
 use crate::app::state::webrtc::AppStateWebrtcExt;
 use crate::audio::manager::{AudioManagerHandle, SourceType};
-use crate::audio::{AudioDevices, AudioHosts, AudioVolumes, VolumeType};
+use crate::audio::sound_pack::{SOUNDS_DIR_NAME, SoundPackInfo, list_sound_packs};
+use crate::audio::{AudioDeviceType, AudioDevices, AudioHosts, AudioVolumes, VolumeType};
 use crate::config::{AUDIO_SETTINGS_FILE_NAME, Persistable, PersistedAudioConfig};
 use crate::error::Error;
 use crate::keybinds::engine::KeybindEngineHandle;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, State};
-use vacs_audio::device::{DeviceSelector, DeviceType};
+use vacs_audio::device::{DeviceCapabilities, DeviceSelector, DeviceType};
 use vacs_audio::error::AudioError;
 
 #[tauri::command]
@@ -15,19 +17,20 @@ use vacs_audio::error::AudioError;
 pub async fn audio_get_hosts(app_state: State<'_, AppState>) -> Result<AudioHosts, Error> {
     log::info!("Getting audio hosts");
 
-    let mut selected = app_state
+    let hosts = DeviceSelector::all_host_names();
+
+    // Devices are opened on the default host while the preferred one is not available
+    let selected = app_state
         .lock()
         .await
         .config
         .audio
         .host_name
-        .clone()
-        .unwrap_or_default();
-    if selected.is_empty() {
-        selected = DeviceSelector::default_host_name();
-    }
-
-    let hosts = DeviceSelector::all_host_names();
+        .as_deref()
+        .filter(|name| !name.is_empty())
+        .and_then(|name| DeviceSelector::find_host_name(name, &hosts))
+        .map(str::to_string)
+        .unwrap_or_else(DeviceSelector::default_host_name);
 
     Ok(AudioHosts {
         selected,
@@ -58,9 +61,9 @@ pub async fn audio_set_host(
         let mut audio_config = state.config.audio.clone();
         audio_config.host_name = Some(host_name).filter(|x| !x.is_empty());
 
-        audio_manager
-            .write()
-            .switch_output_device(app.clone(), &audio_config, false)?;
+        let mut audio_manager = audio_manager.write();
+        audio_manager.switch_output_device(app.clone(), &audio_config, false)?;
+        audio_manager.switch_notification_output_device(app.clone(), &audio_config);
 
         state.config.audio = audio_config;
         state.config.audio.clone().into()
@@ -75,12 +78,36 @@ pub async fn audio_set_host(
     Ok(())
 }
 
+/// Lists the stream configs supported by all devices of the given type and the config picked
+/// for each of them.
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn audio_get_device_capabilities(
+    app_state: State<'_, AppState>,
+    device_type: AudioDeviceType,
+) -> Result<Vec<DeviceCapabilities>, Error> {
+    log::info!("Getting audio device capabilities (type: {device_type:?})");
+
+    let (host, hints) = {
+        let state = app_state.lock().await;
+        (
+            state.config.audio.host_name.clone(),
+            state.config.audio.stream_hints(device_type.device_type()),
+        )
+    };
+    Ok(DeviceSelector::all_device_capabilities(
+        device_type.device_type(),
+        host.as_deref(),
+        hints,
+    )?)
+}
+
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn audio_get_devices(
     app_state: State<'_, AppState>,
     audio_manager: State<'_, AudioManagerHandle>,
-    device_type: DeviceType,
+    device_type: AudioDeviceType,
 ) -> Result<AudioDevices, Error> {
     log::info!("Getting audio devices (type: {:?})", device_type);
 
@@ -89,7 +116,7 @@ pub async fn audio_get_devices(
     let host = state.config.audio.host_name.clone();
     let host = host.as_deref();
     let (preferred, picked) = match device_type {
-        DeviceType::Input => {
+        AudioDeviceType::Input => {
             let preferred = state
                 .config
                 .audio
@@ -100,7 +127,7 @@ pub async fn audio_get_devices(
                 DeviceSelector::picked_device_name(DeviceType::Input, host, Some(&preferred))?;
             (preferred, picked)
         }
-        DeviceType::Output => {
+        AudioDeviceType::Output => {
             let preferred = state
                 .config
                 .audio
@@ -110,9 +137,20 @@ pub async fn audio_get_devices(
             let picked = audio_manager.read().output_device_name();
             (preferred, picked)
         }
+        AudioDeviceType::Notification => {
+            let preferred = state
+                .config
+                .audio
+                .notification_output_device_name
+                .clone()
+                .unwrap_or_default();
+            let picked = audio_manager.read().notification_output_device_name();
+            (preferred, picked)
+        }
     };
     drop(state);
 
+    let device_type = device_type.device_type();
     let default = DeviceSelector::default_device_name(device_type, host)?;
     let devices: Vec<String> = DeviceSelector::all_device_names(device_type, host)?;
 
@@ -130,7 +168,7 @@ pub async fn audio_set_device(
     app: AppHandle,
     app_state: State<'_, AppState>,
     audio_manager: State<'_, AudioManagerHandle>,
-    device_type: DeviceType,
+    device_type: AudioDeviceType,
     device_name: String,
 ) -> Result<(), Error> {
     let mut state = app_state.lock().await;
@@ -143,14 +181,15 @@ pub async fn audio_set_device(
         .into());
     }
 
-    let reattach_input_level_meter =
-        if audio_manager.is_input_device_attached() && matches!(device_type, DeviceType::Input) {
-            log::trace!("Detaching input level meter before switching input device");
-            audio_manager.detach_input_device();
-            true
-        } else {
-            false
-        };
+    let reattach_input_level_meter = if audio_manager.is_input_device_attached()
+        && matches!(device_type, AudioDeviceType::Input)
+    {
+        log::trace!("Detaching input level meter before switching input device");
+        audio_manager.detach_input_device();
+        true
+    } else {
+        false
+    };
 
     log::info!(
         "Setting audio device (name: {:?}, type: {:?})",
@@ -161,13 +200,21 @@ pub async fn audio_set_device(
     let device_name = Some(device_name).filter(|x| !x.is_empty());
     let persisted_audio_config: PersistedAudioConfig = {
         match device_type {
-            DeviceType::Input => state.config.audio.input_device_name = device_name,
-            DeviceType::Output => {
+            AudioDeviceType::Input => state.config.audio.input_device_name = device_name,
+            AudioDeviceType::Output => {
                 let mut audio_config = state.config.audio.clone();
                 audio_config.output_device_name = device_name;
 
                 audio_manager.switch_output_device(app.clone(), &audio_config, false)?;
 
+                state.config.audio = audio_config;
+            }
+            AudioDeviceType::Notification => {
+                let mut audio_config = state.config.audio.clone();
+                audio_config.notification_output_device_name = device_name;
+
+                audio_manager.switch_notification_output_device(app.clone(), &audio_config);
+
                 state.config.audio = audio_config;
             }
         }
@@ -196,6 +243,15 @@ pub async fn audio_set_device(
     Ok(())
 }
 
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn audio_restart_engine(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<(), Error> {
+    app_state.lock().await.restart_audio_engine(&app).await
+}
+
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn audio_get_volumes(app_state: State<'_, AppState>) -> Result<AudioVolumes, Error> {
@@ -238,6 +294,9 @@ pub async fn audio_set_volume(
             audio_manager.set_output_volume(SourceType::Opus, volume);
             audio_manager.set_output_volume(SourceType::Ringback, volume);
             audio_manager.set_output_volume(SourceType::RingbackOneshot, volume);
+            audio_manager.set_output_volume(SourceType::Busy, volume);
+            audio_manager.set_output_volume(SourceType::RejectTone, volume);
+            audio_manager.set_output_volume(SourceType::NoAnswerTone, volume);
             state.config.audio.output_device_volume = volume;
         }
         VolumeType::Click => {
@@ -261,6 +320,20 @@ pub async fn audio_set_volume(
     Ok(())
 }
 
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn audio_get_sound_packs(app: AppHandle) -> Result<Vec<SoundPackInfo>, Error> {
+    log::info!("Getting sound packs");
+
+    let sounds_dir = app
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(SOUNDS_DIR_NAME));
+
+    Ok(list_sound_packs(sounds_dir.as_deref()))
+}
+
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn audio_play_ui_click(
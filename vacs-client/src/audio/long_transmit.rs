@@ -0,0 +1,138 @@
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+type WarnCallback = Arc<dyn Fn(Duration) + Send + Sync>;
+
+/// Warns once the audio input of a call was unmuted for longer than a threshold.
+///
+/// A latched transmit key or a stuck key can keep the mic open without the controller noticing.
+/// The monitor starts timing when the input is unmuted and resets once it is muted again, calling
+/// `on_warn` if the input stays unmuted for the whole threshold. Unlike the radio
+/// [`TransmitWatchdog`](crate::radio::watchdog::TransmitWatchdog), it never ends the transmission.
+pub struct LongTransmitMonitor {
+    threshold: Option<Duration>,
+    on_warn: WarnCallback,
+    token: Mutex<Option<CancellationToken>>,
+}
+
+impl LongTransmitMonitor {
+    /// Creates a new monitor, a `threshold` of zero disables it.
+    pub fn new(threshold: Duration, on_warn: impl Fn(Duration) + Send + Sync + 'static) -> Self {
+        Self {
+            threshold: (!threshold.is_zero()).then_some(threshold),
+            on_warn: Arc::new(on_warn),
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Updates the monitor after the input was muted or unmuted. Unmuting an input which is
+    /// already unmuted keeps the running timer, so the continuous duration is tracked.
+    pub fn set_muted(&self, muted: bool) {
+        if muted {
+            self.reset();
+            return;
+        }
+
+        let Some(threshold) = self.threshold else {
+            return;
+        };
+
+        let mut token = self.token.lock();
+        if token.is_some() {
+            return;
+        }
+
+        let new_token = CancellationToken::new();
+        *token = Some(new_token.clone());
+
+        let on_warn = self.on_warn.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::select! {
+                _ = new_token.cancelled() => {}
+                _ = tokio::time::sleep(threshold) => {
+                    log::warn!("Audio input unmuted for longer than {threshold:?}");
+                    on_warn(threshold);
+                }
+            }
+        });
+    }
+
+    /// Resets the monitor after the input was muted.
+    pub fn reset(&self) {
+        if let Some(token) = self.token.lock().take() {
+            token.cancel();
+        }
+    }
+}
+
+impl std::fmt::Debug for LongTransmitMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LongTransmitMonitor")
+            .field("threshold", &self.threshold)
+            .field("transmitting", &self.token.lock().is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for LongTransmitMonitor {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+
+    const THRESHOLD: Duration = Duration::from_millis(100);
+
+    fn monitor(threshold: Duration) -> (LongTransmitMonitor, UnboundedReceiver<Duration>) {
+        let (tx, rx) = unbounded_channel();
+        let monitor = LongTransmitMonitor::new(threshold, move |duration| {
+            tx.send(duration).ok();
+        });
+        (monitor, rx)
+    }
+
+    #[tokio::test]
+    async fn warns_after_threshold() {
+        let (monitor, mut rx) = monitor(THRESHOLD);
+
+        monitor.set_muted(false);
+        tokio::time::sleep(THRESHOLD / 2).await;
+        // Repeated unmutes while transmitting do not restart the timer
+        monitor.set_muted(false);
+
+        let warned = tokio::time::timeout(THRESHOLD, rx.recv())
+            .await
+            .expect("monitor did not warn");
+        assert_eq!(warned, Some(THRESHOLD));
+    }
+
+    #[tokio::test]
+    async fn resets_when_muted() {
+        let (monitor, mut rx) = monitor(THRESHOLD);
+
+        monitor.set_muted(false);
+        tokio::time::sleep(THRESHOLD * 3 / 4).await;
+        monitor.set_muted(true);
+        monitor.set_muted(false);
+        tokio::time::sleep(THRESHOLD * 3 / 4).await;
+        monitor.set_muted(true);
+
+        tokio::time::sleep(THRESHOLD * 2).await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn zero_threshold_disables_monitor() {
+        let (monitor, mut rx) = monitor(Duration::ZERO);
+
+        monitor.set_muted(false);
+        tokio::time::sleep(THRESHOLD).await;
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -1,8 +1,11 @@
 use crate::app::state::AppState;
 use crate::app::state::signaling::AppStateSignalingExt;
 use crate::app::state::webrtc::AppStateWebrtcExt;
+use crate::audio::long_transmit::LongTransmitMonitor;
+use crate::audio::sound_pack::{BuiltInSoundPack, SOUNDS_DIR_NAME, SoundPack};
 use crate::config::AudioConfig;
 use crate::error::{Error, FrontendError};
+use crate::signaling::history::CallOutcome;
 use parking_lot::RwLock;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -11,93 +14,212 @@ use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::mpsc;
 use vacs_audio::EncodedAudioFrame;
-use vacs_audio::device::{DeviceSelector, DeviceType};
+use vacs_audio::device::{DeviceSelector, DeviceType, ResamplerQuality, StreamDevice};
 use vacs_audio::error::AudioError;
-use vacs_audio::sources::AudioSourceId;
 use vacs_audio::sources::opus::OpusSource;
+use vacs_audio::sources::sample::SampleSource;
 use vacs_audio::sources::waveform::{Waveform, WaveformSource, WaveformTone};
+use vacs_audio::sources::{AudioSource, AudioSourceId};
 use vacs_audio::stream::capture::{CaptureStream, InputLevel};
 use vacs_audio::stream::playback::PlaybackStream;
 use vacs_signaling::protocol::ws::{CallErrorReason, SignalingMessage};
 
 const AUDIO_STREAM_ERROR_CHANNEL_SIZE: usize = 32;
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SourceType {
     Opus,
     Ring,
     Ringback,
     RingbackOneshot,
+    Busy,
     Click,
+    /// One-shot tone played after an outgoing call was rejected by the callee.
+    RejectTone,
+    /// One-shot tone played after an outgoing call was not answered in time.
+    NoAnswerTone,
 }
 
 impl SourceType {
+    /// File name of the sample replacing this sound in a custom sound pack.
+    pub fn sound_file_name(self) -> Option<&'static str> {
+        match self {
+            SourceType::Opus => None,
+            SourceType::Ring => Some("ring.wav"),
+            SourceType::Ringback | SourceType::RingbackOneshot => Some("ringback.wav"),
+            SourceType::Busy => Some("busy.wav"),
+            SourceType::Click => Some("click.wav"),
+            SourceType::RejectTone => Some("reject.wav"),
+            SourceType::NoAnswerTone => Some("no_answer.wav"),
+        }
+    }
+
+    /// Tone played after an outgoing call ended with the given outcome, `None` if the outcome
+    /// has no tone or it is disabled in the audio config.
+    pub fn call_end_tone(outcome: CallOutcome, audio_config: &AudioConfig) -> Option<SourceType> {
+        match outcome {
+            CallOutcome::Rejected if audio_config.reject_tone => Some(SourceType::RejectTone),
+            CallOutcome::NoAnswer if audio_config.no_answer_tone => Some(SourceType::NoAnswerTone),
+            _ => None,
+        }
+    }
+
+    fn into_audio_source(
+        self,
+        sound_pack: &SoundPack,
+        sample_rate: u32,
+        output_channels: usize,
+        volume: f32,
+        notification_fade: Duration,
+        resampler_quality: ResamplerQuality,
+    ) -> Box<dyn AudioSource> {
+        match sound_pack.load_sample(self, sample_rate, resampler_quality) {
+            Some(sample) => Box::new(SampleSource::new(
+                sample,
+                matches!(self, SourceType::Ringback | SourceType::Busy),
+                match self {
+                    SourceType::Click => Duration::from_millis(1),
+                    _ => notification_fade,
+                },
+                output_channels,
+                volume,
+            )),
+            None => Box::new(self.into_waveform_source(
+                sound_pack.built_in(),
+                sample_rate as f32,
+                output_channels,
+                volume,
+                notification_fade,
+            )),
+        }
+    }
+
     fn into_waveform_source(
         self,
+        pack: BuiltInSoundPack,
         sample_rate: f32,
         output_channels: usize,
         volume: f32,
+        notification_fade: Duration,
     ) -> WaveformSource {
-        match self {
+        let (mut tone, tone_dur, pause_dur, mut fade_dur, output_channels) = match self {
             SourceType::Opus => {
                 unimplemented!("Cannot create waveform source for Opus SourceType")
             }
-            SourceType::Ring => WaveformSource::new(
+            SourceType::Ring => (
                 WaveformTone::new(497.0, Waveform::Triangle, 0.2),
                 Duration::from_secs_f32(1.69),
                 None,
-                Duration::from_millis(10),
-                sample_rate,
+                notification_fade,
                 output_channels,
-                volume,
             ),
-            SourceType::Ringback => WaveformSource::new(
+            SourceType::Ringback => (
                 WaveformTone::new(425.0, Waveform::Sine, 0.2),
                 Duration::from_secs(1),
                 Some(Duration::from_secs(4)),
-                Duration::from_millis(10),
-                sample_rate,
+                notification_fade,
                 output_channels,
-                volume,
             ),
-            SourceType::RingbackOneshot => WaveformSource::new(
+            SourceType::RingbackOneshot => (
                 WaveformTone::new(425.0, Waveform::Sine, 0.2),
                 Duration::from_secs(1),
                 None,
-                Duration::from_millis(10),
-                sample_rate,
+                notification_fade,
                 2,
-                volume,
             ),
-            SourceType::Click => WaveformSource::new(
+            SourceType::Busy => (
+                WaveformTone::new(425.0, Waveform::Sine, 0.2),
+                Duration::from_millis(500),
+                Some(Duration::from_millis(500)),
+                notification_fade,
+                output_channels,
+            ),
+            SourceType::Click => (
                 WaveformTone::new(4000.0, Waveform::Sine, 0.2),
                 Duration::from_millis(20),
                 None,
                 Duration::from_millis(1),
-                sample_rate,
                 output_channels,
-                volume,
             ),
+            SourceType::RejectTone => (
+                WaveformTone::new(620.0, Waveform::Sine, 0.2),
+                Duration::from_millis(600),
+                None,
+                notification_fade,
+                output_channels,
+            ),
+            SourceType::NoAnswerTone => (
+                WaveformTone::new(350.0, Waveform::Sine, 0.2),
+                Duration::from_secs(1),
+                None,
+                notification_fade,
+                output_channels,
+            ),
+        };
+
+        if pack == BuiltInSoundPack::Soft {
+            tone = WaveformTone::new(tone.freq * 0.8, Waveform::Sine, tone.amp * 0.75);
+            fade_dur = fade_dur.max(Duration::from_millis(40)).min(tone_dur / 4);
         }
+
+        WaveformSource::new(
+            tone,
+            tone_dur,
+            pause_dur,
+            fade_dur,
+            sample_rate,
+            output_channels,
+            volume,
+        )
     }
 }
 
 pub struct AudioManager {
     output: PlaybackStream,
+    notification_output: Option<PlaybackStream>,
     input: Option<CaptureStream>,
     source_ids: HashMap<SourceType, AudioSourceId>,
+    sound_pack: SoundPack,
+    /// Receive gain of the peer of the attached call, applied on top of the output volume.
+    call_gain: f32,
+    /// Whether the peer of the attached call is muted locally, overriding its receive gain.
+    call_muted: bool,
+    /// Warns about a long transmission while the input of a call is attached.
+    long_transmit: Option<LongTransmitMonitor>,
 }
 
 pub type AudioManagerHandle = Arc<RwLock<AudioManager>>;
 
 impl AudioManager {
     pub fn new(app: AppHandle, audio_config: &AudioConfig) -> Result<Self, Error> {
-        let (output, source_ids) = Self::create_playback_stream(app, audio_config, false)?;
+        let sounds_dir = app
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|dir| dir.join(SOUNDS_DIR_NAME));
+        let sound_pack = SoundPack::resolve(&audio_config.sound_pack, sounds_dir.as_deref());
+        log::info!("Using sound pack {sound_pack:?}");
+
+        // Devices might not be ready yet if the app is started right after boot
+        let open_retries = audio_config.device_open_retries;
+        let output = Self::create_playback_stream(app.clone(), audio_config, false, open_retries)?;
+        let notification_output =
+            Self::create_notification_playback_stream(app, audio_config, open_retries);
+        let source_ids = Self::create_notification_sources(
+            notification_output.as_ref().unwrap_or(&output),
+            audio_config,
+            &sound_pack,
+        );
 
         Ok(Self {
             output,
+            notification_output,
             input: None,
             source_ids,
+            sound_pack,
+            call_gain: 1.0,
+            call_muted: false,
+            long_transmit: None,
         })
     }
 
@@ -105,30 +227,105 @@ impl AudioManager {
         self.output.device_name()
     }
 
+    pub fn notification_output_device_name(&self) -> String {
+        self.notification_stream().device_name()
+    }
+
     pub fn switch_output_device(
         &mut self,
         app: AppHandle,
         audio_config: &AudioConfig,
         restarting: bool,
     ) -> Result<(), Error> {
-        let (output, source_ids) = Self::create_playback_stream(app, audio_config, restarting)?;
-        self.output = output;
-        self.source_ids = source_ids;
+        self.output = Self::create_playback_stream(app, audio_config, restarting, 0)?;
+        self.source_ids.remove(&SourceType::Opus);
+
+        // Notification sources live on the main output unless a separate device is used
+        if self.notification_output.is_none() {
+            self.source_ids =
+                Self::create_notification_sources(&self.output, audio_config, &self.sound_pack);
+        }
         Ok(())
     }
 
+    /// Tears down and rebuilds all output streams and their mixers using the given config,
+    /// re-creating the notification sources with their configured volumes. Unavailable devices
+    /// fall back to the next best option, same as on startup. The current streams are kept if the
+    /// new ones fail to open.
+    ///
+    /// Call audio has to be detached beforehand and attached again afterwards.
+    pub fn restart_output_streams(
+        &mut self,
+        app: AppHandle,
+        audio_config: &AudioConfig,
+    ) -> Result<(), Error> {
+        if self.is_call_attached() {
+            return Err(AudioError::Other(anyhow::anyhow!(
+                "Cannot restart output streams while call is attached"
+            ))
+            .into());
+        }
+
+        let sound_pack = &self.sound_pack;
+        replace_output_streams(
+            &mut self.output,
+            &mut self.notification_output,
+            &mut self.source_ids,
+            || {
+                let output = Self::create_playback_stream(app.clone(), audio_config, false, 0)?;
+                let notification_output =
+                    Self::create_notification_playback_stream(app, audio_config, 0);
+                Ok((output, notification_output))
+            },
+            |stream| Self::create_notification_sources(stream, audio_config, sound_pack),
+        )?;
+        log::info!("Restarted output streams");
+
+        Ok(())
+    }
+
+    pub fn switch_notification_output_device(
+        &mut self,
+        app: AppHandle,
+        audio_config: &AudioConfig,
+    ) {
+        if self.notification_output.is_none() {
+            for (source_type, source_id) in &self.source_ids {
+                if *source_type != SourceType::Opus {
+                    self.output.remove_audio_source(*source_id);
+                }
+            }
+        }
+
+        self.notification_output = Self::create_notification_playback_stream(app, audio_config, 0);
+
+        let opus_source_id = self.source_ids.remove(&SourceType::Opus);
+        self.source_ids = Self::create_notification_sources(
+            self.notification_stream(),
+            audio_config,
+            &self.sound_pack,
+        );
+        if let Some(source_id) = opus_source_id {
+            self.source_ids.insert(SourceType::Opus, source_id);
+        }
+    }
+
     pub fn attach_input_device(
         &mut self,
         app: AppHandle,
         audio_config: &AudioConfig,
         tx: mpsc::Sender<EncodedAudioFrame>,
         muted: bool,
+        warn_long_transmit: bool,
     ) -> Result<(), Error> {
         let (device, is_fallback) = DeviceSelector::open(
             DeviceType::Input,
             audio_config.host_name.as_deref(),
             audio_config.input_device_name.as_deref(),
+            audio_config.input_stream_hints,
         )?;
+        let device = device.with_resampler_quality(audio_config.resampler_quality);
+        Self::emit_device_config(&app, &device);
         if is_fallback {
             app.emit::<FrontendError>("error", FrontendError::from(Error::AudioDevice(Box::from(AudioError::Other(
                 anyhow::anyhow!("Selected audio input device is not available, falling back to next best option. End your call to check your audio settings.")
@@ -179,13 +376,20 @@ impl AudioManager {
             audio_config.input_device_volume_amp,
             error_tx,
             muted,
+            audio_config.opus_dtx,
+            audio_config.input_limiter_ceiling_dbfs,
         )?;
 
         app_clone
             .emit("audio:stop-input-level-meter", Value::Null)
             .ok();
 
+        let long_transmit =
+            Self::create_long_transmit_monitor(app_clone, audio_config, warn_long_transmit);
+        long_transmit.set_muted(muted);
+
         self.input = Some(capture);
+        self.long_transmit = Some(long_transmit);
         Ok(())
     }
 
@@ -199,7 +403,9 @@ impl AudioManager {
             DeviceType::Input,
             audio_config.host_name.as_deref(),
             audio_config.input_device_name.as_deref(),
+            audio_config.input_stream_hints,
         )?;
+        Self::emit_device_config(&app, &device);
 
         let (error_tx, mut error_rx) = mpsc::channel(AUDIO_STREAM_ERROR_CHANNEL_SIZE);
 
@@ -216,6 +422,7 @@ impl AudioManager {
             log::debug!("Playback capture error receiver closed");
         });
 
+        self.long_transmit = None;
         self.input = Some(CaptureStream::start_level_meter(
             device,
             emit,
@@ -237,26 +444,47 @@ impl AudioManager {
             .unwrap_or(false)
     }
 
+    pub fn is_call_attached(&self) -> bool {
+        self.source_ids.contains_key(&SourceType::Opus)
+    }
+
+    pub fn is_call_muted(&self) -> bool {
+        self.call_muted
+    }
+
     pub fn detach_input_device(&mut self) {
         self.input = None;
+        self.long_transmit = None;
         log::info!("Detached input device");
     }
 
     pub fn start(&self, source_type: SourceType) {
         log::trace!("Starting audio source {source_type:?}");
-        self.output
+        self.stream_for(&source_type)
             .start_audio_source(self.source_ids[&source_type]);
     }
 
     pub fn restart(&self, source_type: SourceType) {
         log::trace!("Restarting audio source {source_type:?}");
-        self.output
+        self.stream_for(&source_type)
             .restart_audio_source(self.source_ids[&source_type]);
     }
 
     pub fn stop(&self, source_type: SourceType) {
         log::trace!("Stopping audio source {source_type:?}");
-        self.output.stop_audio_source(self.source_ids[&source_type]);
+        self.stream_for(&source_type)
+            .stop_audio_source(self.source_ids[&source_type]);
+    }
+
+    /// Stops the ringback of an outgoing call that ended with the given outcome, playing the
+    /// matching call end tone if enabled.
+    pub fn play_call_end_tone(&self, outcome: CallOutcome, audio_config: &AudioConfig) {
+        play_call_end_tone(
+            self.notification_stream(),
+            &self.source_ids,
+            outcome,
+            audio_config,
+        );
     }
 
     pub fn set_output_volume(&self, source_type: SourceType, volume: f32) {
@@ -268,18 +496,50 @@ impl AudioManager {
         }
 
         log::trace!("Setting output volume {volume} for audio source {source_type:?}");
-        self.output
-            .set_volume(self.source_ids[&source_type], volume);
+        let volume = match source_type {
+            SourceType::Opus if self.call_muted => 0.0,
+            SourceType::Opus => volume * self.call_gain,
+            _ => volume,
+        };
+        let stream = self.stream_for(&source_type);
+        stream.set_volume(self.source_ids[&source_type], volume);
 
         match source_type {
             SourceType::Ring | SourceType::Click | SourceType::RingbackOneshot => {
-                self.output
-                    .restart_audio_source(self.source_ids[&source_type]);
+                stream.restart_audio_source(self.source_ids[&source_type]);
             }
             _ => {}
         }
     }
 
+    /// Changes the receive gain of the attached call, `volume` being the current output volume.
+    pub fn set_call_gain(&mut self, gain: f32, volume: f32) {
+        self.call_gain = gain;
+        self.set_output_volume(SourceType::Opus, volume);
+    }
+
+    /// Mutes or unmutes the attached call locally, `volume` being the current output volume.
+    /// The connection and the captured audio sent to the peer are not affected.
+    pub fn set_call_muted(&mut self, muted: bool, volume: f32) {
+        self.call_muted = muted;
+        self.set_output_volume(SourceType::Opus, volume);
+    }
+
+    /// Stops all notification sounds and locally mutes the attached call, returning whether a
+    /// call was attached. `volume` is the current output volume, the call stays connected.
+    pub fn silence_output(&mut self, volume: f32) -> bool {
+        for source_type in self.source_ids.keys().filter(|s| **s != SourceType::Opus) {
+            self.stop(*source_type);
+        }
+
+        let call_attached = self.is_call_attached();
+        if call_attached {
+            self.set_call_muted(true, volume);
+        }
+        log::info!("Silenced all audio output");
+        call_attached
+    }
+
     pub fn set_input_volume(&self, volume: f32) {
         if let Some(input) = &self.input {
             input.set_volume(volume);
@@ -290,13 +550,21 @@ impl AudioManager {
         if let Some(input) = &self.input {
             input.set_muted(muted);
         }
+        if let Some(long_transmit) = &self.long_transmit {
+            long_transmit.set_muted(muted);
+        }
     }
 
     pub fn attach_call_output(
         &mut self,
         webrtc_rx: mpsc::Receiver<EncodedAudioFrame>,
         volume: f32,
+        call_gain: f32,
         amp: f32,
+        comfort_noise: bool,
+        loudness_target_dbfs: Option<f32>,
+        on_speaking: Box<dyn Fn(bool) + Send>,
+        channel_map: Option<&[u16]>,
     ) -> Result<(), Error> {
         if self.source_ids.contains_key(&SourceType::Opus) {
             log::warn!("Tried to attach call but a call was already attached");
@@ -306,22 +574,33 @@ impl AudioManager {
             .into());
         }
 
+        self.call_gain = call_gain;
+        self.call_muted = false;
         self.source_ids.insert(
             SourceType::Opus,
             self.output.add_audio_source(Box::new(OpusSource::new(
                 webrtc_rx,
                 self.output.resampler()?,
-                self.output.channels(),
+                self.output.channel_map(channel_map),
                 volume,
                 amp,
+                comfort_noise,
+                loudness_target_dbfs,
+                Some(on_speaking),
             )?)),
         );
-        log::info!("Attached call");
+        if call_gain != 1.0 {
+            // Sources are capped at unity gain, boosting is applied by the mixer
+            self.set_output_volume(SourceType::Opus, volume);
+        }
+        log::info!("Attached call with gain {call_gain}");
 
         Ok(())
     }
 
     pub fn detach_call_output(&mut self) {
+        self.call_gain = 1.0;
+        self.call_muted = false;
         if let Some(source_id) = self.source_ids.remove(&SourceType::Opus) {
             self.output.remove_audio_source(source_id);
             log::info!("Detached call output");
@@ -330,27 +609,64 @@ impl AudioManager {
         }
     }
 
+    /// Detaches the call output like [`AudioManager::detach_call_output`], but keeps playing audio
+    /// still buffered by the call for at most the given window.
+    pub fn drain_call_output(&mut self, window: Duration) {
+        self.call_gain = 1.0;
+        self.call_muted = false;
+        if let Some(source_id) = self.source_ids.remove(&SourceType::Opus) {
+            self.output.drain_audio_source(source_id, window);
+            log::info!("Draining call output");
+        } else {
+            log::info!("Tried to drain call output but no call was attached");
+        }
+    }
+
+    /// Stream the given source is played on. Call audio always uses the main output device, while
+    /// notification sounds use the separate notification device, if configured and available.
+    fn stream_for(&self, source_type: &SourceType) -> &PlaybackStream {
+        match source_type {
+            SourceType::Opus => &self.output,
+            _ => self.notification_stream(),
+        }
+    }
+
+    fn notification_stream(&self) -> &PlaybackStream {
+        self.notification_output.as_ref().unwrap_or(&self.output)
+    }
+
     fn create_playback_stream(
         app: AppHandle,
         audio_config: &AudioConfig,
         restarting: bool,
-    ) -> Result<(PlaybackStream, HashMap<SourceType, AudioSourceId>), Error> {
-        let (output_device, is_fallback) = DeviceSelector::open(
-            DeviceType::Output,
-            audio_config.host_name.as_deref(),
-            audio_config.output_device_name.as_deref(),
+        open_retries: u8,
+    ) -> Result<PlaybackStream, Error> {
+        let (output_device, is_fallback) = open_with_retry(
+            open_retries,
+            Duration::from_millis(audio_config.device_open_retry_delay_ms),
+            || {
+                DeviceSelector::open(
+                    DeviceType::Output,
+                    audio_config.host_name.as_deref(),
+                    audio_config.output_device_name.as_deref(),
+                    audio_config.output_stream_hints,
+                )
+            },
         )?;
+        let output_device = output_device.with_resampler_quality(audio_config.resampler_quality);
+        Self::emit_device_config(&app, &output_device);
         if is_fallback {
             app.emit::<FrontendError>("error", FrontendError::from(Error::AudioDevice(Box::from(AudioError::Other(
                 anyhow::anyhow!("Selected audio output device is not available, falling back to next best option. Check your audio settings.")
             )))).non_critical()).ok();
         }
 
-        let sample_rate = output_device.sample_rate() as f32;
-        let channels = output_device.channels() as usize;
-
         let (error_tx, mut error_rx) = mpsc::channel(AUDIO_STREAM_ERROR_CHANNEL_SIZE);
-        let output = PlaybackStream::start(output_device, error_tx)?;
+        let output = PlaybackStream::start(
+            output_device,
+            Duration::from_millis(audio_config.volume_ramp_ms),
+            error_tx,
+        )?;
 
         let audio_config_clone = audio_config.clone();
         tauri::async_runtime::spawn(async move {
@@ -418,44 +734,514 @@ impl AudioManager {
             log::debug!("Playback stream error receiver closed");
         });
 
-        let mut source_ids = HashMap::new();
-        source_ids.insert(
-            SourceType::Ring,
-            output.add_audio_source(Box::new(SourceType::into_waveform_source(
-                SourceType::Ring,
-                sample_rate,
-                channels,
-                audio_config.chime_volume,
-            ))),
-        );
-        source_ids.insert(
-            SourceType::Ringback,
-            output.add_audio_source(Box::new(SourceType::into_waveform_source(
-                SourceType::Ringback,
-                sample_rate,
-                channels,
+        Ok(output)
+    }
+
+    fn create_notification_playback_stream(
+        app: AppHandle,
+        audio_config: &AudioConfig,
+        open_retries: u8,
+    ) -> Option<PlaybackStream> {
+        let device_name = audio_config.notification_output_device_name.as_deref()?;
+
+        let emit_fallback_warning = |app: &AppHandle| {
+            app.emit::<FrontendError>("error", FrontendError::from(Error::AudioDevice(Box::from(AudioError::Other(
+                anyhow::anyhow!("Selected notification output device is not available, falling back to headset device. Check your audio settings.")
+            )))).non_critical()).ok();
+        };
+
+        let notification_device = match open_with_retry(
+            open_retries,
+            Duration::from_millis(audio_config.device_open_retry_delay_ms),
+            || {
+                DeviceSelector::open(
+                    DeviceType::Output,
+                    audio_config.host_name.as_deref(),
+                    Some(device_name),
+                    audio_config.output_stream_hints,
+                )
+            },
+        ) {
+            Ok((_, true)) => {
+                log::warn!(
+                    "Notification output device {device_name} not available, falling back to main output device"
+                );
+                emit_fallback_warning(&app);
+                return None;
+            }
+            Ok((device, false)) => device.with_resampler_quality(audio_config.resampler_quality),
+            Err(err) => {
+                log::warn!(
+                    "Failed to open notification output device {device_name}, falling back to main output device: {err:?}"
+                );
+                emit_fallback_warning(&app);
+                return None;
+            }
+        };
+
+        Self::emit_device_config(&app, &notification_device);
+
+        let (error_tx, mut error_rx) = mpsc::channel(AUDIO_STREAM_ERROR_CHANNEL_SIZE);
+        let output = match PlaybackStream::start(
+            notification_device,
+            Duration::from_millis(audio_config.volume_ramp_ms),
+            error_tx,
+        ) {
+            Ok(output) => output,
+            Err(err) => {
+                log::warn!(
+                    "Failed to start notification output device {device_name}, falling back to main output device: {err:?}"
+                );
+                emit_fallback_warning(&app);
+                return None;
+            }
+        };
+
+        let audio_config = AudioConfig {
+            notification_output_device_name: None,
+            ..audio_config.clone()
+        };
+        tauri::async_runtime::spawn(async move {
+            if let Some(err) = error_rx.recv().await {
+                log::warn!(
+                    "Notification playback stream failed, falling back to main output device: {err:?}"
+                );
+
+                app.state::<AudioManagerHandle>()
+                    .write()
+                    .switch_notification_output_device(app.clone(), &audio_config);
+
+                app.emit::<FrontendError>(
+                    "error",
+                    FrontendError::from(Error::from(err)).non_critical(),
+                )
+                .ok();
+            }
+            log::debug!("Notification playback stream error receiver closed");
+        });
+
+        Some(output)
+    }
+
+    /// Creates the monitor emitting `audio:long-transmit` with the threshold in seconds once the
+    /// input of a call was unmuted for too long, optionally playing a click as audible cue.
+    /// Creates the long transmission monitor of a call, disabled unless `enabled` is set.
+    fn create_long_transmit_monitor(
+        app: AppHandle,
+        audio_config: &AudioConfig,
+        enabled: bool,
+    ) -> LongTransmitMonitor {
+        let cue = audio_config.long_transmit_cue;
+        let threshold = if enabled {
+            Duration::from_secs(audio_config.long_transmit_warn_seconds)
+        } else {
+            Duration::ZERO
+        };
+        LongTransmitMonitor::new(threshold, move |threshold| {
+            app.emit("audio:long-transmit", threshold.as_secs()).ok();
+            if cue {
+                app.state::<AudioManagerHandle>()
+                    .read()
+                    .start(SourceType::Click);
+            }
+        })
+    }
+
+    fn emit_device_config(app: &AppHandle, device: &StreamDevice) {
+        let info = device.config_info();
+        log::info!("Using {} device config: {info:?}", info.device_type);
+        app.emit("audio:device-config", info).ok();
+    }
+
+    fn create_notification_sources(
+        output: &PlaybackStream,
+        audio_config: &AudioConfig,
+        sound_pack: &SoundPack,
+    ) -> HashMap<SourceType, AudioSourceId> {
+        let sample_rate = output.sample_rate();
+        let channels = output.channels() as usize;
+
+        Self::notification_source_volumes(audio_config)
+            .into_iter()
+            .map(|(source_type, volume)| {
+                let source = source_type.into_audio_source(
+                    sound_pack,
+                    sample_rate,
+                    channels,
+                    volume,
+                    Duration::from_millis(audio_config.notification_fade_ms.max(1)),
+                    audio_config.resampler_quality,
+                );
+                (source_type, output.add_audio_source(source))
+            })
+            .collect()
+    }
+
+    /// Notification sources along with their configured volume.
+    fn notification_source_volumes(audio_config: &AudioConfig) -> [(SourceType, f32); 7] {
+        [
+            (SourceType::Ring, audio_config.chime_volume),
+            (SourceType::Ringback, audio_config.output_device_volume),
+            (
+                SourceType::RingbackOneshot,
                 audio_config.output_device_volume,
-            ))),
+            ),
+            (SourceType::Busy, audio_config.output_device_volume),
+            (SourceType::Click, audio_config.click_volume),
+            (SourceType::RejectTone, audio_config.output_device_volume),
+            (SourceType::NoAnswerTone, audio_config.output_device_volume),
+        ]
+    }
+}
+
+/// Playback controls of the notification sources on an output stream.
+trait NotificationPlayback {
+    fn restart_audio_source(&self, id: AudioSourceId);
+    fn stop_audio_source(&self, id: AudioSourceId);
+}
+
+impl NotificationPlayback for PlaybackStream {
+    fn restart_audio_source(&self, id: AudioSourceId) {
+        PlaybackStream::restart_audio_source(self, id);
+    }
+
+    fn stop_audio_source(&self, id: AudioSourceId) {
+        PlaybackStream::stop_audio_source(self, id);
+    }
+}
+
+fn play_call_end_tone(
+    stream: &impl NotificationPlayback,
+    source_ids: &HashMap<SourceType, AudioSourceId>,
+    outcome: CallOutcome,
+    audio_config: &AudioConfig,
+) {
+    stream.stop_audio_source(source_ids[&SourceType::Ringback]);
+    if let Some(tone) = SourceType::call_end_tone(outcome, audio_config) {
+        log::trace!("Playing call end tone {tone:?} for outcome {outcome:?}");
+        stream.restart_audio_source(source_ids[&tone]);
+    }
+}
+
+/// Replaces the output streams by the ones returned by `open`, creating the notification sources on
+/// the new notification stream (or the new main output without one) via `add_sources`.
+///
+/// The current streams and their sources keep playing until the new streams have been opened and
+/// are left in place if opening fails.
+fn replace_output_streams<S>(
+    output: &mut S,
+    notification_output: &mut Option<S>,
+    source_ids: &mut HashMap<SourceType, AudioSourceId>,
+    open: impl FnOnce() -> Result<(S, Option<S>), Error>,
+    add_sources: impl FnOnce(&S) -> HashMap<SourceType, AudioSourceId>,
+) -> Result<(), Error> {
+    let (new_output, new_notification_output) = open()?;
+    *source_ids = add_sources(new_notification_output.as_ref().unwrap_or(&new_output));
+    *output = new_output;
+    *notification_output = new_notification_output;
+    Ok(())
+}
+
+/// Calls `open` until the device opens, retrying up to `retries` times with the given delay while
+/// opening fails.
+///
+/// Falling back to another device is not an error and is returned right away, so only devices
+/// that fail to open delay the startup.
+/// Returns the result of the last attempt once the retries are exhausted.
+fn open_with_retry<T>(
+    retries: u8,
+    delay: Duration,
+    mut open: impl FnMut() -> Result<(T, bool), AudioError>,
+) -> Result<(T, bool), AudioError> {
+    let mut retry = 0;
+    loop {
+        let err = match open() {
+            Err(err) if retry < retries => err,
+            result => return result,
+        };
+        log::info!(
+            "Failed to open audio device, retrying in {delay:?} ({}/{retries}): {err:?}",
+            retry + 1
+        );
+        retry += 1;
+        std::thread::sleep(delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    /// Device that only becomes available after being opened `ready_after` times, either failing
+    /// or returning a fallback device before.
+    struct MockDevice {
+        ready_after: u8,
+        fallback: bool,
+        attempts: u8,
+    }
+
+    impl MockDevice {
+        fn new(ready_after: u8, fallback: bool) -> Self {
+            Self {
+                ready_after,
+                fallback,
+                attempts: 0,
+            }
+        }
+
+        fn open(&mut self) -> Result<(u8, bool), AudioError> {
+            self.attempts += 1;
+            if self.attempts > self.ready_after {
+                Ok((self.attempts, false))
+            } else if self.fallback {
+                Ok((self.attempts, true))
+            } else {
+                Err(AudioError::Other(anyhow::anyhow!("Device not ready")))
+            }
+        }
+    }
+
+    #[test]
+    fn open_with_retry_succeeds_once_device_is_ready() {
+        let mut device = MockDevice::new(2, false);
+        assert!(open_with_retry(0, Duration::ZERO, || device.open()).is_err());
+
+        let mut device = MockDevice::new(2, false);
+        let result = open_with_retry(3, Duration::ZERO, || device.open());
+        assert!(matches!(result, Ok((3, false))));
+        assert_eq!(device.attempts, 3);
+    }
+
+    #[test]
+    fn open_with_retry_does_not_retry_fallback() {
+        let mut device = MockDevice::new(1, true);
+        let result = open_with_retry(3, Duration::ZERO, || device.open());
+        assert!(matches!(result, Ok((1, true))));
+        assert_eq!(device.attempts, 1);
+    }
+
+    #[test]
+    fn open_with_retry_gives_up_after_retries() {
+        let mut device = MockDevice::new(5, false);
+        assert!(open_with_retry(2, Duration::ZERO, || device.open()).is_err());
+        assert_eq!(device.attempts, 3);
+    }
+
+    #[test]
+    fn call_end_tones() {
+        let audio_config = AudioConfig::default();
+
+        assert_eq!(
+            SourceType::call_end_tone(CallOutcome::Rejected, &audio_config),
+            Some(SourceType::RejectTone)
+        );
+        assert_eq!(
+            SourceType::call_end_tone(CallOutcome::NoAnswer, &audio_config),
+            Some(SourceType::NoAnswerTone)
+        );
+        for outcome in [
+            CallOutcome::Completed,
+            CallOutcome::Missed,
+            CallOutcome::Busy,
+            CallOutcome::Failed,
+        ] {
+            assert_eq!(SourceType::call_end_tone(outcome, &audio_config), None);
+        }
+    }
+
+    /// Output stream recording the volumes of the notification sources added to it and which of
+    /// them are playing.
+    #[derive(Debug, Default)]
+    struct FakeStream {
+        name: &'static str,
+        volumes: RefCell<HashMap<SourceType, f32>>,
+        playing: RefCell<HashSet<AudioSourceId>>,
+    }
+
+    impl NotificationPlayback for FakeStream {
+        fn restart_audio_source(&self, id: AudioSourceId) {
+            self.playing.borrow_mut().insert(id);
+        }
+
+        fn stop_audio_source(&self, id: AudioSourceId) {
+            self.playing.borrow_mut().remove(&id);
+        }
+    }
+
+    impl FakeStream {
+        fn new(name: &'static str) -> Self {
+            Self {
+                name,
+                ..Default::default()
+            }
+        }
+
+        fn add_sources(&self, audio_config: &AudioConfig) -> HashMap<SourceType, AudioSourceId> {
+            AudioManager::notification_source_volumes(audio_config)
+                .into_iter()
+                .enumerate()
+                .map(|(id, (source_type, volume))| {
+                    self.volumes.borrow_mut().insert(source_type, volume);
+                    (source_type, id)
+                })
+                .collect()
+        }
+    }
+
+    /// Sources playing after an outgoing call ringing back ended with the given outcome.
+    fn playing_after_call_end(
+        outcome: CallOutcome,
+        audio_config: &AudioConfig,
+    ) -> HashSet<SourceType> {
+        let stream = FakeStream::new("notification");
+        let source_ids = stream.add_sources(audio_config);
+        stream.restart_audio_source(source_ids[&SourceType::Ringback]);
+
+        play_call_end_tone(&stream, &source_ids, outcome, audio_config);
+
+        let playing = stream.playing.borrow();
+        source_ids
+            .into_iter()
+            .filter(|(_, id)| playing.contains(id))
+            .map(|(source_type, _)| source_type)
+            .collect()
+    }
+
+    #[test]
+    fn call_end_plays_tone_instead_of_ringback() {
+        let audio_config = AudioConfig::default();
+
+        assert_eq!(
+            playing_after_call_end(CallOutcome::Rejected, &audio_config),
+            HashSet::from([SourceType::RejectTone])
+        );
+        assert_eq!(
+            playing_after_call_end(CallOutcome::NoAnswer, &audio_config),
+            HashSet::from([SourceType::NoAnswerTone])
         );
-        source_ids.insert(
+        assert!(playing_after_call_end(CallOutcome::Failed, &audio_config).is_empty());
+
+        let audio_config = AudioConfig {
+            reject_tone: false,
+            ..Default::default()
+        };
+        assert!(playing_after_call_end(CallOutcome::Rejected, &audio_config).is_empty());
+        assert_eq!(
+            playing_after_call_end(CallOutcome::NoAnswer, &audio_config),
+            HashSet::from([SourceType::NoAnswerTone])
+        );
+    }
+
+    #[test]
+    fn restart_applies_configured_volumes() {
+        let audio_config = AudioConfig {
+            output_device_volume: 0.4,
+            click_volume: 0.3,
+            chime_volume: 0.9,
+            ..Default::default()
+        };
+        let mut output = FakeStream::new("old");
+        let mut notification_output = None;
+        let mut source_ids = HashMap::from([(SourceType::Opus, 42)]);
+
+        replace_output_streams(
+            &mut output,
+            &mut notification_output,
+            &mut source_ids,
+            || {
+                Ok((
+                    FakeStream::new("new"),
+                    Some(FakeStream::new("notification")),
+                ))
+            },
+            |stream| stream.add_sources(&audio_config),
+        )
+        .unwrap();
+
+        assert_eq!(output.name, "new");
+        assert!(output.volumes.borrow().is_empty());
+        let notification_output = notification_output.unwrap();
+        assert_eq!(notification_output.name, "notification");
+
+        let volumes = notification_output.volumes.borrow();
+        assert_eq!(volumes.len(), 7);
+        assert_eq!(volumes[&SourceType::Ring], 0.9);
+        assert_eq!(volumes[&SourceType::Click], 0.3);
+        for source_type in [
+            SourceType::Ringback,
             SourceType::RingbackOneshot,
-            output.add_audio_source(Box::new(SourceType::into_waveform_source(
-                SourceType::RingbackOneshot,
-                sample_rate,
-                channels,
-                audio_config.output_device_volume,
-            ))),
+            SourceType::Busy,
+            SourceType::RejectTone,
+            SourceType::NoAnswerTone,
+        ] {
+            assert_eq!(volumes[&source_type], 0.4, "{source_type:?}");
+        }
+        assert_eq!(source_ids.len(), 7);
+        assert!(!source_ids.contains_key(&SourceType::Opus));
+    }
+
+    #[test]
+    fn restart_adds_sources_to_output_without_notification_stream() {
+        let audio_config = AudioConfig::default();
+        let mut output = FakeStream::new("old");
+        let mut notification_output = Some(FakeStream::new("old notification"));
+        let mut source_ids = HashMap::new();
+
+        replace_output_streams(
+            &mut output,
+            &mut notification_output,
+            &mut source_ids,
+            || Ok((FakeStream::new("new"), None)),
+            |stream| stream.add_sources(&audio_config),
+        )
+        .unwrap();
+
+        assert_eq!(output.name, "new");
+        assert_eq!(output.volumes.borrow().len(), 7);
+        assert!(notification_output.is_none());
+        assert_eq!(source_ids.len(), 7);
+    }
+
+    #[test]
+    fn restart_keeps_streams_if_opening_fails() {
+        let mut output = FakeStream::new("old");
+        let mut notification_output = Some(FakeStream::new("old notification"));
+        let mut source_ids = HashMap::from([(SourceType::Ring, 1), (SourceType::Opus, 2)]);
+
+        let result = replace_output_streams(
+            &mut output,
+            &mut notification_output,
+            &mut source_ids,
+            || Err(AudioError::Other(anyhow::anyhow!("Device busy")).into()),
+            |_| unreachable!("no sources are added without new streams"),
         );
-        source_ids.insert(
-            SourceType::Click,
-            output.add_audio_source(Box::new(SourceType::into_waveform_source(
-                SourceType::Click,
-                sample_rate,
-                channels,
-                audio_config.click_volume,
-            ))),
+
+        assert!(result.is_err());
+        assert_eq!(output.name, "old");
+        assert_eq!(notification_output.unwrap().name, "old notification");
+        assert_eq!(
+            source_ids,
+            HashMap::from([(SourceType::Ring, 1), (SourceType::Opus, 2)])
         );
+    }
 
-        Ok((output, source_ids))
+    #[test]
+    fn call_end_tones_disabled() {
+        let audio_config = AudioConfig {
+            reject_tone: false,
+            no_answer_tone: false,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            SourceType::call_end_tone(CallOutcome::Rejected, &audio_config),
+            None
+        );
+        assert_eq!(
+            SourceType::call_end_tone(CallOutcome::NoAnswer, &audio_config),
+            None
+        );
     }
 }
@@ -0,0 +1,320 @@
+use crate::audio::manager::SourceType;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use vacs_audio::device::ResamplerQuality;
+use vacs_audio::sources::sample::SampleData;
+
+/// Directory inside the config directory containing custom sound packs, one subdirectory per pack.
+pub const SOUNDS_DIR_NAME: &str = "sounds";
+pub const DEFAULT_SOUND_PACK: &str = "classic";
+
+/// Sound packs generated from waveforms, available without any additional files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltInSoundPack {
+    Classic,
+    /// Sine tones with longer fades, slightly lower pitch and volume.
+    Soft,
+}
+
+impl BuiltInSoundPack {
+    pub const ALL: [Self; 2] = [Self::Classic, Self::Soft];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BuiltInSoundPack::Classic => DEFAULT_SOUND_PACK,
+            BuiltInSoundPack::Soft => "soft",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|pack| pack.name() == name)
+    }
+}
+
+/// Sound pack selected in the audio config.
+///
+/// Custom packs are directories of WAV files named after the sounds they replace (`ring.wav`,
+/// `ringback.wav`, `busy.wav`, `click.wav`, `reject.wav`, `no_answer.wav`). Sounds missing from a
+/// custom pack or failing to load fall back to the [`BuiltInSoundPack::Classic`] pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoundPack {
+    BuiltIn(BuiltInSoundPack),
+    Custom(PathBuf),
+}
+
+impl Default for SoundPack {
+    fn default() -> Self {
+        SoundPack::BuiltIn(BuiltInSoundPack::Classic)
+    }
+}
+
+impl SoundPack {
+    /// Resolves a configured sound pack name, falling back to the default pack if neither a
+    /// built-in nor a custom pack with that name exists.
+    pub fn resolve(name: &str, sounds_dir: Option<&Path>) -> Self {
+        if let Some(pack) = BuiltInSoundPack::from_name(name) {
+            return SoundPack::BuiltIn(pack);
+        }
+
+        match sounds_dir.map(|dir| dir.join(name)) {
+            Some(dir) if is_valid_pack_name(name) && dir.is_dir() => SoundPack::Custom(dir),
+            _ => {
+                log::warn!("Sound pack {name} not found, falling back to {DEFAULT_SOUND_PACK}");
+                SoundPack::default()
+            }
+        }
+    }
+
+    /// Loads the custom sample for the given sound, resampled to the output sample rate.
+    ///
+    /// Returns `None` for built-in packs and if the custom pack does not contain a valid
+    /// replacement, in which case the built-in waveform should be used.
+    pub fn load_sample(
+        &self,
+        source_type: SourceType,
+        sample_rate: u32,
+        quality: ResamplerQuality,
+    ) -> Option<SampleData> {
+        let SoundPack::Custom(dir) = self else {
+            return None;
+        };
+        let path = dir.join(source_type.sound_file_name()?);
+        if !path.is_file() {
+            log::debug!(
+                "No custom {source_type:?} sound in {}, using built-in sound",
+                dir.display()
+            );
+            return None;
+        }
+
+        match SampleData::load(&path).and_then(|sample| sample.resampled(sample_rate, quality)) {
+            Ok(sample) => {
+                log::debug!(
+                    "Loaded custom {source_type:?} sound from {} ({:?})",
+                    path.display(),
+                    sample.duration()
+                );
+                Some(sample)
+            }
+            Err(err) => {
+                log::warn!(
+                    "Failed to load custom {source_type:?} sound, using built-in sound: {err:?}"
+                );
+                None
+            }
+        }
+    }
+
+    /// Built-in pack providing the waveforms for sounds not replaced by a custom sample.
+    pub fn built_in(&self) -> BuiltInSoundPack {
+        match self {
+            SoundPack::BuiltIn(pack) => *pack,
+            SoundPack::Custom(_) => BuiltInSoundPack::Classic,
+        }
+    }
+}
+
+/// Custom pack names are used as directory names and must not escape the sounds directory.
+fn is_valid_pack_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SoundPackInfo {
+    pub name: String,
+    pub built_in: bool,
+}
+
+/// Lists the built-in packs followed by all custom packs found in the sounds directory.
+pub fn list_sound_packs(sounds_dir: Option<&Path>) -> Vec<SoundPackInfo> {
+    let mut custom = sounds_dir
+        .and_then(|dir| std::fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| is_valid_pack_name(name) && BuiltInSoundPack::from_name(name).is_none())
+        .collect::<Vec<_>>();
+    custom.sort();
+
+    BuiltInSoundPack::ALL
+        .into_iter()
+        .map(|pack| SoundPackInfo {
+            name: pack.name().to_string(),
+            built_in: true,
+        })
+        .chain(custom.into_iter().map(|name| SoundPackInfo {
+            name,
+            built_in: false,
+        }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempSoundsDir(PathBuf);
+
+    impl TempSoundsDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "vacs-sound-pack-test-{name}-{}",
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn add_pack(&self, name: &str, files: &[(&str, &[u8])]) -> PathBuf {
+            let dir = self.0.join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            for (file_name, content) in files {
+                std::fs::write(dir.join(file_name), content).unwrap();
+            }
+            dir
+        }
+    }
+
+    impl Drop for TempSoundsDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Mono 16 bit PCM WAV file containing the given number of frames of a constant sample.
+    fn wav(sample_rate: u32, frames: usize) -> Vec<u8> {
+        let data = (0..frames)
+            .flat_map(|_| 8_192i16.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // Mono
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&data);
+        bytes
+    }
+
+    #[test]
+    fn resolves_built_in_packs() {
+        assert_eq!(
+            SoundPack::resolve("classic", None),
+            SoundPack::BuiltIn(BuiltInSoundPack::Classic)
+        );
+        assert_eq!(
+            SoundPack::resolve("soft", None),
+            SoundPack::BuiltIn(BuiltInSoundPack::Soft)
+        );
+    }
+
+    #[test]
+    fn unknown_pack_falls_back_to_default() {
+        let sounds = TempSoundsDir::new("unknown");
+        assert_eq!(
+            SoundPack::resolve("missing", Some(&sounds.0)),
+            SoundPack::default()
+        );
+        assert_eq!(SoundPack::resolve("missing", None), SoundPack::default());
+        assert_eq!(
+            SoundPack::resolve("..", Some(&sounds.0)),
+            SoundPack::default()
+        );
+    }
+
+    #[test]
+    fn loads_custom_wav() {
+        let sounds = TempSoundsDir::new("custom");
+        let dir = sounds.add_pack("tower", &[("ring.wav", &wav(48_000, 4_800))]);
+
+        let pack = SoundPack::resolve("tower", Some(&sounds.0));
+        assert_eq!(pack, SoundPack::Custom(dir));
+
+        let sample = pack
+            .load_sample(SourceType::Ring, 48_000, ResamplerQuality::Fast)
+            .unwrap();
+        assert_eq!(sample.sample_rate(), 48_000);
+        assert_eq!(sample.samples().len(), 4_800);
+        assert!(sample.samples().iter().all(|s| *s == 0.25));
+    }
+
+    #[test]
+    fn resamples_custom_wav() {
+        let sounds = TempSoundsDir::new("resample");
+        sounds.add_pack("tower", &[("click.wav", &wav(22_050, 2_205))]);
+
+        let sample = SoundPack::resolve("tower", Some(&sounds.0))
+            .load_sample(SourceType::Click, 48_000, ResamplerQuality::Fast)
+            .unwrap();
+        assert_eq!(sample.sample_rate(), 48_000);
+        assert_eq!(sample.samples().len(), 4_800);
+    }
+
+    #[test]
+    fn missing_or_invalid_custom_sounds_fall_back() {
+        let sounds = TempSoundsDir::new("fallback");
+        sounds.add_pack(
+            "broken",
+            &[("ring.wav", b"not a wav file"), ("busy.wav", &[])],
+        );
+        let pack = SoundPack::resolve("broken", Some(&sounds.0));
+        assert!(matches!(pack, SoundPack::Custom(_)));
+        assert_eq!(pack.built_in(), BuiltInSoundPack::Classic);
+
+        for source_type in [SourceType::Ring, SourceType::Busy, SourceType::Ringback] {
+            assert_eq!(
+                pack.load_sample(source_type, 48_000, ResamplerQuality::Fast),
+                None,
+                "{source_type:?} should fall back to the built-in sound"
+            );
+        }
+    }
+
+    #[test]
+    fn built_in_packs_have_no_samples() {
+        let pack = SoundPack::BuiltIn(BuiltInSoundPack::Soft);
+        assert_eq!(
+            pack.load_sample(SourceType::Ring, 48_000, ResamplerQuality::Fast),
+            None
+        );
+        assert_eq!(pack.built_in(), BuiltInSoundPack::Soft);
+    }
+
+    #[test]
+    fn lists_built_in_and_custom_packs() {
+        let sounds = TempSoundsDir::new("list");
+        sounds.add_pack("tower", &[]);
+        sounds.add_pack("approach", &[]);
+        // Custom packs cannot shadow built-in ones
+        sounds.add_pack("soft", &[]);
+        std::fs::write(sounds.0.join("ring.wav"), wav(48_000, 1)).unwrap();
+
+        let names = list_sound_packs(Some(&sounds.0))
+            .into_iter()
+            .map(|info| (info.name, info.built_in))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("classic".to_string(), true),
+                ("soft".to_string(), true),
+                ("approach".to_string(), false),
+                ("tower".to_string(), false),
+            ]
+        );
+
+        assert_eq!(list_sound_packs(None).len(), BuiltInSoundPack::ALL.len());
+    }
+}
@@ -1,30 +1,55 @@
+pub(crate) mod bundle;
+pub(crate) mod watcher;
+
+use crate::app::logging::parse_log_level;
 use crate::app::window::WindowProvider;
+use crate::audio::sound_pack::DEFAULT_SOUND_PACK;
 use crate::error::Error;
+use crate::keybinds::combo::{KeyCombo, KeySequence};
+use crate::platform::Capabilities;
 use crate::radio::push_to_talk::PushToTalkRadio;
 use crate::radio::track_audio::TrackAudioRadio;
 use crate::radio::{DynRadio, RadioIntegration};
 use anyhow::Context;
 use config::{Config, Environment, File};
 use keyboard_types::Code;
+use log::LevelFilter;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tauri::{AppHandle, LogicalSize, PhysicalPosition, PhysicalSize};
+use tauri::{AppHandle, LogicalSize, PhysicalPosition, PhysicalSize, WebviewWindow};
+use vacs_audio::DEFAULT_INPUT_LIMITER_CEILING_DBFS;
+use vacs_audio::device::{DeviceSelector, DeviceType, ResamplerQuality, StreamConfigHints};
+use vacs_signaling::client::ReconnectConfig;
 use vacs_signaling::protocol::http::version::ReleaseChannel;
 use vacs_signaling::protocol::http::webrtc::IceConfig;
+use vacs_webrtc::config::Codec;
 
 /// User-Agent string used for all HTTP requests.
 pub static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 pub const WS_LOGIN_TIMEOUT: Duration = Duration::from_secs(10);
+/// Interval between `signaling:session-stats` events reporting the connection uptime.
+pub const SESSION_STATS_INTERVAL: Duration = Duration::from_secs(5);
 pub const DEFAULT_SETTINGS_FILE_NAME: &str = "config.toml";
 pub const AUDIO_SETTINGS_FILE_NAME: &str = "audio.toml";
 pub const CLIENT_SETTINGS_FILE_NAME: &str = "client.toml";
 pub const STATIONS_SETTINGS_FILE_NAME: &str = "stations.toml";
 pub const ENCODED_AUDIO_FRAME_BUFFER_SIZE: usize = 512;
 pub const ICE_CONFIG_EXPIRY_LEEWAY: Duration = Duration::from_mins(15);
+pub const BUSY_TONE_DURATION: Duration = Duration::from_secs(3);
+/// Maximum time received call audio keeps playing after a call ended with `drain_on_close`.
+pub const CALL_OUTPUT_DRAIN_WINDOW: Duration = Duration::from_millis(200);
+pub const DEFAULT_TRACK_AUDIO_HEARTBEAT_INTERVAL_MS: u64 = 5_000;
+pub const DEFAULT_MAX_RADIO_TRANSMIT_DURATION_SECS: u64 = 30;
+pub const DEFAULT_PEER_VOLUME: f32 = 1.0;
+pub const MAX_PEER_VOLUME: f32 = 2.0;
+pub const SIGNALING_RECONNECT_ATTEMPTS_RANGE: RangeInclusive<u8> = 1..=50;
+pub const MAX_DSCP: u8 = 63;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppConfig {
@@ -38,49 +63,47 @@ pub struct AppConfig {
 
 impl AppConfig {
     pub fn parse(config_dir: &Path) -> anyhow::Result<Self> {
-        let mut builder = Config::builder()
-            .add_source(Config::try_from(&AppConfig::default())?)
-            .add_source(
-                File::with_name(
-                    config_dir
-                        .join(DEFAULT_SETTINGS_FILE_NAME)
-                        .to_str()
-                        .expect("Failed to get local config path"),
-                )
-                .required(false),
-            )
-            .add_source(File::with_name(DEFAULT_SETTINGS_FILE_NAME).required(false))
-            .add_source(
-                File::with_name(
-                    config_dir
-                        .join(AUDIO_SETTINGS_FILE_NAME)
-                        .to_str()
-                        .expect("Failed to get local config path"),
-                )
-                .required(false),
-            )
-            .add_source(File::with_name(AUDIO_SETTINGS_FILE_NAME).required(false))
-            .add_source(
-                File::with_name(
-                    config_dir
-                        .join(STATIONS_SETTINGS_FILE_NAME)
-                        .to_str()
-                        .expect("Failed to get local config path"),
-                )
-                .required(false),
-            )
-            .add_source(File::with_name(STATIONS_SETTINGS_FILE_NAME).required(false))
-            .add_source(
-                File::with_name(
-                    config_dir
-                        .join(CLIENT_SETTINGS_FILE_NAME)
-                        .to_str()
-                        .expect("Failed to get local config path"),
-                )
-                .required(false),
-            )
-            .add_source(File::with_name(CLIENT_SETTINGS_FILE_NAME).required(false))
-            .add_source(Environment::with_prefix("vacs_client"));
+        let mut config = Self::load(config_dir)?;
+
+        config
+            .stations
+            .validate()
+            .context("Failed to validate stations config")?;
+        config
+            .client
+            .validate()
+            .context("Failed to validate client config")?;
+
+        // Migration of legacy selected stations profile previously stored in stations.toml
+        if let Some(legacy_profile) = config.stations.legacy_selected_profile.take()
+            && config.client.selected_stations_profile == "Default"
+            && legacy_profile != "Default"
+        {
+            log::info!(
+                "Migrating legacy selected_stations_profile '{legacy_profile}' to client config"
+            );
+            config.client.selected_stations_profile = legacy_profile;
+
+            let persisted_client_config = PersistedClientConfig::from(config.client.clone());
+            if let Err(err) = persisted_client_config.persist(config_dir, CLIENT_SETTINGS_FILE_NAME)
+            {
+                log::error!("Failed to persist migrated client config: {err}");
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Builds and deserializes all config layers, without validating or migrating the result.
+    fn load(config_dir: &Path) -> anyhow::Result<Self> {
+        let mut builder = Config::builder().add_source(Config::try_from(&AppConfig::default())?);
+        for path in Self::config_files(config_dir) {
+            builder = builder.add_source(
+                File::with_name(path.to_str().expect("Failed to get local config path"))
+                    .required(false),
+            );
+        }
+        builder = builder.add_source(Environment::with_prefix("vacs_client"));
 
         let preliminary_config: AppConfig = builder
             .build_cloned()
@@ -96,30 +119,229 @@ impl AppConfig {
                 .add_source(Environment::with_prefix("vacs_client"));
         }
 
-        let mut config: AppConfig = builder
+        builder
             .build()
             .context("Failed to build config")?
             .try_deserialize()
-            .context("Failed to deserialize config")?;
+            .context("Failed to deserialize config")
+    }
 
-        // Migration of legacy selected stations profile previously stored in stations.toml
-        if let Some(legacy_profile) = config.stations.legacy_selected_profile.take()
-            && config.client.selected_stations_profile == "Default"
-            && legacy_profile != "Default"
-        {
-            log::info!(
-                "Migrating legacy selected_stations_profile '{legacy_profile}' to client config"
-            );
-            config.client.selected_stations_profile = legacy_profile;
+    /// Returns the config files read from the config directory and the current working
+    /// directory, ordered by increasing precedence.
+    fn config_files(config_dir: &Path) -> Vec<PathBuf> {
+        [
+            DEFAULT_SETTINGS_FILE_NAME,
+            AUDIO_SETTINGS_FILE_NAME,
+            STATIONS_SETTINGS_FILE_NAME,
+            CLIENT_SETTINGS_FILE_NAME,
+        ]
+        .into_iter()
+        .flat_map(|file_name| [config_dir.join(file_name), PathBuf::from(file_name)])
+        .collect()
+    }
 
-            let persisted_client_config = PersistedClientConfig::from(config.client.clone());
-            if let Err(err) = persisted_client_config.persist(config_dir, CLIENT_SETTINGS_FILE_NAME)
-            {
-                log::error!("Failed to persist migrated client config: {err}");
+    /// Validates all config layers, returning every issue found.
+    ///
+    /// In contrast to [`AppConfig::parse`], this does not stop at the first error, migrate or
+    /// persist anything, so it can safely be called at any time.
+    pub fn validate(config_dir: &Path) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+
+        let mut files = Self::config_files(config_dir)
+            .into_iter()
+            .filter_map(|path| read_config_file(&path, &mut issues))
+            .collect::<Vec<_>>();
+
+        let config = match Self::load(config_dir) {
+            Ok(config) => config,
+            Err(err) => {
+                issues.push(ConfigIssue::error(None, None, format!("{err:#}")));
+                return issues;
+            }
+        };
+
+        if let Some(extra_config_path) = &config.client.extra_stations_config {
+            let path = Path::new(extra_config_path);
+            if path.exists() {
+                files.extend(read_config_file(path, &mut issues));
+            } else {
+                issues.push(ConfigIssue::warning(
+                    None,
+                    Some("client.extra_stations_config".to_string()),
+                    format!("Extra stations config file {extra_config_path} does not exist"),
+                ));
             }
         }
 
-        Ok(config)
+        if let Some((field, message)) = config.client.invalid_field() {
+            issues.push(ConfigIssue::error(None, Some(field.to_string()), message));
+        }
+
+        let mut profile_names = config.stations.profiles.keys().collect::<Vec<_>>();
+        profile_names.sort();
+        for name in profile_names {
+            let profile = &config.stations.profiles[name];
+            // Attribute issues to the file with the highest precedence defining the profile
+            let file = files
+                .iter()
+                .rev()
+                .find(|(_, table)| {
+                    table
+                        .get("stations")
+                        .and_then(|v| v.get("profiles"))
+                        .and_then(|v| v.get(name.as_str()))
+                        .is_some()
+                })
+                .map(|(path, _)| path.display().to_string());
+
+            profile.validate_into(name, file.as_deref(), &mut issues);
+        }
+
+        issues
+    }
+}
+
+/// Key combo fields of the config, as paths into the config tables.
+const KEY_COMBO_FIELDS: [&[&str]; 3] = [
+    &["client", "transmit_config", "push_to_talk"],
+    &["client", "transmit_config", "push_to_mute"],
+    &["client", "transmit_config", "radio_push_to_talk"],
+];
+
+/// Key code fields of the config, as paths into the config tables.
+const KEY_CODE_FIELDS: [&[&str]; 6] = [
+    &["client", "keybinds", "accept_call"],
+    &["client", "keybinds", "end_call"],
+    &["client", "keybinds", "next_profile"],
+    &["client", "keybinds", "prev_profile"],
+    &["client", "keybinds", "toggle_do_not_disturb"],
+    &["client", "keybinds", "panic"],
+];
+
+/// Key sequence fields of the config, as paths into the config tables.
+const KEY_SEQUENCE_FIELDS: [&[&str]; 1] = [&["client", "radio", "audio_for_vatsim", "emit"]];
+
+#[derive(Debug, Clone, Copy)]
+enum KeyField {
+    Combo,
+    Code,
+    Sequence,
+}
+
+/// Reads and parses a single config file, recording syntax errors and unrecognized key codes.
+///
+/// Returns `None` if the file does not exist or could not be parsed.
+fn read_config_file(path: &Path, issues: &mut Vec<ConfigIssue>) -> Option<(PathBuf, toml::Table)> {
+    let file = Some(path.display().to_string());
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(err) => {
+            issues.push(ConfigIssue::error(
+                file,
+                None,
+                format!("Failed to read file: {err}"),
+            ));
+            return None;
+        }
+    };
+
+    let table = match content.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(err) => {
+            issues.push(ConfigIssue::error(
+                file,
+                None,
+                format!("Invalid TOML: {}", err.message()),
+            ));
+            return None;
+        }
+    };
+
+    let fields = KEY_COMBO_FIELDS
+        .iter()
+        .map(|field| (field, KeyField::Combo))
+        .chain(KEY_CODE_FIELDS.iter().map(|field| (field, KeyField::Code)))
+        .chain(
+            KEY_SEQUENCE_FIELDS
+                .iter()
+                .map(|field| (field, KeyField::Sequence)),
+        );
+    for (field, kind) in fields {
+        let value = field.split_first().and_then(|(first, rest)| {
+            rest.iter()
+                .try_fold(table.get(*first)?, |value, key| value.get(*key))
+        });
+        let code = match (value, kind) {
+            (Some(toml::Value::String(code)), KeyField::Combo) => {
+                code.parse::<KeyCombo>().is_err().then(|| code.clone())
+            }
+            (Some(toml::Value::String(code)), KeyField::Code) => {
+                code.parse::<Code>().is_err().then(|| code.clone())
+            }
+            (
+                Some(value @ (toml::Value::String(_) | toml::Value::Array(_))),
+                KeyField::Sequence,
+            ) => KeySequence::deserialize(value.clone())
+                .is_err()
+                .then(|| value.to_string()),
+            _ => None,
+        };
+        if let Some(code) = code {
+            issues.push(ConfigIssue::error(
+                file.clone(),
+                Some(field.join(".")),
+                format!("Unrecognized key code: {code}"),
+            ));
+        }
+    }
+
+    Some((path.to_path_buf(), table))
+}
+
+/// Severity of an issue found while validating the config.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum ConfigIssueSeverity {
+    /// The config can be loaded, but likely doesn't behave as intended.
+    Warning,
+    /// The config cannot be loaded.
+    Error,
+}
+
+/// An issue found while validating the config.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIssue {
+    pub severity: ConfigIssueSeverity,
+    /// Path of the config file the issue originates from, if it can be attributed to one.
+    pub file: Option<String>,
+    /// Dotted path of the affected field, if the issue is limited to a single field.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    pub fn error(file: Option<String>, field: Option<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: ConfigIssueSeverity::Error,
+            file,
+            field,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(
+        file: Option<String>,
+        field: Option<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity: ConfigIssueSeverity::Warning,
+            file,
+            field,
+            message: message.into(),
+        }
     }
 }
 
@@ -200,7 +422,7 @@ impl Default for BackendEndpointsConfigs {
             logout: "/auth/logout".to_string(),
             ws_token: "/ws/token".to_string(),
             terminate_ws_session: "/ws".to_string(),
-            version_update_check: "/version/update?version={{current_version}}&target={{target}}&arch={{arch}}&bundle_type={{bundle_type}}&channel={{channel}}".to_string(),
+            version_update_check: "/version/update?version={{current_version}}&target={{target}}&arch={{arch}}&bundle_type={{bundle_type}}&channel={{channel}}&cid={{cid}}".to_string(),
             ice_config: "/webrtc/ice-config".to_string(),
         }
     }
@@ -211,12 +433,57 @@ pub struct AudioConfig {
     pub host_name: Option<String>, // Name of audio backend host, None means default host
     pub input_device_name: Option<String>, // None means default device
     pub output_device_name: Option<String>, // None means default device
+    pub notification_output_device_name: Option<String>, // None means main output device
     pub input_device_volume: f32,
     pub input_device_volume_amp: f32,
     pub output_device_volume: f32,
     pub output_device_volume_amp: f32,
     pub click_volume: f32,
     pub chime_volume: f32,
+    /// Fill gaps caused by network jitter with low-level comfort noise instead of silence.
+    pub comfort_noise: bool,
+    /// Slowly adjust the gain of each peer so all of them are played back at a similar loudness.
+    pub normalize_rx_loudness: bool,
+    /// RMS level of far-end speech targeted by loudness normalization.
+    pub rx_loudness_target_dbfs: f32,
+    /// Stop transmitting audio while not speaking, peers fill the gaps with comfort noise.
+    pub opus_dtx: bool,
+    /// Output device channels (1-based) receiving call audio, None means all channels.
+    pub output_channel_map: Option<Vec<u16>>,
+    /// Resampler used for devices not running at 48 kHz, lower quality reduces latency and CPU usage.
+    pub resampler_quality: ResamplerQuality,
+    /// Name of a built-in sound pack or a directory in the `sounds` config directory.
+    pub sound_pack: String,
+    /// Duration of the fade-in and fade-out applied to ring, ringback and busy tones.
+    pub notification_fade_ms: u64,
+    /// Play a short tone after an outgoing call was rejected by the callee.
+    pub reject_tone: bool,
+    /// Play a short tone after an outgoing call was not answered in time.
+    pub no_answer_tone: bool,
+    /// Duration over which volume changes are ramped to avoid audible steps.
+    pub volume_ramp_ms: u64,
+    /// Warn once the audio input of a call was unmuted continuously for this many seconds,
+    /// `0` disables the warning. Only applies to push-to-talk transmit modes, as the input is
+    /// unmuted for the whole call otherwise.
+    pub long_transmit_warn_seconds: u64,
+    /// Play a click sound in addition to showing the long transmission warning.
+    pub long_transmit_cue: bool,
+    /// Ceiling (dBFS) of the soft limiter keeping the amplified input from clipping before
+    /// encoding, clamped to -6.0..=-0.1. Peaks are compressed from 3 dB below the ceiling.
+    pub input_limiter_ceiling_dbfs: f32,
+    /// Preferred channel count and sample rate of the input device, ignored if unsupported.
+    pub input_stream_hints: StreamConfigHints,
+    /// Preferred channel count and sample rate of the output and notification devices, ignored
+    /// if unsupported.
+    pub output_stream_hints: StreamConfigHints,
+    /// Keep playing call audio still buffered when a call ends instead of cutting it off, for at
+    /// most [`CALL_OUTPUT_DRAIN_WINDOW`].
+    pub drain_on_close: bool,
+    /// Number of times opening the output devices is retried on startup while they fail to open,
+    /// e.g. because the audio host is not ready yet right after boot.
+    pub device_open_retries: u8,
+    /// Delay between attempts to open the configured output devices on startup.
+    pub device_open_retry_delay_ms: u64,
 }
 
 impl Default for AudioConfig {
@@ -225,14 +492,60 @@ impl Default for AudioConfig {
             host_name: None,
             input_device_name: None,
             output_device_name: None,
+            notification_output_device_name: None,
             input_device_volume: 0.5,
             input_device_volume_amp: 4.0,
             output_device_volume: 0.5,
             output_device_volume_amp: 2.0,
             click_volume: 0.5,
             chime_volume: 0.5,
+            comfort_noise: true,
+            normalize_rx_loudness: false,
+            rx_loudness_target_dbfs: -20.0,
+            opus_dtx: false,
+            output_channel_map: None,
+            resampler_quality: ResamplerQuality::default(),
+            sound_pack: DEFAULT_SOUND_PACK.to_string(),
+            notification_fade_ms: 20,
+            reject_tone: true,
+            no_answer_tone: true,
+            volume_ramp_ms: 10,
+            long_transmit_warn_seconds: 60,
+            long_transmit_cue: false,
+            input_limiter_ceiling_dbfs: DEFAULT_INPUT_LIMITER_CEILING_DBFS,
+            input_stream_hints: StreamConfigHints::default(),
+            output_stream_hints: StreamConfigHints::default(),
+            drain_on_close: false,
+            device_open_retries: 3,
+            device_open_retry_delay_ms: 500,
+        }
+    }
+}
+
+impl AudioConfig {
+    /// Stream config hints used when opening a device of the given type.
+    pub fn stream_hints(&self, device_type: DeviceType) -> StreamConfigHints {
+        match device_type {
+            DeviceType::Input => self.input_stream_hints,
+            DeviceType::Output => self.output_stream_hints,
         }
     }
+
+    /// Returns the name of the preferred audio host if none of the available hosts matches it.
+    /// Devices are opened on the default host instead.
+    ///
+    /// The preferred host is kept in the config, so it is used again once it is available.
+    pub fn validate_host(&self, host_names: &[String]) -> Option<String> {
+        let host_name = self.host_name.as_deref().filter(|name| !name.is_empty())?;
+        if DeviceSelector::find_host_name(host_name, host_names).is_some() {
+            return None;
+        }
+
+        log::warn!(
+            "Preferred audio host {host_name} is not available (available hosts: {host_names:?}), falling back to default host"
+        );
+        Some(host_name.to_string())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Default)]
@@ -250,13 +563,28 @@ impl From<AudioConfig> for PersistedAudioConfig {
 pub struct ClientConfig {
     pub always_on_top: bool,
     pub fullscreen: bool,
+    /// Center the window on the primary monitor instead of restoring its last position.
+    #[serde(default)]
+    pub always_center_on_start: bool,
     pub position: Option<PhysicalPosition<i32>>,
     pub size: Option<PhysicalSize<u32>>,
+    /// Window position and size per monitor configuration, keyed by [`monitor_fingerprint`].
+    /// Configurations without a stored layout use `position` and `size` instead.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub window_layouts: HashMap<String, WindowLayout>,
     pub release_channel: ReleaseChannel,
     pub signaling_auto_reconnect: bool,
     pub transmit_config: TransmitConfig,
     pub radio: RadioConfig,
+    /// Time in seconds after which an accepted outgoing call whose connection has not been
+    /// established yet is hung up automatically, `0` disables it.
     pub auto_hangup_seconds: u64,
+    /// Time in seconds after which an outgoing call invite the peer neither accepted nor
+    /// rejected is cancelled, `0` disables it.
+    pub outgoing_invite_timeout_seconds: u64,
+    /// Maximum number of simultaneous incoming calls, any further call invites are rejected
+    /// automatically. Must be at least 1.
+    pub max_incoming_calls: usize,
     /// List of peer IDs (CIDs) that should be ignored by the client.
     ///
     /// Any incoming calls initiated by a CID in this list will be silently ignored
@@ -264,10 +592,44 @@ pub struct ClientConfig {
     /// parties as the (local) user can still actively initiate calls to them.
     #[serde(default, skip_serializing_if = "HashSet::is_empty")]
     pub ignored: HashSet<String>,
+    /// List of peer IDs (CIDs) whose incoming calls are accepted automatically.
+    ///
+    /// Calls are only auto-answered while there is no active or outgoing call, otherwise
+    /// they ring like any other incoming call.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub auto_answer: HashSet<String>,
+    /// Receive gain per peer ID (CID), applied to the call audio whenever a call with that peer
+    /// starts. Peers without an entry use [`DEFAULT_PEER_VOLUME`].
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub peer_volumes: HashMap<String, f32>,
     pub extra_stations_config: Option<String>,
     pub selected_stations_profile: String,
     #[serde(default)]
     pub keybinds: KeybindsConfig,
+    #[serde(default)]
+    pub signaling_reconnect: SignalingReconnectConfig,
+    /// Whether to re-invite the peer of the active call after the signaling connection
+    /// was lost and automatically re-established.
+    #[serde(default)]
+    pub reconnect_rejoin: bool,
+    /// Interval between round-trip time measurements to the signaling server in seconds,
+    /// `0` disables them.
+    pub signaling_latency_interval_secs: u64,
+    /// Log level of the vacs crates (`off`, `error`, `warn`, `info`, `debug` or `trace`),
+    /// adjustable at runtime to capture detailed logs on demand.
+    pub log_level: String,
+    /// DSCP value outgoing call audio is marked with (e.g. `46` for Expedited Forwarding),
+    /// `None` disables marking. Only applied where supported by the platform.
+    #[serde(default)]
+    pub media_dscp: Option<u8>,
+    /// Maximum time in milliseconds the local SDP waits for ICE gathering to complete before
+    /// proceeding with the candidates gathered so far, `0` sends it right away and relies on
+    /// trickling all candidates.
+    #[serde(default)]
+    pub ice_gathering_timeout_ms: u64,
+    /// Audio codec sent during calls. Anything other than Opus is only meant for interop testing.
+    #[serde(default)]
+    pub call_codec: Codec,
 }
 
 impl Default for ClientConfig {
@@ -275,24 +637,135 @@ impl Default for ClientConfig {
         Self {
             always_on_top: false,
             fullscreen: false,
+            always_center_on_start: false,
             position: None,
             size: None,
+            window_layouts: HashMap::new(),
             release_channel: ReleaseChannel::default(),
             signaling_auto_reconnect: true,
             transmit_config: TransmitConfig::default(),
             radio: RadioConfig::default(),
             auto_hangup_seconds: 60,
+            outgoing_invite_timeout_seconds: 30,
+            max_incoming_calls: 5,
             ignored: HashSet::new(),
+            auto_answer: HashSet::new(),
+            peer_volumes: HashMap::new(),
             extra_stations_config: None,
             selected_stations_profile: "Default".to_string(),
             keybinds: KeybindsConfig::default(),
+            signaling_reconnect: SignalingReconnectConfig::default(),
+            reconnect_rejoin: false,
+            signaling_latency_interval_secs: 10,
+            log_level: "trace".to_string(),
+            media_dscp: None,
+            ice_gathering_timeout_ms: 0,
+            call_codec: Codec::default(),
         }
     }
 }
 
 impl ClientConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match self.invalid_field() {
+            Some((_, message)) => Err(anyhow::anyhow!(message)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the path of the first invalid field along with a description of the issue.
+    fn invalid_field(&self) -> Option<(&'static str, String)> {
+        if self.max_incoming_calls == 0 {
+            return Some((
+                "client.max_incoming_calls",
+                "max_incoming_calls must be at least 1".to_string(),
+            ));
+        }
+        if let Err(err) = parse_log_level(&self.log_level) {
+            return Some(("client.log_level", err.to_string()));
+        }
+        if let Some(dscp) = self.media_dscp
+            && dscp > MAX_DSCP
+        {
+            return Some((
+                "client.media_dscp",
+                format!("media_dscp must be between 0 and {MAX_DSCP}"),
+            ));
+        }
+        if !SIGNALING_RECONNECT_ATTEMPTS_RANGE.contains(&self.signaling_reconnect.max_attempts) {
+            return Some((
+                "client.signaling_reconnect.max_attempts",
+                format!(
+                    "signaling_reconnect.max_attempts must be between {} and {}",
+                    SIGNALING_RECONNECT_ATTEMPTS_RANGE.start(),
+                    SIGNALING_RECONNECT_ATTEMPTS_RANGE.end()
+                ),
+            ));
+        }
+        None
+    }
+
+    pub fn peer_volume(&self, peer_id: &str) -> f32 {
+        self.peer_volumes
+            .get(peer_id)
+            .copied()
+            .unwrap_or(DEFAULT_PEER_VOLUME)
+    }
+
+    /// Stores the receive gain for the given peer, returning the value actually stored after
+    /// clamping. Peers reset to the default volume are removed from the map.
+    pub fn set_peer_volume(&mut self, peer_id: String, volume: f32) -> f32 {
+        let volume = if volume.is_nan() {
+            DEFAULT_PEER_VOLUME
+        } else {
+            volume.clamp(0.0, MAX_PEER_VOLUME)
+        };
+
+        if volume == DEFAULT_PEER_VOLUME {
+            self.peer_volumes.remove(&peer_id);
+        } else {
+            self.peer_volumes.insert(peer_id, volume);
+        }
+        volume
+    }
+
     pub fn max_signaling_reconnect_attempts(&self) -> u8 {
-        if self.signaling_auto_reconnect { 8 } else { 0 }
+        if self.signaling_auto_reconnect {
+            self.signaling_reconnect.max_attempts
+        } else {
+            0
+        }
+    }
+
+    pub fn signaling_reconnect_config(&self) -> ReconnectConfig {
+        ReconnectConfig {
+            max_tries: self.max_signaling_reconnect_attempts(),
+            ..ReconnectConfig::from(&self.signaling_reconnect)
+        }
+    }
+
+    /// Returns the configured log level, falling back to the most verbose level if invalid.
+    pub fn log_level(&self) -> LevelFilter {
+        parse_log_level(&self.log_level).unwrap_or(LevelFilter::Trace)
+    }
+
+    pub fn signaling_latency_interval(&self) -> Option<Duration> {
+        (self.signaling_latency_interval_secs > 0)
+            .then(|| Duration::from_secs(self.signaling_latency_interval_secs))
+    }
+
+    pub fn outgoing_invite_timeout(&self) -> Option<Duration> {
+        (self.outgoing_invite_timeout_seconds > 0)
+            .then(|| Duration::from_secs(self.outgoing_invite_timeout_seconds))
+    }
+
+    pub fn ice_gathering_timeout(&self) -> Option<Duration> {
+        (self.ice_gathering_timeout_ms > 0)
+            .then(|| Duration::from_millis(self.ice_gathering_timeout_ms))
+    }
+
+    pub fn auto_hangup_timeout(&self) -> Option<Duration> {
+        (self.auto_hangup_seconds > 0).then(|| Duration::from_secs(self.auto_hangup_seconds))
     }
 
     pub fn default_window_size<P>(provider: &P) -> Result<PhysicalSize<u32>, Error>
@@ -327,9 +800,18 @@ impl ClientConfig {
         }
 
         let position = window.position()?;
+        let fingerprint = monitor_fingerprint(
+            window
+                .available_monitors()
+                .context("Failed to get available monitors")?
+                .iter()
+                .map(|m| (m.name().map(String::as_str), *m.size())),
+        );
 
         self.position = Some(position);
         self.size = Some(size);
+        self.window_layouts
+            .insert(fingerprint, WindowLayout { position, size });
 
         log::debug!(
             "Updating window position to {:?} and size to {:?}",
@@ -339,23 +821,44 @@ impl ClientConfig {
         Ok(())
     }
 
-    pub fn restore_window_state<P>(&self, provider: &P) -> Result<(), Error>
+    /// Window position and size stored for the given monitor configuration, falling back to the
+    /// last stored position and size if the configuration is unknown.
+    pub fn window_layout(
+        &self,
+        fingerprint: &str,
+    ) -> (Option<PhysicalPosition<i32>>, Option<PhysicalSize<u32>>) {
+        match self.window_layouts.get(fingerprint) {
+            Some(layout) => (Some(layout.position), Some(layout.size)),
+            None => (self.position, self.size),
+        }
+    }
+
+    /// Restores the saved window position and size. With `always_center_on_start`, the window is
+    /// centered instead of restoring its position, but only if `startup` is set.
+    pub fn restore_window_state<P>(&self, provider: &P, startup: bool) -> Result<(), Error>
     where
         P: WindowProvider + ?Sized,
     {
+        let center = startup && self.always_center_on_start;
         let window = provider.window()?;
+        let monitors = window
+            .available_monitors()
+            .context("Failed to get available monitors")?;
+        let fingerprint = monitor_fingerprint(
+            monitors
+                .iter()
+                .map(|m| (m.name().map(String::as_str), *m.size())),
+        );
+        let (position, size) = self.window_layout(&fingerprint);
 
         log::debug!(
-            "Restoring window position to {:?} and size to {:?}",
-            self.position,
-            self.size
+            "Restoring window position to {position:?} and size to {size:?} for monitor configuration {fingerprint:?}"
         );
 
-        if let Some(position) = self.position {
-            for m in window
-                .available_monitors()
-                .context("Failed to get available monitors")?
-            {
+        if center {
+            log::debug!("Always centering window, skipping position restoration");
+        } else if let Some(position) = position {
+            for m in monitors {
                 let PhysicalPosition { x, y } = *m.position();
                 let PhysicalSize { width, height } = *m.size();
 
@@ -364,7 +867,7 @@ impl ClientConfig {
                 let top = y;
                 let bottom = y + height as i32;
 
-                let size = self.size.unwrap_or(Self::default_window_size(&window)?);
+                let size = size.unwrap_or(Self::default_window_size(&window)?);
 
                 let intersects = [
                     (position.x, position.y),
@@ -387,7 +890,7 @@ impl ClientConfig {
             }
         }
 
-        if let Some(mut size) = self.size {
+        if let Some(mut size) = size {
             if size.width == 0 || size.height == 0 {
                 log::warn!("Window size {size:?} is 0, restoring default size");
                 size = Self::default_window_size(&window)?;
@@ -434,6 +937,35 @@ impl ClientConfig {
             }
         }
 
+        if center {
+            Self::center_window(&window)?;
+        }
+
+        Ok(())
+    }
+
+    fn center_window(window: &WebviewWindow) -> Result<(), Error> {
+        let Some(monitor) = window
+            .primary_monitor()
+            .context("Failed to get primary monitor")?
+            .or(window
+                .current_monitor()
+                .context("Failed to get current monitor")?)
+        else {
+            log::warn!("No monitor found, cannot center window");
+            return Ok(());
+        };
+
+        let work_area = monitor.work_area();
+        let size = window
+            .outer_size()
+            .context("Failed to get outer window size")?;
+        let position = centered_position(work_area.position, work_area.size, size);
+        log::debug!("Centering window at {position:?} in work area {work_area:?}");
+
+        window
+            .set_position(position)
+            .context("Failed to set main window position")?;
         Ok(())
     }
 }
@@ -445,6 +977,89 @@ pub enum TransmitMode {
     PushToTalk,
     PushToMute,
     RadioIntegration,
+    PushToTalkToggle,
+    PushToMuteToggle,
+}
+
+impl TransmitMode {
+    /// Whether a key press latches the transmit state until the next press, instead of
+    /// requiring the key to be held.
+    pub fn is_toggle(&self) -> bool {
+        matches!(self, Self::PushToTalkToggle | Self::PushToMuteToggle)
+    }
+
+    /// Whether the input is only unmuted while transmitting, instead of being open by default.
+    pub fn is_push_to_talk(&self) -> bool {
+        matches!(
+            self,
+            Self::PushToTalk | Self::PushToTalkToggle | Self::RadioIntegration
+        )
+    }
+}
+
+/// Backoff and rate limiting applied when automatically reconnecting to the signaling server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SignalingReconnectConfig {
+    /// Number of attempts per reconnect before giving up, only used if
+    /// `signaling_auto_reconnect` is enabled.
+    pub max_attempts: u8,
+    /// Base delay between reconnect attempts in milliseconds, doubled after each failed attempt.
+    pub retry_base_ms: u64,
+    /// Maximum delay between reconnect attempts in milliseconds.
+    pub retry_cap_ms: u64,
+    /// Number of reconnects allowed within `window_seconds` before reconnecting is paused.
+    pub max_in_window: u32,
+    /// Sliding window in which reconnects are counted, in seconds.
+    pub window_seconds: u64,
+    /// Duration reconnecting is paused once `max_in_window` has been reached, in seconds.
+    pub cooldown_seconds: u64,
+    /// Whether messages sent while reconnecting, e.g. ending a call, are delivered once
+    /// reconnected instead of being lost.
+    pub offline_queue: bool,
+    /// Maximum number of messages held while reconnecting.
+    pub offline_queue_max_len: usize,
+    /// Maximum time a message is held while reconnecting before it is dropped, in milliseconds.
+    pub offline_queue_max_age_ms: u64,
+}
+
+impl Default for SignalingReconnectConfig {
+    fn default() -> Self {
+        ReconnectConfig::default().into()
+    }
+}
+
+impl From<ReconnectConfig> for SignalingReconnectConfig {
+    fn from(config: ReconnectConfig) -> Self {
+        Self {
+            max_attempts: config.max_tries,
+            retry_base_ms: config.retry_base.as_millis() as u64,
+            retry_cap_ms: config.retry_cap.as_millis() as u64,
+            max_in_window: config.gate_max_in_window,
+            window_seconds: config.gate_window.as_secs(),
+            cooldown_seconds: config.gate_cooldown.as_secs(),
+            offline_queue: config.offline_queue,
+            offline_queue_max_len: config.offline_queue_max_len,
+            offline_queue_max_age_ms: config.offline_queue_max_age.as_millis() as u64,
+        }
+    }
+}
+
+impl From<&SignalingReconnectConfig> for ReconnectConfig {
+    fn from(config: &SignalingReconnectConfig) -> Self {
+        Self {
+            max_tries: config.max_attempts,
+            retry_base: Duration::from_millis(config.retry_base_ms),
+            retry_cap: Duration::from_millis(config.retry_cap_ms),
+            gate_max_in_window: config.max_in_window.max(1),
+            gate_window: Duration::from_secs(config.window_seconds),
+            gate_cooldown: Duration::from_secs(config.cooldown_seconds),
+            offline_queue: config.offline_queue,
+            offline_queue_max_len: config.offline_queue_max_len,
+            offline_queue_max_age: Duration::from_millis(config.offline_queue_max_age_ms),
+            ..Default::default()
+        }
+    }
 }
 
 /// Configuration for the transmission mode and associated keybinds.
@@ -452,15 +1067,27 @@ pub enum TransmitMode {
 pub struct TransmitConfig {
     /// The transmit mode to use.
     pub mode: TransmitMode,
-    /// Key code for Push-to-Talk mode.
-    /// Required if mode is `PushToTalk`.
-    pub push_to_talk: Option<Code>,
-    /// Key code for Push-to-Mute mode.
-    /// Required if mode is `PushToMute`.
-    pub push_to_mute: Option<Code>,
-    /// Key code for Radio Integration PTT.
+    /// Key (combo) for Push-to-Talk mode.
+    /// Required if mode is `PushToTalk` or `PushToTalkToggle`.
+    pub push_to_talk: Option<KeyCombo>,
+    /// Key (combo) for Push-to-Mute mode.
+    /// Required if mode is `PushToMute` or `PushToMuteToggle`.
+    pub push_to_mute: Option<KeyCombo>,
+    /// Key (combo) for Radio Integration PTT.
     /// Required if mode is `RadioIntegration`.
-    pub radio_push_to_talk: Option<Code>,
+    pub radio_push_to_talk: Option<KeyCombo>,
+}
+
+impl TransmitConfig {
+    /// Returns the key (combo) used by the configured transmit mode, if any.
+    pub fn active_combo(&self) -> Option<&KeyCombo> {
+        match self.mode {
+            TransmitMode::VoiceActivation => None,
+            TransmitMode::PushToTalk | TransmitMode::PushToTalkToggle => self.push_to_talk.as_ref(),
+            TransmitMode::PushToMute | TransmitMode::PushToMuteToggle => self.push_to_mute.as_ref(),
+            TransmitMode::RadioIntegration => self.radio_push_to_talk.as_ref(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -492,40 +1119,70 @@ impl TryFrom<FrontendTransmitConfig> for TransmitConfig {
             push_to_talk: value
                 .push_to_talk
                 .as_ref()
-                .map(|s| s.parse::<Code>())
+                .map(|s| s.parse::<KeyCombo>())
                 .transpose()
                 .map_err(|_| Error::Other(Box::new(anyhow::anyhow!("Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.", value.push_to_talk.unwrap_or_default()))))?,
             push_to_mute: value
                 .push_to_mute
                 .as_ref()
-                .map(|s| s.parse::<Code>())
+                .map(|s| s.parse::<KeyCombo>())
                 .transpose()
                 .map_err(|_| Error::Other(Box::new(anyhow::anyhow!("Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.", value.push_to_mute.unwrap_or_default()))))?,
             radio_push_to_talk: value
                 .radio_push_to_talk
                 .as_ref()
-                .map(|s| s.parse::<Code>())
+                .map(|s| s.parse::<KeyCombo>())
                 .transpose()
                 .map_err(|_| Error::Other(Box::new(anyhow::anyhow!("Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.", value.radio_push_to_talk.unwrap_or_default()))))?,
         })
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RadioConfig {
     pub integration: RadioIntegration,
     pub audio_for_vatsim: Option<AudioForVatsimRadioConfig>,
     pub track_audio: Option<TrackAudioRadioConfig>,
+    /// Maximum duration of a single radio transmission in seconds, after which it is forcibly
+    /// ended in case the release of the transmit key was missed. `0` disables the limit.
+    pub max_transmit_duration_secs: u64,
+}
+
+impl Default for RadioConfig {
+    fn default() -> Self {
+        Self {
+            integration: RadioIntegration::default(),
+            audio_for_vatsim: None,
+            track_audio: None,
+            max_transmit_duration_secs: DEFAULT_MAX_RADIO_TRANSMIT_DURATION_SECS,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AudioForVatsimRadioConfig {
-    pub emit: Option<Code>,
+    /// Keys emitted while transmitting, either a single code or an ordered list of codes pressed
+    /// together.
+    pub emit: Option<KeySequence>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TrackAudioRadioConfig {
     pub endpoint: Option<String>,
+    /// Interval in which the connection to TrackAudio is probed to detect it silently going away,
+    /// in milliseconds. `0` disables the heartbeat.
+    pub heartbeat_interval_ms: u64,
+}
+
+impl Default for TrackAudioRadioConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            heartbeat_interval_ms: DEFAULT_TRACK_AUDIO_HEARTBEAT_INTERVAL_MS,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -534,6 +1191,8 @@ pub struct FrontendRadioConfig {
     pub integration: RadioIntegration,
     pub audio_for_vatsim: Option<FrontendAudioForVatsimRadioConfig>,
     pub track_audio: Option<FrontendTrackAudioRadioConfig>,
+    #[serde(default)]
+    pub max_transmit_duration_secs: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -546,6 +1205,8 @@ pub struct FrontendAudioForVatsimRadioConfig {
 #[serde(rename_all = "camelCase")]
 pub struct FrontendTrackAudioRadioConfig {
     pub endpoint: Option<String>,
+    #[serde(default)]
+    pub heartbeat_interval_ms: Option<u64>,
 }
 
 impl RadioConfig {
@@ -559,18 +1220,22 @@ impl RadioConfig {
     /// inject key presses into external applications. This works on Windows and macOS, but NOT
     /// on Linux where the emitter is a no-op stub due to Wayland's security model.
     ///
-    /// On Linux, this method will successfully create a radio instance, but it will
-    /// silently do nothing when `transmit()` is called.
+    /// On platforms without a keybind emitter, this method returns
+    /// [`RadioError::Unavailable`](crate::radio::RadioError::Unavailable)
+    /// instead of creating a radio instance that would silently do nothing.
     ///
     /// The TrackAudio integration is not affected by this platform limitation and is thus the
     /// default radio implementation for Linux.
     pub async fn radio(&self, app: AppHandle) -> Result<Option<DynRadio>, Error> {
         match self.integration {
             RadioIntegration::AudioForVatsim => {
+                self.integration
+                    .check_supported(Capabilities::get())
+                    .map_err(Error::from)?;
                 let Some(config) = self.audio_for_vatsim.as_ref() else {
                     return Ok(None);
                 };
-                let Some(emit) = config.emit else {
+                let Some(emit) = config.emit.clone() else {
                     return Ok(None);
                 };
                 log::debug!("Initializing AudioForVatsim radio integration");
@@ -579,8 +1244,17 @@ impl RadioConfig {
             }
             RadioIntegration::TrackAudio => {
                 let endpoint = self.track_audio.as_ref().and_then(|c| c.endpoint.as_ref());
-                log::debug!("Initializing TrackAudio radio integration (endpoint: {endpoint:?})");
-                let radio = TrackAudioRadio::new(app, endpoint)
+                let heartbeat_interval = Duration::from_millis(
+                    self.track_audio
+                        .as_ref()
+                        .map_or(DEFAULT_TRACK_AUDIO_HEARTBEAT_INTERVAL_MS, |c| {
+                            c.heartbeat_interval_ms
+                        }),
+                );
+                log::debug!(
+                    "Initializing TrackAudio radio integration (endpoint: {endpoint:?}, heartbeat interval: {heartbeat_interval:?})"
+                );
+                let radio = TrackAudioRadio::new(app, endpoint, heartbeat_interval)
                     .await
                     .map_err(Error::from)?;
                 Ok(Some(Arc::new(radio)))
@@ -595,6 +1269,7 @@ impl From<RadioConfig> for FrontendRadioConfig {
             integration: radio_integration.integration,
             audio_for_vatsim: radio_integration.audio_for_vatsim.map(|c| c.into()),
             track_audio: radio_integration.track_audio.map(|c| c.into()),
+            max_transmit_duration_secs: Some(radio_integration.max_transmit_duration_secs),
         }
     }
 }
@@ -602,7 +1277,7 @@ impl From<RadioConfig> for FrontendRadioConfig {
 impl From<AudioForVatsimRadioConfig> for FrontendAudioForVatsimRadioConfig {
     fn from(value: AudioForVatsimRadioConfig) -> Self {
         Self {
-            emit: value.emit.map(|c| c.to_string()),
+            emit: value.emit.map(|keys| keys.to_string()),
         }
     }
 }
@@ -611,6 +1286,7 @@ impl From<TrackAudioRadioConfig> for FrontendTrackAudioRadioConfig {
     fn from(value: TrackAudioRadioConfig) -> Self {
         Self {
             endpoint: value.endpoint,
+            heartbeat_interval_ms: Some(value.heartbeat_interval_ms),
         }
     }
 }
@@ -623,6 +1299,9 @@ impl TryFrom<FrontendRadioConfig> for RadioConfig {
             integration: value.integration,
             audio_for_vatsim: value.audio_for_vatsim.map(|c| c.try_into()).transpose()?,
             track_audio: value.track_audio.map(|c| c.try_into()).transpose()?,
+            max_transmit_duration_secs: value
+                .max_transmit_duration_secs
+                .unwrap_or(DEFAULT_MAX_RADIO_TRANSMIT_DURATION_SECS),
         })
     }
 }
@@ -635,7 +1314,7 @@ impl TryFrom<FrontendAudioForVatsimRadioConfig> for AudioForVatsimRadioConfig {
             emit: value
                 .emit
                 .as_ref()
-                .map(|s| s.parse::<Code>())
+                .map(|s| s.parse::<KeySequence>())
                 .transpose()
                 .map_err(|_| Error::Other(Box::new(anyhow::anyhow!("Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.", value.emit.unwrap_or_default()))))?,
         })
@@ -648,6 +1327,9 @@ impl TryFrom<FrontendTrackAudioRadioConfig> for TrackAudioRadioConfig {
     fn try_from(value: FrontendTrackAudioRadioConfig) -> Result<Self, Self::Error> {
         Ok(Self {
             endpoint: value.endpoint,
+            heartbeat_interval_ms: value
+                .heartbeat_interval_ms
+                .unwrap_or(DEFAULT_TRACK_AUDIO_HEARTBEAT_INTERVAL_MS),
         })
     }
 }
@@ -662,6 +1344,15 @@ pub struct KeybindsConfig {
     pub accept_call: Option<Code>,
     /// Key code to end an active call.
     pub end_call: Option<Code>,
+    /// Key code to switch to the next stations profile (in alphabetical order).
+    pub next_profile: Option<Code>,
+    /// Key code to switch to the previous stations profile (in alphabetical order).
+    pub prev_profile: Option<Code>,
+    /// Key code to toggle do-not-disturb mode, automatically rejecting all incoming calls.
+    pub toggle_do_not_disturb: Option<Code>,
+    /// Key code to instantly cut all audio: mutes the input and the active call, stops all
+    /// notification sounds and ends any transmission. The call itself is kept.
+    pub panic: Option<Code>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -669,6 +1360,10 @@ pub struct KeybindsConfig {
 pub struct FrontendKeybindsConfig {
     pub accept_call: Option<String>,
     pub end_call: Option<String>,
+    pub next_profile: Option<String>,
+    pub prev_profile: Option<String>,
+    pub toggle_do_not_disturb: Option<String>,
+    pub panic: Option<String>,
 }
 
 impl From<KeybindsConfig> for FrontendKeybindsConfig {
@@ -676,6 +1371,10 @@ impl From<KeybindsConfig> for FrontendKeybindsConfig {
         Self {
             accept_call: config.accept_call.map(|c| c.to_string()),
             end_call: config.end_call.map(|c| c.to_string()),
+            next_profile: config.next_profile.map(|c| c.to_string()),
+            prev_profile: config.prev_profile.map(|c| c.to_string()),
+            toggle_do_not_disturb: config.toggle_do_not_disturb.map(|c| c.to_string()),
+            panic: config.panic.map(|c| c.to_string()),
         }
     }
 }
@@ -697,10 +1396,75 @@ impl TryFrom<FrontendKeybindsConfig> for KeybindsConfig {
                 .map(|s| s.parse::<Code>())
                 .transpose()
                 .map_err(|_| Error::Other(Box::new(anyhow::anyhow!("Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.", value.end_call.unwrap_or_default()))))?,
+            next_profile: value
+                .next_profile
+                .as_ref()
+                .map(|s| s.parse::<Code>())
+                .transpose()
+                .map_err(|_| Error::Other(Box::new(anyhow::anyhow!("Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.", value.next_profile.unwrap_or_default()))))?,
+            prev_profile: value
+                .prev_profile
+                .as_ref()
+                .map(|s| s.parse::<Code>())
+                .transpose()
+                .map_err(|_| Error::Other(Box::new(anyhow::anyhow!("Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.", value.prev_profile.unwrap_or_default()))))?,
+            toggle_do_not_disturb: value
+                .toggle_do_not_disturb
+                .as_ref()
+                .map(|s| s.parse::<Code>())
+                .transpose()
+                .map_err(|_| Error::Other(Box::new(anyhow::anyhow!("Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.", value.toggle_do_not_disturb.unwrap_or_default()))))?,
+            panic: value
+                .panic
+                .as_ref()
+                .map(|s| s.parse::<Code>())
+                .transpose()
+                .map_err(|_| Error::Other(Box::new(anyhow::anyhow!("Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.", value.panic.unwrap_or_default()))))?,
         })
     }
 }
 
+/// Window position and size stored for a single monitor configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowLayout {
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+}
+
+/// Position centering a window of the given outer size in a monitor's work area. Windows larger
+/// than the work area are aligned to its top-left corner, keeping the title bar reachable.
+pub fn centered_position(
+    work_area_position: PhysicalPosition<i32>,
+    work_area_size: PhysicalSize<u32>,
+    window_size: PhysicalSize<u32>,
+) -> PhysicalPosition<i32> {
+    let offset = |area: u32, window: u32| (area.saturating_sub(window) / 2) as i32;
+    PhysicalPosition::new(
+        work_area_position.x + offset(work_area_size.width, window_size.width),
+        work_area_position.y + offset(work_area_size.height, window_size.height),
+    )
+}
+
+/// Identifies a monitor configuration by the names and resolutions of all connected monitors,
+/// independent of the order they are reported in.
+pub fn monitor_fingerprint<'a>(
+    monitors: impl IntoIterator<Item = (Option<&'a str>, PhysicalSize<u32>)>,
+) -> String {
+    let mut monitors = monitors
+        .into_iter()
+        .map(|(name, size)| {
+            format!(
+                "{}@{}x{}",
+                name.unwrap_or("unknown"),
+                size.width,
+                size.height
+            )
+        })
+        .collect::<Vec<_>>();
+    monitors.sort();
+    monitors.join(",")
+}
+
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct PersistedClientConfig {
     pub client: ClientConfig,
@@ -723,6 +1487,42 @@ pub struct StationsConfig {
     pub profiles: HashMap<String, StationsProfileConfig>,
 }
 
+impl StationsConfig {
+    /// Validates all profiles, ensuring every station pattern compiles using the syntax
+    /// configured for its profile.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, profile) in &self.profiles {
+            profile
+                .validate_patterns()
+                .with_context(|| format!("Invalid patterns in stations profile {name}"))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the profile following (or preceding) `current` in alphabetical order, wrapping
+    /// around at the ends.
+    ///
+    /// Returns `None` if there is no other profile to switch to. If `current` doesn't exist,
+    /// the first (or last) profile is returned.
+    pub fn cycle_profile(&self, current: &str, forward: bool) -> Option<String> {
+        if self.profiles.len() < 2 {
+            return None;
+        }
+
+        let mut names = self.profiles.keys().collect::<Vec<_>>();
+        names.sort();
+
+        let next = match names.iter().position(|name| *name == current) {
+            Some(index) if forward => (index + 1) % names.len(),
+            Some(index) => (index + names.len() - 1) % names.len(),
+            None if forward => 0,
+            None => names.len() - 1,
+        };
+
+        Some(names[next].clone())
+    }
+}
+
 impl Default for StationsConfig {
     fn default() -> Self {
         let mut profiles = HashMap::new();
@@ -734,6 +1534,17 @@ impl Default for StationsConfig {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct PersistedStationsConfig {
+    pub stations: StationsConfig,
+}
+
+impl From<StationsConfig> for PersistedStationsConfig {
+    fn from(stations: StationsConfig) -> Self {
+        Self { stations }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FrontendStationsConfig {
@@ -768,6 +1579,16 @@ pub enum FrequencyDisplayMode {
     HideAll,
 }
 
+/// Format in which frequencies are displayed on DA keys.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub enum FrequencyFormat {
+    /// Frequency in MHz with three decimals, e.g. `123.450` (default).
+    #[default]
+    ThreeDecimal,
+    /// 8.33 kHz channel name, e.g. `123.455` for `123.450`.
+    Channel8_33,
+}
+
 /// Mode for controlling how DA keys are grouped.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub enum StationsGroupMode {
@@ -780,6 +1601,65 @@ pub enum StationsGroupMode {
     FirAndIcao,
     /// Group by the first four letters (ICAO code) of the display name.
     Icao,
+    /// Group by the first N letters of the display name, configured as `{ Prefix = 3 }`.
+    Prefix(usize),
+}
+
+/// Group containing all stations without an underscore-separated display name.
+pub const OTHER_STATIONS_GROUP: &str = "OTHER";
+
+impl StationsGroupMode {
+    /// Returns the innermost group of a station, matching the filter values used by the stations
+    /// list (e.g. `LO` for FIR, `LOWW` for ICAO groups).
+    ///
+    /// Groups are taken from the part of the display name before the first underscore, stations
+    /// with a shorter prefix than the group length form their own group.
+    pub fn group_of<'a>(&self, display_name: &'a str) -> Option<&'a str> {
+        let len = match *self {
+            StationsGroupMode::None => return None,
+            StationsGroupMode::Fir => 2,
+            StationsGroupMode::Icao | StationsGroupMode::FirAndIcao => 4,
+            StationsGroupMode::Prefix(len) => len,
+        };
+
+        let Some((prefix, _)) = display_name.split_once('_') else {
+            return Some(OTHER_STATIONS_GROUP);
+        };
+        Some(
+            prefix
+                .char_indices()
+                .nth(len)
+                .map_or(prefix, |(i, _)| &prefix[..i]),
+        )
+    }
+}
+
+/// Syntax used to interpret the `include`, `exclude` and `priority` patterns of a stations profile.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub enum PatternSyntax {
+    /// Glob-like patterns supporting `*` and `?` wildcards, always matching the whole callsign.
+    #[default]
+    Glob,
+    /// Full regular expressions. Patterns are not anchored implicitly, use `^` and `$` to match
+    /// the whole callsign.
+    Regex,
+}
+
+impl PatternSyntax {
+    /// Compiles the given pattern into a case-insensitive regular expression.
+    pub fn compile(&self, pattern: &str) -> Result<Regex, regex::Error> {
+        let pattern = match self {
+            PatternSyntax::Glob => format!(
+                "^{}$",
+                regex::escape(pattern)
+                    .replace(r"\*", ".*")
+                    .replace(r"\?", ".")
+            ),
+            PatternSyntax::Regex => pattern.to_string(),
+        };
+
+        RegexBuilder::new(&pattern).case_insensitive(true).build()
+    }
 }
 
 /// Config profile for how stations are filtered, prioritized and displayed.
@@ -791,6 +1671,7 @@ pub struct StationsProfileConfig {
     /// - If this list is not empty, only stations matching at least one pattern are eligible to be shown.
     ///
     /// Glob syntax is supported: `"LO*"`, `"LOWW_*"`, `"*_APP"`, …
+    /// Regular expressions can be used instead by setting `pattern_syntax` to `Regex`.
     /// Matching is case-insensitive.
     ///
     /// Example:
@@ -803,6 +1684,7 @@ pub struct StationsProfileConfig {
     /// - Stations matching any pattern here are never shown, even if they match an `include` rule.
     ///
     /// Glob syntax is supported: `"LO*"`, `"LOWW_*"`, `"*_APP"`, …
+    /// Regular expressions can be used instead by setting `pattern_syntax` to `Regex`.
     /// Matching is case-insensitive.
     ///
     /// Example:
@@ -816,6 +1698,7 @@ pub struct StationsProfileConfig {
     /// priority bucket. Earlier entries = higher priority.
     ///
     /// Glob syntax is supported: `"LO*"`, `"LOWW_*"`, `"*_APP"`, …
+    /// Regular expressions can be used instead by setting `pattern_syntax` to `Regex`.
     /// Matching is case-insensitive.
     ///
     /// Example:
@@ -823,6 +1706,17 @@ pub struct StationsProfileConfig {
     #[serde(default)]
     pub priority: Vec<String>,
 
+    /// Optional ordered list of callsign patterns of stations pinned to the top.
+    ///
+    /// Stations matching any pattern here are displayed above all `priority` buckets, ordered by
+    /// the *first* matching pattern. Like `priority`, patterns are matched against the aliased
+    /// display name and only apply to stations remaining after `include`/`exclude` filtering.
+    ///
+    /// Example:
+    ///   `["LOWW_APP", "LOVV_CTR"]`
+    #[serde(default)]
+    pub favorites: Vec<String>,
+
     /// Optional alias mapping of frequencies to custom display names.
     ///
     /// - If a station's frequency matches a key in this map, the corresponding display name will be
@@ -846,6 +1740,20 @@ pub struct StationsProfileConfig {
     #[serde(default)]
     pub aliases: HashMap<String, String>,
 
+    /// Optional alias mapping of CIDs to custom display names.
+    ///
+    /// - Takes precedence over frequency `aliases`, allowing to alias a specific controller
+    ///   regardless of the frequency they are using (e.g. a trainee's personalized callsign).
+    /// - Display names should follow the same underscore-separated format as VATSIM callsigns.
+    ///
+    /// Example:
+    /// ```toml
+    /// [stations.profiles.Default.cid_aliases]
+    /// "1234567" = "AC_CTR"
+    /// ```
+    #[serde(default)]
+    pub cid_aliases: HashMap<String, String>,
+
     /// Control how frequencies are displayed on the DA keys.
     ///
     /// - `ShowAll`: Show frequency for all stations (default).
@@ -854,6 +1762,15 @@ pub struct StationsProfileConfig {
     #[serde(default)]
     pub frequencies: FrequencyDisplayMode,
 
+    /// Control the format of frequencies displayed on the DA keys.
+    ///
+    /// - `ThreeDecimal`: Frequency in MHz with three decimals, e.g. `123.450` (default).
+    /// - `Channel8_33`: 8.33 kHz channel name, e.g. `123.455`.
+    ///
+    /// This is display-only, aliases are matched regardless of the format.
+    #[serde(default)]
+    pub frequency_format: FrequencyFormat,
+
     /// Control how DA keys are grouped.
     ///
     /// - `None`: Don't group.
@@ -861,8 +1778,135 @@ pub struct StationsProfileConfig {
     /// - `FirAndIcao`: First, group by the first two letters (FIR), then by the first four letters
     ///   (ICAO code) of the display name.
     /// - `Icao`: Group by the first four letters (ICAO code) of the display name.
+    /// - `{ Prefix = N }`: Group by the first N letters of the display name.
     #[serde(default)]
     pub grouping: StationsGroupMode,
+
+    /// Syntax used to interpret the `include`, `exclude` and `priority` patterns.
+    ///
+    /// - `Glob`: Glob-like patterns, anchored at start and end (default).
+    /// - `Regex`: Full (case-insensitive) regular expressions, not anchored implicitly.
+    #[serde(default)]
+    pub pattern_syntax: PatternSyntax,
+}
+
+impl StationsProfileConfig {
+    /// Ensures all station patterns of this profile compile using the configured pattern syntax.
+    ///
+    /// The patterns are applied by the frontend, this only rejects invalid ones early.
+    pub fn validate_patterns(&self) -> anyhow::Result<()> {
+        for (field, patterns) in [
+            ("include", &self.include),
+            ("exclude", &self.exclude),
+            ("priority", &self.priority),
+            ("favorites", &self.favorites),
+        ] {
+            for p in patterns {
+                self.pattern_syntax.compile(p).with_context(|| {
+                    format!(
+                        "Invalid {:?} pattern {p:?} in `{field}`",
+                        self.pattern_syntax
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates patterns and aliases of this profile, recording every issue found.
+    fn validate_into(&self, name: &str, file: Option<&str>, issues: &mut Vec<ConfigIssue>) {
+        if self.grouping == StationsGroupMode::Prefix(0) {
+            issues.push(ConfigIssue::error(
+                file.map(String::from),
+                Some(format!("stations.profiles.{name}.grouping")),
+                "Prefix grouping length must be at least 1",
+            ));
+        }
+
+        for (field, patterns) in [
+            ("include", &self.include),
+            ("exclude", &self.exclude),
+            ("priority", &self.priority),
+            ("favorites", &self.favorites),
+        ] {
+            for (i, pattern) in patterns.iter().enumerate() {
+                if let Err(err) = self.pattern_syntax.compile(pattern) {
+                    issues.push(ConfigIssue::error(
+                        file.map(String::from),
+                        Some(format!("stations.profiles.{name}.{field}[{i}]")),
+                        format!(
+                            "Invalid {:?} pattern {pattern:?}: {err}",
+                            self.pattern_syntax
+                        ),
+                    ));
+                }
+            }
+        }
+
+        let mut aliases = self.aliases.iter().collect::<Vec<_>>();
+        aliases.sort();
+        for (frequency, alias) in aliases {
+            let field = Some(format!("stations.profiles.{name}.aliases.\"{frequency}\""));
+            if !is_valid_frequency(frequency) {
+                issues.push(ConfigIssue::warning(
+                    file.map(String::from),
+                    field.clone(),
+                    format!(
+                        "Frequency {frequency:?} is not in the expected format (e.g. \"132.600\")"
+                    ),
+                ));
+            }
+            validate_alias(
+                alias,
+                &format!("frequency {frequency:?}"),
+                file,
+                field,
+                issues,
+            );
+        }
+
+        let mut cid_aliases = self.cid_aliases.iter().collect::<Vec<_>>();
+        cid_aliases.sort();
+        for (cid, alias) in cid_aliases {
+            let field = Some(format!("stations.profiles.{name}.cid_aliases.\"{cid}\""));
+            validate_alias(alias, &format!("CID {cid:?}"), file, field, issues);
+        }
+    }
+}
+
+/// Checks that an alias is usable as display name, `target` describing what is being aliased.
+fn validate_alias(
+    alias: &str,
+    target: &str,
+    file: Option<&str>,
+    field: Option<String>,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    if alias.trim().is_empty() {
+        issues.push(ConfigIssue::warning(
+            file.map(String::from),
+            field,
+            format!("Alias for {target} is empty"),
+        ));
+    } else if !alias.contains('_') {
+        issues.push(ConfigIssue::warning(
+            file.map(String::from),
+            field,
+            format!(
+                "Alias {alias:?} should follow the callsign format (e.g. \"AC_CTR\") for proper filtering, sorting and display"
+            ),
+        ));
+    }
+}
+
+/// Checks whether the given frequency is in the format used by VATSIM (e.g. `132.600`).
+fn is_valid_frequency(frequency: &str) -> bool {
+    frequency.split_once('.').is_some_and(|(mhz, khz)| {
+        mhz.len() == 3
+            && khz.len() == 3
+            && mhz.chars().all(|c| c.is_ascii_digit())
+            && khz.chars().all(|c| c.is_ascii_digit())
+    })
 }
 
 impl Default for StationsProfileConfig {
@@ -877,9 +1921,13 @@ impl Default for StationsProfileConfig {
                 "*_TWR".to_string(),
                 "*_GND".to_string(),
             ],
+            favorites: vec![],
             aliases: HashMap::new(),
+            cid_aliases: HashMap::new(),
             frequencies: FrequencyDisplayMode::default(),
+            frequency_format: FrequencyFormat::default(),
             grouping: StationsGroupMode::default(),
+            pattern_syntax: PatternSyntax::default(),
         }
     }
 }
@@ -890,9 +1938,13 @@ pub struct FrontendStationsProfileConfig {
     pub include: Vec<String>,
     pub exclude: Vec<String>,
     pub priority: Vec<String>,
+    pub favorites: Vec<String>,
     pub aliases: HashMap<String, String>,
+    pub cid_aliases: HashMap<String, String>,
     pub frequencies: FrequencyDisplayMode,
+    pub frequency_format: FrequencyFormat,
     pub grouping: StationsGroupMode,
+    pub pattern_syntax: PatternSyntax,
 }
 
 impl From<StationsProfileConfig> for FrontendStationsProfileConfig {
@@ -901,9 +1953,13 @@ impl From<StationsProfileConfig> for FrontendStationsProfileConfig {
             include: stations_profile_config.include,
             exclude: stations_profile_config.exclude,
             priority: stations_profile_config.priority,
+            favorites: stations_profile_config.favorites,
             aliases: stations_profile_config.aliases,
+            cid_aliases: stations_profile_config.cid_aliases,
             frequencies: stations_profile_config.frequencies,
+            frequency_format: stations_profile_config.frequency_format,
             grouping: stations_profile_config.grouping,
+            pattern_syntax: stations_profile_config.pattern_syntax,
         }
     }
 }
@@ -923,3 +1979,545 @@ impl<T: Serialize> Persistable for T {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::HostFallback;
+
+    fn profile(
+        syntax: PatternSyntax,
+        include: &[&str],
+        exclude: &[&str],
+        priority: &[&str],
+    ) -> StationsProfileConfig {
+        StationsProfileConfig {
+            include: include.iter().map(|s| s.to_string()).collect(),
+            exclude: exclude.iter().map(|s| s.to_string()).collect(),
+            priority: priority.iter().map(|s| s.to_string()).collect(),
+            pattern_syntax: syntax,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pattern_syntax_defaults_to_glob() {
+        let profile: StationsProfileConfig = toml::from_str("include = [\"LO*\"]").unwrap();
+        assert_eq!(profile.pattern_syntax, PatternSyntax::Glob);
+    }
+
+    #[test]
+    fn glob_is_anchored_and_case_insensitive() {
+        let regex = PatternSyntax::Glob.compile("loww_?_app").unwrap();
+        assert!(regex.is_match("LOWW_M_APP"));
+        assert!(!regex.is_match("LOWW_APP"));
+        assert!(!regex.is_match("XLOWW_M_APP"));
+    }
+
+    #[test]
+    fn glob_escapes_regex_characters() {
+        let regex = PatternSyntax::Glob.compile("LOWW.APP").unwrap();
+        assert!(regex.is_match("LOWW.APP"));
+        assert!(!regex.is_match("LOWW_APP"));
+    }
+
+    #[test]
+    fn regex_is_not_anchored() {
+        let regex = PatternSyntax::Regex.compile("WW").unwrap();
+        assert!(regex.is_match("LOWW_APP"));
+
+        let regex = PatternSyntax::Regex.compile("^ww").unwrap();
+        assert!(!regex.is_match("LOWW_APP"));
+    }
+
+    #[test]
+    fn groups_by_prefix_length() {
+        let grouping = StationsGroupMode::Prefix(3);
+        assert_eq!(grouping.group_of("LOWW_APP"), Some("LOW"));
+        assert_eq!(grouping.group_of("LOVV_E_CTR"), Some("LOV"));
+        assert_eq!(grouping.group_of("EDMM_ALB_CTR"), Some("EDM"));
+
+        // Shorter prefixes form their own group
+        assert_eq!(grouping.group_of("LO_CTR"), Some("LO"));
+        assert_eq!(grouping.group_of("ABC_TWR"), Some("ABC"));
+        assert_eq!(grouping.group_of("_CTR"), Some(""));
+        assert_eq!(grouping.group_of("OBSERVER"), Some(OTHER_STATIONS_GROUP));
+
+        assert_eq!(
+            StationsGroupMode::Prefix(5).group_of("LOWW_APP"),
+            Some("LOWW")
+        );
+        assert_eq!(StationsGroupMode::Fir.group_of("LOWW_APP"), Some("LO"));
+        assert_eq!(
+            StationsGroupMode::FirAndIcao.group_of("LOWW_APP"),
+            Some("LOWW")
+        );
+        assert_eq!(StationsGroupMode::None.group_of("LOWW_APP"), None);
+    }
+
+    #[test]
+    fn prefix_grouping_toml_format() {
+        let profile: StationsProfileConfig = toml::from_str("grouping = { Prefix = 3 }").unwrap();
+        assert_eq!(profile.grouping, StationsGroupMode::Prefix(3));
+
+        let profile: StationsProfileConfig = toml::from_str("grouping = \"Icao\"").unwrap();
+        assert_eq!(profile.grouping, StationsGroupMode::Icao);
+
+        let mut issues = Vec::new();
+        StationsProfileConfig {
+            grouping: StationsGroupMode::Prefix(0),
+            ..Default::default()
+        }
+        .validate_into("Default", None, &mut issues);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, ConfigIssueSeverity::Error);
+    }
+
+    #[test]
+    fn media_dscp_must_fit_six_bits() {
+        let mut config = ClientConfig {
+            media_dscp: Some(46),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.media_dscp = Some(64);
+        assert_eq!(
+            config.invalid_field().map(|(field, _)| field),
+            Some("client.media_dscp")
+        );
+    }
+
+    #[test]
+    fn invalid_regex_fails_validation() {
+        let mut config = StationsConfig::default();
+        config.profiles.insert(
+            "Broken".to_string(),
+            profile(PatternSyntax::Regex, &[], &["(LOWW"], &[]),
+        );
+
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("Broken"));
+        assert!(format!("{err:#}").contains("exclude"));
+    }
+
+    #[test]
+    fn glob_patterns_always_validate() {
+        let mut config = StationsConfig::default();
+        config.profiles.insert(
+            "Glob".to_string(),
+            profile(PatternSyntax::Glob, &["(LOWW"], &["[*"], &["+?"]),
+        );
+
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn profile_validation_reports_field_context() {
+        let mut broken = profile(PatternSyntax::Regex, &["^LOWW_"], &["(LOWW"], &[]);
+        broken
+            .aliases
+            .insert("132.600".to_string(), "AC_CTR".to_string());
+        broken
+            .aliases
+            .insert("132.6".to_string(), "FIC".to_string());
+
+        let mut issues = Vec::new();
+        broken.validate_into("Broken", Some("stations.toml"), &mut issues);
+
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[0].severity, ConfigIssueSeverity::Error);
+        assert_eq!(
+            issues[0].field.as_deref(),
+            Some("stations.profiles.Broken.exclude[0]")
+        );
+        assert_eq!(issues[0].file.as_deref(), Some("stations.toml"));
+        assert!(
+            issues[1..]
+                .iter()
+                .all(|i| i.severity == ConfigIssueSeverity::Warning
+                    && i.field.as_deref() == Some("stations.profiles.Broken.aliases.\"132.6\""))
+        );
+    }
+
+    #[test]
+    fn cid_aliases_are_validated() {
+        let mut profile = StationsProfileConfig::default();
+        profile
+            .cid_aliases
+            .insert("1234567".to_string(), "WI_TRAINEE_APP".to_string());
+        profile
+            .cid_aliases
+            .insert("7654321".to_string(), "Trainee".to_string());
+
+        let mut issues = Vec::new();
+        profile.validate_into("Default", None, &mut issues);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, ConfigIssueSeverity::Warning);
+        assert_eq!(
+            issues[0].field.as_deref(),
+            Some("stations.profiles.Default.cid_aliases.\"7654321\"")
+        );
+    }
+
+    #[test]
+    fn long_transmit_warning_only_in_push_to_talk_modes() {
+        assert!(TransmitMode::PushToTalk.is_push_to_talk());
+        assert!(TransmitMode::PushToTalkToggle.is_push_to_talk());
+        assert!(TransmitMode::RadioIntegration.is_push_to_talk());
+        assert!(!TransmitMode::VoiceActivation.is_push_to_talk());
+        assert!(!TransmitMode::PushToMute.is_push_to_talk());
+        assert!(!TransmitMode::PushToMuteToggle.is_push_to_talk());
+    }
+
+    #[test]
+    fn max_incoming_calls_must_be_positive() {
+        let mut config = ClientConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.max_incoming_calls = 0;
+        assert!(config.validate().is_err());
+
+        config.max_incoming_calls = 1;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn signaling_reconnect_attempts_are_configurable() {
+        let mut config = ClientConfig {
+            signaling_reconnect: SignalingReconnectConfig {
+                max_attempts: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.signaling_reconnect_config().max_tries, 3);
+
+        config.signaling_auto_reconnect = false;
+        assert_eq!(config.signaling_reconnect_config().max_tries, 0);
+    }
+
+    #[test]
+    fn signaling_reconnect_attempts_must_be_in_range() {
+        let mut config = ClientConfig::default();
+
+        config.signaling_reconnect.max_attempts = 0;
+        assert_eq!(
+            config.invalid_field().map(|(field, _)| field),
+            Some("client.signaling_reconnect.max_attempts")
+        );
+
+        config.signaling_reconnect.max_attempts = 51;
+        assert!(config.validate().is_err());
+
+        config.signaling_reconnect.max_attempts = 50;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn audio_host_falls_back_when_missing() {
+        let host_names = vec!["ALSA".to_string(), "JACK".to_string()];
+
+        let mut config = AudioConfig {
+            host_name: Some("jack".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.validate_host(&host_names), None);
+        assert_eq!(config.host_name.as_deref(), Some("jack"));
+
+        config.host_name = Some("NotARealHost".to_string());
+        let missing_host = config.validate_host(&host_names);
+        assert_eq!(missing_host.as_deref(), Some("NotARealHost"));
+        assert_eq!(config.host_name.as_deref(), Some("NotARealHost"));
+
+        let fallback = HostFallback {
+            missing_host: missing_host.unwrap(),
+            host: "ALSA".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&fallback).unwrap(),
+            serde_json::json!({"missingHost": "NotARealHost", "host": "ALSA"})
+        );
+
+        // The default host is always valid
+        config.host_name = Some(String::new());
+        assert_eq!(config.validate_host(&host_names), None);
+        assert_eq!(AudioConfig::default().validate_host(&[]), None);
+    }
+
+    #[test]
+    fn outgoing_invite_times_out_before_auto_hangup() {
+        let config = ClientConfig {
+            outgoing_invite_timeout_seconds: 20,
+            auto_hangup_seconds: 60,
+            ..Default::default()
+        };
+
+        // An invite without any response is cancelled after the invite timeout, the auto
+        // hangup only applies once the call has been accepted
+        assert_eq!(
+            config.outgoing_invite_timeout(),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(config.auto_hangup_timeout(), Some(Duration::from_secs(60)));
+
+        let defaults = ClientConfig::default();
+        assert!(defaults.outgoing_invite_timeout() < defaults.auto_hangup_timeout());
+    }
+
+    #[test]
+    fn call_timeouts_can_be_disabled() {
+        let config = ClientConfig {
+            outgoing_invite_timeout_seconds: 0,
+            auto_hangup_seconds: 0,
+            ..Default::default()
+        };
+
+        assert_eq!(config.outgoing_invite_timeout(), None);
+        assert_eq!(config.auto_hangup_timeout(), None);
+    }
+
+    fn monitors(monitors: &[(&'static str, u32, u32)]) -> String {
+        monitor_fingerprint(
+            monitors
+                .iter()
+                .map(|(name, width, height)| (Some(*name), PhysicalSize::new(*width, *height))),
+        )
+    }
+
+    #[test]
+    fn monitor_fingerprint_ignores_order() {
+        assert_eq!(
+            monitors(&[("DELL U2720Q", 3840, 2160), ("Built-in", 2560, 1600)]),
+            monitors(&[("Built-in", 2560, 1600), ("DELL U2720Q", 3840, 2160)])
+        );
+        assert_eq!(
+            monitors(&[("Built-in", 2560, 1600), ("DELL U2720Q", 3840, 2160)]),
+            "Built-in@2560x1600,DELL U2720Q@3840x2160"
+        );
+        assert_eq!(
+            monitor_fingerprint([(None, PhysicalSize::new(1920, 1080))]),
+            "unknown@1920x1080"
+        );
+    }
+
+    #[test]
+    fn monitor_fingerprint_distinguishes_setups() {
+        let undocked = monitors(&[("Built-in", 2560, 1600)]);
+        let docked = monitors(&[("Built-in", 2560, 1600), ("DELL U2720Q", 3840, 2160)]);
+        let rescaled = monitors(&[("Built-in", 1920, 1200)]);
+
+        assert_ne!(undocked, docked);
+        assert_ne!(undocked, rescaled);
+    }
+
+    #[test]
+    fn window_layout_selects_matching_setup() {
+        let undocked = monitors(&[("Built-in", 2560, 1600)]);
+        let docked = monitors(&[("Built-in", 2560, 1600), ("DELL U2720Q", 3840, 2160)]);
+        let office = monitors(&[("LG 27UL850", 3840, 2160)]);
+
+        let undocked_layout = WindowLayout {
+            position: PhysicalPosition::new(100, 100),
+            size: PhysicalSize::new(1000, 753),
+        };
+        let docked_layout = WindowLayout {
+            position: PhysicalPosition::new(2660, 200),
+            size: PhysicalSize::new(1200, 900),
+        };
+        let config = ClientConfig {
+            position: Some(PhysicalPosition::new(2660, 200)),
+            size: Some(PhysicalSize::new(1200, 900)),
+            window_layouts: HashMap::from([
+                (undocked.clone(), undocked_layout),
+                (docked.clone(), docked_layout),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.window_layout(&undocked),
+            (Some(undocked_layout.position), Some(undocked_layout.size))
+        );
+        assert_eq!(
+            config.window_layout(&docked),
+            (Some(docked_layout.position), Some(docked_layout.size))
+        );
+        // Unknown setups fall back to the last stored position and size
+        assert_eq!(
+            config.window_layout(&office),
+            (config.position, config.size)
+        );
+        assert_eq!(ClientConfig::default().window_layout(&office), (None, None));
+    }
+
+    #[test]
+    fn centered_position_in_work_area() {
+        let window = PhysicalSize::new(1000, 753);
+
+        assert_eq!(
+            centered_position(
+                PhysicalPosition::new(0, 0),
+                PhysicalSize::new(1920, 1040),
+                window
+            ),
+            PhysicalPosition::new(460, 143)
+        );
+        // Secondary monitors left of the primary one have negative coordinates
+        assert_eq!(
+            centered_position(
+                PhysicalPosition::new(-2560, 25),
+                PhysicalSize::new(2560, 1415),
+                window
+            ),
+            PhysicalPosition::new(-1780, 356)
+        );
+        // Windows larger than the work area stick to its top-left corner
+        assert_eq!(
+            centered_position(
+                PhysicalPosition::new(0, 40),
+                PhysicalSize::new(800, 560),
+                window
+            ),
+            PhysicalPosition::new(0, 40)
+        );
+    }
+
+    #[test]
+    fn peer_volume_defaults_and_clamping() {
+        let mut config = ClientConfig::default();
+        assert_eq!(config.peer_volume("1234567"), DEFAULT_PEER_VOLUME);
+
+        assert_eq!(config.set_peer_volume("1234567".to_string(), 0.4), 0.4);
+        assert_eq!(config.peer_volume("1234567"), 0.4);
+        assert_eq!(config.peer_volume("7654321"), DEFAULT_PEER_VOLUME);
+
+        assert_eq!(
+            config.set_peer_volume("1234567".to_string(), 5.0),
+            MAX_PEER_VOLUME
+        );
+        assert_eq!(config.set_peer_volume("1234567".to_string(), -1.0), 0.0);
+
+        // Resetting to the default does not keep an entry around
+        config.set_peer_volume("1234567".to_string(), f32::NAN);
+        assert!(config.peer_volumes.is_empty());
+    }
+
+    #[test]
+    fn peer_volume_is_restored_from_persisted_config() {
+        let dir =
+            std::env::temp_dir().join(format!("vacs-peer-volume-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut client = ClientConfig::default();
+        client.set_peer_volume("1234567".to_string(), 1.5);
+        PersistedClientConfig::from(client)
+            .persist(&dir, CLIENT_SETTINGS_FILE_NAME)
+            .unwrap();
+
+        // The next call to that peer picks up the stored gain after a restart
+        let config = AppConfig::parse(&dir).unwrap();
+        assert_eq!(config.client.peer_volume("1234567"), 1.5);
+        assert_eq!(config.client.peer_volume("7654321"), DEFAULT_PEER_VOLUME);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn frequency_format() {
+        assert!(is_valid_frequency("132.600"));
+        assert!(is_valid_frequency("199.998"));
+        assert!(!is_valid_frequency("132.6"));
+        assert!(!is_valid_frequency("132,600"));
+        assert!(!is_valid_frequency("13a.600"));
+        assert!(!is_valid_frequency(""));
+    }
+
+    #[test]
+    fn config_file_validation() {
+        let dir = std::env::temp_dir().join(format!("vacs-config-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let invalid_toml = dir.join("invalid.toml");
+        fs::write(&invalid_toml, "[client\nfoo = 1").unwrap();
+        let invalid_code = dir.join("invalid_code.toml");
+        fs::write(
+            &invalid_code,
+            "[client.transmit_config]\npush_to_talk = \"KeyFoo\"\npush_to_mute = \"ControlLeft+KeyM\"\n",
+        )
+        .unwrap();
+
+        let mut issues = Vec::new();
+        assert!(read_config_file(&dir.join("missing.toml"), &mut issues).is_none());
+        assert!(issues.is_empty());
+
+        assert!(read_config_file(&invalid_toml, &mut issues).is_none());
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].field.is_none());
+
+        issues.clear();
+        assert!(read_config_file(&invalid_code, &mut issues).is_some());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].field.as_deref(),
+            Some("client.transmit_config.push_to_talk")
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn cycle_profile_wraps_around() {
+        let mut config = StationsConfig::default();
+        for name in ["CTR", "APP"] {
+            config
+                .profiles
+                .insert(name.to_string(), StationsProfileConfig::default());
+        }
+
+        assert_eq!(config.cycle_profile("APP", true).as_deref(), Some("CTR"));
+        assert_eq!(
+            config.cycle_profile("CTR", true).as_deref(),
+            Some("Default")
+        );
+        assert_eq!(
+            config.cycle_profile("Default", true).as_deref(),
+            Some("APP")
+        );
+        assert_eq!(
+            config.cycle_profile("APP", false).as_deref(),
+            Some("Default")
+        );
+        assert_eq!(
+            config.cycle_profile("Default", false).as_deref(),
+            Some("CTR")
+        );
+    }
+
+    #[test]
+    fn cycle_profile_unknown_current() {
+        let mut config = StationsConfig::default();
+        config
+            .profiles
+            .insert("APP".to_string(), StationsProfileConfig::default());
+
+        assert_eq!(
+            config.cycle_profile("Missing", true).as_deref(),
+            Some("APP")
+        );
+        assert_eq!(
+            config.cycle_profile("Missing", false).as_deref(),
+            Some("Default")
+        );
+    }
+
+    #[test]
+    fn cycle_profile_single_profile() {
+        let config = StationsConfig::default();
+
+        assert_eq!(config.cycle_profile("Default", true), None);
+        assert_eq!(config.cycle_profile("Default", false), None);
+    }
+}
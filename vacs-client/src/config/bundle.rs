@@ -0,0 +1,164 @@
+use crate::config::{
+    AUDIO_SETTINGS_FILE_NAME, AppConfig, AudioConfig, CLIENT_SETTINGS_FILE_NAME, ClientConfig,
+    Persistable, PersistedAudioConfig, PersistedClientConfig, PersistedStationsConfig,
+    STATIONS_SETTINGS_FILE_NAME, StationsConfig,
+};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Current version of the settings bundle format.
+///
+/// Bump this whenever the bundle format changes in an incompatible way and add a migration
+/// from the previous version to [`SettingsBundle::from_toml`].
+pub const SETTINGS_BUNDLE_VERSION: u32 = 1;
+
+/// Portable bundle of all user settings, used to move a setup between installations.
+///
+/// Only contains the audio, client and stations config. Backend and ICE config are excluded as
+/// they're either environment-specific or contain short-lived credentials, and nothing stored
+/// by the secrets module (session cookies, encryption keys) is ever included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsBundle {
+    pub version: u32,
+    pub audio: AudioConfig,
+    pub client: ClientConfig,
+    pub stations: StationsConfig,
+}
+
+impl SettingsBundle {
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            version: SETTINGS_BUNDLE_VERSION,
+            audio: config.audio.clone(),
+            client: config.client.clone(),
+            stations: config.stations.clone(),
+        }
+    }
+
+    pub fn to_toml(&self) -> anyhow::Result<String> {
+        toml::to_string_pretty(self).context("Failed to serialize settings bundle")
+    }
+
+    /// Parses and validates a settings bundle.
+    ///
+    /// Bundles created by a newer version of vacs are rejected, as they might contain settings
+    /// this version cannot represent.
+    pub fn from_toml(content: &str) -> anyhow::Result<Self> {
+        let table = content
+            .parse::<toml::Table>()
+            .context("Settings bundle is not valid TOML")?;
+
+        let version = table
+            .get("version")
+            .and_then(toml::Value::as_integer)
+            .context("Settings bundle is missing its version")?;
+        let version = u32::try_from(version)
+            .with_context(|| format!("Invalid settings bundle version {version}"))?;
+
+        if version > SETTINGS_BUNDLE_VERSION {
+            anyhow::bail!(
+                "Settings bundle version {version} is newer than the supported version {SETTINGS_BUNDLE_VERSION}, please update vacs"
+            );
+        }
+
+        match version {
+            SETTINGS_BUNDLE_VERSION => {}
+            // Migrations of older bundle versions go here, upgrading `table` step by step
+            _ => anyhow::bail!("Unsupported settings bundle version {version}"),
+        }
+
+        let bundle: Self = toml::Value::Table(table)
+            .try_into()
+            .context("Failed to deserialize settings bundle")?;
+
+        bundle
+            .stations
+            .validate()
+            .context("Failed to validate stations config of settings bundle")?;
+
+        Ok(bundle)
+    }
+
+    /// Writes the bundled settings to their respective config files.
+    pub fn persist(&self, config_dir: &Path) -> anyhow::Result<()> {
+        PersistedAudioConfig::from(self.audio.clone())
+            .persist(config_dir, AUDIO_SETTINGS_FILE_NAME)?;
+        PersistedClientConfig::from(self.client.clone())
+            .persist(config_dir, CLIENT_SETTINGS_FILE_NAME)?;
+        PersistedStationsConfig::from(self.stations.clone())
+            .persist(config_dir, STATIONS_SETTINGS_FILE_NAME)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{PatternSyntax, TransmitMode};
+    use keyboard_types::Code;
+
+    fn to_value<T: Serialize>(value: &T) -> toml::Value {
+        toml::Value::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let mut config = AppConfig::default();
+        config.audio.input_device_name = Some("Headset Microphone".to_string());
+        config.audio.output_device_volume = 0.8;
+        config.client.transmit_config.mode = TransmitMode::PushToTalk;
+        config.client.transmit_config.push_to_talk = Some("ControlLeft+Space".parse().unwrap());
+        config.client.keybinds.end_call = Some(Code::F12);
+        config.client.ignored.insert("1234567".to_string());
+        config.client.selected_stations_profile = "Regex".to_string();
+        let profile = config.stations.profiles.get_mut("Default").unwrap();
+        profile.include = vec!["LO*".to_string()];
+        profile
+            .aliases
+            .insert("132.600".to_string(), "AC_CTR".to_string());
+        let mut regex = profile.clone();
+        regex.pattern_syntax = PatternSyntax::Regex;
+        regex.include = vec!["^LO(WW|VV)_".to_string()];
+        config.stations.profiles.insert("Regex".to_string(), regex);
+
+        let exported = SettingsBundle::new(&config).to_toml().unwrap();
+        let imported = SettingsBundle::from_toml(&exported).unwrap();
+
+        assert_eq!(imported.version, SETTINGS_BUNDLE_VERSION);
+        assert_eq!(to_value(&imported.audio), to_value(&config.audio));
+        assert_eq!(to_value(&imported.client), to_value(&config.client));
+        assert_eq!(to_value(&imported.stations), to_value(&config.stations));
+    }
+
+    #[test]
+    fn import_rejects_newer_version() {
+        let mut bundle = SettingsBundle::new(&AppConfig::default());
+        bundle.version = SETTINGS_BUNDLE_VERSION + 1;
+
+        let err = SettingsBundle::from_toml(&bundle.to_toml().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("newer"));
+    }
+
+    #[test]
+    fn import_rejects_missing_or_unsupported_version() {
+        let mut table = to_value(&SettingsBundle::new(&AppConfig::default()));
+        let table = table.as_table_mut().unwrap();
+
+        table.remove("version");
+        assert!(SettingsBundle::from_toml(&table.to_string()).is_err());
+
+        table.insert("version".to_string(), toml::Value::Integer(0));
+        assert!(SettingsBundle::from_toml(&table.to_string()).is_err());
+    }
+
+    #[test]
+    fn import_rejects_invalid_patterns() {
+        let mut bundle = SettingsBundle::new(&AppConfig::default());
+        let profile = bundle.stations.profiles.get_mut("Default").unwrap();
+        profile.pattern_syntax = PatternSyntax::Regex;
+        profile.include = vec!["(LOWW".to_string()];
+
+        assert!(SettingsBundle::from_toml(&bundle.to_toml().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+use crate::app::logging::LogFilterHandle;
+use crate::app::state::AppState;
+use crate::app::state::keybinds::AppStateKeybindsExt;
+use crate::config::{
+    AUDIO_SETTINGS_FILE_NAME, AppConfig, CLIENT_SETTINGS_FILE_NAME, DEFAULT_SETTINGS_FILE_NAME,
+    FrontendStationsConfig, STATIONS_SETTINGS_FILE_NAME,
+};
+use crate::error::{Error, FrontendError};
+use crate::platform::Capabilities;
+use anyhow::Context;
+use notify::{EventKind, RecursiveMode, Watcher};
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json::Value;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Time without further file changes before a reload is triggered, coalescing editors
+/// writing a file in multiple steps into a single reload.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+const WATCHED_FILE_NAMES: [&str; 4] = [
+    DEFAULT_SETTINGS_FILE_NAME,
+    AUDIO_SETTINGS_FILE_NAME,
+    STATIONS_SETTINGS_FILE_NAME,
+    CLIENT_SETTINGS_FILE_NAME,
+];
+
+/// Watches the config directory (and the directory of the extra stations config, if set)
+/// and reloads the [`AppConfig`] whenever one of the config files changes.
+///
+/// The watcher runs until the given shutdown token is cancelled.
+pub fn spawn_config_watcher(
+    app: AppHandle,
+    config_dir: PathBuf,
+    extra_stations_config: Option<String>,
+    shutdown_token: CancellationToken,
+) -> anyhow::Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<()>();
+
+    let file_names = Arc::new(RwLock::new(watched_file_names(
+        extra_stations_config.as_deref(),
+    )));
+    let handler_file_names = file_names.clone();
+
+    let mut watcher =
+        notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+            Ok(event) => {
+                if !matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    return;
+                }
+
+                let file_names = handler_file_names.read();
+                if event.paths.iter().any(|path| {
+                    path.file_name()
+                        .is_some_and(|name| file_names.iter().any(|n| n == name))
+                }) {
+                    tx.send(()).ok();
+                }
+            }
+            Err(err) => log::warn!("Config watcher error: {err}"),
+        })
+        .context("Failed to create config watcher")?;
+
+    watcher
+        .watch(&config_dir, RecursiveMode::NonRecursive)
+        .context("Failed to watch config directory")?;
+
+    let mut extra_dir = extra_stations_config_dir(extra_stations_config.as_deref(), &config_dir);
+    if let Some(dir) = &extra_dir
+        && let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive)
+    {
+        log::warn!("Failed to watch extra stations config directory {dir:?}: {err}");
+    }
+
+    log::debug!("Watching config directory {config_dir:?} for changes");
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown_token.cancelled() => break,
+                res = rx.recv() => if res.is_none() { break; },
+            }
+
+            // Wait until no further changes arrive within the debounce window
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = shutdown_token.cancelled() => return,
+                    res = rx.recv() => if res.is_none() { return; },
+                    _ = tokio::time::sleep(RELOAD_DEBOUNCE) => break,
+                }
+            }
+
+            let Some(config) = reload_config(&app, &config_dir).await else {
+                continue;
+            };
+
+            // The extra stations config might have been changed, update the watched paths
+            let new_extra_dir = extra_stations_config_dir(
+                config.client.extra_stations_config.as_deref(),
+                &config_dir,
+            );
+            if new_extra_dir != extra_dir {
+                if let Some(dir) = &extra_dir {
+                    watcher.unwatch(dir).ok();
+                }
+                if let Some(dir) = &new_extra_dir
+                    && let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive)
+                {
+                    log::warn!("Failed to watch extra stations config directory {dir:?}: {err}");
+                }
+                extra_dir = new_extra_dir;
+            }
+            *file_names.write() =
+                watched_file_names(config.client.extra_stations_config.as_deref());
+        }
+
+        log::trace!("Config watcher finished");
+    });
+
+    Ok(())
+}
+
+async fn reload_config(app: &AppHandle, config_dir: &Path) -> Option<AppConfig> {
+    log::info!("Config files changed, reloading config");
+
+    let config = match AppConfig::parse(config_dir).context("Failed to reload config") {
+        Ok(config) => config,
+        Err(err) => {
+            log::warn!("{err:?}");
+            app.emit::<FrontendError>(
+                "error",
+                FrontendError::from(Error::from(err)).non_critical(),
+            )
+            .ok();
+            return None;
+        }
+    };
+
+    apply_config(app, config.clone()).await;
+
+    Some(config)
+}
+
+/// Replaces the current config, re-applying station profiles as well as the transmit, keybinds
+/// and radio config to the keybind engine if they've changed.
+///
+/// Emits `config:reloaded` once the new config has been applied, unless nothing changed.
+pub async fn apply_config(app: &AppHandle, config: AppConfig) {
+    let state = app.state::<AppState>();
+    let mut state = state.lock().await;
+
+    if !differs(&state.config, &config) {
+        log::debug!("Config unchanged after reload, ignoring");
+        return;
+    }
+
+    let keybinds_changed = differs(
+        &state.config.client.transmit_config,
+        &config.client.transmit_config,
+    ) || differs(&state.config.client.keybinds, &config.client.keybinds)
+        || differs(&state.config.client.radio, &config.client.radio);
+    let stations_changed = differs(&state.config.stations, &config.stations)
+        || state.config.client.selected_stations_profile != config.client.selected_stations_profile;
+
+    if differs(&state.config.audio, &config.audio)
+        || differs(&state.config.backend, &config.backend)
+    {
+        log::info!("Audio and backend config changes take effect after a restart");
+    }
+
+    if state.config.client.log_level != config.client.log_level {
+        app.state::<LogFilterHandle>()
+            .set_level(config.client.log_level());
+    }
+
+    state.config = config.clone();
+
+    let capabilities = Capabilities::default();
+    if keybinds_changed && (capabilities.keybind_listener || capabilities.keybind_emitter) {
+        log::debug!("Keybinds config changed, reloading keybind engine");
+        if let Err(err) = state
+            .keybind_engine_handle()
+            .write()
+            .await
+            .reload_config(
+                &config.client.transmit_config,
+                &config.client.keybinds,
+                &config.client.radio,
+            )
+            .await
+        {
+            log::warn!("Failed to reload keybind engine: {err}");
+            app.emit::<FrontendError>("error", FrontendError::from(err).non_critical())
+                .ok();
+        }
+    }
+
+    if stations_changed {
+        let mut stations_config = FrontendStationsConfig::from(config.stations.clone());
+        stations_config.selected_profile = config.client.selected_stations_profile.clone();
+        app.emit("signaling:stations-config", stations_config).ok();
+    }
+
+    app.emit("config:reloaded", Value::Null).ok();
+}
+
+fn watched_file_names(extra_stations_config: Option<&str>) -> Vec<OsString> {
+    let mut file_names = WATCHED_FILE_NAMES
+        .iter()
+        .map(OsString::from)
+        .collect::<Vec<_>>();
+    if let Some(file_name) = extra_stations_config.and_then(|path| Path::new(path).file_name()) {
+        file_names.push(file_name.to_os_string());
+    }
+    file_names
+}
+
+fn extra_stations_config_dir(
+    extra_stations_config: Option<&str>,
+    config_dir: &Path,
+) -> Option<PathBuf> {
+    let dir = Path::new(extra_stations_config?).parent()?;
+    if dir.as_os_str().is_empty() || dir == config_dir {
+        return None;
+    }
+    Some(dir.to_path_buf())
+}
+
+/// Compares two config values by their serialized representation, avoiding the need to
+/// implement [`PartialEq`] for every config struct.
+fn differs<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() != serde_json::to_value(b).ok()
+}
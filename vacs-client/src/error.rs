@@ -1,10 +1,13 @@
+use crate::build::VersionInfo;
 use crate::keybinds::KeybindsError;
+use crate::platform::Capabilities;
 use crate::radio::RadioError;
 use serde::Serialize;
 use serde_json::Value;
 use std::fmt::{Debug, Display, Formatter};
 use tauri::{AppHandle, Emitter};
 use thiserror::Error;
+use vacs_signaling::client::DisconnectCause;
 use vacs_signaling::error::{SignalingError, SignalingRuntimeError};
 use vacs_signaling::protocol::ws::{
     CallErrorReason, DisconnectReason, ErrorReason, LoginFailureReason,
@@ -219,8 +222,18 @@ fn format_signaling_error(err: &SignalingError) -> String {
             LoginFailureReason::IncompatibleProtocolVersion => {
                 "Login failed: Incompatible protocol version. Please check your client version."
             }
+            LoginFailureReason::Banned => "Login failed: Your CID is banned from this server.",
         }
         .to_string(),
+        SignalingError::IncompatibleProtocolVersion(details) => {
+            match &details.min_protocol_version {
+                Some(min_version) => format!(
+                    "Login failed: Incompatible protocol version. The server requires protocol version {min_version} or newer, please update your client."
+                ),
+                None => "Login failed: Incompatible protocol version. Please update your client."
+                    .to_string(),
+            }
+        }
         SignalingError::Runtime(runtime_err) => match runtime_err {
             SignalingRuntimeError::ServerError(reason) => match reason {
                 ErrorReason::MalformedMessage => "Server error: Malformed message".to_string(),
@@ -237,13 +250,36 @@ fn format_signaling_error(err: &SignalingError) -> String {
                 None => "Disconnected",
                 Some(DisconnectReason::Terminated) => "Disconnected: Your connection was terminated by another client.",
                 Some(DisconnectReason::NoActiveVatsimConnection) => "Disconnected: No active VATSIM connection was found.",
+                Some(DisconnectReason::Kicked) => "Disconnected: Your connection was terminated by a server administrator.",
+                Some(DisconnectReason::Banned) => "Disconnected: Your CID was banned from this server.",
             }.to_string(),
+            SignalingRuntimeError::SendQueueFull => {
+                "Connection to the server is congested. Please try again.".to_string()
+            }
             _ => runtime_err.to_string(),
         },
         _ => err.to_string(),
     }
 }
 
+/// Formats the cause of a signaling disconnect for display in the frontend.
+/// Returns `None` for disconnects requested by the user, as there is nothing to report.
+pub fn format_disconnect_cause(cause: &DisconnectCause) -> Option<String> {
+    match cause {
+        DisconnectCause::Requested => None,
+        DisconnectCause::LoginFailed(reason) => Some(format_signaling_error(
+            &SignalingError::LoginError(reason.clone()),
+        )),
+        DisconnectCause::IncompatibleProtocolVersion(details) => Some(format_signaling_error(
+            &SignalingError::IncompatibleProtocolVersion(details.clone()),
+        )),
+        DisconnectCause::Error(err) => Some(format_signaling_error(&SignalingError::Runtime(
+            err.clone(),
+        ))),
+        DisconnectCause::Other(reason) => Some(reason.clone()),
+    }
+}
+
 impl From<Error> for CallErrorReason {
     fn from(err: Error) -> Self {
         match err {
@@ -277,7 +313,8 @@ impl CallError {
                     CallErrorReason::AudioFailure => "Audio failure",
                     CallErrorReason::CallFailure => "Call failure",
                     CallErrorReason::SignalingFailure => "Target not reachable",
-                    CallErrorReason::AutoHangup => "Target did not answer",
+                    CallErrorReason::AutoHangup => "Connection not established",
+                    CallErrorReason::InviteTimeout => "Target did not answer",
                     CallErrorReason::Other => "Unknown failure",
                 }
             ),
@@ -285,25 +322,123 @@ impl CallError {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StartupError {
     Audio,
     Config,
     Keybinds,
+    Window,
     Other,
 }
 
+impl StartupError {
+    /// Stable, machine-readable code identifying the error in startup diagnostics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            StartupError::Audio => "startup.audio",
+            StartupError::Config => "startup.config",
+            StartupError::Keybinds => "startup.keybinds",
+            StartupError::Window => "startup.window",
+            StartupError::Other => "startup.other",
+        }
+    }
+}
+
 impl Display for StartupError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
             StartupError::Audio => "No suitable output audio device found. Check your logs for further details.",
             StartupError::Config => "Failed to load configuration. Check your config files for errors or logs for further details.",
             StartupError::Keybinds => "Failed to register keybinds according to your transmit mode. Check your config files or logs for further details.",
+            StartupError::Window => "Failed to show main window. Check your logs for further details.",
             StartupError::Other => "A fatal error occurred during startup. Check your logs for further details.",
         })
     }
 }
 
+/// Step of the application startup, reported alongside a [`StartupError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StartupStep {
+    DeepLink,
+    AppState,
+    HttpState,
+    Keybinds,
+    ShowWindow,
+}
+
+impl Display for StartupStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StartupStep::DeepLink => "Registering deep links",
+            StartupStep::AppState => "Initializing app state",
+            StartupStep::HttpState => "Initializing HTTP client",
+            StartupStep::Keybinds => "Registering keybinds",
+            StartupStep::ShowWindow => "Showing main window",
+        })
+    }
+}
+
+/// Structured diagnostics shown in the fatal error dialog and copyable from the settings.
+///
+/// The error fields are only set if startup failed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupDiagnostics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<StartupStep>,
+    pub version: VersionInfo,
+    pub capabilities: Capabilities,
+}
+
+impl StartupDiagnostics {
+    pub fn gather() -> Self {
+        Self {
+            code: None,
+            message: None,
+            step: None,
+            version: VersionInfo::gather(),
+            capabilities: Capabilities::default(),
+        }
+    }
+
+    pub fn failed(step: StartupStep, err: StartupError) -> Self {
+        Self {
+            code: Some(err.code()),
+            message: Some(err.to_string()),
+            step: Some(step),
+            ..Self::gather()
+        }
+    }
+
+    /// Human-readable summary of the diagnostics, appended to the fatal error dialog.
+    pub fn details(&self) -> String {
+        let mut details = Vec::new();
+        if let Some(code) = self.code {
+            details.push(format!("Error code: {code}"));
+        }
+        if let Some(step) = self.step {
+            details.push(format!("Failed step: {step}"));
+        }
+        details.push(format!(
+            "Version: {} ({})",
+            self.version.version, self.version.build.git_sha
+        ));
+        details.push(format!(
+            "Platform: {} (always on top: {}, keybind listener: {}, keybind emitter: {})",
+            self.capabilities.platform,
+            self.capabilities.always_on_top,
+            self.capabilities.keybind_listener,
+            self.capabilities.keybind_emitter
+        ));
+        details.join("\n")
+    }
+}
+
 pub trait StartupErrorExt<T> {
     fn map_startup_err(self, error: StartupError) -> Result<T, StartupError>;
 }
@@ -319,3 +454,54 @@ impl<T, E: Debug> StartupErrorExt<T> for Result<T, E> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const ALL_STARTUP_ERRORS: [StartupError; 5] = [
+        StartupError::Audio,
+        StartupError::Config,
+        StartupError::Keybinds,
+        StartupError::Window,
+        StartupError::Other,
+    ];
+
+    #[test]
+    fn startup_error_codes_are_distinct() {
+        let codes = ALL_STARTUP_ERRORS
+            .iter()
+            .map(StartupError::code)
+            .collect::<HashSet<_>>();
+        assert_eq!(codes.len(), ALL_STARTUP_ERRORS.len());
+
+        for err in ALL_STARTUP_ERRORS {
+            // Exhaustive match, fails to compile if a variant is missing from the list above
+            match err {
+                StartupError::Audio
+                | StartupError::Config
+                | StartupError::Keybinds
+                | StartupError::Window
+                | StartupError::Other => {}
+            }
+            assert!(err.code().starts_with("startup."), "{err:?}");
+        }
+    }
+
+    #[test]
+    fn failed_diagnostics_keep_message() {
+        let diagnostics = StartupDiagnostics::failed(StartupStep::AppState, StartupError::Config);
+        assert_eq!(diagnostics.code, Some("startup.config"));
+        assert_eq!(
+            diagnostics.message.as_deref(),
+            Some(StartupError::Config.to_string().as_str())
+        );
+        assert_eq!(diagnostics.step, Some(StartupStep::AppState));
+        assert!(diagnostics.details().contains("Error code: startup.config"));
+
+        let json = serde_json::to_value(&diagnostics).unwrap();
+        assert_eq!(json["code"], "startup.config");
+        assert_eq!(json["step"], "appState");
+    }
+}
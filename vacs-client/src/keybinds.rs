@@ -1,8 +1,13 @@
+use crate::keybinds::conflicts::KeybindConflict;
 use keyboard_types::{Code, KeyState};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use thiserror::Error;
 
+pub mod combo;
 pub mod commands;
+pub mod conflicts;
 pub mod engine;
 pub mod runtime;
 
@@ -14,15 +19,90 @@ pub enum KeybindsError {
     Emitter(String),
     #[error("Unrecognized keybinds code: {0}")]
     UnrecognizedCode(String),
+    #[error("Conflicting keybinds: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    Conflicts(Vec<KeybindConflict>),
     #[error("Fake marker")]
     FakeMarker,
     #[error("{0}")]
     Other(String),
 }
 
+/// Prefix identifying gamepad buttons in the string form of an [`InputCode`].
+const GAMEPAD_PREFIX: &str = "Gamepad:";
+
+/// A gamepad or joystick button, identified by the raw button code reported by the driver.
+///
+/// Raw codes are used instead of a mapped layout since flight sim peripherals (yokes, throttles,
+/// button boxes) rarely come with a known mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GamepadButton(pub u32);
+
+impl fmt::Display for GamepadButton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{GAMEPAD_PREFIX}{}", self.0)
+    }
+}
+
+/// An input that can be bound to a keybind, either a keyboard key or a gamepad button.
+///
+/// Keyboard keys use their [`Code`] string, gamepad buttons are prefixed with `Gamepad:`
+/// (e.g. `"Gamepad:3"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputCode {
+    Key(Code),
+    Gamepad(GamepadButton),
+}
+
+impl InputCode {
+    pub fn as_key(&self) -> Option<Code> {
+        match self {
+            Self::Key(code) => Some(*code),
+            Self::Gamepad(_) => None,
+        }
+    }
+}
+
+impl From<Code> for InputCode {
+    fn from(code: Code) -> Self {
+        Self::Key(code)
+    }
+}
+
+impl From<GamepadButton> for InputCode {
+    fn from(button: GamepadButton) -> Self {
+        Self::Gamepad(button)
+    }
+}
+
+impl fmt::Display for InputCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(code) => write!(f, "{code}"),
+            Self::Gamepad(button) => write!(f, "{button}"),
+        }
+    }
+}
+
+impl FromStr for InputCode {
+    type Err = KeybindsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(button) = s.strip_prefix(GAMEPAD_PREFIX) {
+            return button
+                .parse()
+                .map(|button| Self::Gamepad(GamepadButton(button)))
+                .map_err(|_| KeybindsError::UnrecognizedCode(s.to_string()));
+        }
+
+        s.parse::<Code>()
+            .map(Self::Key)
+            .map_err(|_| KeybindsError::UnrecognizedCode(s.to_string()))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct KeyEvent {
-    code: Code,
+    code: InputCode,
     #[allow(dead_code)]
     label: String,
     state: KeyState,
@@ -35,4 +115,8 @@ pub enum Keybind {
     RadioIntegration,
     AcceptCall,
     EndCall,
+    NextProfile,
+    PrevProfile,
+    ToggleDoNotDisturb,
+    Panic,
 }
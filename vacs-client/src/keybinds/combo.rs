@@ -0,0 +1,412 @@
+use crate::keybinds::{InputCode, KeybindsError};
+use keyboard_types::{Code, KeyState};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// Separator between the modifiers and the main key in the string form of a [`KeyCombo`].
+const COMBO_SEPARATOR: char = '+';
+
+/// A key or gamepad button, optionally combined with a set of modifier keys that must be held
+/// down while pressing it.
+///
+/// Serialized as the codes joined by `+`, with the main input last (e.g. `"ControlLeft+Space"`).
+/// A combo without modifiers is serialized as its plain code, keeping single key configs
+/// compatible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCombo {
+    pub modifiers: Vec<Code>,
+    pub code: InputCode,
+}
+
+impl From<InputCode> for KeyCombo {
+    fn from(code: InputCode) -> Self {
+        Self {
+            modifiers: Vec::new(),
+            code,
+        }
+    }
+}
+
+impl From<Code> for KeyCombo {
+    fn from(code: Code) -> Self {
+        InputCode::Key(code).into()
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{modifier}{COMBO_SEPARATOR}")?;
+        }
+        write!(f, "{}", self.code)
+    }
+}
+
+impl FromStr for KeyCombo {
+    type Err = KeybindsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(COMBO_SEPARATOR).map(str::trim).collect::<Vec<_>>();
+
+        let code = parts
+            .pop()
+            .ok_or_else(|| KeybindsError::UnrecognizedCode(s.to_string()))?
+            .parse::<InputCode>()
+            .map_err(|_| KeybindsError::UnrecognizedCode(s.to_string()))?;
+
+        let mut modifiers = Vec::with_capacity(parts.len());
+        for part in parts {
+            let modifier = part
+                .parse::<Code>()
+                .map_err(|_| KeybindsError::UnrecognizedCode(s.to_string()))?;
+            if !is_modifier(modifier)
+                || InputCode::Key(modifier) == code
+                || modifiers.contains(&modifier)
+            {
+                return Err(KeybindsError::UnrecognizedCode(s.to_string()));
+            }
+            modifiers.push(modifier);
+        }
+
+        Ok(Self { modifiers, code })
+    }
+}
+
+impl Serialize for KeyCombo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyCombo {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// An ordered list of keys pressed together, e.g. to emit a key combo to an external application.
+///
+/// Keys are pressed in the configured order and released in reverse. Serialized as the codes joined
+/// by `+` like a [`KeyCombo`], but deserialized from either that form or a list of codes. A single
+/// code is a valid sequence, keeping single key configs compatible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySequence(Vec<Code>);
+
+impl KeySequence {
+    pub fn new(codes: Vec<Code>) -> Result<Self, KeybindsError> {
+        let duplicate = codes
+            .iter()
+            .enumerate()
+            .any(|(i, code)| codes[..i].contains(code));
+        if codes.is_empty() || duplicate {
+            return Err(KeybindsError::UnrecognizedCode(
+                codes
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(&COMBO_SEPARATOR.to_string()),
+            ));
+        }
+        Ok(Self(codes))
+    }
+
+    pub fn codes(&self) -> &[Code] {
+        &self.0
+    }
+}
+
+impl From<Code> for KeySequence {
+    fn from(code: Code) -> Self {
+        Self(vec![code])
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, code) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{COMBO_SEPARATOR}")?;
+            }
+            write!(f, "{code}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = KeybindsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let codes = s
+            .split(COMBO_SEPARATOR)
+            .map(|part| part.trim().parse::<Code>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| KeybindsError::UnrecognizedCode(s.to_string()))?;
+        Self::new(codes).map_err(|_| KeybindsError::UnrecognizedCode(s.to_string()))
+    }
+}
+
+impl Serialize for KeySequence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySequence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Joined(String),
+            List(Vec<String>),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Joined(s) => s.parse(),
+            Repr::List(codes) => codes
+                .iter()
+                .map(|code| {
+                    code.parse::<Code>()
+                        .map_err(|_| KeybindsError::UnrecognizedCode(code.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .and_then(Self::new),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+fn is_modifier(code: Code) -> bool {
+    matches!(
+        code,
+        Code::ControlLeft
+            | Code::ControlRight
+            | Code::ShiftLeft
+            | Code::ShiftRight
+            | Code::AltLeft
+            | Code::AltRight
+            | Code::MetaLeft
+            | Code::MetaRight
+    )
+}
+
+/// Tracks the state of a [`KeyCombo`] across raw key events.
+///
+/// The combo is pressed once its main key goes down while all modifiers are held, and released
+/// as soon as either the main key or one of the modifiers goes up again. Pressing the main key
+/// before the modifiers does not activate the combo.
+#[derive(Debug)]
+pub struct KeyComboState {
+    combo: KeyCombo,
+    held_modifiers: HashSet<Code>,
+    active: bool,
+}
+
+impl KeyComboState {
+    pub fn new(combo: KeyCombo) -> Self {
+        Self {
+            combo,
+            held_modifiers: HashSet::new(),
+            active: false,
+        }
+    }
+
+    /// Processes a raw input event, returning the resulting state of the combo if the event is
+    /// relevant to it.
+    ///
+    /// Releasing the main key is always reported, allowing callers to clean up transmit state
+    /// that was set before the combo state was tracked.
+    pub fn handle(&mut self, code: InputCode, state: KeyState) -> Option<KeyState> {
+        if code == self.combo.code {
+            return match state {
+                KeyState::Down
+                    if self
+                        .combo
+                        .modifiers
+                        .iter()
+                        .all(|m| self.held_modifiers.contains(m)) =>
+                {
+                    self.active = true;
+                    Some(KeyState::Down)
+                }
+                KeyState::Down => None,
+                KeyState::Up => {
+                    self.active = false;
+                    Some(KeyState::Up)
+                }
+            };
+        }
+
+        let code = code.as_key().filter(|c| self.combo.modifiers.contains(c))?;
+
+        match state {
+            KeyState::Down => {
+                self.held_modifiers.insert(code);
+                None
+            }
+            KeyState::Up => {
+                self.held_modifiers.remove(&code);
+                if self.active {
+                    self.active = false;
+                    Some(KeyState::Up)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keybinds::GamepadButton;
+
+    fn combo(s: &str) -> KeyComboState {
+        KeyComboState::new(s.parse().unwrap())
+    }
+
+    #[test]
+    fn parse_and_format() {
+        let single: KeyCombo = "Space".parse().unwrap();
+        assert_eq!(single, KeyCombo::from(Code::Space));
+        assert_eq!(single.to_string(), "Space");
+
+        let combo: KeyCombo = "ControlLeft+ShiftLeft+Space".parse().unwrap();
+        assert_eq!(combo.modifiers, vec![Code::ControlLeft, Code::ShiftLeft]);
+        assert_eq!(combo.code, InputCode::Key(Code::Space));
+        assert_eq!(combo.to_string(), "ControlLeft+ShiftLeft+Space");
+
+        assert!("".parse::<KeyCombo>().is_err());
+        assert!("KeyFoo".parse::<KeyCombo>().is_err());
+        assert!("ControlLeft+".parse::<KeyCombo>().is_err());
+        assert!("KeyA+Space".parse::<KeyCombo>().is_err());
+        assert!("ControlLeft+ControlLeft+Space".parse::<KeyCombo>().is_err());
+        assert!("ControlLeft+ControlLeft".parse::<KeyCombo>().is_err());
+
+        let gamepad: KeyCombo = "Gamepad:3".parse().unwrap();
+        assert_eq!(gamepad.code, InputCode::Gamepad(GamepadButton(3)));
+        assert_eq!(gamepad.to_string(), "Gamepad:3");
+        assert!("Gamepad:".parse::<KeyCombo>().is_err());
+        assert!("Gamepad:3+Space".parse::<KeyCombo>().is_err());
+    }
+
+    #[test]
+    fn parse_and_format_sequence() {
+        let single: KeySequence = "F12".parse().unwrap();
+        assert_eq!(single, KeySequence::from(Code::F12));
+        assert_eq!(single.to_string(), "F12");
+
+        let sequence: KeySequence = "ControlLeft+KeyA+F12".parse().unwrap();
+        assert_eq!(sequence.codes(), [Code::ControlLeft, Code::KeyA, Code::F12]);
+        assert_eq!(sequence.to_string(), "ControlLeft+KeyA+F12");
+
+        assert!("".parse::<KeySequence>().is_err());
+        assert!("KeyA+".parse::<KeySequence>().is_err());
+        assert!("KeyA+KeyA".parse::<KeySequence>().is_err());
+        assert!("Gamepad:3".parse::<KeySequence>().is_err());
+
+        let list: KeySequence = serde_json::from_str(r#"["ShiftRight", "KeyB"]"#).unwrap();
+        assert_eq!(list.codes(), [Code::ShiftRight, Code::KeyB]);
+        let joined: KeySequence = serde_json::from_str(r#""ShiftRight+KeyB""#).unwrap();
+        assert_eq!(joined, list);
+        assert!(serde_json::from_str::<KeySequence>("[]").is_err());
+    }
+
+    #[test]
+    fn single_key_press_release() {
+        let mut state = combo("Space");
+        assert_eq!(state.handle(Code::KeyA.into(), KeyState::Down), None);
+        assert_eq!(
+            state.handle(Code::Space.into(), KeyState::Down),
+            Some(KeyState::Down)
+        );
+        assert_eq!(
+            state.handle(Code::Space.into(), KeyState::Up),
+            Some(KeyState::Up)
+        );
+    }
+
+    #[test]
+    fn combo_modifier_then_key() {
+        let mut state = combo("ControlLeft+Space");
+        assert_eq!(state.handle(Code::ControlLeft.into(), KeyState::Down), None);
+        assert_eq!(
+            state.handle(Code::Space.into(), KeyState::Down),
+            Some(KeyState::Down)
+        );
+        // Key repeat while held
+        assert_eq!(
+            state.handle(Code::Space.into(), KeyState::Down),
+            Some(KeyState::Down)
+        );
+        assert_eq!(
+            state.handle(Code::Space.into(), KeyState::Up),
+            Some(KeyState::Up)
+        );
+        assert_eq!(state.handle(Code::ControlLeft.into(), KeyState::Up), None);
+    }
+
+    #[test]
+    fn combo_modifier_released_first() {
+        let mut state = combo("ControlLeft+Space");
+        state.handle(Code::ControlLeft.into(), KeyState::Down);
+        assert_eq!(
+            state.handle(Code::Space.into(), KeyState::Down),
+            Some(KeyState::Down)
+        );
+        assert_eq!(
+            state.handle(Code::ControlLeft.into(), KeyState::Up),
+            Some(KeyState::Up)
+        );
+        // Main key is still held, but the combo is no longer complete
+        assert_eq!(state.handle(Code::Space.into(), KeyState::Down), None);
+        assert_eq!(
+            state.handle(Code::Space.into(), KeyState::Up),
+            Some(KeyState::Up)
+        );
+    }
+
+    #[test]
+    fn combo_key_before_modifier() {
+        let mut state = combo("ControlLeft+Space");
+        assert_eq!(state.handle(Code::Space.into(), KeyState::Down), None);
+        assert_eq!(state.handle(Code::ControlLeft.into(), KeyState::Down), None);
+        assert_eq!(state.handle(Code::ControlLeft.into(), KeyState::Up), None);
+        assert_eq!(
+            state.handle(Code::Space.into(), KeyState::Up),
+            Some(KeyState::Up)
+        );
+    }
+
+    #[test]
+    fn gamepad_button_press_release() {
+        let mut state = combo("Gamepad:3");
+        let button = InputCode::Gamepad(GamepadButton(3));
+        assert_eq!(state.handle(GamepadButton(4).into(), KeyState::Down), None);
+        assert_eq!(state.handle(button, KeyState::Down), Some(KeyState::Down));
+        assert_eq!(state.handle(button, KeyState::Up), Some(KeyState::Up));
+    }
+
+    #[test]
+    fn combo_requires_all_modifiers() {
+        let mut state = combo("ControlLeft+ShiftLeft+Space");
+        state.handle(Code::ControlLeft.into(), KeyState::Down);
+        assert_eq!(state.handle(Code::Space.into(), KeyState::Down), None);
+        state.handle(Code::Space.into(), KeyState::Up);
+        state.handle(Code::ShiftLeft.into(), KeyState::Down);
+        assert_eq!(
+            state.handle(Code::Space.into(), KeyState::Down),
+            Some(KeyState::Down)
+        );
+        assert_eq!(state.handle(Code::ControlRight.into(), KeyState::Up), None);
+        assert_eq!(
+            state.handle(Code::ShiftLeft.into(), KeyState::Up),
+            Some(KeyState::Up)
+        );
+    }
+}
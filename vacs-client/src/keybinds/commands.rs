@@ -1,16 +1,22 @@
 use crate::app::state::AppState;
 use crate::config::{
     CLIENT_SETTINGS_FILE_NAME, FrontendKeybindsConfig, FrontendRadioConfig, FrontendTransmitConfig,
-    KeybindsConfig, Persistable, PersistedClientConfig, RadioConfig, TransmitConfig, TransmitMode,
+    KeybindsConfig, Persistable, PersistedClientConfig, RadioConfig, TransmitConfig,
 };
 use crate::error::Error;
+use crate::keybinds::Keybind;
+use crate::keybinds::combo::KeyCombo;
+use crate::keybinds::conflicts::{KeybindAction, KeybindConflict, check_conflicts, find_conflicts};
 use crate::keybinds::engine::KeybindEngineHandle;
-use crate::keybinds::{Keybind, KeybindsError};
+use crate::keybinds::runtime::{GamepadListener, KeybindListener};
 use crate::platform::Capabilities;
-use crate::radio::{RadioIntegration, RadioState};
-use keyboard_types::Code;
+use crate::radio::RadioState;
+use keyboard_types::{Code, KeyState};
+use std::time::Duration;
 use tauri::{AppHandle, Manager, State};
 
+const GAMEPAD_CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn keybinds_get_transmit_config(
@@ -44,7 +50,11 @@ pub async fn keybinds_set_transmit_config(
 
         let transmit_config: TransmitConfig = transmit_config.try_into()?;
 
-        validate_afv_radio_integration_config(&transmit_config, &state.config.client.radio)?;
+        check_conflicts(
+            &transmit_config,
+            &state.config.client.keybinds,
+            &state.config.client.radio,
+        )?;
 
         keybind_engine
             .write()
@@ -97,9 +107,19 @@ pub async fn keybinds_set_binding(
         match keybind {
             Keybind::AcceptCall => keybinds_config.accept_call = code,
             Keybind::EndCall => keybinds_config.end_call = code,
+            Keybind::NextProfile => keybinds_config.next_profile = code,
+            Keybind::PrevProfile => keybinds_config.prev_profile = code,
+            Keybind::ToggleDoNotDisturb => keybinds_config.toggle_do_not_disturb = code,
+            Keybind::Panic => keybinds_config.panic = code,
             _ => {}
         }
 
+        check_conflicts(
+            &state.config.client.transmit_config,
+            &keybinds_config,
+            &state.config.client.radio,
+        )?;
+
         keybind_engine
             .write()
             .await
@@ -119,6 +139,82 @@ pub async fn keybinds_set_binding(
     Ok(())
 }
 
+/// Returns the conflicts the given binding would cause with the current config, without
+/// applying it.
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn keybinds_check_binding(
+    app_state: State<'_, AppState>,
+    code: Option<String>,
+    keybind: Keybind,
+) -> Result<Vec<KeybindConflict>, Error> {
+    let state = app_state.lock().await;
+    let mut transmit_config = state.config.client.transmit_config.clone();
+    let mut keybinds_config = state.config.client.keybinds.clone();
+
+    let unrecognized = || {
+        Error::Other(Box::new(anyhow::anyhow!(
+            "Unrecognized key code: {}. Please report this error in our GitHub repository's issue tracker.",
+            code.clone().unwrap_or_default()
+        )))
+    };
+    let combo = || {
+        code.as_ref()
+            .map(|s| s.parse::<KeyCombo>())
+            .transpose()
+            .map_err(|_| unrecognized())
+    };
+    let single = || {
+        code.as_ref()
+            .map(|s| s.parse::<Code>())
+            .transpose()
+            .map_err(|_| unrecognized())
+    };
+
+    match keybind {
+        Keybind::PushToTalk => transmit_config.push_to_talk = combo()?,
+        Keybind::PushToMute => transmit_config.push_to_mute = combo()?,
+        Keybind::RadioIntegration => transmit_config.radio_push_to_talk = combo()?,
+        Keybind::AcceptCall => keybinds_config.accept_call = single()?,
+        Keybind::EndCall => keybinds_config.end_call = single()?,
+        Keybind::NextProfile => keybinds_config.next_profile = single()?,
+        Keybind::PrevProfile => keybinds_config.prev_profile = single()?,
+        Keybind::ToggleDoNotDisturb => keybinds_config.toggle_do_not_disturb = single()?,
+        Keybind::Panic => keybinds_config.panic = single()?,
+    }
+
+    let action = KeybindAction::from(keybind);
+    Ok(find_conflicts(
+        &transmit_config,
+        &keybinds_config,
+        &state.config.client.radio,
+    )
+    .into_iter()
+    .filter(|conflict| conflict.actions.contains(&action))
+    .collect())
+}
+
+/// Waits for the next gamepad button press and returns its code, or `None` if no button was
+/// pressed within the timeout.
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn keybinds_capture_gamepad_button() -> Result<Option<String>, Error> {
+    let (_listener, mut rx) = GamepadListener::start().await?;
+
+    let capture = async {
+        while let Some(event) = rx.recv().await {
+            if event.state == KeyState::Down {
+                return Some(event.code.to_string());
+            }
+        }
+        None
+    };
+
+    Ok(tokio::time::timeout(GAMEPAD_CAPTURE_TIMEOUT, capture)
+        .await
+        .unwrap_or_default())
+}
+
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn keybinds_get_radio_config(
@@ -145,7 +241,11 @@ pub async fn keybinds_set_radio_config(
 
         let radio_config: RadioConfig = radio_config.try_into()?;
 
-        validate_afv_radio_integration_config(&state.config.client.transmit_config, &radio_config)?;
+        check_conflicts(
+            &state.config.client.transmit_config,
+            &state.config.client.keybinds,
+            &radio_config,
+        )?;
 
         keybind_engine
             .write()
@@ -179,6 +279,19 @@ pub async fn keybinds_get_radio_state(
     Ok(keybind_engine.read().await.radio_state())
 }
 
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn keybinds_get_transmit_latched(
+    keybind_engine: State<'_, KeybindEngineHandle>,
+) -> Result<bool, Error> {
+    let capabilities = Capabilities::default();
+    if !capabilities.keybind_listener {
+        return Ok(false);
+    }
+
+    Ok(keybind_engine.read().await.transmit_latched())
+}
+
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn keybinds_get_external_binding(
@@ -218,22 +331,3 @@ pub async fn keybinds_reconnect_radio(
 ) -> Result<(), Error> {
     keybind_engine.read().await.reconnect_radio().await
 }
-
-fn validate_afv_radio_integration_config(
-    transmit_config: &TransmitConfig,
-    radio_config: &RadioConfig,
-) -> Result<(), Error> {
-    if transmit_config.mode == TransmitMode::RadioIntegration
-        && radio_config.integration == RadioIntegration::AudioForVatsim
-        && let Some(selected_key) = transmit_config.radio_push_to_talk
-        && let Some(afv_key) = radio_config.audio_for_vatsim.as_ref().and_then(|c| c.emit)
-        && afv_key == selected_key
-    {
-        return Err(KeybindsError::Other(
-            "AFV emit key must be distinct from your radio integration push-to-talk key"
-                .to_string(),
-        )
-        .into());
-    }
-    Ok(())
-}
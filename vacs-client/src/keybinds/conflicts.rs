@@ -0,0 +1,253 @@
+use crate::config::{KeybindsConfig, RadioConfig, TransmitConfig, TransmitMode};
+use crate::keybinds::{InputCode, Keybind, KeybindsError};
+use crate::radio::RadioIntegration;
+use serde::Serialize;
+use std::fmt;
+
+/// Action a key is bound to, used to report conflicting keybinds.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum KeybindAction {
+    Transmit,
+    AcceptCall,
+    EndCall,
+    NextProfile,
+    PrevProfile,
+    ToggleDoNotDisturb,
+    Panic,
+    RadioEmit,
+}
+
+impl From<Keybind> for KeybindAction {
+    fn from(keybind: Keybind) -> Self {
+        match keybind {
+            Keybind::PushToTalk | Keybind::PushToMute | Keybind::RadioIntegration => {
+                KeybindAction::Transmit
+            }
+            Keybind::AcceptCall => KeybindAction::AcceptCall,
+            Keybind::EndCall => KeybindAction::EndCall,
+            Keybind::NextProfile => KeybindAction::NextProfile,
+            Keybind::PrevProfile => KeybindAction::PrevProfile,
+            Keybind::ToggleDoNotDisturb => KeybindAction::ToggleDoNotDisturb,
+            Keybind::Panic => KeybindAction::Panic,
+        }
+    }
+}
+
+impl fmt::Display for KeybindAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            KeybindAction::Transmit => "transmit",
+            KeybindAction::AcceptCall => "accept call",
+            KeybindAction::EndCall => "end call",
+            KeybindAction::NextProfile => "next stations profile",
+            KeybindAction::PrevProfile => "previous stations profile",
+            KeybindAction::ToggleDoNotDisturb => "toggle do not disturb",
+            KeybindAction::Panic => "panic (mute all audio)",
+            KeybindAction::RadioEmit => "AFV emit",
+        })
+    }
+}
+
+/// A key bound to multiple actions at once.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeybindConflict {
+    pub code: String,
+    pub actions: Vec<KeybindAction>,
+}
+
+impl fmt::Display for KeybindConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let actions = self
+            .actions
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        write!(f, "{} is bound to {}", self.code, actions.join(", "))
+    }
+}
+
+/// Finds keys bound to more than one action.
+///
+/// Only the key of the active transmit mode is considered, and transmit combos conflict with
+/// other keybinds using their main key, as those are triggered by pressing the combo as well.
+/// Likewise, emitted key sequences conflict using their last key.
+/// Binding accept and end call to the same key is intended and not reported as a conflict.
+pub fn find_conflicts(
+    transmit_config: &TransmitConfig,
+    keybinds_config: &KeybindsConfig,
+    radio_config: &RadioConfig,
+) -> Vec<KeybindConflict> {
+    let emit_code = if transmit_config.mode == TransmitMode::RadioIntegration
+        && radio_config.integration == RadioIntegration::AudioForVatsim
+    {
+        radio_config
+            .audio_for_vatsim
+            .as_ref()
+            .and_then(|c| c.emit.as_ref())
+            .and_then(|keys| keys.codes().last().copied())
+    } else {
+        None
+    };
+
+    let bindings = [
+        (
+            KeybindAction::Transmit,
+            transmit_config.active_combo().map(|combo| combo.code),
+        ),
+        (
+            KeybindAction::AcceptCall,
+            keybinds_config.accept_call.map(Into::into),
+        ),
+        (
+            KeybindAction::EndCall,
+            keybinds_config.end_call.map(Into::into),
+        ),
+        (
+            KeybindAction::NextProfile,
+            keybinds_config.next_profile.map(Into::into),
+        ),
+        (
+            KeybindAction::PrevProfile,
+            keybinds_config.prev_profile.map(Into::into),
+        ),
+        (
+            KeybindAction::ToggleDoNotDisturb,
+            keybinds_config.toggle_do_not_disturb.map(Into::into),
+        ),
+        (KeybindAction::Panic, keybinds_config.panic.map(Into::into)),
+        (KeybindAction::RadioEmit, emit_code.map(Into::into)),
+    ];
+
+    let mut codes: Vec<(InputCode, Vec<KeybindAction>)> = Vec::new();
+    for (action, code) in bindings {
+        let Some(code) = code else {
+            continue;
+        };
+        match codes.iter_mut().find(|(c, _)| *c == code) {
+            Some((_, actions)) => actions.push(action),
+            None => codes.push((code, vec![action])),
+        }
+    }
+
+    codes
+        .into_iter()
+        .filter(|(_, actions)| {
+            actions.len() > 1 && actions[..] != [KeybindAction::AcceptCall, KeybindAction::EndCall]
+        })
+        .map(|(code, actions)| KeybindConflict {
+            code: code.to_string(),
+            actions,
+        })
+        .collect()
+}
+
+/// Returns an error listing all conflicting keybinds, if there are any.
+pub fn check_conflicts(
+    transmit_config: &TransmitConfig,
+    keybinds_config: &KeybindsConfig,
+    radio_config: &RadioConfig,
+) -> Result<(), KeybindsError> {
+    let conflicts = find_conflicts(transmit_config, keybinds_config, radio_config);
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(KeybindsError::Conflicts(conflicts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AudioForVatsimRadioConfig;
+    use keyboard_types::Code;
+
+    fn ptt(combo: &str) -> TransmitConfig {
+        TransmitConfig {
+            mode: TransmitMode::PushToTalk,
+            push_to_talk: Some(combo.parse().unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn transmit_and_call_control_conflict() {
+        let keybinds = KeybindsConfig {
+            accept_call: Some(Code::ShiftRight),
+            end_call: Some(Code::ShiftRight),
+            next_profile: Some(Code::F5),
+            ..Default::default()
+        };
+
+        let conflicts = find_conflicts(&ptt("ShiftRight"), &keybinds, &RadioConfig::default());
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].code, "ShiftRight");
+        assert_eq!(
+            conflicts[0].actions,
+            vec![
+                KeybindAction::Transmit,
+                KeybindAction::AcceptCall,
+                KeybindAction::EndCall
+            ]
+        );
+
+        // Combos conflict using their main key
+        let conflicts = find_conflicts(&ptt("ControlLeft+F5"), &keybinds, &RadioConfig::default());
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].actions,
+            vec![KeybindAction::Transmit, KeybindAction::NextProfile]
+        );
+    }
+
+    #[test]
+    fn shared_call_control_is_allowed() {
+        let keybinds = KeybindsConfig {
+            accept_call: Some(Code::F12),
+            end_call: Some(Code::F12),
+            ..Default::default()
+        };
+
+        assert!(find_conflicts(&ptt("Space"), &keybinds, &RadioConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn inactive_keys_are_ignored() {
+        let transmit = TransmitConfig {
+            mode: TransmitMode::PushToMute,
+            push_to_talk: Some(Code::F12.into()),
+            push_to_mute: Some(Code::F11.into()),
+            ..Default::default()
+        };
+        let keybinds = KeybindsConfig {
+            accept_call: Some(Code::F12),
+            ..Default::default()
+        };
+        let radio = RadioConfig {
+            integration: RadioIntegration::AudioForVatsim,
+            audio_for_vatsim: Some(AudioForVatsimRadioConfig {
+                emit: Some(Code::F11.into()),
+            }),
+            track_audio: None,
+            ..Default::default()
+        };
+
+        assert!(find_conflicts(&transmit, &keybinds, &radio).is_empty());
+
+        let transmit = TransmitConfig {
+            mode: TransmitMode::RadioIntegration,
+            radio_push_to_talk: Some(Code::F11.into()),
+            ..transmit
+        };
+        let conflicts = find_conflicts(&transmit, &keybinds, &radio);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].actions,
+            vec![KeybindAction::Transmit, KeybindAction::RadioEmit]
+        );
+        assert_eq!(
+            conflicts[0].to_string(),
+            "F11 is bound to transmit, AFV emit"
+        );
+    }
+}
@@ -1,16 +1,24 @@
 use crate::app::state::AppState;
 use crate::app::state::signaling::AppStateSignalingExt;
 use crate::app::state::webrtc::AppStateWebrtcExt;
+use crate::audio::PeerMuted;
 use crate::audio::manager::AudioManagerHandle;
-use crate::config::{KeybindsConfig, RadioConfig, TransmitConfig, TransmitMode};
-use crate::error::Error;
+use crate::config::{
+    CLIENT_SETTINGS_FILE_NAME, KeybindsConfig, Persistable, PersistedClientConfig, RadioConfig,
+    TransmitConfig, TransmitMode,
+};
+use crate::error::{Error, FrontendError};
+use crate::keybinds::combo::{KeyCombo, KeyComboState};
 use crate::keybinds::runtime::{DynKeybindListener, KeybindListener, PlatformListener};
 use crate::keybinds::{KeyEvent, Keybind};
-use crate::radio::{DynRadio, RadioState, TransmissionState};
+use crate::radio::watchdog::TransmitWatchdog;
+use crate::radio::{DynRadio, RadioError, RadioState, TransmissionState};
 use keyboard_types::{Code, KeyState};
 use parking_lot::RwLock;
+use serde_json::Value;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::RwLock as TokioRwLock;
@@ -23,9 +31,13 @@ use crate::platform::Platform;
 #[derive(Debug)]
 pub struct KeybindEngine {
     mode: TransmitMode,
-    transmit_code: Option<Code>,
+    transmit_combo: Option<KeyCombo>,
     accept_call_code: Option<Code>,
     end_call_code: Option<Code>,
+    next_profile_code: Option<Code>,
+    prev_profile_code: Option<Code>,
+    toggle_do_not_disturb_code: Option<Code>,
+    panic_code: Option<Code>,
     radio_config: RadioConfig,
     app: AppHandle,
     listener: RwLock<Option<DynKeybindListener>>,
@@ -37,6 +49,7 @@ pub struct KeybindEngine {
     call_active: Arc<AtomicBool>,
     radio_prio: Arc<AtomicBool>,
     implicit_radio_prio: Arc<AtomicBool>,
+    reload_pending: AtomicBool,
 }
 
 pub type KeybindEngineHandle = Arc<TokioRwLock<KeybindEngine>>;
@@ -51,9 +64,15 @@ impl KeybindEngine {
     ) -> Self {
         Self {
             mode: transmit_config.mode,
-            transmit_code: Self::select_active_transmit_code(transmit_config),
+            transmit_combo: Self::select_active_transmit_combo(transmit_config),
             accept_call_code: Self::select_accept_call_code(call_control_config),
             end_call_code: Self::select_end_call_code(call_control_config),
+            next_profile_code: Self::select_next_profile_code(call_control_config),
+            prev_profile_code: Self::select_prev_profile_code(call_control_config),
+            toggle_do_not_disturb_code: Self::select_toggle_do_not_disturb_code(
+                call_control_config,
+            ),
+            panic_code: Self::select_panic_code(call_control_config),
             radio_config: radio_config.clone(),
             app,
             listener: RwLock::new(None),
@@ -65,6 +84,7 @@ impl KeybindEngine {
             call_active: Arc::new(AtomicBool::new(false)),
             radio_prio: Arc::new(AtomicBool::new(false)),
             implicit_radio_prio: Arc::new(AtomicBool::new(false)),
+            reload_pending: AtomicBool::new(false),
         }
     }
 
@@ -72,14 +92,20 @@ impl KeybindEngine {
         if self.rx_task.is_some() {
             return Ok(());
         }
-        let has_call_controls = self.accept_call_code.is_some() || self.end_call_code.is_some();
-
-        if self.mode == TransmitMode::VoiceActivation && !has_call_controls {
+        let has_call_controls = self.accept_call_code.is_some()
+            || self.end_call_code.is_some()
+            || self.toggle_do_not_disturb_code.is_some()
+            || self.panic_code.is_some();
+        let has_profile_controls =
+            self.next_profile_code.is_some() || self.prev_profile_code.is_some();
+
+        if self.mode == TransmitMode::VoiceActivation && !has_call_controls && !has_profile_controls
+        {
             log::trace!(
-                "TransmitMode set to voice activation and no call controls defined, no keybind engine required"
+                "TransmitMode set to voice activation and no call or profile controls defined, no keybind engine required"
             );
             return Ok(());
-        } else if self.mode != TransmitMode::VoiceActivation && self.transmit_code.is_none() {
+        } else if self.mode != TransmitMode::VoiceActivation && self.transmit_combo.is_none() {
             log::trace!(
                 "No keybind set for TransmitMode {:?}, keybind engine not starting",
                 self.mode
@@ -87,17 +113,40 @@ impl KeybindEngine {
             return Ok(());
         }
 
+        // Create the radio before starting the listener, so a failing radio integration does not
+        // leave a listener running without anything processing its events.
+        let radio = if self.mode == TransmitMode::RadioIntegration {
+            match self.radio_config.radio(self.app.clone()).await {
+                Ok(radio) => radio,
+                Err(Error::Radio(err)) => match *err {
+                    // Keep the call controls working, but surface the missing radio instead of
+                    // silently transmitting nothing when the PTT key is pressed.
+                    RadioError::Unavailable(reason) => {
+                        log::warn!("Radio integration unavailable: {reason}");
+                        self.app.emit("radio:unavailable", Some(&reason)).ok();
+                        self.app
+                            .emit::<FrontendError>(
+                                "error",
+                                FrontendError::from(Error::from(RadioError::Unavailable(reason)))
+                                    .non_critical(),
+                            )
+                            .ok();
+                        None
+                    }
+                    err => return Err(err.into()),
+                },
+                Err(err) => return Err(err),
+            }
+        } else {
+            self.app.emit("radio:integration-available", false).ok();
+            None
+        };
+
         self.stop_token = Some(self.shutdown_token.child_token());
 
         let (listener, rx) = PlatformListener::start().await?;
         *self.listener.write() = Some(Arc::new(listener));
-
-        if self.mode == TransmitMode::RadioIntegration {
-            let radio = self.radio_config.radio(self.app.clone()).await?;
-            *self.radio.write() = radio;
-        } else {
-            self.app.emit("radio:integration-available", false).ok();
-        }
+        *self.radio.write() = radio;
 
         self.spawn_rx_loop(rx);
 
@@ -114,6 +163,7 @@ impl KeybindEngine {
 
         self.radio.write().take();
         self.app.emit("radio:integration-available", false).ok();
+        self.app.emit("radio:unavailable", None::<String>).ok();
 
         if let Some(stop_token) = self.stop_token.take() {
             stop_token.cancel();
@@ -136,11 +186,15 @@ impl KeybindEngine {
     ) -> Result<(), Error> {
         self.stop();
 
-        self.transmit_code = Self::select_active_transmit_code(transmit_config);
+        self.transmit_combo = Self::select_active_transmit_combo(transmit_config);
         self.mode = transmit_config.mode;
 
         self.accept_call_code = Self::select_accept_call_code(keybinds_config);
         self.end_call_code = Self::select_end_call_code(keybinds_config);
+        self.next_profile_code = Self::select_next_profile_code(keybinds_config);
+        self.prev_profile_code = Self::select_prev_profile_code(keybinds_config);
+        self.toggle_do_not_disturb_code = Self::select_toggle_do_not_disturb_code(keybinds_config);
+        self.panic_code = Self::select_panic_code(keybinds_config);
 
         self.reset_input_state();
 
@@ -161,6 +215,45 @@ impl KeybindEngine {
         Ok(())
     }
 
+    /// Applies a reloaded config, restarting the engine at most once.
+    ///
+    /// Restarting the engine resets the input state, which would mute or unmute an ongoing call.
+    /// If a call is currently active, the restart is deferred until the call ends and the latest
+    /// config stored in the app state is applied instead.
+    pub async fn reload_config(
+        &mut self,
+        transmit_config: &TransmitConfig,
+        keybinds_config: &KeybindsConfig,
+        radio_config: &RadioConfig,
+    ) -> Result<(), Error> {
+        if self.call_active.load(Ordering::Relaxed) {
+            log::info!("Call active, deferring keybind engine restart until call ends");
+            self.reload_pending.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+        self.reload_pending.store(false, Ordering::Relaxed);
+
+        self.stop();
+
+        self.transmit_combo = Self::select_active_transmit_combo(transmit_config);
+        self.mode = transmit_config.mode;
+
+        self.accept_call_code = Self::select_accept_call_code(keybinds_config);
+        self.end_call_code = Self::select_end_call_code(keybinds_config);
+        self.next_profile_code = Self::select_next_profile_code(keybinds_config);
+        self.prev_profile_code = Self::select_prev_profile_code(keybinds_config);
+        self.toggle_do_not_disturb_code = Self::select_toggle_do_not_disturb_code(keybinds_config);
+        self.panic_code = Self::select_panic_code(keybinds_config);
+
+        self.radio_config = radio_config.clone();
+
+        self.reset_input_state();
+
+        self.start().await?;
+
+        Ok(())
+    }
+
     pub async fn reconnect_radio(&self) -> Result<(), Error> {
         let radio = self.radio.read().clone();
         if let Some(radio) = radio {
@@ -194,6 +287,15 @@ impl KeybindEngine {
             self.implicit_radio_prio.store(false, Ordering::Relaxed);
             self.radio_prio.store(false, Ordering::Relaxed);
             self.app.emit("audio:implicit-radio-prio", false).ok();
+
+            if self.mode.is_toggle() && self.pressed.load(Ordering::Relaxed) {
+                log::trace!("Call ended, releasing latched {:?} key", self.mode);
+                self.reset_input_state();
+            }
+
+            if self.reload_pending.swap(false, Ordering::Relaxed) {
+                self.spawn_deferred_reload();
+            }
         }
     }
 
@@ -208,7 +310,8 @@ impl KeybindEngine {
         }
 
         match (&self.mode, self.pressed.load(Ordering::Relaxed)) {
-            (TransmitMode::VoiceActivation, _) | (TransmitMode::PushToMute, false) => {
+            (TransmitMode::VoiceActivation, _)
+            | (TransmitMode::PushToMute | TransmitMode::PushToMuteToggle, false) => {
                 log::info!(
                     "Setting audio input {}",
                     if prio { "muted" } else { "unmuted" }
@@ -224,14 +327,19 @@ impl KeybindEngine {
 
     pub fn should_attach_input_muted(&self) -> bool {
         match (&self.mode, self.pressed.load(Ordering::Relaxed)) {
-            (TransmitMode::PushToTalk, false) => true,
-            (TransmitMode::PushToMute, true) => true,
+            (TransmitMode::PushToTalk | TransmitMode::PushToTalkToggle, false) => true,
+            (TransmitMode::PushToMute | TransmitMode::PushToMuteToggle, true) => true,
             (TransmitMode::RadioIntegration, false) => true,
             (TransmitMode::RadioIntegration, true) => self.radio_prio.load(Ordering::Relaxed),
             _ => false,
         }
     }
 
+    /// Whether the transmit key is currently latched in one of the toggle transmit modes.
+    pub fn transmit_latched(&self) -> bool {
+        self.mode.is_toggle() && self.pressed.load(Ordering::Relaxed)
+    }
+
     pub fn radio_state(&self) -> RadioState {
         if let Some(radio) = self.radio.read().as_ref() {
             radio.state()
@@ -267,12 +375,47 @@ impl KeybindEngine {
         None
     }
 
+    fn spawn_deferred_reload(&self) {
+        let app = self.app.clone();
+        tauri::async_runtime::spawn(async move {
+            log::info!("Call ended, applying deferred keybind engine restart");
+
+            let (transmit_config, keybinds_config, radio_config) = {
+                let state = app.state::<AppState>();
+                let state = state.lock().await;
+                (
+                    state.config.client.transmit_config.clone(),
+                    state.config.client.keybinds.clone(),
+                    state.config.client.radio.clone(),
+                )
+            };
+
+            if let Err(err) = app
+                .state::<KeybindEngineHandle>()
+                .write()
+                .await
+                .reload_config(&transmit_config, &keybinds_config, &radio_config)
+                .await
+            {
+                log::warn!("Failed to apply deferred keybind engine restart: {err}");
+                app.emit::<FrontendError>("error", FrontendError::from(err).non_critical())
+                    .ok();
+            }
+        });
+    }
+
     fn reset_input_state(&self) {
-        self.pressed.store(false, Ordering::Relaxed);
+        if self.pressed.swap(false, Ordering::Relaxed) {
+            self.app.emit("keybinds:transmit-latched", false).ok();
+        }
 
         let muted = match &self.mode {
-            TransmitMode::PushToTalk | TransmitMode::RadioIntegration => true,
-            TransmitMode::PushToMute | TransmitMode::VoiceActivation => false,
+            TransmitMode::PushToTalk
+            | TransmitMode::PushToTalkToggle
+            | TransmitMode::RadioIntegration => true,
+            TransmitMode::PushToMute
+            | TransmitMode::PushToMuteToggle
+            | TransmitMode::VoiceActivation => false,
         };
 
         log::trace!(
@@ -340,13 +483,174 @@ impl KeybindEngine {
         }
     }
 
+    async fn handle_stations_profile_event(
+        app: &AppHandle,
+        code: Code,
+        next_profile: Option<Code>,
+        prev_profile: Option<Code>,
+    ) {
+        let forward = if next_profile.is_some_and(|c| c == code) {
+            true
+        } else if prev_profile.is_some_and(|c| c == code) {
+            false
+        } else {
+            return;
+        };
+
+        log::trace!(
+            "{} stations profile key pressed",
+            if forward { "Next" } else { "Previous" }
+        );
+
+        let persisted_client_config = {
+            let state = app.state::<AppState>();
+            let mut state = state.lock().await;
+
+            let Some(profile) = state
+                .config
+                .stations
+                .cycle_profile(&state.config.client.selected_stations_profile, forward)
+            else {
+                log::trace!("No other stations profile to switch to via keybind");
+                return;
+            };
+
+            log::info!("Switching to stations profile {profile} via keybind");
+            state.config.client.selected_stations_profile = profile.clone();
+            app.emit("signaling:stations-profile-selected", profile)
+                .ok();
+
+            PersistedClientConfig::from(state.config.client.clone())
+        };
+
+        let config_dir = app
+            .path()
+            .app_config_dir()
+            .expect("Cannot get config directory");
+        if let Err(err) = persisted_client_config.persist(&config_dir, CLIENT_SETTINGS_FILE_NAME) {
+            log::warn!("Failed to persist selected stations profile: {err}");
+        }
+    }
+
+    async fn handle_do_not_disturb_event(
+        app: &AppHandle,
+        code: Code,
+        toggle_do_not_disturb: Option<Code>,
+    ) {
+        if toggle_do_not_disturb.is_none_or(|c| c != code) {
+            return;
+        }
+        log::trace!("Toggle do-not-disturb key pressed");
+
+        let state = app.state::<AppState>();
+        let mut state = state.lock().await;
+        let enabled = !state.do_not_disturb();
+        state.set_do_not_disturb(app, enabled);
+    }
+
+    /// Instantly cuts all audio: mutes the input, ends any radio transmission, stops all
+    /// notification sounds and mutes the active call locally. The transmit key is released and
+    /// ignored until it is pressed again, overriding any transmission in progress. The call
+    /// itself is kept, so it can be resumed by unmuting the peer and transmitting again.
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_panic_event(
+        app: &AppHandle,
+        mode: TransmitMode,
+        radio: Option<&DynRadio>,
+        watchdog: &TransmitWatchdog,
+        pressed: &Arc<AtomicBool>,
+        radio_prio: &AtomicBool,
+        implicit_radio_prio: &AtomicBool,
+        panic_latch: &mut PanicLatch,
+    ) {
+        log::info!("Panic key pressed, cutting all audio");
+
+        if panic_latch.engage(pressed, !mode.is_toggle()) && mode.is_toggle() {
+            app.emit("keybinds:transmit-latched", false).ok();
+        }
+        Self::set_input_muted(app, true);
+
+        if let Some(radio) = radio {
+            Self::set_radio_transmit(app, radio, TransmissionState::Inactive, watchdog, pressed)
+                .await;
+        }
+        if implicit_radio_prio.swap(false, Ordering::Relaxed)
+            && radio_prio.swap(false, Ordering::Relaxed)
+        {
+            app.emit("audio:implicit-radio-prio", false).ok();
+        }
+
+        {
+            let state = app.state::<AppState>();
+            let state = state.lock().await;
+            let call_muted = app
+                .state::<AudioManagerHandle>()
+                .write()
+                .silence_output(state.config.audio.output_device_volume);
+
+            if call_muted && let Some(peer_id) = state.active_call_peer_id() {
+                app.emit(
+                    "call:peer-muted",
+                    PeerMuted {
+                        peer_id: peer_id.clone(),
+                        muted: true,
+                    },
+                )
+                .ok();
+            }
+        }
+
+        app.emit("audio:panic", Value::Null).ok();
+    }
+
+    /// Updates the pressed state for a transmit key event, returning whether the audio input
+    /// should be muted or `None` if the event does not change the transmit state.
+    fn transmit_muted(mode: TransmitMode, state: KeyState, pressed: &AtomicBool) -> Option<bool> {
+        match (mode, state) {
+            (
+                TransmitMode::PushToTalk
+                | TransmitMode::PushToTalkToggle
+                | TransmitMode::RadioIntegration,
+                KeyState::Down,
+            ) if !pressed.swap(true, Ordering::Relaxed) => Some(false),
+            (
+                TransmitMode::PushToTalk
+                | TransmitMode::PushToTalkToggle
+                | TransmitMode::RadioIntegration,
+                KeyState::Up,
+            ) if pressed.swap(false, Ordering::Relaxed) => Some(true),
+            (TransmitMode::PushToMute | TransmitMode::PushToMuteToggle, KeyState::Down)
+                if !pressed.swap(true, Ordering::Relaxed) =>
+            {
+                Some(true)
+            }
+            (TransmitMode::PushToMute | TransmitMode::PushToMuteToggle, KeyState::Up)
+                if pressed.swap(false, Ordering::Relaxed) =>
+            {
+                Some(false)
+            }
+            _ => None,
+        }
+    }
+
     fn spawn_rx_loop(&mut self, mut rx: UnboundedReceiver<KeyEvent>) {
         let app = self.app.clone();
-        let transmit = self.transmit_code;
+        let transmit = self.transmit_combo.clone();
         let accept_call = self.accept_call_code;
         let end_call = self.end_call_code;
-
-        if transmit.is_none() && accept_call.is_none() && end_call.is_none() {
+        let next_profile = self.next_profile_code;
+        let prev_profile = self.prev_profile_code;
+        let toggle_do_not_disturb = self.toggle_do_not_disturb_code;
+        let panic = self.panic_code;
+
+        if transmit.is_none()
+            && accept_call.is_none()
+            && end_call.is_none()
+            && next_profile.is_none()
+            && prev_profile.is_none()
+            && toggle_do_not_disturb.is_none()
+            && panic.is_none()
+        {
             return;
         }
 
@@ -360,12 +664,19 @@ impl KeybindEngine {
         let call_active = self.call_active.clone();
         let radio_prio = self.radio_prio.clone();
         let implicit_radio_prio = self.implicit_radio_prio.clone();
+        let watchdog = TransmitWatchdog::new(Duration::from_secs(
+            self.radio_config.max_transmit_duration_secs,
+        ));
 
         let handle = tauri::async_runtime::spawn(async move {
             log::debug!(
-                "Keybind engine starting: mode={mode:?}, transmit={transmit:?}, accept_call={accept_call:?}, end_call={end_call:?}",
+                "Keybind engine starting: mode={mode:?}, transmit={transmit:?}, accept_call={accept_call:?}, end_call={end_call:?}, next_profile={next_profile:?}, prev_profile={prev_profile:?}, toggle_do_not_disturb={toggle_do_not_disturb:?}, panic={panic:?}",
             );
 
+            let mut transmit_state = transmit.map(KeyComboState::new);
+            let mut toggle_held = false;
+            let mut panic_latch = PanicLatch::default();
+
             loop {
                 tokio::select! {
                     biased;
@@ -373,28 +684,62 @@ impl KeybindEngine {
                     res = rx.recv() => {
                         let Some(event) = res else { break; };
 
-                        if event.state == KeyState::Down {
-                            Self::handle_call_control_event(&app, event.code, accept_call, end_call).await;
+                        if event.state == KeyState::Down && let Some(code) = event.code.as_key() {
+                            Self::handle_call_control_event(&app, code, accept_call, end_call).await;
+                            Self::handle_stations_profile_event(&app, code, next_profile, prev_profile).await;
+                            Self::handle_do_not_disturb_event(&app, code, toggle_do_not_disturb).await;
+
+                            if panic.is_some_and(|c| c == code) {
+                                Self::handle_panic_event(&app, mode, radio.as_ref(), &watchdog, &pressed, &radio_prio, &implicit_radio_prio, &mut panic_latch).await;
+                            }
                         }
 
-                        if transmit.is_none_or(|c| c != event.code) {
+                        let Some(state) = transmit_state
+                            .as_mut()
+                            .and_then(|combo| combo.handle(event.code, event.state))
+                            .and_then(|state| panic_latch.filter(state)) else {
                             continue;
-                        }
+                        };
+
+                        // Toggle modes flip the latched state on each key press, ignoring repeats and releases.
+                        // Latching is only possible during a call, so a call never starts keyed.
+                        let state = if mode.is_toggle() {
+                            match state {
+                                KeyState::Down if !toggle_held => {
+                                    toggle_held = true;
+                                    if pressed.load(Ordering::Relaxed) {
+                                        KeyState::Up
+                                    } else if call_active.load(Ordering::Relaxed) {
+                                        KeyState::Down
+                                    } else {
+                                        log::trace!("No call active, ignoring {mode:?} key press");
+                                        continue;
+                                    }
+                                }
+                                KeyState::Down => continue,
+                                KeyState::Up => {
+                                    toggle_held = false;
+                                    continue;
+                                }
+                            }
+                        } else {
+                            state
+                        };
 
-                        let muted = match (&mode, &event.state) {
-                            (TransmitMode::PushToTalk | TransmitMode::RadioIntegration, KeyState::Down) if !pressed.swap(true, Ordering::Relaxed) => false,
-                            (TransmitMode::PushToTalk | TransmitMode::RadioIntegration, KeyState::Up) if pressed.swap(false, Ordering::Relaxed) => true,
-                            (TransmitMode::PushToMute, KeyState::Down) if !pressed.swap(true, Ordering::Relaxed) => true,
-                            (TransmitMode::PushToMute, KeyState::Up) if pressed.swap(false, Ordering::Relaxed) => false,
-                            _ => continue,
+                        let Some(muted) = Self::transmit_muted(mode, state, &pressed) else {
+                            continue;
                         };
 
+                        if mode.is_toggle() {
+                            app.emit("keybinds:transmit-latched", state.is_down()).ok();
+                        }
+
                         match (&mode, call_active.load(Ordering::Relaxed), radio_prio.load(Ordering::Relaxed)) {
                             (TransmitMode::RadioIntegration, false, _) => {
-                                let state = event.state.into();
+                                let state = state.into();
                                 if let Some(radio) = radio.as_ref() {
                                     log::trace!("No call active, setting radio transmission {state:?}");
-                                    Self::set_radio_transmit(radio, state).await;
+                                    Self::set_radio_transmit(&app, radio, state, &watchdog, &pressed).await;
                                 } else {
                                     log::trace!("No call active, radio not initialized, cannot set transmission {state:?}");
                                 }
@@ -404,21 +749,21 @@ impl KeybindEngine {
                                 Self::set_input_muted(&app, muted);
                             },
                             (TransmitMode::RadioIntegration, true, true) => {
-                                let state = event.state.into();
+                                let state = state.into();
                                 if let Some(radio) = radio.as_ref() {
                                     log::trace!("Call active, radio prio set, setting audio input muted and radio transmission {state:?}");
                                     Self::set_input_muted(&app, true);
-                                    Self::set_radio_transmit(radio, state).await;
+                                    Self::set_radio_transmit(&app, radio, state, &watchdog, &pressed).await;
                                 } else {
                                     log::trace!("Call active, radio prio set, radio not initialized, setting audio input muted, but cannot set transmission {state:?}");
                                     Self::set_input_muted(&app, true);
                                 }
                             }
-                            (TransmitMode::PushToTalk | TransmitMode::PushToMute, true, false) => {
+                            (TransmitMode::PushToTalk | TransmitMode::PushToTalkToggle | TransmitMode::PushToMute | TransmitMode::PushToMuteToggle, true, false) => {
                                 log::trace!("Call active, setting audio input {}", if muted { "muted" } else { "unmuted" });
                                 Self::set_input_muted(&app, muted);
                             },
-                            (TransmitMode::PushToTalk, true, true) => {
+                            (TransmitMode::PushToTalk | TransmitMode::PushToTalkToggle, true, true) => {
                                 log::trace!("Call active, would set audio input {}, but radio prio is set, so keeping audio input muted", if muted { "muted" } else { "unmuted" });
                                 Self::set_input_muted(&app, true);
                             }
@@ -426,13 +771,13 @@ impl KeybindEngine {
 
                         }
 
-                        if event.state.is_up() && implicit_radio_prio.swap(false, Ordering::Relaxed) {
+                        if state.is_up() && implicit_radio_prio.swap(false, Ordering::Relaxed) {
                             if radio_prio.swap(false, Ordering::Relaxed) {
                                 log::trace!("Implicit radio prio cleared on {:?} key release", mode);
                                 app.emit("audio:implicit-radio-prio", false).ok();
                             } else if let Some(radio) = radio.as_ref() {
                                 log::trace!("Implicit radio prio cleared on {mode:?} key release, but radio prio was not set. Setting transmission Inactive");
-                                Self::set_radio_transmit(radio, TransmissionState::Inactive).await;
+                                Self::set_radio_transmit(&app, radio, TransmissionState::Inactive, &watchdog, &pressed).await;
                             } else {
                                 log::trace!("Implicit radio prio cleared on {mode:?} key release, but radio not initialized, ignoring");
                             }
@@ -448,7 +793,7 @@ impl KeybindEngine {
     }
 
     #[inline]
-    fn select_active_transmit_code(config: &TransmitConfig) -> Option<Code> {
+    fn select_active_transmit_combo(config: &TransmitConfig) -> Option<KeyCombo> {
         #[cfg(target_os = "linux")]
         if matches!(Platform::get(), Platform::LinuxWayland) {
             // Wayland Code Mapping Strategy:
@@ -462,27 +807,31 @@ impl KeybindEngine {
             // conflict with user input. When the portal activates a shortcut, we emit the
             // corresponding F-key code, and the rest of the keybind engine works unchanged.
             //
-            // This effectively overrides the user-configured codes in the config file on Wayland,
-            // since the actual key binding is managed by the desktop environment.
+            // This effectively overrides the user-configured codes and combos in the config file
+            // on Wayland, since the actual key binding is managed by the desktop environment.
+            // Gamepad buttons are the exception, as they are read directly from the device and
+            // never go through the portal.
+            if let Some(combo) = config
+                .active_combo()
+                .filter(|combo| matches!(combo.code, crate::keybinds::InputCode::Gamepad(_)))
+            {
+                return Some(combo.clone());
+            }
+
             let code = match config.mode {
                 TransmitMode::VoiceActivation => None,
-                TransmitMode::PushToTalk => Some(Code::F33),
-                TransmitMode::PushToMute => Some(Code::F34),
+                TransmitMode::PushToTalk | TransmitMode::PushToTalkToggle => Some(Code::F33),
+                TransmitMode::PushToMute | TransmitMode::PushToMuteToggle => Some(Code::F34),
                 TransmitMode::RadioIntegration => Some(Code::F35),
             };
             log::trace!(
                 "Using portal shortcut code {code:?} for transmit mode {:?}",
                 config.mode
             );
-            return code;
+            return code.map(KeyCombo::from);
         }
 
-        match config.mode {
-            TransmitMode::VoiceActivation => None,
-            TransmitMode::PushToTalk => config.push_to_talk,
-            TransmitMode::PushToMute => config.push_to_mute,
-            TransmitMode::RadioIntegration => config.radio_push_to_talk,
-        }
+        config.active_combo().cloned()
     }
 
     #[inline]
@@ -513,6 +862,58 @@ impl KeybindEngine {
         config.end_call
     }
 
+    #[inline]
+    fn select_next_profile_code(config: &KeybindsConfig) -> Option<Code> {
+        #[cfg(target_os = "linux")]
+        if matches!(Platform::get(), Platform::LinuxWayland) {
+            // Wayland Code Mapping Strategy:
+            // Same as with the transmit code, we define our global shortcuts on OS level.
+            // Cycling stations profiles uses F30 (next) and F31 (previous).
+            return Some(Code::F30);
+        }
+
+        config.next_profile
+    }
+
+    #[inline]
+    fn select_prev_profile_code(config: &KeybindsConfig) -> Option<Code> {
+        #[cfg(target_os = "linux")]
+        if matches!(Platform::get(), Platform::LinuxWayland) {
+            // Wayland Code Mapping Strategy:
+            // Same as with the transmit code, we define our global shortcuts on OS level.
+            // Cycling stations profiles uses F30 (next) and F31 (previous).
+            return Some(Code::F31);
+        }
+
+        config.prev_profile
+    }
+
+    #[inline]
+    fn select_toggle_do_not_disturb_code(config: &KeybindsConfig) -> Option<Code> {
+        #[cfg(target_os = "linux")]
+        if matches!(Platform::get(), Platform::LinuxWayland) {
+            // Wayland Code Mapping Strategy:
+            // Same as with the transmit code, we define our global shortcuts on OS level.
+            // Toggling do-not-disturb uses F29.
+            return Some(Code::F29);
+        }
+
+        config.toggle_do_not_disturb
+    }
+
+    #[inline]
+    fn select_panic_code(config: &KeybindsConfig) -> Option<Code> {
+        #[cfg(target_os = "linux")]
+        if matches!(Platform::get(), Platform::LinuxWayland) {
+            // Wayland Code Mapping Strategy:
+            // Same as with the transmit code, we define our global shortcuts on OS level.
+            // The panic key uses F28.
+            return Some(Code::F28);
+        }
+
+        config.panic
+    }
+
     #[inline]
     fn set_input_muted(app: &AppHandle, muted: bool) {
         app.state::<AudioManagerHandle>()
@@ -520,10 +921,41 @@ impl KeybindEngine {
             .set_input_muted(muted);
     }
 
-    #[inline]
-    async fn set_radio_transmit(radio: &DynRadio, state: TransmissionState) {
+    /// Sets the radio transmission state, arming the watchdog for active transmissions.
+    async fn set_radio_transmit(
+        app: &AppHandle,
+        radio: &DynRadio,
+        state: TransmissionState,
+        watchdog: &TransmitWatchdog,
+        pressed: &Arc<AtomicBool>,
+    ) {
         if let Err(err) = radio.transmit(state).await {
             log::warn!("Failed to set radio transmission state {state:?}: {err}");
+            return;
+        }
+
+        match state {
+            TransmissionState::Active => {
+                let app = app.clone();
+                let pressed = pressed.clone();
+                watchdog.arm(radio.clone(), move |max_duration| {
+                    // Treat the transmit key as released, so the next press starts a new transmission
+                    pressed.store(false, Ordering::Relaxed);
+                    app.emit::<FrontendError>(
+                        "error",
+                        FrontendError::new(
+                            "Radio transmission stopped",
+                            format!(
+                                "Radio transmission was stopped after {}s, as the transmit key was not released.",
+                                max_duration.as_secs()
+                            ),
+                        )
+                        .non_critical(),
+                    )
+                    .ok();
+                });
+            }
+            TransmissionState::Inactive => watchdog.disarm(),
         }
     }
 }
@@ -533,3 +965,100 @@ impl Drop for KeybindEngine {
         self.stop();
     }
 }
+
+/// Releases the transmit key when the panic key is pressed, ignoring it until it is released.
+///
+/// Without it, key repeats of a transmit key still held down would immediately resume the
+/// transmission that was just cut.
+#[derive(Debug, Default)]
+struct PanicLatch {
+    engaged: bool,
+}
+
+impl PanicLatch {
+    /// Releases the transmit key, returning whether it was pressed. `held` indicates whether
+    /// the key is physically held down while pressed, which is not the case in toggle modes.
+    fn engage(&mut self, pressed: &AtomicBool, held: bool) -> bool {
+        let was_pressed = pressed.swap(false, Ordering::Relaxed);
+        self.engaged = was_pressed && held;
+        was_pressed
+    }
+
+    /// Filters transmit key events, swallowing them while engaged until the key is released.
+    fn filter(&mut self, state: KeyState) -> Option<KeyState> {
+        if !self.engaged {
+            return Some(state);
+        }
+        if state.is_up() {
+            self.engaged = false;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle_ptt(latch: &mut PanicLatch, pressed: &AtomicBool, state: KeyState) -> Option<bool> {
+        latch.filter(state).and_then(|state| {
+            KeybindEngine::transmit_muted(TransmitMode::PushToTalk, state, pressed)
+        })
+    }
+
+    #[test]
+    fn panic_overrides_push_to_talk_transmission() {
+        let pressed = AtomicBool::new(false);
+        let mut latch = PanicLatch::default();
+
+        assert_eq!(
+            handle_ptt(&mut latch, &pressed, KeyState::Down),
+            Some(false)
+        );
+        assert_eq!(handle_ptt(&mut latch, &pressed, KeyState::Down), None);
+
+        assert!(latch.engage(&pressed, true));
+        assert!(!pressed.load(Ordering::Relaxed));
+
+        // Key repeats and the release of the still held key do not resume the transmission
+        assert_eq!(handle_ptt(&mut latch, &pressed, KeyState::Down), None);
+        assert_eq!(handle_ptt(&mut latch, &pressed, KeyState::Up), None);
+        assert!(!pressed.load(Ordering::Relaxed));
+
+        // Pressing the key again transmits as usual
+        assert_eq!(
+            handle_ptt(&mut latch, &pressed, KeyState::Down),
+            Some(false)
+        );
+        assert_eq!(handle_ptt(&mut latch, &pressed, KeyState::Up), Some(true));
+    }
+
+    #[test]
+    fn panic_releases_latched_toggle_transmission() {
+        let pressed = AtomicBool::new(false);
+        let mut latch = PanicLatch::default();
+
+        assert_eq!(
+            KeybindEngine::transmit_muted(TransmitMode::PushToTalkToggle, KeyState::Down, &pressed),
+            Some(false)
+        );
+
+        // The latched key is not held down, so the next press is not swallowed
+        assert!(latch.engage(&pressed, false));
+        assert!(!pressed.load(Ordering::Relaxed));
+        assert_eq!(latch.filter(KeyState::Down), Some(KeyState::Down));
+    }
+
+    #[test]
+    fn panic_without_transmission() {
+        let pressed = AtomicBool::new(false);
+        let mut latch = PanicLatch::default();
+
+        assert!(!latch.engage(&pressed, true));
+        assert_eq!(latch.filter(KeyState::Down), Some(KeyState::Down));
+        assert_eq!(
+            KeybindEngine::transmit_muted(TransmitMode::PushToMute, KeyState::Down, &pressed),
+            Some(true)
+        );
+    }
+}
@@ -31,17 +31,30 @@
 //! - **Linux**: Runtime detection (see `linux.rs`) to choose between Wayland/X11/Unknown
 //! - **Other**: Stub no-op implementation
 //!
+//! ## Gamepads
+//!
+//! Gamepad and joystick buttons are captured by a separate `GamepadListener` that reads the
+//! devices directly and works the same on all platforms, including Wayland. `PlatformListener`
+//! merges its events with the ones of the platform's native keyboard listener. If gamepad
+//! support cannot be initialized, the native listener is used on its own.
+//!
 //! ## External Bindings
 //!
 //! The `get_external_binding()` method on `KeybindListener` allows querying OS-configured
 //! keybinds. This is currently only implemented for Wayland, where shortcuts are configured
 //! in the desktop environment rather than in the app.
 
+mod gamepad;
+
+pub use gamepad::GamepadListener;
+
 use crate::keybinds::{KeyEvent, Keybind, KeybindsError};
+use crate::platform::Platform;
 use keyboard_types::{Code, KeyState};
+use serde::Serialize;
 use std::fmt::Debug;
 use std::sync::Arc;
-use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
 
 /// Trait for platform-specific keybind listeners that capture global keyboard events.
 ///
@@ -76,27 +89,230 @@ pub trait KeybindEmitter: Send + Sync + Debug + 'static {
         Self: Sized;
 
     fn emit(&self, code: Code, state: KeyState) -> Result<(), KeybindsError>;
+
+    /// Emits all given keys, pressing them in order and releasing them in reverse order.
+    ///
+    /// If pressing a key fails, the keys already pressed are released again. Releasing attempts
+    /// all keys even if some fail, returning the first error.
+    fn emit_all(&self, codes: &[Code], state: KeyState) -> Result<(), KeybindsError> {
+        match state {
+            KeyState::Down => {
+                for (i, code) in codes.iter().enumerate() {
+                    if let Err(err) = self.emit(*code, KeyState::Down) {
+                        self.emit_all(&codes[..i], KeyState::Up).ok();
+                        return Err(err);
+                    }
+                }
+                Ok(())
+            }
+            KeyState::Up => codes
+                .iter()
+                .rev()
+                .map(|code| self.emit(*code, KeyState::Up))
+                .fold(Ok(()), Result::and),
+        }
+    }
 }
 
 pub type DynKeybindEmitter = Arc<dyn KeybindEmitter>;
 
+/// Concrete keybind listener or emitter implementation selected for a platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeybindBackend {
+    /// Win32 low-level keyboard hooks and `SendInput`.
+    Win32,
+    /// Core Graphics event taps.
+    CoreGraphics,
+    /// XDG Desktop Portal global shortcuts.
+    WaylandPortal,
+    /// No-op stub, keybinds are not available.
+    Noop,
+}
+
+impl KeybindBackend {
+    /// Listener backend used on the given platform. On Wayland, the portal backend is only used if
+    /// the global shortcuts portal is available.
+    pub fn listener(platform: Platform, portal_available: bool) -> Self {
+        match platform {
+            Platform::Windows => KeybindBackend::Win32,
+            Platform::MacOs => KeybindBackend::CoreGraphics,
+            Platform::LinuxWayland if portal_available => KeybindBackend::WaylandPortal,
+            Platform::LinuxWayland
+            | Platform::LinuxX11
+            | Platform::LinuxUnknown
+            | Platform::Unknown => KeybindBackend::Noop,
+        }
+    }
+
+    /// Emitter backend used on the given platform, see the `linux` module for why all Linux
+    /// platforms use the no-op stub.
+    pub fn emitter(platform: Platform) -> Self {
+        match platform {
+            Platform::Windows => KeybindBackend::Win32,
+            Platform::MacOs => KeybindBackend::CoreGraphics,
+            Platform::LinuxWayland
+            | Platform::LinuxX11
+            | Platform::LinuxUnknown
+            | Platform::Unknown => KeybindBackend::Noop,
+        }
+    }
+
+    pub fn is_available(self) -> bool {
+        self != KeybindBackend::Noop
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
         mod windows;
         pub use windows::WindowsKeybindEmitter as PlatformEmitter;
-        pub use windows::WindowsKeybindListener as PlatformListener;
+        use windows::WindowsKeybindListener as NativeListener;
     } else if #[cfg(target_os = "macos")] {
         mod macos;
-        pub use macos::MacOsKeybindListener as PlatformListener;
+        use macos::MacOsKeybindListener as NativeListener;
         pub use macos::MacOsKeybindEmitter as PlatformEmitter;
     } else if #[cfg(target_os = "linux")] {
         mod linux;
         mod stub;
         pub use linux::LinuxKeybindEmitter as PlatformEmitter;
-        pub use linux::LinuxKeybindListener as PlatformListener;
+        use linux::LinuxKeybindListener as NativeListener;
     } else {
         mod stub;
         pub use stub::NoopKeybindEmitter as PlatformEmitter;
-        pub use stub::NoopKeybindListener as PlatformListener;
+        use stub::NoopKeybindListener as NativeListener;
+    }
+}
+
+/// Keybind listener combining the platform's native keyboard listener with the gamepad listener.
+#[derive(Debug)]
+pub struct PlatformListener {
+    native: NativeListener,
+    _gamepad: Option<GamepadListener>,
+}
+
+impl KeybindListener for PlatformListener {
+    async fn start() -> Result<(Self, UnboundedReceiver<KeyEvent>), KeybindsError>
+    where
+        Self: Sized,
+    {
+        let (native, native_rx) = NativeListener::start().await?;
+        let (tx, rx) = unbounded_channel();
+        Self::forward(native_rx, tx.clone());
+
+        let gamepad = match GamepadListener::start().await {
+            Ok((gamepad, gamepad_rx)) => {
+                Self::forward(gamepad_rx, tx);
+                Some(gamepad)
+            }
+            Err(err) => {
+                log::warn!("Gamepad listener not available, gamepad keybinds will not work: {err}");
+                None
+            }
+        };
+
+        Ok((
+            Self {
+                native,
+                _gamepad: gamepad,
+            },
+            rx,
+        ))
+    }
+
+    fn get_external_binding(&self, keybind: Keybind) -> Option<String> {
+        self.native.get_external_binding(keybind)
+    }
+}
+
+impl PlatformListener {
+    /// Forwards all events of a listener into the merged channel until either side is closed.
+    fn forward(mut rx: UnboundedReceiver<KeyEvent>, tx: UnboundedSender<KeyEvent>) {
+        tauri::async_runtime::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                if tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Emitter recording all emitted keys, failing to emit `fail_on`.
+    #[derive(Debug, Default)]
+    struct RecordingEmitter {
+        emitted: Mutex<Vec<(Code, KeyState)>>,
+        fail_on: Option<Code>,
+    }
+
+    impl KeybindEmitter for RecordingEmitter {
+        fn start() -> Result<Self, KeybindsError> {
+            Ok(Self::default())
+        }
+
+        fn emit(&self, code: Code, state: KeyState) -> Result<(), KeybindsError> {
+            if self.fail_on == Some(code) {
+                return Err(KeybindsError::Emitter(format!("failed to emit {code}")));
+            }
+            self.emitted.lock().unwrap().push((code, state));
+            Ok(())
+        }
+    }
+
+    const CODES: [Code; 3] = [Code::ControlLeft, Code::ShiftLeft, Code::F12];
+
+    #[test]
+    fn emit_all_presses_in_order_and_releases_in_reverse() {
+        let emitter = RecordingEmitter::default();
+
+        emitter.emit_all(&CODES, KeyState::Down).unwrap();
+        emitter.emit_all(&CODES, KeyState::Up).unwrap();
+
+        assert_eq!(
+            *emitter.emitted.lock().unwrap(),
+            vec![
+                (Code::ControlLeft, KeyState::Down),
+                (Code::ShiftLeft, KeyState::Down),
+                (Code::F12, KeyState::Down),
+                (Code::F12, KeyState::Up),
+                (Code::ShiftLeft, KeyState::Up),
+                (Code::ControlLeft, KeyState::Up),
+            ]
+        );
+    }
+
+    #[test]
+    fn emit_all_releases_pressed_keys_on_failure() {
+        let emitter = RecordingEmitter {
+            fail_on: Some(Code::F12),
+            ..Default::default()
+        };
+
+        assert!(emitter.emit_all(&CODES, KeyState::Down).is_err());
+        assert_eq!(
+            *emitter.emitted.lock().unwrap(),
+            vec![
+                (Code::ControlLeft, KeyState::Down),
+                (Code::ShiftLeft, KeyState::Down),
+                (Code::ShiftLeft, KeyState::Up),
+                (Code::ControlLeft, KeyState::Up),
+            ]
+        );
+
+        // Releasing continues past the failing key
+        emitter.emitted.lock().unwrap().clear();
+        assert!(emitter.emit_all(&CODES, KeyState::Up).is_err());
+        assert_eq!(
+            *emitter.emitted.lock().unwrap(),
+            vec![
+                (Code::ShiftLeft, KeyState::Up),
+                (Code::ControlLeft, KeyState::Up),
+            ]
+        );
     }
 }
@@ -0,0 +1,146 @@
+use crate::keybinds::runtime::KeybindListener;
+use crate::keybinds::{GamepadButton, KeyEvent, KeybindsError};
+use gilrs::{Event, EventType, GamepadId, Gilrs};
+use keyboard_types::KeyState;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use tokio::sync::oneshot;
+
+/// Maximum time the event loop blocks waiting for gamepad events before checking whether
+/// it should stop.
+const POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Listens for button presses on all connected gamepads and joysticks.
+///
+/// Gamepads are read directly from the device, so this works independently of the platform's
+/// keyboard listener (including Wayland, where keyboard shortcuts must go through the portal).
+/// Gamepads connected after startup are picked up automatically.
+#[derive(Debug)]
+pub struct GamepadListener {
+    stop: Arc<AtomicBool>,
+    thread_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl KeybindListener for GamepadListener {
+    async fn start() -> Result<(Self, UnboundedReceiver<KeyEvent>), KeybindsError>
+    where
+        Self: Sized,
+    {
+        log::debug!("Starting gamepad listener");
+        let (key_event_tx, key_event_rx) = unbounded_channel::<KeyEvent>();
+        let (startup_res_tx, start_res_rx) = oneshot::channel::<Result<(), KeybindsError>>();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_stop = stop.clone();
+        let thread_handle = thread::Builder::new()
+            .name("VACS_Gamepad_EventLoop".to_string())
+            .spawn(move || {
+                log::debug!("Gamepad thread started");
+                match Gilrs::new() {
+                    Ok(gilrs) => {
+                        for (id, gamepad) in gilrs.gamepads() {
+                            log::debug!("Found gamepad {id}: {}", gamepad.name());
+                        }
+                        let _ = startup_res_tx.send(Ok(()));
+                        Self::run_event_loop(gilrs, key_event_tx, thread_stop);
+                    }
+                    Err(err) => {
+                        let _ = startup_res_tx.send(Err(KeybindsError::Listener(format!(
+                            "Failed to initialize gamepad support: {err}"
+                        ))));
+                    }
+                }
+                log::debug!("Gamepad thread finished");
+            })
+            .map_err(|err| KeybindsError::Listener(format!("Failed to spawn thread: {err}")))?;
+
+        match tokio::time::timeout(Duration::from_secs(1), start_res_rx).await {
+            Ok(Ok(Ok(()))) => Ok((
+                Self {
+                    stop,
+                    thread_handle: Some(thread_handle),
+                },
+                key_event_rx,
+            )),
+            Ok(Ok(Err(err))) => Err(err),
+            Ok(Err(_)) => Err(KeybindsError::Listener(
+                "GamepadListener startup channel closed".to_string(),
+            )),
+            Err(_) => {
+                stop.store(true, Ordering::Relaxed);
+                Err(KeybindsError::Listener(
+                    "GamepadListener startup timed out".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+impl Drop for GamepadListener {
+    fn drop(&mut self) {
+        if let Some(handle) = self.thread_handle.take() {
+            log::debug!("Stopping gamepad listener");
+            self.stop.store(true, Ordering::Relaxed);
+            _ = handle.join();
+        }
+    }
+}
+
+impl GamepadListener {
+    fn run_event_loop(mut gilrs: Gilrs, tx: UnboundedSender<KeyEvent>, stop: Arc<AtomicBool>) {
+        // Buttons currently held per gamepad, released if the gamepad disconnects mid-press
+        // to avoid getting stuck transmitting.
+        let mut pressed: HashMap<GamepadId, HashSet<GamepadButton>> = HashMap::new();
+
+        while !stop.load(Ordering::Relaxed) {
+            let Some(Event { id, event, .. }) = gilrs.next_event_blocking(Some(POLL_TIMEOUT))
+            else {
+                continue;
+            };
+
+            let (button, state) = match event {
+                EventType::ButtonPressed(_, code) => {
+                    (GamepadButton(code.into_u32()), KeyState::Down)
+                }
+                EventType::ButtonReleased(_, code) => {
+                    (GamepadButton(code.into_u32()), KeyState::Up)
+                }
+                EventType::Connected => {
+                    log::info!("Gamepad {id} connected: {}", gilrs.gamepad(id).name());
+                    continue;
+                }
+                EventType::Disconnected => {
+                    log::info!("Gamepad {id} disconnected");
+                    for button in pressed.remove(&id).unwrap_or_default() {
+                        Self::send(&tx, button, KeyState::Up);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            let buttons = pressed.entry(id).or_default();
+            let changed = match state {
+                KeyState::Down => buttons.insert(button),
+                KeyState::Up => buttons.remove(&button),
+            };
+            if changed {
+                Self::send(&tx, button, state);
+            }
+        }
+    }
+
+    fn send(tx: &UnboundedSender<KeyEvent>, button: GamepadButton, state: KeyState) {
+        if let Err(err) = tx.send(KeyEvent {
+            code: button.into(),
+            label: button.to_string(),
+            state,
+        }) {
+            log::error!("Failed to send keybinds event: {err}");
+        }
+    }
+}
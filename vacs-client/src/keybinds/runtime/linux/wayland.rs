@@ -24,6 +24,10 @@
 //! represented as a single `keyboard_types::Code`. To work around this, we map each
 //! transmit mode to a unique function key:
 //!
+//! - `Panic` → `Code::F28`
+//! - `ToggleDoNotDisturb` → `Code::F29`
+//! - `NextStationsProfile` → `Code::F30`
+//! - `PrevStationsProfile` → `Code::F31`
 //! - `CallControl` → `Code::F32`
 //! - `PushToTalk` → `Code::F33`
 //! - `PushToMute` → `Code::F34`
@@ -61,6 +65,10 @@ pub enum PortalShortcutId {
     PushToMute,
     RadioIntegration,
     CallControl,
+    NextStationsProfile,
+    PrevStationsProfile,
+    ToggleDoNotDisturb,
+    Panic,
 }
 
 impl PortalShortcutId {
@@ -70,6 +78,10 @@ impl PortalShortcutId {
             PortalShortcutId::PushToMute => "push_to_mute",
             PortalShortcutId::RadioIntegration => "radio_integration",
             PortalShortcutId::CallControl => "call_control",
+            PortalShortcutId::NextStationsProfile => "next_stations_profile",
+            PortalShortcutId::PrevStationsProfile => "prev_stations_profile",
+            PortalShortcutId::ToggleDoNotDisturb => "toggle_do_not_disturb",
+            PortalShortcutId::Panic => "panic",
         }
     }
 
@@ -79,6 +91,10 @@ impl PortalShortcutId {
             PortalShortcutId::PushToMute => "Push-to-mute (mute microphone while held)",
             PortalShortcutId::RadioIntegration => "Radio Integration",
             PortalShortcutId::CallControl => "Call Control (end active/accept next)",
+            PortalShortcutId::NextStationsProfile => "Switch to next stations profile",
+            PortalShortcutId::PrevStationsProfile => "Switch to previous stations profile",
+            PortalShortcutId::ToggleDoNotDisturb => "Toggle do not disturb",
+            PortalShortcutId::Panic => "Panic (mute all audio)",
         }
     }
 
@@ -88,13 +104,19 @@ impl PortalShortcutId {
             PortalShortcutId::PushToMute,
             PortalShortcutId::RadioIntegration,
             PortalShortcutId::CallControl,
+            PortalShortcutId::NextStationsProfile,
+            PortalShortcutId::PrevStationsProfile,
+            PortalShortcutId::ToggleDoNotDisturb,
+            PortalShortcutId::Panic,
         ]
     }
 
     pub const fn from_transmit_mode(mode: crate::config::TransmitMode) -> Option<Self> {
         match mode {
-            crate::config::TransmitMode::PushToTalk => Some(PortalShortcutId::PushToTalk),
-            crate::config::TransmitMode::PushToMute => Some(PortalShortcutId::PushToMute),
+            crate::config::TransmitMode::PushToTalk
+            | crate::config::TransmitMode::PushToTalkToggle => Some(PortalShortcutId::PushToTalk),
+            crate::config::TransmitMode::PushToMute
+            | crate::config::TransmitMode::PushToMuteToggle => Some(PortalShortcutId::PushToMute),
             crate::config::TransmitMode::RadioIntegration => {
                 Some(PortalShortcutId::RadioIntegration)
             }
@@ -111,6 +133,10 @@ impl FromStr for PortalShortcutId {
             "push_to_mute" => Ok(PortalShortcutId::PushToMute),
             "radio_integration" => Ok(PortalShortcutId::RadioIntegration),
             "call_control" => Ok(PortalShortcutId::CallControl),
+            "next_stations_profile" => Ok(PortalShortcutId::NextStationsProfile),
+            "prev_stations_profile" => Ok(PortalShortcutId::PrevStationsProfile),
+            "toggle_do_not_disturb" => Ok(PortalShortcutId::ToggleDoNotDisturb),
+            "panic" => Ok(PortalShortcutId::Panic),
             _ => Err(format!("unknown portal shortcut id {s}")),
         }
     }
@@ -151,6 +177,10 @@ impl From<PortalShortcutId> for NewShortcut {
 impl From<PortalShortcutId> for Code {
     fn from(value: PortalShortcutId) -> Self {
         match value {
+            PortalShortcutId::Panic => Code::F28,
+            PortalShortcutId::ToggleDoNotDisturb => Code::F29,
+            PortalShortcutId::NextStationsProfile => Code::F30,
+            PortalShortcutId::PrevStationsProfile => Code::F31,
             PortalShortcutId::CallControl => Code::F32,
             PortalShortcutId::PushToTalk => Code::F33,
             PortalShortcutId::PushToMute => Code::F34,
@@ -163,6 +193,10 @@ impl TryFrom<Code> for PortalShortcutId {
     type Error = String;
     fn try_from(value: Code) -> Result<Self, Self::Error> {
         match value {
+            Code::F28 => Ok(PortalShortcutId::Panic),
+            Code::F29 => Ok(PortalShortcutId::ToggleDoNotDisturb),
+            Code::F30 => Ok(PortalShortcutId::NextStationsProfile),
+            Code::F31 => Ok(PortalShortcutId::PrevStationsProfile),
             Code::F32 => Ok(PortalShortcutId::CallControl),
             Code::F33 => Ok(PortalShortcutId::PushToTalk),
             Code::F34 => Ok(PortalShortcutId::PushToMute),
@@ -180,6 +214,10 @@ impl From<Keybind> for PortalShortcutId {
             Keybind::RadioIntegration => PortalShortcutId::RadioIntegration,
             Keybind::AcceptCall => PortalShortcutId::CallControl,
             Keybind::EndCall => PortalShortcutId::CallControl,
+            Keybind::NextProfile => PortalShortcutId::NextStationsProfile,
+            Keybind::PrevProfile => PortalShortcutId::PrevStationsProfile,
+            Keybind::ToggleDoNotDisturb => PortalShortcutId::ToggleDoNotDisturb,
+            Keybind::Panic => PortalShortcutId::Panic,
         }
     }
 }
@@ -44,7 +44,7 @@ use crate::keybinds::{KeyEvent, Keybind, KeybindsError};
 use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut, Shortcut};
 use ashpd::zbus::export::futures_core::Stream;
 use futures_util::StreamExt;
-use keyboard_types::KeyState;
+use keyboard_types::{Code, KeyState};
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -422,7 +422,7 @@ async fn run_shortcuts_listener(
                     log::trace!("Shortcut activated: {shortcut_id:?}");
 
                     let _ = key_event_tx.send(KeyEvent {
-                        code: shortcut_id.into(),
+                        code: Code::from(shortcut_id).into(),
                         label: shortcut_id.as_str().to_string(),
                         state: KeyState::Down
                     });
@@ -437,7 +437,7 @@ async fn run_shortcuts_listener(
                     log::trace!("Shortcut deactivated: {shortcut_id:?}");
 
                     let _ = key_event_tx.send(KeyEvent {
-                        code: shortcut_id.into(),
+                        code: Code::from(shortcut_id).into(),
                         label: shortcut_id.as_str().to_string(),
                         state: KeyState::Up
                     });
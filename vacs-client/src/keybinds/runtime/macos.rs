@@ -35,7 +35,7 @@ impl KeyEventConverter {
                     CGEvent::integer_value_field(Some(event), CGEventField::KeyboardEventKeycode);
                 let code = cg_keycode_to_code(keycode)?;
                 Ok(KeyEvent {
-                    code,
+                    code: code.into(),
                     label: code.to_string(),
                     state,
                 })
@@ -71,7 +71,7 @@ impl KeyEventConverter {
                 }
 
                 Ok(KeyEvent {
-                    code,
+                    code: code.into(),
                     label: "".to_string(),
                     state,
                 })
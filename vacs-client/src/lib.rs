@@ -10,6 +10,7 @@ mod radio;
 mod secrets;
 mod signaling;
 
+use crate::app::logging::{LogFilterHandle, OTHER_LOG_LEVEL, VACS_LOG_TARGETS};
 use crate::app::open_fatal_error_dialog;
 use crate::app::state::audio::AppStateAudioExt;
 use crate::app::state::http::HttpState;
@@ -17,30 +18,38 @@ use crate::app::state::keybinds::AppStateKeybindsExt;
 use crate::app::state::{AppState, AppStateInner};
 use crate::audio::manager::AudioManagerHandle;
 use crate::build::VersionInfo;
+use crate::config::watcher::spawn_config_watcher;
 use crate::config::{CLIENT_SETTINGS_FILE_NAME, Persistable, PersistedClientConfig};
-use crate::error::{StartupError, StartupErrorExt};
+use crate::error::{StartupDiagnostics, StartupError, StartupErrorExt, StartupStep};
 use crate::keybinds::engine::KeybindEngineHandle;
 use crate::platform::Capabilities;
+use crate::signaling::network::spawn_network_monitor;
 use tauri::{App, Manager, RunEvent, WindowEvent};
 use tauri_plugin_deep_link::DeepLinkExt;
 use tokio::sync::Mutex as TokioMutex;
 
 pub fn run() {
+    let log_filter = LogFilterHandle::default();
+
     tauri::Builder::default()
         .plugin(
-            tauri_plugin_log::Builder::new()
-                .max_file_size(1_000_000)
-                .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepSome(5))
-                .timezone_strategy(tauri_plugin_log::TimezoneStrategy::UseLocal)
-                .level(log::LevelFilter::Warn)
-                .level_for("vacs_client_lib", log::LevelFilter::Trace)
-                .level_for("vacs_audio", log::LevelFilter::Trace)
-                .level_for("vacs_signaling", log::LevelFilter::Trace)
-                .level_for("vacs_vatsim", log::LevelFilter::Trace)
-                .level_for("vacs_webrtc", log::LevelFilter::Trace)
-                .level_for("trackaudio", log::LevelFilter::Trace)
+            VACS_LOG_TARGETS
+                .into_iter()
+                .fold(
+                    tauri_plugin_log::Builder::new()
+                        .max_file_size(1_000_000)
+                        .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepSome(5))
+                        .timezone_strategy(tauri_plugin_log::TimezoneStrategy::UseLocal)
+                        .level(OTHER_LOG_LEVEL),
+                    |builder, target| builder.level_for(target, log::LevelFilter::Trace),
+                )
+                .filter({
+                    let log_filter = log_filter.clone();
+                    move |metadata| log_filter.enabled(metadata)
+                })
                 .build(),
         )
+        .manage(log_filter)
         .plugin(tauri_plugin_single_instance::init(|app, argv, _| {
             if let Some(url) = argv.get(1) {
                 app::handle_deep_link(app.clone(), url.to_string());
@@ -63,7 +72,7 @@ pub fn run() {
                 });
             }
 
-            async fn setup(app: &mut App) -> Result<(), StartupError> {
+            async fn setup(app: &mut App, step: &mut StartupStep) -> Result<(), StartupError> {
                 #[cfg(not(target_os = "macos"))]
                 {
                     use anyhow::Context;
@@ -76,16 +85,23 @@ pub fn run() {
 
                 let capabilities = Capabilities::default();
 
+                *step = StartupStep::AppState;
                 let state = AppStateInner::new(app.handle())?;
+                app.state::<LogFilterHandle>().set_level(state.config.client.log_level());
 
                 let transmit_config = state.config.client.transmit_config.clone();
                 let call_control_config = state.config.client.keybinds.clone();
                 let keybind_engine = state.keybind_engine_handle();
+                let extra_stations_config = state.config.client.extra_stations_config.clone();
+                let watcher_shutdown_token = state.child_shutdown_token();
+                let network_monitor_shutdown_token = state.child_shutdown_token();
 
+                *step = StartupStep::HttpState;
                 app.manage::<HttpState>(HttpState::new(app.handle())?);
                 app.manage::<AudioManagerHandle>(state.audio_manager_handle());
                 app.manage::<AppState>(TokioMutex::new(state));
 
+                *step = StartupStep::Keybinds;
                 if capabilities.keybind_listener || capabilities.keybind_emitter {
                     keybind_engine
                         .write()
@@ -99,13 +115,30 @@ pub fn run() {
 
                 app.manage::<KeybindEngineHandle>(keybind_engine);
 
+                match app.path().app_config_dir() {
+                    Ok(config_dir) => {
+                        if let Err(err) = spawn_config_watcher(
+                            app.handle().clone(),
+                            config_dir,
+                            extra_stations_config,
+                            watcher_shutdown_token,
+                        ) {
+                            log::warn!("Failed to start config watcher, config changes require a restart: {err:?}");
+                        }
+                    }
+                    Err(err) => log::warn!("Cannot get config directory, config watcher not started: {err}"),
+                }
+
+                spawn_network_monitor(app.handle().clone(), network_monitor_shutdown_token);
+
                 Ok(())
             }
 
-            if let Err(err) = tauri::async_runtime::block_on(setup(app)) {
-                log::error!("Startup failed. Err: {err:?}");
+            let mut step = StartupStep::DeepLink;
+            if let Err(err) = tauri::async_runtime::block_on(setup(app, &mut step)) {
+                log::error!("Startup failed during {step:?}. Err: {err:?}");
 
-                open_fatal_error_dialog(app.handle(), &err.to_string());
+                open_fatal_error_dialog(app.handle(), &StartupDiagnostics::failed(step, err));
 
                 return Err(anyhow::anyhow!("{err}").into());
             }
@@ -114,8 +147,15 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             app::commands::app_check_for_update,
+            app::commands::app_export_settings,
+            app::commands::app_export_diagnostics,
+            app::commands::app_set_log_level,
             app::commands::app_frontend_ready,
+            app::commands::app_get_diagnostics,
+            app::commands::app_import_settings,
             app::commands::app_open_folder,
+            app::commands::app_open_log_file,
+            app::commands::app_open_config_dir,
             app::commands::app_pick_extra_stations_config,
             app::commands::app_platform_capabilities,
             app::commands::app_quit,
@@ -123,10 +163,14 @@ pub fn run() {
             app::commands::app_set_always_on_top,
             app::commands::app_set_fullscreen,
             app::commands::app_update,
+            app::commands::app_validate_config,
+            audio::commands::audio_get_device_capabilities,
             audio::commands::audio_get_devices,
             audio::commands::audio_get_hosts,
+            audio::commands::audio_get_sound_packs,
             audio::commands::audio_get_volumes,
             audio::commands::audio_play_ui_click,
+            audio::commands::audio_restart_engine,
             audio::commands::audio_set_device,
             audio::commands::audio_set_host,
             audio::commands::audio_set_radio_prio,
@@ -136,11 +180,14 @@ pub fn run() {
             auth::commands::auth_check_session,
             auth::commands::auth_logout,
             auth::commands::auth_open_oauth_url,
+            keybinds::commands::keybinds_capture_gamepad_button,
+            keybinds::commands::keybinds_check_binding,
             keybinds::commands::keybinds_get_external_binding,
             keybinds::commands::keybinds_get_keybinds_config,
             keybinds::commands::keybinds_get_radio_config,
             keybinds::commands::keybinds_get_radio_state,
             keybinds::commands::keybinds_get_transmit_config,
+            keybinds::commands::keybinds_get_transmit_latched,
             keybinds::commands::keybinds_open_system_shortcuts_settings,
             keybinds::commands::keybinds_reconnect_radio,
             keybinds::commands::keybinds_set_binding,
@@ -151,9 +198,17 @@ pub fn run() {
             signaling::commands::signaling_connect,
             signaling::commands::signaling_disconnect,
             signaling::commands::signaling_end_call,
+            signaling::commands::signaling_get_call_history,
+            signaling::commands::signaling_get_do_not_disturb,
             signaling::commands::signaling_get_ignored_clients,
+            signaling::commands::signaling_get_max_incoming_calls,
+            signaling::commands::signaling_get_peer_volume,
             signaling::commands::signaling_get_stations_config,
             signaling::commands::signaling_remove_ignored_client,
+            signaling::commands::signaling_search_stations,
+            signaling::commands::signaling_set_do_not_disturb,
+            signaling::commands::signaling_set_peer_muted,
+            signaling::commands::signaling_set_peer_volume,
             signaling::commands::signaling_set_selected_stations_config_profile,
             signaling::commands::signaling_start_call,
             signaling::commands::signaling_terminate,
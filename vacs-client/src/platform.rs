@@ -1,3 +1,4 @@
+use crate::keybinds::runtime::KeybindBackend;
 use serde::Serialize;
 use std::fmt::Display;
 use std::sync::OnceLock;
@@ -9,13 +10,17 @@ use std::sync::OnceLock;
 /// - **Linux Wayland**: Listener support via XDG portal, but no emitter (security model)
 /// - **Linux X11**: Currently stub implementations (to be implemented)
 /// - **Linux Unknown**: No display server detected, stub implementations
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
 pub struct Capabilities {
     pub always_on_top: bool,
     pub keybind_listener: bool,
     pub keybind_emitter: bool,
+    pub keybind_listener_backend: KeybindBackend,
+    pub keybind_emitter_backend: KeybindBackend,
+    /// Whether the XDG Global Shortcuts portal is available, always `false` outside of Wayland.
+    pub global_shortcuts_portal: bool,
 
     pub platform: Platform,
 }
@@ -31,7 +36,7 @@ impl Capabilities {
         let platform = *Platform::get();
 
         #[cfg(target_os = "linux")]
-        let keybind_listener = if matches!(platform, Platform::LinuxWayland) {
+        let global_shortcuts_portal = if matches!(platform, Platform::LinuxWayland) {
             if let Ok(handle) = tokio::runtime::Handle::try_current() {
                 tokio::task::block_in_place(|| {
                     handle.block_on(check_wayland_global_shortcuts_portal())
@@ -44,12 +49,24 @@ impl Capabilities {
         };
 
         #[cfg(not(target_os = "linux"))]
-        let keybind_listener = matches!(platform, Platform::Windows | Platform::MacOs);
+        let global_shortcuts_portal = false;
+
+        Self::for_platform(platform, global_shortcuts_portal)
+    }
+
+    pub(crate) fn for_platform(platform: Platform, global_shortcuts_portal: bool) -> Self {
+        let global_shortcuts_portal =
+            global_shortcuts_portal && matches!(platform, Platform::LinuxWayland);
+        let keybind_listener_backend = KeybindBackend::listener(platform, global_shortcuts_portal);
+        let keybind_emitter_backend = KeybindBackend::emitter(platform);
 
         Self {
             always_on_top: !matches!(platform, Platform::LinuxWayland),
-            keybind_listener,
-            keybind_emitter: matches!(platform, Platform::Windows | Platform::MacOs),
+            keybind_listener: keybind_listener_backend.is_available(),
+            keybind_emitter: keybind_emitter_backend.is_available(),
+            keybind_listener_backend,
+            keybind_emitter_backend,
+            global_shortcuts_portal,
             platform,
         }
     }
@@ -203,7 +220,7 @@ impl Display for Platform {
 ///
 /// This enum represents the various desktop environments that can be detected
 /// on Linux systems. Detection is based on the `XDG_CURRENT_DESKTOP` environment
-/// variable.
+/// variable, falling back to `DESKTOP_SESSION` if it is not set.
 #[cfg(target_os = "linux")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[allow(dead_code)]
@@ -212,6 +229,8 @@ pub enum DesktopEnvironment {
     Kde,
     /// GNOME desktop environment
     Gnome,
+    /// Cinnamon desktop environment
+    Cinnamon,
     /// XFCE desktop environment
     Xfce,
     /// Hyprland Wayland compositor
@@ -225,155 +244,138 @@ pub enum DesktopEnvironment {
 #[allow(dead_code)]
 static DESKTOP_ENV_CACHE: OnceLock<DesktopEnvironment> = OnceLock::new();
 
+/// Program and arguments launched to open a settings page.
+#[cfg(target_os = "linux")]
+type SettingsCommand = (&'static str, &'static [&'static str]);
+
+/// Generic openers tried if no desktop environment specific settings page could be opened.
+#[cfg(target_os = "linux")]
+const GENERIC_SETTINGS_COMMANDS: &[SettingsCommand] = &[("xdg-open", &["settings://keyboard"])];
+
 #[cfg(target_os = "linux")]
 impl DesktopEnvironment {
     /// Get the current desktop environment, using a cached value if available.
     ///
     /// This method detects the desktop environment by examining the `XDG_CURRENT_DESKTOP`
-    /// environment variable. The result is cached for subsequent calls.
-    ///
-    /// # Platform Support
-    ///
-    /// This method only performs detection on Linux. On other platforms, it returns
-    /// `DesktopEnvironment::Unknown`.
+    /// and `DESKTOP_SESSION` environment variables. The result is cached for subsequent calls.
     #[allow(dead_code)]
     pub fn get() -> DesktopEnvironment {
         *DESKTOP_ENV_CACHE.get_or_init(Self::detect)
     }
 
-    /// Detect the current desktop environment.
-    ///
-    /// This method performs the actual detection logic by examining the
-    /// `XDG_CURRENT_DESKTOP` environment variable.
+    /// Detect the current desktop environment from the environment variables.
     #[allow(dead_code)]
     fn detect() -> DesktopEnvironment {
-        #[cfg(target_os = "linux")]
-        {
-            use std::env;
-            let desktop = env::var("XDG_CURRENT_DESKTOP")
-                .unwrap_or_default()
-                .to_lowercase();
-
-            if desktop.contains("kde") || desktop.contains("plasma") {
-                DesktopEnvironment::Kde
-            } else if desktop.contains("gnome") {
-                DesktopEnvironment::Gnome
-            } else if desktop.contains("xfce") {
-                DesktopEnvironment::Xfce
-            } else if desktop.contains("hyprland") {
-                DesktopEnvironment::Hyprland
-            } else {
-                DesktopEnvironment::Unknown
+        use std::env;
+
+        match env::var("XDG_CURRENT_DESKTOP") {
+            Ok(current_desktop) if !current_desktop.trim().is_empty() => {
+                Self::from_xdg_current_desktop(&current_desktop)
             }
+            _ => Self::from_xdg_current_desktop(&env::var("DESKTOP_SESSION").unwrap_or_default()),
         }
+    }
 
-        #[cfg(not(target_os = "linux"))]
-        {
-            DesktopEnvironment::Unknown
+    /// Parses a value of `XDG_CURRENT_DESKTOP`.
+    ///
+    /// The variable contains a colon-separated list of desktop names, ordered from most to least
+    /// specific (e.g. `ubuntu:GNOME`). The first known desktop environment in the list is used.
+    pub fn from_xdg_current_desktop(value: &str) -> DesktopEnvironment {
+        value
+            .split(':')
+            .map(|desktop| desktop.trim().to_lowercase())
+            .find_map(|desktop| match desktop.as_str() {
+                "kde" | "plasma" | "plasmawayland" => Some(DesktopEnvironment::Kde),
+                "gnome" | "gnome-classic" | "gnome-flashback" | "gnome-xorg" => {
+                    Some(DesktopEnvironment::Gnome)
+                }
+                "cinnamon" | "x-cinnamon" => Some(DesktopEnvironment::Cinnamon),
+                "xfce" | "xfce4" => Some(DesktopEnvironment::Xfce),
+                "hyprland" => Some(DesktopEnvironment::Hyprland),
+                _ => None,
+            })
+            .unwrap_or(DesktopEnvironment::Unknown)
+    }
+
+    /// Commands opening the keyboard shortcuts settings of this desktop environment, in the order
+    /// they are tried.
+    ///
+    /// - **KDE**: System Settings → Shortcuts (`systemsettings kcm_keys`, Plasma 5 fallback)
+    /// - **GNOME**: Settings → Keyboard, containing the keyboard shortcuts
+    /// - **Cinnamon**: System Settings → Keyboard
+    /// - **XFCE**: Keyboard Settings
+    /// - **Hyprland**: No settings GUI, opens the config file in the default editor
+    /// - **Unknown**: Only the generic openers are tried
+    fn shortcuts_settings_commands(&self) -> Vec<(String, Vec<String>)> {
+        let commands: &[SettingsCommand] = match self {
+            DesktopEnvironment::Kde => &[
+                ("systemsettings", &["kcm_keys"]),
+                ("systemsettings5", &["kcm_keys"]),
+                ("kcmshell6", &["kcm_keys"]),
+                ("kcmshell5", &["kcm_keys"]),
+            ],
+            DesktopEnvironment::Gnome => &[("gnome-control-center", &["keyboard"])],
+            DesktopEnvironment::Cinnamon => &[("cinnamon-settings", &["keyboard"])],
+            DesktopEnvironment::Xfce => &[("xfce4-keyboard-settings", &[])],
+            DesktopEnvironment::Hyprland => &[],
+            DesktopEnvironment::Unknown => &[],
+        };
+
+        let mut commands = commands
+            .iter()
+            .chain(GENERIC_SETTINGS_COMMANDS)
+            .map(|(program, args)| {
+                (
+                    program.to_string(),
+                    args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if *self == DesktopEnvironment::Hyprland {
+            // Follow XDG Base Directory specification
+            let config_path = if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+                format!("{xdg_config}/hypr/hyprland.conf")
+            } else if let Ok(home) = std::env::var("HOME") {
+                format!("{home}/.config/hypr/hyprland.conf")
+            } else {
+                "~/.config/hypr/hyprland.conf".to_string()
+            };
+            commands.insert(0, ("xdg-open".to_string(), vec![config_path]));
         }
+
+        commands
     }
 
     /// Open the keyboard shortcuts settings for this desktop environment.
     ///
-    /// This method attempts to open the appropriate system settings page for
-    /// configuring keyboard shortcuts. The exact behavior depends on the desktop
-    /// environment:
-    ///
-    /// - **KDE**: Opens System Settings → Shortcuts (`systemsettings5 kcm_keys`)
-    /// - **GNOME**: Opens Settings → Keyboard (`gnome-control-center keyboard`)
-    /// - **XFCE**: Opens Keyboard Settings (`xfce4-keyboard-settings`)
-    /// - **Hyprland**: Opens config file in default editor (`xdg-open ~/.config/hypr/hyprland.conf`)
-    /// - **Unknown**: Tries generic fallbacks (`xdg-open settings://keyboard`)
+    /// Tries the settings pages of the detected desktop environment first, falling back to
+    /// generic openers, see [`DesktopEnvironment::shortcuts_settings_commands`].
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - The platform is not Linux
-    /// - The desktop environment's settings application cannot be launched
+    /// Returns an error if none of the settings applications could be launched.
     #[allow(dead_code)]
     pub fn open_keyboard_shortcuts_settings(&self) -> Result<(), String> {
-        #[cfg(target_os = "linux")]
-        {
-            use std::process::Command;
-
-            log::debug!("Opening keyboard shortcuts settings for {:?}", self);
-
-            let result = match self {
-                DesktopEnvironment::Kde => {
-                    // KDE Plasma: Open System Settings to Shortcuts page
-                    log::debug!("Opening KDE System Settings shortcuts page");
-                    Command::new("systemsettings5")
-                        .arg("kcm_keys")
-                        .spawn()
-                        .or_else(|_| {
-                            // Fallback for KDE 6
-                            Command::new("systemsettings").arg("kcm_keys").spawn()
-                        })
-                }
-                DesktopEnvironment::Gnome => {
-                    // GNOME: Open Settings to Keyboard Shortcuts
-                    log::debug!("Opening GNOME Settings keyboard shortcuts");
-                    Command::new("gnome-control-center").arg("keyboard").spawn()
-                }
-                DesktopEnvironment::Xfce => {
-                    // XFCE: Open Keyboard Settings
-                    log::debug!("Opening XFCE keyboard settings");
-                    Command::new("xfce4-keyboard-settings").spawn()
-                }
-                DesktopEnvironment::Hyprland => {
-                    // Hyprland: Open config file in default editor
-                    // Hyprland doesn't have a traditional settings GUI, it's configured via text files
-                    log::debug!("Opening Hyprland config file");
-
-                    // Follow XDG Base Directory specification
-                    let config_path = if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
-                        format!("{}/hypr/hyprland.conf", xdg_config)
-                    } else if let Ok(home) = std::env::var("HOME") {
-                        format!("{}/.config/hypr/hyprland.conf", home)
-                    } else {
-                        "~/.config/hypr/hyprland.conf".to_string()
-                    };
-
-                    Command::new("xdg-open").arg(&config_path).spawn()
-                }
-                DesktopEnvironment::Unknown => {
-                    // Unknown DE: Try generic approaches
-                    log::debug!("Unknown DE, trying generic keyboard settings");
-
-                    // Try xdg-open with settings:// URI (some DEs support this)
-                    Command::new("xdg-open")
-                        .arg("settings://keyboard")
-                        .spawn()
-                        .or_else(|_| {
-                            // Fallback: just open system settings
-                            Command::new("xdg-settings")
-                                .arg("get")
-                                .arg("default-url-scheme-handler")
-                                .arg("settings")
-                                .spawn()
-                        })
-                }
-            };
+        use std::process::Command;
+
+        log::debug!("Opening keyboard shortcuts settings for {self:?}");
 
-            match result {
+        for (program, args) in self.shortcuts_settings_commands() {
+            match Command::new(&program).args(&args).spawn() {
                 Ok(_) => {
-                    log::info!("Successfully opened keyboard shortcuts settings");
-                    Ok(())
-                }
-                Err(err) => {
-                    log::warn!("Failed to open keyboard shortcuts settings: {}", err);
-                    Err(format!(
-                        "Failed to open system settings. Please open your desktop environment's keyboard shortcuts settings manually. Error: {}",
-                        err
-                    ))
+                    log::info!("Opened keyboard shortcuts settings using {program} {args:?}");
+                    return Ok(());
                 }
+                Err(err) => log::debug!("Failed to launch {program} {args:?}: {err}"),
             }
         }
 
-        #[cfg(not(target_os = "linux"))]
-        {
-            Err("Opening keyboard shortcuts settings is only supported on Linux".to_string())
-        }
+        log::warn!("No settings application found to open keyboard shortcuts settings");
+        Err(format!(
+            "No settings application found for your desktop environment ({}). Please open your desktop environment's keyboard shortcuts settings manually.",
+            self.as_str()
+        ))
     }
 
     #[allow(dead_code)]
@@ -381,9 +383,115 @@ impl DesktopEnvironment {
         match self {
             DesktopEnvironment::Kde => "KDE",
             DesktopEnvironment::Gnome => "GNOME",
+            DesktopEnvironment::Cinnamon => "Cinnamon",
             DesktopEnvironment::Xfce => "XFCE",
             DesktopEnvironment::Hyprland => "Hyprland",
             DesktopEnvironment::Unknown => "Unknown",
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keybind_backends_per_platform() {
+        use KeybindBackend::*;
+
+        for (platform, portal, listener, emitter) in [
+            (Platform::Windows, false, Win32, Win32),
+            (Platform::MacOs, false, CoreGraphics, CoreGraphics),
+            (Platform::LinuxWayland, true, WaylandPortal, Noop),
+            (Platform::LinuxWayland, false, Noop, Noop),
+            (Platform::LinuxX11, false, Noop, Noop),
+            (Platform::LinuxUnknown, false, Noop, Noop),
+            (Platform::Unknown, false, Noop, Noop),
+        ] {
+            let capabilities = Capabilities::for_platform(platform, portal);
+            assert_eq!(
+                capabilities.keybind_listener_backend, listener,
+                "{platform}"
+            );
+            assert_eq!(capabilities.keybind_emitter_backend, emitter, "{platform}");
+            assert_eq!(
+                capabilities.keybind_listener,
+                listener != Noop,
+                "{platform}"
+            );
+            assert_eq!(capabilities.keybind_emitter, emitter != Noop, "{platform}");
+            assert_eq!(capabilities.global_shortcuts_portal, portal, "{platform}");
+        }
+    }
+
+    #[test]
+    fn portal_only_reported_on_wayland() {
+        for platform in [
+            Platform::Windows,
+            Platform::MacOs,
+            Platform::LinuxX11,
+            Platform::LinuxUnknown,
+            Platform::Unknown,
+        ] {
+            let capabilities = Capabilities::for_platform(platform, true);
+            assert!(!capabilities.global_shortcuts_portal, "{platform}");
+            assert_ne!(
+                capabilities.keybind_listener_backend,
+                KeybindBackend::WaylandPortal,
+                "{platform}"
+            );
+        }
+        assert!(!Capabilities::for_platform(Platform::LinuxWayland, true).always_on_top);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn detects_desktop_environment() {
+        for (value, expected) in [
+            ("KDE", DesktopEnvironment::Kde),
+            ("plasma", DesktopEnvironment::Kde),
+            ("GNOME", DesktopEnvironment::Gnome),
+            ("ubuntu:GNOME", DesktopEnvironment::Gnome),
+            ("pop:GNOME", DesktopEnvironment::Gnome),
+            ("GNOME-Classic:GNOME", DesktopEnvironment::Gnome),
+            ("X-Cinnamon", DesktopEnvironment::Cinnamon),
+            ("XFCE", DesktopEnvironment::Xfce),
+            ("Hyprland", DesktopEnvironment::Hyprland),
+            ("Budgie:GNOME", DesktopEnvironment::Gnome),
+            ("sway", DesktopEnvironment::Unknown),
+            ("", DesktopEnvironment::Unknown),
+            // Desktop names are matched exactly, not as substrings
+            ("gnomeish", DesktopEnvironment::Unknown),
+        ] {
+            assert_eq!(
+                DesktopEnvironment::from_xdg_current_desktop(value),
+                expected,
+                "{value:?}"
+            );
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn shortcuts_settings_fall_back_to_generic_opener() {
+        let generic = (
+            "xdg-open".to_string(),
+            vec!["settings://keyboard".to_string()],
+        );
+
+        let gnome = DesktopEnvironment::Gnome.shortcuts_settings_commands();
+        assert_eq!(gnome[0].0, "gnome-control-center");
+        assert_eq!(gnome.last(), Some(&generic));
+
+        let kde = DesktopEnvironment::Kde.shortcuts_settings_commands();
+        assert!(
+            kde.iter()
+                .all(|(_, args)| args == &["kcm_keys"] || args == &generic.1)
+        );
+
+        assert_eq!(
+            DesktopEnvironment::Unknown.shortcuts_settings_commands(),
+            vec![generic]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 pub mod push_to_talk;
 pub mod track_audio;
+pub mod watchdog;
 
 use crate::platform::Capabilities;
 use keyboard_types::KeyState;
@@ -15,6 +16,8 @@ pub enum RadioError {
     Integration(String),
     #[error("Radio transmit error: {0}")]
     Transmit(String),
+    #[error("Radio integration unavailable: {0}")]
+    Unavailable(String),
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -23,6 +26,25 @@ pub enum RadioIntegration {
     TrackAudio,
 }
 
+impl RadioIntegration {
+    /// Checks whether the integration can work with the given platform capabilities.
+    ///
+    /// AudioForVatsim relies on emitting key presses to the radio client, which is not possible
+    /// without a keybind emitter (e.g. on Linux). Creating it anyway would result in a PTT that
+    /// appears to work, but never transmits on the radio.
+    pub fn check_supported(self, capabilities: &Capabilities) -> Result<(), RadioError> {
+        match self {
+            RadioIntegration::AudioForVatsim if !capabilities.keybind_emitter => {
+                Err(RadioError::Unavailable(format!(
+                    "AudioForVatsim requires emitting key presses, which is not supported on {}. Please use the TrackAudio integration instead.",
+                    capabilities.platform
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 impl Default for RadioIntegration {
     fn default() -> Self {
         if Capabilities::default().keybind_emitter {
@@ -106,3 +128,42 @@ pub trait Radio: Send + Sync + Debug + 'static {
 }
 
 pub type DynRadio = Arc<dyn Radio>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::Platform;
+
+    #[test]
+    fn audio_for_vatsim_requires_keybind_emitter() {
+        for platform in [
+            Platform::LinuxWayland,
+            Platform::LinuxX11,
+            Platform::LinuxUnknown,
+        ] {
+            let capabilities = Capabilities::for_platform(platform, true);
+            assert!(!capabilities.keybind_emitter);
+
+            let err = RadioIntegration::AudioForVatsim
+                .check_supported(&capabilities)
+                .unwrap_err();
+            assert!(matches!(err, RadioError::Unavailable(_)), "{platform}");
+            assert!(err.to_string().contains("TrackAudio"), "{platform}");
+            assert!(
+                RadioIntegration::TrackAudio
+                    .check_supported(&capabilities)
+                    .is_ok()
+            );
+        }
+
+        for platform in [Platform::Windows, Platform::MacOs] {
+            let capabilities = Capabilities::for_platform(platform, false);
+            assert!(
+                RadioIntegration::AudioForVatsim
+                    .check_supported(&capabilities)
+                    .is_ok(),
+                "{platform}"
+            );
+        }
+    }
+}
@@ -9,6 +9,10 @@
 //! key press to the configured external radio client. This allows using a single PTT key
 //! for both vacs and the radar client's radio.
 //!
+//! The emitted keys are configured as a [`KeySequence`], allowing to emit combos such as
+//! `ControlLeft+F12`: all keys are pressed in order when transmitting starts and released in
+//! reverse order when it stops.
+//!
 //! # Platform Limitations
 //!
 //! **This feature does NOT work on Linux/Wayland** because:
@@ -25,9 +29,10 @@
 //! - Configure vacs and their radio client separately with different PTT keys
 //! - Use "Push-to-Mute" transmit mode instead of "Radio Integration"
 
+use crate::keybinds::combo::KeySequence;
 use crate::keybinds::runtime::{DynKeybindEmitter, KeybindEmitter, PlatformEmitter};
 use crate::radio::{Radio, RadioError, RadioState, TransmissionState};
-use keyboard_types::{Code, KeyState};
+use keyboard_types::KeyState;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::{AppHandle, Emitter};
@@ -41,18 +46,18 @@ use tauri::{AppHandle, Emitter};
 #[derive(Clone)]
 pub struct PushToTalkRadio {
     app: AppHandle,
-    code: Code,
+    keys: KeySequence,
     emitter: DynKeybindEmitter,
     active: Arc<AtomicBool>,
 }
 
 impl PushToTalkRadio {
-    pub fn new(app: AppHandle, code: Code) -> Result<Self, RadioError> {
-        log::trace!("PushToTalkRadio starting: code {:?}", code);
+    pub fn new(app: AppHandle, keys: KeySequence) -> Result<Self, RadioError> {
+        log::trace!("PushToTalkRadio starting: keys {keys}");
 
         let radio = Self {
             app,
-            code,
+            keys,
             emitter: Arc::new(
                 PlatformEmitter::start().map_err(|err| RadioError::Integration(err.to_string()))?,
             ),
@@ -79,12 +84,12 @@ impl Radio for PushToTalkRadio {
         };
 
         log::trace!(
-            "Setting transmission {state:?}, emitting {:?} {key_state:?}",
-            self.code,
+            "Setting transmission {state:?}, emitting {} {key_state:?}",
+            self.keys,
         );
 
         self.emitter
-            .emit(self.code, key_state)
+            .emit_all(self.keys.codes(), key_state)
             .map_err(|err| RadioError::Transmit(err.to_string()))?;
 
         self.app.emit("radio:state", radio_state).ok();
@@ -104,7 +109,7 @@ impl Radio for PushToTalkRadio {
 impl std::fmt::Debug for PushToTalkRadio {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PushToTalkRadio")
-            .field("code", &self.code)
+            .field("keys", &self.keys)
             .field("active", &self.active)
             .finish()
     }
@@ -112,10 +117,10 @@ impl std::fmt::Debug for PushToTalkRadio {
 
 impl Drop for PushToTalkRadio {
     fn drop(&mut self) {
-        log::trace!("Dropping PushToTalkRadio: code {:?}", self.code);
+        log::trace!("Dropping PushToTalkRadio: keys {}", self.keys);
 
         if self.active.load(Ordering::Relaxed)
-            && let Err(err) = self.emitter.emit(self.code, KeyState::Up)
+            && let Err(err) = self.emitter.emit_all(self.keys.codes(), KeyState::Up)
         {
             log::warn!("Failed to release PTT key while dropping: {err}");
         }
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 use tokio_util::sync::CancellationToken;
 use trackaudio::messages::events::StationState;
@@ -26,10 +26,16 @@ impl TrackAudioRadio {
     const TRANSMIT_TIMEOUT: Duration = Duration::from_millis(250);
     const VOICE_CONNECTED_STATE_TIMEOUT: Duration = Duration::from_millis(250);
     const STATION_STATES_TIMEOUT: Duration = Duration::from_millis(250);
+    const HEARTBEAT_TIMEOUT: Duration = Duration::from_millis(500);
 
+    /// Connects to TrackAudio at the given endpoint (or the default one).
+    ///
+    /// If `heartbeat_interval` is non-zero, the connection is probed periodically to detect
+    /// TrackAudio silently going away, see [`HeartbeatMonitor`].
     pub async fn new(
         app: AppHandle,
         endpoint: Option<impl AsRef<str>>,
+        heartbeat_interval: Duration,
     ) -> Result<Self, RadioError> {
         app.emit("radio:state", RadioState::Disconnected).ok();
 
@@ -64,6 +70,17 @@ impl TrackAudioRadio {
             });
         }
 
+        if !heartbeat_interval.is_zero() {
+            let app = app.clone();
+            let client = client.clone();
+            let token = cancellation_token.clone();
+            let state = state.clone();
+
+            tauri::async_runtime::spawn(async move {
+                Self::heartbeat_task(app, client, token, state, heartbeat_interval).await;
+            });
+        }
+
         let radio = Self {
             app,
             client,
@@ -108,6 +125,90 @@ impl TrackAudioRadio {
         log::debug!("TrackAudio events task ended");
     }
 
+    async fn heartbeat_task(
+        app: AppHandle,
+        client: TrackAudioClient,
+        cancellation_token: CancellationToken,
+        state: Arc<TrackAudioState>,
+        interval: Duration,
+    ) {
+        log::debug!("Starting TrackAudio heartbeat task (interval: {interval:?})");
+
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut monitor = HeartbeatMonitor::default();
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = cancellation_token.cancelled() => {
+                    log::debug!("TrackAudio heartbeat task cancelled");
+                    break;
+                }
+                _ = ticker.tick() => {}
+            }
+
+            match Self::heartbeat(&client, &state, &mut monitor).await {
+                HeartbeatAction::None => {}
+                HeartbeatAction::VoiceConnected(voice_connected) => {
+                    log::debug!(
+                        "TrackAudio heartbeat corrected voice connected state to {voice_connected}"
+                    );
+                    state.set_voice_connected(voice_connected, &app);
+                }
+                HeartbeatAction::ConnectionLost => {
+                    log::warn!("TrackAudio stopped responding, reconnecting");
+                    state.clear();
+                    state.emit(&app);
+                    Self::heartbeat_reconnect(&client);
+                }
+                HeartbeatAction::Reconnect => {
+                    log::debug!("TrackAudio still not connected, retrying reconnect");
+                    Self::heartbeat_reconnect(&client);
+                }
+            }
+        }
+
+        log::debug!("TrackAudio heartbeat task ended");
+    }
+
+    /// Probes the TrackAudio connection once, returning the action required to recover from a
+    /// lost connection or a diverged voice connected state.
+    async fn heartbeat(
+        client: &TrackAudioClient,
+        state: &TrackAudioState,
+        monitor: &mut HeartbeatMonitor,
+    ) -> HeartbeatAction {
+        if !state.connected.load(Ordering::Relaxed) {
+            return monitor.on_disconnected(Instant::now());
+        }
+
+        match client
+            .api()
+            .get_voice_connected_state(Some(Self::HEARTBEAT_TIMEOUT))
+            .await
+        {
+            Ok(voice_connected) => {
+                monitor.on_success();
+                if state.voice_connected.load(Ordering::Relaxed) != voice_connected {
+                    HeartbeatAction::VoiceConnected(voice_connected)
+                } else {
+                    HeartbeatAction::None
+                }
+            }
+            Err(err) => {
+                log::debug!("TrackAudio heartbeat failed: {err}");
+                monitor.on_failure(Instant::now())
+            }
+        }
+    }
+
+    fn heartbeat_reconnect(client: &TrackAudioClient) {
+        if let Err(err) = client.reconnect() {
+            log::warn!("Failed to reconnect to TrackAudio after missed heartbeats: {err}");
+        }
+    }
+
     async fn handle_event(
         event: trackaudio::Event,
         state: &TrackAudioState,
@@ -433,3 +534,286 @@ impl TrackAudioState {
         self.emit(app);
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeartbeatAction {
+    None,
+    /// TrackAudio reported a voice connected state differing from the tracked one.
+    VoiceConnected(bool),
+    /// The connection was considered lost, the state should be reset and a reconnect attempted.
+    ConnectionLost,
+    /// A previously lost connection has not been re-established yet, reconnect again.
+    Reconnect,
+}
+
+/// Tracks missed heartbeats of the TrackAudio connection.
+///
+/// The connection is considered lost after [`HeartbeatMonitor::MAX_MISSED`] consecutive failed
+/// heartbeats. Until it is re-established, reconnects are retried with exponential backoff. Drops
+/// reported by the TrackAudio client itself are left to its own reconnect handling.
+#[derive(Debug, Default)]
+struct HeartbeatMonitor {
+    missed: u32,
+    reconnect_attempts: u32,
+    next_reconnect: Option<Instant>,
+}
+
+impl HeartbeatMonitor {
+    const MAX_MISSED: u32 = 2;
+    const RECONNECT_BASE: Duration = Duration::from_secs(1);
+    const RECONNECT_CAP: Duration = Duration::from_secs(30);
+
+    fn on_success(&mut self) {
+        *self = Self::default();
+    }
+
+    fn on_failure(&mut self, now: Instant) -> HeartbeatAction {
+        self.missed += 1;
+        if self.missed < Self::MAX_MISSED {
+            return HeartbeatAction::None;
+        }
+
+        self.missed = 0;
+        self.schedule_reconnect(now);
+        HeartbeatAction::ConnectionLost
+    }
+
+    fn on_disconnected(&mut self, now: Instant) -> HeartbeatAction {
+        match self.next_reconnect {
+            Some(at) if now >= at => {
+                self.schedule_reconnect(now);
+                HeartbeatAction::Reconnect
+            }
+            _ => HeartbeatAction::None,
+        }
+    }
+
+    fn schedule_reconnect(&mut self, now: Instant) {
+        let delay = Self::RECONNECT_BASE
+            .saturating_mul(1u32 << self.reconnect_attempts.min(16))
+            .min(Self::RECONNECT_CAP);
+        self.reconnect_attempts += 1;
+        self.next_reconnect = Some(now + delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use std::net::SocketAddr;
+    use std::sync::atomic::AtomicUsize;
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// Minimal TrackAudio websocket endpoint answering voice connected state requests.
+    ///
+    /// While not responding, connections are still accepted but requests are silently dropped,
+    /// like a TrackAudio instance that stopped working without closing its connections.
+    struct MockTrackAudio {
+        addr: SocketAddr,
+        responding: Arc<AtomicBool>,
+        connections: Arc<AtomicUsize>,
+        handle: JoinHandle<()>,
+    }
+
+    impl MockTrackAudio {
+        async fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let responding = Arc::new(AtomicBool::new(true));
+            let connections = Arc::new(AtomicUsize::new(0));
+
+            let handle = {
+                let responding = responding.clone();
+                let connections = connections.clone();
+                tokio::spawn(async move {
+                    while let Ok((stream, _)) = listener.accept().await {
+                        let responding = responding.clone();
+                        connections.fetch_add(1, Ordering::Relaxed);
+                        tokio::spawn(async move {
+                            let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+                                return;
+                            };
+                            while let Some(Ok(message)) = ws.next().await {
+                                let Message::Text(text) = message else {
+                                    continue;
+                                };
+                                let request: serde_json::Value =
+                                    serde_json::from_str(&text).unwrap_or_default();
+                                if request["type"] == "kGetVoiceConnectedState"
+                                    && responding.load(Ordering::Relaxed)
+                                {
+                                    let response = serde_json::json!({
+                                        "type": "kVoiceConnectedState",
+                                        "value": { "connected": true },
+                                    });
+                                    if ws.send(Message::text(response.to_string())).await.is_err() {
+                                        break;
+                                    }
+                                }
+                            }
+                        });
+                    }
+                })
+            };
+
+            Self {
+                addr,
+                responding,
+                connections,
+                handle,
+            }
+        }
+
+        async fn connect(&self) -> TrackAudioClient {
+            let config = TrackAudioConfig::new(self.addr.to_string()).unwrap();
+            TrackAudioClient::connect(config).await.unwrap()
+        }
+
+        fn set_responding(&self, responding: bool) {
+            self.responding.store(responding, Ordering::Relaxed);
+        }
+
+        fn connections(&self) -> usize {
+            self.connections.load(Ordering::Relaxed)
+        }
+    }
+
+    impl Drop for MockTrackAudio {
+        fn drop(&mut self) {
+            self.handle.abort();
+        }
+    }
+
+    async fn wait_for_connected(events: &mut tokio::sync::broadcast::Receiver<trackaudio::Event>) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Ok(trackaudio::Event::Client(ClientEvent::ConnectionStateChanged(
+                    ConnectionState::Connected,
+                ))) = events.recv().await
+                {
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("TrackAudio client did not reconnect");
+    }
+
+    #[tokio::test]
+    async fn heartbeat_reconnects_to_endpoint_going_away_and_coming_back() {
+        let track_audio = MockTrackAudio::start().await;
+        let client = track_audio.connect().await;
+        let state = TrackAudioState::default();
+        let mut monitor = HeartbeatMonitor::default();
+
+        // Connected, the events task would have set this on the connection state change
+        state.connected.store(true, Ordering::Relaxed);
+        assert_eq!(
+            TrackAudioRadio::heartbeat(&client, &state, &mut monitor).await,
+            HeartbeatAction::VoiceConnected(true)
+        );
+        state.voice_connected.store(true, Ordering::Relaxed);
+        assert_eq!(
+            TrackAudioRadio::heartbeat(&client, &state, &mut monitor).await,
+            HeartbeatAction::None
+        );
+        assert_eq!(track_audio.connections(), 1);
+
+        // Endpoint goes away without closing the connection
+        track_audio.set_responding(false);
+        assert_eq!(
+            TrackAudioRadio::heartbeat(&client, &state, &mut monitor).await,
+            HeartbeatAction::None
+        );
+        assert_eq!(
+            TrackAudioRadio::heartbeat(&client, &state, &mut monitor).await,
+            HeartbeatAction::ConnectionLost
+        );
+
+        let mut events = client.subscribe();
+        state.clear();
+        TrackAudioRadio::heartbeat_reconnect(&client);
+        wait_for_connected(&mut events).await;
+        assert_eq!(track_audio.connections(), 2);
+
+        // Endpoint comes back
+        track_audio.set_responding(true);
+        state.connected.store(true, Ordering::Relaxed);
+        assert_eq!(
+            TrackAudioRadio::heartbeat(&client, &state, &mut monitor).await,
+            HeartbeatAction::VoiceConnected(true)
+        );
+        state.voice_connected.store(true, Ordering::Relaxed);
+        assert_eq!(
+            TrackAudioRadio::heartbeat(&client, &state, &mut monitor).await,
+            HeartbeatAction::None
+        );
+        assert_eq!(monitor.next_reconnect, None);
+        assert_eq!(track_audio.connections(), 2);
+    }
+
+    #[test]
+    fn healthy_connection_never_reconnects() {
+        let mut monitor = HeartbeatMonitor::default();
+        let now = Instant::now();
+
+        assert_eq!(monitor.on_failure(now), HeartbeatAction::None);
+        monitor.on_success();
+        assert_eq!(monitor.on_failure(now), HeartbeatAction::None);
+        monitor.on_success();
+
+        // Disconnects not detected by the heartbeat are handled by the TrackAudio client
+        assert_eq!(
+            monitor.on_disconnected(now + Duration::from_secs(60)),
+            HeartbeatAction::None
+        );
+    }
+
+    #[test]
+    fn endpoint_going_away_and_coming_back() {
+        let mut monitor = HeartbeatMonitor::default();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        // Endpoint goes away
+        assert_eq!(monitor.on_failure(at(0)), HeartbeatAction::None);
+        assert_eq!(monitor.on_failure(at(5)), HeartbeatAction::ConnectionLost);
+
+        // Reconnects are retried with exponential backoff while the endpoint is gone
+        assert_eq!(monitor.on_disconnected(at(5)), HeartbeatAction::None);
+        assert_eq!(monitor.on_disconnected(at(6)), HeartbeatAction::Reconnect);
+        assert_eq!(monitor.on_disconnected(at(7)), HeartbeatAction::None);
+        assert_eq!(monitor.on_disconnected(at(8)), HeartbeatAction::Reconnect);
+        assert_eq!(monitor.on_disconnected(at(11)), HeartbeatAction::None);
+        assert_eq!(monitor.on_disconnected(at(12)), HeartbeatAction::Reconnect);
+
+        // Endpoint comes back
+        monitor.on_success();
+        assert_eq!(monitor.on_disconnected(at(100)), HeartbeatAction::None);
+
+        // A later drop starts over with the initial backoff
+        assert_eq!(monitor.on_failure(at(200)), HeartbeatAction::None);
+        assert_eq!(monitor.on_failure(at(205)), HeartbeatAction::ConnectionLost);
+        assert_eq!(monitor.on_disconnected(at(206)), HeartbeatAction::Reconnect);
+    }
+
+    #[test]
+    fn reconnect_backoff_is_capped() {
+        let mut monitor = HeartbeatMonitor::default();
+        let mut now = Instant::now();
+        monitor.on_failure(now);
+        monitor.on_failure(now);
+
+        for _ in 0..20 {
+            now = monitor.next_reconnect.unwrap();
+            assert_eq!(monitor.on_disconnected(now), HeartbeatAction::Reconnect);
+        }
+        assert_eq!(
+            monitor.next_reconnect.unwrap() - now,
+            HeartbeatMonitor::RECONNECT_CAP
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::radio::{DynRadio, TransmissionState};
+use parking_lot::Mutex;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Forces a radio transmission to end after a maximum duration.
+///
+/// If the key-up event ending a transmission is lost (e.g. dropped by the compositor on
+/// Wayland), the radio would stay keyed indefinitely, jamming the frequency. The watchdog is armed
+/// whenever a transmission starts and disarmed once it legitimately ends. If it is not disarmed in
+/// time, it sets the radio transmission inactive itself.
+#[derive(Debug)]
+pub struct TransmitWatchdog {
+    max_duration: Option<Duration>,
+    token: Mutex<Option<CancellationToken>>,
+}
+
+impl TransmitWatchdog {
+    /// Creates a new watchdog, a `max_duration` of zero disables it.
+    pub fn new(max_duration: Duration) -> Self {
+        Self {
+            max_duration: (!max_duration.is_zero()).then_some(max_duration),
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Arms the watchdog for a transmission on `radio` that just started, replacing a previously
+    /// armed timer.
+    ///
+    /// `on_timeout` is called after the transmission was forced to end.
+    pub fn arm(&self, radio: DynRadio, on_timeout: impl FnOnce(Duration) + Send + 'static) {
+        self.disarm();
+        let Some(max_duration) = self.max_duration else {
+            return;
+        };
+
+        let token = CancellationToken::new();
+        *self.token.lock() = Some(token.clone());
+
+        tauri::async_runtime::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = tokio::time::sleep(max_duration) => {
+                    log::warn!(
+                        "Radio transmission active for longer than {max_duration:?}, forcing transmission inactive"
+                    );
+                    if let Err(err) = radio.transmit(TransmissionState::Inactive).await {
+                        log::warn!("Failed to force radio transmission inactive: {err}");
+                    }
+                    on_timeout(max_duration);
+                }
+            }
+        });
+    }
+
+    /// Disarms the watchdog after the transmission legitimately ended.
+    pub fn disarm(&self) {
+        if let Some(token) = self.token.lock().take() {
+            token.cancel();
+        }
+    }
+}
+
+impl Drop for TransmitWatchdog {
+    fn drop(&mut self) {
+        self.disarm();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::radio::{Radio, RadioError, RadioState};
+    use std::sync::Arc;
+    use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+
+    /// Radio reporting all transmission changes.
+    #[derive(Debug)]
+    struct RecordingRadio(UnboundedSender<TransmissionState>);
+
+    #[async_trait::async_trait]
+    impl Radio for RecordingRadio {
+        async fn transmit(&self, state: TransmissionState) -> Result<(), RadioError> {
+            self.0.send(state).ok();
+            Ok(())
+        }
+
+        fn state(&self) -> RadioState {
+            RadioState::RxIdle
+        }
+    }
+
+    const MAX_DURATION: Duration = Duration::from_millis(100);
+
+    #[tokio::test]
+    async fn fires_on_missing_key_up() {
+        let (tx, mut rx) = unbounded_channel();
+        let radio: DynRadio = Arc::new(RecordingRadio(tx));
+        let watchdog = TransmitWatchdog::new(MAX_DURATION);
+
+        // Key down sets the radio active, the matching key up never arrives
+        radio.transmit(TransmissionState::Active).await.unwrap();
+        let (fired_tx, fired_rx) = tokio::sync::oneshot::channel();
+        watchdog.arm(radio, move |duration| {
+            fired_tx.send(duration).ok();
+        });
+
+        assert_eq!(rx.recv().await, Some(TransmissionState::Active));
+        let fired = tokio::time::timeout(MAX_DURATION * 10, fired_rx)
+            .await
+            .expect("watchdog did not fire")
+            .unwrap();
+        assert_eq!(fired, MAX_DURATION);
+        assert_eq!(rx.recv().await, Some(TransmissionState::Inactive));
+    }
+
+    #[tokio::test]
+    async fn does_not_fire_after_key_up() {
+        let (tx, mut rx) = unbounded_channel();
+        let radio: DynRadio = Arc::new(RecordingRadio(tx));
+        let watchdog = TransmitWatchdog::new(MAX_DURATION);
+
+        watchdog.arm(radio.clone(), |_| {});
+        tokio::time::sleep(MAX_DURATION / 4).await;
+        // Re-arming on a new transmission restarts the timer
+        watchdog.arm(radio.clone(), |_| {});
+        tokio::time::sleep(MAX_DURATION / 4).await;
+        radio.transmit(TransmissionState::Inactive).await.unwrap();
+        watchdog.disarm();
+
+        tokio::time::sleep(MAX_DURATION * 2).await;
+        assert_eq!(rx.recv().await, Some(TransmissionState::Inactive));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn zero_duration_disables_watchdog() {
+        let (tx, mut rx) = unbounded_channel();
+        let radio: DynRadio = Arc::new(RecordingRadio(tx));
+        let watchdog = TransmitWatchdog::new(Duration::ZERO);
+
+        watchdog.arm(radio, |_| {});
+        tokio::time::sleep(MAX_DURATION).await;
+        assert!(rx.try_recv().is_err());
+    }
+}
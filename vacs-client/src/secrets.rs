@@ -5,11 +5,14 @@ use base64::prelude::*;
 use keyring::Entry;
 use keyring::error::Error::NoEntry;
 
+#[derive(Debug, Clone, Copy)]
 pub enum SecretKey {
     CookieStoreEncryptionKey,
 }
 
 impl SecretKey {
+    pub const ALL: [SecretKey; 1] = [SecretKey::CookieStoreEncryptionKey];
+
     pub fn as_str(&self) -> &str {
         match self {
             SecretKey::CookieStoreEncryptionKey => "cookie-store-encryption-key",
@@ -17,7 +20,6 @@ impl SecretKey {
     }
 }
 
-#[allow(dead_code)]
 pub fn get(key: SecretKey) -> anyhow::Result<Option<String>> {
     match entry_for_key(key)?.get_password() {
         Ok(password) => Ok(Some(password)),
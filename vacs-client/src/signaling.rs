@@ -1,2 +1,6 @@
 pub(crate) mod auth;
 pub(crate) mod commands;
+pub(crate) mod frequency;
+pub(crate) mod history;
+pub(crate) mod network;
+pub(crate) mod stations;
@@ -1,35 +1,60 @@
 use crate::app::state::http::HttpState;
 use crate::config::BackendEndpoint;
 use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
-use vacs_signaling::auth::TokenProvider;
+use vacs_signaling::auth::{ExpiringToken, TokenCache, TokenProvider};
 use vacs_signaling::error::SignalingError;
 use vacs_signaling::protocol::http::ws::WebSocketToken;
 
+/// Time before expiry at which a cached WebSocket auth token is refreshed, leaving enough room to
+/// complete the login with it.
+const TOKEN_REFRESH_LEEWAY: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone)]
 pub struct TauriTokenProvider {
     handle: AppHandle,
+    cache: Arc<TokenCache>,
 }
 
 impl TauriTokenProvider {
     pub fn new(handle: AppHandle) -> Self {
-        Self { handle }
+        Self {
+            handle,
+            cache: Arc::new(TokenCache::new(TOKEN_REFRESH_LEEWAY)),
+        }
     }
-}
 
-#[async_trait]
-impl TokenProvider for TauriTokenProvider {
-    async fn get_token(&self) -> Result<String, SignalingError> {
+    async fn fetch_token(&self) -> Result<ExpiringToken, SignalingError> {
         log::debug!("Retrieving WebSocket auth token");
         let http_state = self.handle.state::<HttpState>();
 
         let token = http_state
             .http_get::<WebSocketToken>(BackendEndpoint::WsToken, None)
             .await
-            .map_err(|err| SignalingError::ProtocolError(err.to_string()))?
-            .token;
+            .map_err(|err| SignalingError::ProtocolError(err.to_string()))?;
+
+        log::debug!(
+            "Successfully retrieved WebSocket auth token (expires in: {:?}s)",
+            token.expires_in
+        );
+        Ok(ExpiringToken {
+            token: token.token,
+            expires_at: token
+                .expires_in
+                .map(|secs| Instant::now() + Duration::from_secs(secs)),
+        })
+    }
+}
+
+#[async_trait]
+impl TokenProvider for TauriTokenProvider {
+    async fn get_token(&self) -> Result<String, SignalingError> {
+        self.cache.get_or_refresh(|| self.fetch_token()).await
+    }
 
-        log::debug!("Successfully retrieved WebSocket auth token");
-        Ok(token)
+    async fn invalidate_token(&self) {
+        self.cache.invalidate().await;
     }
 }
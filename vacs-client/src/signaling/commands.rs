@@ -2,32 +2,35 @@ use crate::app::state::http::HttpState;
 use crate::app::state::signaling::AppStateSignalingExt;
 use crate::app::state::webrtc::AppStateWebrtcExt;
 use crate::app::state::{AppState, AppStateInner};
-use crate::audio::manager::{AudioManagerHandle, SourceType};
+use crate::audio::PeerMuted;
+use crate::audio::manager::AudioManagerHandle;
 use crate::config::{
     BackendEndpoint, CLIENT_SETTINGS_FILE_NAME, FrontendStationsConfig, Persistable,
     PersistedClientConfig,
 };
 use crate::error::{Error, HandleUnauthorizedExt};
+use crate::signaling::history::CallHistoryEntry;
+use crate::signaling::stations::{StationEntry, StationSearchResult, search_stations};
 use std::collections::HashSet;
-use tauri::{AppHandle, Manager, State};
-use vacs_signaling::protocol::http::webrtc::IceConfig;
-use vacs_signaling::protocol::ws::SignalingMessage;
+use tauri::{AppHandle, Emitter, Manager, State};
+use vacs_signaling::error::SignalingError;
 
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn signaling_connect(
+    app: AppHandle,
     app_state: State<'_, AppState>,
-    http_state: State<'_, HttpState>,
 ) -> Result<(), Error> {
+    AppStateInner::connect_signaling(&app).await?;
+
     let mut app_state = app_state.lock().await;
-    app_state.connect_signaling().await?;
 
     if !app_state.config.ice.is_default() {
         log::info!("Modified ICE config detected, not fetching from server");
         return Ok(());
     }
 
-    refresh_ice_config(&http_state, &mut app_state).await;
+    app_state.refresh_ice_config(&app).await;
 
     Ok(())
 }
@@ -67,29 +70,12 @@ pub async fn signaling_terminate(
 pub async fn signaling_start_call(
     app: AppHandle,
     app_state: State<'_, AppState>,
-    http_state: State<'_, HttpState>,
-    audio_manager: State<'_, AudioManagerHandle>,
     peer_id: String,
 ) -> Result<(), Error> {
     log::debug!("Starting call with {peer_id}");
 
     let mut state = app_state.lock().await;
-
-    state
-        .send_signaling_message(SignalingMessage::CallInvite {
-            peer_id: peer_id.clone(),
-        })
-        .await?;
-
-    if state.is_ice_config_expired() {
-        refresh_ice_config(&http_state, &mut state).await;
-    }
-
-    state.add_call_to_call_list(&app, &peer_id, false);
-    state.start_unanswered_call_timer(&app, &peer_id);
-    state.set_outgoing_call_peer_id(Some(peer_id));
-
-    audio_manager.read().restart(SourceType::Ringback);
+    state.start_call(&app, peer_id).await?;
 
     Ok(())
 }
@@ -162,6 +148,34 @@ pub async fn signaling_set_selected_stations_config_profile(
     Ok(())
 }
 
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn signaling_search_stations(
+    app_state: State<'_, AppState>,
+    stations: Vec<StationEntry>,
+    query: String,
+) -> Result<StationSearchResult, Error> {
+    let grouping = {
+        let state = app_state.lock().await;
+        let profiles = &state.config.stations.profiles;
+        profiles
+            .get(&state.config.client.selected_stations_profile)
+            .or_else(|| profiles.get("Default"))
+            .map(|profile| profile.grouping)
+            .unwrap_or_default()
+    };
+
+    Ok(search_stations(&stations, &query, grouping).into())
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn signaling_get_max_incoming_calls(
+    app_state: State<'_, AppState>,
+) -> Result<usize, Error> {
+    Ok(app_state.lock().await.config.client.max_incoming_calls)
+}
+
 #[tauri::command]
 #[vacs_macros::log_err]
 pub async fn signaling_get_ignored_clients(
@@ -216,21 +230,105 @@ pub async fn signaling_remove_ignored_client(
     Ok(removed)
 }
 
-async fn refresh_ice_config(http_state: &HttpState, app_state: &mut AppStateInner) {
-    let config = match http_state
-        .http_get::<IceConfig>(BackendEndpoint::IceConfig, None)
-        .await
-    {
-        Ok(config) => config,
-        Err(err) => {
-            log::warn!("Failed to fetch ICE config, falling back to default: {err:?}");
-            return;
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn signaling_get_peer_volume(
+    app_state: State<'_, AppState>,
+    peer_id: String,
+) -> Result<f32, Error> {
+    Ok(app_state.lock().await.config.client.peer_volume(&peer_id))
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn signaling_set_peer_volume(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    audio_manager: State<'_, AudioManagerHandle>,
+    peer_id: String,
+    volume: f32,
+) -> Result<f32, Error> {
+    let (persisted_client_config, volume): (PersistedClientConfig, f32) = {
+        let mut state = app_state.lock().await;
+        let volume = state.config.client.set_peer_volume(peer_id.clone(), volume);
+
+        if state.active_call_peer_id() == Some(&peer_id) {
+            audio_manager
+                .write()
+                .set_call_gain(volume, state.config.audio.output_device_volume);
         }
+
+        (state.config.client.clone().into(), volume)
     };
 
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .expect("Cannot get config directory");
+    persisted_client_config.persist(&config_dir, CLIENT_SETTINGS_FILE_NAME)?;
+
+    Ok(volume)
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn signaling_set_peer_muted(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    audio_manager: State<'_, AudioManagerHandle>,
+    peer_id: String,
+    muted: bool,
+) -> Result<(), Error> {
+    let state = app_state.lock().await;
+    if state.active_call_peer_id() != Some(&peer_id) {
+        return Err(Error::Signaling(Box::from(SignalingError::Other(format!(
+            "No active call with {peer_id}"
+        )))));
+    }
+
     log::info!(
-        "Received ICE config from server, expires at {}",
-        config.expires_at.unwrap_or_default()
+        "{} peer {peer_id}",
+        if muted { "Muting" } else { "Unmuting" }
     );
-    app_state.set_ice_config(config);
+    audio_manager
+        .write()
+        .set_call_muted(muted, state.config.audio.output_device_volume);
+
+    app.emit("call:peer-muted", PeerMuted { peer_id, muted })
+        .ok();
+
+    Ok(())
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn signaling_get_do_not_disturb(app_state: State<'_, AppState>) -> Result<bool, Error> {
+    Ok(app_state.lock().await.do_not_disturb())
+}
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn signaling_set_do_not_disturb(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), Error> {
+    app_state.lock().await.set_do_not_disturb(&app, enabled);
+
+    Ok(())
+}
+
+/// Default number of entries returned by [`signaling_get_call_history`].
+const DEFAULT_CALL_HISTORY_LIMIT: usize = 100;
+
+#[tauri::command]
+#[vacs_macros::log_err]
+pub async fn signaling_get_call_history(
+    app_state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<CallHistoryEntry>, Error> {
+    Ok(app_state
+        .lock()
+        .await
+        .recent_calls(limit.unwrap_or(DEFAULT_CALL_HISTORY_LIMIT)))
 }
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use vacs_signaling::protocol::ws::ClientInfo;
+
+/// Normalizes a frequency or 8.33 kHz channel name to the frequency of the channel in Hz.
+///
+/// Channel names ending in `0` or `5` within a 25 kHz block (e.g. `123.450`, `123.455`) both refer
+/// to the same frequency, `123.45` is accepted as shorthand for `123.450`. Returns `None` for
+/// malformed values and names not denoting a valid channel (e.g. the observer frequency
+/// `199.998`), which are never grouped with any other client.
+pub fn normalize_frequency(frequency: &str) -> Option<u32> {
+    let (mhz, khz) = frequency.trim().split_once('.')?;
+    if mhz.is_empty()
+        || khz.is_empty()
+        || khz.len() > 3
+        || !mhz.chars().chain(khz.chars()).all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let mhz = mhz.parse::<u32>().ok()?;
+    let khz = format!("{khz:0<3}").parse::<u32>().ok()?;
+
+    let block = khz - khz % 25;
+    let offset_hz = match khz % 25 {
+        0 | 5 => 0,
+        10 => 8_333,
+        15 => 16_667,
+        _ => return None,
+    };
+
+    Some(mhz * 1_000_000 + block * 1_000 + offset_hz)
+}
+
+/// Groups the given clients by their normalized primary frequency, skipping clients without a
+/// valid frequency. CIDs within a group are sorted.
+pub fn group_by_frequency<'a>(
+    clients: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> HashMap<u32, Vec<String>> {
+    let mut groups: HashMap<u32, Vec<String>> = HashMap::new();
+    for (id, frequency) in clients {
+        if let Some(frequency) = normalize_frequency(frequency) {
+            groups.entry(frequency).or_default().push(id.to_string());
+        }
+    }
+    for ids in groups.values_mut() {
+        ids.sort();
+    }
+    groups
+}
+
+/// Tracks the primary frequencies of all connected clients to determine which other controllers
+/// are on the own frequency.
+#[derive(Debug, Default)]
+pub struct FrequencyPeers {
+    own: Option<(String, String)>,    // (id, frequency)
+    clients: HashMap<String, String>, // id -> frequency
+    last_peers: Vec<String>,
+}
+
+impl FrequencyPeers {
+    pub fn set_own(&mut self, info: &ClientInfo) {
+        self.own = Some((info.id.clone(), info.frequency.clone()));
+    }
+
+    pub fn set_clients(&mut self, clients: &[ClientInfo]) {
+        self.clients = clients
+            .iter()
+            .map(|c| (c.id.clone(), c.frequency.clone()))
+            .collect();
+    }
+
+    pub fn insert(&mut self, client: &ClientInfo) {
+        self.clients
+            .insert(client.id.clone(), client.frequency.clone());
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.clients.remove(id);
+    }
+
+    pub fn clear(&mut self) {
+        self.own = None;
+        self.clients.clear();
+        self.last_peers.clear();
+    }
+
+    /// CIDs of all other connected clients sharing the own primary frequency, sorted.
+    pub fn peers(&self) -> Vec<String> {
+        let Some((own_id, own_frequency)) = &self.own else {
+            return Vec::new();
+        };
+        let Some(own_frequency) = normalize_frequency(own_frequency) else {
+            return Vec::new();
+        };
+
+        group_by_frequency(
+            self.clients
+                .iter()
+                .map(|(id, frequency)| (id.as_str(), frequency.as_str())),
+        )
+        .remove(&own_frequency)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|id| id != own_id)
+        .collect()
+    }
+
+    /// Returns the current co-frequency peers if they changed since the last call.
+    pub fn take_update(&mut self) -> Option<Vec<String>> {
+        let peers = self.peers();
+        if peers == self.last_peers {
+            return None;
+        }
+        self.last_peers = peers.clone();
+        Some(peers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(id: &str, frequency: &str) -> ClientInfo {
+        ClientInfo {
+            id: id.to_string(),
+            display_name: format!("CLIENT_{id}"),
+            frequency: frequency.to_string(),
+        }
+    }
+
+    #[test]
+    fn normalizes_833_channel_names() {
+        assert_eq!(normalize_frequency("123.450"), Some(123_450_000));
+        assert_eq!(normalize_frequency("123.455"), Some(123_450_000));
+        assert_eq!(normalize_frequency("123.45"), Some(123_450_000));
+        assert_eq!(normalize_frequency("123.460"), Some(123_458_333));
+        assert_eq!(normalize_frequency("123.465"), Some(123_466_667));
+        assert_eq!(normalize_frequency("123.475"), Some(123_475_000));
+        assert_eq!(normalize_frequency("123.470"), None);
+        assert_eq!(normalize_frequency("199.998"), None);
+        assert_eq!(normalize_frequency("123.4500"), None);
+        assert_eq!(normalize_frequency(""), None);
+        assert_eq!(normalize_frequency("abc.def"), None);
+    }
+
+    #[test]
+    fn groups_mixed_client_list() {
+        let clients = [
+            client("1", "123.455"),
+            client("2", "123.45"),
+            client("3", "134.350"),
+            client("4", "199.998"),
+            client("5", "123.450"),
+            client("6", "134.355"),
+            client("7", ""),
+            client("8", "123.460"),
+        ];
+
+        let groups = group_by_frequency(
+            clients
+                .iter()
+                .map(|c| (c.id.as_str(), c.frequency.as_str())),
+        );
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[&123_450_000], vec!["1", "2", "5"]);
+        assert_eq!(groups[&134_350_000], vec!["3", "6"]);
+        assert_eq!(groups[&123_458_333], vec!["8"]);
+    }
+
+    #[test]
+    fn tracks_co_frequency_peers() {
+        let mut peers = FrequencyPeers::default();
+        peers.set_clients(&[
+            client("1", "123.455"),
+            client("2", "123.45"),
+            client("3", "134.350"),
+        ]);
+        // Own frequency unknown until connected
+        assert_eq!(peers.take_update(), None);
+
+        peers.set_own(&client("1", "123.450"));
+        assert_eq!(peers.take_update(), Some(vec!["2".to_string()]));
+        assert_eq!(peers.take_update(), None);
+
+        peers.insert(&client("4", "123.455"));
+        assert_eq!(
+            peers.take_update(),
+            Some(vec!["2".to_string(), "4".to_string()])
+        );
+
+        // Clients on other frequencies do not change the peers
+        peers.insert(&client("5", "118.100"));
+        assert_eq!(peers.take_update(), None);
+
+        peers.remove("2");
+        peers.remove("4");
+        assert_eq!(peers.take_update(), Some(vec![]));
+    }
+}
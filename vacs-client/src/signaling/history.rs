@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const CALL_HISTORY_FILE_NAME: &str = "call_history.json";
+/// Maximum number of calls kept in the history, older calls are pruned first.
+const MAX_CALL_HISTORY_ENTRIES: usize = 500;
+/// Maximum age of calls kept in the history.
+const MAX_CALL_HISTORY_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CallDirection {
+    Incoming,
+    Outgoing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CallOutcome {
+    /// The call was answered and ended normally.
+    Completed,
+    /// An incoming call ended before it was answered.
+    Missed,
+    /// An outgoing call ended before it was answered.
+    NoAnswer,
+    /// The call was rejected by the callee.
+    Rejected,
+    /// The callee was already in a call with another peer.
+    Busy,
+    /// The call failed due to a signaling, WebRTC or audio error.
+    Failed,
+    /// The call was still ongoing when the application was closed.
+    EndedOnExit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallHistoryEntry {
+    pub peer_id: String,
+    pub display_name: Option<String>,
+    pub direction: CallDirection,
+    /// Unix timestamp in milliseconds the call was started or received at.
+    pub started_at: u64,
+    /// Unix timestamp in milliseconds the call was answered at.
+    pub answered_at: Option<u64>,
+    /// Unix timestamp in milliseconds the call ended at.
+    pub ended_at: Option<u64>,
+    /// Outcome of the call, `None` while the call is still ongoing.
+    pub outcome: Option<CallOutcome>,
+}
+
+/// Locally persisted history of calls, newest last.
+#[derive(Debug, Default)]
+pub struct CallHistory {
+    path: Option<PathBuf>,
+    entries: VecDeque<CallHistoryEntry>,
+    display_names: HashMap<String, String>,
+}
+
+impl CallHistory {
+    /// Loads the call history from the given file, starting with an empty history if it does not
+    /// exist or cannot be parsed. Calls still ongoing according to the file were interrupted by the
+    /// application exiting without recording their end.
+    pub fn load(path: PathBuf) -> Self {
+        let mut entries = match fs::read(&path) {
+            Ok(content) => serde_json::from_slice::<VecDeque<CallHistoryEntry>>(&content)
+                .unwrap_or_else(|err| {
+                    log::warn!("Failed to parse call history, starting with empty one: {err}");
+                    VecDeque::new()
+                }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => VecDeque::new(),
+            Err(err) => {
+                log::warn!("Failed to read call history, starting with empty one: {err}");
+                VecDeque::new()
+            }
+        };
+
+        for entry in entries.iter_mut().filter(|e| e.outcome.is_none()) {
+            entry.outcome = Some(CallOutcome::EndedOnExit);
+        }
+
+        let mut history = Self {
+            path: Some(path),
+            entries,
+            display_names: HashMap::new(),
+        };
+        history.prune(now_ms());
+        history
+    }
+
+    /// Replaces the known display names of connected clients.
+    pub fn set_display_names(&mut self, names: impl IntoIterator<Item = (String, String)>) {
+        self.display_names = names.into_iter().collect();
+    }
+
+    pub fn set_display_name(&mut self, peer_id: String, display_name: String) {
+        self.display_names.insert(peer_id, display_name);
+    }
+
+    /// Records the start of a call. Any call with the same peer still considered ongoing is ended.
+    pub fn start(&mut self, peer_id: &str, direction: CallDirection) {
+        self.end_ongoing(peer_id, None);
+
+        let now = now_ms();
+        self.entries.push_back(CallHistoryEntry {
+            peer_id: peer_id.to_string(),
+            display_name: self.display_names.get(peer_id).cloned(),
+            direction,
+            started_at: now,
+            answered_at: None,
+            ended_at: None,
+            outcome: None,
+        });
+        self.prune(now);
+        self.persist();
+    }
+
+    /// Records an incoming call that was rejected right away.
+    pub fn record_rejected(&mut self, peer_id: &str) {
+        self.start(peer_id, CallDirection::Incoming);
+        self.end(peer_id, Some(CallOutcome::Rejected));
+    }
+
+    pub fn answer(&mut self, peer_id: &str) {
+        if let Some(entry) = self.ongoing_mut(peer_id)
+            && entry.answered_at.is_none()
+        {
+            entry.answered_at = Some(now_ms());
+            self.persist();
+        }
+    }
+
+    /// Records the end of the ongoing call with the given peer, if any. Without an explicit
+    /// outcome, it is derived from whether the call has been answered.
+    pub fn end(&mut self, peer_id: &str, outcome: Option<CallOutcome>) {
+        if self.end_ongoing(peer_id, outcome) {
+            self.persist();
+        }
+    }
+
+    /// Records the end of all ongoing calls, e.g. after losing the signaling connection.
+    pub fn end_all(&mut self, outcome: Option<CallOutcome>) {
+        let now = now_ms();
+        let mut changed = false;
+        for entry in self.entries.iter_mut().filter(|e| e.outcome.is_none()) {
+            entry.finish(now, outcome);
+            changed = true;
+        }
+        if changed {
+            self.persist();
+        }
+    }
+
+    /// Returns up to `limit` calls, newest first.
+    pub fn recent(&self, limit: usize) -> Vec<CallHistoryEntry> {
+        self.entries.iter().rev().take(limit).cloned().collect()
+    }
+
+    fn ongoing_mut(&mut self, peer_id: &str) -> Option<&mut CallHistoryEntry> {
+        self.entries
+            .iter_mut()
+            .rev()
+            .find(|e| e.outcome.is_none() && e.peer_id == peer_id)
+    }
+
+    fn end_ongoing(&mut self, peer_id: &str, outcome: Option<CallOutcome>) -> bool {
+        match self.ongoing_mut(peer_id) {
+            Some(entry) => {
+                entry.finish(now_ms(), outcome);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn prune(&mut self, now: u64) {
+        let min_started_at = now.saturating_sub(MAX_CALL_HISTORY_AGE.as_millis() as u64);
+        while self
+            .entries
+            .front()
+            .is_some_and(|e| e.started_at < min_started_at && e.outcome.is_some())
+        {
+            self.entries.pop_front();
+        }
+
+        while self.entries.len() > MAX_CALL_HISTORY_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(err) = write_entries(path, &self.entries) {
+            log::warn!("Failed to persist call history: {err:?}");
+        }
+    }
+}
+
+impl CallHistoryEntry {
+    fn finish(&mut self, now: u64, outcome: Option<CallOutcome>) {
+        self.ended_at = Some(now);
+        self.outcome = Some(outcome.unwrap_or(match (self.answered_at, self.direction) {
+            (Some(_), _) => CallOutcome::Completed,
+            (None, CallDirection::Incoming) => CallOutcome::Missed,
+            (None, CallDirection::Outgoing) => CallOutcome::NoAnswer,
+        }));
+    }
+}
+
+fn write_entries(path: &Path, entries: &VecDeque<CallHistoryEntry>) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context("Failed to create data directory")?;
+    }
+    let serialized = serde_json::to_vec(entries).context("Failed to serialize call history")?;
+    fs::write(path, serialized).context("Failed to write call history to file")?;
+
+    Ok(())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "vacs-call-history-{name}-{}.json",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn outcome_derived_from_answer() {
+        let mut history = CallHistory::default();
+
+        history.start("1000001", CallDirection::Outgoing);
+        history.answer("1000001");
+        history.end("1000001", None);
+
+        history.start("1000002", CallDirection::Outgoing);
+        history.end("1000002", None);
+
+        history.start("1000003", CallDirection::Incoming);
+        history.end("1000003", None);
+
+        let outcomes = history
+            .recent(10)
+            .into_iter()
+            .map(|e| (e.peer_id, e.outcome))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("1000003".to_string(), Some(CallOutcome::Missed)),
+                ("1000002".to_string(), Some(CallOutcome::NoAnswer)),
+                ("1000001".to_string(), Some(CallOutcome::Completed)),
+            ]
+        );
+    }
+
+    #[test]
+    fn first_end_wins() {
+        let mut history = CallHistory::default();
+
+        history.start("1000001", CallDirection::Outgoing);
+        history.end("1000001", Some(CallOutcome::Rejected));
+        history.end("1000001", None);
+
+        let entries = history.recent(10);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].outcome, Some(CallOutcome::Rejected));
+        assert!(entries[0].ended_at.is_some());
+    }
+
+    #[test]
+    fn record_rejected_and_display_name() {
+        let mut history = CallHistory::default();
+        history.set_display_names([("1000001".to_string(), "LOWW_APP".to_string())]);
+
+        history.record_rejected("1000001");
+        history.record_rejected("1000002");
+
+        let entries = history.recent(10);
+        assert_eq!(entries[0].display_name, None);
+        assert_eq!(entries[1].display_name.as_deref(), Some("LOWW_APP"));
+        assert!(
+            entries
+                .iter()
+                .all(|e| e.direction == CallDirection::Incoming
+                    && e.outcome == Some(CallOutcome::Rejected))
+        );
+    }
+
+    #[test]
+    fn end_all_on_exit() {
+        let mut history = CallHistory::default();
+        history.start("1000001", CallDirection::Outgoing);
+        history.answer("1000001");
+        history.start("1000002", CallDirection::Incoming);
+        history.start("1000003", CallDirection::Incoming);
+        history.end("1000003", None);
+
+        history.end_all(Some(CallOutcome::EndedOnExit));
+
+        let outcomes = history
+            .recent(10)
+            .into_iter()
+            .map(|e| e.outcome)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                Some(CallOutcome::Missed),
+                Some(CallOutcome::EndedOnExit),
+                Some(CallOutcome::EndedOnExit),
+            ]
+        );
+    }
+
+    #[test]
+    fn capped_and_pruned() {
+        let mut history = CallHistory::default();
+        let now = now_ms();
+        let old = now - MAX_CALL_HISTORY_AGE.as_millis() as u64 - 1;
+        history.entries.push_back(CallHistoryEntry {
+            peer_id: "old".to_string(),
+            display_name: None,
+            direction: CallDirection::Incoming,
+            started_at: old,
+            answered_at: None,
+            ended_at: Some(old),
+            outcome: Some(CallOutcome::Missed),
+        });
+
+        history.start("1000000", CallDirection::Outgoing);
+        assert!(history.entries.iter().all(|e| e.peer_id != "old"));
+
+        for i in 0..MAX_CALL_HISTORY_ENTRIES + 10 {
+            history.start(&format!("{}", 1000001 + i), CallDirection::Outgoing);
+            history.end(&format!("{}", 1000001 + i), None);
+        }
+        assert_eq!(history.entries.len(), MAX_CALL_HISTORY_ENTRIES);
+        assert_eq!(
+            history.recent(1)[0].peer_id,
+            format!("{}", 1000000 + MAX_CALL_HISTORY_ENTRIES + 10)
+        );
+        assert_eq!(history.recent(5).len(), 5);
+    }
+
+    #[test]
+    fn persisted_and_interrupted_calls_ended_on_exit() {
+        let path = temp_path("persist");
+        let _ = fs::remove_file(&path);
+
+        {
+            let mut history = CallHistory::load(path.clone());
+            assert!(history.recent(10).is_empty());
+
+            history.start("1000001", CallDirection::Outgoing);
+            history.end("1000001", None);
+            history.start("1000002", CallDirection::Incoming);
+            history.answer("1000002");
+            // Application crashes without ending the call
+        }
+
+        let history = CallHistory::load(path.clone());
+        let entries = history.recent(10);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].peer_id, "1000002");
+        assert_eq!(entries[0].outcome, Some(CallOutcome::EndedOnExit));
+        assert!(entries[0].answered_at.is_some());
+        assert_eq!(entries[1].outcome, Some(CallOutcome::NoAnswer));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_file_starts_empty() {
+        let path = temp_path("invalid");
+        fs::write(&path, "not json").unwrap();
+
+        let history = CallHistory::load(path.clone());
+        assert!(history.recent(10).is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn serialized_outcome_names() {
+        assert_eq!(
+            serde_json::to_string(&CallOutcome::EndedOnExit).unwrap(),
+            "\"ended-on-exit\""
+        );
+        assert_eq!(
+            serde_json::to_string(&CallDirection::Incoming).unwrap(),
+            "\"incoming\""
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use crate::app::state::AppState;
+use crate::app::state::signaling::AppStateSignalingExt;
+use std::net::{IpAddr, UdpSocket};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+/// Interval in which the local network address is checked for changes.
+const NETWORK_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Public address used to determine the local address of the default route. Connecting a UDP
+/// socket does not send any packets, so this never reaches the network.
+const ROUTE_PROBE_ADDR: &str = "1.1.1.1:53";
+
+/// Periodically checks the local address used for outgoing traffic and forces a refresh of the
+/// ICE config whenever it changes, e.g. after moving to a different network.
+///
+/// The monitor runs until the given shutdown token is cancelled.
+pub fn spawn_network_monitor(app: AppHandle, shutdown_token: CancellationToken) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(NETWORK_CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        let mut last_addr = local_route_addr();
+        log::debug!("Monitoring network changes, current local address: {last_addr:?}");
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown_token.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            let addr = local_route_addr();
+            if addr == last_addr {
+                continue;
+            }
+
+            log::info!(
+                "Network change detected, local address changed from {last_addr:?} to {addr:?}"
+            );
+            last_addr = addr;
+
+            if addr.is_none() {
+                log::debug!("Network unavailable, keeping last known ICE config");
+                continue;
+            }
+
+            app.state::<AppState>()
+                .lock()
+                .await
+                .force_refresh_ice_config(&app)
+                .await;
+        }
+
+        log::trace!("Network monitor finished");
+    });
+}
+
+fn local_route_addr() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect(ROUTE_PROBE_ADDR).ok()?;
+    let addr = socket.local_addr().ok()?.ip();
+    (!addr.is_unspecified()).then_some(addr)
+}
@@ -0,0 +1,189 @@
+use crate::config::StationsGroupMode;
+use serde::{Deserialize, Serialize};
+
+/// Station as displayed in the (already filtered and sorted) stations list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StationEntry {
+    pub id: String,
+    pub display_name: String,
+    pub alias: Option<String>,
+}
+
+/// Result of [`search_stations`], borrowing from the searched stations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StationSearch<'a> {
+    /// Group headers containing at least one match, in the order of their best match. Each header
+    /// is the filter value selecting the group in the stations list.
+    pub groups: Vec<&'a str>,
+    /// IDs of all matching stations, prefix matches first.
+    pub stations: Vec<&'a str>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StationSearchResult {
+    pub groups: Vec<String>,
+    pub stations: Vec<String>,
+}
+
+impl From<StationSearch<'_>> for StationSearchResult {
+    fn from(search: StationSearch<'_>) -> Self {
+        Self {
+            groups: search.groups.into_iter().map(String::from).collect(),
+            stations: search.stations.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchKind {
+    Prefix,
+    Substring,
+}
+
+/// Searches the callsigns and aliases of the given stations, case-insensitively.
+///
+/// Stations with a callsign or alias starting with the query are ranked before stations merely
+/// containing it, otherwise the order of `stations` is preserved. An empty query matches nothing.
+pub fn search_stations<'a>(
+    stations: &'a [StationEntry],
+    query: &str,
+    grouping: StationsGroupMode,
+) -> StationSearch<'a> {
+    let query = query.trim();
+    if query.is_empty() {
+        return StationSearch::default();
+    }
+
+    let mut search = StationSearch::default();
+    for kind in [MatchKind::Prefix, MatchKind::Substring] {
+        for station in stations {
+            if match_kind(station, query) != Some(kind) {
+                continue;
+            }
+
+            search.stations.push(&station.id);
+            if let Some(group) = grouping.group_of(&station.display_name)
+                && !search.groups.contains(&group)
+            {
+                search.groups.push(group);
+            }
+        }
+    }
+    search
+}
+
+fn match_kind(station: &StationEntry, query: &str) -> Option<MatchKind> {
+    [
+        Some(station.display_name.as_str()),
+        station.alias.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|name| {
+        if starts_with_ignore_case(name, query) {
+            Some(MatchKind::Prefix)
+        } else if contains_ignore_case(name, query) {
+            Some(MatchKind::Substring)
+        } else {
+            None
+        }
+    })
+    .min()
+}
+
+fn starts_with_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack
+        .as_bytes()
+        .get(..needle.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .any(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OTHER_STATIONS_GROUP;
+
+    fn station(id: &str, display_name: &str, alias: Option<&str>) -> StationEntry {
+        StationEntry {
+            id: id.to_string(),
+            display_name: display_name.to_string(),
+            alias: alias.map(String::from),
+        }
+    }
+
+    fn stations() -> Vec<StationEntry> {
+        vec![
+            station("1", "LOVV_CTR", None),
+            station("2", "EDDM_APP", Some("MUNICH_APP")),
+            station("3", "LOWW_APP", Some("VIENNA_APP")),
+            station("4", "LOWW_TWR", None),
+            station("5", "EDMM_LOWW_CTR", None),
+            station("6", "OBSERVER", None),
+        ]
+    }
+
+    #[test]
+    fn ranks_prefix_before_substring() {
+        let stations = stations();
+
+        let search = search_stations(&stations, "loww", StationsGroupMode::None);
+        assert_eq!(search.stations, vec!["3", "4", "5"]);
+        assert!(search.groups.is_empty());
+
+        let search = search_stations(&stations, "V", StationsGroupMode::None);
+        assert_eq!(search.stations, vec!["3", "1", "6"]);
+
+        let search = search_stations(&stations, "_app", StationsGroupMode::None);
+        assert_eq!(search.stations, vec!["2", "3"]);
+    }
+
+    #[test]
+    fn prefers_best_match_of_callsign_and_alias() {
+        let stations = vec![
+            station("1", "LOWW_TOWER", None),
+            station("2", "EDDM_TWR", Some("TOWER_TWR")),
+            station("3", "LOWI_TWR", None),
+        ];
+
+        // Prefix match on the alias outranks a substring match on the callsign
+        let search = search_stations(&stations, "tow", StationsGroupMode::None);
+        assert_eq!(search.stations, vec!["2", "1"]);
+
+        let search = search_stations(&stations, "LOW", StationsGroupMode::None);
+        assert_eq!(search.stations, vec!["1", "3"]);
+    }
+
+    #[test]
+    fn empty_query_matches_nothing() {
+        let stations = stations();
+        assert_eq!(
+            search_stations(&stations, "  ", StationsGroupMode::Fir),
+            StationSearch::default()
+        );
+    }
+
+    #[test]
+    fn returns_group_headers_of_matches() {
+        let stations = stations();
+
+        let search = search_stations(&stations, "w", StationsGroupMode::Fir);
+        assert_eq!(search.stations, vec!["3", "4", "5"]);
+        assert_eq!(search.groups, vec!["LO", "ED"]);
+
+        let search = search_stations(&stations, "w", StationsGroupMode::FirAndIcao);
+        assert_eq!(search.groups, vec!["LOWW", "EDMM"]);
+
+        let search = search_stations(&stations, "obs", StationsGroupMode::Icao);
+        assert_eq!(search.stations, vec!["6"]);
+        assert_eq!(search.groups, vec![OTHER_STATIONS_GROUP]);
+    }
+}
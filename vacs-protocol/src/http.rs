@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "http")]
+pub mod admin;
 #[cfg(feature = "http")]
 pub mod auth;
 #[cfg(feature = "http")]
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A client currently connected to the signaling server, as reported by the admin API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedClient {
+    /// CID of the client.
+    pub id: String,
+    /// Display name (callsign) of the client.
+    pub display_name: String,
+    /// Frequency of the client.
+    pub frequency: String,
+    /// Time the client connected, as seconds since the Unix epoch.
+    pub connected_at: u64,
+}
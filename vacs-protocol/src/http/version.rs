@@ -6,6 +6,8 @@ use std::str::FromStr;
 pub struct Release {
     pub version: String,
     pub required: bool,
+    #[serde(default)]
+    pub mandatory: bool,
     pub url: String,
     pub signature: String,
     #[serde(skip_serializing_if = "Option::is_none")]
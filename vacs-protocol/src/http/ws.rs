@@ -3,4 +3,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct WebSocketToken {
     pub token: String,
+    /// Lifetime of the token in seconds, tokens without a lifetime must not be reused.
+    #[serde(default)]
+    pub expires_in: Option<u64>,
 }
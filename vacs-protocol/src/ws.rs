@@ -15,6 +15,20 @@ pub enum LoginFailureReason {
     Timeout,
     /// The client is using an unsupported protocol version.
     IncompatibleProtocolVersion,
+    /// The client's CID is banned from the server.
+    Banned,
+}
+
+/// Additional details on a login failure, helping the client to resolve it.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginFailureDetails {
+    /// Minimum protocol version supported by the server, set for [`LoginFailureReason::IncompatibleProtocolVersion`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_protocol_version: Option<String>,
+    /// URL a compatible client version can be downloaded from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
 }
 
 /// Possible reasons for a client or server error.
@@ -47,12 +61,31 @@ pub enum CallErrorReason {
     CallFailure,
     /// An error with the signaling connection to the peer occurred.
     SignalingFailure,
-    /// A call was automatically ended because the peer did not respond to the call invite within the specified timeout.
+    /// An accepted call was automatically ended because its connection was not established within the specified timeout.
     AutoHangup,
+    /// A call invite was automatically cancelled because the peer neither accepted nor rejected it within the specified timeout.
+    InviteTimeout,
     /// An unspecified error occurred.
     Other,
 }
 
+/// Possible reasons for the signaling server ending a call.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum CallEndReason {
+    /// The call reached the maximum call duration configured on the signaling server.
+    MaxDurationReached,
+}
+
+/// Possible reasons for a callee rejecting a call.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum CallRejectReason {
+    /// The callee has enabled do-not-disturb mode and automatically rejects all incoming calls.
+    DoNotDisturb,
+    /// The signaling server's call policy does not permit calls between the caller and the callee.
+    /// Only sent by the signaling server.
+    NotPermitted,
+}
+
 /// Possible reasons for being forcefully disconnected by the signaling server.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub enum DisconnectReason {
@@ -60,6 +93,44 @@ pub enum DisconnectReason {
     Terminated,
     /// No active VATSIM connection was found.
     NoActiveVatsimConnection,
+    /// The connection was terminated by a server administrator.
+    Kicked,
+    /// The client's CID was banned from the server.
+    Banned,
+}
+
+/// Optional protocol features negotiated during login.
+///
+/// Messages only understood by newer clients are gated on a capability, the server never sends
+/// them to clients that did not advertise the corresponding capability in their
+/// [`SignalingMessage::Login`].
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Capability {
+    /// The client handles [`SignalingMessage::CallBusy`]. Without it, call invites are forwarded
+    /// to busy peers.
+    CallBusy,
+    /// The client handles [`SignalingMessage::CallDurationWarning`]. Calls are ended at the
+    /// maximum call duration regardless of this capability.
+    CallDurationWarning,
+    /// The client handles [`CallRejectReason::NotPermitted`]. Without it, call invites not
+    /// permitted by the server's call policy are rejected without a reason.
+    CallPolicy,
+    /// The client handles [`SignalingMessage::CallIceEndOfCandidates`]. Without it, the end of
+    /// ICE candidates is not forwarded and the client has to finish gathering on its own.
+    IceEndOfCandidates,
+    /// A capability not known to this version of the protocol, ignored during negotiation.
+    #[serde(other)]
+    Unknown,
+}
+
+impl Capability {
+    /// All capabilities implemented by this version of the protocol.
+    pub const ALL: &[Capability] = &[
+        Capability::CallBusy,
+        Capability::CallDurationWarning,
+        Capability::CallPolicy,
+        Capability::IceEndOfCandidates,
+    ];
 }
 
 /// Represents a client as observed by the signaling server.
@@ -89,11 +160,21 @@ pub enum SignalingMessage {
         token: String,
         /// Version of the vacs protocol implemented by the client.
         protocol_version: String,
+        /// Optional protocol features supported by the client.
+        ///
+        /// Omitted by older clients, which will only receive messages not gated by a [`Capability`].
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        capabilities: Vec<Capability>,
     },
     /// A login failure message sent by the signaling server after a failed login attempt.
     LoginFailure {
         /// Reason for the login failure.
         reason: LoginFailureReason,
+        /// Optional details on how to resolve the login failure.
+        ///
+        /// Omitted by older servers and for failures without further guidance.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<LoginFailureDetails>,
     },
     /// A logout message sent by the client upon disconnection.
     ///
@@ -125,6 +206,12 @@ pub enum SignalingMessage {
         own: bool,
         /// Updated information about the client.
         info: ClientInfo,
+        /// Capabilities negotiated for the session, i.e. the subset of the capabilities advertised
+        /// in [`SignalingMessage::Login`] supported by the server.
+        ///
+        /// Only set in the response to a successful login, omitted by older servers.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        capabilities: Option<Vec<Capability>>,
     },
     /// A call accept message sent by the target client to accept an incoming call.
     ///
@@ -143,6 +230,17 @@ pub enum SignalingMessage {
         /// When sent to the signaling server by the callee, this is the ID of the source client initiating the call.
         /// When received from the signaling server (by the caller), this is the ID of the target client rejecting the call.
         peer_id: String,
+        /// Reason for the rejection, if the call was not rejected manually.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<CallRejectReason>,
+    },
+    /// A message sent by the signaling server in response to a [`SignalingMessage::CallInvite`] if the target client is already in an active call with another peer.
+    ///
+    /// The call invite is not forwarded to the target client, the caller should end its outgoing call attempt.
+    #[serde(rename_all = "camelCase")]
+    CallBusy {
+        /// ID of the target client that is busy.
+        peer_id: String,
     },
     /// A call offer message sent by the client to initiate a call with another client.
     ///
@@ -180,8 +278,26 @@ pub enum SignalingMessage {
     /// A call end message sent by either client to indicate the gracious end of a call.
     ///
     /// The signaling server will forward the message to the given peer, exchanging the [`SignalingMessage::CallEnd::peer_id`] with the other peer's ID.
+    ///
+    /// The signaling server also sends this message to both peers if it ended the call itself, providing the [`SignalingMessage::CallEnd::reason`].
     #[serde(rename_all = "camelCase")]
-    CallEnd { peer_id: String },
+    CallEnd {
+        peer_id: String,
+        /// Reason the call was ended by the signaling server, `None` for calls ended by a client.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<CallEndReason>,
+    },
+    /// A message sent by the signaling server to both peers of a call shortly before the call reaches the maximum call duration.
+    ///
+    /// Once the remaining time elapsed, the signaling server ends the call by sending a [`SignalingMessage::CallEnd`] with [`CallEndReason::MaxDurationReached`] to both peers.
+    /// Only sent to clients advertising [`Capability::CallDurationWarning`].
+    #[serde(rename_all = "camelCase")]
+    CallDurationWarning {
+        /// ID of the other peer of the call.
+        peer_id: String,
+        /// Number of seconds until the call is ended.
+        remaining_secs: u64,
+    },
     /// A call error message sent by either client to indicate an error during an active call or while trying to establish a call.
     ///
     /// The signaling server will forward the message to the given peer, exchanging the [`SignalingMessage::CallError::peer_id`] with the other peer's ID.
@@ -203,6 +319,15 @@ pub enum SignalingMessage {
         /// Contains the ID of the respective other peer during call setup.
         peer_id: String,
     },
+    /// A message sent by either client once it has finished gathering ICE candidates, signalling
+    /// that no further [`SignalingMessage::CallIceCandidate`] messages will follow for this call.
+    ///
+    /// The signaling server will forward the message to the given peer, exchanging the [`SignalingMessage::CallIceEndOfCandidates::peer_id`] with the other peer's ID.
+    #[serde(rename_all = "camelCase")]
+    CallIceEndOfCandidates {
+        /// Contains the ID of the respective other peer during call setup.
+        peer_id: String,
+    },
     /// A message sent by the signaling server if no peer with the given ID was found.
     #[serde(rename_all = "camelCase")]
     PeerNotFound {
@@ -257,6 +382,168 @@ impl SignalingMessage {
     pub fn deserialize(message: &str) -> serde_json::error::Result<Self> {
         serde_json::from_str(message)
     }
+
+    /// Encodes a [`SignalingMessage`] using the given [`WireFormat`].
+    pub fn encode(message: &Self, format: WireFormat) -> Result<EncodedMessage, CodecError> {
+        match format {
+            WireFormat::Json => Ok(EncodedMessage::Text(serde_json::to_string(message)?)),
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => {
+                // Internally tagged enums require struct fields to be encoded as maps.
+                Ok(EncodedMessage::Binary(rmp_serde::to_vec_named(message)?))
+            }
+        }
+    }
+
+    /// Decodes a [`SignalingMessage`] encoded using the given [`WireFormat`].
+    pub fn decode(message: &[u8], format: WireFormat) -> Result<Self, CodecError> {
+        match format {
+            WireFormat::Json => Ok(serde_json::from_slice(message)?),
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => Ok(rmp_serde::from_slice(message)?),
+        }
+    }
+
+    /// Returns the ID of the peer a message refers to, or `None` for messages not related to a
+    /// specific peer.
+    pub fn peer_id(&self) -> Option<&str> {
+        match self {
+            SignalingMessage::CallInvite { peer_id }
+            | SignalingMessage::CallAccept { peer_id }
+            | SignalingMessage::CallReject { peer_id, .. }
+            | SignalingMessage::CallBusy { peer_id }
+            | SignalingMessage::CallOffer { peer_id, .. }
+            | SignalingMessage::CallAnswer { peer_id, .. }
+            | SignalingMessage::CallEnd { peer_id, .. }
+            | SignalingMessage::CallDurationWarning { peer_id, .. }
+            | SignalingMessage::CallError { peer_id, .. }
+            | SignalingMessage::CallIceCandidate { peer_id, .. }
+            | SignalingMessage::CallIceEndOfCandidates { peer_id }
+            | SignalingMessage::PeerNotFound { peer_id }
+            | SignalingMessage::Error {
+                peer_id: Some(peer_id),
+                ..
+            } => Some(peer_id),
+            _ => None,
+        }
+    }
+}
+
+/// Format used to encode [`SignalingMessage`]s on the wire.
+///
+/// Clients select a format by requesting its [`WireFormat::subprotocol`] during the WebSocket
+/// handshake. Connections without a requested subprotocol use [`WireFormat::Json`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WireFormat {
+    /// Messages are encoded as JSON and sent as text frames.
+    #[default]
+    Json,
+    /// Messages are encoded as MessagePack and sent as binary frames.
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl WireFormat {
+    /// All wire formats supported by this build.
+    pub const SUPPORTED: &[WireFormat] = &[
+        WireFormat::Json,
+        #[cfg(feature = "msgpack")]
+        WireFormat::MessagePack,
+    ];
+
+    /// Returns the WebSocket subprotocol identifying this format.
+    pub fn subprotocol(self) -> &'static str {
+        match self {
+            WireFormat::Json => "vacs.json",
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => "vacs.msgpack",
+        }
+    }
+
+    /// Returns the format identified by the given WebSocket subprotocol, or `None` if it is
+    /// unknown or not supported by this build.
+    pub fn from_subprotocol(subprotocol: &str) -> Option<Self> {
+        Self::SUPPORTED
+            .iter()
+            .copied()
+            .find(|format| format.subprotocol() == subprotocol)
+    }
+}
+
+/// A [`SignalingMessage`] encoded by [`SignalingMessage::encode`], matching the WebSocket frame
+/// type it must be sent as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodedMessage {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl EncodedMessage {
+    /// Returns the length of the encoded message in bytes.
+    pub fn len(&self) -> usize {
+        match self {
+            EncodedMessage::Text(text) => text.len(),
+            EncodedMessage::Binary(data) => data.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Error encoding or decoding a [`SignalingMessage`].
+#[derive(Debug)]
+pub enum CodecError {
+    Json(serde_json::Error),
+    #[cfg(feature = "msgpack")]
+    MessagePackEncode(rmp_serde::encode::Error),
+    #[cfg(feature = "msgpack")]
+    MessagePackDecode(rmp_serde::decode::Error),
+}
+
+impl std::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodecError::Json(err) => write!(f, "JSON codec error: {err}"),
+            #[cfg(feature = "msgpack")]
+            CodecError::MessagePackEncode(err) => write!(f, "MessagePack encode error: {err}"),
+            #[cfg(feature = "msgpack")]
+            CodecError::MessagePackDecode(err) => write!(f, "MessagePack decode error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodecError::Json(err) => Some(err),
+            #[cfg(feature = "msgpack")]
+            CodecError::MessagePackEncode(err) => Some(err),
+            #[cfg(feature = "msgpack")]
+            CodecError::MessagePackDecode(err) => Some(err),
+        }
+    }
+}
+
+impl From<serde_json::Error> for CodecError {
+    fn from(err: serde_json::Error) -> Self {
+        CodecError::Json(err)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::encode::Error> for CodecError {
+    fn from(err: rmp_serde::encode::Error) -> Self {
+        CodecError::MessagePackEncode(err)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::decode::Error> for CodecError {
+    fn from(err: rmp_serde::decode::Error) -> Self {
+        CodecError::MessagePackDecode(err)
+    }
 }
 
 #[cfg(test)]
@@ -270,6 +557,7 @@ mod tests {
         let message = SignalingMessage::Login {
             token: "token1".to_string(),
             protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+            capabilities: vec![],
         };
 
         let serialized = SignalingMessage::serialize(&message).unwrap();
@@ -285,18 +573,93 @@ mod tests {
             SignalingMessage::Login {
                 token,
                 protocol_version,
+                capabilities,
             } => {
                 assert_eq!(token, "token1");
                 assert_eq!(protocol_version, VACS_PROTOCOL_VERSION);
+                assert_eq!(capabilities, vec![]);
+            }
+            _ => panic!("Expected Login message"),
+        }
+    }
+
+    #[test]
+    fn test_serialize_deserialize_login_with_capabilities() {
+        let message = SignalingMessage::Login {
+            token: "token1".to_string(),
+            protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+            capabilities: vec![Capability::CallBusy, Capability::CallPolicy],
+        };
+
+        let serialized = SignalingMessage::serialize(&message).unwrap();
+        assert_eq!(
+            serialized,
+            format!(
+                "{{\"type\":\"Login\",\"token\":\"token1\",\"protocolVersion\":\"{VACS_PROTOCOL_VERSION}\",\"capabilities\":[\"CallBusy\",\"CallPolicy\"]}}"
+            )
+        );
+
+        let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, message);
+    }
+
+    #[test]
+    fn test_deserialize_login_with_unknown_capability() {
+        let deserialized = SignalingMessage::deserialize(
+            "{\"type\":\"Login\",\"token\":\"token1\",\"protocolVersion\":\"1.0.0\",\"capabilities\":[\"CallBusy\",\"FutureFeature\"]}",
+        )
+        .unwrap();
+        match deserialized {
+            SignalingMessage::Login { capabilities, .. } => {
+                assert_eq!(
+                    capabilities,
+                    vec![Capability::CallBusy, Capability::Unknown]
+                );
             }
             _ => panic!("Expected Login message"),
         }
     }
 
+    #[test]
+    fn test_serialize_deserialize_client_info_with_capabilities() {
+        let message = SignalingMessage::ClientInfo {
+            own: true,
+            info: ClientInfo {
+                id: "client1".to_string(),
+                display_name: "station1".to_string(),
+                frequency: "100.000".to_string(),
+            },
+            capabilities: Some(vec![Capability::CallBusy]),
+        };
+
+        let serialized = SignalingMessage::serialize(&message).unwrap();
+        assert_eq!(
+            serialized,
+            "{\"type\":\"ClientInfo\",\"own\":true,\"info\":{\"id\":\"client1\",\"displayName\":\"station1\",\"frequency\":\"100.000\"},\"capabilities\":[\"CallBusy\"]}"
+        );
+
+        let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, message);
+
+        // Older servers do not negotiate capabilities.
+        let deserialized = SignalingMessage::deserialize(
+            "{\"type\":\"ClientInfo\",\"own\":true,\"info\":{\"id\":\"client1\",\"displayName\":\"station1\",\"frequency\":\"100.000\"}}",
+        )
+        .unwrap();
+        assert!(matches!(
+            deserialized,
+            SignalingMessage::ClientInfo {
+                capabilities: None,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn test_serialize_deserialize_login_failure() {
         let message = SignalingMessage::LoginFailure {
             reason: LoginFailureReason::DuplicateId,
+            details: None,
         };
 
         let serialized = SignalingMessage::serialize(&message).unwrap();
@@ -307,13 +670,52 @@ mod tests {
 
         let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
         match deserialized {
-            SignalingMessage::LoginFailure { reason } => {
+            SignalingMessage::LoginFailure { reason, details } => {
                 assert_eq!(reason, LoginFailureReason::DuplicateId);
+                assert_eq!(details, None);
             }
             _ => panic!("Expected LoginFailure message"),
         }
     }
 
+    #[test]
+    fn test_serialize_deserialize_login_failure_with_details() {
+        let message = SignalingMessage::LoginFailure {
+            reason: LoginFailureReason::IncompatibleProtocolVersion,
+            details: Some(LoginFailureDetails {
+                min_protocol_version: Some("0.3.0".to_string()),
+                download_url: Some("https://example.com/download".to_string()),
+            }),
+        };
+
+        let serialized = SignalingMessage::serialize(&message).unwrap();
+        assert_eq!(
+            serialized,
+            "{\"type\":\"LoginFailure\",\"reason\":\"IncompatibleProtocolVersion\",\"details\":{\"minProtocolVersion\":\"0.3.0\",\"downloadUrl\":\"https://example.com/download\"}}"
+        );
+
+        let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, message);
+    }
+
+    #[test]
+    fn test_deserialize_login_failure_with_partial_details() {
+        let deserialized = SignalingMessage::deserialize(
+            "{\"type\":\"LoginFailure\",\"reason\":\"IncompatibleProtocolVersion\",\"details\":{\"minProtocolVersion\":\"0.3.0\"}}",
+        )
+        .unwrap();
+        assert_eq!(
+            deserialized,
+            SignalingMessage::LoginFailure {
+                reason: LoginFailureReason::IncompatibleProtocolVersion,
+                details: Some(LoginFailureDetails {
+                    min_protocol_version: Some("0.3.0".to_string()),
+                    download_url: None,
+                }),
+            }
+        );
+    }
+
     #[test]
     fn test_serialize_deserialize_logout() {
         let message = SignalingMessage::Logout {};
@@ -375,6 +777,7 @@ mod tests {
     fn test_serialize_deserialize_call_reject() {
         let message = SignalingMessage::CallReject {
             peer_id: "client1".to_string(),
+            reason: None,
         };
 
         let serialized = SignalingMessage::serialize(&message).unwrap();
@@ -385,17 +788,60 @@ mod tests {
 
         let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
         match deserialized {
-            SignalingMessage::CallReject { peer_id } => {
+            SignalingMessage::CallReject { peer_id, reason } => {
+                assert_eq!(peer_id, "client1");
+                assert_eq!(reason, None);
+            }
+            _ => panic!("Expected CallReject message"),
+        }
+    }
+
+    #[test]
+    fn test_serialize_deserialize_call_reject_with_reason() {
+        let message = SignalingMessage::CallReject {
+            peer_id: "client1".to_string(),
+            reason: Some(CallRejectReason::DoNotDisturb),
+        };
+
+        let serialized = SignalingMessage::serialize(&message).unwrap();
+        assert_eq!(
+            serialized,
+            "{\"type\":\"CallReject\",\"peerId\":\"client1\",\"reason\":\"DoNotDisturb\"}"
+        );
+
+        let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
+        match deserialized {
+            SignalingMessage::CallReject { peer_id, reason } => {
                 assert_eq!(peer_id, "client1");
+                assert_eq!(reason, Some(CallRejectReason::DoNotDisturb));
             }
             _ => panic!("Expected CallReject message"),
         }
     }
 
+    #[test]
+    fn test_serialize_deserialize_call_busy() {
+        let message = SignalingMessage::CallBusy {
+            peer_id: "client1".to_string(),
+        };
+
+        let serialized = SignalingMessage::serialize(&message).unwrap();
+        assert_eq!(serialized, "{\"type\":\"CallBusy\",\"peerId\":\"client1\"}");
+
+        let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
+        match deserialized {
+            SignalingMessage::CallBusy { peer_id } => {
+                assert_eq!(peer_id, "client1");
+            }
+            _ => panic!("Expected CallBusy message"),
+        }
+    }
+
     #[test]
     fn test_serialize_deserialize_call_end() {
         let message = SignalingMessage::CallEnd {
             peer_id: "client1".to_string(),
+            reason: None,
         };
 
         let serialized = SignalingMessage::serialize(&message).unwrap();
@@ -403,13 +849,57 @@ mod tests {
 
         let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
         match deserialized {
-            SignalingMessage::CallEnd { peer_id } => {
+            SignalingMessage::CallEnd { peer_id, reason } => {
                 assert_eq!(peer_id, "client1");
+                assert_eq!(reason, None);
             }
             _ => panic!("Expected CallEnd message"),
         }
     }
 
+    #[test]
+    fn test_serialize_deserialize_call_end_with_reason() {
+        let message = SignalingMessage::CallEnd {
+            peer_id: "client1".to_string(),
+            reason: Some(CallEndReason::MaxDurationReached),
+        };
+
+        let serialized = SignalingMessage::serialize(&message).unwrap();
+        assert_eq!(
+            serialized,
+            "{\"type\":\"CallEnd\",\"peerId\":\"client1\",\"reason\":\"MaxDurationReached\"}"
+        );
+
+        let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, message);
+    }
+
+    #[test]
+    fn test_serialize_deserialize_call_duration_warning() {
+        let message = SignalingMessage::CallDurationWarning {
+            peer_id: "client1".to_string(),
+            remaining_secs: 30,
+        };
+
+        let serialized = SignalingMessage::serialize(&message).unwrap();
+        assert_eq!(
+            serialized,
+            "{\"type\":\"CallDurationWarning\",\"peerId\":\"client1\",\"remainingSecs\":30}"
+        );
+
+        let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
+        match deserialized {
+            SignalingMessage::CallDurationWarning {
+                peer_id,
+                remaining_secs,
+            } => {
+                assert_eq!(peer_id, "client1");
+                assert_eq!(remaining_secs, 30);
+            }
+            _ => panic!("Expected CallDurationWarning message"),
+        }
+    }
+
     #[test]
     fn test_serialize_deserialize_call_ice_candidate() {
         let message = SignalingMessage::CallIceCandidate {
@@ -433,6 +923,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_serialize_deserialize_call_ice_end_of_candidates() {
+        let message = SignalingMessage::CallIceEndOfCandidates {
+            peer_id: "client1".to_string(),
+        };
+
+        let serialized = SignalingMessage::serialize(&message).unwrap();
+        assert_eq!(
+            serialized,
+            "{\"type\":\"CallIceEndOfCandidates\",\"peerId\":\"client1\"}"
+        );
+
+        let deserialized = SignalingMessage::deserialize(&serialized).unwrap();
+        match deserialized {
+            SignalingMessage::CallIceEndOfCandidates { peer_id } => {
+                assert_eq!(peer_id, "client1");
+            }
+            _ => panic!("Expected CallIceEndOfCandidates message"),
+        }
+    }
+
     #[test]
     fn test_serialize_deserialize_client_connected() {
         let message = SignalingMessage::ClientConnected {
@@ -570,4 +1081,228 @@ mod tests {
             _ => panic!("Expected Error message"),
         }
     }
+
+    #[test]
+    fn test_peer_id() {
+        let message = SignalingMessage::CallOffer {
+            sdp: "sdp1".to_string(),
+            peer_id: "client1".to_string(),
+        };
+        assert_eq!(message.peer_id(), Some("client1"));
+
+        let message = SignalingMessage::Error {
+            reason: ErrorReason::MalformedMessage,
+            peer_id: Some("client2".to_string()),
+        };
+        assert_eq!(message.peer_id(), Some("client2"));
+
+        let message = SignalingMessage::Error {
+            reason: ErrorReason::MalformedMessage,
+            peer_id: None,
+        };
+        assert_eq!(message.peer_id(), None);
+        assert_eq!(SignalingMessage::Logout.peer_id(), None);
+    }
+
+    #[test]
+    fn test_wire_format_subprotocol() {
+        for format in WireFormat::SUPPORTED {
+            assert_eq!(
+                WireFormat::from_subprotocol(format.subprotocol()),
+                Some(*format)
+            );
+        }
+        assert_eq!(WireFormat::from_subprotocol("vacs.unknown"), None);
+    }
+
+    #[test]
+    fn test_encode_decode_json() {
+        let message = SignalingMessage::CallEnd {
+            peer_id: "client1".to_string(),
+            reason: None,
+        };
+
+        let encoded = SignalingMessage::encode(&message, WireFormat::Json).unwrap();
+        assert_eq!(
+            encoded,
+            EncodedMessage::Text(SignalingMessage::serialize(&message).unwrap())
+        );
+
+        let EncodedMessage::Text(text) = encoded else {
+            panic!("Expected Text message");
+        };
+        assert_eq!(
+            SignalingMessage::decode(text.as_bytes(), WireFormat::Json).unwrap(),
+            message
+        );
+    }
+
+    #[cfg(feature = "msgpack")]
+    mod msgpack {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        /// One instance of every [`SignalingMessage`] variant, including optional fields both set
+        /// and unset.
+        fn all_messages() -> Vec<SignalingMessage> {
+            let client = ClientInfo {
+                id: "client1".to_string(),
+                display_name: "Client 1".to_string(),
+                frequency: "100.000".to_string(),
+            };
+            vec![
+                SignalingMessage::Login {
+                    token: "token1".to_string(),
+                    protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+                    capabilities: vec![],
+                },
+                SignalingMessage::Login {
+                    token: "token1".to_string(),
+                    protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+                    capabilities: Capability::ALL.to_vec(),
+                },
+                SignalingMessage::LoginFailure {
+                    reason: LoginFailureReason::DuplicateId,
+                    details: None,
+                },
+                SignalingMessage::LoginFailure {
+                    reason: LoginFailureReason::IncompatibleProtocolVersion,
+                    details: Some(LoginFailureDetails {
+                        min_protocol_version: Some("2.0.0".to_string()),
+                        download_url: None,
+                    }),
+                },
+                SignalingMessage::Logout,
+                SignalingMessage::CallInvite {
+                    peer_id: "client1".to_string(),
+                },
+                SignalingMessage::ClientInfo {
+                    own: true,
+                    info: client.clone(),
+                    capabilities: None,
+                },
+                SignalingMessage::ClientInfo {
+                    own: true,
+                    info: client.clone(),
+                    capabilities: Some(vec![Capability::CallBusy]),
+                },
+                SignalingMessage::CallAccept {
+                    peer_id: "client1".to_string(),
+                },
+                SignalingMessage::CallReject {
+                    peer_id: "client1".to_string(),
+                    reason: None,
+                },
+                SignalingMessage::CallReject {
+                    peer_id: "client1".to_string(),
+                    reason: Some(CallRejectReason::DoNotDisturb),
+                },
+                SignalingMessage::CallReject {
+                    peer_id: "client1".to_string(),
+                    reason: Some(CallRejectReason::NotPermitted),
+                },
+                SignalingMessage::CallBusy {
+                    peer_id: "client1".to_string(),
+                },
+                SignalingMessage::CallOffer {
+                    sdp: "sdp1".to_string(),
+                    peer_id: "client1".to_string(),
+                },
+                SignalingMessage::CallAnswer {
+                    sdp: "sdp1".to_string(),
+                    peer_id: "client1".to_string(),
+                },
+                SignalingMessage::CallEnd {
+                    peer_id: "client1".to_string(),
+                    reason: None,
+                },
+                SignalingMessage::CallEnd {
+                    peer_id: "client1".to_string(),
+                    reason: Some(CallEndReason::MaxDurationReached),
+                },
+                SignalingMessage::CallDurationWarning {
+                    peer_id: "client1".to_string(),
+                    remaining_secs: 30,
+                },
+                SignalingMessage::CallError {
+                    peer_id: "client1".to_string(),
+                    reason: CallErrorReason::WebrtcFailure,
+                },
+                SignalingMessage::CallIceCandidate {
+                    candidate: "candidate1".to_string(),
+                    peer_id: "client1".to_string(),
+                },
+                SignalingMessage::CallIceEndOfCandidates {
+                    peer_id: "client1".to_string(),
+                },
+                SignalingMessage::PeerNotFound {
+                    peer_id: "client1".to_string(),
+                },
+                SignalingMessage::ClientConnected {
+                    client: client.clone(),
+                },
+                SignalingMessage::ClientDisconnected {
+                    id: "client1".to_string(),
+                },
+                SignalingMessage::ListClients,
+                SignalingMessage::ClientList {
+                    clients: vec![client],
+                },
+                SignalingMessage::Error {
+                    reason: ErrorReason::RateLimited {
+                        retry_after_secs: 42,
+                    },
+                    peer_id: None,
+                },
+                SignalingMessage::Error {
+                    reason: ErrorReason::UnexpectedMessage("error1".to_string()),
+                    peer_id: Some("client1".to_string()),
+                },
+                SignalingMessage::Disconnected {
+                    reason: DisconnectReason::Kicked,
+                },
+            ]
+        }
+
+        #[test]
+        fn test_encode_decode_all_messages() {
+            for message in all_messages() {
+                let encoded = SignalingMessage::encode(&message, WireFormat::MessagePack).unwrap();
+                let EncodedMessage::Binary(data) = encoded else {
+                    panic!("Expected Binary message for {message:?}");
+                };
+
+                let decoded = SignalingMessage::decode(&data, WireFormat::MessagePack).unwrap();
+                assert_eq!(decoded, message);
+            }
+        }
+
+        #[test]
+        fn test_messagepack_matches_json() {
+            for message in all_messages() {
+                let EncodedMessage::Text(json) =
+                    SignalingMessage::encode(&message, WireFormat::Json).unwrap()
+                else {
+                    panic!("Expected Text message for {message:?}");
+                };
+                let EncodedMessage::Binary(msgpack) =
+                    SignalingMessage::encode(&message, WireFormat::MessagePack).unwrap()
+                else {
+                    panic!("Expected Binary message for {message:?}");
+                };
+
+                let from_json: serde_json::Value = serde_json::from_str(&json).unwrap();
+                let from_msgpack: serde_json::Value = rmp_serde::from_slice(&msgpack).unwrap();
+                assert_eq!(from_msgpack, from_json);
+            }
+        }
+
+        #[test]
+        fn test_decode_invalid_messagepack() {
+            assert!(SignalingMessage::decode(b"invalid", WireFormat::MessagePack).is_err());
+
+            let unknown = rmp_serde::to_vec_named(&serde_json::json!({"type": "Unknown"})).unwrap();
+            assert!(SignalingMessage::decode(&unknown, WireFormat::MessagePack).is_err());
+        }
+    }
 }
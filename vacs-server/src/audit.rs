@@ -0,0 +1,255 @@
+use crate::metrics::labels::AsMetricLabel;
+use serde::{Deserialize, Serialize};
+use std::time::UNIX_EPOCH;
+use tokio::sync::mpsc;
+use tower_sessions_redis_store::fred::prelude::{Pool, StreamsInterface};
+use tracing::Instrument;
+use vacs_protocol::ws::CallErrorReason;
+
+/// Maximum number of audit records buffered for asynchronous sinks before new records are dropped.
+const AUDIT_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSinkKind {
+    Disabled,
+    /// Emits a structured tracing event with target `audit` for each record.
+    #[default]
+    Tracing,
+    /// Appends each record to a Redis stream. Requires the Redis store.
+    RedisStream,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    pub sink: AuditSinkKind,
+    pub redis_stream_key: String,
+    /// Approximate maximum length of the Redis stream, older entries are trimmed.
+    pub redis_stream_max_len: i64,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            sink: AuditSinkKind::Tracing,
+            redis_stream_key: "audit.calls".to_string(),
+            redis_stream_max_len: 100_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallAuditEvent {
+    Invite,
+    Accept,
+    Reject,
+    End,
+    Error,
+}
+
+impl CallAuditEvent {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CallAuditEvent::Invite => "invite",
+            CallAuditEvent::Accept => "accept",
+            CallAuditEvent::Reject => "reject",
+            CallAuditEvent::End => "end",
+            CallAuditEvent::Error => "error",
+        }
+    }
+}
+
+/// Audit record of a single call event.
+///
+/// Only contains metadata about the call, SDP and ICE candidate payloads are never recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallAuditRecord {
+    pub event: CallAuditEvent,
+    /// CID of the client that initiated the call, if known.
+    pub caller: Option<String>,
+    /// CID of the client that was called, if known.
+    pub callee: Option<String>,
+    /// CID of the client that sent the message causing this event.
+    pub sender: String,
+    /// Unix timestamp in milliseconds.
+    pub timestamp: u64,
+    pub outcome: String,
+}
+
+impl CallAuditRecord {
+    pub fn new(
+        event: CallAuditEvent,
+        sender: &str,
+        peer: &str,
+        caller: Option<String>,
+        error: Option<&CallErrorReason>,
+    ) -> Self {
+        let callee = caller
+            .as_deref()
+            .map(|caller| if caller == sender { peer } else { sender }.to_string());
+        let outcome = match (event, error) {
+            (_, Some(reason)) => reason.as_metric_label(),
+            (CallAuditEvent::Invite, _) => "initiated",
+            (CallAuditEvent::Accept, _) => "accepted",
+            (CallAuditEvent::Reject, _) => "rejected",
+            (CallAuditEvent::End, _) => "ended",
+            (CallAuditEvent::Error, None) => "other",
+        };
+
+        Self {
+            event,
+            caller,
+            callee,
+            sender: sender.to_string(),
+            timestamp: UNIX_EPOCH.elapsed().unwrap_or_default().as_millis() as u64,
+            outcome: outcome.to_string(),
+        }
+    }
+}
+
+enum AuditSink {
+    Disabled,
+    Tracing,
+    Channel(mpsc::Sender<CallAuditRecord>),
+    #[cfg(any(test, feature = "test-utils"))]
+    Memory(std::sync::Arc<parking_lot::Mutex<Vec<CallAuditRecord>>>),
+}
+
+/// Records call audit events.
+///
+/// Recording never blocks call handling: the tracing sink emits events inline, while the Redis
+/// sink buffers records and writes them from a background task, dropping records if the buffer is
+/// full or Redis is unavailable.
+pub struct AuditLog {
+    sink: AuditSink,
+}
+
+impl AuditLog {
+    pub fn disabled() -> Self {
+        Self {
+            sink: AuditSink::Disabled,
+        }
+    }
+
+    pub fn tracing() -> Self {
+        Self {
+            sink: AuditSink::Tracing,
+        }
+    }
+
+    /// Creates an audit log appending records to the given Redis stream. Must be called from
+    /// within a Tokio runtime.
+    pub fn redis_stream(pool: Pool, key: String, max_len: i64) -> Self {
+        let (tx, mut rx) = mpsc::channel::<CallAuditRecord>(AUDIT_CHANNEL_CAPACITY);
+
+        tokio::spawn(
+            async move {
+                while let Some(record) = rx.recv().await {
+                    let fields = vec![
+                        ("event", record.event.as_str().to_string()),
+                        ("caller", record.caller.unwrap_or_default()),
+                        ("callee", record.callee.unwrap_or_default()),
+                        ("sender", record.sender),
+                        ("timestamp", record.timestamp.to_string()),
+                        ("outcome", record.outcome),
+                    ];
+                    if let Err(err) = pool
+                        .xadd::<(), _, _, _, _>(&key, false, ("MAXLEN", "~", max_len), "*", fields)
+                        .await
+                    {
+                        tracing::warn!(?err, "Failed to write audit record to Redis stream");
+                    }
+                }
+                tracing::debug!("Audit log channel closed, stopping Redis audit writer");
+            }
+            .in_current_span(),
+        );
+
+        Self {
+            sink: AuditSink::Channel(tx),
+        }
+    }
+
+    /// Creates an audit log collecting records in memory, returning the shared record list.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn memory() -> (
+        Self,
+        std::sync::Arc<parking_lot::Mutex<Vec<CallAuditRecord>>>,
+    ) {
+        let records = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        (
+            Self {
+                sink: AuditSink::Memory(records.clone()),
+            },
+            records,
+        )
+    }
+
+    pub fn record(&self, record: CallAuditRecord) {
+        match &self.sink {
+            AuditSink::Disabled => {}
+            AuditSink::Tracing => {
+                tracing::info!(
+                    target: "audit",
+                    event = record.event.as_str(),
+                    caller = record.caller.as_deref(),
+                    callee = record.callee.as_deref(),
+                    sender = record.sender,
+                    timestamp = record.timestamp,
+                    outcome = record.outcome,
+                    "Call audit record"
+                );
+            }
+            AuditSink::Channel(tx) => {
+                if let Err(err) = tx.try_send(record) {
+                    tracing::warn!(?err, "Failed to queue audit record, dropping it");
+                }
+            }
+            #[cfg(any(test, feature = "test-utils"))]
+            AuditSink::Memory(records) => records.lock().push(record),
+        }
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::tracing()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn record_resolves_callee_from_caller() {
+        let record = CallAuditRecord::new(
+            CallAuditEvent::End,
+            "client2",
+            "client1",
+            Some("client1".to_string()),
+            None,
+        );
+        assert_eq!(record.caller.as_deref(), Some("client1"));
+        assert_eq!(record.callee.as_deref(), Some("client2"));
+        assert_eq!(record.sender, "client2");
+        assert_eq!(record.outcome, "ended");
+    }
+
+    #[test]
+    fn record_uses_error_reason_as_outcome() {
+        let record = CallAuditRecord::new(
+            CallAuditEvent::Error,
+            "client1",
+            "client2",
+            None,
+            Some(&CallErrorReason::WebrtcFailure),
+        );
+        assert_eq!(record.caller, None);
+        assert_eq!(record.callee, None);
+        assert_eq!(record.outcome, "webrtc_failure");
+    }
+}
@@ -0,0 +1,45 @@
+use crate::http::error::AppError;
+use crate::state::AppState;
+use axum::extract::FromRequestParts;
+use axum::http::header;
+use axum::http::request::Parts;
+use std::sync::Arc;
+
+/// Extractor guarding admin routes.
+///
+/// Requires an `Authorization: Bearer <token>` header matching the configured admin token.
+/// If no admin token is configured, all admin requests are rejected.
+#[derive(Debug, Clone, Copy)]
+pub struct AdminAuth;
+
+impl FromRequestParts<Arc<AppState>> for AdminAuth {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let expected = state.config.admin.token.as_str();
+        if expected.is_empty() {
+            return Err(AppError::Unauthorized("Admin API is disabled".to_string()));
+        }
+
+        let provided = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| AppError::Unauthorized("Missing admin token".to_string()))?;
+
+        if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+            tracing::warn!("Rejected admin request with invalid token");
+            return Err(AppError::Unauthorized("Invalid admin token".to_string()));
+        }
+
+        Ok(AdminAuth)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -19,27 +19,26 @@ pub async fn setup_auth_layer(
 ) -> anyhow::Result<AuthManagerLayer<Backend, RedisStore<Pool>, SignedCookie>> {
     tracing::debug!("Setting up authentication layer");
 
-    let client = BasicClient::new(ClientId::new(config.auth.oauth.client_id.clone()))
-        .set_client_secret(ClientSecret::new(config.auth.oauth.client_secret.clone()))
-        .set_auth_uri(AuthUrl::new(config.auth.oauth.auth_url.clone()).context("Invalid auth URL")?)
-        .set_token_uri(
-            TokenUrl::new(config.auth.oauth.token_url.clone()).context("Invalid token URL")?,
-        )
-        .set_redirect_uri(
-            RedirectUrl::new(config.auth.oauth.redirect_url.clone())
-                .context("Invalid redirect URL")?,
-        );
-    let backend = Backend::new(
-        client,
-        config.vatsim.user_service.user_details_endpoint_url.clone(),
-    )?;
-
+    let backend = setup_backend(config)?;
     let session_layer = setup_redis_session_manager(config, redis_pool).await?;
 
     tracing::debug!("Authentication layer setup complete");
     Ok(AuthManagerLayerBuilder::new(backend, session_layer).build())
 }
 
+#[instrument(level = "debug", skip_all, err)]
+pub async fn setup_memory_auth_layer(
+    config: &AppConfig,
+) -> anyhow::Result<AuthManagerLayer<Backend, MemoryStore, SignedCookie>> {
+    tracing::debug!("Setting up authentication layer with in-memory sessions");
+
+    let backend = setup_backend(config)?;
+    let session_layer = setup_memory_session_manager(config).await?;
+
+    tracing::debug!("Authentication layer setup complete");
+    Ok(AuthManagerLayerBuilder::new(backend, session_layer).build())
+}
+
 #[instrument(level = "debug", skip_all, err)]
 pub async fn setup_mock_auth_layer(
     config: &AppConfig,
@@ -53,3 +52,20 @@ pub async fn setup_mock_auth_layer(
     tracing::debug!("Mock authentication layer setup complete");
     Ok(AuthManagerLayerBuilder::new(backend, session_layer).build())
 }
+
+fn setup_backend(config: &AppConfig) -> anyhow::Result<Backend> {
+    let client = BasicClient::new(ClientId::new(config.auth.oauth.client_id.clone()))
+        .set_client_secret(ClientSecret::new(config.auth.oauth.client_secret.clone()))
+        .set_auth_uri(AuthUrl::new(config.auth.oauth.auth_url.clone()).context("Invalid auth URL")?)
+        .set_token_uri(
+            TokenUrl::new(config.auth.oauth.token_url.clone()).context("Invalid token URL")?,
+        )
+        .set_redirect_uri(
+            RedirectUrl::new(config.auth.oauth.redirect_url.clone())
+                .context("Invalid redirect URL")?,
+        );
+    Backend::new(
+        client,
+        config.vatsim.user_service.user_details_endpoint_url.clone(),
+    )
+}
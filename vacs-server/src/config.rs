@@ -1,3 +1,4 @@
+use crate::audit::AuditConfig;
 use crate::ice::IceConfig;
 use crate::ratelimit::RateLimitersConfig;
 use crate::release::catalog::CatalogConfig;
@@ -7,6 +8,7 @@ use config::{Config, Environment, File};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::time::Duration;
+use vacs_vatsim::slurper::SlurperFieldIndices;
 
 pub const BROADCAST_CHANNEL_CAPACITY: usize = 100;
 pub const CLIENT_CHANNEL_CAPACITY: usize = 100;
@@ -14,17 +16,33 @@ pub const CLIENT_WEBSOCKET_TASK_CHANNEL_CAPACITY: usize = 100;
 pub const CLIENT_WEBSOCKET_PING_INTERVAL: Duration = Duration::from_secs(10);
 pub const CLIENT_WEBSOCKET_PONG_TIMEOUT: Duration = Duration::from_secs(30);
 pub const SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+pub const WS_AUTH_TOKEN_TTL: Duration = Duration::from_secs(30);
+/// Factor applied to [`ServerConfig::max_message_size`] to derive the limit enforced by the
+/// WebSocket protocol layer. Frames exceeding it are dropped without being buffered, closing the
+/// connection, while smaller oversized messages are rejected gracefully.
+pub const WS_MESSAGE_SIZE_HARD_LIMIT_FACTOR: usize = 4;
+/// Time before [`CallsConfig::max_duration`] is reached at which both peers are warned about the
+/// call being ended.
+pub const CALL_DURATION_WARNING_LEAD: Duration = Duration::from_secs(30);
+/// Fraction of [`PresenceConfig::ttl`] after which a client's last-seen timestamp is refreshed in
+/// the store, limiting store writes for active clients.
+pub const PRESENCE_REFRESH_DIVISOR: u32 = 4;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct AppConfig {
     pub server: ServerConfig,
+    pub store: StoreConfig,
     pub redis: RedisConfig,
     pub session: SessionConfig,
     pub auth: AuthConfig,
+    pub admin: AdminConfig,
     pub vatsim: VatsimConfig,
     pub updates: UpdatesConfig,
     pub rate_limiters: RateLimitersConfig,
     pub ice: IceConfig,
+    pub audit: AuditConfig,
+    pub calls: CallsConfig,
+    pub presence: PresenceConfig,
 }
 
 impl AppConfig {
@@ -49,6 +67,8 @@ impl AppConfig {
             anyhow::bail!("OAuth client secret is empty");
         } else if config.session.signing_key.is_empty() {
             anyhow::bail!("Session signing key is empty");
+        } else if !(0.0..1.0).contains(&config.vatsim.controller_update_jitter) {
+            anyhow::bail!("Controller update jitter must be at least 0 and less than 1");
         }
 
         Ok(config)
@@ -69,6 +89,9 @@ pub struct ServerConfig {
     pub bind_addr: String,
     pub metrics_bind_addr: String,
     pub client_ip_source: ClientIpSource,
+    /// Maximum size (in bytes) of a single signaling message received from a client. Larger
+    /// messages are rejected with `ErrorReason::MalformedMessage`.
+    pub max_message_size: usize,
 }
 
 impl Default for ServerConfig {
@@ -77,10 +100,27 @@ impl Default for ServerConfig {
             bind_addr: "0.0.0.0:3000".to_string(),
             metrics_bind_addr: "0.0.0.0:9200".to_string(),
             client_ip_source: ClientIpSource::ConnectInfo,
+            max_message_size: 64 * 1024,
         }
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StoreConfig {
+    pub kind: StoreKind,
+}
+
+/// Backend used for the application store and the session store.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreKind {
+    #[default]
+    Redis,
+    /// Keeps all data in process memory. Data is lost on restart and cannot be shared between
+    /// instances, so this is only suitable for single-instance deployments.
+    Memory,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RedisConfig {
     pub addr: String,
@@ -117,6 +157,8 @@ impl Default for SessionConfig {
 pub struct AuthConfig {
     pub login_flow_timeout_millis: u64,
     pub oauth: OAuthConfig,
+    /// CIDs added to the ban list on startup. Further bans can be managed via the admin API.
+    pub banned_cids: Vec<String>,
 }
 
 impl Default for AuthConfig {
@@ -124,10 +166,17 @@ impl Default for AuthConfig {
         Self {
             login_flow_timeout_millis: 10000,
             oauth: OAuthConfig::default(),
+            banned_cids: Vec::new(),
         }
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AdminConfig {
+    /// Bearer token required for the admin API. The admin API is disabled if empty.
+    pub token: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OAuthConfig {
     pub auth_url: String,
@@ -154,8 +203,13 @@ pub struct VatsimConfig {
     pub user_service: VatsimUserServiceConfig,
     pub require_active_connection: bool,
     pub slurper_base_url: String,
+    pub slurper_field_indices: SlurperFieldIndices,
     pub data_feed_url: String,
     pub controller_update_interval: Duration,
+    /// Fraction of `controller_update_interval` by which each interval is randomly lengthened or
+    /// shortened, spreading data feed requests of multiple instances. Must be at least 0 and less
+    /// than 1, so intervals never shrink to zero.
+    pub controller_update_jitter: f64,
 }
 
 impl Default for VatsimConfig {
@@ -164,8 +218,10 @@ impl Default for VatsimConfig {
             user_service: Default::default(),
             require_active_connection: true,
             slurper_base_url: "https://slurper.vatsim.net".to_string(),
+            slurper_field_indices: SlurperFieldIndices::default(),
             data_feed_url: "https://data.vatsim.net/v3/vatsim-data.json".to_string(),
             controller_update_interval: Duration::from_secs(30),
+            controller_update_jitter: 0.1,
         }
     }
 }
@@ -187,6 +243,9 @@ impl Default for VatsimUserServiceConfig {
 pub struct UpdatesConfig {
     pub policy_path: String,
     pub catalog: CatalogConfig,
+    /// URL clients can download a compatible version from, reported on incompatible logins.
+    #[serde(default)]
+    pub download_url: Option<String>,
 }
 
 impl Default for UpdatesConfig {
@@ -195,6 +254,53 @@ impl Default for UpdatesConfig {
             policy_path: config_file_path("release_policy.toml")
                 .expect("Failed to build policy path"),
             catalog: CatalogConfig::default(),
+            download_url: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CallsConfig {
+    /// Maximum duration of an established call, after which the server ends it for both peers.
+    /// Calls are not limited if unset.
+    pub max_duration: Option<Duration>,
+    /// Relationship a caller and callee must meet for a call invite to be forwarded.
+    pub policy: CallPolicy,
+    /// Pairs of callsign prefixes permitted to call each other regardless of the policy.
+    pub allowed_pairs: Vec<(String, String)>,
+}
+
+/// Restriction applied to call invites, rejecting invites between clients not meeting it with
+/// `CallRejectReason::NotPermitted`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CallPolicy {
+    /// All calls are permitted.
+    #[default]
+    Unrestricted,
+    /// Only clients on the same frequency can call each other.
+    SameFrequency,
+    /// Only clients whose callsigns share the same region prefix (e.g. `LOWW` for `LOWW_APP`)
+    /// can call each other.
+    SameRegion,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PresenceConfig {
+    /// Time without any message from a client, including pong responses, after which the client
+    /// is considered stale and removed from the client list. Presence is not tracked if unset.
+    pub ttl: Option<Duration>,
+    /// Interval at which connected clients are checked for stale presence.
+    pub reap_interval: Duration,
+}
+
+impl Default for PresenceConfig {
+    fn default() -> Self {
+        Self {
+            ttl: None,
+            reap_interval: Duration::from_secs(30),
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::ice::provider::IceConfigProvider;
 use crate::ice::provider::cloudflare::CloudflareIceProvider;
 use crate::ice::provider::stun::StunOnlyProvider;
+use crate::ice::provider::turn_rest::TurnRestProvider;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
@@ -22,6 +23,8 @@ pub enum IceConfigProviderType {
     #[default]
     StunOnly,
     Cloudflare,
+    /// Self-hosted TURN server using ephemeral credentials derived from a shared secret.
+    TurnRest,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +33,8 @@ pub struct IceConfig {
     pub stun_servers: Option<Vec<String>>,
     pub cloudflare_turn_key_id: Option<String>,
     pub cloudflare_turn_key_api_token: Option<String>,
+    pub turn_servers: Option<Vec<String>>,
+    pub turn_rest_secret: Option<String>,
     pub turn_credential_ttl: Option<Duration>,
 }
 
@@ -43,6 +48,8 @@ impl Default for IceConfig {
             ]),
             cloudflare_turn_key_api_token: None,
             cloudflare_turn_key_id: None,
+            turn_servers: None,
+            turn_rest_secret: None,
             turn_credential_ttl: Some(Self::DEFAULT_TURN_CREDENTIAL_TTL),
         }
     }
@@ -79,6 +86,19 @@ impl IceConfig {
                     )),
                 }
             }
+            IceConfigProviderType::TurnRest => match (&self.turn_servers, &self.turn_rest_secret) {
+                (Some(turn_servers), Some(secret)) => Ok(Arc::new(TurnRestProvider::new(
+                    self.stun_servers.clone().unwrap_or_default(),
+                    turn_servers.clone(),
+                    secret,
+                    self.turn_credential_ttl
+                        .unwrap_or(Self::DEFAULT_TURN_CREDENTIAL_TTL)
+                        .as_secs(),
+                )?)),
+                _ => Err(IceError::Config(
+                    "Missing TURN servers or TURN REST secret".to_string(),
+                )),
+            },
         }
     }
 }
@@ -1,5 +1,6 @@
 pub mod cloudflare;
 pub mod stun;
+pub mod turn_rest;
 
 use crate::ice::IceError;
 use vacs_protocol::http::webrtc::IceConfig;
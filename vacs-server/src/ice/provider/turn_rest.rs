@@ -0,0 +1,157 @@
+use crate::ice::IceError;
+use crate::ice::provider::IceConfigProvider;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use std::fmt::{Debug, Formatter};
+use std::time::UNIX_EPOCH;
+use tracing::instrument;
+use vacs_protocol::http::webrtc::{IceConfig, IceServer};
+
+/// Provides ephemeral TURN credentials for a self-hosted TURN server using the TURN REST API
+/// scheme (as supported by e.g. coturn's `use-auth-secret`).
+///
+/// The username is the Unix timestamp the credentials expire at, the credential is
+/// `base64(HMAC-SHA1(secret, username))`. Credentials are generated locally on every request,
+/// so no round trip to the TURN server is required.
+#[derive(Clone)]
+pub struct TurnRestProvider {
+    stun_servers: Vec<String>,
+    turn_servers: Vec<String>,
+    secret: String,
+    ttl: u64,
+}
+
+impl Debug for TurnRestProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TurnRestProvider")
+            .field("stun_servers", &self.stun_servers)
+            .field("turn_servers", &self.turn_servers)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TurnRestProvider {
+    /// Clients refresh their ICE config 15 minutes before it expires, so shorter TTLs cause a
+    /// refresh before every call.
+    const CLIENT_EXPIRY_LEEWAY_SECS: u64 = 15 * 60;
+
+    pub fn new(
+        stun_servers: Vec<String>,
+        turn_servers: Vec<String>,
+        secret: impl Into<String>,
+        ttl: u64,
+    ) -> Result<Self, IceError> {
+        let secret = secret.into();
+        if turn_servers.is_empty() {
+            return Err(IceError::Config("Missing TURN servers".to_string()));
+        }
+        if secret.is_empty() {
+            return Err(IceError::Config("Missing TURN REST secret".to_string()));
+        }
+        if ttl <= Self::CLIENT_EXPIRY_LEEWAY_SECS {
+            tracing::warn!(
+                ?ttl,
+                "TURN credential TTL is shorter than the client refresh leeway, credentials will be refreshed before every call"
+            );
+        }
+
+        Ok(Self {
+            stun_servers,
+            turn_servers,
+            secret,
+            ttl,
+        })
+    }
+
+    fn calculate_expiry(&self) -> u64 {
+        UNIX_EPOCH.elapsed().unwrap_or_default().as_secs() + self.ttl
+    }
+
+    fn credential(secret: &str, username: &str) -> String {
+        let mut mac = Hmac::<Sha1>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(username.as_bytes());
+        BASE64_STANDARD.encode(mac.finalize().into_bytes())
+    }
+}
+
+#[async_trait::async_trait]
+impl IceConfigProvider for TurnRestProvider {
+    #[instrument(level = "debug", err)]
+    async fn get_ice_config(&self, user_id: &str) -> Result<IceConfig, IceError> {
+        tracing::debug!("Providing TURN REST ICE config");
+
+        let expiry = self.calculate_expiry();
+        let username = expiry.to_string();
+        let credential = Self::credential(&self.secret, &username);
+        tracing::trace!(?expiry, "Generated TURN credentials");
+
+        let mut ice_servers = Vec::with_capacity(2);
+        if !self.stun_servers.is_empty() {
+            ice_servers.push(IceServer::new(self.stun_servers.clone()));
+        }
+        ice_servers.push(IceServer::new(self.turn_servers.clone()).with_auth(username, credential));
+
+        Ok(IceConfig::from(ice_servers).with_expiry(expiry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_log::test;
+
+    fn provider(ttl: u64) -> TurnRestProvider {
+        TurnRestProvider::new(
+            vec!["stun:turn.example.com:3478".to_string()],
+            vec!["turn:turn.example.com:3478".to_string()],
+            "north",
+            ttl,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn credential_matches_known_vector() {
+        assert_eq!(
+            TurnRestProvider::credential("north", "1700000000"),
+            "CWyHi3zCeWqXBir9thl4m+iZPRY="
+        );
+    }
+
+    #[test]
+    fn new_requires_turn_servers_and_secret() {
+        assert!(TurnRestProvider::new(vec![], vec![], "north", 60).is_err());
+        assert!(
+            TurnRestProvider::new(vec![], vec!["turn:turn.example.com".to_string()], "", 60)
+                .is_err()
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn ice_config_contains_fresh_credentials() {
+        let provider = provider(3600);
+        let now = UNIX_EPOCH.elapsed().unwrap().as_secs();
+
+        let config = provider.get_ice_config("client1").await.unwrap();
+        let expires_at = config.expires_at.expect("Missing expiry");
+        assert!(expires_at >= now + 3600 && expires_at <= now + 3601);
+
+        assert_eq!(config.ice_servers.len(), 2);
+        assert_eq!(config.ice_servers[0].username, None);
+        let turn = &config.ice_servers[1];
+        assert_eq!(turn.urls, vec!["turn:turn.example.com:3478".to_string()]);
+        assert_eq!(turn.username, Some(expires_at.to_string()));
+        assert_eq!(
+            turn.credential,
+            Some(TurnRestProvider::credential(
+                "north",
+                &expires_at.to_string()
+            ))
+        );
+    }
+}
@@ -3,9 +3,10 @@ use std::sync::Arc;
 use tokio::signal;
 use tokio::sync::watch;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use vacs_server::auth::layer::setup_auth_layer;
+use vacs_server::audit::{AuditLog, AuditSinkKind};
+use vacs_server::auth::layer::{setup_auth_layer, setup_memory_auth_layer};
 use vacs_server::build::BuildInfo;
-use vacs_server::config::AppConfig;
+use vacs_server::config::{AppConfig, StoreKind};
 use vacs_server::metrics::setup_prometheus_metric_layer;
 use vacs_server::ratelimit::RateLimiters;
 use vacs_server::release::UpdateChecker;
@@ -13,6 +14,7 @@ use vacs_server::release::policy::Policy;
 use vacs_server::routes::{create_app, create_metrics_app};
 use vacs_server::state::AppState;
 use vacs_server::store::Store;
+use vacs_server::store::memory::MemoryStore;
 use vacs_server::store::redis::RedisStore;
 use vacs_vatsim::data_feed::VatsimDataFeed;
 use vacs_vatsim::slurper::SlurperClient;
@@ -40,10 +42,8 @@ async fn main() -> anyhow::Result<()> {
     let policy = Policy::new(&config.updates.policy_path)?;
     let updates = UpdateChecker::new(config.updates.catalog.to_catalog().await?, policy);
 
-    let redis_store = RedisStore::new(&config.redis).await?;
-    let redis_pool = redis_store.get_pool().clone();
-
-    let slurper = SlurperClient::new(config.vatsim.slurper_base_url.as_str())?;
+    let slurper = SlurperClient::new(config.vatsim.slurper_base_url.as_str())?
+        .with_field_indices(config.vatsim.slurper_field_indices)?;
     let data_feed = Arc::new(VatsimDataFeed::new(config.vatsim.data_feed_url.as_str())?);
 
     let rate_limiters = RateLimiters::from(config.rate_limiters);
@@ -52,26 +52,60 @@ async fn main() -> anyhow::Result<()> {
 
     let (prom_layer, prom_handle) = setup_prometheus_metric_layer();
 
+    let (store, app) = match config.store.kind {
+        StoreKind::Redis => {
+            let redis_store = RedisStore::new(&config.redis).await?;
+            let redis_pool = redis_store.get_pool().clone();
+            let auth_layer = setup_auth_layer(&config, redis_pool).await?;
+            let app = create_app(
+                auth_layer,
+                Some(prom_layer),
+                config.server.client_ip_source.clone(),
+            );
+            (Store::Redis(redis_store), app)
+        }
+        StoreKind::Memory => {
+            tracing::warn!(
+                "Using in-memory store, data is not persisted and cannot be shared between instances"
+            );
+            let auth_layer = setup_memory_auth_layer(&config).await?;
+            let app = create_app(
+                auth_layer,
+                Some(prom_layer),
+                config.server.client_ip_source.clone(),
+            );
+            (Store::Memory(MemoryStore::default()), app)
+        }
+    };
+
+    let audit_log = match (config.audit.sink, &store) {
+        (AuditSinkKind::Disabled, _) => AuditLog::disabled(),
+        (AuditSinkKind::Tracing, _) => AuditLog::tracing(),
+        (AuditSinkKind::RedisStream, Store::Redis(redis_store)) => AuditLog::redis_stream(
+            redis_store.get_pool().clone(),
+            config.audit.redis_stream_key.clone(),
+            config.audit.redis_stream_max_len,
+        ),
+        (AuditSinkKind::RedisStream, _) => {
+            anyhow::bail!("Redis stream audit sink requires the Redis store")
+        }
+    };
+
     let (shutdown_tx, shutdown_rx) = watch::channel(());
 
     let app_state = Arc::new(AppState::new(
         config.clone(),
         updates,
-        Store::Redis(redis_store),
+        store,
         slurper,
         data_feed,
         rate_limiters,
         shutdown_rx.clone(),
         ice_config_provider,
+        audit_log,
     ));
+    app_state.load_configured_bans().await?;
 
-    let auth_layer = setup_auth_layer(&config, redis_pool).await?;
-
-    let app = create_app(
-        auth_layer,
-        Some(prom_layer),
-        config.server.client_ip_source.clone(),
-    );
     let listener = tokio::net::TcpListener::bind(config.server.bind_addr).await?;
     tracing::info!(bind_addr = ?listener.local_addr(), "Started main listener");
 
@@ -83,11 +117,18 @@ async fn main() -> anyhow::Result<()> {
         Some(AppState::start_controller_update_task(
             app_state.clone(),
             config.vatsim.controller_update_interval,
+            config.vatsim.controller_update_jitter,
         ))
     } else {
         None
     };
 
+    let ban_listener_task = AppState::start_ban_listener_task(app_state.clone()).await?;
+
+    let presence_reaper_task = config.presence.ttl.map(|ttl| {
+        AppState::start_presence_reaper_task(app_state.clone(), ttl, config.presence.reap_interval)
+    });
+
     let metrics_server = axum::serve(metrics_listener, metrics_app.into_make_service())
         .with_graceful_shutdown(shutdown_signal(shutdown_tx.clone()));
 
@@ -106,6 +147,16 @@ async fn main() -> anyhow::Result<()> {
         tracing::warn!(?err, "Controller update task finished with error");
     }
 
+    if let Err(err) = ban_listener_task.await {
+        tracing::warn!(?err, "Ban listener task finished with error");
+    }
+
+    if let Some(presence_reaper_task) = presence_reaper_task
+        && let Err(err) = presence_reaper_task.await
+    {
+        tracing::warn!(?err, "Presence reaper task finished with error");
+    }
+
     Ok(())
 }
 
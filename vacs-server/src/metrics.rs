@@ -1,5 +1,5 @@
 pub mod guards;
-mod labels;
+pub(crate) mod labels;
 
 use crate::metrics::labels::AsMetricLabel;
 use crate::release::catalog::BundleType;
@@ -11,13 +11,13 @@ use axum_prometheus::{
 use metrics::{Unit, counter, describe_counter, describe_gauge, describe_histogram, histogram};
 use semver::Version;
 use vacs_protocol::http::version::ReleaseChannel;
-use vacs_protocol::ws::LoginFailureReason;
+use vacs_protocol::ws::{CallErrorReason, LoginFailureReason};
 
 pub fn setup_prometheus_metric_layer() -> (PrometheusMetricLayer<'static>, PrometheusHandle) {
     register_metrics();
 
     PrometheusMetricLayerBuilder::new()
-        .with_ignore_patterns(&["/health", "/favicon.ico"])
+        .with_ignore_patterns(&["/health", "/healthz", "/readyz", "/favicon.ico"])
         .with_metrics_from_fn(|| {
             PrometheusBuilder::new()
                 .set_buckets_for_metric(
@@ -115,9 +115,13 @@ impl ClientMetrics {
     }
 }
 
-struct CallMetrics;
+pub struct CallMetrics;
 
 impl CallMetrics {
+    pub fn failed(reason: &CallErrorReason) {
+        counter!("vacs_calls_failed_total", "reason" => reason.as_metric_label()).increment(1);
+    }
+
     fn register() {
         describe_gauge!(
             "vacs_calls_active",
@@ -129,6 +133,16 @@ impl CallMetrics {
             Unit::Count,
             "Total number of calls initiated, labeled by outcome (accepted, error, cancelled, no_answer, aborted)"
         );
+        describe_counter!(
+            "vacs_calls_initiated_total",
+            Unit::Count,
+            "Total number of call attempts started"
+        );
+        describe_counter!(
+            "vacs_calls_failed_total",
+            Unit::Count,
+            "Call errors reported by clients, labeled by reason"
+        );
         describe_counter!(
             "vacs_calls_total",
             Unit::Count,
@@ -48,6 +48,7 @@ impl Drop for ClientConnectionGuard {
 pub enum CallAttemptOutcome {
     Accepted,
     Rejected,
+    Busy,
     Error(CallErrorReason),
     Cancelled,
     Aborted,
@@ -70,6 +71,8 @@ impl CallAttemptGuard {
 
 impl Default for CallAttemptGuard {
     fn default() -> Self {
+        counter!("vacs_calls_initiated_total").increment(1);
+
         Self {
             start_time: Instant::now(),
             outcome: None,
@@ -14,6 +14,8 @@ impl AsMetricLabel for DisconnectReason {
         match self {
             DisconnectReason::Terminated => "terminated",
             DisconnectReason::NoActiveVatsimConnection => "no_active_vatsim_connection",
+            DisconnectReason::Kicked => "kicked",
+            DisconnectReason::Banned => "banned",
         }
     }
 }
@@ -36,6 +38,21 @@ impl AsMetricLabel for LoginFailureReason {
             LoginFailureReason::NoActiveVatsimConnection => "no_active_vatsim_connection",
             LoginFailureReason::Timeout => "timeout",
             LoginFailureReason::IncompatibleProtocolVersion => "incompatible_protocol_version",
+            LoginFailureReason::Banned => "banned",
+        }
+    }
+}
+
+impl AsMetricLabel for CallErrorReason {
+    fn as_metric_label(&self) -> &'static str {
+        match self {
+            CallErrorReason::AudioFailure => "audio_failure",
+            CallErrorReason::AutoHangup => "auto_hangup",
+            CallErrorReason::InviteTimeout => "invite_timeout",
+            CallErrorReason::WebrtcFailure => "webrtc_failure",
+            CallErrorReason::CallFailure => "call_failure",
+            CallErrorReason::SignalingFailure => "signaling_failure",
+            CallErrorReason::Other => "other",
         }
     }
 }
@@ -45,10 +62,12 @@ impl AsMetricLabel for CallAttemptOutcome {
         match self {
             CallAttemptOutcome::Accepted => "accepted",
             CallAttemptOutcome::Rejected => "rejected",
+            CallAttemptOutcome::Busy => "busy",
             CallAttemptOutcome::Cancelled => "cancelled",
             CallAttemptOutcome::Aborted => "aborted",
             CallAttemptOutcome::Error(CallErrorReason::AudioFailure) => "error_audio_failure",
             CallAttemptOutcome::Error(CallErrorReason::AutoHangup) => "error_auto_hangup",
+            CallAttemptOutcome::Error(CallErrorReason::InviteTimeout) => "error_invite_timeout",
             CallAttemptOutcome::Error(CallErrorReason::WebrtcFailure) => "error_webrtc_failure",
             CallAttemptOutcome::Error(CallErrorReason::CallFailure) => "error_call_failure",
             CallAttemptOutcome::Error(CallErrorReason::SignalingFailure) => {
@@ -90,11 +109,14 @@ impl AsMetricLabel for SignalingMessage {
             SignalingMessage::ClientInfo { .. } => "client_info",
             SignalingMessage::CallAccept { .. } => "call_accept",
             SignalingMessage::CallReject { .. } => "call_reject",
+            SignalingMessage::CallBusy { .. } => "call_busy",
             SignalingMessage::CallOffer { .. } => "call_offer",
             SignalingMessage::CallAnswer { .. } => "call_answer",
             SignalingMessage::CallEnd { .. } => "call_end",
+            SignalingMessage::CallDurationWarning { .. } => "call_duration_warning",
             SignalingMessage::CallError { .. } => "call_error",
             SignalingMessage::CallIceCandidate { .. } => "call_ice_candidate",
+            SignalingMessage::CallIceEndOfCandidates { .. } => "call_ice_end_of_candidates",
             SignalingMessage::PeerNotFound { .. } => "peer_not_found",
             SignalingMessage::ClientConnected { .. } => "client_connected",
             SignalingMessage::ClientDisconnected { .. } => "client_disconnected",
@@ -99,6 +99,8 @@ pub struct RateLimiters {
     failed_auth_per_minute: Option<KeyedLimiter<Key>>,
     version_update: Option<KeyedLimiter<Key>>,
     version_update_per_minute: Option<KeyedLimiter<Key>>,
+    message: Option<KeyedLimiter<Key>>,
+    message_per_minute: Option<KeyedLimiter<Key>>,
 }
 
 impl RateLimiters {
@@ -127,6 +129,13 @@ impl RateLimiters {
         .and_then(|_| Self::check(&self.version_update, "version_update", &key))
     }
 
+    #[inline]
+    pub fn check_message(&self, key: impl Into<Key>) -> Result<(), Duration> {
+        let key = key.into();
+        Self::check(&self.message_per_minute, "message_per_minute", &key)
+            .and_then(|_| Self::check(&self.message, "message", &key))
+    }
+
     #[inline]
     fn check(
         limiter: &Option<KeyedLimiter<Key>>,
@@ -144,6 +153,17 @@ impl RateLimiters {
     }
 }
 
+/// Converts the wait time returned by a rate limit check into the number of seconds a client has
+/// to wait before retrying, rounding up so the client never retries too early.
+pub fn retry_after_secs(until: Duration) -> u64 {
+    until.as_secs() + u64::from(until.subsec_nanos() > 0)
+}
+
+/// Rate limits applied by the server.
+///
+/// `call_invite` and `message` are applied to logged-in clients and keyed by CID, so clients
+/// sharing an IP address do not share a limit. Call end and ICE messages are exempt from
+/// `message`. `failed_auth` and `version_update` are keyed by client IP.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(default)]
 pub struct RateLimitersConfig {
@@ -154,6 +174,8 @@ pub struct RateLimitersConfig {
     pub failed_auth_per_minute: u32,
     pub version_update: Policy,
     pub version_update_per_minute: u32,
+    pub message: Policy,
+    pub message_per_minute: u32,
 }
 
 impl Default for RateLimitersConfig {
@@ -166,6 +188,8 @@ impl Default for RateLimitersConfig {
             failed_auth_per_minute: 0, // 60
             version_update: Policy::new(1, nonzero!(10u32)),
             version_update_per_minute: 60,
+            message: Policy::new(1, nonzero!(50u32)),
+            message_per_minute: 300,
         }
     }
 }
@@ -180,6 +204,8 @@ impl From<RateLimitersConfig> for RateLimiters {
                 failed_auth_per_minute: None,
                 version_update: None,
                 version_update_per_minute: None,
+                message: None,
+                message_per_minute: None,
             };
         }
 
@@ -228,6 +254,20 @@ impl From<RateLimitersConfig> for RateLimiters {
             None
         };
 
+        let message = if value.message.enabled {
+            Some(KeyedLimiter::<Key>::keyed(value.message.quota()))
+        } else {
+            None
+        };
+        let message_per_minute = if value.message_per_minute > 0 {
+            let val = NonZero::new(value.message_per_minute).expect("invalid message_per_minute");
+            Some(KeyedLimiter::<Key>::keyed(
+                Quota::per_minute(val).allow_burst(val),
+            ))
+        } else {
+            None
+        };
+
         Self {
             call_invite,
             call_invite_per_minute,
@@ -235,6 +275,68 @@ impl From<RateLimitersConfig> for RateLimiters {
             failed_auth_per_minute,
             version_update,
             version_update_per_minute,
+            message,
+            message_per_minute,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn message_only_config(burst: NonZeroU32) -> RateLimitersConfig {
+        RateLimitersConfig {
+            call_invite: Policy::default().disabled(),
+            call_invite_per_minute: 0,
+            version_update: Policy::default().disabled(),
+            version_update_per_minute: 0,
+            message: Policy::new(60, burst),
+            message_per_minute: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn message_limit_is_per_key() {
+        let limiters = RateLimiters::from(message_only_config(nonzero!(2u32)));
+
+        assert!(limiters.check_message("client1").is_ok());
+        assert!(limiters.check_message("client1").is_ok());
+        assert!(limiters.check_message("client1").is_err());
+
+        assert!(limiters.check_message("client2").is_ok());
+        assert!(limiters.check_message("client2").is_ok());
+    }
+
+    #[test]
+    fn message_limit_reports_wait_time() {
+        let limiters = RateLimiters::from(message_only_config(nonzero!(1u32)));
+
+        assert!(limiters.check_message("client1").is_ok());
+        let until = limiters.check_message("client1").unwrap_err();
+        assert!(until > Duration::from_secs(59) && until <= Duration::from_secs(60));
+        assert_eq!(retry_after_secs(until), 60);
+    }
+
+    #[test]
+    fn disabled_limiters_allow_everything() {
+        let limiters = RateLimiters::from(RateLimitersConfig {
+            enabled: false,
+            ..message_only_config(nonzero!(1u32))
+        });
+
+        for _ in 0..10 {
+            assert!(limiters.check_message("client1").is_ok());
+        }
+    }
+
+    #[test]
+    fn retry_after_secs_rounds_up() {
+        assert_eq!(retry_after_secs(Duration::ZERO), 0);
+        assert_eq!(retry_after_secs(Duration::from_millis(1)), 1);
+        assert_eq!(retry_after_secs(Duration::from_secs(2)), 2);
+        assert_eq!(retry_after_secs(Duration::from_millis(2001)), 3);
+    }
+}
@@ -28,6 +28,7 @@ impl UpdateChecker {
         target: &String,
         arch: &String,
         bundle_type: &BundleType,
+        cid: Option<&str>,
     ) -> Result<Option<Release>, AppError> {
         tracing::debug!("Checking for update");
 
@@ -37,6 +38,7 @@ impl UpdateChecker {
         for ch in &visible {
             for m in self.catalog.list(*ch).await? {
                 if m.version > *client_version
+                    && self.policy.is_rolled_out(&m.version, cid)
                     && let Some(a) = m.assets.iter().find(|a| {
                         a.bundle_type == *bundle_type && a.target == *target && a.arch == *arch
                     })
@@ -60,6 +62,7 @@ impl UpdateChecker {
             'outer: for ch in &visible {
                 for m in self.catalog.list(*ch).await? {
                     if m.version > *client_version
+                        && self.policy.is_rolled_out(&m.version, cid)
                         && m.assets.iter().any(|a| {
                             a.bundle_type == *bundle_type && a.target == *target && a.arch == *arch
                         })
@@ -73,13 +76,16 @@ impl UpdateChecker {
             req
         };
 
+        let mandatory = self.policy.is_mandatory(channel, client_version);
+
         let release = Release {
             version: meta.version.to_string(),
             notes: meta.notes.clone(),
             pub_date: meta.pub_date.clone(),
             url: asset.url.clone(),
             signature: self.catalog.load_signature(&meta, &asset).await?,
-            required,
+            required: required || mandatory,
+            mandatory,
         };
 
         tracing::debug!(?visible, ?release, "Update found");
@@ -98,6 +104,11 @@ impl UpdateChecker {
         );
         compatible
     }
+
+    /// Minimum protocol version clients need to implement, reported to incompatible clients.
+    pub fn min_compatible_protocol(&self) -> Option<Version> {
+        self.policy.min_compatible_protocol()
+    }
 }
 
 impl Default for UpdateChecker {
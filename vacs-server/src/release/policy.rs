@@ -3,6 +3,7 @@ use anyhow::Context;
 use parking_lot::RwLock;
 use semver::{Version, VersionReq};
 use serde::Deserialize;
+use sha1::{Digest, Sha1};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -14,8 +15,10 @@ use vacs_protocol::http::version::ReleaseChannel;
 pub struct Policy {
     path: PathBuf,
     required_ranges: RwLock<HashMap<ReleaseChannel, Vec<VersionReq>>>,
+    mandatory_ranges: RwLock<HashMap<ReleaseChannel, Vec<VersionReq>>>,
     compatible_protocol_range: RwLock<VersionReq>,
     visibility: RwLock<HashMap<ReleaseChannel, Vec<ReleaseChannel>>>,
+    rollout: RwLock<HashMap<Version, u8>>,
 }
 
 impl Policy {
@@ -24,8 +27,10 @@ impl Policy {
         let policy = Self {
             path: path.into(),
             required_ranges: Default::default(),
+            mandatory_ranges: Default::default(),
             compatible_protocol_range: Default::default(),
             visibility: Default::default(),
+            rollout: Default::default(),
         };
         policy.reload()?;
         Ok(policy)
@@ -44,23 +49,15 @@ impl Policy {
             fs::read(&self.path).with_context(|| format!("reading policy {:?}", self.path))?;
         let raw_policy: RawPolicy = toml::from_slice(&bytes).context("parsing policy")?;
 
-        let mut required_ranges = HashMap::new();
-        for (k, reqs) in raw_policy.required_ranges {
-            let parsed = reqs
-                .into_iter()
-                .map(|s| {
-                    VersionReq::parse(&s).with_context(|| {
-                        format!("invalid version req '{s}' in required_ranges: {k}")
-                    })
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-            required_ranges.insert(
-                k.parse()
-                    .map_err(|e: String| anyhow::anyhow!(e))
-                    .with_context(|| format!("invalid channel key in required_ranges: {k}"))?,
-                parsed,
-            );
-        }
+        self.apply(raw_policy)?;
+
+        tracing::info!("Policy reloaded");
+        Ok(())
+    }
+
+    fn apply(&self, raw_policy: RawPolicy) -> Result<(), AppError> {
+        let required_ranges = parse_ranges(raw_policy.required_ranges, "required_ranges")?;
+        let mandatory_ranges = parse_ranges(raw_policy.mandatory_ranges, "mandatory_ranges")?;
 
         let compatible_protocol_range = VersionReq::parse(&raw_policy.compatible_protocol_range)
             .with_context(|| {
@@ -126,11 +123,25 @@ impl Policy {
             );
         }
 
+        let mut rollout = HashMap::new();
+        for (k, percentage) in raw_policy.rollout {
+            let version =
+                Version::parse(&k).with_context(|| format!("invalid version '{k}' in rollout"))?;
+            if percentage > 100 {
+                return Err(anyhow::anyhow!(
+                    "invalid rollout percentage {percentage} for version {k}, must be between 0 and 100"
+                )
+                .into());
+            }
+            rollout.insert(version, percentage);
+        }
+
         *self.required_ranges.write() = required_ranges;
+        *self.mandatory_ranges.write() = mandatory_ranges;
         *self.compatible_protocol_range.write() = compatible_protocol_range;
         *self.visibility.write() = visibility;
+        *self.rollout.write() = rollout;
 
-        tracing::info!("Policy reloaded");
         Ok(())
     }
 
@@ -145,10 +156,36 @@ impl Policy {
         })
     }
 
+    /// Returns whether clients running `client_version` must update before they can continue,
+    /// e.g. because the release they are running contains a critical bug.
+    pub fn is_mandatory(&self, channel: &ReleaseChannel, client_version: &Version) -> bool {
+        let visible = self.visible_channels(channel);
+
+        visible.iter().any(|ch| {
+            self.mandatory_ranges
+                .read()
+                .get(ch)
+                .is_some_and(|reqs| reqs.iter().any(|req| req.matches(client_version)))
+        })
+    }
+
     pub fn is_compatible_protocol(&self, version: &Version) -> bool {
         self.compatible_protocol_range.read().matches(version)
     }
 
+    /// Lowest protocol version satisfying the lower bounds of the compatible protocol range.
+    ///
+    /// Since upper bounds are rejected for the compatible protocol range, this is the minimum
+    /// version clients need to implement to be able to connect.
+    pub fn min_compatible_protocol(&self) -> Option<Version> {
+        let range = self.compatible_protocol_range.read();
+        if range.comparators.is_empty() {
+            // `*` matches every version
+            return Some(Version::new(0, 0, 0));
+        }
+        range.comparators.iter().filter_map(lower_bound).max()
+    }
+
     pub fn visible_channels(&self, channel: &ReleaseChannel) -> Vec<ReleaseChannel> {
         let visibility = self.visibility.read();
         visibility
@@ -156,18 +193,231 @@ impl Policy {
             .cloned()
             .unwrap_or_else(|| vec![*channel])
     }
+
+    /// Returns whether `version` is offered to the client identified by `cid`.
+    ///
+    /// Versions without a rollout rule are offered to everyone. Versions in a staged rollout are
+    /// only offered to clients whose CID bucket falls below the configured percentage, so clients
+    /// not providing a CID only receive fully rolled out versions.
+    pub fn is_rolled_out(&self, version: &Version, cid: Option<&str>) -> bool {
+        let percentage = match self.rollout.read().get(version) {
+            Some(percentage) => *percentage,
+            None => return true,
+        };
+
+        if percentage >= 100 {
+            return true;
+        }
+
+        cid.is_some_and(|cid| rollout_bucket(cid) < percentage)
+    }
+}
+
+fn lower_bound(comp: &semver::Comparator) -> Option<Version> {
+    let minor = comp.minor.unwrap_or(0);
+    let patch = comp.patch.unwrap_or(0);
+    let mut version = match comp.op {
+        semver::Op::Exact
+        | semver::Op::GreaterEq
+        | semver::Op::Tilde
+        | semver::Op::Caret
+        | semver::Op::Wildcard => Version::new(comp.major, minor, patch),
+        semver::Op::Greater => match (comp.minor, comp.patch) {
+            (Some(minor), Some(patch)) => Version::new(comp.major, minor, patch + 1),
+            (Some(minor), None) => Version::new(comp.major, minor + 1, 0),
+            _ => Version::new(comp.major + 1, 0, 0),
+        },
+        _ => return None,
+    };
+    if !matches!(comp.op, semver::Op::Greater) {
+        version.pre = comp.pre.clone();
+    }
+    Some(version)
+}
+
+fn parse_ranges(
+    raw: HashMap<String, Vec<String>>,
+    field: &str,
+) -> Result<HashMap<ReleaseChannel, Vec<VersionReq>>, AppError> {
+    let mut ranges = HashMap::new();
+    for (k, reqs) in raw {
+        let parsed = reqs
+            .into_iter()
+            .map(|s| {
+                VersionReq::parse(&s)
+                    .with_context(|| format!("invalid version req '{s}' in {field}: {k}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        ranges.insert(
+            k.parse()
+                .map_err(|e: String| anyhow::anyhow!(e))
+                .with_context(|| format!("invalid channel key in {field}: {k}"))?,
+            parsed,
+        );
+    }
+    Ok(ranges)
+}
+
+/// Maps a CID to a stable bucket in `0..100`, used for staged rollouts.
+///
+/// The bucket is derived from a SHA-1 digest of the CID so it stays the same across server
+/// restarts and instances, ensuring a client doesn't flip in and out of a rollout.
+pub fn rollout_bucket(cid: &str) -> u8 {
+    let digest = Sha1::digest(cid.as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    (u64::from_be_bytes(bytes) % 100) as u8
 }
 
 #[derive(Deserialize)]
 struct RawPolicy {
     #[serde(default)]
     required_ranges: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    mandatory_ranges: HashMap<String, Vec<String>>,
     #[serde(default = "default_compatible_protocol_range")]
     compatible_protocol_range: String,
     #[serde(default)]
     visibility: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    rollout: HashMap<String, u8>,
 }
 
 fn default_compatible_protocol_range() -> String {
     ">=0.0.0".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn policy_with_rollout(version: &Version, percentage: u8) -> Policy {
+        let policy = Policy::new("does-not-exist.toml").unwrap();
+        policy.rollout.write().insert(version.clone(), percentage);
+        policy
+    }
+
+    fn policy_from_toml(toml: &str) -> Policy {
+        let policy = Policy::new("does-not-exist.toml").unwrap();
+        policy.apply(toml::from_str(toml).unwrap()).unwrap();
+        policy
+    }
+
+    #[test]
+    fn mandatory_ranges_match_client_version() {
+        let policy = policy_from_toml(
+            r#"
+            [mandatory_ranges]
+            stable = ["=1.1.0", ">=1.0.0, <1.0.3"]
+            "#,
+        );
+
+        assert!(policy.is_mandatory(&ReleaseChannel::Stable, &Version::new(1, 1, 0)));
+        assert!(policy.is_mandatory(&ReleaseChannel::Stable, &Version::new(1, 0, 2)));
+        assert!(!policy.is_mandatory(&ReleaseChannel::Stable, &Version::new(1, 0, 3)));
+        assert!(!policy.is_mandatory(&ReleaseChannel::Stable, &Version::new(1, 1, 1)));
+    }
+
+    #[test]
+    fn mandatory_ranges_respect_visibility() {
+        let policy = policy_from_toml(
+            r#"
+            [mandatory_ranges]
+            beta = ["=1.2.0-beta.1"]
+            "#,
+        );
+        let version = Version::parse("1.2.0-beta.1").unwrap();
+
+        assert!(!policy.is_mandatory(&ReleaseChannel::Stable, &version));
+        assert!(policy.is_mandatory(&ReleaseChannel::Beta, &version));
+        assert!(policy.is_mandatory(&ReleaseChannel::Dev, &version));
+    }
+
+    #[test]
+    fn invalid_mandatory_range_is_rejected() {
+        let policy = Policy::new("does-not-exist.toml").unwrap();
+        let raw = toml::from_str(
+            r#"
+            [mandatory_ranges]
+            stable = ["not a range"]
+            "#,
+        )
+        .unwrap();
+
+        assert!(policy.apply(raw).is_err());
+    }
+
+    #[test]
+    fn min_compatible_protocol_uses_highest_lower_bound() {
+        let policy = policy_from_toml(r#"compatible_protocol_range = ">=0.1.0, ^1.1""#);
+        assert_eq!(
+            policy.min_compatible_protocol(),
+            Some(Version::new(1, 1, 0))
+        );
+
+        let policy = policy_from_toml(r#"compatible_protocol_range = ">1.0""#);
+        assert_eq!(
+            policy.min_compatible_protocol(),
+            Some(Version::new(1, 1, 0))
+        );
+
+        let policy = policy_from_toml(r#"compatible_protocol_range = "*""#);
+        assert_eq!(
+            policy.min_compatible_protocol(),
+            Some(Version::new(0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn rollout_bucket_is_deterministic() {
+        for cid in ["1000000", "1234567", "client1", ""] {
+            let bucket = rollout_bucket(cid);
+            assert!(bucket < 100);
+            assert_eq!(rollout_bucket(cid), bucket);
+        }
+    }
+
+    #[test]
+    fn rollout_bucket_spreads_clients() {
+        let buckets = (0..1000)
+            .map(|i| rollout_bucket(&(1_000_000 + i).to_string()))
+            .collect::<std::collections::HashSet<_>>();
+        assert!(buckets.len() > 90);
+    }
+
+    #[test]
+    fn raising_percentage_includes_more_clients() {
+        let version = Version::new(1, 2, 0);
+        let cids = (0..500)
+            .map(|i| (1_000_000 + i).to_string())
+            .collect::<Vec<_>>();
+
+        let mut previous: Vec<&String> = Vec::new();
+        for percentage in [0, 1, 10, 25, 50, 75, 99, 100] {
+            let policy = policy_with_rollout(&version, percentage);
+            let included = cids
+                .iter()
+                .filter(|cid| policy.is_rolled_out(&version, Some(cid)))
+                .collect::<Vec<_>>();
+
+            assert!(previous.iter().all(|cid| included.contains(cid)));
+            assert!(included.len() >= previous.len());
+            previous = included;
+        }
+
+        assert_eq!(previous.len(), cids.len());
+    }
+
+    #[test]
+    fn staged_versions_require_cid() {
+        let version = Version::new(1, 2, 0);
+        let policy = policy_with_rollout(&version, 99);
+
+        assert!(!policy.is_rolled_out(&version, None));
+        assert!(policy.is_rolled_out(&Version::new(1, 1, 0), None));
+
+        let policy = policy_with_rollout(&version, 100);
+        assert!(policy.is_rolled_out(&version, None));
+    }
+}
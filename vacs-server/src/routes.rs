@@ -1,3 +1,4 @@
+mod admin;
 mod auth;
 mod root;
 mod version;
@@ -31,6 +32,7 @@ where
     S: SessionStore + Send + Sync + 'static + Clone,
 {
     let app = Router::new()
+        .nest("/admin", admin::routes())
         .nest("/auth", auth::routes())
         .nest("/ws", ws::routes().merge(crate::ws::routes()))
         .nest("/version", version::routes())
@@ -49,7 +51,7 @@ where
             TraceLayer::new_for_http().make_span_with(move |req: &Request<_>| {
                 let path = req.uri().path();
                 match path {
-                    "/health" | "/favicon.ico" => Span::none(),
+                    "/health" | "/healthz" | "/readyz" | "/favicon.ico" => Span::none(),
                     _ => debug_span!(
                         "request",
                         method = %req.method(),
@@ -0,0 +1,109 @@
+use crate::state::AppState;
+use axum::Router;
+use axum::routing::{get, post, put};
+use std::sync::Arc;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/clients", get(get::clients))
+        .route("/disconnect/{cid}", post(post::disconnect))
+        .route("/bans/{cid}", put(put::ban).delete(delete::unban))
+}
+
+mod get {
+    use super::*;
+    use crate::auth::admin::AdminAuth;
+    use crate::http::ApiResult;
+    use axum::Json;
+    use axum::extract::State;
+    use std::time::UNIX_EPOCH;
+    use vacs_protocol::http::admin::ConnectedClient;
+
+    pub async fn clients(
+        _: AdminAuth,
+        State(state): State<Arc<AppState>>,
+    ) -> ApiResult<Vec<ConnectedClient>> {
+        tracing::debug!("Listing connected clients");
+        let clients = state
+            .list_client_sessions()
+            .await
+            .into_iter()
+            .map(|session| ConnectedClient {
+                id: session.client_info.id.clone(),
+                display_name: session.client_info.display_name.clone(),
+                frequency: session.client_info.frequency.clone(),
+                connected_at: session
+                    .connected_at()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            })
+            .collect();
+
+        Ok(Json(clients))
+    }
+}
+
+mod post {
+    use super::*;
+    use crate::auth::admin::AdminAuth;
+    use crate::http::StatusCodeResult;
+    use crate::http::error::AppError;
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use vacs_protocol::ws::DisconnectReason;
+
+    pub async fn disconnect(
+        _: AdminAuth,
+        Path(cid): Path<String>,
+        State(state): State<Arc<AppState>>,
+    ) -> StatusCodeResult {
+        tracing::info!(?cid, "Disconnecting client on admin request");
+        if !state
+            .unregister_client(&cid, Some(DisconnectReason::Kicked))
+            .await
+        {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
+
+mod put {
+    use super::*;
+    use crate::auth::admin::AdminAuth;
+    use crate::http::StatusCodeResult;
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+
+    pub async fn ban(
+        _: AdminAuth,
+        Path(cid): Path<String>,
+        State(state): State<Arc<AppState>>,
+    ) -> StatusCodeResult {
+        tracing::info!(?cid, "Banning client on admin request");
+        state.ban_client(&cid).await?;
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
+
+mod delete {
+    use super::*;
+    use crate::auth::admin::AdminAuth;
+    use crate::http::StatusCodeResult;
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+
+    pub async fn unban(
+        _: AdminAuth,
+        Path(cid): Path<String>,
+        State(state): State<Arc<AppState>>,
+    ) -> StatusCodeResult {
+        tracing::info!(?cid, "Unbanning client on admin request");
+        state.unban_client(&cid).await?;
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
@@ -10,6 +10,8 @@ pub fn routes() -> Router<Arc<AppState>> {
 pub fn untraced_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/health", get(get::health))
+        .route("/healthz", get(get::liveness))
+        .route("/readyz", get(get::readiness))
         .route("/favicon.ico", get(get::favicon))
 }
 
@@ -31,6 +33,21 @@ mod get {
         }
     }
 
+    pub async fn liveness() -> impl IntoResponse {
+        (StatusCode::OK, "OK")
+    }
+
+    pub async fn readiness(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+        match tokio::time::timeout(Duration::from_secs(3), state.readiness_check()).await {
+            Ok(Ok(_)) => (StatusCode::OK, "OK"),
+            Ok(Err(err)) => {
+                tracing::debug!(?err, "Readiness check failed");
+                (StatusCode::SERVICE_UNAVAILABLE, "Service Unavailable")
+            }
+            Err(_) => (StatusCode::SERVICE_UNAVAILABLE, "Service Unavailable"),
+        }
+    }
+
     pub async fn favicon() -> impl IntoResponse {
         StatusCode::NOT_FOUND
     }
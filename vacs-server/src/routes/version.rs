@@ -11,6 +11,7 @@ mod get {
     use crate::http::error::{AppError, ProblemDetails};
     use crate::http::{ApiMaybe, MaybeJsonOrProblem};
     use crate::metrics::VersionMetrics;
+    use crate::ratelimit::retry_after_secs;
     use crate::release::catalog::BundleType;
     use crate::state::AppState;
     use axum::extract::{Query, State};
@@ -28,6 +29,7 @@ mod get {
         arch: String,
         bundle_type: BundleType,
         channel: Option<ReleaseChannel>,
+        cid: Option<String>,
     }
 
     pub async fn update(
@@ -41,7 +43,7 @@ mod get {
                 ?until,
                 "Rate limit exceeded, rejecting version update check"
             );
-            return Err(AppError::TooManyRequests(until.as_secs()));
+            return Err(AppError::TooManyRequests(retry_after_secs(until)));
         }
 
         let client_ver = match Version::parse(&params.version) {
@@ -66,6 +68,8 @@ mod get {
                 &params.target,
                 &params.arch,
                 &params.bundle_type,
+                // Clients without a session send an empty CID
+                params.cid.as_deref().filter(|cid| !cid.is_empty()),
             )
             .await
         {
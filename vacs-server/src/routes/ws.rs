@@ -20,6 +20,7 @@ pub fn routes() -> Router<Arc<AppState>> {
 
 mod get {
     use super::*;
+    use crate::config::WS_AUTH_TOKEN_TTL;
     use vacs_protocol::http::ws::WebSocketToken;
 
     pub async fn token(
@@ -31,7 +32,10 @@ mod get {
         tracing::debug!(?user, "Generating websocket token");
         let token = state.generate_ws_auth_token(user.cid.as_str()).await?;
 
-        Ok(Json(WebSocketToken { token }))
+        Ok(Json(WebSocketToken {
+            token,
+            expires_in: Some(WS_AUTH_TOKEN_TTL.as_secs()),
+        }))
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::audit::AuditLog;
 use crate::config;
 use crate::config::AppConfig;
 use crate::ice::provider::IceConfigProvider;
@@ -9,30 +10,39 @@ use crate::store::{Store, StoreBackend};
 use crate::ws::ClientSession;
 use crate::ws::calls::CallStateManager;
 use anyhow::Context;
-use std::collections::{HashMap, HashSet};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, UNIX_EPOCH};
 use tokio::sync::{RwLock, broadcast, mpsc, watch};
 use tokio::task::JoinHandle;
 use tokio::time;
 use tracing::{Instrument, instrument};
 use uuid::Uuid;
-use vacs_protocol::ws::{ClientInfo, DisconnectReason, ErrorReason, SignalingMessage};
+use vacs_protocol::ws::{Capability, ClientInfo, DisconnectReason, ErrorReason, SignalingMessage};
 use vacs_vatsim::data_feed::DataFeed;
-use vacs_vatsim::slurper::SlurperClient;
+use vacs_vatsim::slurper::{ControllerLookup, SlurperClient};
 use vacs_vatsim::{ControllerInfo, FacilityType};
 
+/// Store channel bans are published on, see [`AppState::ban_client`].
+pub const BAN_CHANNEL: &str = "bans";
+
 pub struct AppState {
     pub config: AppConfig,
     pub updates: UpdateChecker,
     pub call_state: CallStateManager,
     pub ice_config_provider: Arc<dyn IceConfigProvider>,
+    pub audit: AuditLog,
     store: Store,
     /// Key: CID
     clients: RwLock<HashMap<String, ClientSession>>,
     broadcast_tx: broadcast::Sender<SignalingMessage>,
     slurper: SlurperClient,
     data_feed: Arc<dyn DataFeed>,
+    /// Set once the controller update task completed its first successful data feed update.
+    data_feed_ready: AtomicBool,
     rate_limiters: RateLimiters,
     shutdown_rx: watch::Receiver<()>,
 }
@@ -48,18 +58,21 @@ impl AppState {
         rate_limiters: RateLimiters,
         shutdown_rx: watch::Receiver<()>,
         ice_config_provider: Arc<dyn IceConfigProvider>,
+        audit: AuditLog,
     ) -> Self {
         let (broadcast_tx, _) = broadcast::channel(config::BROADCAST_CHANNEL_CAPACITY);
         Self {
             config,
             updates,
             ice_config_provider,
+            audit,
             store,
             clients: RwLock::new(HashMap::new()),
             call_state: CallStateManager::new(),
             broadcast_tx,
             slurper,
             data_feed,
+            data_feed_ready: AtomicBool::new(false),
             rate_limiters,
             shutdown_rx,
         }
@@ -75,6 +88,7 @@ impl AppState {
     pub async fn register_client(
         &self,
         client_info: ClientInfo,
+        capabilities: BTreeSet<Capability>,
         client_connection_guard: ClientConnectionGuard,
     ) -> anyhow::Result<(ClientSession, mpsc::Receiver<SignalingMessage>)> {
         tracing::trace!("Registering client");
@@ -85,8 +99,14 @@ impl AppState {
             anyhow::bail!("Client already exists");
         }
 
+        // Record presence before the client is listed, so the reaper never sees it without one.
+        if let Err(err) = self.touch_presence(&client_id).await {
+            tracing::warn!(?err, "Failed to record initial client presence");
+        }
+
         let (tx, rx) = mpsc::channel(config::CLIENT_CHANNEL_CAPACITY);
-        let client = ClientSession::new(client_info, tx, client_connection_guard);
+        let client = ClientSession::new(client_info, tx, client_connection_guard)
+            .with_capabilities(capabilities);
 
         self.clients
             .write()
@@ -115,18 +135,24 @@ impl AppState {
         &self,
         client_id: &str,
         disconnect_reason: Option<DisconnectReason>,
-    ) {
+    ) -> bool {
         tracing::trace!("Unregistering client");
 
         let Some(client) = self.clients.write().await.remove(client_id) else {
             tracing::debug!("Client not found in client list, skipping unregister");
-            return;
+            return false;
         };
 
         client.disconnect(disconnect_reason);
 
         self.call_state.cleanup_client_calls(client_id);
 
+        if self.config.presence.ttl.is_some()
+            && let Err(err) = self.store.remove(presence_key(client_id).as_str()).await
+        {
+            tracing::warn!(?err, "Failed to remove client presence");
+        }
+
         if self.broadcast_tx.receiver_count() > 1 {
             tracing::trace!("Broadcasting client disconnected message");
             if let Err(err) = self
@@ -144,6 +170,7 @@ impl AppState {
         }
 
         tracing::debug!("Client unregistered");
+        true
     }
 
     pub async fn list_clients(&self) -> Vec<ClientInfo> {
@@ -159,6 +186,14 @@ impl AppState {
         clients
     }
 
+    pub async fn list_client_sessions(&self) -> Vec<ClientSession> {
+        let mut sessions: Vec<ClientSession> =
+            self.clients.read().await.values().cloned().collect();
+
+        sessions.sort_by(|a, b| a.id().cmp(b.id()));
+        sessions
+    }
+
     pub async fn list_clients_without_self(&self, self_client_id: &str) -> Vec<ClientInfo> {
         self.list_clients()
             .await
@@ -213,6 +248,21 @@ impl AppState {
         }
     }
 
+    /// Updates the last-seen timestamp of the given client in the store.
+    ///
+    /// Does nothing if presence tracking is disabled.
+    pub async fn touch_presence(&self, client_id: &str) -> anyhow::Result<()> {
+        let Some(ttl) = self.config.presence.ttl else {
+            return Ok(());
+        };
+
+        tracing::trace!(?client_id, "Updating client presence");
+        self.store
+            .set(presence_key(client_id).as_str(), unix_millis(), Some(ttl))
+            .await
+            .context("Failed to store client presence")
+    }
+
     #[instrument(level = "debug", skip(self), err)]
     pub async fn generate_ws_auth_token(&self, cid: &str) -> anyhow::Result<String> {
         tracing::debug!("Generating web socket auth token");
@@ -224,7 +274,7 @@ impl AppState {
             .set(
                 format!("ws.token.{token}").as_str(),
                 cid,
-                Some(Duration::from_secs(30)),
+                Some(config::WS_AUTH_TOKEN_TTL),
             )
             .await
             .context("Failed to store web socket auth token")?;
@@ -247,13 +297,112 @@ impl AppState {
         }
     }
 
+    /// Adds all CIDs from the configured ban list to the store.
     #[instrument(level = "debug", skip(self), err)]
-    pub async fn get_vatsim_controller_info(
-        &self,
-        cid: &str,
-    ) -> anyhow::Result<Option<ControllerInfo>> {
+    pub async fn load_configured_bans(&self) -> anyhow::Result<()> {
+        for cid in &self.config.auth.banned_cids {
+            self.store
+                .set(format!("ban.{cid}").as_str(), true, None)
+                .await
+                .context("Failed to store configured ban")?;
+        }
+
+        tracing::debug!(
+            count = self.config.auth.banned_cids.len(),
+            "Loaded configured bans"
+        );
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn is_banned(&self, cid: &str) -> anyhow::Result<bool> {
+        let banned: Option<bool> = self
+            .store
+            .get(format!("ban.{cid}").as_str())
+            .await
+            .context("Failed to retrieve ban")?;
+        Ok(banned.unwrap_or(false))
+    }
+
+    /// Bans the given CID and disconnects the client.
+    ///
+    /// Bans are kept in the store and are thus shared between instances. The ban is also
+    /// published on [`BAN_CHANNEL`], so instances running [`AppState::start_ban_listener_task`]
+    /// disconnect the client right away if it is connected to them.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn ban_client(&self, cid: &str) -> anyhow::Result<()> {
+        tracing::debug!("Banning client");
+        self.store
+            .set(format!("ban.{cid}").as_str(), true, None)
+            .await
+            .context("Failed to store ban")?;
+
+        if let Err(err) = self.store.publish(BAN_CHANNEL, cid).await {
+            tracing::warn!(
+                ?err,
+                "Failed to publish ban, client is only rejected by other instances on its next login"
+            );
+        }
+
+        self.unregister_client(cid, Some(DisconnectReason::Banned))
+            .await;
+        Ok(())
+    }
+
+    /// Subscribes to bans published by any instance and disconnects banned clients connected
+    /// to this instance.
+    #[instrument(level = "debug", skip(state), err)]
+    pub async fn start_ban_listener_task(state: Arc<AppState>) -> anyhow::Result<JoinHandle<()>> {
+        let mut ban_rx = state
+            .store
+            .subscribe(BAN_CHANNEL)
+            .await
+            .context("Failed to subscribe to bans")?;
+
+        Ok(tokio::spawn(
+            async move {
+                let mut shutdown = state.shutdown_rx.clone();
+                loop {
+                    tokio::select! {
+                        biased;
+                        _ = shutdown.changed() => {
+                            tracing::info!("Shutting down ban listener task");
+                            break;
+                        }
+                        cid = ban_rx.recv() => match cid {
+                            Ok(cid) => {
+                                if state.unregister_client(&cid, Some(DisconnectReason::Banned)).await {
+                                    tracing::info!(?cid, "Disconnected client banned by another instance");
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                tracing::warn!(?skipped, "Ban listener lagged, skipped bans");
+                            }
+                            Err(broadcast::error::RecvError::Closed) => {
+                                tracing::warn!("Ban subscription closed");
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            .in_current_span(),
+        ))
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn unban_client(&self, cid: &str) -> anyhow::Result<()> {
+        tracing::debug!("Unbanning client");
+        self.store
+            .remove(format!("ban.{cid}").as_str())
+            .await
+            .context("Failed to remove ban")
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn lookup_vatsim_controller(&self, cid: &str) -> anyhow::Result<ControllerLookup> {
         tracing::debug!("Retrieving connection info from VATSIM slurper");
-        self.slurper.get_controller_info(cid).await
+        self.slurper.lookup_controller(cid).await
     }
 
     #[instrument(level = "debug", skip(self), err)]
@@ -266,11 +415,12 @@ impl AppState {
     pub fn start_controller_update_task(
         state: Arc<AppState>,
         interval: Duration,
+        jitter: f64,
     ) -> JoinHandle<()> {
         tokio::spawn(
             async move {
-                let mut ticker = time::interval(interval);
-                ticker.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+                let mut intervals = JitteredInterval::new(interval, jitter);
+                let mut next_update = time::Instant::now();
 
                 let mut shutdown = state.shutdown_rx.clone();
                 let mut pending_disconnect = HashSet::new();
@@ -281,15 +431,26 @@ impl AppState {
                             tracing::info!("Shutting down controller update task");
                             break;
                         }
-                        _ = ticker.tick() => {
-                            if state.clients.read().await.is_empty() {
+                        _ = time::sleep_until(next_update) => {
+                            next_update = time::Instant::now() + intervals.next();
+
+                            // Until the first successful update, keep polling the data feed regardless
+                            // of connected clients, as readiness depends on it.
+                            if state.clients.read().await.is_empty() && state.data_feed_ready.load(Ordering::Relaxed) {
                                 tracing::trace!("No clients connected, skipping controller update");
                                 continue;
                             }
 
                             tracing::debug!("Updating controller info");
-                            if let Err(err) = Self::update_vatsim_controllers(&state, &mut pending_disconnect).await {
-                                tracing::warn!(?err, "Failed to update controller info");
+                            match Self::update_vatsim_controllers(&state, &mut pending_disconnect).await {
+                                Ok(()) => {
+                                    if !state.data_feed_ready.swap(true, Ordering::Relaxed) {
+                                        tracing::info!("Initial controller update completed");
+                                    }
+                                }
+                                Err(err) => {
+                                    tracing::warn!(?err, "Failed to update controller info");
+                                }
                             }
                         }
                     }
@@ -299,6 +460,65 @@ impl AppState {
         )
     }
 
+    #[instrument(level = "debug", skip(state))]
+    pub fn start_presence_reaper_task(
+        state: Arc<AppState>,
+        ttl: Duration,
+        interval: Duration,
+    ) -> JoinHandle<()> {
+        tokio::spawn(
+            async move {
+                let mut ticker = time::interval(interval);
+                ticker.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+                let mut shutdown = state.shutdown_rx.clone();
+                loop {
+                    tokio::select! {
+                        biased;
+                        _ = shutdown.changed() => {
+                            tracing::info!("Shutting down presence reaper task");
+                            break;
+                        }
+                        _ = ticker.tick() => {
+                            Self::reap_stale_clients(&state, ttl).await;
+                        }
+                    }
+                }
+            }
+            .in_current_span(),
+        )
+    }
+
+    /// Unregisters all clients whose last-seen timestamp is older than `ttl` or has expired,
+    /// broadcasting their disconnect.
+    async fn reap_stale_clients(state: &Arc<AppState>, ttl: Duration) {
+        let client_ids: Vec<String> = state.clients.read().await.keys().cloned().collect();
+        let now = unix_millis();
+
+        for cid in client_ids {
+            let last_seen: Option<u64> = match state.store.get(presence_key(&cid).as_str()).await {
+                Ok(last_seen) => last_seen,
+                Err(err) => {
+                    tracing::warn!(?cid, ?err, "Failed to retrieve client presence, skipping");
+                    continue;
+                }
+            };
+
+            if last_seen.is_some_and(|last_seen| {
+                u128::from(now.saturating_sub(last_seen)) <= ttl.as_millis()
+            }) {
+                continue;
+            }
+
+            tracing::debug!(
+                ?cid,
+                ?last_seen,
+                "Client presence expired, removing stale client"
+            );
+            state.unregister_client(&cid, None).await;
+        }
+    }
+
     async fn update_vatsim_controllers(
         state: &Arc<AppState>,
         pending_disconnect: &mut HashSet<String>,
@@ -380,6 +600,7 @@ impl AppState {
                             updates.push(SignalingMessage::ClientInfo {
                                 own: false,
                                 info: session.client_info.clone(),
+                                capabilities: None,
                             });
                         } else {
                             tracing::trace!(
@@ -423,7 +644,90 @@ impl AppState {
         self.store.is_healthy().await
     }
 
+    /// Checks whether the server is ready to accept clients: the store must be reachable and,
+    /// if an active VATSIM connection is required, the data feed must have been loaded at least
+    /// once.
+    pub async fn readiness_check(&self) -> anyhow::Result<()> {
+        if self.config.vatsim.require_active_connection
+            && !self.data_feed_ready.load(Ordering::Relaxed)
+        {
+            anyhow::bail!("VATSIM data feed not loaded yet");
+        }
+        self.store.is_healthy().await
+    }
+
     pub fn rate_limiters(&self) -> &RateLimiters {
         &self.rate_limiters
     }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+}
+
+/// Interval randomly lengthened or shortened by up to `jitter` times the base interval, so tasks
+/// started at the same time do not stay aligned.
+struct JitteredInterval {
+    base: Duration,
+    jitter: f64,
+    rng: StdRng,
+}
+
+impl JitteredInterval {
+    fn new(base: Duration, jitter: f64) -> Self {
+        Self {
+            base,
+            jitter: jitter.clamp(0.0, 1.0),
+            rng: StdRng::from_os_rng(),
+        }
+    }
+
+    fn next(&mut self) -> Duration {
+        if self.jitter == 0.0 {
+            return self.base;
+        }
+        let factor = self.rng.random_range(1.0 - self.jitter..=1.0 + self.jitter);
+        self.base.mul_f64(factor)
+    }
+}
+
+fn presence_key(client_id: &str) -> String {
+    format!("presence.{client_id}")
+}
+
+fn unix_millis() -> u64 {
+    UNIX_EPOCH.elapsed().unwrap_or_default().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jittered_interval_varies_within_bounds() {
+        let base = Duration::from_secs(30);
+        let mut intervals = JitteredInterval::new(base, 0.2);
+
+        let samples: Vec<Duration> = (0..100).map(|_| intervals.next()).collect();
+        for interval in &samples {
+            assert!(
+                (Duration::from_secs(24)..=Duration::from_secs(36)).contains(interval),
+                "interval {interval:?} outside of jitter bounds"
+            );
+        }
+        assert!(
+            samples.iter().any(|interval| *interval != samples[0]),
+            "intervals should vary"
+        );
+    }
+
+    #[test]
+    fn jittered_interval_without_jitter_is_constant() {
+        let base = Duration::from_secs(30);
+        let mut intervals = JitteredInterval::new(base, 0.0);
+
+        for _ in 0..10 {
+            assert_eq!(intervals.next(), base);
+        }
+    }
 }
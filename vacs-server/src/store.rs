@@ -6,6 +6,10 @@ use crate::store::redis::RedisStore;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Capacity of the channels delivering published messages to subscribers.
+pub const SUBSCRIPTION_CHANNEL_SIZE: usize = 64;
 
 #[async_trait::async_trait]
 pub trait StoreBackend {
@@ -17,6 +21,12 @@ pub trait StoreBackend {
         expiry: Option<Duration>,
     ) -> anyhow::Result<()>;
     async fn remove(&self, key: &str) -> anyhow::Result<()>;
+    /// Publishes a message to all subscribers of the channel, including those of other instances
+    /// sharing the store.
+    async fn publish(&self, channel: &str, message: &str) -> anyhow::Result<()>;
+    /// Subscribes to messages published to the channel. The subscription ends once the returned
+    /// receiver is dropped.
+    async fn subscribe(&self, channel: &str) -> anyhow::Result<broadcast::Receiver<String>>;
     async fn is_healthy(&self) -> anyhow::Result<()>;
 }
 
@@ -53,6 +63,20 @@ impl StoreBackend for Store {
         }
     }
 
+    async fn publish(&self, channel: &str, message: &str) -> anyhow::Result<()> {
+        match self {
+            Store::Redis(store) => store.publish(channel, message).await,
+            Store::Memory(store) => store.publish(channel, message).await,
+        }
+    }
+
+    async fn subscribe(&self, channel: &str) -> anyhow::Result<broadcast::Receiver<String>> {
+        match self {
+            Store::Redis(store) => store.subscribe(channel).await,
+            Store::Memory(store) => store.subscribe(channel).await,
+        }
+    }
+
     async fn is_healthy(&self) -> anyhow::Result<()> {
         match self {
             Store::Redis(store) => store.is_healthy().await,
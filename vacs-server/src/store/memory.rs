@@ -1,4 +1,4 @@
-use crate::store::StoreBackend;
+use crate::store::{SUBSCRIPTION_CHANNEL_SIZE, StoreBackend};
 use anyhow::Context;
 use bytes::Bytes;
 use dashmap::DashMap;
@@ -6,6 +6,7 @@ use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tracing::instrument;
 
 #[derive(Debug)]
@@ -14,13 +15,29 @@ struct StoredValue {
     expires_at: Option<Instant>,
 }
 
-#[derive(Debug)]
+impl StoredValue {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| now > expires_at)
+    }
+}
+
+/// In-process store backed by a [`DashMap`].
+///
+/// All values live in the memory of the running server, so they are neither persisted across
+/// restarts nor shared between instances. This backend is only suitable for single-instance
+/// deployments (and tests) and does not support horizontal scaling; use the Redis store for that.
+/// Published messages are likewise only delivered to subscribers of the same instance.
+#[derive(Debug, Default)]
 pub struct MemoryStore {
     map: DashMap<String, StoredValue>,
+    channels: DashMap<String, broadcast::Sender<String>>,
 }
 
-impl Default for MemoryStore {
-    fn default() -> Self {
+impl MemoryStore {
+    /// Creates a memory store pre-populated with the websocket tokens `token0` to `token5`,
+    /// mapping to the client IDs `client0` to `client5` respectively.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn with_test_tokens() -> Self {
         let map = DashMap::new();
         for i in 0..=5 {
             map.insert(
@@ -32,7 +49,16 @@ impl Default for MemoryStore {
             );
         }
 
-        Self { map }
+        Self {
+            map,
+            ..Default::default()
+        }
+    }
+
+    fn purge_expired(&self) {
+        let now = Instant::now();
+        self.map
+            .retain(|_, stored_value| !stored_value.is_expired(now));
     }
 }
 
@@ -42,10 +68,9 @@ impl StoreBackend for MemoryStore {
     async fn get<V: DeserializeOwned + Send>(&self, key: &str) -> anyhow::Result<Option<V>> {
         tracing::trace!("Getting value from memory store");
         if let Some(stored_value) = self.map.get(key) {
-            if let Some(expires_at) = stored_value.expires_at
-                && Instant::now() > expires_at
-            {
+            if stored_value.is_expired(Instant::now()) {
                 tracing::trace!("Value expired, removing from memory store and returning None");
+                drop(stored_value);
                 self.map.remove(key);
                 return Ok(None);
             }
@@ -73,6 +98,7 @@ impl StoreBackend for MemoryStore {
         let serialized = serde_json::to_vec(&value).context("Failed to serialize value")?;
 
         tracing::trace!("Storing value in memory store");
+        self.purge_expired();
         self.map.insert(
             key.to_string(),
             StoredValue {
@@ -94,7 +120,142 @@ impl StoreBackend for MemoryStore {
         Ok(())
     }
 
+    #[instrument(level = "trace", skip(self, message), err)]
+    async fn publish(&self, channel: &str, message: &str) -> anyhow::Result<()> {
+        tracing::trace!("Publishing message to memory store");
+        let receivers = self
+            .channels
+            .get(channel)
+            .and_then(|tx| tx.send(message.to_string()).ok())
+            .unwrap_or_default();
+
+        tracing::trace!(?receivers, "Successfully published message to memory store");
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self), err)]
+    async fn subscribe(&self, channel: &str) -> anyhow::Result<broadcast::Receiver<String>> {
+        tracing::trace!("Subscribing to memory store channel");
+        Ok(self
+            .channels
+            .entry(channel.to_string())
+            .or_insert_with(|| broadcast::channel(SUBSCRIPTION_CHANNEL_SIZE).0)
+            .subscribe())
+    }
+
     async fn is_healthy(&self) -> anyhow::Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_log::test;
+
+    #[test(tokio::test)]
+    async fn new_store_is_empty() {
+        let store = MemoryStore::default();
+        let value: Option<String> = store.get("ws.token.token0").await.unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[test(tokio::test)]
+    async fn set_get_remove() {
+        let store = MemoryStore::default();
+        store
+            .set("ws.token.abc", "client1".to_string(), None)
+            .await
+            .unwrap();
+
+        let value: Option<String> = store.get("ws.token.abc").await.unwrap();
+        assert_eq!(value, Some("client1".to_string()));
+
+        store.remove("ws.token.abc").await.unwrap();
+        let value: Option<String> = store.get("ws.token.abc").await.unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[test(tokio::test)]
+    async fn set_overwrites_existing_value() {
+        let store = MemoryStore::default();
+        store.set("key", 1u32, None).await.unwrap();
+        store.set("key", 2u32, None).await.unwrap();
+
+        let value: Option<u32> = store.get("key").await.unwrap();
+        assert_eq!(value, Some(2));
+    }
+
+    #[test(tokio::test)]
+    async fn remove_missing_key() {
+        let store = MemoryStore::default();
+        assert!(store.remove("missing").await.is_ok());
+    }
+
+    #[test(tokio::test)]
+    async fn expired_value_is_not_returned() {
+        let store = MemoryStore::default();
+        store
+            .set("key", "value", Some(Duration::from_millis(10)))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let value: Option<String> = store.get("key").await.unwrap();
+        assert_eq!(value, None);
+        assert!(store.map.is_empty());
+    }
+
+    #[test(tokio::test)]
+    async fn set_purges_expired_values() {
+        let store = MemoryStore::default();
+        store
+            .set("expiring", "value", Some(Duration::from_millis(10)))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        store.set("other", "value", None).await.unwrap();
+
+        assert!(!store.map.contains_key("expiring"));
+        assert!(store.map.contains_key("other"));
+    }
+
+    #[test(tokio::test)]
+    async fn deserialize_mismatch_errors() {
+        let store = MemoryStore::default();
+        store.set("key", "not a number", None).await.unwrap();
+
+        let result: anyhow::Result<Option<u32>> = store.get("key").await;
+        assert!(result.is_err());
+    }
+
+    #[test(tokio::test)]
+    async fn test_tokens_resolve_to_clients() {
+        let store = MemoryStore::with_test_tokens();
+        for i in 0..=5 {
+            let value: Option<String> = store.get(&format!("ws.token.token{i}")).await.unwrap();
+            assert_eq!(value, Some(format!("client{i}")));
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn publish_reaches_subscribers_of_channel() {
+        let store = MemoryStore::default();
+        assert!(store.publish("bans", "client0").await.is_ok());
+
+        let mut rx1 = store.subscribe("bans").await.unwrap();
+        let mut rx2 = store.subscribe("bans").await.unwrap();
+        let mut other_rx = store.subscribe("other").await.unwrap();
+
+        store.publish("bans", "client1").await.unwrap();
+        assert_eq!(rx1.recv().await.unwrap(), "client1");
+        assert_eq!(rx2.recv().await.unwrap(), "client1");
+        assert!(other_rx.try_recv().is_err());
+    }
+
+    #[test(tokio::test)]
+    async fn is_healthy() {
+        assert!(MemoryStore::default().is_healthy().await.is_ok());
+    }
+}
@@ -1,20 +1,23 @@
 use crate::config::RedisConfig;
-use crate::store::StoreBackend;
+use crate::store::{SUBSCRIPTION_CHANNEL_SIZE, StoreBackend};
 use anyhow::Context;
 use bytes::Bytes;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 use std::time::Duration;
-use tower_sessions_redis_store::fred::interfaces::ClientLike;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tower_sessions_redis_store::fred::interfaces::{ClientLike, EventInterface, PubsubInterface};
 use tower_sessions_redis_store::fred::prelude::Expiration::EX;
-use tower_sessions_redis_store::fred::prelude::{Config, KeysInterface, Pool};
+use tower_sessions_redis_store::fred::prelude::{Client, Config, KeysInterface, Pool};
 use tower_sessions_redis_store::fred::types::Builder;
-use tracing::instrument;
+use tracing::{Instrument, instrument};
 
 #[derive(Debug)]
 pub struct RedisStore {
     pool: Pool,
+    config: Config,
 }
 
 impl RedisStore {
@@ -23,7 +26,7 @@ impl RedisStore {
         tracing::trace!("Creating Redis pool");
         let pool_config = Config::from_url_centralized(&redis_config.addr)
             .context("Failed to create redis pool config")?;
-        let pool = Builder::from_config(pool_config)
+        let pool = Builder::from_config(pool_config.clone())
             .with_performance_config(|config| {
                 config.default_command_timeout = Duration::from_secs(2);
             })
@@ -37,12 +40,52 @@ impl RedisStore {
             .context("Failed to connect to redis")?;
 
         tracing::info!("Redis connection pool created");
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            config: pool_config,
+        })
     }
 
     pub fn get_pool(&self) -> &Pool {
         &self.pool
     }
+
+    /// Forwards messages received by the subscribed client until all receivers are dropped,
+    /// subscribing to the channel again after the client reconnected.
+    async fn forward_messages(client: Client, channel: String, tx: broadcast::Sender<String>) {
+        let mut message_rx = client.message_rx();
+        let mut reconnect_rx = client.reconnect_rx();
+        loop {
+            tokio::select! {
+                message = message_rx.recv() => match message {
+                    Ok(message) => {
+                        let Some(value) = message.value.as_string() else {
+                            tracing::warn!(?message, "Ignoring non-string message");
+                            continue;
+                        };
+                        if tx.send(value).is_err() {
+                            tracing::trace!("All subscribers dropped, ending subscription");
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(?skipped, "Subscription lagged, skipped messages");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                Ok(_) = reconnect_rx.recv() => {
+                    tracing::debug!("Redis subscriber reconnected, subscribing again");
+                    if let Err(err) = client.subscribe(channel.as_str()).await {
+                        tracing::warn!(?err, "Failed to subscribe to redis channel again");
+                    }
+                }
+            }
+        }
+
+        if let Err(err) = client.quit().await {
+            tracing::debug!(?err, "Failed to close redis subscriber");
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -110,6 +153,43 @@ impl StoreBackend for RedisStore {
         Ok(())
     }
 
+    #[instrument(level = "trace", skip(self, message), err)]
+    async fn publish(&self, channel: &str, message: &str) -> anyhow::Result<()> {
+        tracing::trace!("Publishing message to redis");
+        let receivers = self
+            .pool
+            .next()
+            .publish::<i64, _, _>(channel, message)
+            .await
+            .context("Failed to publish message to redis")?;
+
+        tracing::trace!(?receivers, "Successfully published message to redis");
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self), err)]
+    async fn subscribe(&self, channel: &str) -> anyhow::Result<broadcast::Receiver<String>> {
+        // Subscribed connections cannot run other commands, so each subscription gets its own
+        tracing::trace!("Connecting redis subscriber");
+        let client = Builder::from_config(self.config.clone())
+            .build()
+            .context("Failed to create redis subscriber")?;
+        client
+            .init()
+            .await
+            .context("Failed to connect redis subscriber")?;
+        client
+            .subscribe(channel)
+            .await
+            .context("Failed to subscribe to redis channel")?;
+
+        let (tx, rx) = broadcast::channel(SUBSCRIPTION_CHANNEL_SIZE);
+        tokio::spawn(Self::forward_messages(client, channel.to_string(), tx).in_current_span());
+
+        tracing::trace!("Successfully subscribed to redis channel");
+        Ok(rx)
+    }
+
     async fn is_healthy(&self) -> anyhow::Result<()> {
         self.pool.ping(None).await.context("Failed to ping redis")
     }
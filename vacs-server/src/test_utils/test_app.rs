@@ -1,5 +1,6 @@
+use crate::audit::AuditLog;
 use crate::auth::layer::setup_mock_auth_layer;
-use crate::config::{AppConfig, AuthConfig, VatsimConfig};
+use crate::config::{AdminConfig, AppConfig, AuthConfig, CallsConfig, VatsimConfig};
 use crate::ice::provider::stun::StunOnlyProvider;
 use crate::ratelimit::RateLimiters;
 use crate::release::UpdateChecker;
@@ -9,49 +10,119 @@ use crate::store::Store;
 use crate::store::memory::MemoryStore;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
+use vacs_vatsim::data_feed::DataFeed;
 use vacs_vatsim::data_feed::mock::MockDataFeed;
 use vacs_vatsim::slurper::SlurperClient;
 
+pub const TEST_ADMIN_TOKEN: &str = "admin-token";
+
 pub struct TestApp {
     state: Arc<AppState>,
     addr: String,
+    http_addr: String,
     shutdown_tx: watch::Sender<()>,
     handle: JoinHandle<()>,
 }
 
 impl TestApp {
     pub async fn new() -> Self {
-        let config = AppConfig {
+        Self::new_with_rate_limiters(RateLimiters::default()).await
+    }
+
+    pub async fn new_with_rate_limiters(rate_limiters: RateLimiters) -> Self {
+        Self::start(rate_limiters, None, |_| {}).await
+    }
+
+    /// Starts a test app requiring an active VATSIM connection, with the controller update task
+    /// polling the given data feed.
+    pub async fn new_with_data_feed(data_feed: Arc<dyn DataFeed>) -> Self {
+        Self::start(RateLimiters::default(), Some(data_feed), |_| {}).await
+    }
+
+    /// Starts a test app ending established calls after the given duration.
+    pub async fn new_with_max_call_duration(max_duration: Duration) -> Self {
+        Self::start(RateLimiters::default(), None, |config| {
+            config.calls.max_duration = Some(max_duration);
+        })
+        .await
+    }
+
+    /// Starts a test app with the given call configuration, e.g. to restrict calls to a policy.
+    pub async fn new_with_calls_config(calls: CallsConfig) -> Self {
+        Self::start(RateLimiters::default(), None, |config| config.calls = calls).await
+    }
+
+    /// Starts a test app tracking client presence with the given TTL, with the presence reaper
+    /// task checking for stale clients every 50ms.
+    pub async fn new_with_presence_ttl(ttl: Duration) -> Self {
+        Self::start(RateLimiters::default(), None, |config| {
+            config.presence.ttl = Some(ttl);
+            config.presence.reap_interval = Duration::from_millis(50);
+        })
+        .await
+    }
+
+    async fn start(
+        rate_limiters: RateLimiters,
+        data_feed: Option<Arc<dyn DataFeed>>,
+        configure: impl FnOnce(&mut AppConfig),
+    ) -> Self {
+        let require_active_connection = data_feed.is_some();
+        let mut config = AppConfig {
             auth: AuthConfig {
                 login_flow_timeout_millis: 100,
                 ..Default::default()
             },
+            admin: AdminConfig {
+                token: TEST_ADMIN_TOKEN.to_string(),
+            },
             vatsim: VatsimConfig {
                 user_service: Default::default(),
-                require_active_connection: false,
+                require_active_connection,
                 slurper_base_url: Default::default(),
-                controller_update_interval: Default::default(),
+                controller_update_interval: Duration::from_millis(50),
+                controller_update_jitter: 0.0,
                 data_feed_url: Default::default(),
+                ..Default::default()
             },
             ..Default::default()
         };
+        configure(&mut config);
 
-        let mock_data_feed = MockDataFeed::default();
+        let data_feed = data_feed.unwrap_or_else(|| Arc::new(MockDataFeed::default()));
 
         let (shutdown_tx, shutdown_rx) = watch::channel(());
         let state = Arc::new(AppState::new(
             config.clone(),
             UpdateChecker::default(),
-            Store::Memory(MemoryStore::default()),
+            Store::Memory(MemoryStore::with_test_tokens()),
             SlurperClient::new("http://localhost:12345").unwrap(),
-            Arc::new(mock_data_feed),
-            RateLimiters::default(),
+            data_feed,
+            rate_limiters,
             shutdown_rx,
             Arc::new(StunOnlyProvider::default()),
+            AuditLog::disabled(),
         ));
 
+        if require_active_connection {
+            AppState::start_controller_update_task(
+                state.clone(),
+                config.vatsim.controller_update_interval,
+                config.vatsim.controller_update_jitter,
+            );
+        }
+
+        AppState::start_ban_listener_task(state.clone())
+            .await
+            .unwrap();
+
+        if let Some(ttl) = config.presence.ttl {
+            AppState::start_presence_reaper_task(state.clone(), ttl, config.presence.reap_interval);
+        }
+
         let auth_layer = setup_mock_auth_layer(&config).await.unwrap();
         let app = create_app(auth_layer, None, config.server.client_ip_source.clone());
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -71,6 +142,7 @@ impl TestApp {
         Self {
             state,
             addr: format!("ws://{addr}/ws"),
+            http_addr: format!("http://{addr}"),
             shutdown_tx,
             handle,
         }
@@ -80,6 +152,10 @@ impl TestApp {
         &self.addr
     }
 
+    pub fn http_addr(&self) -> &str {
+        &self.http_addr
+    }
+
     pub fn state(&self) -> Arc<AppState> {
         self.state.clone()
     }
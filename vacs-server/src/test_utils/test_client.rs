@@ -6,11 +6,12 @@ use tokio::net::TcpStream;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use vacs_protocol::VACS_PROTOCOL_VERSION;
-use vacs_protocol::ws::{ClientInfo, SignalingMessage};
+use vacs_protocol::ws::{Capability, ClientInfo, SignalingMessage};
 
 pub struct TestClient {
     id: String,
     token: String,
+    capabilities: Vec<Capability>,
     ws_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
 }
 
@@ -20,10 +21,18 @@ impl TestClient {
         Ok(Self {
             id: id.to_string(),
             token: token.to_string(),
+            capabilities: Capability::ALL.to_vec(),
             ws_stream,
         })
     }
 
+    /// Advertises the given capabilities on login instead of all capabilities, e.g. to emulate
+    /// older clients.
+    pub fn with_capabilities(mut self, capabilities: Vec<Capability>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
     pub async fn new_with_login<FI, FC>(
         ws_addr: &str,
         id: &str,
@@ -58,10 +67,11 @@ impl TestClient {
         let login_msg = SignalingMessage::Login {
             token: self.token.to_string(),
             protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+            capabilities: self.capabilities.clone(),
         };
         self.send_and_expect_with_timeout(login_msg, Duration::from_millis(100), |msg| match msg {
-            SignalingMessage::ClientInfo { own, info } => client_info_predicate(own, info),
-            SignalingMessage::LoginFailure { reason } => {
+            SignalingMessage::ClientInfo { own, info, .. } => client_info_predicate(own, info),
+            SignalingMessage::LoginFailure { reason, .. } => {
                 Err(anyhow::anyhow!("Login failed: {:?}", reason))
             }
             _ => Err(anyhow::anyhow!("Unexpected response: {:?}", msg)),
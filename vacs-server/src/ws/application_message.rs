@@ -1,28 +1,55 @@
-use crate::metrics::ErrorMetrics;
+use crate::audit::{CallAuditEvent, CallAuditRecord};
+use crate::config::{CALL_DURATION_WARNING_LEAD, CallPolicy};
 use crate::metrics::guards::CallAttemptOutcome;
+use crate::metrics::{CallMetrics, ErrorMetrics};
+use crate::ratelimit::retry_after_secs;
 use crate::state::AppState;
 use crate::ws::ClientSession;
+use crate::ws::calls::is_call_permitted;
 use crate::ws::message::send_message;
 use axum::extract::ws;
 use std::ops::ControlFlow;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
-use vacs_protocol::ws::{CallErrorReason, ErrorReason, SignalingMessage};
+use vacs_protocol::ws::{
+    CallEndReason, CallErrorReason, CallRejectReason, Capability, ErrorReason, SignalingMessage,
+    WireFormat,
+};
 
 pub async fn handle_application_message(
     state: &Arc<AppState>,
     client: &ClientSession,
     ws_outbound_tx: &mpsc::Sender<ws::Message>,
+    wire_format: WireFormat,
     message: SignalingMessage,
 ) -> ControlFlow<(), ()> {
     tracing::trace!(?message, "Handling application message");
 
+    // Call teardown and ICE must get through even when rate limited, so calls never get stuck
+    if !matches!(
+        message,
+        SignalingMessage::Logout
+            | SignalingMessage::CallEnd { .. }
+            | SignalingMessage::CallIceCandidate { .. }
+            | SignalingMessage::CallIceEndOfCandidates { .. }
+    ) && let Err(until) = state.rate_limiters().check_message(client.id())
+    {
+        tracing::debug!(?until, "Rate limit exceeded, rejecting message");
+        send_rate_limit_error(ws_outbound_tx, wire_format, until, message.peer_id()).await;
+        return ControlFlow::Continue(());
+    }
+
     match message {
         SignalingMessage::ListClients => {
             tracing::trace!("Returning list of clients");
             let clients = state.list_clients_without_self(client.id()).await;
-            if let Err(err) =
-                send_message(ws_outbound_tx, SignalingMessage::ClientList { clients }).await
+            if let Err(err) = send_message(
+                ws_outbound_tx,
+                wire_format,
+                SignalingMessage::ClientList { clients },
+            )
+            .await
             {
                 tracing::warn!(?err, "Failed to send client list");
             }
@@ -33,87 +60,105 @@ pub async fn handle_application_message(
             ControlFlow::Break(())
         }
         SignalingMessage::CallInvite { peer_id } => {
-            if check_self_message(ws_outbound_tx, client, peer_id.clone()).await {
+            if check_self_message(ws_outbound_tx, wire_format, client, peer_id.clone()).await {
                 return ControlFlow::Continue(());
             }
             if let Err(until) = state.rate_limiters().check_call_invite(client.id()) {
                 tracing::debug!(?until, "Rate limit exceeded, rejecting call invite");
-                let reason = ErrorReason::RateLimited {
-                    retry_after_secs: until.as_secs(),
-                };
-                ErrorMetrics::error(&reason);
-
-                if let Err(err) = send_message(
-                    ws_outbound_tx,
-                    SignalingMessage::Error {
-                        reason,
-                        peer_id: Some(peer_id.to_string()),
-                    },
-                )
-                .await
-                {
-                    tracing::warn!(?err, "Failed to send rate limit error message");
-                }
+                send_rate_limit_error(ws_outbound_tx, wire_format, until, Some(&peer_id)).await;
             } else {
                 handle_call_invite(state, client, &peer_id).await;
             }
             ControlFlow::Continue(())
         }
         SignalingMessage::CallAccept { peer_id } => {
-            if check_self_message(ws_outbound_tx, client, peer_id.clone()).await {
+            if check_self_message(ws_outbound_tx, wire_format, client, peer_id.clone()).await {
                 return ControlFlow::Continue(());
             }
             handle_call_accept(state, client, &peer_id).await;
             ControlFlow::Continue(())
         }
-        SignalingMessage::CallReject { peer_id } => {
-            if check_self_message(ws_outbound_tx, client, peer_id.clone()).await {
+        SignalingMessage::CallReject { peer_id, reason } => {
+            if check_self_message(ws_outbound_tx, wire_format, client, peer_id.clone()).await {
                 return ControlFlow::Continue(());
             }
-            handle_call_reject(state, client, &peer_id).await;
+            handle_call_reject(state, client, &peer_id, reason).await;
             ControlFlow::Continue(())
         }
         SignalingMessage::CallOffer { peer_id, sdp } => {
-            if check_self_message(ws_outbound_tx, client, peer_id.clone()).await {
+            if check_self_message(ws_outbound_tx, wire_format, client, peer_id.clone()).await {
                 return ControlFlow::Continue(());
             }
             handle_call_offer(state, client, &peer_id, &sdp).await;
             ControlFlow::Continue(())
         }
         SignalingMessage::CallAnswer { peer_id, sdp } => {
-            if check_self_message(ws_outbound_tx, client, peer_id.clone()).await {
+            if check_self_message(ws_outbound_tx, wire_format, client, peer_id.clone()).await {
                 return ControlFlow::Continue(());
             }
             handle_call_answer(state, client, &peer_id, &sdp).await;
             ControlFlow::Continue(())
         }
-        SignalingMessage::CallEnd { peer_id } => {
-            if check_self_message(ws_outbound_tx, client, peer_id.clone()).await {
+        SignalingMessage::CallEnd { peer_id, .. } => {
+            if check_self_message(ws_outbound_tx, wire_format, client, peer_id.clone()).await {
                 return ControlFlow::Continue(());
             }
             handle_call_end(state, client, &peer_id).await;
             ControlFlow::Continue(())
         }
         SignalingMessage::CallError { peer_id, reason } => {
-            if check_self_message(ws_outbound_tx, client, peer_id.clone()).await {
+            if check_self_message(ws_outbound_tx, wire_format, client, peer_id.clone()).await {
                 return ControlFlow::Continue(());
             }
             handle_call_error(state, client, &peer_id, reason).await;
             ControlFlow::Continue(())
         }
         SignalingMessage::CallIceCandidate { peer_id, candidate } => {
-            if check_self_message(ws_outbound_tx, client, peer_id.clone()).await {
+            if check_self_message(ws_outbound_tx, wire_format, client, peer_id.clone()).await {
                 return ControlFlow::Continue(());
             }
             handle_call_ice_candidate(state, client, &peer_id, &candidate).await;
             ControlFlow::Continue(())
         }
+        SignalingMessage::CallIceEndOfCandidates { peer_id } => {
+            if check_self_message(ws_outbound_tx, wire_format, client, peer_id.clone()).await {
+                return ControlFlow::Continue(());
+            }
+            handle_call_ice_end_of_candidates(state, client, &peer_id).await;
+            ControlFlow::Continue(())
+        }
         _ => ControlFlow::Continue(()),
     }
 }
 
+async fn send_rate_limit_error(
+    ws_outbound_tx: &mpsc::Sender<ws::Message>,
+    wire_format: WireFormat,
+    until: Duration,
+    peer_id: Option<&str>,
+) {
+    let reason = ErrorReason::RateLimited {
+        retry_after_secs: retry_after_secs(until),
+    };
+    ErrorMetrics::error(&reason);
+
+    if let Err(err) = send_message(
+        ws_outbound_tx,
+        wire_format,
+        SignalingMessage::Error {
+            reason,
+            peer_id: peer_id.map(str::to_string),
+        },
+    )
+    .await
+    {
+        tracing::warn!(?err, "Failed to send rate limit error message");
+    }
+}
+
 async fn check_self_message(
     ws_outbound_tx: &mpsc::Sender<ws::Message>,
+    wire_format: WireFormat,
     client: &ClientSession,
     peer_id: String,
 ) -> bool {
@@ -123,6 +168,7 @@ async fn check_self_message(
         ErrorMetrics::error(&reason);
         if let Err(err) = send_message(
             ws_outbound_tx,
+            wire_format,
             SignalingMessage::Error {
                 reason,
                 peer_id: Some(peer_id.to_string()),
@@ -137,9 +183,82 @@ async fn check_self_message(
     false
 }
 
+/// Must be called before the call state is updated, as a failed or ended call forgets its caller.
+fn audit_call_event(
+    state: &AppState,
+    event: CallAuditEvent,
+    client: &ClientSession,
+    peer_id: &str,
+    error: Option<&CallErrorReason>,
+) {
+    let caller = state.call_state.caller(client.id(), peer_id);
+    state.audit.record(CallAuditRecord::new(
+        event,
+        client.id(),
+        peer_id,
+        caller,
+        error,
+    ));
+}
+
 async fn handle_call_invite(state: &AppState, client: &ClientSession, peer_id: &str) {
     tracing::trace!(?peer_id, "Handling call invite");
     state.call_state.start_call_attempt(client.id(), peer_id);
+    audit_call_event(state, CallAuditEvent::Invite, client, peer_id, None);
+
+    // Callers not supporting busy signals have the invite forwarded, as they would fail to
+    // process the CallBusy message.
+    if client.has_capability(Capability::CallBusy) && state.call_state.is_busy(peer_id, client.id())
+    {
+        tracing::debug!(?peer_id, "Peer is busy, rejecting call invite");
+        state
+            .call_state
+            .complete_call_attempt(client.id(), peer_id, CallAttemptOutcome::Busy);
+        if let Err(err) = client
+            .send_message(SignalingMessage::CallBusy {
+                peer_id: peer_id.to_string(),
+            })
+            .await
+        {
+            tracing::warn!(?err, ?peer_id, "Failed to send call busy message");
+        }
+        return;
+    }
+
+    // Client info is updated in the client list, so the caller's own session might be stale.
+    if state.config.calls.policy != CallPolicy::Unrestricted
+        && let (Some(caller), Some(callee)) = (
+            state.get_client(client.id()).await,
+            state.get_client(peer_id).await,
+        )
+        && !is_call_permitted(
+            &state.config.calls,
+            caller.get_client_info(),
+            callee.get_client_info(),
+        )
+    {
+        tracing::debug!(
+            ?peer_id,
+            "Call not permitted by call policy, rejecting call invite"
+        );
+        state
+            .call_state
+            .complete_call_attempt(client.id(), peer_id, CallAttemptOutcome::Rejected);
+        // Callers not supporting the call policy would fail to process the reason.
+        let reason = client
+            .has_capability(Capability::CallPolicy)
+            .then_some(CallRejectReason::NotPermitted);
+        if let Err(err) = client
+            .send_message(SignalingMessage::CallReject {
+                peer_id: peer_id.to_string(),
+                reason,
+            })
+            .await
+        {
+            tracing::warn!(?err, ?peer_id, "Failed to send call reject message");
+        }
+        return;
+    }
 
     state
         .send_message_to_peer(
@@ -154,6 +273,7 @@ async fn handle_call_invite(state: &AppState, client: &ClientSession, peer_id: &
 
 async fn handle_call_accept(state: &AppState, client: &ClientSession, peer_id: &str) {
     tracing::trace!(?peer_id, "Handling call acceptance");
+    audit_call_event(state, CallAuditEvent::Accept, client, peer_id, None);
     state
         .call_state
         .complete_call_attempt(client.id(), peer_id, CallAttemptOutcome::Accepted);
@@ -169,8 +289,14 @@ async fn handle_call_accept(state: &AppState, client: &ClientSession, peer_id: &
         .await;
 }
 
-async fn handle_call_reject(state: &AppState, client: &ClientSession, peer_id: &str) {
+async fn handle_call_reject(
+    state: &AppState,
+    client: &ClientSession,
+    peer_id: &str,
+    reason: Option<CallRejectReason>,
+) {
     tracing::trace!(?peer_id, "Handling call rejection");
+    audit_call_event(state, CallAuditEvent::Reject, client, peer_id, None);
     state
         .call_state
         .complete_call_attempt(client.id(), peer_id, CallAttemptOutcome::Rejected);
@@ -181,6 +307,7 @@ async fn handle_call_reject(state: &AppState, client: &ClientSession, peer_id: &
             peer_id,
             SignalingMessage::CallReject {
                 peer_id: client.id().to_string(),
+                reason,
             },
         )
         .await;
@@ -200,9 +327,25 @@ async fn handle_call_offer(state: &AppState, client: &ClientSession, peer_id: &s
         .await;
 }
 
-async fn handle_call_answer(state: &AppState, client: &ClientSession, peer_id: &str, sdp: &str) {
+async fn handle_call_answer(
+    state: &Arc<AppState>,
+    client: &ClientSession,
+    peer_id: &str,
+    sdp: &str,
+) {
     tracing::trace!(?peer_id, "Handling call answer");
     state.call_state.start_call(client.id(), peer_id);
+    if let Some(max_duration) = state.config.calls.max_duration {
+        let task = tokio::spawn(enforce_max_call_duration(
+            state.clone(),
+            client.id().to_string(),
+            peer_id.to_string(),
+            max_duration,
+        ));
+        state
+            .call_state
+            .set_duration_limit(client.id(), peer_id, task);
+    }
 
     state
         .send_message_to_peer(
@@ -216,8 +359,73 @@ async fn handle_call_answer(state: &AppState, client: &ClientSession, peer_id: &
         .await;
 }
 
+/// Warns both peers shortly before the call reaches `max_duration` and ends it afterwards.
+///
+/// Aborted by the [`crate::ws::calls::CallStateManager`] if the call ends before.
+async fn enforce_max_call_duration(
+    state: Arc<AppState>,
+    peer1_id: String,
+    peer2_id: String,
+    max_duration: Duration,
+) {
+    let warning_lead = CALL_DURATION_WARNING_LEAD.min(max_duration);
+    tokio::time::sleep(max_duration - warning_lead).await;
+
+    tracing::debug!(
+        ?peer1_id,
+        ?peer2_id,
+        "Call approaching maximum duration, warning peers"
+    );
+    let remaining_secs = retry_after_secs(warning_lead);
+    for (id, other_id) in [(&peer1_id, &peer2_id), (&peer2_id, &peer1_id)] {
+        if let Some(peer) = state.get_client(id).await
+            && peer.has_capability(Capability::CallDurationWarning)
+            && let Err(err) = peer
+                .send_message(SignalingMessage::CallDurationWarning {
+                    peer_id: other_id.clone(),
+                    remaining_secs,
+                })
+                .await
+        {
+            tracing::warn!(peer_id = ?id, ?err, "Failed to send call duration warning");
+        }
+    }
+
+    tokio::time::sleep(warning_lead).await;
+
+    tracing::debug!(
+        ?peer1_id,
+        ?peer2_id,
+        "Call reached maximum duration, ending call"
+    );
+    let caller = state.call_state.caller(&peer1_id, &peer2_id);
+    state.audit.record(CallAuditRecord::new(
+        CallAuditEvent::End,
+        &peer1_id,
+        &peer2_id,
+        caller,
+        None,
+    ));
+    for (id, other_id) in [(&peer1_id, &peer2_id), (&peer2_id, &peer1_id)] {
+        if let Some(peer) = state.get_client(id).await
+            && let Err(err) = peer
+                .send_message(SignalingMessage::CallEnd {
+                    peer_id: other_id.clone(),
+                    reason: Some(CallEndReason::MaxDurationReached),
+                })
+                .await
+        {
+            tracing::warn!(peer_id = ?id, ?err, "Failed to send call end");
+        }
+    }
+
+    // Ending the call aborts this task, so this must be the last step.
+    state.call_state.end_call(peer1_id, peer2_id);
+}
+
 async fn handle_call_end(state: &AppState, client: &ClientSession, peer_id: &str) {
     tracing::trace!(?peer_id, "Handling call end");
+    audit_call_event(state, CallAuditEvent::End, client, peer_id, None);
     state
         .call_state
         .complete_call_attempt(client.id(), peer_id, CallAttemptOutcome::Cancelled);
@@ -229,6 +437,7 @@ async fn handle_call_end(state: &AppState, client: &ClientSession, peer_id: &str
             peer_id,
             SignalingMessage::CallEnd {
                 peer_id: client.id().to_string(),
+                reason: None,
             },
         )
         .await;
@@ -241,6 +450,8 @@ async fn handle_call_error(
     reason: CallErrorReason,
 ) {
     tracing::trace!(?peer_id, "Handling call error");
+    CallMetrics::failed(&reason);
+    audit_call_event(state, CallAuditEvent::Error, client, peer_id, Some(&reason));
     state.call_state.complete_call_attempt(
         client.id(),
         peer_id,
@@ -279,6 +490,32 @@ async fn handle_call_ice_candidate(
         .await;
 }
 
+async fn handle_call_ice_end_of_candidates(
+    state: &AppState,
+    client: &ClientSession,
+    peer_id: &str,
+) {
+    tracing::trace!(?peer_id, "Handling call ICE end of candidates");
+    if let Some(peer) = state.get_client(peer_id).await
+        && !peer.has_capability(Capability::IceEndOfCandidates)
+    {
+        tracing::trace!(
+            ?peer_id,
+            "Peer does not support ICE end of candidates, not forwarding"
+        );
+        return;
+    }
+    state
+        .send_message_to_peer(
+            client,
+            peer_id,
+            SignalingMessage::CallIceEndOfCandidates {
+                peer_id: client.id().to_string(),
+            },
+        )
+        .await;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,6 +536,7 @@ mod tests {
             &setup.app_state,
             &setup.session,
             setup.websocket_tx.lock().await.deref(),
+            WireFormat::Json,
             SignalingMessage::ListClients,
         )
         .await;
@@ -326,6 +564,7 @@ mod tests {
             &setup.app_state,
             &setup.session,
             setup.websocket_tx.lock().await.deref(),
+            WireFormat::Json,
             SignalingMessage::ListClients,
         )
         .await;
@@ -352,6 +591,7 @@ mod tests {
             &setup.app_state,
             &setup.session,
             setup.websocket_tx.lock().await.deref(),
+            WireFormat::Json,
             SignalingMessage::Logout,
         )
         .await;
@@ -371,6 +611,7 @@ mod tests {
             &setup.app_state,
             &setup.session,
             setup.websocket_tx.lock().await.deref(),
+            WireFormat::Json,
             SignalingMessage::CallOffer {
                 peer_id: "client2".to_string(),
                 sdp: "sdp1".to_string(),
@@ -395,6 +636,42 @@ mod tests {
         );
     }
 
+    #[test(tokio::test)]
+    async fn handle_application_message_call_ice_end_of_candidates() {
+        let setup = TestSetup::new();
+        let client_info_1 = create_client_info(1);
+        let client_info_2 = create_client_info(2);
+        let mut clients = setup
+            .register_clients(vec![client_info_1, client_info_2])
+            .await;
+
+        let control_flow = handle_application_message(
+            &setup.app_state,
+            &setup.session,
+            setup.websocket_tx.lock().await.deref(),
+            WireFormat::Json,
+            SignalingMessage::CallIceEndOfCandidates {
+                peer_id: "client2".to_string(),
+            },
+        )
+        .await;
+        assert_eq!(control_flow, ControlFlow::Continue(()));
+
+        let message = clients
+            .get_mut("client2")
+            .unwrap()
+            .1
+            .recv()
+            .await
+            .expect("Failed to receive message");
+        assert_eq!(
+            message,
+            SignalingMessage::CallIceEndOfCandidates {
+                peer_id: "client1".to_string(),
+            }
+        );
+    }
+
     #[test(tokio::test)]
     async fn handle_application_message_unknown() {
         let setup = TestSetup::new();
@@ -403,8 +680,10 @@ mod tests {
             &setup.app_state,
             &setup.session,
             setup.websocket_tx.lock().await.deref(),
+            WireFormat::Json,
             SignalingMessage::LoginFailure {
                 reason: LoginFailureReason::DuplicateId,
+                details: None,
             },
         )
         .await;
@@ -417,6 +696,7 @@ mod tests {
 
         let is_self_message = check_self_message(
             setup.websocket_tx.lock().await.deref(),
+            WireFormat::Json,
             &setup.session,
             "client2".to_string(),
         )
@@ -430,10 +710,84 @@ mod tests {
 
         let is_self_message = check_self_message(
             setup.websocket_tx.lock().await.deref(),
+            WireFormat::Json,
             &setup.session,
             "client1".to_string(),
         )
         .await;
         assert_eq!(is_self_message, true);
     }
+
+    #[test(tokio::test)]
+    async fn call_events_are_audited() {
+        let setup = TestSetup::new();
+        let clients = setup
+            .register_clients(vec![create_client_info(1), create_client_info(2)])
+            .await;
+        let (client2, _) = clients.get("client2").unwrap();
+        let websocket_tx = setup.websocket_tx.lock().await;
+
+        for (client, message) in [
+            (
+                &setup.session,
+                SignalingMessage::CallInvite {
+                    peer_id: "client2".to_string(),
+                },
+            ),
+            (
+                client2,
+                SignalingMessage::CallAccept {
+                    peer_id: "client1".to_string(),
+                },
+            ),
+            (
+                client2,
+                SignalingMessage::CallOffer {
+                    peer_id: "client1".to_string(),
+                    sdp: "sdp1".to_string(),
+                },
+            ),
+            (
+                &setup.session,
+                SignalingMessage::CallEnd {
+                    peer_id: "client2".to_string(),
+                    reason: None,
+                },
+            ),
+        ] {
+            let control_flow = handle_application_message(
+                &setup.app_state,
+                client,
+                websocket_tx.deref(),
+                WireFormat::Json,
+                message,
+            )
+            .await;
+            assert_eq!(control_flow, ControlFlow::Continue(()));
+        }
+
+        let records = setup.audit_records.lock().clone();
+        assert_eq!(
+            records
+                .iter()
+                .map(|r| (r.event, r.sender.as_str(), r.outcome.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (CallAuditEvent::Invite, "client1", "initiated"),
+                (CallAuditEvent::Accept, "client2", "accepted"),
+                (CallAuditEvent::End, "client1", "ended"),
+            ]
+        );
+        for record in &records {
+            assert_eq!(record.caller.as_deref(), Some("client1"));
+            assert_eq!(record.callee.as_deref(), Some("client2"));
+        }
+        assert!(
+            setup
+                .app_state
+                .call_state
+                .caller("client1", "client2")
+                .is_none()
+        );
+    }
 }
@@ -5,10 +5,14 @@ use axum::extract::ws;
 use axum::extract::ws::WebSocket;
 use futures_util::stream::{SplitSink, SplitStream};
 use semver::Version;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::instrument;
-use vacs_protocol::ws::{ErrorReason, LoginFailureReason, SignalingMessage};
+use vacs_protocol::ws::{
+    Capability, ErrorReason, LoginFailureDetails, LoginFailureReason, SignalingMessage, WireFormat,
+};
+use vacs_vatsim::slurper::ControllerLookup;
 use vacs_vatsim::{ControllerInfo, FacilityType};
 
 #[instrument(level = "debug", skip_all)]
@@ -16,12 +20,14 @@ pub async fn handle_websocket_login(
     state: Arc<AppState>,
     websocket_receiver: &mut SplitStream<WebSocket>,
     websocket_sender: &mut SplitSink<WebSocket, ws::Message>,
-) -> Option<ControllerInfo> {
+    wire_format: WireFormat,
+) -> Option<(ControllerInfo, BTreeSet<Capability>)> {
     tracing::trace!("Handling websocket login flow");
     match tokio::time::timeout(Duration::from_millis(state.config.auth.login_flow_timeout_millis), async {
         loop {
-            return match receive_message(websocket_receiver).await {
-                MessageResult::ApplicationMessage(SignalingMessage::Login { token, protocol_version }) => {
+            return match receive_message(websocket_receiver, state.config.server.max_message_size).await {
+                MessageResult::ApplicationMessage(SignalingMessage::Login { token, protocol_version, capabilities }) => {
+                    let capabilities = negotiate_capabilities(&capabilities);
                     let is_compatible_protocol = Version::parse(&protocol_version)
                         .map(|version| state.updates.is_compatible_protocol(version)).unwrap_or(false);
                     if !is_compatible_protocol {
@@ -30,9 +36,16 @@ pub async fn handle_websocket_login(
                         ClientMetrics::login_failure(LoginFailureReason::IncompatibleProtocolVersion);
                         let login_failure_message = SignalingMessage::LoginFailure {
                             reason: LoginFailureReason::IncompatibleProtocolVersion,
+                            details: Some(LoginFailureDetails {
+                                min_protocol_version: state
+                                    .updates
+                                    .min_compatible_protocol()
+                                    .map(|version| version.to_string()),
+                                download_url: state.config.updates.download_url.clone(),
+                            }),
                         };
                         if let Err(err) =
-                            send_message_raw(websocket_sender, login_failure_message).await
+                            send_message_raw(websocket_sender, wire_format, login_failure_message).await
                         {
                             tracing::warn!(?err, "Failed to send websocket login failure message");
                         }
@@ -41,30 +54,66 @@ pub async fn handle_websocket_login(
 
                     match state.verify_ws_auth_token(token.as_str()).await {
                         Ok(cid) => {
+                            match state.is_banned(&cid).await {
+                                Ok(false) => {}
+                                Ok(true) => {
+                                    tracing::debug!(?cid, "Websocket login flow failed, CID is banned");
+                                    ClientMetrics::login_attempt(false);
+                                    ClientMetrics::login_failure(LoginFailureReason::Banned);
+                                    let login_failure_message = SignalingMessage::LoginFailure {
+                                        reason: LoginFailureReason::Banned,
+                                        details: None,
+                                    };
+                                    if let Err(err) =
+                                        send_message_raw(websocket_sender, wire_format, login_failure_message).await
+                                    {
+                                        tracing::warn!(?err, "Failed to send websocket login failure message");
+                                    }
+                                    return None;
+                                }
+                                Err(err) => {
+                                    tracing::warn!(?cid, ?err, "Failed to check ban list");
+                                    let reason = ErrorReason::Internal("Failed to check ban list".to_string());
+                                    ClientMetrics::login_attempt(false);
+                                    ErrorMetrics::error(&reason);
+                                    let login_failure_message = SignalingMessage::Error {
+                                        reason,
+                                        peer_id: None,
+                                    };
+                                    if let Err(err) =
+                                        send_message_raw(websocket_sender, wire_format, login_failure_message).await
+                                    {
+                                        tracing::warn!(?err, "Failed to send websocket login failure message");
+                                    }
+                                    return None;
+                                }
+                            }
+
                             if !state.config.vatsim.require_active_connection {
                                 tracing::trace!(?cid, "Websocket token verified, no active VATSIM connection required, websocket login flow completed");
-                                return Some(ControllerInfo { cid: cid.to_string(), callsign: cid, frequency: "".to_string(), facility_type: FacilityType::Unknown });
+                                return Some((ControllerInfo { cid: cid.to_string(), callsign: cid, frequency: "".to_string(), facility_type: FacilityType::Unknown }, capabilities));
                             }
 
                             tracing::trace!(?cid, "Websocket token verified, checking for active VATSIM connection");
-                            match state.get_vatsim_controller_info(&cid).await {
-                                Ok(None) | Ok(Some(ControllerInfo { facility_type: FacilityType::Unknown, ..})) => {
-                                    tracing::trace!(?cid, "No active VATSIM connection found, rejecting login");
+                            match state.lookup_vatsim_controller(&cid).await {
+                                Ok(lookup @ (ControllerLookup::Active(ControllerInfo { facility_type: FacilityType::Unknown, ..}) | ControllerLookup::ConnectedNoRange | ControllerLookup::Pilot | ControllerLookup::NotConnected)) => {
+                                    tracing::trace!(?cid, ?lookup, "No active VATSIM connection found, rejecting login");
                                     ClientMetrics::login_attempt(false);
                                     ClientMetrics::login_failure(LoginFailureReason::NoActiveVatsimConnection);
                                     let login_failure_message = SignalingMessage::LoginFailure {
                                         reason: LoginFailureReason::NoActiveVatsimConnection,
+                                        details: None,
                                     };
                                     if let Err(err) =
-                                        send_message_raw(websocket_sender, login_failure_message).await
+                                        send_message_raw(websocket_sender, wire_format, login_failure_message).await
                                     {
                                         tracing::warn!(?err, "Failed to send websocket login failure message");
                                     }
                                     None
                                 }
-                                Ok(Some(user_info)) => {
+                                Ok(ControllerLookup::Active(user_info)) => {
                                     tracing::trace!(?cid, ?user_info, "VATSIM user info found, websocket login flow completed");
-                                    Some(user_info)
+                                    Some((user_info, capabilities))
                                 }
                                 Err(err) => {
                                     tracing::warn!(?cid, ?err, "Failed to retrieve VATSIM user info");
@@ -76,7 +125,7 @@ pub async fn handle_websocket_login(
                                         peer_id: None,
                                     };
                                     if let Err(err) =
-                                        send_message_raw(websocket_sender, login_failure_message).await
+                                        send_message_raw(websocket_sender, wire_format, login_failure_message).await
                                     {
                                         tracing::warn!(?err, "Failed to send websocket login failure message");
                                     }
@@ -90,9 +139,10 @@ pub async fn handle_websocket_login(
                             ClientMetrics::login_failure(LoginFailureReason::InvalidCredentials);
                             let login_failure_message = SignalingMessage::LoginFailure {
                                 reason: LoginFailureReason::InvalidCredentials,
+                                details: None,
                             };
                             if let Err(err) =
-                                send_message_raw(websocket_sender, login_failure_message).await
+                                send_message_raw(websocket_sender, wire_format, login_failure_message).await
                             {
                                 tracing::warn!(?err, "Failed to send websocket login failure message");
                             }
@@ -106,8 +156,23 @@ pub async fn handle_websocket_login(
                     ClientMetrics::login_failure(LoginFailureReason::Unauthorized);
                     let login_failure_message = SignalingMessage::LoginFailure {
                         reason: LoginFailureReason::Unauthorized,
+                        details: None,
                     };
-                    if let Err(err) = send_message_raw(websocket_sender, login_failure_message).await {
+                    if let Err(err) = send_message_raw(websocket_sender, wire_format, login_failure_message).await {
+                        tracing::warn!(?err, "Failed to send websocket login failure message");
+                    }
+                    None
+                }
+                MessageResult::Oversized(size) => {
+                    tracing::debug!(size, "Received oversized message during websocket login flow");
+                    ClientMetrics::login_attempt(false);
+                    let reason = ErrorReason::MalformedMessage;
+                    ErrorMetrics::error(&reason);
+                    let login_failure_message = SignalingMessage::Error {
+                        reason,
+                        peer_id: None,
+                    };
+                    if let Err(err) = send_message_raw(websocket_sender, wire_format, login_failure_message).await {
                         tracing::warn!(?err, "Failed to send websocket login failure message");
                     }
                     None
@@ -137,11 +202,21 @@ pub async fn handle_websocket_login(
             ClientMetrics::login_failure(LoginFailureReason::Timeout);
             let login_timeout_message = SignalingMessage::LoginFailure {
                 reason: LoginFailureReason::Timeout,
+                details: None,
             };
-            if let Err(err) = send_message_raw(websocket_sender, login_timeout_message).await {
+            if let Err(err) = send_message_raw(websocket_sender, wire_format, login_timeout_message).await {
                 tracing::warn!(?err, "Failed to send websocket login timeout message");
             }
             None
         }
     }
 }
+
+/// Returns the capabilities advertised by the client that are supported by the server as well.
+fn negotiate_capabilities(requested: &[Capability]) -> BTreeSet<Capability> {
+    requested
+        .iter()
+        .copied()
+        .filter(|capability| Capability::ALL.contains(capability))
+        .collect()
+}
@@ -1,6 +1,9 @@
+use crate::config::{CallPolicy, CallsConfig};
 use crate::metrics::guards::{CallAttemptGuard, CallAttemptOutcome, CallGuard};
 use parking_lot::RwLock;
 use std::collections::HashMap;
+use tokio::task::JoinHandle;
+use vacs_protocol::ws::ClientInfo;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct Call(String, String);
@@ -24,9 +27,26 @@ impl From<(String, String)> for Call {
     }
 }
 
+/// An established call, tracked until it ends.
+struct ActiveCall {
+    _guard: CallGuard,
+    /// Task enforcing the maximum call duration, aborted once the call ends.
+    duration_limit: Option<JoinHandle<()>>,
+}
+
+impl Drop for ActiveCall {
+    fn drop(&mut self) {
+        if let Some(task) = self.duration_limit.take() {
+            task.abort();
+        }
+    }
+}
+
 pub struct CallStateManager {
     call_attempts: RwLock<HashMap<Call, CallAttemptGuard>>,
-    active_calls: RwLock<HashMap<Call, CallGuard>>,
+    active_calls: RwLock<HashMap<Call, ActiveCall>>,
+    /// CID of the client that initiated the call, kept until the call attempt fails or the call ends.
+    callers: RwLock<HashMap<Call, String>>,
 }
 
 impl CallStateManager {
@@ -34,10 +54,25 @@ impl CallStateManager {
         Self::default()
     }
 
+    /// Starts a call attempt initiated by `peer1_id`.
     pub fn start_call_attempt(&self, peer1_id: impl Into<String>, peer2_id: impl Into<String>) {
+        let peer1_id = peer1_id.into();
+        let call = Call::new(peer1_id.clone(), peer2_id);
+        self.callers.write().insert(call.clone(), peer1_id);
         self.call_attempts
             .write()
-            .insert(Call::new(peer1_id, peer2_id), CallAttemptGuard::new());
+            .insert(call, CallAttemptGuard::new());
+    }
+
+    pub fn caller(
+        &self,
+        peer1_id: impl Into<String>,
+        peer2_id: impl Into<String>,
+    ) -> Option<String> {
+        self.callers
+            .read()
+            .get(&Call::new(peer1_id, peer2_id))
+            .cloned()
     }
 
     pub fn complete_call_attempt(
@@ -46,25 +81,58 @@ impl CallStateManager {
         peer2_id: impl Into<String>,
         outcome: CallAttemptOutcome,
     ) {
-        if let Some(mut guard) = self
-            .call_attempts
-            .write()
-            .remove(&Call::new(peer1_id, peer2_id))
-        {
+        let call = Call::new(peer1_id, peer2_id);
+        if !matches!(outcome, CallAttemptOutcome::Accepted) {
+            self.callers.write().remove(&call);
+        }
+        if let Some(mut guard) = self.call_attempts.write().remove(&call) {
             guard.set_outcome(outcome);
         }
     }
 
+    /// Returns whether `peer_id` is in an active call with any client other than `caller_id`.
+    pub fn is_busy(&self, peer_id: &str, caller_id: &str) -> bool {
+        self.active_calls.read().keys().any(|call| {
+            (call.0 == peer_id && call.1 != caller_id) || (call.1 == peer_id && call.0 != caller_id)
+        })
+    }
+
     pub fn start_call(&self, peer1_id: impl Into<String>, peer2_id: impl Into<String>) {
-        self.active_calls
+        self.active_calls.write().insert(
+            Call::new(peer1_id, peer2_id),
+            ActiveCall {
+                _guard: CallGuard::new(),
+                duration_limit: None,
+            },
+        );
+    }
+
+    /// Attaches the task enforcing the maximum call duration to an active call, aborting it once
+    /// the call ends. The task is aborted immediately if the call is not active.
+    pub fn set_duration_limit(
+        &self,
+        peer1_id: impl Into<String>,
+        peer2_id: impl Into<String>,
+        task: JoinHandle<()>,
+    ) {
+        match self
+            .active_calls
             .write()
-            .insert(Call::new(peer1_id, peer2_id), CallGuard::new());
+            .get_mut(&Call::new(peer1_id, peer2_id))
+        {
+            Some(call) => {
+                if let Some(previous) = call.duration_limit.replace(task) {
+                    previous.abort();
+                }
+            }
+            None => task.abort(),
+        }
     }
 
     pub fn end_call(&self, peer1_id: impl Into<String>, peer2_id: impl Into<String>) {
-        self.active_calls
-            .write()
-            .remove(&Call::new(peer1_id, peer2_id));
+        let call = Call::new(peer1_id, peer2_id);
+        self.callers.write().remove(&call);
+        self.active_calls.write().remove(&call);
     }
 
     pub fn cleanup_client_calls(&self, peer_id: impl Into<String>) {
@@ -82,6 +150,10 @@ impl CallStateManager {
         self.active_calls
             .write()
             .retain(|call, _| call.0 != peer_id && call.1 != peer_id);
+
+        self.callers
+            .write()
+            .retain(|call, _| call.0 != peer_id && call.1 != peer_id);
     }
 }
 
@@ -90,6 +162,113 @@ impl Default for CallStateManager {
         Self {
             call_attempts: RwLock::new(HashMap::new()),
             active_calls: RwLock::new(HashMap::new()),
+            callers: RwLock::new(HashMap::new()),
         }
     }
 }
+
+/// Returns whether the configured call policy permits `caller` to call `callee`.
+pub fn is_call_permitted(config: &CallsConfig, caller: &ClientInfo, callee: &ClientInfo) -> bool {
+    let permitted = match config.policy {
+        CallPolicy::Unrestricted => true,
+        CallPolicy::SameFrequency => caller.frequency == callee.frequency,
+        CallPolicy::SameRegion => region(&caller.display_name) == region(&callee.display_name),
+    };
+
+    permitted
+        || config.allowed_pairs.iter().any(|(a, b)| {
+            let matches = |first: &ClientInfo, second: &ClientInfo| {
+                first.display_name.starts_with(a.as_str())
+                    && second.display_name.starts_with(b.as_str())
+            };
+            matches(caller, callee) || matches(callee, caller)
+        })
+}
+
+/// Region prefix of a callsign, e.g. `LOWW` for `LOWW_APP`.
+fn region(callsign: &str) -> &str {
+    callsign.split('_').next().unwrap_or(callsign)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(callsign: &str, frequency: &str) -> ClientInfo {
+        ClientInfo {
+            id: callsign.to_string(),
+            display_name: callsign.to_string(),
+            frequency: frequency.to_string(),
+        }
+    }
+
+    #[test]
+    fn unrestricted_policy_permits_all_calls() {
+        let config = CallsConfig::default();
+        assert!(is_call_permitted(
+            &config,
+            &client("LOWW_APP", "134.675"),
+            &client("EDDM_TWR", "118.700"),
+        ));
+    }
+
+    #[test]
+    fn same_frequency_policy() {
+        let config = CallsConfig {
+            policy: CallPolicy::SameFrequency,
+            ..Default::default()
+        };
+        assert!(is_call_permitted(
+            &config,
+            &client("LOWW_APP", "134.675"),
+            &client("LOWW_F_APP", "134.675"),
+        ));
+        assert!(!is_call_permitted(
+            &config,
+            &client("LOWW_APP", "134.675"),
+            &client("LOWW_TWR", "119.400"),
+        ));
+    }
+
+    #[test]
+    fn same_region_policy() {
+        let config = CallsConfig {
+            policy: CallPolicy::SameRegion,
+            ..Default::default()
+        };
+        assert!(is_call_permitted(
+            &config,
+            &client("LOWW_APP", "134.675"),
+            &client("LOWW_TWR", "119.400"),
+        ));
+        assert!(!is_call_permitted(
+            &config,
+            &client("LOWW_APP", "134.675"),
+            &client("LOWS_APP", "123.800"),
+        ));
+    }
+
+    #[test]
+    fn allowed_pairs_permit_calls_in_both_directions() {
+        let config = CallsConfig {
+            policy: CallPolicy::SameRegion,
+            allowed_pairs: vec![("LOWW".to_string(), "LOWS".to_string())],
+            ..Default::default()
+        };
+        assert!(is_call_permitted(
+            &config,
+            &client("LOWW_APP", "134.675"),
+            &client("LOWS_APP", "123.800"),
+        ));
+        assert!(is_call_permitted(
+            &config,
+            &client("LOWS_APP", "123.800"),
+            &client("LOWW_APP", "134.675"),
+        ));
+        assert!(!is_call_permitted(
+            &config,
+            &client("LOWW_APP", "134.675"),
+            &client("EDDM_TWR", "118.700"),
+        ));
+    }
+}
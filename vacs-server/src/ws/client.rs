@@ -1,20 +1,25 @@
 use crate::config;
+use crate::metrics::ErrorMetrics;
 use crate::metrics::guards::ClientConnectionGuard;
 use crate::state::AppState;
 use crate::ws::application_message::handle_application_message;
-use crate::ws::message::{MessageResult, receive_message, send_message};
+use crate::ws::message::{MessageResult, encode_message, receive_message, send_message};
 use crate::ws::traits::{WebSocketSink, WebSocketStream};
 use axum::extract::ws;
 use futures_util::SinkExt;
 use parking_lot::Mutex;
+use std::collections::BTreeSet;
 use std::fmt::{Debug, Formatter};
 use std::ops::ControlFlow;
 use std::sync::Arc;
+use std::time::SystemTime;
 use tokio::sync::{broadcast, mpsc, oneshot, watch};
 use tokio::task::JoinHandle;
 use tokio::time::Instant;
 use tracing::{Instrument, instrument};
-use vacs_protocol::ws::{ClientInfo, DisconnectReason, SignalingMessage};
+use vacs_protocol::ws::{
+    Capability, ClientInfo, DisconnectReason, ErrorReason, SignalingMessage, WireFormat,
+};
 
 #[derive(Clone)]
 pub struct ClientSession {
@@ -22,6 +27,8 @@ pub struct ClientSession {
     tx: mpsc::Sender<SignalingMessage>,
     client_shutdown_tx: watch::Sender<Option<DisconnectReason>>,
     client_connection_guard: Arc<Mutex<ClientConnectionGuard>>,
+    connected_at: SystemTime,
+    capabilities: BTreeSet<Capability>,
 }
 
 impl ClientSession {
@@ -36,9 +43,17 @@ impl ClientSession {
             tx,
             client_shutdown_tx,
             client_connection_guard: Arc::new(Mutex::new(client_connection_guard)),
+            connected_at: SystemTime::now(),
+            capabilities: BTreeSet::new(),
         }
     }
 
+    /// Sets the capabilities negotiated during login, gating optional messages sent to the client.
+    pub fn with_capabilities(mut self, capabilities: BTreeSet<Capability>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
     pub fn id(&self) -> &str {
         &self.client_info.id
     }
@@ -47,6 +62,18 @@ impl ClientSession {
         &self.client_info
     }
 
+    pub fn connected_at(&self) -> SystemTime {
+        self.connected_at
+    }
+
+    pub fn capabilities(&self) -> &BTreeSet<Capability> {
+        &self.capabilities
+    }
+
+    pub fn has_capability(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
     #[instrument(level = "debug", skip(self))]
     pub fn disconnect(&self, disconnect_reason: Option<DisconnectReason>) {
         tracing::trace!("Disconnecting client");
@@ -77,19 +104,26 @@ impl ClientSession {
         rx: &mut mpsc::Receiver<SignalingMessage>,
         app_shutdown_rx: &mut watch::Receiver<()>,
         client_info: ClientInfo,
+        wire_format: WireFormat,
     ) {
         tracing::debug!("Starting to handle client interaction");
 
         let (pong_update_tx, pong_update_rx) = watch::channel(Instant::now());
+        let mut presence_rx = pong_update_tx.subscribe();
+        let mut last_presence_update = Instant::now();
 
         let (writer_handle, ws_outbound_tx) = ClientSession::spawn_writer(
             websocket_tx,
+            wire_format,
             app_shutdown_rx.clone(),
             self.client_shutdown_tx.subscribe(),
         )
         .await;
         let (reader_handle, mut ws_inbound_rx) = ClientSession::spawn_reader(
             websocket_rx,
+            &ws_outbound_tx,
+            wire_format,
+            app_state.config.server.max_message_size,
             app_shutdown_rx.clone(),
             self.client_shutdown_tx.subscribe(),
             pong_update_tx,
@@ -101,9 +135,11 @@ impl ClientSession {
         tracing::trace!("Sending initial client info");
         if let Err(err) = send_message(
             &ws_outbound_tx,
+            wire_format,
             SignalingMessage::ClientInfo {
                 own: true,
                 info: client_info.clone(),
+                capabilities: Some(self.capabilities.iter().copied().collect()),
             },
         )
         .await
@@ -113,8 +149,12 @@ impl ClientSession {
 
         tracing::trace!("Sending initial client list");
         let clients = app_state.list_clients_without_self(&client_info.id).await;
-        if let Err(err) =
-            send_message(&ws_outbound_tx, SignalingMessage::ClientList { clients }).await
+        if let Err(err) = send_message(
+            &ws_outbound_tx,
+            wire_format,
+            SignalingMessage::ClientList { clients },
+        )
+        .await
         {
             tracing::warn!(?err, "Failed to send initial client info");
         }
@@ -133,10 +173,15 @@ impl ClientSession {
                     break;
                 }
 
+                Ok(()) = presence_rx.changed() => {
+                    self.refresh_presence(app_state, &mut last_presence_update).await;
+                }
+
                 msg = ws_inbound_rx.recv() => {
                     match msg {
                         Some(msg) => {
-                            match handle_application_message(app_state, self, &ws_outbound_tx, msg).await {
+                            self.refresh_presence(app_state, &mut last_presence_update).await;
+                            match handle_application_message(app_state, self, &ws_outbound_tx, wire_format, msg).await {
                                 ControlFlow::Continue(()) => continue,
                                 ControlFlow::Break(()) => {
                                     tracing::debug!("Breaking interaction loop");
@@ -155,7 +200,7 @@ impl ClientSession {
                     match msg {
                         Some(msg) => {
                             tracing::trace!("Received direct message");
-                            if let Err(err) = send_message(&ws_outbound_tx, msg).await {
+                            if let Err(err) = send_message(&ws_outbound_tx, wire_format, msg).await {
                                 tracing::warn!(?err, "Failed to send direct message");
                             }
                         }
@@ -170,14 +215,14 @@ impl ClientSession {
                     match msg {
                         Ok(mut msg) => {
                             tracing::trace!("Received broadcast message");
-                            if let SignalingMessage::ClientInfo {ref info, ref mut own} = msg
+                            if let SignalingMessage::ClientInfo {ref info, ref mut own, ..} = msg
                                 && info.id == self.client_info.id {
                                     tracing::trace!("Setting own flag for client info update broadcast");
                                     *own = true;
 
                             }
 
-                            if let Err(err) = send_message(&ws_outbound_tx, msg).await {
+                            if let Err(err) = send_message(&ws_outbound_tx, wire_format, msg).await {
                                 tracing::warn!(?err, "Failed to send broadcast message");
                             }
                         }
@@ -196,9 +241,25 @@ impl ClientSession {
         tracing::debug!("Finished handling client interaction");
     }
 
+    /// Refreshes the client's presence in the store, at most once per fraction of the presence TTL.
+    async fn refresh_presence(&self, app_state: &AppState, last_update: &mut Instant) {
+        let Some(ttl) = app_state.config.presence.ttl else {
+            return;
+        };
+        if last_update.elapsed() < ttl / config::PRESENCE_REFRESH_DIVISOR {
+            return;
+        }
+
+        match app_state.touch_presence(self.id()).await {
+            Ok(()) => *last_update = Instant::now(),
+            Err(err) => tracing::warn!(?err, "Failed to refresh client presence"),
+        }
+    }
+
     #[instrument(level = "debug", skip_all)]
     pub async fn spawn_writer<T: WebSocketSink + 'static>(
         mut websocket_tx: T,
+        wire_format: WireFormat,
         mut app_shutdown_rx: watch::Receiver<()>,
         mut client_shutdown_rx: watch::Receiver<Option<DisconnectReason>>,
     ) -> (JoinHandle<()>, mpsc::Sender<ws::Message>) {
@@ -225,9 +286,9 @@ impl ClientSession {
 
                         if let Some(reason) = reason_opt {
                             tracing::trace!(?reason, "Sending Disconnect message before stopping WebSocket writer task");
-                            match SignalingMessage::serialize(&SignalingMessage::Disconnected {reason}) {
+                            match encode_message(&SignalingMessage::Disconnected {reason}, wire_format) {
                                 Ok(msg) => {
-                                    if let Err(err) = websocket_tx.send(ws::Message::from(msg)).await {
+                                    if let Err(err) = websocket_tx.send(msg).await {
                                         tracing::warn!(?err, "Failed to send Disconnect message");
                                     }
                                 },
@@ -272,6 +333,9 @@ impl ClientSession {
     #[instrument(level = "debug", skip_all)]
     pub async fn spawn_reader<R: WebSocketStream + 'static>(
         mut websocket_rx: R,
+        ws_outbound_tx: &mpsc::Sender<ws::Message>,
+        wire_format: WireFormat,
+        max_message_size: usize,
         mut app_shutdown_rx: watch::Receiver<()>,
         mut client_shutdown_rx: watch::Receiver<Option<DisconnectReason>>,
         pong_update_tx: watch::Sender<Instant>,
@@ -279,6 +343,7 @@ impl ClientSession {
         let (ws_inbound_tx, ws_inbound_rx) =
             mpsc::channel::<SignalingMessage>(config::CLIENT_WEBSOCKET_TASK_CHANNEL_CAPACITY);
 
+        let ws_outbound_tx = ws_outbound_tx.clone();
         let join_handle = tokio::spawn(async move {
             tracing::trace!("WebSocket reader task started");
             let _guard = TaskDropLogger::new("reader");
@@ -297,7 +362,7 @@ impl ClientSession {
                         break;
                     }
 
-                    msg = receive_message(&mut websocket_rx) => {
+                    msg = receive_message(&mut websocket_rx, max_message_size) => {
                         match msg {
                             MessageResult::ApplicationMessage(message) => {
                                 if let Err(err) = ws_inbound_tx.send(message).await {
@@ -311,6 +376,14 @@ impl ClientSession {
                                     continue;
                                 }
                             },
+                            MessageResult::Oversized(size) => {
+                                tracing::debug!(size, "Rejecting oversized message from client");
+                                let reason = ErrorReason::MalformedMessage;
+                                ErrorMetrics::error(&reason);
+                                if let Err(err) = send_message(&ws_outbound_tx, wire_format, SignalingMessage::Error { reason, peer_id: None }).await {
+                                    tracing::warn!(?err, "Failed to send malformed message error");
+                                }
+                            }
                             MessageResult::Disconnected => {
                                 tracing::debug!("Client disconnected");
                                 break;
@@ -489,7 +562,7 @@ mod tests {
                 assert_eq!(
                     text,
                     Utf8Bytes::from_static(
-                        r#"{"type":"ClientInfo","own":true,"info":{"id":"client1","displayName":"Client 1","frequency":"100.000"}}"#
+                        r#"{"type":"ClientInfo","own":true,"info":{"id":"client1","displayName":"Client 1","frequency":"100.000"},"capabilities":["CallBusy","CallDurationWarning","CallPolicy","IceEndOfCandidates"]}"#
                     )
                 );
             }
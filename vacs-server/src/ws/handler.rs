@@ -1,3 +1,4 @@
+use crate::config;
 use crate::metrics::ClientMetrics;
 use crate::metrics::guards::ClientConnectionGuard;
 use crate::state::AppState;
@@ -11,34 +12,63 @@ use futures_util::{SinkExt, StreamExt};
 use std::sync::Arc;
 use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode as TungsteniteCloseCode;
 use tracing::Instrument;
-use vacs_protocol::ws::{ClientInfo, LoginFailureReason, SignalingMessage};
+use vacs_protocol::ws::{ClientInfo, LoginFailureReason, SignalingMessage, WireFormat};
 
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     ClientIp(ip): ClientIp,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
+    // permessage-deflate is intentionally not negotiated: tungstenite (used by both axum and the
+    // client's transport) does not implement the extension and rejects frames with RSV1 set, so
+    // compression would require replacing the WebSocket stack on both ends.
+    // Messages exceeding the configured limit are rejected by the client session, the hard limit
+    // only guards against buffering arbitrarily large frames.
+    let hard_limit =
+        state.config.server.max_message_size * config::WS_MESSAGE_SIZE_HARD_LIMIT_FACTOR;
+    let ws = ws
+        .max_message_size(hard_limit)
+        .max_frame_size(hard_limit)
+        .protocols(
+            WireFormat::SUPPORTED
+                .iter()
+                .map(|wire_format| wire_format.subprotocol()),
+        );
+    // Clients not requesting a subprotocol predate format negotiation and always use JSON.
+    let wire_format = ws
+        .selected_protocol()
+        .and_then(|protocol| protocol.to_str().ok())
+        .and_then(WireFormat::from_subprotocol)
+        .unwrap_or_default();
+
     ws.on_upgrade(move |socket| {
         let span = tracing::trace_span!("websocket_connection", client_ip = ?ip, client_id = tracing::field::Empty);
         async move {
-            handle_socket(socket, state).await;
+            handle_socket(socket, state, wire_format).await;
         }.instrument(span)
     })
 }
 
-async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
-    tracing::trace!("Handling new websocket connection");
+async fn handle_socket(socket: WebSocket, state: Arc<AppState>, wire_format: WireFormat) {
+    tracing::trace!(?wire_format, "Handling new websocket connection");
     let client_connection_guard = ClientConnectionGuard::new();
 
     let (mut websocket_tx, mut websocket_rx) = socket.split();
 
-    let controller_info =
-        match handle_websocket_login(state.clone(), &mut websocket_rx, &mut websocket_tx).await {
-            Some(id) => id,
-            None => return,
-        };
+    let (controller_info, capabilities) = match handle_websocket_login(
+        state.clone(),
+        &mut websocket_rx,
+        &mut websocket_tx,
+        wire_format,
+    )
+    .await
+    {
+        Some(login) => login,
+        None => return,
+    };
 
     tracing::Span::current().record("client_id", &controller_info.cid);
+    tracing::debug!(?capabilities, "Negotiated client capabilities");
 
     let client_info = ClientInfo {
         id: controller_info.cid.clone(),
@@ -47,7 +77,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
     };
 
     let res = state
-        .register_client(client_info.clone(), client_connection_guard)
+        .register_client(client_info.clone(), capabilities, client_connection_guard)
         .await;
     let (mut client, mut rx) = match res {
         Ok(client) => client,
@@ -57,8 +87,10 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
 
             if let Err(err) = send_message_raw(
                 &mut websocket_tx,
+                wire_format,
                 SignalingMessage::LoginFailure {
                     reason: LoginFailureReason::DuplicateId,
+                    details: None,
                 },
             )
             .await
@@ -92,6 +124,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
             &mut rx,
             &mut shutdown_rx,
             client_info,
+            wire_format,
         )
         .await;
 
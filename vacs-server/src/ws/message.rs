@@ -3,7 +3,7 @@ use crate::ws::traits::{WebSocketSink, WebSocketStream};
 use axum::extract::ws;
 use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
-use vacs_protocol::ws::SignalingMessage;
+use vacs_protocol::ws::{EncodedMessage, SignalingMessage, WireFormat};
 
 /// Represents the outcome of [`receive_message`], indicating whether the message received should be handled, skipped or receiving errored.
 #[derive(Debug)]
@@ -12,6 +12,8 @@ pub enum MessageResult {
     ApplicationMessage(SignalingMessage),
     /// A control message (e.g., Ping, Pong) that should be skipped.
     ControlMessage,
+    /// An application-message exceeding the maximum message size, containing its size in bytes.
+    Oversized(usize),
     /// The client has disconnected.
     Disconnected,
     /// An error occurred while receiving the message.
@@ -23,6 +25,7 @@ impl PartialEq for MessageResult {
         match (self, other) {
             (MessageResult::ApplicationMessage(a), MessageResult::ApplicationMessage(b)) => a == b,
             (MessageResult::ControlMessage, MessageResult::ControlMessage) => true,
+            (MessageResult::Oversized(a), MessageResult::Oversized(b)) => a == b,
             (MessageResult::Disconnected, MessageResult::Disconnected) => true,
             (MessageResult::Error(self_err), MessageResult::Error(other_err)) => {
                 self_err.to_string() == other_err.to_string()
@@ -34,13 +37,12 @@ impl PartialEq for MessageResult {
 
 pub async fn send_message(
     ws_outbound_tx: &mpsc::Sender<ws::Message>,
+    wire_format: WireFormat,
     message: SignalingMessage,
 ) -> anyhow::Result<()> {
-    let serialized_message = SignalingMessage::serialize(&message)
-        .map_err(|e| anyhow::anyhow!(e).context("Failed to serialize message"))?;
-    MessageMetrics::sent(&message, serialized_message.len());
+    let encoded_message = encode_message(&message, wire_format)?;
     ws_outbound_tx
-        .send(ws::Message::from(serialized_message))
+        .send(encoded_message)
         .await
         .map_err(|e| anyhow::anyhow!(e).context("Failed to send message"))?;
     Ok(())
@@ -48,33 +50,45 @@ pub async fn send_message(
 
 pub async fn send_message_raw<T: WebSocketSink>(
     websocket_tx: &mut T,
+    wire_format: WireFormat,
     message: SignalingMessage,
 ) -> anyhow::Result<()> {
-    let serialized_message = SignalingMessage::serialize(&message)
-        .map_err(|e| anyhow::anyhow!(e).context("Failed to serialize message"))?;
-    MessageMetrics::sent(&message, serialized_message.len());
+    let encoded_message = encode_message(&message, wire_format)?;
     websocket_tx
-        .send(ws::Message::from(serialized_message))
+        .send(encoded_message)
         .await
         .map_err(|e| anyhow::anyhow!(e).context("Failed to send message"))?;
     Ok(())
 }
 
-pub async fn receive_message<R: WebSocketStream>(websocket_rx: &mut R) -> MessageResult {
+/// Encodes a message using the wire format negotiated for the connection.
+pub fn encode_message(
+    message: &SignalingMessage,
+    wire_format: WireFormat,
+) -> anyhow::Result<ws::Message> {
+    let encoded_message = SignalingMessage::encode(message, wire_format)
+        .map_err(|e| anyhow::anyhow!(e).context("Failed to serialize message"))?;
+    MessageMetrics::sent(message, encoded_message.len());
+    Ok(match encoded_message {
+        EncodedMessage::Text(text) => ws::Message::from(text),
+        EncodedMessage::Binary(data) => ws::Message::Binary(data.into()),
+    })
+}
+
+/// Receives the next message from the client, decoding it based on the frame type: text frames
+/// contain JSON, binary frames MessagePack (if supported). Messages larger than
+/// `max_message_size` bytes are not decoded.
+pub async fn receive_message<R: WebSocketStream>(
+    websocket_rx: &mut R,
+    max_message_size: usize,
+) -> MessageResult {
     match websocket_rx.next().await {
         Some(Ok(ws::Message::Text(raw_message))) => {
-            match SignalingMessage::deserialize(&raw_message) {
-                Ok(message) => {
-                    MessageMetrics::received(&message, raw_message.len());
-                    MessageResult::ApplicationMessage(message)
-                }
-                Err(err) => {
-                    MessageMetrics::malformed();
-                    MessageResult::Error(
-                        anyhow::anyhow!(err).context("Failed to deserialize message"),
-                    )
-                }
-            }
+            decode_message(raw_message.as_bytes(), WireFormat::Json, max_message_size)
+        }
+        #[cfg(feature = "msgpack")]
+        Some(Ok(ws::Message::Binary(raw_message))) => {
+            decode_message(&raw_message, WireFormat::MessagePack, max_message_size)
         }
         Some(Ok(ws::Message::Ping(_))) => MessageResult::ControlMessage,
         Some(Ok(ws::Message::Pong(_))) => MessageResult::ControlMessage,
@@ -82,7 +96,8 @@ pub async fn receive_message<R: WebSocketStream>(websocket_rx: &mut R) -> Messag
             tracing::debug!(?reason, "Received websocket close message");
             MessageResult::Disconnected
         }
-        Some(Ok(other)) => {
+        #[cfg(not(feature = "msgpack"))]
+        Some(Ok(other @ ws::Message::Binary(_))) => {
             tracing::trace!(?other, "Received unexpected websocket message");
             MessageResult::Error(anyhow::anyhow!("Received unexpected websocket message"))
         }
@@ -97,6 +112,33 @@ pub async fn receive_message<R: WebSocketStream>(websocket_rx: &mut R) -> Messag
     }
 }
 
+fn decode_message(
+    raw_message: &[u8],
+    wire_format: WireFormat,
+    max_message_size: usize,
+) -> MessageResult {
+    if raw_message.len() > max_message_size {
+        tracing::debug!(
+            size = raw_message.len(),
+            max_message_size,
+            "Received oversized message"
+        );
+        MessageMetrics::malformed();
+        return MessageResult::Oversized(raw_message.len());
+    }
+
+    match SignalingMessage::decode(raw_message, wire_format) {
+        Ok(message) => {
+            MessageMetrics::received(&message, raw_message.len());
+            MessageResult::ApplicationMessage(message)
+        }
+        Err(err) => {
+            MessageMetrics::malformed();
+            MessageResult::Error(anyhow::anyhow!(err).context("Failed to deserialize message"))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +151,8 @@ mod tests {
     use vacs_protocol::VACS_PROTOCOL_VERSION;
     use vacs_protocol::ws::ClientInfo;
 
+    const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
     #[test(tokio::test)]
     async fn send_single_message_raw() {
         let (tx, mut rx) = mpsc::channel(100);
@@ -123,7 +167,7 @@ mod tests {
         };
 
         assert!(
-            send_message_raw(&mut mock_sink, message.clone())
+            send_message_raw(&mut mock_sink, WireFormat::Json, message.clone())
                 .await
                 .is_ok()
         );
@@ -150,13 +194,14 @@ mod tests {
             SignalingMessage::Login {
                 token: "token1".to_string(),
                 protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+                capabilities: vec![],
             },
             SignalingMessage::ListClients,
             SignalingMessage::Logout,
         ];
         for message in &messages {
             assert!(
-                send_message_raw(&mut mock_sink, message.clone())
+                send_message_raw(&mut mock_sink, WireFormat::Json, message.clone())
                     .await
                     .is_ok()
             );
@@ -184,6 +229,7 @@ mod tests {
             SignalingMessage::Login {
                 token: "token1".to_string(),
                 protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+                capabilities: vec![],
             },
             SignalingMessage::ListClients,
             SignalingMessage::Logout,
@@ -195,7 +241,7 @@ mod tests {
             let message = message.clone();
             let task = tokio::spawn(async move {
                 let mut mock_sink = mock_sink.lock().await;
-                send_message_raw(&mut *mock_sink, message.clone()).await
+                send_message_raw(&mut *mock_sink, WireFormat::Json, message.clone()).await
             });
             tasks.push(task);
         }
@@ -237,7 +283,7 @@ mod tests {
         };
 
         assert!(
-            send_message_raw(&mut mock_sink, message.clone())
+            send_message_raw(&mut mock_sink, WireFormat::Json, message.clone())
                 .await
                 .is_err_and(|err| err.to_string().contains("Failed to send message"))
         );
@@ -249,13 +295,14 @@ mod tests {
             "{\"type\":\"Login\",\"id\":\"client1\",\"token\":\"token1\",\"protocolVersion\":\"0.0.0\"}",
         ))]);
 
-        let result = receive_message(&mut mock_stream).await;
+        let result = receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await;
 
         assert_eq!(
             result,
             MessageResult::ApplicationMessage(SignalingMessage::Login {
                 token: "token1".to_string(),
                 protocol_version: "0.0.0".to_string(),
+                capabilities: vec![],
             })
         );
     }
@@ -273,18 +320,19 @@ mod tests {
         ]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::ApplicationMessage(SignalingMessage::Login {
                 token: "token1".to_string(),
                 protocol_version: "0.0.0".to_string(),
+                capabilities: vec![],
             })
         );
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::ApplicationMessage(SignalingMessage::Logout)
         );
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::ApplicationMessage(SignalingMessage::CallOffer {
                 peer_id: "client1".to_string(),
                 sdp: "sdp1".to_string()
@@ -309,7 +357,7 @@ mod tests {
             let mock_stream = mock_stream.clone();
             let task = tokio::spawn(async move {
                 let mut mock_stream = mock_stream.lock().await;
-                receive_message(&mut *mock_stream).await
+                receive_message(&mut *mock_stream, MAX_MESSAGE_SIZE).await
             });
             tasks.push(task);
         }
@@ -330,10 +378,11 @@ mod tests {
 
         for _ in 0..2 {
             assert_eq!(
-                receive_message(&mut mock_stream).await,
+                receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
                 MessageResult::ApplicationMessage(SignalingMessage::Login {
                     token: "token1".to_string(),
                     protocol_version: "0.0.0".to_string(),
+                    capabilities: vec![],
                 })
             );
         }
@@ -348,18 +397,40 @@ mod tests {
 
         for _ in 0..2 {
             assert_eq!(
-                receive_message(&mut mock_stream).await,
+                receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
                 MessageResult::ControlMessage
             );
         }
     }
 
+    #[test(tokio::test)]
+    async fn receive_oversized_message() {
+        let message = format!(
+            r#"{{"type":"CallOffer","peerId":"client1","sdp":"{}"}}"#,
+            "a".repeat(MAX_MESSAGE_SIZE)
+        );
+        let size = message.len();
+        let mut mock_stream = MockStream::new(vec![
+            Ok(ws::Message::from(message)),
+            Ok(ws::Message::from(r#"{"type":"ListClients"}"#)),
+        ]);
+
+        assert_eq!(
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
+            MessageResult::Oversized(size)
+        );
+        assert_eq!(
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
+            MessageResult::ApplicationMessage(SignalingMessage::ListClients)
+        );
+    }
+
     #[test(tokio::test)]
     async fn receive_close_message() {
         let mut mock_stream = MockStream::new(vec![Ok(ws::Message::Close(None))]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Disconnected
         );
     }
@@ -372,7 +443,7 @@ mod tests {
         })))]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Disconnected
         );
     }
@@ -386,15 +457,15 @@ mod tests {
         ]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::ControlMessage
         );
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::ApplicationMessage(SignalingMessage::Logout)
         );
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::ControlMessage
         );
     }
@@ -405,7 +476,7 @@ mod tests {
             MockStream::new(vec![Ok(ws::Message::Text(ws::Utf8Bytes::from("invalid")))]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Error(anyhow::anyhow!("Failed to deserialize message"))
         );
     }
@@ -416,7 +487,7 @@ mod tests {
             MockStream::new(vec![Ok(ws::Message::Text(ws::Utf8Bytes::from("\"Logout")))]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Error(anyhow::anyhow!("Failed to deserialize message"))
         );
     }
@@ -428,7 +499,7 @@ mod tests {
         )))]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Error(anyhow::anyhow!("Failed to deserialize message"))
         );
     }
@@ -438,7 +509,7 @@ mod tests {
         let mut mock_stream = MockStream::new(vec![Ok(ws::Message::Text(ws::Utf8Bytes::from("")))]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Error(anyhow::anyhow!("Failed to deserialize message"))
         );
     }
@@ -450,11 +521,12 @@ mod tests {
         )))]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Error(anyhow::anyhow!("Failed to receive message"))
         );
     }
 
+    #[cfg(not(feature = "msgpack"))]
     #[test(tokio::test)]
     async fn receive_unexpected_message() {
         let mut mock_stream = MockStream::new(vec![Ok(ws::Message::Binary(
@@ -462,11 +534,61 @@ mod tests {
         ))]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Error(anyhow::anyhow!("Received unexpected websocket message"))
         );
     }
 
+    #[cfg(feature = "msgpack")]
+    #[test(tokio::test)]
+    async fn send_message_raw_msgpack() {
+        let (tx, mut rx) = mpsc::channel(100);
+        let mut mock_sink = MockSink::new(tx);
+
+        let message = SignalingMessage::CallOffer {
+            peer_id: "client1".to_string(),
+            sdp: "sdp1".to_string(),
+        };
+
+        assert!(
+            send_message_raw(&mut mock_sink, WireFormat::MessagePack, message.clone())
+                .await
+                .is_ok()
+        );
+
+        match rx.recv().await.expect("No message received") {
+            ws::Message::Binary(data) => {
+                let decoded = SignalingMessage::decode(&data, WireFormat::MessagePack)
+                    .expect("Failed to deserialize message");
+                assert_eq!(decoded, message);
+            }
+            other => panic!("Expected a Binary message, got: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test(tokio::test)]
+    async fn receive_msgpack_message() {
+        let EncodedMessage::Binary(data) =
+            SignalingMessage::encode(&SignalingMessage::Logout, WireFormat::MessagePack).unwrap()
+        else {
+            panic!("Expected MessagePack to be encoded as binary");
+        };
+        let mut mock_stream = MockStream::new(vec![
+            Ok(ws::Message::Binary(data.into())),
+            Ok(ws::Message::Binary(tungstenite::Bytes::from("binary"))),
+        ]);
+
+        assert_eq!(
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
+            MessageResult::ApplicationMessage(SignalingMessage::Logout)
+        );
+        assert_eq!(
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
+            MessageResult::Error(anyhow::anyhow!("Failed to deserialize message"))
+        );
+    }
+
     #[test(tokio::test)]
     async fn receive_message_socket_error() {
         let mut mock_stream = MockStream::new(vec![Err(axum::Error::new(
@@ -474,7 +596,7 @@ mod tests {
         ))]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Error(anyhow::anyhow!("Failed to receive message"))
         );
     }
@@ -484,7 +606,7 @@ mod tests {
         let mut mock_stream = MockStream::new(vec![]);
 
         assert_eq!(
-            receive_message(&mut mock_stream).await,
+            receive_message(&mut mock_stream, MAX_MESSAGE_SIZE).await,
             MessageResult::Disconnected
         );
     }
@@ -1,3 +1,4 @@
+use crate::audit::{AuditLog, CallAuditRecord};
 use crate::config::{AppConfig, VatsimConfig};
 use crate::ice::provider::stun::StunOnlyProvider;
 use crate::metrics::guards::ClientConnectionGuard;
@@ -14,7 +15,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::sync::{Mutex, broadcast, mpsc, watch};
-use vacs_protocol::ws::{ClientInfo, SignalingMessage};
+use vacs_protocol::ws::{Capability, ClientInfo, SignalingMessage, WireFormat};
 use vacs_vatsim::data_feed::mock::MockDataFeed;
 use vacs_vatsim::slurper::SlurperClient;
 
@@ -80,6 +81,7 @@ pub struct TestSetup {
     pub rx: mpsc::Receiver<SignalingMessage>,
     pub broadcast_rx: broadcast::Receiver<SignalingMessage>,
     pub shutdown_tx: watch::Sender<()>,
+    pub audit_records: Arc<parking_lot::Mutex<Vec<CallAuditRecord>>>,
 }
 
 impl TestSetup {
@@ -94,21 +96,25 @@ impl TestSetup {
                 user_service: Default::default(),
                 require_active_connection: false,
                 slurper_base_url: Default::default(),
+                slurper_field_indices: Default::default(),
                 controller_update_interval: Default::default(),
+                controller_update_jitter: Default::default(),
                 data_feed_url: Default::default(),
             },
             ..Default::default()
         };
         let mock_data_feed = Arc::new(MockDataFeed::default());
+        let (audit_log, audit_records) = AuditLog::memory();
         let app_state = Arc::new(AppState::new(
             config,
             UpdateChecker::default(),
-            Store::Memory(MemoryStore::default()),
+            Store::Memory(MemoryStore::with_test_tokens()),
             SlurperClient::new("http://localhost:12345").unwrap(),
             mock_data_feed.clone(),
             RateLimiters::default(),
             shutdown_rx,
             Arc::new(StunOnlyProvider::default()),
+            audit_log,
         ));
         let client_info = ClientInfo {
             id: "client1".to_string(),
@@ -116,7 +122,8 @@ impl TestSetup {
             frequency: "100.000".to_string(),
         };
         let (tx, rx) = mpsc::channel(10);
-        let session = ClientSession::new(client_info, tx, ClientConnectionGuard::default());
+        let session = ClientSession::new(client_info, tx, ClientConnectionGuard::default())
+            .with_capabilities(Capability::ALL.iter().copied().collect());
         let (websocket_tx, websocket_rx) = mpsc::channel(100);
         let mock_stream = MockStream::new(vec![]);
         let mock_sink = MockSink::new(websocket_tx.clone());
@@ -132,6 +139,7 @@ impl TestSetup {
             rx,
             broadcast_rx,
             shutdown_tx,
+            audit_records,
         }
     }
 
@@ -145,7 +153,11 @@ impl TestSetup {
         client_info: ClientInfo,
     ) -> (ClientSession, mpsc::Receiver<SignalingMessage>) {
         self.app_state
-            .register_client(client_info, ClientConnectionGuard::default())
+            .register_client(
+                client_info,
+                Capability::ALL.iter().copied().collect(),
+                ClientConnectionGuard::default(),
+            )
             .await
             .expect("Failed to register client")
     }
@@ -183,6 +195,7 @@ impl TestSetup {
                     &mut self.rx,
                     &mut self.shutdown_tx.subscribe(),
                     client_info,
+                    WireFormat::Json,
                 )
                 .await;
         })
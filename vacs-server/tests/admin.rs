@@ -0,0 +1,229 @@
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::time::Duration;
+use test_log::test;
+use vacs_protocol::http::admin::ConnectedClient;
+use vacs_protocol::ws::{DisconnectReason, SignalingMessage};
+use vacs_server::state::BAN_CHANNEL;
+use vacs_server::store::StoreBackend;
+use vacs_server::test_utils::{TEST_ADMIN_TOKEN, TestApp, TestClient, setup_n_test_clients};
+
+#[test(tokio::test)]
+async fn list_clients() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let _clients = setup_n_test_clients(test_app.addr(), 2).await;
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/admin/clients", test_app.http_addr()))
+        .bearer_auth(TEST_ADMIN_TOKEN)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let clients: Vec<ConnectedClient> = response.json().await?;
+    assert_eq!(
+        clients.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(),
+        vec!["client1", "client2"]
+    );
+    assert!(clients.iter().all(|c| c.connected_at > 0));
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn rejects_missing_or_invalid_token() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let client = reqwest::Client::new();
+    let url = format!("{}/admin/clients", test_app.http_addr());
+
+    let response = client.get(&url).send().await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client.get(&url).bearer_auth("wrong-token").send().await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(format!("{}/admin/disconnect/client1", test_app.http_addr()))
+        .bearer_auth("wrong-token")
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn disconnect_client() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/admin/disconnect/client1", test_app.http_addr()))
+        .bearer_auth(TEST_ADMIN_TOKEN)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    client1
+        .recv_with_timeout_and_filter(Duration::from_millis(100), |m| {
+            matches!(
+                m,
+                SignalingMessage::Disconnected {
+                    reason: DisconnectReason::Kicked
+                }
+            )
+        })
+        .await
+        .expect("Disconnected message was not received");
+
+    client2
+        .recv_with_timeout_and_filter(
+            Duration::from_millis(100),
+            |m| matches!(m, SignalingMessage::ClientDisconnected { id } if id == "client1"),
+        )
+        .await
+        .expect("ClientDisconnected broadcast was not received");
+
+    assert!(test_app.state().get_client("client1").await.is_none());
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn disconnect_unknown_client() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/admin/disconnect/unknown", test_app.http_addr()))
+        .bearer_auth(TEST_ADMIN_TOKEN)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn banned_client_cannot_login() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let client = reqwest::Client::new();
+    let ban_url = format!("{}/admin/bans/client1", test_app.http_addr());
+
+    let response = client
+        .put(&ban_url)
+        .bearer_auth(TEST_ADMIN_TOKEN)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    assert!(
+        TestClient::new_with_login(
+            test_app.addr(),
+            "client1",
+            "token1",
+            |_, _| Ok(()),
+            |_| Ok(())
+        )
+        .await
+        .is_err_and(|err| err.to_string() == "Login failed: Banned")
+    );
+    TestClient::new_with_login(
+        test_app.addr(),
+        "client2",
+        "token2",
+        |_, _| Ok(()),
+        |_| Ok(()),
+    )
+    .await
+    .expect("Failed to log in client that is not banned");
+
+    let response = client
+        .delete(&ban_url)
+        .bearer_auth(TEST_ADMIN_TOKEN)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    TestClient::new_with_login(
+        test_app.addr(),
+        "client1",
+        "token1",
+        |_, _| Ok(()),
+        |_| Ok(()),
+    )
+    .await
+    .expect("Failed to log in client after unban");
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn ban_disconnects_connected_client() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+
+    let response = reqwest::Client::new()
+        .put(format!("{}/admin/bans/client1", test_app.http_addr()))
+        .bearer_auth(TEST_ADMIN_TOKEN)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    client1
+        .recv_with_timeout_and_filter(Duration::from_millis(100), |m| {
+            matches!(
+                m,
+                SignalingMessage::Disconnected {
+                    reason: DisconnectReason::Banned
+                }
+            )
+        })
+        .await
+        .expect("Disconnected message was not received");
+
+    client2
+        .recv_with_timeout_and_filter(
+            Duration::from_millis(100),
+            |m| matches!(m, SignalingMessage::ClientDisconnected { id } if id == "client1"),
+        )
+        .await
+        .expect("ClientDisconnected broadcast was not received");
+
+    assert!(test_app.state().get_client("client1").await.is_none());
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn ban_published_by_other_instance_disconnects_client() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+    let mut client1 = clients.remove(0);
+
+    // Another instance sharing the store banned client1
+    test_app
+        .state()
+        .store()
+        .publish(BAN_CHANNEL, "client1")
+        .await?;
+
+    client1
+        .recv_with_timeout_and_filter(Duration::from_millis(100), |m| {
+            matches!(
+                m,
+                SignalingMessage::Disconnected {
+                    reason: DisconnectReason::Banned
+                }
+            )
+        })
+        .await
+        .expect("Disconnected message was not received");
+    assert!(test_app.state().get_client("client1").await.is_none());
+    assert!(test_app.state().get_client("client2").await.is_some());
+
+    Ok(())
+}
@@ -111,7 +111,7 @@ async fn unauthorized_message_before_login() {
 
     let message_result = ws_stream.next().await;
     assert_raw_message_matches(message_result, |response| match response {
-        SignalingMessage::LoginFailure { reason } => {
+        SignalingMessage::LoginFailure { reason, .. } => {
             assert_eq!(
                 reason,
                 LoginFailureReason::Unauthorized,
@@ -167,6 +167,7 @@ async fn login_timeout() {
             SignalingMessage::serialize(&SignalingMessage::Login {
                 token: "token".to_string(),
                 protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+                capabilities: vec![],
             })
             .unwrap(),
         ))
@@ -176,7 +177,7 @@ async fn login_timeout() {
     match ws_stream.next().await {
         Some(Ok(tungstenite::Message::Text(response))) => {
             match SignalingMessage::deserialize(&response) {
-                Ok(SignalingMessage::LoginFailure { reason }) => {
+                Ok(SignalingMessage::LoginFailure { reason, .. }) => {
                     assert_eq!(reason, LoginFailureReason::Timeout);
                 }
                 _ => panic!("Unexpected response: {response:?}"),
@@ -1,7 +1,8 @@
 use std::time::Duration;
 use test_log::test;
-use vacs_protocol::ws::SignalingMessage;
-use vacs_server::test_utils::{TestApp, setup_n_test_clients};
+use vacs_protocol::ws::{CallEndReason, CallRejectReason, ErrorReason, SignalingMessage};
+use vacs_server::config::{CallPolicy, CallsConfig};
+use vacs_server::test_utils::{TestApp, TestClient, setup_n_test_clients};
 
 #[test(tokio::test)]
 async fn call_offer() -> anyhow::Result<()> {
@@ -265,3 +266,514 @@ async fn peer_not_found() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn call_invite_busy() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 3).await;
+
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+    let mut client3 = clients.remove(0);
+
+    client1
+        .send(SignalingMessage::CallInvite {
+            peer_id: client2.id().to_string(),
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallAccept {
+            peer_id: client1.id().to_string(),
+        })
+        .await?;
+    client1
+        .send(SignalingMessage::CallOffer {
+            peer_id: client2.id().to_string(),
+            sdp: "sdp1".to_string(),
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallAnswer {
+            peer_id: client1.id().to_string(),
+            sdp: "sdp2".to_string(),
+        })
+        .await?;
+
+    let call_answer_messages = client1
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::CallAnswer { .. })
+        })
+        .await;
+    assert_eq!(
+        call_answer_messages.len(),
+        1,
+        "client1 should have received exactly one CallAnswer message"
+    );
+    client2.recv_until_timeout(Duration::from_millis(100)).await;
+
+    for peer_id in [client1.id().to_string(), client2.id().to_string()] {
+        client3
+            .send(SignalingMessage::CallInvite {
+                peer_id: peer_id.clone(),
+            })
+            .await?;
+
+        let call_busy_messages = client3
+            .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+                matches!(m, SignalingMessage::CallBusy { .. })
+            })
+            .await;
+        assert_eq!(
+            call_busy_messages,
+            vec![SignalingMessage::CallBusy { peer_id }],
+            "client3 should have received exactly one CallBusy message"
+        );
+    }
+
+    for (i, client) in [&mut client1, &mut client2].into_iter().enumerate() {
+        let call_invite_messages = client
+            .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+                matches!(m, SignalingMessage::CallInvite { .. })
+            })
+            .await;
+        assert!(
+            call_invite_messages.is_empty(),
+            "client{} should have received no CallInvite messages, but received: {:?}",
+            i + 1,
+            call_invite_messages
+        );
+    }
+
+    client1
+        .send(SignalingMessage::CallEnd {
+            peer_id: client2.id().to_string(),
+            reason: None,
+        })
+        .await?;
+    client2.recv_until_timeout(Duration::from_millis(100)).await;
+
+    client3
+        .send(SignalingMessage::CallInvite {
+            peer_id: client2.id().to_string(),
+        })
+        .await?;
+
+    let call_invite_messages = client2
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::CallInvite { .. })
+        })
+        .await;
+    assert_eq!(
+        call_invite_messages,
+        vec![SignalingMessage::CallInvite {
+            peer_id: client3.id().to_string()
+        }],
+        "client2 should have received the CallInvite after the call ended"
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn call_invite_busy_without_capability() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+    let mut client3 = TestClient::new(test_app.addr(), "client3", "token3")
+        .await?
+        .with_capabilities(vec![]);
+    client3.login(|_, _| Ok(()), |_| Ok(())).await?;
+
+    client1
+        .send(SignalingMessage::CallInvite {
+            peer_id: client2.id().to_string(),
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallAccept {
+            peer_id: client1.id().to_string(),
+        })
+        .await?;
+    client1
+        .send(SignalingMessage::CallOffer {
+            peer_id: client2.id().to_string(),
+            sdp: "sdp1".to_string(),
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallAnswer {
+            peer_id: client1.id().to_string(),
+            sdp: "sdp2".to_string(),
+        })
+        .await?;
+    client1.recv_until_timeout(Duration::from_millis(100)).await;
+    client2.recv_until_timeout(Duration::from_millis(100)).await;
+
+    client3
+        .send(SignalingMessage::CallInvite {
+            peer_id: client2.id().to_string(),
+        })
+        .await?;
+
+    let call_busy_messages = client3
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::CallBusy { .. })
+        })
+        .await;
+    assert!(
+        call_busy_messages.is_empty(),
+        "client3 did not advertise busy support, but received: {:?}",
+        call_busy_messages
+    );
+
+    // Without busy support, the invite is forwarded to the busy peer.
+    let call_invite_messages = client2
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::CallInvite { .. })
+        })
+        .await;
+    assert_eq!(
+        call_invite_messages,
+        vec![SignalingMessage::CallInvite {
+            peer_id: client3.id().to_string(),
+        }],
+        "client2 should have received exactly one CallInvite message"
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn call_ice_end_of_candidates_without_capability() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 1).await;
+
+    let mut client1 = clients.remove(0);
+    let mut client2 = TestClient::new(test_app.addr(), "client2", "token2")
+        .await?
+        .with_capabilities(vec![]);
+    client2.login(|_, _| Ok(()), |_| Ok(())).await?;
+    client2.recv_until_timeout(Duration::from_millis(100)).await;
+
+    client1
+        .send(SignalingMessage::CallIceCandidate {
+            peer_id: client2.id().to_string(),
+            candidate: "candidate".to_string(),
+        })
+        .await?;
+    client1
+        .send(SignalingMessage::CallIceEndOfCandidates {
+            peer_id: client2.id().to_string(),
+        })
+        .await?;
+
+    // Older clients cannot deserialize the end of candidates, only the candidate is forwarded.
+    let messages = client2.recv_until_timeout(Duration::from_millis(100)).await;
+    assert_eq!(
+        messages,
+        vec![SignalingMessage::CallIceCandidate {
+            peer_id: client1.id().to_string(),
+            candidate: "candidate".to_string(),
+        }]
+    );
+
+    // The sender is not notified about the dropped message.
+    let errors = client1
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::Error { .. })
+        })
+        .await;
+    assert!(errors.is_empty(), "client1 received errors: {errors:?}");
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn call_offer_oversized_sdp() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+
+    client1
+        .send(SignalingMessage::CallInvite {
+            peer_id: client2.id().to_string(),
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallAccept {
+            peer_id: client1.id().to_string(),
+        })
+        .await?;
+    client1.recv_until_timeout(Duration::from_millis(100)).await;
+    client2.recv_until_timeout(Duration::from_millis(100)).await;
+
+    client1
+        .send(SignalingMessage::CallOffer {
+            peer_id: client2.id().to_string(),
+            sdp: "a".repeat(128 * 1024),
+        })
+        .await?;
+
+    let error_messages = client1
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::Error { .. })
+        })
+        .await;
+    assert_eq!(
+        error_messages,
+        vec![SignalingMessage::Error {
+            reason: ErrorReason::MalformedMessage,
+            peer_id: None,
+        }],
+        "client1 should have received exactly one MalformedMessage error"
+    );
+
+    // The connection stays usable for messages within the limit.
+    client1
+        .send(SignalingMessage::CallOffer {
+            peer_id: client2.id().to_string(),
+            sdp: "sdp1".to_string(),
+        })
+        .await?;
+
+    let call_offer_messages = client2
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::CallOffer { .. })
+        })
+        .await;
+    assert_eq!(
+        call_offer_messages,
+        vec![SignalingMessage::CallOffer {
+            peer_id: client1.id().to_string(),
+            sdp: "sdp1".to_string(),
+        }],
+        "client2 should have received only the CallOffer within the size limit"
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn call_end_simultaneous() -> anyhow::Result<()> {
+    let test_app = TestApp::new().await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+
+    client1
+        .send(SignalingMessage::CallInvite {
+            peer_id: client2.id().to_string(),
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallAccept {
+            peer_id: client1.id().to_string(),
+        })
+        .await?;
+    client1
+        .send(SignalingMessage::CallOffer {
+            peer_id: client2.id().to_string(),
+            sdp: "sdp1".to_string(),
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallAnswer {
+            peer_id: client1.id().to_string(),
+            sdp: "sdp2".to_string(),
+        })
+        .await?;
+    client1.recv_until_timeout(Duration::from_millis(100)).await;
+    client2.recv_until_timeout(Duration::from_millis(100)).await;
+
+    // Both sides hang up before receiving the other's call end.
+    client1
+        .send(SignalingMessage::CallEnd {
+            peer_id: client2.id().to_string(),
+            reason: None,
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallEnd {
+            peer_id: client1.id().to_string(),
+            reason: None,
+        })
+        .await?;
+
+    let (client1_id, client2_id) = (client1.id().to_string(), client2.id().to_string());
+    for (client, peer_id) in [(&mut client1, client2_id), (&mut client2, client1_id)] {
+        let messages = client.recv_until_timeout(Duration::from_millis(100)).await;
+        assert_eq!(
+            messages,
+            vec![SignalingMessage::CallEnd {
+                peer_id,
+                reason: None
+            }],
+            "{} should have received exactly one CallEnd message",
+            client.id()
+        );
+    }
+
+    // The call is fully ended, so a new call can be established.
+    client2
+        .send(SignalingMessage::CallInvite {
+            peer_id: client1.id().to_string(),
+        })
+        .await?;
+
+    let call_invite_messages = client1
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::CallInvite { .. })
+        })
+        .await;
+    assert_eq!(
+        call_invite_messages,
+        vec![SignalingMessage::CallInvite {
+            peer_id: client2.id().to_string(),
+        }],
+        "client1 should have received exactly one CallInvite message"
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn call_max_duration() -> anyhow::Result<()> {
+    let test_app = TestApp::new_with_max_call_duration(Duration::from_secs(1)).await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+
+    client1
+        .send(SignalingMessage::CallInvite {
+            peer_id: client2.id().to_string(),
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallAccept {
+            peer_id: client1.id().to_string(),
+        })
+        .await?;
+    client1
+        .send(SignalingMessage::CallOffer {
+            peer_id: client2.id().to_string(),
+            sdp: "sdp1".to_string(),
+        })
+        .await?;
+    client2
+        .send(SignalingMessage::CallAnswer {
+            peer_id: client1.id().to_string(),
+            sdp: "sdp2".to_string(),
+        })
+        .await?;
+
+    // The maximum duration is shorter than the warning lead, so both peers are warned right away
+    // and the call is ended once the maximum duration is reached.
+    let (client1_id, client2_id) = (client1.id().to_string(), client2.id().to_string());
+    for (client, peer_id) in [(&mut client1, client2_id), (&mut client2, client1_id)] {
+        let messages = client
+            .recv_until_timeout_with_filter(Duration::from_secs(2), |m| {
+                matches!(
+                    m,
+                    SignalingMessage::CallDurationWarning { .. } | SignalingMessage::CallEnd { .. }
+                )
+            })
+            .await;
+        assert_eq!(
+            messages,
+            vec![
+                SignalingMessage::CallDurationWarning {
+                    peer_id: peer_id.clone(),
+                    remaining_secs: 1,
+                },
+                SignalingMessage::CallEnd {
+                    peer_id,
+                    reason: Some(CallEndReason::MaxDurationReached),
+                },
+            ],
+            "{} should have received a call duration warning followed by a CallEnd message",
+            client.id()
+        );
+    }
+
+    assert!(
+        !test_app.state().call_state.is_busy(client1.id(), "client3"),
+        "call should have been ended"
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn call_invite_not_permitted_by_policy() -> anyhow::Result<()> {
+    // Without an active VATSIM connection requirement, callsigns equal the client IDs, so every
+    // client is in its own region and only the allowed pair may call each other.
+    let test_app = TestApp::new_with_calls_config(CallsConfig {
+        policy: CallPolicy::SameRegion,
+        allowed_pairs: vec![("client1".to_string(), "client3".to_string())],
+        ..Default::default()
+    })
+    .await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 3).await;
+
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+    let mut client3 = clients.remove(0);
+
+    client1
+        .send(SignalingMessage::CallInvite {
+            peer_id: client2.id().to_string(),
+        })
+        .await?;
+
+    let call_reject_messages = client1
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::CallReject { .. })
+        })
+        .await;
+    assert_eq!(
+        call_reject_messages,
+        vec![SignalingMessage::CallReject {
+            peer_id: client2.id().to_string(),
+            reason: Some(CallRejectReason::NotPermitted),
+        }],
+        "client1 should have received exactly one CallReject message"
+    );
+
+    let call_invite_messages = client2
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::CallInvite { .. })
+        })
+        .await;
+    assert!(
+        call_invite_messages.is_empty(),
+        "client2 should have received no CallInvite message, but received: {:?}",
+        call_invite_messages
+    );
+
+    client1
+        .send(SignalingMessage::CallInvite {
+            peer_id: client3.id().to_string(),
+        })
+        .await?;
+
+    let call_invite_messages = client3
+        .recv_until_timeout_with_filter(Duration::from_millis(100), |m| {
+            matches!(m, SignalingMessage::CallInvite { .. })
+        })
+        .await;
+    assert_eq!(
+        call_invite_messages,
+        vec![SignalingMessage::CallInvite {
+            peer_id: client1.id().to_string(),
+        }],
+        "client3 should have received exactly one CallInvite message"
+    );
+
+    Ok(())
+}
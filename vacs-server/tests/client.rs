@@ -185,3 +185,36 @@ async fn control_messages() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn stale_client_reaped() -> anyhow::Result<()> {
+    let test_app = TestApp::new_with_presence_ttl(Duration::from_millis(300)).await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+    let mut client2 = clients.remove(1);
+
+    // client2 keeps sending messages, refreshing its presence, while client1 stays silent.
+    let mut client_disconnected = None;
+    for _ in 0..20 {
+        client2.send(SignalingMessage::ListClients).await?;
+        client_disconnected = client2
+            .recv_with_timeout_and_filter(Duration::from_millis(50), |m| {
+                matches!(m, SignalingMessage::ClientDisconnected { .. })
+            })
+            .await;
+        if client_disconnected.is_some() {
+            break;
+        }
+    }
+
+    assert_eq!(
+        client_disconnected,
+        Some(SignalingMessage::ClientDisconnected {
+            id: "client1".to_string()
+        }),
+        "client2 should have received a ClientDisconnected broadcast for client1"
+    );
+    assert!(test_app.state().get_client("client1").await.is_none());
+    assert!(test_app.state().get_client("client2").await.is_some());
+
+    Ok(())
+}
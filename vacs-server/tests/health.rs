@@ -0,0 +1,67 @@
+use reqwest::StatusCode;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use test_log::test;
+use vacs_server::test_utils::TestApp;
+use vacs_vatsim::ControllerInfo;
+use vacs_vatsim::data_feed::DataFeed;
+
+#[derive(Default)]
+struct ToggleDataFeed {
+    available: AtomicBool,
+}
+
+#[async_trait::async_trait]
+impl DataFeed for ToggleDataFeed {
+    async fn fetch_controller_info(&self) -> anyhow::Result<Vec<ControllerInfo>> {
+        if self.available.load(Ordering::Relaxed) {
+            Ok(Vec::new())
+        } else {
+            anyhow::bail!("Data feed unavailable")
+        }
+    }
+}
+
+async fn status(url: &str) -> StatusCode {
+    reqwest::get(url).await.expect("Request failed").status()
+}
+
+#[test(tokio::test)]
+async fn liveness() {
+    let test_app = TestApp::new().await;
+
+    assert_eq!(
+        status(&format!("{}/healthz", test_app.http_addr())).await,
+        StatusCode::OK
+    );
+}
+
+#[test(tokio::test)]
+async fn readiness_without_required_data_feed() {
+    let test_app = TestApp::new().await;
+
+    assert_eq!(
+        status(&format!("{}/readyz", test_app.http_addr())).await,
+        StatusCode::OK
+    );
+}
+
+#[test(tokio::test)]
+async fn readiness_waits_for_initial_data_feed_load() {
+    let data_feed = Arc::new(ToggleDataFeed::default());
+    let test_app = TestApp::new_with_data_feed(data_feed.clone()).await;
+    let url = format!("{}/readyz", test_app.http_addr());
+
+    // Give the controller update task a few failed cycles.
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(status(&url).await, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        status(&format!("{}/healthz", test_app.http_addr())).await,
+        StatusCode::OK
+    );
+
+    data_feed.available.store(true, Ordering::Relaxed);
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(status(&url).await, StatusCode::OK);
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+use test_log::test;
+use vacs_protocol::ws::{CallErrorReason, SignalingMessage};
+use vacs_server::metrics::setup_prometheus_metric_layer;
+use vacs_server::routes::create_metrics_app;
+use vacs_server::test_utils::{TestApp, TestClient, setup_n_test_clients};
+
+async fn relay(from: &mut TestClient, to: &mut TestClient, msg: SignalingMessage) {
+    let expected = std::mem::discriminant(&msg);
+    from.send(msg).await.expect("Failed to send message");
+    to.recv_with_timeout_and_filter(Duration::from_millis(100), |m| {
+        std::mem::discriminant(m) == expected
+    })
+    .await
+    .expect("Message was not relayed to peer");
+}
+
+#[test(tokio::test)]
+async fn scrape_contains_domain_metrics() -> anyhow::Result<()> {
+    // The recorder is installed globally, so all assertions live in a single test.
+    let (_prom_layer, prom_handle) = setup_prometheus_metric_layer();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let metrics_url = format!("http://{}/metrics", listener.local_addr()?);
+    tokio::spawn(async move {
+        axum::serve(listener, create_metrics_app(prom_handle))
+            .await
+            .unwrap();
+    });
+
+    let test_app = TestApp::new().await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+    let (id1, id2) = (client1.id().to_string(), client2.id().to_string());
+
+    relay(
+        &mut client1,
+        &mut client2,
+        SignalingMessage::CallInvite {
+            peer_id: id2.clone(),
+        },
+    )
+    .await;
+    relay(
+        &mut client2,
+        &mut client1,
+        SignalingMessage::CallAccept {
+            peer_id: id1.clone(),
+        },
+    )
+    .await;
+    relay(
+        &mut client1,
+        &mut client2,
+        SignalingMessage::CallOffer {
+            sdp: "sdp1".to_string(),
+            peer_id: id2.clone(),
+        },
+    )
+    .await;
+    relay(
+        &mut client2,
+        &mut client1,
+        SignalingMessage::CallAnswer {
+            sdp: "sdp2".to_string(),
+            peer_id: id1.clone(),
+        },
+    )
+    .await;
+    relay(
+        &mut client2,
+        &mut client1,
+        SignalingMessage::CallError {
+            peer_id: id1,
+            reason: CallErrorReason::WebrtcFailure,
+        },
+    )
+    .await;
+
+    let mut duplicate = TestClient::new(test_app.addr(), "client1", "token1").await?;
+    assert!(duplicate.login(|_, _| Ok(()), |_| Ok(())).await.is_err());
+
+    let scrape = reqwest::get(&metrics_url).await?.text().await?;
+    for expected in [
+        "vacs_clients_connected",
+        "vacs_clients_login_failures_total{reason=\"duplicate_id\"}",
+        "vacs_calls_initiated_total",
+        "vacs_calls_failed_total{reason=\"webrtc_failure\"}",
+        "vacs_calls_active",
+    ] {
+        assert!(
+            scrape.contains(expected),
+            "metric {expected} missing from scrape:\n{scrape}"
+        );
+    }
+
+    Ok(())
+}
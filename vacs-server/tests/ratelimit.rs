@@ -0,0 +1,171 @@
+use nonzero_ext::nonzero;
+use pretty_assertions::assert_matches;
+use std::time::Duration;
+use test_log::test;
+use vacs_protocol::ws::{ErrorReason, SignalingMessage};
+use vacs_server::ratelimit::{Policy, RateLimiters, RateLimitersConfig};
+use vacs_server::test_utils::{TestApp, TestClient, setup_n_test_clients};
+
+fn message_rate_limiters() -> RateLimiters {
+    RateLimiters::from(RateLimitersConfig {
+        call_invite: Policy::default().disabled(),
+        call_invite_per_minute: 0,
+        message: Policy::new(60, nonzero!(2u32)),
+        message_per_minute: 0,
+        ..Default::default()
+    })
+}
+
+async fn expect_client_list(client: &mut TestClient) -> bool {
+    client
+        .send(SignalingMessage::ListClients)
+        .await
+        .expect("Failed to send message");
+    client
+        .recv_with_timeout_and_filter(Duration::from_millis(100), |msg| {
+            matches!(
+                msg,
+                SignalingMessage::ClientList { .. } | SignalingMessage::Error { .. }
+            )
+        })
+        .await
+        .is_some_and(|msg| matches!(msg, SignalingMessage::ClientList { .. }))
+}
+
+#[test(tokio::test)]
+async fn message_rate_limit_is_per_cid() {
+    let test_app = TestApp::new_with_rate_limiters(message_rate_limiters()).await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+
+    assert!(expect_client_list(&mut client1).await);
+    assert!(expect_client_list(&mut client1).await);
+
+    client1
+        .send(SignalingMessage::ListClients)
+        .await
+        .expect("Failed to send message");
+    let message = client1
+        .recv_with_timeout_and_filter(Duration::from_millis(100), |msg| {
+            matches!(msg, SignalingMessage::Error { .. })
+        })
+        .await
+        .expect("Message exceeding limit was not rejected");
+    assert_matches!(
+        message,
+        SignalingMessage::Error {
+            reason: ErrorReason::RateLimited { retry_after_secs },
+            peer_id: None,
+        } if retry_after_secs > 0 && retry_after_secs <= 60
+    );
+
+    // client2 connects from the same IP, but must not be affected by client1's limit.
+    assert!(expect_client_list(&mut client2).await);
+    assert!(expect_client_list(&mut client2).await);
+}
+
+#[test(tokio::test)]
+async fn message_rate_limit_without_capabilities() {
+    let test_app = TestApp::new_with_rate_limiters(message_rate_limiters()).await;
+    let mut client = TestClient::new(test_app.addr(), "client1", "token1")
+        .await
+        .expect("Failed to create test client")
+        .with_capabilities(vec![]);
+    client
+        .login(|_, _| Ok(()), |_| Ok(()))
+        .await
+        .expect("Failed to login");
+
+    assert!(expect_client_list(&mut client).await);
+    assert!(expect_client_list(&mut client).await);
+
+    client
+        .send(SignalingMessage::ListClients)
+        .await
+        .expect("Failed to send message");
+    let message = client
+        .recv_with_timeout_and_filter(Duration::from_millis(100), |msg| {
+            matches!(msg, SignalingMessage::Error { .. })
+        })
+        .await
+        .expect("Message exceeding limit was not rejected");
+    // Rate limits are reported the same way regardless of the negotiated capabilities.
+    assert_matches!(
+        message,
+        SignalingMessage::Error {
+            reason: ErrorReason::RateLimited { .. },
+            peer_id: None,
+        }
+    );
+}
+
+#[test(tokio::test)]
+async fn rate_limited_call_message_contains_peer_id() {
+    let test_app = TestApp::new_with_rate_limiters(message_rate_limiters()).await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+    let mut client1 = clients.remove(0);
+
+    for _ in 0..2 {
+        client1
+            .send(SignalingMessage::ListClients)
+            .await
+            .expect("Failed to send message");
+    }
+
+    client1
+        .send(SignalingMessage::CallInvite {
+            peer_id: "client2".to_string(),
+        })
+        .await
+        .expect("Failed to send message");
+    client1
+        .recv_with_timeout_and_filter(Duration::from_millis(100), |msg| {
+            matches!(
+                msg,
+                SignalingMessage::Error {
+                    reason: ErrorReason::RateLimited { .. },
+                    peer_id: Some(peer_id),
+                } if peer_id == "client2"
+            )
+        })
+        .await
+        .expect("Rate limit error with peer ID was not received");
+}
+
+#[test(tokio::test)]
+async fn call_end_and_ice_are_not_rate_limited() {
+    let test_app = TestApp::new_with_rate_limiters(message_rate_limiters()).await;
+    let mut clients = setup_n_test_clients(test_app.addr(), 2).await;
+    let mut client1 = clients.remove(0);
+    let mut client2 = clients.remove(0);
+
+    assert!(expect_client_list(&mut client1).await);
+    assert!(expect_client_list(&mut client1).await);
+
+    let exempt = |peer_id: &str| {
+        [
+            SignalingMessage::CallIceCandidate {
+                candidate: "candidate".to_string(),
+                peer_id: peer_id.to_string(),
+            },
+            SignalingMessage::CallIceEndOfCandidates {
+                peer_id: peer_id.to_string(),
+            },
+            SignalingMessage::CallEnd {
+                peer_id: peer_id.to_string(),
+                reason: None,
+            },
+        ]
+    };
+    for (message, expected) in exempt("client2").into_iter().zip(exempt("client1")) {
+        client1.send(message).await.expect("Failed to send message");
+        client2
+            .recv_with_timeout_and_filter(Duration::from_millis(100), |msg| msg == &expected)
+            .await
+            .expect("Exempt message was not forwarded");
+    }
+
+    // Other messages are still rejected
+    assert!(!expect_client_list(&mut client1).await);
+}
@@ -1,6 +1,8 @@
 use futures_util::{SinkExt, StreamExt};
 use test_log::test;
 use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
 use vacs_server::test_utils::{TestApp, connect_to_websocket};
 
 #[test(tokio::test)]
@@ -20,3 +22,37 @@ async fn websocket_ping_pong() {
         _ => panic!("Did not receive pong message"),
     }
 }
+
+#[test(tokio::test)]
+async fn websocket_does_not_negotiate_compression() {
+    let test_app = TestApp::new().await;
+    let mut request = test_app
+        .addr()
+        .into_client_request()
+        .expect("Failed to build WebSocket request");
+    request.headers_mut().insert(
+        "Sec-WebSocket-Extensions",
+        HeaderValue::from_static("permessage-deflate; client_max_window_bits"),
+    );
+
+    // tungstenite rejects frames with RSV1 set, so accepting the offer would break the connection
+    // on the first compressed message.
+    let (mut ws_stream, response) = tokio_tungstenite::connect_async(request)
+        .await
+        .expect("Failed to connect to WebSocket server");
+    assert!(
+        response.headers().get("Sec-WebSocket-Extensions").is_none(),
+        "Server negotiated an extension: {response:?}"
+    );
+
+    ws_stream
+        .send(tungstenite::Message::Ping(tungstenite::Bytes::from_static(
+            b"ping",
+        )))
+        .await
+        .expect("Failed to send ping message");
+    match ws_stream.next().await {
+        Some(Ok(tungstenite::Message::Pong(_))) => (),
+        _ => panic!("Did not receive pong message"),
+    }
+}
@@ -3,8 +3,117 @@ pub mod mock;
 
 use crate::error::SignalingError;
 use async_trait::async_trait;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
 
 #[async_trait]
 pub trait TokenProvider: Send + Sync + 'static {
     async fn get_token(&self) -> Result<String, SignalingError>;
+
+    /// Discards a cached token after it was rejected by the server, so the next call to
+    /// [`TokenProvider::get_token`] retrieves a fresh one.
+    async fn invalidate_token(&self) {}
+}
+
+/// Auth token along with the instant it expires at, if known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpiringToken {
+    pub token: String,
+    /// Tokens without a known expiry are never reused.
+    pub expires_at: Option<Instant>,
+}
+
+/// Caches an auth token, proactively refreshing it before it expires.
+///
+/// Allows [`TokenProvider`] implementations to hand out a valid token for every login, e.g. for
+/// all attempts while reconnecting during a long session, without retrieving a new one each time.
+#[derive(Debug)]
+pub struct TokenCache {
+    refresh_leeway: Duration,
+    token: Mutex<Option<ExpiringToken>>,
+}
+
+impl TokenCache {
+    /// Creates an empty cache, refreshing tokens `refresh_leeway` before they expire.
+    pub fn new(refresh_leeway: Duration) -> Self {
+        Self {
+            refresh_leeway,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached token, calling `refresh` to retrieve a new one if none is cached or the
+    /// cached token expires within the refresh leeway.
+    ///
+    /// Concurrent callers wait for a single refresh instead of all retrieving a token.
+    pub async fn get_or_refresh<F, Fut>(&self, refresh: F) -> Result<String, SignalingError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<ExpiringToken, SignalingError>>,
+    {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref()
+            && token
+                .expires_at
+                .is_some_and(|expires_at| Instant::now() + self.refresh_leeway < expires_at)
+        {
+            tracing::trace!("Using cached auth token");
+            return Ok(token.token.clone());
+        }
+
+        tracing::debug!("Auth token missing or about to expire, refreshing");
+        let token = refresh().await?;
+        *cached = Some(token.clone());
+        Ok(token.token)
+    }
+
+    pub async fn invalidate(&self) {
+        self.token.lock().await.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use test_log::test;
+
+    async fn get(cache: &TokenCache, refreshes: &AtomicUsize, ttl: Option<Duration>) -> String {
+        cache
+            .get_or_refresh(|| async {
+                let n = refreshes.fetch_add(1, Ordering::Relaxed) + 1;
+                Ok(ExpiringToken {
+                    token: format!("token{n}"),
+                    expires_at: ttl.map(|ttl| Instant::now() + ttl),
+                })
+            })
+            .await
+            .unwrap()
+    }
+
+    #[test(tokio::test)]
+    async fn refreshes_before_expiry() {
+        let cache = TokenCache::new(Duration::from_millis(50));
+        let refreshes = AtomicUsize::new(0);
+        let ttl = Some(Duration::from_millis(100));
+
+        assert_eq!(get(&cache, &refreshes, ttl).await, "token1");
+        assert_eq!(get(&cache, &refreshes, ttl).await, "token1");
+
+        // Within the refresh leeway, but not expired yet
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(get(&cache, &refreshes, ttl).await, "token2");
+
+        cache.invalidate().await;
+        assert_eq!(get(&cache, &refreshes, ttl).await, "token3");
+    }
+
+    #[test(tokio::test)]
+    async fn never_reuses_tokens_without_expiry() {
+        let cache = TokenCache::new(Duration::ZERO);
+        let refreshes = AtomicUsize::new(0);
+
+        assert_eq!(get(&cache, &refreshes, None).await, "token1");
+        assert_eq!(get(&cache, &refreshes, None).await, "token2");
+    }
 }
@@ -1,29 +1,66 @@
-use crate::auth::TokenProvider;
+use crate::auth::{ExpiringToken, TokenCache, TokenProvider};
 use crate::error::SignalingError;
 use async_trait::async_trait;
-use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 pub struct MockTokenProvider {
     client_id: usize,
     delay: Option<Duration>,
+    expiry: Option<Duration>,
+    cache: Arc<TokenCache>,
+    refreshes: Arc<AtomicUsize>,
 }
 
 impl MockTokenProvider {
     pub fn new(client_id: usize, delay: Option<Duration>) -> Self {
-        Self { client_id, delay }
+        Self {
+            client_id,
+            delay,
+            expiry: None,
+            cache: Arc::new(TokenCache::new(Duration::ZERO)),
+            refreshes: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Issues tokens expiring after `expiry`, caching them until they are about to expire.
+    pub fn with_expiry(mut self, expiry: Duration, refresh_leeway: Duration) -> Self {
+        self.expiry = Some(expiry);
+        self.cache = Arc::new(TokenCache::new(refresh_leeway));
+        self
+    }
+
+    /// Number of tokens issued so far.
+    pub fn refreshes(&self) -> usize {
+        self.refreshes.load(Ordering::Relaxed)
     }
 }
 
 #[async_trait]
 impl TokenProvider for MockTokenProvider {
     async fn get_token(&self) -> Result<String, SignalingError> {
-        if let Some(delay) = self.delay {
-            tokio::time::sleep(delay).await;
-        }
-        if self.client_id == usize::MAX {
-            return Ok("".to_string());
-        }
-        Ok(format!("token{}", self.client_id))
+        self.cache
+            .get_or_refresh(|| async {
+                if let Some(delay) = self.delay {
+                    tokio::time::sleep(delay).await;
+                }
+                self.refreshes.fetch_add(1, Ordering::Relaxed);
+                let token = if self.client_id == usize::MAX {
+                    "".to_string()
+                } else {
+                    format!("token{}", self.client_id)
+                };
+                Ok(ExpiringToken {
+                    token,
+                    expires_at: self.expiry.map(|expiry| Instant::now() + expiry),
+                })
+            })
+            .await
+    }
+
+    async fn invalidate_token(&self) {
+        self.cache.invalidate().await;
     }
 }
@@ -1,5 +1,6 @@
 use crate::auth::TokenProvider;
 use crate::error::{SignalingError, SignalingRuntimeError, UntilInstant};
+use crate::latency::LatencyTracker;
 use crate::matcher::ResponseMatcher;
 use crate::transport::{SignalingReceiver, SignalingSender, SignalingTransport};
 use parking_lot::Mutex;
@@ -7,17 +8,66 @@ use rand::{Rng, SeedableRng};
 use std::collections::VecDeque;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
+use tokio::sync::mpsc::error::SendTimeoutError;
 use tokio::sync::{broadcast, mpsc, watch};
 use tokio::task::{JoinHandle, JoinSet};
 use tokio_tungstenite::tungstenite;
 use tokio_util::sync::CancellationToken;
 use tracing::{Instrument, instrument};
 use vacs_protocol::VACS_PROTOCOL_VERSION;
-use vacs_protocol::ws::{ClientInfo, SignalingMessage};
+use vacs_protocol::ws::{
+    Capability, ClientInfo, EncodedMessage, LoginFailureDetails, LoginFailureReason,
+    SignalingMessage,
+};
 
 const BROADCAST_CHANNEL_SIZE: usize = 100;
 const SEND_CHANNEL_SIZE: usize = 100;
+/// Maximum time [`SignalingClient::send`] waits for space in a full send queue before giving up.
+const SEND_QUEUE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Parameters controlling how a [`SignalingClient`] reconnects after a fatal error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectConfig {
+    /// Maximum number of attempts per reconnect, `0` disables automatic reconnects.
+    pub max_tries: u8,
+    /// Base delay of the exponential backoff between attempts.
+    pub retry_base: Duration,
+    /// Upper bound of the delay between attempts.
+    pub retry_cap: Duration,
+    /// Number of reconnects allowed within `gate_window` before further reconnects are
+    /// suppressed. Values below 1 are treated as 1.
+    pub gate_max_in_window: u32,
+    /// Sliding window in which reconnects are counted.
+    pub gate_window: Duration,
+    /// Duration reconnects stay suppressed once the limit has been hit.
+    pub gate_cooldown: Duration,
+    /// Whether messages sent while reconnecting are queued and delivered once logged in again,
+    /// instead of failing with [`SignalingRuntimeError::Disconnected`]. Call setup and ICE
+    /// messages are never queued, as the server drops the call setup of disconnected clients.
+    pub offline_queue: bool,
+    /// Maximum number of queued messages, further messages fail as if queueing was disabled.
+    pub offline_queue_max_len: usize,
+    /// Maximum age of queued messages, older messages are dropped instead of being delivered.
+    pub offline_queue_max_age: Duration,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            max_tries: 8,
+            retry_base: Duration::from_millis(100),
+            retry_cap: Duration::from_secs(5),
+            gate_max_in_window: 3,
+            gate_window: Duration::from_secs(60),
+            gate_cooldown: Duration::from_secs(120),
+            offline_queue: false,
+            offline_queue_max_len: 16,
+            offline_queue_max_age: Duration::from_secs(5),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum State {
@@ -38,6 +88,36 @@ pub enum State {
     LoggedIn,
 }
 
+/// Cause of the last transition of a [`SignalingClient`] to [`State::Disconnected`].
+#[derive(Debug, Clone)]
+pub enum DisconnectCause {
+    /// The disconnect was requested via [`SignalingClient::disconnect`].
+    Requested,
+    /// The connection was established, but the server rejected the login.
+    LoginFailed(LoginFailureReason),
+    /// The connection was established, but the server does not support the client's protocol
+    /// version. The client needs to be updated before it can connect again.
+    IncompatibleProtocolVersion(LoginFailureDetails),
+    /// The connection was closed due to a fatal [`SignalingRuntimeError`], either reported by
+    /// one of the processing tasks or received from the server.
+    Error(SignalingRuntimeError),
+    /// The connection was closed for any other reason, e.g. an unexpected response during login.
+    Other(String),
+}
+
+impl From<&SignalingError> for DisconnectCause {
+    fn from(err: &SignalingError) -> Self {
+        match err {
+            SignalingError::LoginError(reason) => DisconnectCause::LoginFailed(reason.clone()),
+            SignalingError::IncompatibleProtocolVersion(details) => {
+                DisconnectCause::IncompatibleProtocolVersion(details.clone())
+            }
+            SignalingError::Runtime(err) => DisconnectCause::Error(err.clone()),
+            other => DisconnectCause::Other(other.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum SignalingEvent {
     /// Emitted after the [`SignalingClient`] successfully connected to the server, including authentication.
@@ -48,6 +128,30 @@ pub enum SignalingEvent {
     /// Emitted for every [`SignalingRuntimeError`] handled by the [`SignalingClientInner`].
     /// This includes issues during transmission or other errors received from the server.
     Error(SignalingRuntimeError),
+    /// Emitted after a connected [`SignalingClient`] transitioned to [`State::Disconnected`],
+    /// containing the cause of the disconnect.
+    Disconnected(DisconnectCause),
+    /// Emitted before every attempt of an automatic reconnect, `attempt` starting at 1 and counting
+    /// up to `max_attempts`. Passed to the event callback directly, as the supervisor task is busy
+    /// reconnecting and does not process broadcast events until it finished.
+    Reconnecting { attempt: u8, max_attempts: u8 },
+}
+
+/// Uptime and stability of the current signaling session, which spans automatic reconnects and
+/// ends with an explicit [`SignalingClient::disconnect`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionStats {
+    /// Instant of the first successful login of the session, `None` if no session was started.
+    pub connected_since: Option<Instant>,
+    /// Number of successful automatic reconnects during the session.
+    pub reconnects: u32,
+}
+
+impl SessionStats {
+    /// Returns the time elapsed since the first successful login of the session.
+    pub fn uptime(&self) -> Option<Duration> {
+        self.connected_since.map(|since| since.elapsed())
+    }
 }
 
 type BoxFutUnit = Pin<Box<dyn Future<Output = ()> + Send>>;
@@ -56,7 +160,21 @@ type OnEventCb = Arc<dyn Fn(SignalingEvent) -> BoxFutUnit + Send + Sync>;
 #[derive(Clone)]
 pub struct SignalingClient<ST: SignalingTransport, TP: TokenProvider> {
     inner: Arc<SignalingClientInner<ST, TP>>,
-    supervisor_task: Arc<JoinHandle<()>>,
+    /// Shared by all clones, shutting the client down once the last clone is dropped.
+    _shutdown_guard: Arc<ShutdownGuard>,
+}
+
+/// Stops the supervisor task of a [`SignalingClient`] when dropped.
+struct ShutdownGuard {
+    shutdown_token: CancellationToken,
+    supervisor_task: JoinHandle<()>,
+}
+
+impl Drop for ShutdownGuard {
+    fn drop(&mut self) {
+        self.shutdown_token.cancel();
+        self.supervisor_task.abort();
+    }
 }
 
 impl<ST: SignalingTransport, TP: TokenProvider> SignalingClient<ST, TP> {
@@ -66,7 +184,7 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClient<ST, TP> {
         on_event: F,
         shutdown_token: CancellationToken,
         login_timeout: Duration,
-        reconnect_max_tries: u8,
+        reconnect_config: ReconnectConfig,
         handle: &tokio::runtime::Handle,
     ) -> Self
     where
@@ -79,17 +197,20 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClient<ST, TP> {
             Arc::new(move |e| Box::pin(on_event(e))),
             shutdown_token,
             login_timeout,
-            reconnect_max_tries,
+            reconnect_config,
         ));
 
         let inner_clone = inner.clone();
-        let supervisor_task = Arc::new(handle.spawn(async move {
+        let supervisor_task = handle.spawn(async move {
             inner_clone.supervisor_task().await;
-        }));
+        });
 
         Self {
+            _shutdown_guard: Arc::new(ShutdownGuard {
+                shutdown_token: inner.shutdown_token.clone(),
+                supervisor_task,
+            }),
             inner,
-            supervisor_task,
         }
     }
 
@@ -107,18 +228,82 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClient<ST, TP> {
         self.inner.state()
     }
 
+    /// Returns the cause of the last disconnect, or `None` if the client was never disconnected
+    /// after connecting.
+    pub fn last_disconnect_cause(&self) -> Option<DisconnectCause> {
+        self.inner.last_disconnect_cause()
+    }
+
+    /// Returns the capabilities negotiated with the server during the last login.
+    pub fn capabilities(&self) -> Vec<Capability> {
+        self.inner.capabilities.lock().clone()
+    }
+
+    /// Returns whether the server agreed to use the given capability for the current session.
+    pub fn has_capability(&self, capability: Capability) -> bool {
+        self.inner.capabilities.lock().contains(&capability)
+    }
+
+    /// Returns the uptime and reconnect count of the current session.
+    pub fn session_stats(&self) -> SessionStats {
+        *self.inner.session_stats_tx.borrow()
+    }
+
+    /// Subscribes to a watch containing the stats of the current session, updated whenever a
+    /// session starts, ends or an automatic reconnect succeeds.
+    pub fn subscribe_session_stats(&self) -> watch::Receiver<SessionStats> {
+        self.inner.session_stats_tx.subscribe()
+    }
+
+    /// Subscribes to a watch containing the last measured round-trip time to the server, `None`
+    /// while disconnected or before the first measurement.
+    pub fn subscribe_latency(&self) -> watch::Receiver<Option<Duration>> {
+        self.inner.latency.subscribe()
+    }
+
+    /// Returns the last measured round-trip time to the server.
+    pub fn latency(&self) -> Option<Duration> {
+        self.inner.latency.latency()
+    }
+
+    /// Sets the interval between latency measurements, `None` disables them.
+    ///
+    /// Takes effect on the next connect.
+    pub fn set_latency_interval(&self, interval: Option<Duration>) {
+        *self.inner.latency_interval.lock() = interval.filter(|interval| !interval.is_zero());
+    }
+
     pub async fn connect(&self) -> Result<(), SignalingError> {
         self.inner.connect().await
     }
 
     pub async fn disconnect(&self) {
-        self.inner.disconnect(true).await;
+        self.inner.disconnect(DisconnectCause::Requested).await;
     }
 
     pub async fn send(&self, msg: SignalingMessage) -> Result<(), SignalingError> {
         self.inner.send(msg).await
     }
 
+    /// Sends a message to a peer and waits for the correlated response.
+    ///
+    /// Only responses from the peer the message is addressed to that satisfy `predicate` are
+    /// matched, so concurrent requests to different peers never pick up each other's replies.
+    /// The response is still broadcast to all subscribers as usual.
+    ///
+    /// Returns a [`SignalingError::ProtocolError`] if `msg` is not addressed to a peer.
+    pub async fn send_and_await<F>(
+        &self,
+        msg: SignalingMessage,
+        predicate: F,
+        timeout: Duration,
+    ) -> Result<SignalingMessage, SignalingError>
+    where
+        F: Fn(&SignalingMessage) -> bool + Send + Sync + 'static,
+    {
+        self.inner.send_and_await(msg, predicate, timeout).await
+    }
+
     pub async fn recv(&self) -> Result<SignalingMessage, SignalingError> {
         self.inner.recv().await
     }
@@ -135,13 +320,6 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClient<ST, TP> {
     }
 }
 
-impl<ST: SignalingTransport, TP: TokenProvider> Drop for SignalingClient<ST, TP> {
-    fn drop(&mut self) {
-        self.inner.shutdown_token.cancel();
-        self.supervisor_task.abort();
-    }
-}
-
 #[derive(Clone)]
 struct SignalingClientInner<ST: SignalingTransport, TP: TokenProvider> {
     transport: ST,
@@ -151,6 +329,7 @@ struct SignalingClientInner<ST: SignalingTransport, TP: TokenProvider> {
 
     state_tx: watch::Sender<State>,
     state_rx: watch::Receiver<State>,
+    last_disconnect_cause: Arc<Mutex<Option<DisconnectCause>>>,
 
     disconnect_token: Arc<Mutex<CancellationToken>>,
     shutdown_token: CancellationToken,
@@ -161,8 +340,16 @@ struct SignalingClientInner<ST: SignalingTransport, TP: TokenProvider> {
     send_tx: Arc<Mutex<Option<mpsc::Sender<tungstenite::Message>>>>,
 
     login_timeout: Duration,
-    reconnect_max_tries: u8,
+    reconnect_config: ReconnectConfig,
     reconnect_gate: Arc<Mutex<ReconnectGate>>,
+    reconnecting: Arc<AtomicBool>,
+    offline_queue: Arc<Mutex<VecDeque<(Instant, SignalingMessage)>>>,
+
+    latency: LatencyTracker,
+    latency_interval: Arc<Mutex<Option<Duration>>>,
+
+    capabilities: Arc<Mutex<Vec<Capability>>>,
+    session_stats_tx: watch::Sender<SessionStats>,
 
     worker_tasks: Arc<Mutex<JoinSet<()>>>,
 }
@@ -175,7 +362,7 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
         on_event: OnEventCb,
         shutdown_token: CancellationToken,
         login_timeout: Duration,
-        reconnect_max_tries: u8,
+        reconnect_config: ReconnectConfig,
     ) -> Self {
         let (state_tx, state_rx) = watch::channel(State::Disconnected);
         Self {
@@ -186,6 +373,7 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
 
             state_tx,
             state_rx,
+            last_disconnect_cause: Arc::new(Mutex::new(None)),
 
             disconnect_token: Arc::new(Mutex::new(shutdown_token.child_token())),
             shutdown_token,
@@ -196,8 +384,16 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
             send_tx: Arc::new(Mutex::new(None)),
 
             login_timeout,
-            reconnect_max_tries,
-            reconnect_gate: Arc::new(Mutex::new(ReconnectGate::default())),
+            reconnect_config,
+            reconnect_gate: Arc::new(Mutex::new(ReconnectGate::from(&reconnect_config))),
+            reconnecting: Arc::new(AtomicBool::new(false)),
+            offline_queue: Arc::new(Mutex::new(VecDeque::new())),
+
+            latency: LatencyTracker::new(),
+            latency_interval: Arc::new(Mutex::new(None)),
+
+            capabilities: Arc::new(Mutex::new(Vec::new())),
+            session_stats_tx: watch::channel(SessionStats::default()).0,
 
             worker_tasks: Arc::new(Mutex::new(JoinSet::new())),
         }
@@ -221,25 +417,41 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
         *self.state_rx.borrow()
     }
 
+    fn last_disconnect_cause(&self) -> Option<DisconnectCause> {
+        self.last_disconnect_cause.lock().clone()
+    }
+
     #[instrument(level = "debug", skip_all)]
-    async fn disconnect(&self, requested: bool) {
-        if self.state() != State::Disconnected {
-            tracing::trace!(?requested, "Sending logout message before disconnecting");
+    async fn disconnect(&self, cause: DisconnectCause) {
+        let was_connected = self.state() != State::Disconnected;
+        if was_connected {
+            tracing::trace!(?cause, "Sending logout message before disconnecting");
             if let Err(err) = self.send(SignalingMessage::Logout).await {
                 tracing::warn!(?err, "Failed to send Logout message before disconnecting");
             }
         }
         self.disconnect_token.lock().cancel();
+        *self.last_disconnect_cause.lock() = Some(cause.clone());
         self.set_state(State::Disconnected);
         self.cleanup().await;
-        if requested {
+        if matches!(cause, DisconnectCause::Requested) {
             self.reconnect_gate.lock().clear();
+            self.stop_reconnecting();
+            self.session_stats_tx.send_replace(SessionStats::default());
+        }
+        if was_connected
+            && let Err(err) = self.broadcast_tx.send(SignalingEvent::Disconnected(cause))
+        {
+            tracing::warn!(?err, "Failed to broadcast disconnected event");
         }
     }
 
     #[instrument(level = "debug", skip(self), err)]
     pub async fn send(&self, msg: SignalingMessage) -> Result<(), SignalingError> {
         match self.state() {
+            State::Disconnected | State::Connected if self.try_queue_offline(&msg) => {
+                return Ok(());
+            }
             State::Disconnected => {
                 tracing::warn!("Tried to send message before signaling client was started");
                 return Err(SignalingError::Runtime(
@@ -263,15 +475,122 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
         };
 
         tracing::debug!("Sending message to send channel");
-        let serialized = SignalingMessage::serialize(&msg).map_err(|err| {
-            tracing::warn!(?err, "Failed to serialize message");
-            SignalingError::Runtime(SignalingRuntimeError::SerializationError(err.to_string()))
-        })?;
+        let encoded =
+            SignalingMessage::encode(&msg, self.transport.wire_format()).map_err(|err| {
+                tracing::warn!(?err, "Failed to serialize message");
+                SignalingError::Runtime(SignalingRuntimeError::SerializationError(err.to_string()))
+            })?;
+        let message = match encoded {
+            EncodedMessage::Text(text) => tungstenite::Message::from(text),
+            EncodedMessage::Binary(data) => tungstenite::Message::from(data),
+        };
 
-        send_tx
-            .send(tungstenite::Message::from(serialized))
-            .await
-            .map_err(|_| SignalingError::Runtime(SignalingRuntimeError::Disconnected(None)))
+        match send_tx.send_timeout(message, SEND_QUEUE_TIMEOUT).await {
+            Ok(()) => Ok(()),
+            Err(SendTimeoutError::Timeout(_)) => {
+                tracing::warn!("Send queue is full, dropping message");
+                Err(SignalingError::Runtime(
+                    SignalingRuntimeError::SendQueueFull,
+                ))
+            }
+            Err(SendTimeoutError::Closed(_)) => Err(SignalingError::Runtime(
+                SignalingRuntimeError::Disconnected(None),
+            )),
+        }
+    }
+
+    /// Queues a message sent while reconnecting, to be delivered once logged in again.
+    ///
+    /// Returns `false` if the message cannot be queued, because queueing is disabled, no reconnect
+    /// is in progress, the queue is full or the message is part of the login flow or call setup.
+    fn try_queue_offline(&self, msg: &SignalingMessage) -> bool {
+        if !self.reconnect_config.offline_queue
+            || !self.reconnecting.load(Ordering::Relaxed)
+            || matches!(
+                msg,
+                SignalingMessage::Login { .. }
+                    | SignalingMessage::Logout
+                    | SignalingMessage::CallInvite { .. }
+                    | SignalingMessage::CallAccept { .. }
+                    | SignalingMessage::CallOffer { .. }
+                    | SignalingMessage::CallAnswer { .. }
+                    | SignalingMessage::CallIceCandidate { .. }
+                    | SignalingMessage::CallIceEndOfCandidates { .. }
+            )
+        {
+            return false;
+        }
+
+        let mut queue = self.offline_queue.lock();
+        if queue.len() >= self.reconnect_config.offline_queue_max_len {
+            tracing::warn!("Offline queue is full, not queueing message");
+            return false;
+        }
+
+        tracing::debug!("Reconnecting, queueing message until logged in again");
+        queue.push_back((Instant::now(), msg.clone()));
+        true
+    }
+
+    /// Sends all messages queued while reconnecting, dropping those exceeding the maximum age.
+    #[instrument(level = "debug", skip(self))]
+    async fn flush_offline_queue(&self) {
+        let queued = std::mem::take(&mut *self.offline_queue.lock());
+        if queued.is_empty() {
+            return;
+        }
+
+        tracing::debug!(count = queued.len(), "Flushing offline queue");
+        for (queued_at, msg) in queued {
+            if queued_at.elapsed() > self.reconnect_config.offline_queue_max_age {
+                tracing::warn!(?msg, "Dropping queued message exceeding maximum age");
+                continue;
+            }
+            if let Err(err) = self.send(msg).await {
+                tracing::warn!(?err, "Failed to send queued message");
+            }
+        }
+    }
+
+    /// Marks the current reconnect as finished, dropping all messages that could not be delivered.
+    fn stop_reconnecting(&self) {
+        self.reconnecting.store(false, Ordering::Relaxed);
+        let dropped = std::mem::take(&mut *self.offline_queue.lock());
+        if !dropped.is_empty() {
+            tracing::warn!(
+                count = dropped.len(),
+                "Dropping undelivered queued messages"
+            );
+        }
+    }
+
+    #[instrument(level = "debug", skip(self, predicate), err)]
+    async fn send_and_await<F>(
+        &self,
+        msg: SignalingMessage,
+        predicate: F,
+        timeout: Duration,
+    ) -> Result<SignalingMessage, SignalingError>
+    where
+        F: Fn(&SignalingMessage) -> bool + Send + Sync + 'static,
+    {
+        let peer_id = msg
+            .peer_id()
+            .ok_or_else(|| {
+                SignalingError::ProtocolError(
+                    "Cannot await response for message without peer ID".to_string(),
+                )
+            })?
+            .to_string();
+
+        // Register before sending, otherwise a fast response could arrive before we're listening.
+        let pending = self
+            .matcher
+            .register(move |m| m.peer_id() == Some(peer_id.as_str()) && predicate(m));
+        self.send(msg).await?;
+
+        tracing::debug!("Waiting for correlated response");
+        pending.wait(timeout).await
     }
 
     #[instrument(level = "debug", skip(self), err)]
@@ -333,17 +652,44 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
         self.send(SignalingMessage::Login {
             token: token.to_string(),
             protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+            capabilities: Capability::ALL.to_vec(),
         })
         .await?;
 
         tracing::debug!("Awaiting authentication response from server");
         match self.recv_with_timeout(self.login_timeout).await? {
-            SignalingMessage::ClientInfo { own, info } if own => {
-                tracing::info!(?info, "Login successful, received own client info");
+            SignalingMessage::ClientInfo {
+                own,
+                info,
+                capabilities,
+            } if own => {
+                tracing::info!(
+                    ?info,
+                    ?capabilities,
+                    "Login successful, received own client info"
+                );
+                // Servers predating capability negotiation do not send any optional messages
+                *self.capabilities.lock() = capabilities.unwrap_or_default();
                 Ok(info)
             }
-            SignalingMessage::LoginFailure { reason } => {
+            SignalingMessage::LoginFailure {
+                reason: LoginFailureReason::IncompatibleProtocolVersion,
+                details,
+            } => {
+                tracing::warn!(?details, "Login failed, protocol version is incompatible");
+                Err(SignalingError::IncompatibleProtocolVersion(
+                    details.unwrap_or_default(),
+                ))
+            }
+            SignalingMessage::LoginFailure { reason, .. } => {
                 tracing::warn!(?reason, "Login failed");
+                if matches!(
+                    reason,
+                    LoginFailureReason::Unauthorized | LoginFailureReason::InvalidCredentials
+                ) {
+                    tracing::debug!("Invalidating rejected auth token");
+                    self.token_provider.invalidate_token().await;
+                }
                 Err(SignalingError::LoginError(reason))
             }
             SignalingMessage::Error { reason, peer_id } => {
@@ -364,7 +710,8 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
     #[instrument(level = "debug", skip(self), err)]
     pub async fn connect(&self) -> Result<(), SignalingError> {
         tracing::trace!("Connecting to signaling server");
-        let (sender, receiver) = self.transport.connect().await?;
+        let (sender, mut receiver) = self.transport.connect().await?;
+        receiver.track_latency(self.latency.clone());
 
         let (send_tx, send_rx) = mpsc::channel::<tungstenite::Message>(SEND_CHANNEL_SIZE);
         tracing::trace!("Successfully connected to signaling server, starting worker tasks");
@@ -397,6 +744,18 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
                 ),
                 &rt_handle,
             );
+
+            if let Some(interval) = *self.latency_interval.lock() {
+                tasks.spawn_on(
+                    Self::latency_task(
+                        interval,
+                        self.latency.clone(),
+                        send_tx.clone(),
+                        self.disconnect_token.lock().clone(),
+                    ),
+                    &rt_handle,
+                );
+            }
         }
 
         *self.send_tx.lock() = Some(send_tx);
@@ -407,6 +766,18 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
             Ok(client_info) => {
                 tracing::trace!("Successfully logged in to server");
 
+                // Automatic reconnects continue the session instead of starting a new one
+                let reconnecting = self.reconnecting.load(Ordering::Relaxed);
+                self.session_stats_tx.send_modify(|session_stats| {
+                    if reconnecting {
+                        session_stats.reconnects += 1;
+                    } else {
+                        *session_stats = SessionStats {
+                            connected_since: Some(Instant::now()),
+                            reconnects: 0,
+                        };
+                    }
+                });
                 self.set_state(State::LoggedIn);
                 if let Err(err) = self
                     .broadcast_tx
@@ -415,11 +786,13 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
                     tracing::warn!(?err, "Failed to broadcast connected event");
                 }
 
+                self.flush_offline_queue().await;
+
                 Ok(())
             }
             Err(err) => {
                 tracing::warn!(?err, "Failed to login to server");
-                self.disconnect(false).await;
+                self.disconnect(DisconnectCause::from(&err)).await;
                 Err(err)
             }
         }
@@ -436,6 +809,7 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
 
         tracing::trace!("Aborting worker tasks");
         worker_tasks.abort_all();
+        self.latency.reset();
         tracing::trace!("Waiting for worker tasks to finish");
         while let Some(res) = worker_tasks.join_next().await {
             if let Err(err) = res
@@ -445,7 +819,7 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
             }
         }
 
-        self.matcher.clear().await;
+        self.matcher.clear();
         *self.disconnect_token.lock() = self.shutdown_token.child_token();
         self.send_tx.lock().take();
 
@@ -475,7 +849,9 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
                                 (self.on_event)(event.clone()).await;
 
                                 tracing::debug!(?err, "Received error event, disconnecting");
-                                self.disconnect(false).await;
+                                let will_reconnect = err.can_reconnect() && self.reconnect_config.max_tries > 0;
+                                self.reconnecting.store(will_reconnect, Ordering::Relaxed);
+                                self.disconnect(DisconnectCause::Error(err.clone())).await;
 
                                 if err.can_reconnect() {
                                     {
@@ -486,6 +862,7 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
                                                 tracing::warn!(?err, "Failed to broadcast reconnect suppressed error event");
                                             }
                                             gate.clear();
+                                            self.stop_reconnecting();
                                             continue;
                                         }
 
@@ -493,7 +870,9 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
                                     }
 
                                     tracing::info!("Reconnecting after error");
-                                    if let Err(err) = self.reconnect().await {
+                                    let reconnect_result = self.reconnect().await;
+                                    self.stop_reconnecting();
+                                    if let Err(err) = reconnect_result {
                                         tracing::warn!(?err, "Received error while reconnecting");
                                         if let Err(err) = self.broadcast_tx.send(SignalingEvent::Error(err)) {
                                             tracing::warn!(?err, "Failed to broadcast reconnect error event");
@@ -506,7 +885,7 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
                         },
                         Err(err) => {
                             tracing::warn!(?err, "Failed to receive broadcast event, exiting supervisor task");
-                            self.disconnect(false).await;
+                            self.disconnect(DisconnectCause::Other(err.to_string())).await;
                             break;
                         }
                     }
@@ -525,23 +904,33 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
 
     #[instrument(level = "debug", skip(self), err)]
     async fn reconnect(&self) -> Result<(), SignalingRuntimeError> {
-        if self.reconnect_max_tries == 0 {
+        let max_tries = self.reconnect_config.max_tries;
+        if max_tries == 0 {
             tracing::debug!("Reconnecting disabled");
             return Ok(());
         }
 
-        let mut retry_strategy = RetryStrategy::default();
+        let mut retry_strategy = RetryStrategy::from(&self.reconnect_config);
 
         let mut reconnect_error = SignalingError::Other("Unknown".to_string());
-        for attempt in 1..=self.reconnect_max_tries {
+        for attempt in 1..=max_tries {
             tracing::trace!(?attempt, "Reconnecting");
+            (self.on_event)(SignalingEvent::Reconnecting {
+                attempt,
+                max_attempts: max_tries,
+            })
+            .await;
             match self.connect().await {
                 Ok(()) => return Ok(()),
                 Err(err) => {
                     tracing::warn!(?err, ?attempt, "Failed to reconnect");
+                    if matches!(err, SignalingError::IncompatibleProtocolVersion(_)) {
+                        tracing::debug!("Protocol version is incompatible, aborting reconnect");
+                        return Err(SignalingRuntimeError::ReconnectFailed(err.into()));
+                    }
                     reconnect_error = err;
 
-                    if attempt < self.reconnect_max_tries {
+                    if attempt < max_tries {
                         let timeout = retry_strategy.timeout(attempt as u32);
                         tracing::debug!(?attempt, ?timeout, "Sleeping before attempting reconnect");
 
@@ -676,6 +1065,41 @@ impl<ST: SignalingTransport, TP: TokenProvider> SignalingClientInner<ST, TP> {
             }
         }.instrument(tracing::Span::current())
     }
+
+    #[instrument(level = "debug", skip_all)]
+    fn latency_task(
+        interval: Duration,
+        latency: LatencyTracker,
+        send_tx: mpsc::Sender<tungstenite::Message>,
+        disconnect_token: CancellationToken,
+    ) -> impl Future<Output = ()> + Send {
+        async move {
+            tracing::debug!(?interval, "Starting latency task");
+            let _guard = TaskDropLogger::new("latency");
+
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    biased;
+
+                    _ = disconnect_token.cancelled() => {
+                        tracing::debug!("Disconnect signal received, exiting latency task");
+                        break;
+                    }
+
+                    _ = ticker.tick() => {
+                        if let Err(err) = send_tx.try_send(latency.ping()) {
+                            // Skipping a measurement is fine, the writer task reports a closed transport
+                            tracing::debug!(?err, "Failed to queue latency ping");
+                        }
+                    }
+                }
+            }
+        }
+        .instrument(tracing::Span::current())
+    }
 }
 
 struct TaskDropLogger {
@@ -702,15 +1126,25 @@ pub struct RetryStrategy {
 
 impl Default for RetryStrategy {
     fn default() -> Self {
+        Self::from(&ReconnectConfig::default())
+    }
+}
+
+impl From<&ReconnectConfig> for RetryStrategy {
+    fn from(config: &ReconnectConfig) -> Self {
+        Self::new(config.retry_base, config.retry_cap)
+    }
+}
+
+impl RetryStrategy {
+    fn new(base: Duration, cap: Duration) -> Self {
         Self {
-            base: Duration::from_millis(100),
-            cap: Duration::from_secs(5),
+            base,
+            cap,
             rng: rand::rngs::StdRng::from_os_rng(),
         }
     }
-}
 
-impl RetryStrategy {
     fn timeout(&mut self, attempt: u32) -> Duration {
         if attempt == 0 {
             return Duration::from_millis(0);
@@ -745,7 +1179,17 @@ pub struct ReconnectGate {
 
 impl Default for ReconnectGate {
     fn default() -> Self {
-        Self::new(3, Duration::from_secs(60), Duration::from_secs(120))
+        Self::from(&ReconnectConfig::default())
+    }
+}
+
+impl From<&ReconnectConfig> for ReconnectGate {
+    fn from(config: &ReconnectConfig) -> Self {
+        Self::new(
+            config.gate_max_in_window.max(1),
+            config.gate_window,
+            config.gate_cooldown,
+        )
     }
 }
 
@@ -810,7 +1254,7 @@ mod tests {
     use pretty_assertions::{assert_eq, assert_matches};
     use test_log::test;
     use tokio::sync::Notify;
-    use vacs_protocol::ws::{ErrorReason, LoginFailureReason};
+    use vacs_protocol::ws::{ErrorReason, LoginFailureDetails, LoginFailureReason};
 
     async fn setup_test_client(
         transport: MockTransport,
@@ -835,6 +1279,7 @@ mod tests {
                         display_name: "client1".to_string(),
                         frequency: "".to_string(),
                     },
+                    capabilities: Some(vec![Capability::CallBusy]),
                 })
                 .unwrap()
                 .into(),
@@ -848,7 +1293,10 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            reconnect_max_tries,
+            ReconnectConfig {
+                max_tries: reconnect_max_tries,
+                ..Default::default()
+            },
             &tokio::runtime::Handle::current(),
         );
 
@@ -864,6 +1312,32 @@ mod tests {
         setup_test_client(MockTransport::default(), 0).await;
     }
 
+    #[test(tokio::test)]
+    async fn login_negotiates_capabilities() {
+        let transport = MockTransport::default();
+        let mut outgoing_rx = transport.outgoing_tx.subscribe();
+        let (client, _shutdown_token) = setup_test_client(transport, 0).await;
+
+        let login = outgoing_rx
+            .recv_with_timeout(
+                Duration::from_millis(100),
+                |m| matches!(m, tungstenite::Message::Text(text) if text.contains("\"Login\"")),
+            )
+            .await
+            .unwrap();
+        let tungstenite::Message::Text(login) = login else {
+            unreachable!()
+        };
+        assert_matches!(
+            SignalingMessage::deserialize(&login),
+            Ok(SignalingMessage::Login { capabilities, .. }) if capabilities == Capability::ALL
+        );
+
+        assert_eq!(client.capabilities(), vec![Capability::CallBusy]);
+        assert!(client.has_capability(Capability::CallBusy));
+        assert!(!client.has_capability(Capability::CallPolicy));
+    }
+
     #[test(tokio::test)]
     async fn shutdown() {
         let (client, shutdown_token) = setup_test_client(MockTransport::default(), 0).await;
@@ -875,13 +1349,46 @@ mod tests {
         assert_matches!(client.state(), State::Disconnected);
     }
 
+    #[test(tokio::test)]
+    async fn dropping_clone_keeps_client_running() {
+        let (client, shutdown_token) = setup_test_client(MockTransport::default(), 0).await;
+
+        drop(SignalingClient::clone(&client));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_matches!(client.state(), State::LoggedIn);
+        assert!(!shutdown_token.is_cancelled());
+
+        let clone = SignalingClient::clone(&client);
+        drop(client);
+        assert!(!shutdown_token.is_cancelled());
+        drop(clone);
+        assert!(shutdown_token.is_cancelled());
+    }
+
     #[test(tokio::test)]
     async fn disconnect() {
         let (client, _shutdown_token) = setup_test_client(MockTransport::default(), 0).await;
 
+        let mut broadcast_rx = client.subscribe();
+        assert!(client.last_disconnect_cause().is_none());
+
         client.disconnect().await;
 
         assert_matches!(client.state(), State::Disconnected);
+        assert_matches!(
+            client.last_disconnect_cause(),
+            Some(DisconnectCause::Requested)
+        );
+        let event = broadcast_rx
+            .recv_with_timeout(Duration::from_millis(100), |event| {
+                matches!(event, SignalingEvent::Disconnected(_))
+            })
+            .await;
+        assert_matches!(
+            event,
+            Ok(SignalingEvent::Disconnected(DisconnectCause::Requested))
+        );
     }
 
     #[test(tokio::test)]
@@ -904,6 +1411,40 @@ mod tests {
         assert!(sent_msg.is_ok());
     }
 
+    #[test(tokio::test)]
+    async fn send_queue_full() {
+        let transport = MockTransport::default();
+        let (client, _shutdown_token) = setup_test_client(transport, 0).await;
+
+        let (send_tx, send_rx) = mpsc::channel(1);
+        send_tx
+            .try_send(tungstenite::Message::from("queued"))
+            .unwrap();
+        *client.inner.send_tx.lock() = Some(send_tx);
+
+        let msg = SignalingMessage::CallIceCandidate {
+            candidate: "candidate".to_string(),
+            peer_id: "client2".to_string(),
+        };
+        let result = client.send(msg.clone()).await;
+        assert_matches!(
+            result,
+            Err(SignalingError::Runtime(
+                SignalingRuntimeError::SendQueueFull
+            ))
+        );
+        assert_matches!(client.state(), State::LoggedIn);
+
+        drop(send_rx);
+        let result = client.send(msg).await;
+        assert_matches!(
+            result,
+            Err(SignalingError::Runtime(
+                SignalingRuntimeError::Disconnected(None)
+            ))
+        );
+    }
+
     #[test(tokio::test)]
     async fn send_without_start() {
         let shutdown_token = CancellationToken::new();
@@ -915,13 +1456,14 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            8,
+            ReconnectConfig::default(),
             &tokio::runtime::Handle::current(),
         );
 
         let msg = SignalingMessage::Login {
             token: "test".to_string(),
             protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+            capabilities: vec![],
         };
 
         let result = client.send(msg.clone()).await;
@@ -946,7 +1488,7 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            8,
+            ReconnectConfig::default(),
             &tokio::runtime::Handle::current(),
         ));
 
@@ -982,6 +1524,7 @@ mod tests {
         let msg = SignalingMessage::Login {
             token: "test".to_string(),
             protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+            capabilities: vec![],
         };
 
         let result = client.send(msg.clone()).await;
@@ -1006,6 +1549,7 @@ mod tests {
         let msg = SignalingMessage::Login {
             token: "test".to_string(),
             protocol_version: VACS_PROTOCOL_VERSION.to_string(),
+            capabilities: vec![],
         };
 
         let result = client.send(msg.clone()).await;
@@ -1017,26 +1561,495 @@ mod tests {
         );
     }
 
+    /// Sets up a logged in client with the offline queue enabled, delaying the login response
+    /// after reconnecting by `relogin_delay` to keep the reconnect window open.
+    async fn setup_offline_queue_client(
+        transport: MockTransport,
+        max_age: Duration,
+        relogin_delay: Duration,
+    ) -> (
+        SignalingClient<MockTransport, MockTokenProvider>,
+        CancellationToken,
+    ) {
+        let shutdown_token = CancellationToken::new();
+        let token_provider = MockTokenProvider::new(1, None);
+
+        let mock_tx = transport.incoming_tx.clone();
+        let ready = transport.ready.clone();
+
+        tokio::spawn(async move {
+            for delay in [Duration::ZERO, relogin_delay] {
+                ready.notified().await;
+                tokio::time::sleep(delay).await;
+                let msg = tungstenite::Message::Text(
+                    SignalingMessage::serialize(&SignalingMessage::ClientInfo {
+                        own: true,
+                        info: ClientInfo {
+                            id: "client1".to_string(),
+                            display_name: "client1".to_string(),
+                            frequency: "".to_string(),
+                        },
+                        capabilities: None,
+                    })
+                    .unwrap()
+                    .into(),
+                );
+                let _ = mock_tx.send(msg);
+            }
+        });
+
+        let client = SignalingClient::new(
+            transport,
+            token_provider,
+            |_| async {},
+            shutdown_token.clone(),
+            Duration::from_millis(500),
+            ReconnectConfig {
+                max_tries: 1,
+                offline_queue: true,
+                offline_queue_max_age: max_age,
+                ..Default::default()
+            },
+            &tokio::runtime::Handle::current(),
+        );
+
+        assert!(client.connect().await.is_ok());
+        (client, shutdown_token)
+    }
+
+    /// Closes the connection and waits for the client to start reconnecting.
+    async fn drop_connection(
+        client: &SignalingClient<MockTransport, MockTokenProvider>,
+        incoming_tx: &broadcast::Sender<tungstenite::Message>,
+    ) {
+        let mut state_rx = client.subscribe_state();
+        incoming_tx.send(tungstenite::Message::Close(None)).unwrap();
+        tokio::time::timeout(
+            Duration::from_millis(100),
+            state_rx.wait_for(|state| *state != State::LoggedIn),
+        )
+        .await
+        .expect("Client did not notice dropped connection")
+        .unwrap();
+    }
+
     #[test(tokio::test)]
-    async fn recv() {
+    async fn send_while_reconnecting_is_delivered_after_reconnect() {
         let transport = MockTransport::default();
         let incoming_tx = transport.incoming_tx.clone();
-        let (client, _shutdown_token) = setup_test_client(transport, 0).await;
+        let mut outgoing_rx = transport.outgoing_tx.subscribe();
+        let (client, shutdown_token) = setup_offline_queue_client(
+            transport,
+            Duration::from_secs(1),
+            Duration::from_millis(100),
+        )
+        .await;
 
-        let msg = SignalingMessage::CallInvite {
+        drop_connection(&client, &incoming_tx).await;
+
+        let msg = SignalingMessage::CallEnd {
             peer_id: "client2".to_string(),
+            reason: None,
         };
+        let serialized = tungstenite::Message::from(SignalingMessage::serialize(&msg).unwrap());
+        assert!(client.send(msg).await.is_ok());
 
-        let task = tokio::spawn(async move {
-            return client.recv().await;
-        });
-
-        let result = incoming_tx.send(tungstenite::Message::from(
-            SignalingMessage::serialize(&msg).unwrap(),
-        ));
-        assert!(result.is_ok());
+        let sent_msg = outgoing_rx
+            .recv_with_timeout(Duration::from_millis(500), |m| m == &serialized)
+            .await;
+        assert!(sent_msg.is_ok());
+        assert_matches!(client.state(), State::LoggedIn);
 
-        assert_eq!(task.await.unwrap().unwrap(), msg);
+        shutdown_token.cancel();
+    }
+
+    #[test(tokio::test)]
+    async fn send_while_reconnecting_is_dropped_after_max_age() {
+        let transport = MockTransport::default();
+        let incoming_tx = transport.incoming_tx.clone();
+        let mut outgoing_rx = transport.outgoing_tx.subscribe();
+        let (client, shutdown_token) = setup_offline_queue_client(
+            transport,
+            Duration::from_millis(50),
+            Duration::from_millis(150),
+        )
+        .await;
+
+        drop_connection(&client, &incoming_tx).await;
+
+        let msg = SignalingMessage::CallEnd {
+            peer_id: "client2".to_string(),
+            reason: None,
+        };
+        let serialized = tungstenite::Message::from(SignalingMessage::serialize(&msg).unwrap());
+        assert!(client.send(msg).await.is_ok());
+
+        let mut state_rx = client.subscribe_state();
+        tokio::time::timeout(
+            Duration::from_millis(500),
+            state_rx.wait_for(|state| *state == State::LoggedIn),
+        )
+        .await
+        .expect("Client did not reconnect")
+        .unwrap();
+
+        let sent_msg = outgoing_rx
+            .recv_with_timeout(Duration::from_millis(100), |m| m == &serialized)
+            .await;
+        assert!(sent_msg.is_err());
+
+        shutdown_token.cancel();
+    }
+
+    #[test(tokio::test)]
+    async fn reconnect_counts_towards_session_stats() {
+        let transport = MockTransport::default();
+        let incoming_tx = transport.incoming_tx.clone();
+        let ready = transport.ready.clone();
+
+        // Answer the initial login and every reconnect
+        let mock_tx = incoming_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                ready.notified().await;
+                let msg = tungstenite::Message::Text(
+                    SignalingMessage::serialize(&SignalingMessage::ClientInfo {
+                        own: true,
+                        info: ClientInfo {
+                            id: "client1".to_string(),
+                            display_name: "client1".to_string(),
+                            frequency: "".to_string(),
+                        },
+                        capabilities: None,
+                    })
+                    .unwrap()
+                    .into(),
+                );
+                let _ = mock_tx.send(msg);
+            }
+        });
+
+        let shutdown_token = CancellationToken::new();
+        let client = SignalingClient::new(
+            transport,
+            MockTokenProvider::new(1, None),
+            |_| async {},
+            shutdown_token.clone(),
+            Duration::from_millis(100),
+            ReconnectConfig {
+                max_tries: 1,
+                ..Default::default()
+            },
+            &tokio::runtime::Handle::current(),
+        );
+        assert_eq!(client.session_stats(), SessionStats::default());
+        let mut session_stats_rx = client.subscribe_session_stats();
+
+        assert!(client.connect().await.is_ok());
+        assert!(session_stats_rx.has_changed().unwrap());
+        let connected_since = session_stats_rx.borrow_and_update().connected_since;
+        assert!(connected_since.is_some());
+        assert_eq!(client.session_stats().reconnects, 0);
+
+        for reconnects in 1..=2 {
+            // Logging in again is answered right away, so the client is back to logged in before
+            // a state change could be observed. Wait for the reconnect to be counted instead.
+            incoming_tx.send(tungstenite::Message::Close(None)).unwrap();
+            tokio::time::timeout(
+                Duration::from_millis(500),
+                session_stats_rx.wait_for(|session_stats| session_stats.reconnects == reconnects),
+            )
+            .await
+            .expect("Client did not notice dropped connection")
+            .unwrap();
+
+            let mut state_rx = client.subscribe_state();
+            tokio::time::timeout(
+                Duration::from_millis(500),
+                state_rx.wait_for(|state| *state == State::LoggedIn),
+            )
+            .await
+            .expect("Client did not reconnect")
+            .unwrap();
+
+            assert_eq!(
+                client.session_stats(),
+                SessionStats {
+                    connected_since,
+                    reconnects,
+                }
+            );
+        }
+
+        client.disconnect().await;
+        assert_eq!(client.session_stats(), SessionStats::default());
+        assert!(client.session_stats().uptime().is_none());
+
+        shutdown_token.cancel();
+    }
+
+    #[test(tokio::test)]
+    async fn call_setup_while_reconnecting_is_not_queued() {
+        let transport = MockTransport::default();
+        let incoming_tx = transport.incoming_tx.clone();
+        let (client, shutdown_token) = setup_offline_queue_client(
+            transport,
+            Duration::from_secs(1),
+            Duration::from_millis(100),
+        )
+        .await;
+
+        drop_connection(&client, &incoming_tx).await;
+
+        for msg in [
+            SignalingMessage::CallOffer {
+                sdp: "sdp".to_string(),
+                peer_id: "client2".to_string(),
+            },
+            SignalingMessage::CallIceCandidate {
+                candidate: "candidate".to_string(),
+                peer_id: "client2".to_string(),
+            },
+        ] {
+            assert_matches!(
+                client.send(msg).await,
+                Err(SignalingError::Runtime(
+                    SignalingRuntimeError::Disconnected(None)
+                ))
+            );
+        }
+
+        shutdown_token.cancel();
+    }
+
+    #[test(tokio::test)]
+    async fn send_while_disconnected_is_not_queued() {
+        let transport = MockTransport::default();
+        let (client, shutdown_token) =
+            setup_offline_queue_client(transport, Duration::from_secs(1), Duration::ZERO).await;
+
+        client.disconnect().await;
+
+        let result = client
+            .send(SignalingMessage::CallEnd {
+                peer_id: "client2".to_string(),
+                reason: None,
+            })
+            .await;
+        assert_matches!(
+            result,
+            Err(SignalingError::Runtime(
+                SignalingRuntimeError::Disconnected(None)
+            ))
+        );
+
+        shutdown_token.cancel();
+    }
+
+    #[test(tokio::test)]
+    async fn reconnect_emits_attempt_events() {
+        let transport = MockTransport::default();
+        let incoming_tx = transport.incoming_tx.clone();
+        let ready = transport.ready.clone();
+
+        // Only answer the initial login, all reconnect attempts time out
+        let mock_tx = incoming_tx.clone();
+        tokio::spawn(async move {
+            ready.notified().await;
+            let msg = tungstenite::Message::Text(
+                SignalingMessage::serialize(&SignalingMessage::ClientInfo {
+                    own: true,
+                    info: ClientInfo {
+                        id: "client1".to_string(),
+                        display_name: "client1".to_string(),
+                        frequency: "".to_string(),
+                    },
+                    capabilities: None,
+                })
+                .unwrap()
+                .into(),
+            );
+            let _ = mock_tx.send(msg);
+        });
+
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let shutdown_token = CancellationToken::new();
+        let client = SignalingClient::new(
+            transport,
+            MockTokenProvider::new(1, None),
+            move |event| {
+                let events_tx = events_tx.clone();
+                async move {
+                    let _ = events_tx.send(event);
+                }
+            },
+            shutdown_token.clone(),
+            Duration::from_millis(50),
+            ReconnectConfig {
+                max_tries: 3,
+                retry_base: Duration::from_millis(10),
+                retry_cap: Duration::from_millis(20),
+                ..Default::default()
+            },
+            &tokio::runtime::Handle::current(),
+        );
+        assert!(client.connect().await.is_ok());
+
+        drop_connection(&client, &incoming_tx).await;
+
+        let mut attempts = Vec::new();
+        loop {
+            let event = tokio::time::timeout(Duration::from_secs(1), events_rx.recv())
+                .await
+                .expect("Reconnect did not finish")
+                .unwrap();
+            match event {
+                SignalingEvent::Reconnecting {
+                    attempt,
+                    max_attempts,
+                } => attempts.push((attempt, max_attempts)),
+                SignalingEvent::Error(SignalingRuntimeError::ReconnectFailed(_)) => break,
+                _ => {}
+            }
+        }
+        assert_eq!(attempts, vec![(1, 3), (2, 3), (3, 3)]);
+
+        shutdown_token.cancel();
+    }
+
+    #[test(tokio::test)]
+    async fn send_and_await_interleaved_peers() {
+        let transport = MockTransport::default();
+        let incoming_tx = transport.incoming_tx.clone();
+        let outgoing_tx = transport.outgoing_tx.clone();
+        let (client, _shutdown_token) = setup_test_client(transport, 0).await;
+        let mut outgoing_rx = outgoing_tx.subscribe();
+
+        let mut tasks = Vec::new();
+        for peer_id in ["client2", "client3"] {
+            let client = client.clone();
+            tasks.push(tokio::spawn(async move {
+                client
+                    .send_and_await(
+                        SignalingMessage::CallInvite {
+                            peer_id: peer_id.to_string(),
+                        },
+                        |m| matches!(m, SignalingMessage::CallAccept { .. }),
+                        Duration::from_millis(500),
+                    )
+                    .await
+            }));
+        }
+        for _ in 0..2 {
+            outgoing_rx
+                .recv_with_timeout(Duration::from_millis(100), |_| true)
+                .await
+                .unwrap();
+        }
+
+        // Responses arrive out of order, with unrelated messages for the same peers in between.
+        for msg in [
+            SignalingMessage::CallIceCandidate {
+                candidate: "candidate2".to_string(),
+                peer_id: "client2".to_string(),
+            },
+            SignalingMessage::CallAccept {
+                peer_id: "client3".to_string(),
+            },
+            SignalingMessage::CallReject {
+                peer_id: "client2".to_string(),
+                reason: None,
+            },
+            SignalingMessage::CallAccept {
+                peer_id: "client2".to_string(),
+            },
+        ] {
+            incoming_tx
+                .send(tungstenite::Message::from(
+                    SignalingMessage::serialize(&msg).unwrap(),
+                ))
+                .unwrap();
+        }
+
+        let client2 = tasks.remove(0).await.unwrap();
+        let client3 = tasks.remove(0).await.unwrap();
+        assert_matches!(client2, Ok(SignalingMessage::CallAccept { peer_id }) if peer_id == "client2");
+        assert_matches!(client3, Ok(SignalingMessage::CallAccept { peer_id }) if peer_id == "client3");
+    }
+
+    #[test(tokio::test)]
+    async fn send_and_await_ignores_other_peers() {
+        let transport = MockTransport::default();
+        let incoming_tx = transport.incoming_tx.clone();
+        let outgoing_tx = transport.outgoing_tx.clone();
+        let (client, _shutdown_token) = setup_test_client(transport, 0).await;
+        let mut outgoing_rx = outgoing_tx.subscribe();
+
+        let task = tokio::spawn(async move {
+            client
+                .send_and_await(
+                    SignalingMessage::CallOffer {
+                        sdp: "offer".to_string(),
+                        peer_id: "client2".to_string(),
+                    },
+                    |m| matches!(m, SignalingMessage::CallAnswer { .. }),
+                    Duration::from_millis(100),
+                )
+                .await
+        });
+        outgoing_rx
+            .recv_with_timeout(Duration::from_millis(100), |_| true)
+            .await
+            .unwrap();
+
+        let msg = SignalingMessage::CallAnswer {
+            sdp: "answer".to_string(),
+            peer_id: "client3".to_string(),
+        };
+        incoming_tx
+            .send(tungstenite::Message::from(
+                SignalingMessage::serialize(&msg).unwrap(),
+            ))
+            .unwrap();
+
+        assert_matches!(task.await.unwrap(), Err(SignalingError::Timeout(_)));
+    }
+
+    #[test(tokio::test)]
+    async fn send_and_await_without_peer_id() {
+        let transport = MockTransport::default();
+        let (client, _shutdown_token) = setup_test_client(transport, 0).await;
+
+        let result = client
+            .send_and_await(
+                SignalingMessage::ListClients,
+                |_| true,
+                Duration::from_millis(100),
+            )
+            .await;
+        assert_matches!(result, Err(SignalingError::ProtocolError(_)));
+    }
+
+    #[test(tokio::test)]
+    async fn recv() {
+        let transport = MockTransport::default();
+        let incoming_tx = transport.incoming_tx.clone();
+        let (client, _shutdown_token) = setup_test_client(transport, 0).await;
+
+        let msg = SignalingMessage::CallInvite {
+            peer_id: "client2".to_string(),
+        };
+
+        let task = tokio::spawn(async move {
+            return client.recv().await;
+        });
+
+        let result = incoming_tx.send(tungstenite::Message::from(
+            SignalingMessage::serialize(&msg).unwrap(),
+        ));
+        assert!(result.is_ok());
+
+        assert_eq!(task.await.unwrap().unwrap(), msg);
     }
 
     #[test(tokio::test)]
@@ -1152,6 +2165,7 @@ mod tests {
             let msg = tungstenite::Message::Text(
                 SignalingMessage::serialize(&SignalingMessage::LoginFailure {
                     reason: LoginFailureReason::Timeout,
+                    details: None,
                 })
                 .unwrap()
                 .into(),
@@ -1165,7 +2179,10 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            0,
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
             &tokio::runtime::Handle::current(),
         );
 
@@ -1190,7 +2207,10 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            0,
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
             &tokio::runtime::Handle::current(),
         ));
 
@@ -1213,6 +2233,7 @@ mod tests {
             let msg = tungstenite::Message::Text(
                 SignalingMessage::serialize(&SignalingMessage::LoginFailure {
                     reason: LoginFailureReason::Unauthorized,
+                    details: None,
                 })
                 .unwrap()
                 .into(),
@@ -1226,7 +2247,10 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            0,
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
             &tokio::runtime::Handle::current(),
         );
 
@@ -1237,6 +2261,164 @@ mod tests {
             SignalingError::LoginError(LoginFailureReason::Unauthorized)
         );
         assert_matches!(client.state(), State::Disconnected);
+        assert_matches!(
+            client.last_disconnect_cause(),
+            Some(DisconnectCause::LoginFailed(
+                LoginFailureReason::Unauthorized
+            ))
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn measures_latency_with_delayed_pong() {
+        const PONG_DELAY: Duration = Duration::from_millis(100);
+
+        let transport = MockTransport::default();
+        let shutdown_token = CancellationToken::new();
+        let token_provider = MockTokenProvider::new(1, None);
+
+        let mock_tx = transport.incoming_tx.clone();
+        let mut outgoing_rx = transport.outgoing_tx.subscribe();
+        let ready = transport.ready.clone();
+
+        tokio::spawn(async move {
+            ready.notified().await;
+            let client_info = SignalingMessage::ClientInfo {
+                own: true,
+                info: ClientInfo {
+                    id: "client1".to_string(),
+                    display_name: "client1".to_string(),
+                    frequency: "".to_string(),
+                },
+                capabilities: None,
+            };
+            let _ = mock_tx.send(tungstenite::Message::Text(
+                SignalingMessage::serialize(&client_info).unwrap().into(),
+            ));
+
+            // Answer all latency pings after a delay, simulating a slow link
+            while let Ok(msg) = outgoing_rx.recv().await {
+                if let tungstenite::Message::Ping(data) = msg {
+                    let mock_tx = mock_tx.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(PONG_DELAY).await;
+                        let _ = mock_tx.send(tungstenite::Message::Pong(data));
+                    });
+                }
+            }
+        });
+
+        let client = SignalingClient::new(
+            transport,
+            token_provider,
+            |_| async {},
+            shutdown_token.clone(),
+            Duration::from_millis(100),
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
+            &tokio::runtime::Handle::current(),
+        );
+        client.set_latency_interval(Some(Duration::from_millis(500)));
+        let mut latency_rx = client.subscribe_latency();
+
+        assert!(client.connect().await.is_ok());
+
+        tokio::time::timeout(Duration::from_secs(1), latency_rx.changed())
+            .await
+            .expect("No latency measured")
+            .unwrap();
+        let latency = latency_rx.borrow().expect("No latency measured");
+        assert!(
+            latency >= PONG_DELAY && latency < PONG_DELAY * 2,
+            "Measured latency {latency:?} outside of tolerance"
+        );
+        assert_eq!(client.latency(), Some(latency));
+
+        client.disconnect().await;
+        assert_eq!(client.latency(), None);
+
+        shutdown_token.cancel();
+    }
+
+    #[test(tokio::test)]
+    async fn login_refreshes_expiring_token() {
+        let transport = MockTransport::default();
+        let shutdown_token = CancellationToken::new();
+        let token_provider = MockTokenProvider::new(1, None)
+            .with_expiry(Duration::from_millis(200), Duration::from_millis(50));
+
+        let client_info = SignalingMessage::ClientInfo {
+            own: true,
+            info: ClientInfo {
+                id: "client1".to_string(),
+                display_name: "client1".to_string(),
+                frequency: "".to_string(),
+            },
+            capabilities: None,
+        };
+        let responses = [
+            client_info.clone(),
+            client_info.clone(),
+            SignalingMessage::LoginFailure {
+                reason: LoginFailureReason::Unauthorized,
+                details: None,
+            },
+            client_info.clone(),
+            client_info,
+        ];
+
+        let mock_tx = transport.incoming_tx.clone();
+        let ready = transport.ready.clone();
+
+        tokio::spawn(async move {
+            for response in responses {
+                ready.notified().await;
+                let msg = tungstenite::Message::Text(
+                    SignalingMessage::serialize(&response).unwrap().into(),
+                );
+                let _ = mock_tx.send(msg);
+            }
+        });
+
+        let client = SignalingClient::new(
+            transport,
+            token_provider.clone(),
+            |_| async {},
+            shutdown_token.clone(),
+            Duration::from_millis(100),
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
+            &tokio::runtime::Handle::current(),
+        );
+
+        // Token is retrieved on the first login and reused while it is valid
+        assert!(client.connect().await.is_ok());
+        client.disconnect().await;
+        assert!(client.connect().await.is_ok());
+        client.disconnect().await;
+        assert_eq!(token_provider.refreshes(), 1);
+
+        // A rejected token is discarded, so the next attempt retrieves a fresh one
+        assert_matches!(
+            client.connect().await,
+            Err(SignalingError::LoginError(LoginFailureReason::Unauthorized))
+        );
+        assert!(client.connect().await.is_ok());
+        client.disconnect().await;
+        assert_eq!(token_provider.refreshes(), 2);
+
+        // A token about to expire is refreshed before the next attempt
+        tokio::time::sleep(Duration::from_millis(170)).await;
+        assert!(client.connect().await.is_ok());
+        assert_matches!(client.state(), State::LoggedIn);
+        assert_eq!(token_provider.refreshes(), 3);
+
+        shutdown_token.cancel();
+        client.disconnect().await;
     }
 
     #[test(tokio::test)]
@@ -1253,6 +2435,7 @@ mod tests {
             let msg = tungstenite::Message::Text(
                 SignalingMessage::serialize(&SignalingMessage::LoginFailure {
                     reason: LoginFailureReason::InvalidCredentials,
+                    details: None,
                 })
                 .unwrap()
                 .into(),
@@ -1266,7 +2449,10 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            0,
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
             &tokio::runtime::Handle::current(),
         );
 
@@ -1293,6 +2479,7 @@ mod tests {
             let msg = tungstenite::Message::Text(
                 SignalingMessage::serialize(&SignalingMessage::LoginFailure {
                     reason: LoginFailureReason::DuplicateId,
+                    details: None,
                 })
                 .unwrap()
                 .into(),
@@ -1306,7 +2493,10 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            0,
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
             &tokio::runtime::Handle::current(),
         );
 
@@ -1319,6 +2509,58 @@ mod tests {
         assert_matches!(client.state(), State::Disconnected);
     }
 
+    #[test(tokio::test)]
+    async fn login_incompatible_protocol_version() {
+        let transport = MockTransport::default();
+        let shutdown_token = CancellationToken::new();
+        let token_provider = MockTokenProvider::new(1, None);
+
+        let mock_tx = transport.incoming_tx.clone();
+        let ready = transport.ready.clone();
+
+        let details = LoginFailureDetails {
+            min_protocol_version: Some("2.0.0".to_string()),
+            download_url: Some("https://example.com/download".to_string()),
+        };
+        let failure_details = details.clone();
+        tokio::spawn(async move {
+            ready.notified().await;
+            let msg = tungstenite::Message::Text(
+                SignalingMessage::serialize(&SignalingMessage::LoginFailure {
+                    reason: LoginFailureReason::IncompatibleProtocolVersion,
+                    details: Some(failure_details),
+                })
+                .unwrap()
+                .into(),
+            );
+            let _ = mock_tx.send(msg);
+        });
+
+        let client = SignalingClient::new(
+            transport,
+            token_provider,
+            |_| async {},
+            shutdown_token.clone(),
+            Duration::from_millis(100),
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
+            &tokio::runtime::Handle::current(),
+        );
+
+        let res = client.connect().await;
+        assert_matches!(
+            res.unwrap_err(),
+            SignalingError::IncompatibleProtocolVersion(err_details) if err_details == details
+        );
+        assert_matches!(client.state(), State::Disconnected);
+        assert_matches!(
+            client.last_disconnect_cause(),
+            Some(DisconnectCause::IncompatibleProtocolVersion(cause_details)) if cause_details == details
+        );
+    }
+
     #[test(tokio::test)]
     async fn login_unexpected_message() {
         let transport = MockTransport::default();
@@ -1347,7 +2589,10 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            0,
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
             &tokio::runtime::Handle::current(),
         );
 
@@ -1385,7 +2630,10 @@ mod tests {
             |_| async {},
             shutdown_token.clone(),
             Duration::from_millis(100),
-            0,
+            ReconnectConfig {
+                max_tries: 0,
+                ..Default::default()
+            },
             &tokio::runtime::Handle::current(),
         );
 
@@ -1489,5 +2737,85 @@ mod tests {
             assert_eq!(g.recent.len(), 0);
             assert!(g.suppressed_until.is_none());
         }
+
+        #[test]
+        fn honors_custom_config() {
+            let config = ReconnectConfig {
+                gate_max_in_window: 2,
+                gate_window: Duration::from_secs(5),
+                gate_cooldown: Duration::from_secs(7),
+                ..Default::default()
+            };
+            let mut g = ReconnectGate::from(&config);
+            let t0 = Instant::now();
+
+            assert!(g.can_reconnect(t0).is_ok());
+            g.on_reconnect(t0);
+            let t1 = t0 + Duration::from_secs(1);
+            assert!(g.can_reconnect(t1).is_ok());
+            g.on_reconnect(t1);
+
+            let t2 = t0 + Duration::from_secs(2);
+            let until = g.can_reconnect(t2).unwrap_err();
+            assert_eq!(until, t2 + Duration::from_secs(7));
+
+            let t3 = until + Duration::from_secs(5);
+            assert!(g.can_reconnect(t3).is_ok());
+            assert_eq!(g.suppressed_until, None);
+        }
+
+        #[test]
+        fn custom_window_prunes_recent_reconnects() {
+            let config = ReconnectConfig {
+                gate_max_in_window: 1,
+                gate_window: Duration::from_secs(2),
+                gate_cooldown: Duration::from_secs(60),
+                ..Default::default()
+            };
+            let mut g = ReconnectGate::from(&config);
+            let t0 = Instant::now();
+
+            g.on_reconnect(t0);
+            assert!(g.can_reconnect(t0 + Duration::from_secs(1)).is_err());
+
+            let mut g = ReconnectGate::from(&config);
+            g.on_reconnect(t0);
+            assert!(g.can_reconnect(t0 + Duration::from_secs(3)).is_ok());
+        }
+
+        #[test]
+        fn zero_max_in_window_is_clamped() {
+            let config = ReconnectConfig {
+                gate_max_in_window: 0,
+                ..Default::default()
+            };
+            let mut g = ReconnectGate::from(&config);
+            let t0 = Instant::now();
+
+            assert!(g.can_reconnect(t0).is_ok());
+            g.on_reconnect(t0);
+            assert!(g.can_reconnect(t0 + Duration::from_secs(1)).is_err());
+        }
+    }
+
+    mod retry_strategy {
+        use super::super::*;
+        use test_log::test;
+
+        #[test]
+        fn honors_custom_cap() {
+            let config = ReconnectConfig {
+                retry_base: Duration::from_millis(10),
+                retry_cap: Duration::from_millis(50),
+                ..Default::default()
+            };
+            let mut strategy = RetryStrategy::from(&config);
+
+            assert_eq!(strategy.timeout(0), Duration::ZERO);
+            assert!(strategy.timeout(1) <= Duration::from_millis(10));
+            for attempt in 2..20 {
+                assert!(strategy.timeout(attempt) <= Duration::from_millis(50));
+            }
+        }
     }
 }
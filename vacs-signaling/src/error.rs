@@ -3,12 +3,16 @@ use std::ops::Add;
 use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio_tungstenite::tungstenite;
-use vacs_protocol::ws::{DisconnectReason, ErrorReason, LoginFailureReason};
+use vacs_protocol::ws::{DisconnectReason, ErrorReason, LoginFailureDetails, LoginFailureReason};
 
 #[derive(Debug, Error)]
 pub enum SignalingError {
     #[error("login failed: {0:?}")]
     LoginError(LoginFailureReason),
+    /// The server rejected the login since it does not support the client's protocol version.
+    /// Contains the server's guidance on which version to update to, if provided.
+    #[error("login failed: incompatible protocol version")]
+    IncompatibleProtocolVersion(LoginFailureDetails),
     #[error("transport error: {0}")]
     Transport(#[from] Box<tungstenite::error::Error>),
     #[error("signaling protocol error: {0}")]
@@ -37,6 +41,8 @@ pub enum SignalingRuntimeError {
     SerializationError(String),
     #[error("rate limited for {0}")]
     RateLimited(UntilInstant),
+    #[error("send queue full")]
+    SendQueueFull,
 }
 
 impl SignalingRuntimeError {
@@ -86,15 +92,17 @@ impl From<SignalingError> for ReconnectFailureReason {
     fn from(value: SignalingError) -> ReconnectFailureReason {
         match value {
             SignalingError::LoginError(reason) => ReconnectFailureReason::Login(reason),
+            SignalingError::IncompatibleProtocolVersion(_) => {
+                ReconnectFailureReason::Login(LoginFailureReason::IncompatibleProtocolVersion)
+            }
             SignalingError::Transport(_) => ReconnectFailureReason::Connection,
             SignalingError::ProtocolError(reason) => ReconnectFailureReason::Other(reason),
             SignalingError::Timeout(reason) => ReconnectFailureReason::Other(reason),
             SignalingError::Runtime(error) => match error {
                 SignalingRuntimeError::Disconnected(_)
                 | SignalingRuntimeError::ServerError(_)
-                | SignalingRuntimeError::SerializationError(_) => {
-                    ReconnectFailureReason::Connection
-                }
+                | SignalingRuntimeError::SerializationError(_)
+                | SignalingRuntimeError::SendQueueFull => ReconnectFailureReason::Connection,
                 _ => {
                     unreachable!("SignalingRuntimeError is not valid as ReconnectFailureReason");
                 }
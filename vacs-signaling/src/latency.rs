@@ -0,0 +1,141 @@
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite;
+
+/// Maximum number of pings awaiting a pong, older pings are considered lost once exceeded.
+const MAX_OUTSTANDING_PINGS: usize = 8;
+
+/// Measures the round-trip time to the signaling server using WebSocket ping/pong frames.
+///
+/// Every ping sent via [`LatencyTracker::ping`] carries a sequence number, which the server echoes
+/// back in its pong. Pongs passed to [`LatencyTracker::pong`] are matched against the outstanding
+/// pings, publishing the measured round-trip time to all subscribers. Pongs without a matching
+/// ping (e.g. replies to the transport's own heartbeat pings) are ignored.
+#[derive(Debug, Clone)]
+pub struct LatencyTracker {
+    inner: Arc<LatencyTrackerInner>,
+}
+
+#[derive(Debug)]
+struct LatencyTrackerInner {
+    next_seq: Mutex<u64>,
+    outstanding: Mutex<VecDeque<(u64, Instant)>>,
+    latency_tx: watch::Sender<Option<Duration>>,
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(LatencyTrackerInner {
+                next_seq: Mutex::new(0),
+                outstanding: Mutex::new(VecDeque::with_capacity(MAX_OUTSTANDING_PINGS)),
+                latency_tx: watch::channel(None).0,
+            }),
+        }
+    }
+
+    /// Subscribes to a watch containing the last measured round-trip time, `None` if no
+    /// measurement is available for the current connection.
+    pub fn subscribe(&self) -> watch::Receiver<Option<Duration>> {
+        self.inner.latency_tx.subscribe()
+    }
+
+    /// Returns the last measured round-trip time.
+    pub fn latency(&self) -> Option<Duration> {
+        *self.inner.latency_tx.borrow()
+    }
+
+    /// Creates a new ping frame, recording the instant it was created at.
+    pub fn ping(&self) -> tungstenite::Message {
+        let seq = {
+            let mut next_seq = self.inner.next_seq.lock();
+            *next_seq = next_seq.wrapping_add(1);
+            *next_seq
+        };
+
+        let mut outstanding = self.inner.outstanding.lock();
+        if outstanding.len() == MAX_OUTSTANDING_PINGS {
+            outstanding.pop_front();
+        }
+        outstanding.push_back((seq, Instant::now()));
+
+        tungstenite::Message::Ping(tungstenite::Bytes::copy_from_slice(&seq.to_be_bytes()))
+    }
+
+    /// Handles a pong frame received from the server, returning the measured round-trip time if
+    /// it answers an outstanding ping.
+    pub fn pong(&self, payload: &[u8]) -> Option<Duration> {
+        let seq = u64::from_be_bytes(payload.try_into().ok()?);
+
+        let sent_at = {
+            let mut outstanding = self.inner.outstanding.lock();
+            let index = outstanding.iter().position(|(s, _)| *s == seq)?;
+            // Pings sent before the matched one will not be answered anymore
+            let (_, sent_at) = outstanding.drain(..=index).next_back()?;
+            sent_at
+        };
+
+        let rtt = sent_at.elapsed();
+        tracing::trace!(?rtt, "Measured signaling round-trip time");
+        self.inner.latency_tx.send_replace(Some(rtt));
+        Some(rtt)
+    }
+
+    /// Discards all outstanding pings and the last measurement, e.g. after disconnecting.
+    pub fn reset(&self) {
+        self.inner.outstanding.lock().clear();
+        self.inner.latency_tx.send_replace(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn payload(msg: tungstenite::Message) -> tungstenite::Bytes {
+        match msg {
+            tungstenite::Message::Ping(data) => data,
+            other => panic!("Expected Ping frame, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn measures_matching_pong() {
+        let tracker = LatencyTracker::new();
+        let rx = tracker.subscribe();
+
+        let first = payload(tracker.ping());
+        let second = payload(tracker.ping());
+        std::thread::sleep(Duration::from_millis(20));
+
+        let rtt = tracker.pong(&second).unwrap();
+        assert!(rtt >= Duration::from_millis(20));
+        assert_eq!(*rx.borrow(), Some(rtt));
+
+        // Older pings are discarded once a newer one was answered
+        assert_eq!(tracker.pong(&first), None);
+
+        tracker.reset();
+        assert_eq!(tracker.latency(), None);
+    }
+
+    #[test]
+    fn ignores_unknown_pongs() {
+        let tracker = LatencyTracker::new();
+        tracker.ping();
+
+        assert_eq!(tracker.pong(b""), None);
+        assert_eq!(tracker.pong(&42u64.to_be_bytes()), None);
+        assert_eq!(tracker.latency(), None);
+    }
+}
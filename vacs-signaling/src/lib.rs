@@ -1,6 +1,7 @@
 pub mod auth;
 pub mod client;
 pub mod error;
+pub mod latency;
 pub mod matcher;
 #[cfg(feature = "test-utils")]
 pub mod test_utils;
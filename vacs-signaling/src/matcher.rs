@@ -1,8 +1,9 @@
 use crate::error::{SignalingError, SignalingRuntimeError};
+use parking_lot::Mutex;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{Mutex, oneshot};
+use tokio::sync::oneshot;
 use tracing::instrument;
 use vacs_protocol::ws::SignalingMessage;
 
@@ -20,11 +21,57 @@ pub struct ResponseMatcher {
     inner: Arc<Mutex<VecDeque<MatcherEntry>>>,
 }
 
+/// A matcher entry registered via [`ResponseMatcher::register`], resolving once a matching
+/// message has been received.
+///
+/// Dropping it before a match abandons the entry, it will not consume any further messages.
+pub struct PendingMatch {
+    rx: oneshot::Receiver<SignalingMessage>,
+}
+
+impl PendingMatch {
+    /// Waits for the matching message with a timeout.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Message)` if a matching message was received within the timeout.
+    /// - `Err(SignalingError:Timeout)` if the timeout was reached before a matching message was received.
+    /// - `Err(SignalingError:Disconnected)` if the Matcher was closed unexpectedly.
+    pub async fn wait(self, timeout: Duration) -> Result<SignalingMessage, SignalingError> {
+        match tokio::time::timeout(timeout, self.rx).await {
+            Ok(Ok(msg)) => Ok(msg),
+            Ok(Err(_)) => Err(SignalingError::Runtime(
+                SignalingRuntimeError::Disconnected(None),
+            )),
+            Err(_) => Err(SignalingError::Timeout("Matcher timed out".to_string())),
+        }
+    }
+}
+
 impl ResponseMatcher {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Registers a matcher entry for the given predicate without waiting for it.
+    ///
+    /// Registering before sending a request guarantees a response arriving immediately
+    /// afterward is not missed. Use [`PendingMatch::wait`] to retrieve the matched message.
+    pub fn register<F>(&self, predicate: F) -> PendingMatch
+    where
+        F: Fn(&SignalingMessage) -> bool + Send + Sync + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+
+        let entry = MatcherEntry {
+            predicate: Box::new(predicate),
+            responder: tx,
+        };
+
+        self.inner.lock().push_back(entry);
+        PendingMatch { rx }
+    }
+
     /// Waits for an incoming message to match the given predicate with a timeout.
     /// Entries are evaluated in order of appearance and removed from the internal queue in case of a match.
     /// Only the first successful matcher will receive the message.
@@ -43,22 +90,7 @@ impl ResponseMatcher {
     where
         F: Fn(&SignalingMessage) -> bool + Send + Sync + 'static,
     {
-        let (tx, rx) = oneshot::channel();
-
-        let entry = MatcherEntry {
-            predicate: Box::new(predicate),
-            responder: tx,
-        };
-
-        self.inner.lock().await.push_back(entry);
-
-        match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(msg)) => Ok(msg),
-            Ok(Err(_)) => Err(SignalingError::Runtime(
-                SignalingRuntimeError::Disconnected(None),
-            )),
-            Err(_) => Err(SignalingError::Timeout("Matcher timed out".to_string())),
-        }
+        self.register(predicate).wait(timeout).await
     }
 
     /// Waits for an incoming message to match the given predicate until one has been received.
@@ -84,11 +116,14 @@ impl ResponseMatcher {
 
     /// Called by the receiving task to check if a message completes any match. If so, the message is
     /// forwarded to the matcher awaiting it and not processed any further by [`try_match`].
+    ///
+    /// Entries whose waiter has already given up (e.g. timed out) are discarded, so they never
+    /// swallow a message another waiter is still interested in.
     #[instrument(level = "debug", skip(self, msg))]
     pub fn try_match(&self, msg: &SignalingMessage) {
-        let mut inner = self.inner.try_lock();
-        if let Ok(ref mut queue) = inner
-            && let Some(pos) = queue.iter().position(|entry| (entry.predicate)(msg))
+        let mut queue = self.inner.lock();
+        queue.retain(|entry| !entry.responder.is_closed());
+        if let Some(pos) = queue.iter().position(|entry| (entry.predicate)(msg))
             && let Some(entry) = queue.remove(pos)
         {
             let _ = entry.responder.send(msg.clone());
@@ -98,8 +133,8 @@ impl ResponseMatcher {
     /// Clears all currently stored matchers.
     /// This should be called when the transport is disconnected/reset to a clean state to avoid
     /// an inconsistent consumer state.
-    pub async fn clear(&self) {
-        self.inner.lock().await.clear();
+    pub fn clear(&self) {
+        self.inner.lock().clear();
     }
 }
 
@@ -372,6 +407,37 @@ mod tests {
         assert_matches!(r2, Ok(SignalingMessage::ClientList { .. }));
     }
 
+    #[test(tokio::test)]
+    async fn register_receives_message_before_wait() {
+        let matcher = ResponseMatcher::new();
+
+        let pending = matcher.register(|m| matches!(m, SignalingMessage::Logout));
+        matcher.try_match(&SignalingMessage::Logout);
+
+        let result = pending.wait(Duration::from_millis(10)).await;
+        assert_matches!(result, Ok(SignalingMessage::Logout));
+    }
+
+    #[test(tokio::test)]
+    async fn try_match_skips_abandoned_entries() {
+        let matcher = ResponseMatcher::new();
+
+        let expired = matcher
+            .wait_for_with_timeout(
+                |m| matches!(m, SignalingMessage::Logout),
+                Duration::from_millis(1),
+            )
+            .await;
+        assert_matches!(expired, Err(SignalingError::Timeout(_)));
+
+        let pending = matcher.register(|m| matches!(m, SignalingMessage::Logout));
+        matcher.try_match(&SignalingMessage::Logout);
+
+        let result = pending.wait(Duration::from_millis(10)).await;
+        assert_matches!(result, Ok(SignalingMessage::Logout));
+        assert!(matcher.inner.lock().is_empty());
+    }
+
     #[test(tokio::test)]
     async fn try_match_without_matchers() {
         let matcher = ResponseMatcher::new();
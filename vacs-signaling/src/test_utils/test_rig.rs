@@ -1,5 +1,5 @@
 use crate::auth::mock::MockTokenProvider;
-use crate::client::{SignalingClient, SignalingEvent};
+use crate::client::{ReconnectConfig, SignalingClient, SignalingEvent};
 use crate::test_utils::RecvWithTimeoutExt;
 use crate::transport::tokio::TokioTransport;
 use std::time::Duration;
@@ -57,7 +57,7 @@ impl TestRig {
                 |_| async {},
                 shutdown_token.child_token(),
                 Duration::from_millis(100),
-                8,
+                ReconnectConfig::default(),
                 &tokio::runtime::Handle::current(),
             );
 
@@ -3,10 +3,11 @@ pub mod mock;
 pub mod tokio;
 
 use crate::error::{SignalingError, SignalingRuntimeError};
+use crate::latency::LatencyTracker;
 use ::tokio::sync::mpsc;
 use async_trait::async_trait;
 use tokio_tungstenite::tungstenite;
-use vacs_protocol::ws::SignalingMessage;
+use vacs_protocol::ws::{SignalingMessage, WireFormat};
 
 #[async_trait]
 pub trait SignalingTransport: Send + Sync + 'static {
@@ -14,6 +15,11 @@ pub trait SignalingTransport: Send + Sync + 'static {
     type Receiver: SignalingReceiver;
 
     async fn connect(&self) -> Result<(Self::Sender, Self::Receiver), SignalingError>;
+
+    /// Returns the [`WireFormat`] messages sent via this transport must be encoded with.
+    fn wire_format(&self) -> WireFormat {
+        WireFormat::Json
+    }
 }
 
 #[async_trait]
@@ -28,4 +34,9 @@ pub trait SignalingReceiver: Send + Sync + 'static {
         &mut self,
         send_tx: &mpsc::Sender<tungstenite::Message>,
     ) -> Result<SignalingMessage, SignalingRuntimeError>;
+
+    /// Passes all pong frames received from now on to the given [`LatencyTracker`].
+    ///
+    /// Transports not supporting latency measurements can ignore this.
+    fn track_latency(&mut self, _tracker: LatencyTracker) {}
 }
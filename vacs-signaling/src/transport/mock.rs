@@ -1,4 +1,5 @@
 use crate::error::{SignalingError, SignalingRuntimeError, TransportFailureReason};
+use crate::latency::LatencyTracker;
 use crate::transport::{SignalingReceiver, SignalingSender, SignalingTransport};
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -31,6 +32,19 @@ impl Default for MockTransport {
     }
 }
 
+impl Clone for MockTransport {
+    fn clone(&self) -> Self {
+        Self {
+            outgoing_rx: self.outgoing_rx.resubscribe(),
+            outgoing_tx: self.outgoing_tx.clone(),
+            incoming_tx: self.incoming_tx.clone(),
+            incoming_rx: self.incoming_rx.resubscribe(),
+            ready: self.ready.clone(),
+            disconnect_token: self.disconnect_token.clone(),
+        }
+    }
+}
+
 impl MockTransport {
     pub fn disconnect_token(&self) -> CancellationToken {
         self.disconnect_token.clone()
@@ -50,6 +64,7 @@ impl SignalingTransport for MockTransport {
         let receiver = MockReceiver {
             rx: self.incoming_tx.subscribe(),
             disconnect_token: self.disconnect_token.child_token(),
+            latency: None,
         };
 
         self.ready.notify_one();
@@ -66,6 +81,7 @@ pub struct MockSender {
 pub struct MockReceiver {
     rx: broadcast::Receiver<tungstenite::Message>,
     disconnect_token: CancellationToken,
+    latency: Option<LatencyTracker>,
 }
 
 #[async_trait]
@@ -134,6 +150,11 @@ impl SignalingReceiver for MockReceiver {
                                 return Err(SignalingRuntimeError::Disconnected(None));
                             }
                         }
+                        Ok(tungstenite::Message::Pong(data)) => {
+                            if let Some(latency) = &self.latency {
+                                latency.pong(&data);
+                            }
+                        }
                         Ok(other) => {
                             tracing::debug!(?other, "Skipping non-text WebSocket frame");
                         }
@@ -146,4 +167,8 @@ impl SignalingReceiver for MockReceiver {
             }
         }
     }
+
+    fn track_latency(&mut self, tracker: LatencyTracker) {
+        self.latency = Some(tracker);
+    }
 }
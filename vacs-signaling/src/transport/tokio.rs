@@ -1,4 +1,5 @@
 use crate::error::{SignalingError, SignalingRuntimeError, TransportFailureReason};
+use crate::latency::LatencyTracker;
 use crate::transport::{SignalingReceiver, SignalingSender, SignalingTransport};
 use async_trait::async_trait;
 use futures_util::stream::{SplitSink, SplitStream};
@@ -9,26 +10,52 @@ use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
 use tokio::sync::{Notify, mpsc, watch};
 use tokio::task::JoinHandle;
-use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::http::header::SEC_WEBSOCKET_PROTOCOL;
 use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, WebSocketConfig};
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite};
 use tokio_util::sync::CancellationToken;
-use vacs_protocol::ws::SignalingMessage;
+use vacs_protocol::ws::{SignalingMessage, WireFormat};
 
 const HEARTBEAT_PING_INTERVAL: Duration = Duration::from_secs(15);
 const HEARTBEAT_PONG_TIMEOUT: Duration = Duration::from_secs(5);
+/// Default maximum size (in bytes) of a single message received from the server.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+/// Factor applied to the maximum message size to derive the limit enforced by the WebSocket
+/// protocol layer. Frames exceeding it are dropped without being buffered, closing the connection.
+const MESSAGE_SIZE_HARD_LIMIT_FACTOR: usize = 4;
 
 #[derive(Debug, Clone)]
 pub struct TokioTransport {
     url: String,
+    wire_format: WireFormat,
+    max_message_size: usize,
 }
 
 impl TokioTransport {
     pub fn new(url: &str) -> Self {
         Self {
             url: url.to_string(),
+            wire_format: WireFormat::default(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
         }
     }
+
+    /// Encodes messages using the given [`WireFormat`], requesting it from the server via the
+    /// WebSocket subprotocol. Connecting fails if the server does not support the format.
+    pub fn with_wire_format(mut self, wire_format: WireFormat) -> Self {
+        self.wire_format = wire_format;
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of a single message received from the server. Larger
+    /// messages are discarded without being decoded.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
 }
 
 #[async_trait]
@@ -36,15 +63,37 @@ impl SignalingTransport for TokioTransport {
     type Sender = TokioSender;
     type Receiver = TokioReceiver;
 
+    fn wire_format(&self) -> WireFormat {
+        self.wire_format
+    }
+
     #[tracing::instrument(level = "info", err)]
     async fn connect(&self) -> Result<(Self::Sender, Self::Receiver), SignalingError> {
         tracing::info!("Connecting to signaling server");
-        let (websocket_stream, response) = tokio_tungstenite::connect_async(&self.url)
-            .await
-            .map_err(|err| {
-                tracing::error!(?err, "Failed to connect to signaling server");
-                SignalingError::Transport(err.into())
-            })?;
+        let mut request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(|err| SignalingError::Transport(err.into()))?;
+        // JSON is used by servers predating format negotiation, so no subprotocol is requested.
+        if self.wire_format != WireFormat::Json {
+            request.headers_mut().insert(
+                SEC_WEBSOCKET_PROTOCOL,
+                HeaderValue::from_static(self.wire_format.subprotocol()),
+            );
+        }
+
+        let hard_limit = self.max_message_size * MESSAGE_SIZE_HARD_LIMIT_FACTOR;
+        let config = WebSocketConfig::default()
+            .max_message_size(Some(hard_limit))
+            .max_frame_size(Some(hard_limit));
+        let (websocket_stream, response) =
+            tokio_tungstenite::connect_async_with_config(request, Some(config), false)
+                .await
+                .map_err(|err| {
+                    tracing::error!(?err, "Failed to connect to signaling server");
+                    SignalingError::Transport(err.into())
+                })?;
         tracing::debug!(?response, "WebSocket handshake response");
 
         let (websocket_tx, websocket_rx) = websocket_stream.split();
@@ -52,7 +101,7 @@ impl SignalingTransport for TokioTransport {
         tracing::info!("Successfully established connection to signaling server");
         Ok((
             TokioSender::new(websocket_tx),
-            TokioReceiver::new(websocket_rx),
+            TokioReceiver::new(websocket_rx, self.max_message_size),
         ))
     }
 }
@@ -66,6 +115,8 @@ pub struct TokioReceiver {
     cancel: CancellationToken,
     heartbeat_state: Arc<HeartbeatState>,
     heartbeat_handle: Option<JoinHandle<()>>,
+    latency: Option<LatencyTracker>,
+    max_message_size: usize,
 }
 
 #[async_trait]
@@ -129,20 +180,19 @@ impl SignalingReceiver for TokioReceiver {
                         Ok(tungstenite::Message::Text(text)) => {
                             tracing::debug!("Received message");
                             self.heartbeat_state.mark_rx();
-                            return match SignalingMessage::deserialize(&text) {
-                                Ok(SignalingMessage::Disconnected { reason }) => {
-                                    tracing::debug!(
-                                        ?reason,
-                                        "Received Disconnected message, returning disconnected error"
-                                    );
-                                    Err(SignalingRuntimeError::Disconnected(Some(reason)))
-                                }
-                                Ok(msg) => Ok(msg),
-                                Err(err) => {
-                                    tracing::warn!(?err, "Failed to deserialize message");
-                                    Err(SignalingRuntimeError::SerializationError(err.to_string()))
-                                }
-                            };
+                            if self.is_oversized(text.len()) {
+                                continue;
+                            }
+                            return Self::decode(text.as_bytes(), WireFormat::Json);
+                        }
+                        #[cfg(feature = "msgpack")]
+                        Ok(tungstenite::Message::Binary(data)) => {
+                            tracing::debug!("Received message");
+                            self.heartbeat_state.mark_rx();
+                            if self.is_oversized(data.len()) {
+                                continue;
+                            }
+                            return Self::decode(&data, WireFormat::MessagePack);
                         }
                         Ok(tungstenite::Message::Close(reason)) => {
                             tracing::warn!(?reason, "Received Close WebSocket frame");
@@ -155,8 +205,11 @@ impl SignalingReceiver for TokioReceiver {
                                 return Err(SignalingRuntimeError::Disconnected(None));
                             }
                         }
-                        Ok(tungstenite::Message::Pong(_)) => {
+                        Ok(tungstenite::Message::Pong(data)) => {
                             self.heartbeat_state.mark_pong();
+                            if let Some(latency) = &self.latency {
+                                latency.pong(&data);
+                            }
                         }
                         Ok(other) => {
                             tracing::debug!(?other, "Skipping non-text WebSocket frame");
@@ -174,6 +227,10 @@ impl SignalingReceiver for TokioReceiver {
         tracing::warn!("WebSocket stream closed");
         Err(SignalingRuntimeError::Disconnected(None))
     }
+
+    fn track_latency(&mut self, tracker: LatencyTracker) {
+        self.latency = Some(tracker);
+    }
 }
 
 impl TokioSender {
@@ -185,12 +242,49 @@ impl TokioSender {
 }
 
 impl TokioReceiver {
-    fn new(websocket_rx: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>) -> Self {
+    fn new(
+        websocket_rx: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+        max_message_size: usize,
+    ) -> Self {
         Self {
             websocket_rx,
             cancel: CancellationToken::new(),
             heartbeat_state: HeartbeatState::new(),
             heartbeat_handle: None,
+            latency: None,
+            max_message_size,
+        }
+    }
+
+    fn is_oversized(&self, size: usize) -> bool {
+        if size > self.max_message_size {
+            tracing::warn!(
+                size,
+                max_message_size = self.max_message_size,
+                "Discarding oversized message"
+            );
+            return true;
+        }
+        false
+    }
+
+    fn decode(
+        message: &[u8],
+        wire_format: WireFormat,
+    ) -> Result<SignalingMessage, SignalingRuntimeError> {
+        match SignalingMessage::decode(message, wire_format) {
+            Ok(SignalingMessage::Disconnected { reason }) => {
+                tracing::debug!(
+                    ?reason,
+                    "Received Disconnected message, returning disconnected error"
+                );
+                Err(SignalingRuntimeError::Disconnected(Some(reason)))
+            }
+            Ok(msg) => Ok(msg),
+            Err(err) => {
+                tracing::warn!(?err, "Failed to deserialize message");
+                Err(SignalingRuntimeError::SerializationError(err.to_string()))
+            }
         }
     }
 
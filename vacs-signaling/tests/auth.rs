@@ -2,10 +2,12 @@ use pretty_assertions::assert_matches;
 use std::time::Duration;
 use test_log::test;
 use tokio_util::sync::CancellationToken;
+#[cfg(feature = "msgpack")]
+use vacs_protocol::ws::WireFormat;
 use vacs_protocol::ws::{LoginFailureReason, SignalingMessage};
 use vacs_server::test_utils::{TestApp, TestClient};
 use vacs_signaling::auth::mock::MockTokenProvider;
-use vacs_signaling::client::{SignalingClient, SignalingEvent, State};
+use vacs_signaling::client::{ReconnectConfig, SignalingClient, SignalingEvent, State};
 use vacs_signaling::error::SignalingError;
 use vacs_signaling::test_utils::{RecvWithTimeoutExt, TestRig};
 use vacs_signaling::transport::tokio::TokioTransport;
@@ -25,7 +27,7 @@ async fn login_without_self() {
         |_| async {},
         shutdown_token.clone(),
         Duration::from_millis(100),
-        8,
+        ReconnectConfig::default(),
         &tokio::runtime::Handle::current(),
     );
 
@@ -44,6 +46,55 @@ async fn login_without_self() {
     client.disconnect().await;
 }
 
+#[cfg(feature = "msgpack")]
+#[test(tokio::test)]
+#[cfg_attr(target_os = "windows", ignore)]
+async fn login_msgpack() {
+    let test_app = TestApp::new().await;
+
+    let transport = TokioTransport::new(test_app.addr()).with_wire_format(WireFormat::MessagePack);
+    let token_provider = MockTokenProvider::new(1, None);
+    let shutdown_token = CancellationToken::new();
+
+    let client = SignalingClient::new(
+        transport,
+        token_provider,
+        |_| async {},
+        shutdown_token.clone(),
+        Duration::from_millis(100),
+        ReconnectConfig::default(),
+        &tokio::runtime::Handle::current(),
+    );
+
+    let mut broadcast_rx = client.subscribe();
+    let res = client.connect().await;
+    let client_list_event = broadcast_rx
+        .recv_with_timeout(Duration::from_millis(100), |event| {
+            matches!(
+                event,
+                SignalingEvent::Message(SignalingMessage::ClientList { .. })
+            )
+        })
+        .await;
+    assert!(res.is_ok());
+    assert!(client_list_event.is_ok());
+
+    // Responses to requests sent after login must be encoded using the negotiated format as well
+    client.send(SignalingMessage::ListClients).await.unwrap();
+    let client_list_event = broadcast_rx
+        .recv_with_timeout(Duration::from_millis(100), |event| {
+            matches!(
+                event,
+                SignalingEvent::Message(SignalingMessage::ClientList { .. })
+            )
+        })
+        .await;
+    assert!(client_list_event.is_ok());
+
+    shutdown_token.cancel();
+    client.disconnect().await;
+}
+
 #[test(tokio::test)]
 #[cfg_attr(target_os = "windows", ignore)]
 async fn login() {
@@ -59,7 +110,7 @@ async fn login() {
         |_| async {},
         shutdown_token1.child_token(),
         Duration::from_millis(100),
-        8,
+        ReconnectConfig::default(),
         &tokio::runtime::Handle::current(),
     );
 
@@ -84,7 +135,7 @@ async fn login() {
         |_| async {},
         shutdown_token2.child_token(),
         Duration::from_millis(100),
-        8,
+        ReconnectConfig::default(),
         &tokio::runtime::Handle::current(),
     );
 
@@ -120,7 +171,7 @@ async fn login_timeout() {
         |_| async {},
         shutdown_token.clone(),
         Duration::from_millis(100),
-        8,
+        ReconnectConfig::default(),
         &tokio::runtime::Handle::current(),
     );
 
@@ -147,7 +198,7 @@ async fn login_invalid_credentials() {
         |_| async {},
         shutdown_token.clone(),
         Duration::from_millis(100),
-        8,
+        ReconnectConfig::default(),
         &tokio::runtime::Handle::current(),
     );
 
@@ -177,7 +228,7 @@ async fn login_duplicate_id() {
         |_| async {},
         shutdown_token.clone(),
         Duration::from_millis(100),
-        8,
+        ReconnectConfig::default(),
         &tokio::runtime::Handle::current(),
     );
 
@@ -35,11 +35,14 @@
 
 use crate::{ControllerInfo, FacilityType};
 use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
 use tracing::instrument;
 
 /// Default timeout for HTTP requests against the slurper API.
 /// Can be overwritten using [`SlurperClient::with_timeout`].
-const SLURPER_DEFAULT_HTTP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+const SLURPER_DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(1);
 /// User information endpoint for the slurper API.
 const SLURPER_USER_INFO_ENDPOINT: &str = "/users/info";
 /// Index of the callsign field in the slurper CSV line.
@@ -54,15 +57,145 @@ const SLURPER_FREQUENCY_FIELD_INDEX: usize = 3;
 /// Index of the visibility range field in the slurper CSV line.
 /// Fields are listed in the [VATSIM Slurper API docs](https://vatsim.dev/api/slurper-api/get-user-info).
 const SLURPER_VISIBILITY_RANGE_FIELD_INDEX: usize = 4;
+/// Upper bound (exclusive) for configurable field indices, well above the number of fields
+/// currently returned by the slurper.
+const SLURPER_MAX_FIELD_INDEX: usize = 64;
 /// Slurper facility type for ATC clients.
 const SLURPER_FACILITY_TYPE_ATC: &str = "atc";
 /// Slurper facility type for pilots.
 const SLURPER_FACILITY_TYPE_PILOT: &str = "pilot";
 
+/// Retry policy for transient failures of the slurper API.
+///
+/// Timeouts, connection errors and 5xx responses are retried with exponential backoff, other
+/// errors (e.g. 4xx responses) fail immediately. Can be overwritten using
+/// [`SlurperClient::with_retry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlurperRetryConfig {
+    /// Maximum number of attempts, including the initial request. `1` disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every subsequent retry.
+    pub base_delay: Duration,
+    /// Upper bound for the delay between two attempts.
+    pub max_delay: Duration,
+    /// Upper bound for the total time spent on all attempts and delays. Requests are cut short
+    /// and no further retries are attempted once the deadline is reached.
+    pub deadline: Duration,
+}
+
+impl Default for SlurperRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            deadline: Duration::from_secs(3),
+        }
+    }
+}
+
+impl SlurperRetryConfig {
+    /// Retry policy performing a single attempt only.
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before the given retry attempt (starting at 1), `base_delay * 2^(attempt - 1)`
+    /// capped at `max_delay`.
+    fn delay(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(1u32 << attempt.saturating_sub(1).min(31))
+            .min(self.max_delay)
+    }
+}
+
+/// Indices of the fields in the slurper CSV lines, allowing to adapt to format changes of the
+/// slurper API without a new release. Can be overwritten using
+/// [`SlurperClient::with_field_indices`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlurperFieldIndices {
+    pub callsign: usize,
+    pub facility_type: usize,
+    pub frequency: usize,
+    pub visibility_range: usize,
+}
+
+impl Default for SlurperFieldIndices {
+    fn default() -> Self {
+        Self {
+            callsign: SLURPER_CALLSIGN_FIELD_INDEX,
+            facility_type: SLURPER_FACILITY_TYPE_FIELD_INDEX,
+            frequency: SLURPER_FREQUENCY_FIELD_INDEX,
+            visibility_range: SLURPER_VISIBILITY_RANGE_FIELD_INDEX,
+        }
+    }
+}
+
+impl SlurperFieldIndices {
+    /// Validates that all indices are distinct and within a sane range. Index 0 holds the CID
+    /// of the line, so the configurable fields start at index 1.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let indices = [
+            ("callsign", self.callsign),
+            ("facility_type", self.facility_type),
+            ("frequency", self.frequency),
+            ("visibility_range", self.visibility_range),
+        ];
+
+        for (i, (name, index)) in indices.iter().enumerate() {
+            if !(1..SLURPER_MAX_FIELD_INDEX).contains(index) {
+                anyhow::bail!(
+                    "Slurper field index {name} ({index}) must be between 1 and {}",
+                    SLURPER_MAX_FIELD_INDEX - 1
+                );
+            }
+            if let Some((other, _)) = indices[..i].iter().find(|(_, other)| other == index) {
+                anyhow::bail!("Slurper field indices {other} and {name} are both set to {index}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result of looking up the VATSIM connection of a CID, see [`SlurperClient::lookup_controller`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControllerLookup {
+    /// The CID is connected as ATC with a visibility range and a valid station.
+    Active(ControllerInfo),
+    /// The CID is connected as ATC, but without any usable station, e.g. as an observer, with
+    /// ATIS stations only or with a callsign not denoting a valid facility.
+    ConnectedNoRange,
+    /// The CID is connected as a pilot.
+    Pilot,
+    /// The CID is not connected to VATSIM.
+    NotConnected,
+}
+
+impl ControllerLookup {
+    /// Specificity of the lookup, used to pick the most relevant result across multiple entries.
+    fn rank(&self) -> u8 {
+        match self {
+            ControllerLookup::Active(_) => 3,
+            ControllerLookup::ConnectedNoRange => 2,
+            ControllerLookup::Pilot => 1,
+            ControllerLookup::NotConnected => 0,
+        }
+    }
+}
+
 /// Client for accessing the VATSIM Slurper API.
 pub struct SlurperClient {
     /// HTTP client used for all requests.
     client: reqwest::Client,
+    /// Timeout of a single HTTP request.
+    timeout: Duration,
+    /// Retry policy for transient failures.
+    retry: SlurperRetryConfig,
+    /// Indices of the fields in the slurper CSV lines.
+    field_indices: SlurperFieldIndices,
     /// Full URL for the user information endpoint.
     user_info_endpoint_url: String,
 }
@@ -71,7 +204,8 @@ impl SlurperClient {
     /// Creates a new [`SlurperClient`] with the given API base URL.
     ///
     /// A default HTTP timeout is set ([`SLURPER_DEFAULT_HTTP_TIMEOUT`]), which can be overwritten
-    /// using [`SlurperClient::with_timeout`] if necessary.
+    /// using [`SlurperClient::with_timeout`] if necessary. Transient failures are retried using
+    /// the default [`SlurperRetryConfig`], see [`SlurperClient::with_retry`].
     ///
     /// # Examples
     ///
@@ -88,6 +222,9 @@ impl SlurperClient {
             .context("Failed to create HTTP client")?;
         Ok(Self {
             client,
+            timeout: SLURPER_DEFAULT_HTTP_TIMEOUT,
+            retry: SlurperRetryConfig::default(),
+            field_indices: SlurperFieldIndices::default(),
             user_info_endpoint_url: format!("{api_base_url}{SLURPER_USER_INFO_ENDPOINT}"),
         })
     }
@@ -105,12 +242,62 @@ impl SlurperClient {
     ///     .with_timeout(Duration::from_secs(2))
     ///     .unwrap();
     /// ```
-    pub fn with_timeout(mut self, timeout: std::time::Duration) -> anyhow::Result<Self> {
+    pub fn with_timeout(mut self, timeout: Duration) -> anyhow::Result<Self> {
         self.client = reqwest::ClientBuilder::new()
             .user_agent(crate::APP_USER_AGENT)
             .timeout(timeout)
             .build()
             .context("Failed to create HTTP client")?;
+        self.timeout = timeout;
+        Ok(self)
+    }
+
+    /// Creates a version of the [`SlurperClient`] with a user-defined [`SlurperRetryConfig`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use vacs_vatsim::slurper::{SlurperClient, SlurperRetryConfig};
+    /// use std::time::Duration;
+    ///
+    /// let client = SlurperClient::new("https://slurper.vatsim.net")
+    ///     .unwrap()
+    ///     .with_retry(SlurperRetryConfig {
+    ///         max_attempts: 5,
+    ///         deadline: Duration::from_secs(5),
+    ///         ..Default::default()
+    ///     });
+    /// ```
+    pub fn with_retry(mut self, retry: SlurperRetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Creates a version of the [`SlurperClient`] with user-defined [`SlurperFieldIndices`].
+    ///
+    /// Returns an error if the indices are not distinct or out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use vacs_vatsim::slurper::{SlurperClient, SlurperFieldIndices};
+    ///
+    /// let client = SlurperClient::new("https://slurper.vatsim.net")
+    ///     .unwrap()
+    ///     .with_field_indices(SlurperFieldIndices {
+    ///         frequency: 5,
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn with_field_indices(
+        mut self,
+        field_indices: SlurperFieldIndices,
+    ) -> anyhow::Result<Self> {
+        field_indices
+            .validate()
+            .context("Invalid slurper field indices")?;
+        self.field_indices = field_indices;
         Ok(self)
     }
 
@@ -160,58 +347,140 @@ impl SlurperClient {
     /// ```
     #[instrument(level = "debug", skip(self), err)]
     pub async fn get_controller_info(&self, cid: &str) -> anyhow::Result<Option<ControllerInfo>> {
+        Ok(match self.lookup_controller(cid).await? {
+            ControllerLookup::Active(info) => Some(info),
+            _ => None,
+        })
+    }
+
+    /// Looks up the VATSIM connection of a given CID, distinguishing why no controller info is
+    /// available.
+    ///
+    /// Uses the same selection as [`SlurperClient::get_controller_info`]. If no usable ATC
+    /// entry was found, the most specific [`ControllerLookup`] of all entries is returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use vacs_vatsim::slurper::{ControllerLookup, SlurperClient};
+    /// use wiremock::matchers::{method, path, query_param};
+    /// use wiremock::{Mock, MockServer, ResponseTemplate};
+    ///
+    /// #[tokio::test]
+    /// async fn lookup_controller() -> anyhow::Result<()> {
+    ///     let server = MockServer::start().await;
+    ///     Mock::given(method("GET"))
+    ///         .and(path("/users/info"))
+    ///         .and(query_param("cid", "1234567"))
+    ///         .respond_with(ResponseTemplate::new(200).set_body_string(
+    ///             "1234567,LOVV_OBS,atc,199.998,0,47.66667,14.33333,0,0,0,0,0,0,0,0,\n",
+    ///         ))
+    ///         .mount(&server)
+    ///         .await;
+    ///
+    ///     let client = SlurperClient::new(&server.uri())?;
+    ///
+    ///     let lookup = client.lookup_controller("1234567").await?;
+    ///
+    ///     assert_eq!(lookup, ControllerLookup::ConnectedNoRange);
+    ///     Ok(())
+    ///  }
+    /// ```
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn lookup_controller(&self, cid: &str) -> anyhow::Result<ControllerLookup> {
         tracing::debug!("Retrieving controller info for CID");
 
         if cid.is_empty() {
-            tracing::debug!("CID is empty, returning None");
-            return Ok(None);
+            tracing::debug!("CID is empty, returning NotConnected");
+            return Ok(ControllerLookup::NotConnected);
         }
 
         let body = self.fetch_slurper_data(cid).await?;
         if body.is_empty() {
-            tracing::debug!(?cid, "CID is not present in slurper, returning None");
-            return Ok(None);
+            tracing::debug!(
+                ?cid,
+                "CID is not present in slurper, returning NotConnected"
+            );
+            return Ok(ControllerLookup::NotConnected);
         }
 
         self.parse_slurper_data(cid, body)
     }
 
-    /// Performs an HTTP request to fetch the user info data from the Slurper API.
+    /// Fetches the user info data from the Slurper API, retrying transient failures according to
+    /// the configured [`SlurperRetryConfig`].
     #[instrument(level = "trace", skip(self), err)]
     async fn fetch_slurper_data(&self, cid: &str) -> anyhow::Result<bytes::Bytes> {
-        tracing::trace!("Performing HTTP request");
+        let deadline = Instant::now() + self.retry.deadline;
+        let mut attempt = 1;
+        loop {
+            let timeout = self
+                .timeout
+                .min(deadline.saturating_duration_since(Instant::now()));
+            let err = match self.fetch_slurper_data_once(cid, timeout).await {
+                Ok(body) => return Ok(body),
+                Err(err) => err,
+            };
+
+            let delay = self.retry.delay(attempt);
+            if !is_transient(&err)
+                || attempt >= self.retry.max_attempts
+                || Instant::now() + delay >= deadline
+            {
+                let context = if err.is_status() {
+                    "Received non-200 HTTP status code"
+                } else if err.is_body() || err.is_decode() {
+                    "Failed to read response body"
+                } else {
+                    "Failed to perform HTTP request"
+                };
+                return Err(err).context(context);
+            }
+
+            tracing::debug!(
+                ?attempt,
+                ?delay,
+                ?err,
+                "Transient slurper failure, retrying"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Performs a single HTTP request to fetch the user info data from the Slurper API.
+    async fn fetch_slurper_data_once(
+        &self,
+        cid: &str,
+        timeout: Duration,
+    ) -> Result<bytes::Bytes, reqwest::Error> {
+        tracing::trace!(?timeout, "Performing HTTP request");
         let response = self
             .client
             .get(self.user_info_endpoint_url.as_str())
             .query(&[("cid", cid)])
+            .timeout(timeout)
             .send()
-            .await
-            .context("Failed to perform HTTP request")?
-            .error_for_status()
-            .context("Received non-200 HTTP status code")?;
+            .await?
+            .error_for_status()?;
 
         tracing::trace!(content_length = ?response.content_length(), "Reading response body");
-        let body = response
-            .bytes()
-            .await
-            .context("Failed to read response body")?;
-
-        Ok(body)
+        response.bytes().await
     }
 
     /// Parses the CSV data retrieved from the Slurper user info endpoint and returns the
-    /// extracted [`ControllerInfo`].
+    /// extracted [`ControllerLookup`].
     #[instrument(level = "trace", skip(self, body), err)]
     fn parse_slurper_data(
         &self,
         cid: &str,
         body: bytes::Bytes,
-    ) -> anyhow::Result<Option<ControllerInfo>> {
+    ) -> anyhow::Result<ControllerLookup> {
         tracing::trace!("Parsing CSV");
         let mut reader = csv::ReaderBuilder::new()
             .has_headers(false)
             .from_reader(body.as_ref());
 
+        let mut lookup = ControllerLookup::NotConnected;
         for result in reader.records() {
             let record = match result {
                 Ok(rec) => rec,
@@ -222,15 +491,17 @@ impl SlurperClient {
             };
 
             match self.extract_controller_info(cid, record)? {
-                Some(info) => return Ok(Some(info)),
-                None => continue,
+                ControllerLookup::Active(info) => return Ok(ControllerLookup::Active(info)),
+                other if other.rank() > lookup.rank() => lookup = other,
+                _ => continue,
             }
         }
 
         tracing::debug!(
-            "CID is present in slurper, but no valid controller info found, returning None"
+            ?lookup,
+            "CID is present in slurper, but no valid controller info found"
         );
-        Ok(None)
+        Ok(lookup)
     }
 
     /// Extracts the [`ControllerInfo`] from the parsed [`csv::StringRecord`], validating the client is
@@ -240,51 +511,61 @@ impl SlurperClient {
         &self,
         cid: &str,
         record: csv::StringRecord,
-    ) -> anyhow::Result<Option<ControllerInfo>> {
-        let facility_type = record
-            .get(SLURPER_FACILITY_TYPE_FIELD_INDEX)
-            .unwrap_or(SLURPER_FACILITY_TYPE_PILOT);
+    ) -> anyhow::Result<ControllerLookup> {
+        let Some(facility_type) = record.get(self.field_indices.facility_type) else {
+            tracing::trace!("Facility type is not present in CSV record, returning NotConnected");
+            return Ok(ControllerLookup::NotConnected);
+        };
 
+        if facility_type.eq_ignore_ascii_case(SLURPER_FACILITY_TYPE_PILOT) {
+            tracing::trace!("CID is pilot, returning Pilot");
+            return Ok(ControllerLookup::Pilot);
+        }
         if !facility_type.eq_ignore_ascii_case(SLURPER_FACILITY_TYPE_ATC) {
-            tracing::trace!("CID is pilot, returning None");
-            return Ok(None);
+            tracing::trace!(
+                ?facility_type,
+                "Unknown facility type, returning NotConnected"
+            );
+            return Ok(ControllerLookup::NotConnected);
         }
 
         let visibility_range = record
-            .get(SLURPER_VISIBILITY_RANGE_FIELD_INDEX)
+            .get(self.field_indices.visibility_range)
             .unwrap_or("0")
             .parse::<i32>()
             .unwrap_or(0);
 
         if visibility_range == 0 {
-            tracing::trace!("Station has no visibility range, returning None");
-            return Ok(None);
+            tracing::trace!("Station has no visibility range, returning ConnectedNoRange");
+            return Ok(ControllerLookup::ConnectedNoRange);
         }
 
-        let callsign = match record.get(SLURPER_CALLSIGN_FIELD_INDEX) {
+        let callsign = match record.get(self.field_indices.callsign) {
             Some(callsign) => callsign,
             None => {
-                tracing::trace!("Callsign is not present in CSV record in slurper, returning None");
-                return Ok(None);
+                tracing::trace!(
+                    "Callsign is not present in CSV record in slurper, returning ConnectedNoRange"
+                );
+                return Ok(ControllerLookup::ConnectedNoRange);
             }
         };
         if callsign.is_empty() {
-            tracing::trace!("Empty callsign, returning None");
-            return Ok(None);
+            tracing::trace!("Empty callsign, returning ConnectedNoRange");
+            return Ok(ControllerLookup::ConnectedNoRange);
         }
 
-        let frequency = match record.get(SLURPER_FREQUENCY_FIELD_INDEX) {
+        let frequency = match record.get(self.field_indices.frequency) {
             Some(frequency) => frequency,
             None => {
                 tracing::trace!(
-                    "Frequency is not present in CSV record in slurper, returning None"
+                    "Frequency is not present in CSV record in slurper, returning ConnectedNoRange"
                 );
-                return Ok(None);
+                return Ok(ControllerLookup::ConnectedNoRange);
             }
         };
         if frequency.is_empty() {
-            tracing::trace!("Empty frequency, returning None");
-            return Ok(None);
+            tracing::trace!("Empty frequency, returning ConnectedNoRange");
+            return Ok(ControllerLookup::ConnectedNoRange);
         }
 
         let facility_type: FacilityType = callsign.into();
@@ -292,9 +573,9 @@ impl SlurperClient {
             tracing::warn!(
                 ?callsign,
                 ?frequency,
-                "Callsign is not a valid facility type, returning None"
+                "Callsign is not a valid facility type, returning ConnectedNoRange"
             );
-            return Ok(None);
+            return Ok(ControllerLookup::ConnectedNoRange);
         }
 
         tracing::debug!(
@@ -303,7 +584,7 @@ impl SlurperClient {
             ?facility_type,
             "Found controller info for CID"
         );
-        Ok(Some(ControllerInfo {
+        Ok(ControllerLookup::Active(ControllerInfo {
             cid: cid.to_string(),
             callsign: callsign.to_string(),
             frequency: frequency.to_string(),
@@ -312,6 +593,13 @@ impl SlurperClient {
     }
 }
 
+/// Whether the error is likely to be resolved by retrying the request.
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout()
+        || err.is_connect()
+        || err.status().is_some_and(|status| status.is_server_error())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -701,4 +989,258 @@ mod tests {
         assert_eq!(controller_info, None);
         Ok(())
     }
+
+    #[test(tokio::test)]
+    async fn get_controller_info_retries_server_errors() -> anyhow::Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/users/info"))
+            .and(query_param("cid", "1234567"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/users/info"))
+            .and(query_param("cid", "1234567"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "1234567,LOVV_CTR,atc,123.450,600,47.66667,14.33333,0,0,0,0,0,0,0,0,\n",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = SlurperClient::new(&server.uri())?.with_retry(SlurperRetryConfig {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(20),
+            deadline: Duration::from_secs(1),
+        });
+
+        let controller_info = client
+            .get_controller_info("1234567")
+            .await
+            .context("Failed to get controller info")?
+            .expect("No controller info found");
+
+        assert_eq!(controller_info.callsign, "LOVV_CTR".to_string());
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn get_controller_info_does_not_retry_client_errors() -> anyhow::Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/users/info"))
+            .and(query_param("cid", "1234567"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = SlurperClient::new(&server.uri())?.with_retry(SlurperRetryConfig {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(20),
+            deadline: Duration::from_secs(1),
+        });
+
+        let result = client.get_controller_info("1234567").await;
+
+        assert!(result.is_err());
+        server.verify().await;
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn get_controller_info_retries_within_deadline() -> anyhow::Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/users/info"))
+            .and(query_param("cid", "1234567"))
+            .respond_with(ResponseTemplate::new(500).set_delay(Duration::from_millis(50)))
+            .mount(&server)
+            .await;
+
+        let client = SlurperClient::new(&server.uri())?.with_retry(SlurperRetryConfig {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(1),
+            deadline: Duration::from_millis(300),
+        });
+
+        let started = std::time::Instant::now();
+        let result = client.get_controller_info("1234567").await;
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_millis(500));
+        let requests = server.received_requests().await.unwrap_or_default();
+        assert!(
+            (2..10).contains(&requests.len()),
+            "unexpected number of attempts: {}",
+            requests.len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn retry_delay_is_exponential_and_capped() {
+        let retry = SlurperRetryConfig {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+            deadline: Duration::from_secs(10),
+        };
+        assert_eq!(retry.delay(1), Duration::from_millis(100));
+        assert_eq!(retry.delay(2), Duration::from_millis(200));
+        assert_eq!(retry.delay(3), Duration::from_millis(400));
+        assert_eq!(retry.delay(4), Duration::from_millis(500));
+        assert_eq!(retry.delay(100), Duration::from_millis(500));
+    }
+
+    #[test(tokio::test)]
+    async fn lookup_controller_scenarios() -> anyhow::Result<()> {
+        let active = ControllerLookup::Active(ControllerInfo {
+            cid: "1234567".to_string(),
+            callsign: "LOVV_CTR".to_string(),
+            frequency: "123.450".to_string(),
+            facility_type: FacilityType::Enroute,
+        });
+
+        for (body, expected) in [
+            (
+                "1234567,LOVV_CTR,atc,123.450,600,47.66667,14.33333,0,0,0,0,0,0,0,0,\n",
+                active.clone(),
+            ),
+            (
+                "1234567,LOWW_D_ATIS,atc,121.730,0,48.11028,16.56972,0,0,0,0,0,0,0,0,\n\
+                1234567,LOVV_CTR,atc,123.450,600,47.66667,14.33333,0,0,0,0,0,0,0,0,\n",
+                active,
+            ),
+            (
+                "1234567,LOVV_OBS,atc,199.998,0,47.66667,14.33333,0,0,0,0,0,0,0,0,\n",
+                ControllerLookup::ConnectedNoRange,
+            ),
+            (
+                "1234567,LOWW_D_ATIS,atc,121.730,50,48.11028,16.56972,0,0,0,0,0,0,0,0,\n",
+                ControllerLookup::ConnectedNoRange,
+            ),
+            (
+                "1234567,,atc,123.450,600\n",
+                ControllerLookup::ConnectedNoRange,
+            ),
+            (
+                "1234567,LOVV_CTR,atc,,600\n",
+                ControllerLookup::ConnectedNoRange,
+            ),
+            (
+                "1234567,AUA123,pilot,,,47.66667,14.33333,0,0,0,0,0,0,0,0,\n",
+                ControllerLookup::Pilot,
+            ),
+            (
+                "1234567,AUA123,pilot,,,47.66667,14.33333,0,0,0,0,0,0,0,0,\n\
+                1234567,LOVV_OBS,atc,199.998,0,47.66667,14.33333,0,0,0,0,0,0,0,0,\n",
+                ControllerLookup::ConnectedNoRange,
+            ),
+            ("", ControllerLookup::NotConnected),
+            ("\n", ControllerLookup::NotConnected),
+            ("1234567,LOVV_CTR,\n", ControllerLookup::NotConnected),
+            ("1234567\n", ControllerLookup::NotConnected),
+        ] {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/users/info"))
+                .and(query_param("cid", "1234567"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+
+            let client = SlurperClient::new(&server.uri()).context("Failed to create client")?;
+
+            let lookup = client
+                .lookup_controller("1234567")
+                .await
+                .context("Failed to look up controller")?;
+            assert_eq!(lookup, expected, "{body:?}");
+        }
+
+        let client = SlurperClient::new("http://localhost").context("Failed to create client")?;
+        assert_eq!(
+            client.lookup_controller("").await?,
+            ControllerLookup::NotConnected
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validate_field_indices() {
+        assert!(SlurperFieldIndices::default().validate().is_ok());
+
+        let duplicate = SlurperFieldIndices {
+            frequency: SLURPER_CALLSIGN_FIELD_INDEX,
+            ..Default::default()
+        };
+        let err = duplicate.validate().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Slurper field indices callsign and frequency are both set to 1"
+        );
+
+        let out_of_range = SlurperFieldIndices {
+            visibility_range: 1000,
+            ..Default::default()
+        };
+        assert!(out_of_range.validate().is_err());
+
+        let cid_index = SlurperFieldIndices {
+            callsign: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            cid_index.validate().unwrap_err().to_string(),
+            "Slurper field index callsign (0) must be between 1 and 63"
+        );
+
+        assert!(
+            SlurperClient::new("https://example.org")
+                .unwrap()
+                .with_field_indices(duplicate)
+                .is_err()
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn get_controller_info_custom_field_indices() -> anyhow::Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/users/info"))
+            .and(query_param("cid", "1234567"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "1234567,atc,600,47.66667,14.33333,LOWW_D_ATIS,121.730,0\n\
+                1234567,atc,0,47.66667,14.33333,LOWW_A_ATIS,122.955,0\n\
+                1234567,atc,600,47.66667,14.33333,LOVV_CTR,123.450,0\n",
+            ))
+            .mount(&server)
+            .await;
+
+        let client =
+            SlurperClient::new(&server.uri())?.with_field_indices(SlurperFieldIndices {
+                callsign: 5,
+                facility_type: 1,
+                frequency: 6,
+                visibility_range: 2,
+            })?;
+
+        let controller_info = client
+            .get_controller_info("1234567")
+            .await
+            .context("Failed to get controller info")?
+            .expect("No controller info found");
+
+        assert_eq!(controller_info.callsign, "LOVV_CTR".to_string());
+        assert_eq!(controller_info.frequency, "123.450".to_string());
+        assert_eq!(controller_info.facility_type, FacilityType::Enroute);
+        Ok(())
+    }
 }
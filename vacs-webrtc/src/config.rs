@@ -1,11 +1,88 @@
+use serde::{Deserialize, Serialize};
+use vacs_audio::TARGET_SAMPLE_RATE;
+use vacs_audio::codec::PCMU_SAMPLE_RATE;
 use vacs_protocol::http::webrtc::{IceConfig, IceServer};
+use webrtc::api::media_engine::{MIME_TYPE_OPUS, MIME_TYPE_PCMU};
 use webrtc::ice_transport::ice_server::RTCIceServer;
 use webrtc::peer_connection::configuration::RTCConfiguration;
+use webrtc::rtp_transceiver::PayloadType;
+use webrtc::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
 
 pub(crate) const WEBRTC_TRACK_ID: &str = "audio";
 pub(crate) const WEBRTC_TRACK_STREAM_ID: &str = "main";
 pub(crate) const WEBRTC_CHANNELS: u16 = 1;
 pub(crate) const PEER_EVENTS_CAPACITY: usize = 128;
+/// Static RTP payload type of PCMU (RFC 3551).
+pub(crate) const PCMU_PAYLOAD_TYPE: PayloadType = 0;
+
+/// Expedited Forwarding (RFC 3246), the DSCP recommended for interactive voice.
+pub const DSCP_EF: u8 = 46;
+
+/// Opus format parameters (RFC 7587) pinned in the local SDP of every call, so FEC and DTX are
+/// negotiated explicitly instead of being left to the defaults of the remote implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpusFmtp {
+    /// Maximum sample rate (Hz) the local decoder is able to play back.
+    pub max_playback_rate: u32,
+    pub stereo: bool,
+    pub use_inband_fec: bool,
+    pub use_dtx: bool,
+}
+
+impl Default for OpusFmtp {
+    fn default() -> Self {
+        Self {
+            max_playback_rate: TARGET_SAMPLE_RATE,
+            stereo: false,
+            use_inband_fec: true,
+            use_dtx: false,
+        }
+    }
+}
+
+impl OpusFmtp {
+    /// Returns the fmtp parameters as key-value pairs, in the order they are added to the SDP.
+    pub(crate) fn params(&self) -> [(&'static str, String); 4] {
+        let flag = |enabled: bool| u8::from(enabled).to_string();
+        [
+            ("maxplaybackrate", self.max_playback_rate.to_string()),
+            ("stereo", flag(self.stereo)),
+            ("useinbandfec", flag(self.use_inband_fec)),
+            ("usedtx", flag(self.use_dtx)),
+        ]
+    }
+}
+
+/// Audio codec sent by the local peer.
+///
+/// Opus is the only codec meant for regular use, PCMU is available for interop testing with
+/// third-party endpoints. Selecting it registers PCMU as the only codec, so calls only succeed
+/// with peers supporting it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    #[default]
+    Opus,
+    Pcmu,
+}
+
+impl Codec {
+    pub(crate) fn capability(self) -> RTCRtpCodecCapability {
+        match self {
+            Codec::Opus => RTCRtpCodecCapability {
+                mime_type: MIME_TYPE_OPUS.to_owned(),
+                clock_rate: TARGET_SAMPLE_RATE,
+                channels: WEBRTC_CHANNELS,
+                ..Default::default()
+            },
+            Codec::Pcmu => RTCRtpCodecCapability {
+                mime_type: MIME_TYPE_PCMU.to_owned(),
+                clock_rate: PCMU_SAMPLE_RATE,
+                ..Default::default()
+            },
+        }
+    }
+}
 
 pub trait IntoRtc<T> {
     fn into_rtc(self) -> T;
@@ -1,9 +1,13 @@
 pub mod config;
 pub mod error;
+mod net;
 mod peer;
 mod receiver;
+mod sdp;
 mod sender;
 
+pub use peer::DtlsTransportState;
+pub use peer::IceConnectionState;
 pub use peer::Peer;
 pub use peer::PeerConnectionState;
 pub use peer::PeerEvent;
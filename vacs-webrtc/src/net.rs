@@ -0,0 +1,77 @@
+use anyhow::Context;
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use webrtc::ice::udp_mux::{UDPMuxDefault, UDPMuxParams};
+
+/// Binds the UDP socket used for all host candidates of a peer, marking outgoing packets with
+/// the given DSCP (e.g. [`DSCP_EF`](crate::config::DSCP_EF)).
+///
+/// Marking is best effort: some platforms (notably Windows without a matching QoS policy)
+/// silently ignore the socket option, in which case packets are sent unmarked.
+pub(crate) fn marked_udp_mux(dscp: u8) -> anyhow::Result<Arc<UDPMuxDefault>> {
+    let socket = bind_marked_socket(dscp)?;
+    Ok(UDPMuxDefault::new(UDPMuxParams::new(socket)))
+}
+
+fn bind_marked_socket(dscp: u8) -> anyhow::Result<tokio::net::UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))
+        .context("Failed to create media socket")?;
+
+    // DSCP occupies the upper six bits of the former IPv4 TOS field
+    let tos = u32::from(dscp) << 2;
+    match socket.set_tos(tos) {
+        Ok(()) => match socket.tos() {
+            Ok(applied) if applied == tos => {
+                tracing::debug!(?dscp, "Applied DSCP marking to media socket");
+            }
+            Ok(applied) => {
+                tracing::warn!(
+                    ?dscp,
+                    ?applied,
+                    "DSCP marking was not applied to media socket"
+                );
+            }
+            Err(err) => {
+                tracing::debug!(?dscp, ?err, "Failed to verify DSCP marking of media socket");
+            }
+        },
+        Err(err) => {
+            tracing::warn!(?dscp, ?err, "Failed to set DSCP marking on media socket");
+        }
+    }
+
+    socket
+        .set_nonblocking(true)
+        .context("Failed to set media socket to non-blocking")?;
+    socket
+        .bind(&SockAddr::from(SocketAddr::from((
+            Ipv4Addr::UNSPECIFIED,
+            0,
+        ))))
+        .context("Failed to bind media socket")?;
+
+    tokio::net::UdpSocket::from_std(socket.into()).context("Failed to register media socket")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DSCP_EF;
+    use socket2::SockRef;
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn marks_media_socket() {
+        let socket = bind_marked_socket(DSCP_EF).unwrap();
+        assert_eq!(SockRef::from(&socket).tos().unwrap(), 0xB8);
+    }
+
+    #[tokio::test]
+    async fn binds_unspecified_address() {
+        let socket = bind_marked_socket(0).unwrap();
+        let addr = socket.local_addr().unwrap();
+        assert!(addr.ip().is_unspecified());
+        assert_ne!(addr.port(), 0);
+    }
+}
@@ -1,31 +1,58 @@
 use crate::config::{
-    IntoRtc, PEER_EVENTS_CAPACITY, WEBRTC_CHANNELS, WEBRTC_TRACK_ID, WEBRTC_TRACK_STREAM_ID,
+    Codec, IntoRtc, OpusFmtp, PCMU_PAYLOAD_TYPE, PEER_EVENTS_CAPACITY, WEBRTC_TRACK_ID,
+    WEBRTC_TRACK_STREAM_ID,
 };
 use crate::error::WebrtcError;
+use crate::net::marked_udp_mux;
+use crate::sdp::munge_opus_fmtp;
 use anyhow::Context;
-use std::sync::Arc;
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::{broadcast, mpsc};
 use tracing::instrument;
-use vacs_audio::{EncodedAudioFrame, TARGET_SAMPLE_RATE};
+use vacs_audio::EncodedAudioFrame;
+use vacs_audio::codec::PcmuEncoder;
 use vacs_protocol::http::webrtc::IceConfig;
 use webrtc::api::APIBuilder;
 use webrtc::api::interceptor_registry::register_default_interceptors;
-use webrtc::api::media_engine::{MIME_TYPE_OPUS, MediaEngine};
+use webrtc::api::media_engine::MediaEngine;
+use webrtc::api::setting_engine::SettingEngine;
+use webrtc::dtls_transport::dtls_transport_state::RTCDtlsTransportState;
+use webrtc::ice::udp_mux::{UDPMux, UDPMuxDefault};
+use webrtc::ice::udp_network::UDPNetwork;
 use webrtc::ice_transport::ice_candidate::{RTCIceCandidate, RTCIceCandidateInit};
+use webrtc::ice_transport::ice_connection_state::RTCIceConnectionState;
+use webrtc::ice_transport::ice_gatherer_state::RTCIceGathererState;
 use webrtc::interceptor::registry::Registry;
 use webrtc::peer_connection::RTCPeerConnection;
 use webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState;
 use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
-use webrtc::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
+use webrtc::rtp_transceiver::rtp_codec::{RTCRtpCodecParameters, RTPCodecType};
 use webrtc::track::track_local::TrackLocal;
 use webrtc::track::track_local::track_local_static_sample::TrackLocalStaticSample;
 
 pub type PeerConnectionState = RTCPeerConnectionState;
+pub type IceConnectionState = RTCIceConnectionState;
+pub type DtlsTransportState = RTCDtlsTransportState;
+
+type StateChangeHandler<S> =
+    Box<dyn FnMut(S) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> + Send + Sync>;
 
 #[derive(Debug, Clone)]
 pub enum PeerEvent {
     ConnectionState(PeerConnectionState),
+    /// State of the ICE transport, which fails if no candidate pair could be connected.
+    IceConnectionStateChanged(IceConnectionState),
+    /// State of the DTLS transport, which fails if the handshake on a connected candidate pair did.
+    DtlsStateChanged(DtlsTransportState),
+    /// Local ICE candidate to trickle to the remote peer, held back until [`Peer::start_trickle`].
     IceCandidate(String),
+    /// Local ICE gathering has completed, no further [`PeerEvent::IceCandidate`]s will follow.
+    /// Held back until [`Peer::start_trickle`] like the candidates.
+    IceGatheringComplete,
     Error(String),
 }
 
@@ -35,23 +62,64 @@ pub struct Peer {
     sender: Option<crate::Sender>,
     receiver: Option<crate::Receiver>,
     events_tx: broadcast::Sender<PeerEvent>,
+    opus_fmtp: OpusFmtp,
+    udp_mux: Option<Arc<UDPMuxDefault>>,
+    ice_gathering_timeout: Option<Duration>,
+    trickle_gate: Arc<Mutex<TrickleGate>>,
+    codec: Codec,
 }
 
 impl Peer {
     #[instrument(level = "debug", err)]
     pub async fn new(
         config: IceConfig,
+        opus_fmtp: OpusFmtp,
+        dscp: Option<u8>,
+        ice_gathering_timeout: Option<Duration>,
+        codec: Codec,
     ) -> Result<(Self, broadcast::Receiver<PeerEvent>), WebrtcError> {
         let mut media_engine = MediaEngine::default();
-        media_engine
-            .register_default_codecs()
-            .context("Failed to register default codecs")?;
+        match codec {
+            Codec::Opus => media_engine
+                .register_default_codecs()
+                .context("Failed to register default codecs")?,
+            Codec::Pcmu => media_engine
+                .register_codec(
+                    RTCRtpCodecParameters {
+                        capability: codec.capability(),
+                        payload_type: PCMU_PAYLOAD_TYPE,
+                        ..Default::default()
+                    },
+                    RTPCodecType::Audio,
+                )
+                .context("Failed to register PCMU codec")?,
+        }
 
         let mut registry = Registry::new();
         registry = register_default_interceptors(registry, &mut media_engine)
             .context("Failed to register default interceptors")?;
 
+        // Host candidates share a single socket marked with the DSCP, all other candidates use
+        // sockets managed by the ICE agent which cannot be marked.
+        let mut setting_engine = SettingEngine::default();
+        let udp_mux = dscp.and_then(|dscp| match marked_udp_mux(dscp) {
+            Ok(udp_mux) => Some(udp_mux),
+            Err(err) => {
+                tracing::warn!(
+                    ?err,
+                    "Failed to bind DSCP marked media socket, using unmarked sockets"
+                );
+                None
+            }
+        });
+        if let Some(udp_mux) = &udp_mux {
+            setting_engine.set_udp_network(UDPNetwork::Muxed(
+                Arc::clone(udp_mux) as Arc<dyn UDPMux + Send + Sync>
+            ));
+        }
+
         let api = APIBuilder::new()
+            .with_setting_engine(setting_engine)
             .with_media_engine(media_engine)
             .with_interceptor_registry(registry)
             .build();
@@ -62,12 +130,7 @@ impl Peer {
             .context("Failed to create peer connection")?;
 
         let track = Arc::new(TrackLocalStaticSample::new(
-            RTCRtpCodecCapability {
-                mime_type: MIME_TYPE_OPUS.to_owned(),
-                clock_rate: TARGET_SAMPLE_RATE,
-                channels: WEBRTC_CHANNELS,
-                ..Default::default()
-            },
+            codec.capability(),
             WEBRTC_TRACK_ID.to_owned(),
             WEBRTC_TRACK_STREAM_ID.to_owned(),
         ));
@@ -78,22 +141,27 @@ impl Peer {
             .context("Failed to add track to peer connection")?;
 
         let (events_tx, events_rx) = broadcast::channel(PEER_EVENTS_CAPACITY);
+        let trickle_gate = Arc::new(Mutex::new(TrickleGate::default()));
 
-        {
-            let events_tx = events_tx.clone();
-            peer_connection.on_peer_connection_state_change(Box::new(
-                move |state: RTCPeerConnectionState| {
-                    tracing::trace!(?state, "Peer connection state changed");
-                    if let Err(err) = events_tx.send(PeerEvent::ConnectionState(state)) {
-                        tracing::warn!(?err, "Failed to send peer connection state event");
-                    }
-                    Box::pin(async {})
-                },
+        peer_connection.on_peer_connection_state_change(forward_state_changes(
+            events_tx.clone(),
+            PeerEvent::ConnectionState,
+        ));
+        peer_connection.on_ice_connection_state_change(forward_state_changes(
+            events_tx.clone(),
+            PeerEvent::IceConnectionStateChanged,
+        ));
+        peer_connection
+            .sctp()
+            .transport()
+            .on_state_change(forward_state_changes(
+                events_tx.clone(),
+                PeerEvent::DtlsStateChanged,
             ));
-        }
 
         {
             let events_tx = events_tx.clone();
+            let trickle_gate = Arc::clone(&trickle_gate);
             peer_connection.on_ice_candidate(Box::new(
                 move |candidate: Option<RTCIceCandidate>| {
                     tracing::trace!(?candidate, "ICE candidate received");
@@ -101,10 +169,10 @@ impl Peer {
                         match candidate.to_json() {
                             Ok(init) => match serde_json::to_string(&init) {
                                 Ok(init) => {
-                                    if let Err(err) = events_tx.send(PeerEvent::IceCandidate(init))
-                                    {
-                                        tracing::warn!(?err, "Failed to send ICE candidate event");
-                                    }
+                                    trickle_gate
+                                        .lock()
+                                        .unwrap()
+                                        .send(&events_tx, PeerEvent::IceCandidate(init));
                                 }
                                 Err(err) => {
                                     tracing::warn!(?err, "Failed to serialize ICE candidate");
@@ -120,6 +188,24 @@ impl Peer {
             ));
         }
 
+        {
+            let events_tx = events_tx.clone();
+            let trickle_gate = Arc::clone(&trickle_gate);
+            let gathering_complete = AtomicBool::new(false);
+            peer_connection.on_ice_gathering_state_change(Box::new(
+                move |state: RTCIceGathererState| {
+                    tracing::trace!(?state, "ICE gathering state changed");
+                    if is_first_gathering_complete(state, &gathering_complete) {
+                        trickle_gate
+                            .lock()
+                            .unwrap()
+                            .send(&events_tx, PeerEvent::IceGatheringComplete);
+                    }
+                    Box::pin(async {})
+                },
+            ));
+        }
+
         Ok((
             Self {
                 peer_connection,
@@ -127,6 +213,11 @@ impl Peer {
                 sender: None,
                 receiver: None,
                 events_tx,
+                opus_fmtp,
+                udp_mux,
+                ice_gathering_timeout,
+                trickle_gate,
+                codec,
             },
             events_rx,
         ))
@@ -152,7 +243,15 @@ impl Peer {
             self.receiver = Some(crate::Receiver::new(&self.peer_connection, output_tx));
         }
 
-        self.sender = Some(crate::Sender::new(Arc::clone(&self.track), input_rx));
+        let transcoder = match self.codec {
+            Codec::Opus => None,
+            Codec::Pcmu => Some(PcmuEncoder::new().context("Failed to create PCMU transcoder")?),
+        };
+        self.sender = Some(crate::Sender::new(
+            Arc::clone(&self.track),
+            input_rx,
+            transcoder,
+        ));
 
         tracing::trace!("Successfully started peer");
         Ok(())
@@ -196,6 +295,14 @@ impl Peer {
             .await
             .context("Failed to close peer connection")?;
 
+        if let Some(udp_mux) = self.udp_mux.take() {
+            tracing::trace!("Closing media socket");
+            udp_mux
+                .close()
+                .await
+                .context("Failed to close media socket")?;
+        }
+
         tracing::trace!("Successfully closed peer connection");
         Ok(())
     }
@@ -204,6 +311,16 @@ impl Peer {
         self.events_tx.subscribe()
     }
 
+    /// Starts trickling local ICE candidates once the SDP returned by [`Peer::create_offer`] or
+    /// [`Peer::accept_offer`] has been sent, emitting the candidates held back until then.
+    ///
+    /// Candidates reaching the remote peer before the SDP would be rejected, as it cannot add
+    /// candidates without a remote description.
+    pub fn start_trickle(&self) {
+        tracing::trace!("Starting to trickle ICE candidates");
+        self.trickle_gate.lock().unwrap().open(&self.events_tx);
+    }
+
     #[instrument(level = "trace", skip(self), err)]
     pub async fn create_offer(&self) -> Result<String, WebrtcError> {
         tracing::trace!("Creating SDP offer");
@@ -214,10 +331,12 @@ impl Peer {
             .await
             .context("Failed to create offer")?;
 
+        let gathering_complete_rx = self.gathering_complete_rx().await;
         self.peer_connection
             .set_local_description(offer)
             .await
             .context("Failed to set offer as local description")?;
+        self.wait_for_gathering(gathering_complete_rx).await;
 
         let local_description = self
             .peer_connection
@@ -225,8 +344,11 @@ impl Peer {
             .await
             .context("Failed to get local description")?;
 
-        let sdp = serde_json::to_string(&local_description)
-            .context("Failed to serialize local description")?;
+        let sdp = self.signaled_description(&local_description, RTCSessionDescription::offer)?;
+        self.trickle_gate
+            .lock()
+            .unwrap()
+            .signaled(&local_description.sdp);
 
         tracing::trace!("Created SDP offer");
         Ok(sdp)
@@ -248,10 +370,12 @@ impl Peer {
             .create_answer(None)
             .await
             .context("Failed to create answer")?;
+        let gathering_complete_rx = self.gathering_complete_rx().await;
         self.peer_connection
             .set_local_description(answer)
             .await
             .context("Failed to set answer as local description")?;
+        self.wait_for_gathering(gathering_complete_rx).await;
 
         let answer = self
             .peer_connection
@@ -259,8 +383,8 @@ impl Peer {
             .await
             .context("Failed to get local description for answer")?;
 
-        let sdp =
-            serde_json::to_string(&answer).context("Failed to serialize local description")?;
+        let sdp = self.signaled_description(&answer, RTCSessionDescription::answer)?;
+        self.trickle_gate.lock().unwrap().signaled(&answer.sdp);
 
         tracing::trace!("Created SDP answer");
         Ok(sdp)
@@ -296,4 +420,316 @@ impl Peer {
         tracing::trace!("Added remote ICE candidate");
         Ok(())
     }
+
+    #[instrument(level = "trace", skip(self), err)]
+    pub async fn end_remote_ice_candidates(&self) -> Result<(), WebrtcError> {
+        tracing::trace!("Signalling end of remote ICE candidates");
+
+        // An empty candidate marks the end of the remote candidates for the ICE agent.
+        self.peer_connection
+            .add_ice_candidate(RTCIceCandidateInit::default())
+            .await
+            .context("Failed to signal end of remote ICE candidates")?;
+
+        tracing::trace!("Signalled end of remote ICE candidates");
+        Ok(())
+    }
+
+    /// Serializes a local description to be sent to the remote peer, applying the Opus fmtp
+    /// parameters.
+    ///
+    /// Only the signaled copy is munged, as the peer connection rejects local descriptions that
+    /// differ from the offer or answer it created.
+    fn signaled_description(
+        &self,
+        description: &RTCSessionDescription,
+        into_description: fn(String) -> Result<RTCSessionDescription, webrtc::Error>,
+    ) -> Result<String, WebrtcError> {
+        let description = into_description(munge_opus_fmtp(&description.sdp, &self.opus_fmtp))
+            .context("Failed to parse munged description")?;
+        Ok(serde_json::to_string(&description).context("Failed to serialize local description")?)
+    }
+
+    /// Subscribes to the completion of ICE gathering if the local description should wait for
+    /// it. Must be called before setting the local description, which starts the gathering.
+    async fn gathering_complete_rx(&self) -> Option<mpsc::Receiver<()>> {
+        match self.ice_gathering_timeout {
+            Some(_) => Some(self.peer_connection.gathering_complete_promise().await),
+            None => None,
+        }
+    }
+
+    /// Waits for ICE gathering to complete, so the local description contains all candidates
+    /// gathered until then. Candidates gathered afterwards are still trickled once
+    /// [`Peer::start_trickle`] is called.
+    async fn wait_for_gathering(&self, gathering_complete_rx: Option<mpsc::Receiver<()>>) {
+        if let (Some(rx), Some(timeout)) = (gathering_complete_rx, self.ice_gathering_timeout) {
+            wait_for_gathering_complete(rx, timeout).await;
+        }
+    }
+}
+
+/// Returns whether ICE gathering completed before the timeout elapsed.
+async fn wait_for_gathering_complete(
+    mut gathering_complete_rx: mpsc::Receiver<()>,
+    timeout: Duration,
+) -> bool {
+    match tokio::time::timeout(timeout, gathering_complete_rx.recv()).await {
+        Ok(_) => true,
+        Err(_) => {
+            tracing::debug!(
+                ?timeout,
+                "ICE gathering timed out, proceeding with candidates gathered so far"
+            );
+            false
+        }
+    }
+}
+
+/// Holds back trickled ICE events until the local SDP has been sent, see [`Peer::start_trickle`].
+///
+/// Events are sent while holding the gate, so the held back events are emitted before any newer
+/// ones and the end of candidates is always emitted last.
+#[derive(Debug, Default)]
+struct TrickleGate {
+    open: bool,
+    held: Vec<PeerEvent>,
+}
+
+impl TrickleGate {
+    /// Sends the event if trickling has started, holding it back otherwise.
+    fn send(&mut self, events_tx: &broadcast::Sender<PeerEvent>, event: PeerEvent) {
+        if !self.open {
+            tracing::trace!(?event, "Holding back ICE event until the SDP has been sent");
+            self.held.push(event);
+        } else if let Err(err) = events_tx.send(event) {
+            tracing::warn!(?err, "Failed to send ICE event");
+        }
+    }
+
+    /// Drops the held back candidates already contained in the signaled SDP.
+    fn signaled(&mut self, sdp: &str) {
+        self.held.retain(|event| match event {
+            PeerEvent::IceCandidate(candidate) => {
+                !serde_json::from_str::<RTCIceCandidateInit>(candidate)
+                    .is_ok_and(|init| sdp.contains(&init.candidate))
+            }
+            _ => true,
+        });
+    }
+
+    /// Starts trickling, sending all held back events.
+    fn open(&mut self, events_tx: &broadcast::Sender<PeerEvent>) {
+        self.open = true;
+        for event in std::mem::take(&mut self.held) {
+            self.send(events_tx, event);
+        }
+    }
+}
+
+/// Returns a state change handler emitting every new state as a [`PeerEvent`].
+fn forward_state_changes<S: Debug + Send + 'static>(
+    events_tx: broadcast::Sender<PeerEvent>,
+    into_event: fn(S) -> PeerEvent,
+) -> StateChangeHandler<S> {
+    Box::new(move |state: S| {
+        let event = into_event(state);
+        tracing::trace!(?event, "Peer state changed");
+        if let Err(err) = events_tx.send(event) {
+            tracing::warn!(?err, "Failed to send peer state event");
+        }
+        Box::pin(async {})
+    })
+}
+
+/// Returns `true` only for the first transition into [`RTCIceGathererState::Complete`], so the end
+/// of candidates is signalled exactly once per peer, even if gathering is restarted.
+fn is_first_gathering_complete(state: RTCIceGathererState, signalled: &AtomicBool) -> bool {
+    state == RTCIceGathererState::Complete && !signalled.swap(true, Ordering::AcqRel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signals_gathering_complete_exactly_once() {
+        let signalled = AtomicBool::new(false);
+
+        let signals = [
+            RTCIceGathererState::New,
+            RTCIceGathererState::Gathering,
+            RTCIceGathererState::Complete,
+            RTCIceGathererState::Gathering,
+            RTCIceGathererState::Complete,
+        ]
+        .into_iter()
+        .filter(|state| is_first_gathering_complete(*state, &signalled))
+        .count();
+
+        assert_eq!(signals, 1);
+    }
+
+    #[tokio::test]
+    async fn forwards_ice_and_dtls_state_changes() {
+        let (events_tx, mut events_rx) = broadcast::channel(PEER_EVENTS_CAPACITY);
+        let mut on_ice_state =
+            forward_state_changes(events_tx.clone(), PeerEvent::IceConnectionStateChanged);
+        let mut on_dtls_state = forward_state_changes(events_tx, PeerEvent::DtlsStateChanged);
+
+        on_ice_state(RTCIceConnectionState::Checking).await;
+        on_ice_state(RTCIceConnectionState::Connected).await;
+        on_dtls_state(RTCDtlsTransportState::Failed).await;
+
+        assert!(matches!(
+            events_rx.try_recv(),
+            Ok(PeerEvent::IceConnectionStateChanged(
+                RTCIceConnectionState::Checking
+            ))
+        ));
+        assert!(matches!(
+            events_rx.try_recv(),
+            Ok(PeerEvent::IceConnectionStateChanged(
+                RTCIceConnectionState::Connected
+            ))
+        ));
+        assert!(matches!(
+            events_rx.try_recv(),
+            Ok(PeerEvent::DtlsStateChanged(RTCDtlsTransportState::Failed))
+        ));
+        assert!(events_rx.try_recv().is_err());
+    }
+
+    async fn offered_sdp(codec: Codec) -> String {
+        let ice_config = IceConfig {
+            ice_servers: Vec::new(),
+            expires_at: None,
+        };
+        let (mut peer, _) = Peer::new(ice_config, OpusFmtp::default(), None, None, codec)
+            .await
+            .unwrap();
+        let offer = peer.create_offer().await.unwrap();
+        peer.close().await.unwrap();
+        serde_json::from_str::<RTCSessionDescription>(&offer)
+            .unwrap()
+            .sdp
+    }
+
+    #[tokio::test]
+    async fn completes_offer_answer_with_munged_fmtp() {
+        let ice_config = IceConfig {
+            ice_servers: Vec::new(),
+            expires_at: None,
+        };
+        let fmtp = OpusFmtp::default();
+        let (mut caller, _) = Peer::new(ice_config.clone(), fmtp, None, None, Codec::Opus)
+            .await
+            .unwrap();
+        let (mut callee, _) = Peer::new(ice_config, fmtp, None, None, Codec::Opus)
+            .await
+            .unwrap();
+
+        let offer = caller.create_offer().await.unwrap();
+        let answer = callee.accept_offer(offer.clone()).await.unwrap();
+        caller.accept_answer(answer.clone()).await.unwrap();
+
+        for sdp in [offer, answer] {
+            let sdp = serde_json::from_str::<RTCSessionDescription>(&sdp)
+                .unwrap()
+                .sdp;
+            assert!(sdp.contains("maxplaybackrate="), "{sdp}");
+            assert!(sdp.contains("usedtx="), "{sdp}");
+        }
+
+        caller.close().await.unwrap();
+        callee.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn offers_opus_by_default() {
+        let sdp = offered_sdp(Codec::default()).await;
+        assert!(sdp.contains("opus/48000/2"));
+    }
+
+    #[tokio::test]
+    async fn offers_only_pcmu_when_selected() {
+        let sdp = offered_sdp(Codec::Pcmu).await;
+        assert!(sdp.contains("a=rtpmap:0 PCMU/8000"));
+        assert!(!sdp.to_ascii_lowercase().contains("opus"));
+    }
+
+    #[tokio::test]
+    async fn holds_back_ice_candidates_until_trickle_starts() {
+        let ice_config = IceConfig {
+            ice_servers: Vec::new(),
+            expires_at: None,
+        };
+        let (mut peer, mut events_rx) = Peer::new(
+            ice_config,
+            OpusFmtp::default(),
+            None,
+            Some(Duration::from_secs(5)),
+            Codec::Opus,
+        )
+        .await
+        .unwrap();
+
+        let offer = peer.create_offer().await.unwrap();
+        let sdp = serde_json::from_str::<RTCSessionDescription>(&offer)
+            .unwrap()
+            .sdp;
+        assert!(sdp.contains("a=candidate:"), "{sdp}");
+        while let Ok(event) = events_rx.try_recv() {
+            assert!(
+                !matches!(
+                    event,
+                    PeerEvent::IceCandidate(_) | PeerEvent::IceGatheringComplete
+                ),
+                "{event:?} emitted before the SDP was sent"
+            );
+        }
+
+        peer.start_trickle();
+        loop {
+            match tokio::time::timeout(Duration::from_secs(5), events_rx.recv())
+                .await
+                .unwrap()
+                .unwrap()
+            {
+                PeerEvent::IceCandidate(candidate) => {
+                    let init = serde_json::from_str::<RTCIceCandidateInit>(&candidate).unwrap();
+                    assert!(
+                        !sdp.contains(&init.candidate),
+                        "{candidate} already offered"
+                    );
+                }
+                PeerEvent::IceGatheringComplete => break,
+                _ => {}
+            }
+        }
+
+        peer.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn proceeds_once_gathering_completes() {
+        let (tx, rx) = mpsc::channel(1);
+        tx.send(()).await.unwrap();
+
+        assert!(wait_for_gathering_complete(rx, Duration::from_secs(5)).await);
+    }
+
+    #[tokio::test]
+    async fn proceeds_after_gathering_timeout() {
+        let (tx, rx) = mpsc::channel(1);
+        // Simulates a slow TURN server delaying the completion of the gathering.
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            tx.send(()).await.ok();
+        });
+
+        let start = std::time::Instant::now();
+        assert!(!wait_for_gathering_complete(rx, Duration::from_millis(50)).await);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
 }
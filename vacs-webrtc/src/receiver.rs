@@ -1,7 +1,9 @@
+use crate::config::PCMU_PAYLOAD_TYPE;
 use tokio::sync::mpsc;
 use tokio::sync::watch;
 use tracing::instrument;
 use vacs_audio::EncodedAudioFrame;
+use vacs_audio::codec::PcmuDecoder;
 use webrtc::peer_connection::RTCPeerConnection;
 
 pub struct Receiver {
@@ -24,8 +26,9 @@ impl Receiver {
 
             Box::pin(async move {
                 let mut output_tx = output_selection_rx.borrow().clone();
+                let mut transcoder: Option<PcmuDecoder> = None;
 
-                loop {
+                'receive: loop {
                     tokio::select! {
                         biased;
                         _ = shutdown_rx.changed() => {
@@ -38,10 +41,39 @@ impl Receiver {
                         rtp = track.read_rtp() => {
                             match rtp {
                                 Ok((packet, _)) => {
-                                    if let Some(output_tx) = output_tx.as_ref() &&
-                                        output_tx.send(packet.payload).await.is_err() {
+                                    let Some(output_tx) = output_tx.as_ref() else {
+                                        continue;
+                                    };
+
+                                    // Playback always expects Opus frames, so PCMU payloads are
+                                    // transcoded before being passed on.
+                                    let frames = if packet.header.payload_type == PCMU_PAYLOAD_TYPE {
+                                        let transcoder = match transcoder.as_mut() {
+                                            Some(transcoder) => transcoder,
+                                            None => match PcmuDecoder::new() {
+                                                Ok(decoder) => transcoder.insert(decoder),
+                                                Err(err) => {
+                                                    tracing::warn!(?err, "Failed to create PCMU transcoder");
+                                                    break;
+                                                }
+                                            },
+                                        };
+                                        match transcoder.decode(&packet.payload) {
+                                            Ok(frames) => frames,
+                                            Err(err) => {
+                                                tracing::warn!(?err, "Failed to transcode received PCMU payload");
+                                                continue;
+                                            }
+                                        }
+                                    } else {
+                                        vec![packet.payload]
+                                    };
+
+                                    for frame in frames {
+                                        if output_tx.send(frame).await.is_err() {
                                             tracing::warn!("Failed to send received RTP packet to output");
-                                            break;
+                                            break 'receive;
+                                        }
                                     }
                                 }
                                 Err(err) => {
@@ -0,0 +1,160 @@
+use crate::config::OpusFmtp;
+use std::collections::HashSet;
+
+const RTPMAP_PREFIX: &str = "a=rtpmap:";
+const FMTP_PREFIX: &str = "a=fmtp:";
+const OPUS_ENCODING_NAME: &str = "opus";
+
+/// Rewrites the fmtp attributes of all Opus payload types in the given SDP to the given
+/// parameters, adding an fmtp attribute if a payload type has none.
+///
+/// Parameters not covered by [`OpusFmtp`] (e.g. `minptime`) are kept, other codecs are left
+/// untouched. The returned SDP always uses CRLF line endings.
+pub(crate) fn munge_opus_fmtp(sdp: &str, fmtp: &OpusFmtp) -> String {
+    let opus_payload_types = sdp
+        .lines()
+        .filter_map(|line| line.strip_prefix(RTPMAP_PREFIX))
+        .filter_map(|rtpmap| rtpmap.split_once(' '))
+        .filter(|(_, encoding)| {
+            encoding
+                .split('/')
+                .next()
+                .is_some_and(|name| name.eq_ignore_ascii_case(OPUS_ENCODING_NAME))
+        })
+        .map(|(payload_type, _)| payload_type)
+        .collect::<HashSet<_>>();
+
+    if opus_payload_types.is_empty() {
+        return sdp.to_string();
+    }
+
+    let with_fmtp = sdp
+        .lines()
+        .filter_map(|line| line.strip_prefix(FMTP_PREFIX))
+        .filter_map(|fmtp| fmtp.split_once(' ').map(|(payload_type, _)| payload_type))
+        .collect::<HashSet<_>>();
+
+    let mut munged = String::with_capacity(sdp.len() + 128);
+    for line in sdp.lines() {
+        if let Some((payload_type, params)) = line
+            .strip_prefix(FMTP_PREFIX)
+            .and_then(|fmtp| fmtp.split_once(' '))
+            .filter(|(payload_type, _)| opus_payload_types.contains(payload_type))
+        {
+            push_line(
+                &mut munged,
+                &format!("{FMTP_PREFIX}{payload_type} {}", merge_params(params, fmtp)),
+            );
+            continue;
+        }
+
+        push_line(&mut munged, line);
+
+        if let Some((payload_type, _)) = line
+            .strip_prefix(RTPMAP_PREFIX)
+            .and_then(|rtpmap| rtpmap.split_once(' '))
+            .filter(|(payload_type, _)| {
+                opus_payload_types.contains(payload_type) && !with_fmtp.contains(payload_type)
+            })
+        {
+            push_line(
+                &mut munged,
+                &format!("{FMTP_PREFIX}{payload_type} {}", merge_params("", fmtp)),
+            );
+        }
+    }
+    munged
+}
+
+fn push_line(sdp: &mut String, line: &str) {
+    sdp.push_str(line);
+    sdp.push_str("\r\n");
+}
+
+/// Overrides the pinned parameters in a `;` separated fmtp parameter list, appending missing ones.
+fn merge_params(params: &str, fmtp: &OpusFmtp) -> String {
+    let pinned = fmtp.params();
+    let is_pinned = |key: &str| pinned.iter().any(|(k, _)| k.eq_ignore_ascii_case(key));
+
+    params
+        .split(';')
+        .map(str::trim)
+        .filter(|param| !param.is_empty())
+        .filter(|param| {
+            let key = param.split_once('=').map_or(*param, |(key, _)| key);
+            !is_pinned(key.trim())
+        })
+        .map(str::to_string)
+        .chain(pinned.iter().map(|(key, value)| format!("{key}={value}")))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFER: &str = "v=0\r\n\
+        o=- 4215775240449105457 2 IN IP4 0.0.0.0\r\n\
+        s=-\r\n\
+        t=0 0\r\n\
+        m=audio 9 UDP/TLS/RTP/SAVPF 111 9 0\r\n\
+        c=IN IP4 0.0.0.0\r\n\
+        a=rtpmap:111 opus/48000/2\r\n\
+        a=fmtp:111 minptime=10;useinbandfec=0;usedtx=1\r\n\
+        a=rtpmap:9 G722/8000\r\n\
+        a=rtpmap:0 PCMU/8000\r\n\
+        a=fmtp:0 foo=bar\r\n\
+        a=sendrecv\r\n";
+
+    fn fmtp_lines(sdp: &str) -> Vec<&str> {
+        sdp.lines()
+            .filter(|line| line.starts_with(FMTP_PREFIX))
+            .collect()
+    }
+
+    #[test]
+    fn pins_opus_fmtp_params() {
+        let munged = munge_opus_fmtp(OFFER, &OpusFmtp::default());
+
+        assert_eq!(
+            fmtp_lines(&munged),
+            vec![
+                "a=fmtp:111 minptime=10;maxplaybackrate=48000;stereo=0;useinbandfec=1;usedtx=0",
+                "a=fmtp:0 foo=bar",
+            ]
+        );
+        assert_eq!(munged.lines().count(), OFFER.lines().count());
+        assert!(munged.ends_with("\r\n"));
+    }
+
+    #[test]
+    fn enables_dtx_when_configured() {
+        let fmtp = OpusFmtp {
+            use_dtx: true,
+            ..Default::default()
+        };
+        let munged = munge_opus_fmtp(OFFER, &fmtp);
+
+        assert!(munged.contains(
+            "a=fmtp:111 minptime=10;maxplaybackrate=48000;stereo=0;useinbandfec=1;usedtx=1\r\n"
+        ));
+    }
+
+    #[test]
+    fn adds_missing_opus_fmtp() {
+        let offer = OFFER.replace("a=fmtp:111 minptime=10;useinbandfec=0;usedtx=1\r\n", "");
+        let munged = munge_opus_fmtp(&offer, &OpusFmtp::default());
+
+        assert!(munged.contains(
+            "a=rtpmap:111 opus/48000/2\r\n\
+            a=fmtp:111 maxplaybackrate=48000;stereo=0;useinbandfec=1;usedtx=0\r\n"
+        ));
+    }
+
+    #[test]
+    fn leaves_sdp_without_opus_untouched() {
+        let sdp = "v=0\r\nm=audio 9 UDP/TLS/RTP/SAVPF 0\r\na=rtpmap:0 PCMU/8000\r\n";
+        assert_eq!(munge_opus_fmtp(sdp, &OpusFmtp::default()), sdp);
+    }
+}
@@ -4,6 +4,7 @@ use tokio::sync::mpsc;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
 use tracing::{Instrument, instrument};
+use vacs_audio::codec::PcmuEncoder;
 use vacs_audio::{EncodedAudioFrame, FRAME_DURATION_MS};
 use webrtc::media::Sample;
 use webrtc::track::track_local::track_local_static_sample::TrackLocalStaticSample;
@@ -18,6 +19,7 @@ impl Sender {
     pub fn new(
         track: Arc<TrackLocalStaticSample>,
         mut input_rx: mpsc::Receiver<EncodedAudioFrame>,
+        mut transcoder: Option<PcmuEncoder>,
     ) -> Self {
         let (shutdown_tx, mut shutdown_rx) = watch::channel(());
 
@@ -32,6 +34,15 @@ impl Sender {
                     frame = input_rx.recv() => {
                         match frame {
                             Some(frame) => {
+                                // Input frames are always Opus, transcode them if the track sends PCMU.
+                                let frame = match transcoder.as_mut().map(|t| t.encode(&frame)) {
+                                    None => frame,
+                                    Some(Ok(frame)) => frame,
+                                    Some(Err(err)) => {
+                                        tracing::warn!(?err, "Failed to transcode input frame");
+                                        continue;
+                                    }
+                                };
                                 let sample = Sample {
                                     data: frame,
                                     duration: std::time::Duration::from_millis(FRAME_DURATION_MS),